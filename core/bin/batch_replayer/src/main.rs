@@ -0,0 +1,107 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Context as _;
+use clap::Parser;
+use tokio::runtime::Handle;
+use zksync_config::{
+    configs::{chain::NetworkConfig, ObservabilityConfig},
+    PostgresConfig,
+};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_types::L1BatchNumber;
+
+use crate::replay::{compare_with_storage, replay_l1_batch, TracerKind};
+
+mod replay;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Re-executes an L1 batch and compares the results with the data stored in Postgres",
+    long_about = None
+)]
+struct Cli {
+    /// Number of the L1 batch to replay.
+    #[arg(long)]
+    l1_batch_number: u32,
+    /// Tracer to execute transactions with.
+    #[arg(long, value_enum, default_value_t = TracerKind::None)]
+    tracer: TracerKind,
+    /// Path to write the tracer output (e.g., call traces keyed by the transaction hash) to as JSON.
+    /// If not specified, the tracer output is discarded.
+    #[arg(long)]
+    tracer_output: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Cli::parse();
+
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    // The replayer only reads from the database, so it's safe to use a replica.
+    let pool = ConnectionPool::builder(postgres_config.replica_url()?, 2)
+        .set_schema(postgres_config.schema())
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+
+    let l1_batch_number = L1BatchNumber(opt.l1_batch_number);
+    anyhow::ensure!(
+        l1_batch_number > L1BatchNumber(0),
+        "genesis L1 batch cannot be replayed"
+    );
+    let replayed_batch = tokio::task::spawn_blocking({
+        let pool = pool.clone();
+        let l2_chain_id = network_config.zksync_network_id;
+        move || {
+            replay_l1_batch(
+                Handle::current(),
+                &pool,
+                l1_batch_number,
+                l2_chain_id,
+                opt.tracer,
+            )
+        }
+    })
+    .await
+    .context("replay panicked")??;
+    println!(
+        "Replayed L1 batch #{l1_batch_number}: {} miniblocks, {} transactions",
+        replayed_batch.miniblocks.len(),
+        replayed_batch.transaction_count()
+    );
+
+    if let Some(path) = &opt.tracer_output {
+        let output = serde_json::to_string_pretty(&replayed_batch.call_traces)
+            .context("failed serializing tracer output")?;
+        fs::write(path, output)
+            .with_context(|| format!("failed writing tracer output to {path:?}"))?;
+        println!("Tracer output written to {path:?}");
+    }
+
+    let mismatches = compare_with_storage(&pool, l1_batch_number, replayed_batch).await?;
+    if mismatches.is_empty() {
+        println!("Replayed events and storage writes match the stored ones");
+        return Ok(());
+    }
+    for mismatch in &mismatches {
+        println!("{mismatch}");
+    }
+    anyhow::bail!(
+        "found {} discrepancies between replayed and stored data for L1 batch #{l1_batch_number}",
+        mismatches.len()
+    )
+}
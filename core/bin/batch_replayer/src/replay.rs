@@ -0,0 +1,370 @@
+//! Re-execution of L1 batches and comparison of the results with the data stored in Postgres.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::Arc,
+};
+
+use anyhow::Context as _;
+use clap::ValueEnum;
+use multivm::{
+    interface::{L2BlockEnv, VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled},
+    tracers::CallTracer,
+    vm_latest::HistoryEnabled,
+    MultiVMTracer, MultiVmTracerPointer, VmInstance,
+};
+use once_cell::sync::OnceCell;
+use tokio::runtime::Handle;
+use vm_utils::create_vm;
+use zksync_dal::ConnectionPool;
+use zksync_state::WriteStorage;
+use zksync_types::{
+    api::GetLogsFilter, storage_writes_deduplicator::StorageWritesDeduplicator, vm_trace::Call,
+    Address, L1BatchNumber, L2ChainId, MiniblockNumber, StorageKey, StorageLogQuery, Transaction,
+    VmEvent, H256,
+};
+use zksync_utils::u256_to_h256;
+
+/// Tracer used when re-executing transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum TracerKind {
+    /// Transactions are executed without tracers.
+    None,
+    /// Call traces are collected for all transactions.
+    Call,
+}
+
+/// Results of re-executing an L1 batch.
+#[derive(Debug, Default)]
+pub(crate) struct ReplayedL1Batch {
+    pub miniblocks: Vec<ReplayedMiniblock>,
+    /// Call traces keyed by the transaction hash. Only collected with [`TracerKind::Call`].
+    pub call_traces: BTreeMap<H256, Vec<Call>>,
+}
+
+impl ReplayedL1Batch {
+    pub fn transaction_count(&self) -> usize {
+        self.miniblocks
+            .iter()
+            .map(|miniblock| miniblock.transaction_count)
+            .sum()
+    }
+
+    /// Returns storage writes produced by the batch, deduplicated in the same way as by the state keeper
+    /// (i.e., on the miniblock level), with the latest value for each touched slot.
+    fn storage_writes(&self) -> HashMap<StorageKey, H256> {
+        let mut writes = HashMap::new();
+        for miniblock in &self.miniblocks {
+            let mut deduplicator = StorageWritesDeduplicator::new();
+            deduplicator.apply(
+                miniblock
+                    .storage_logs
+                    .iter()
+                    .filter(|log| log.log_query.rw_flag),
+            );
+            let modified_slots = deduplicator.into_modified_key_values();
+            writes.extend(
+                modified_slots
+                    .into_iter()
+                    .map(|(key, slot)| (key, u256_to_h256(slot.value))),
+            );
+        }
+        writes
+    }
+}
+
+/// Results of re-executing a miniblock. For the last (fictive) miniblock in the batch,
+/// the results include the execution of the batch tip.
+#[derive(Debug)]
+pub(crate) struct ReplayedMiniblock {
+    pub number: MiniblockNumber,
+    pub transaction_count: usize,
+    pub events: Vec<VmEvent>,
+    pub storage_logs: Vec<StorageLogQuery>,
+}
+
+/// Re-executes the specified L1 batch on top of the Postgres state preceding it.
+/// Must be called from a blocking context.
+pub(crate) fn replay_l1_batch(
+    rt_handle: Handle,
+    pool: &ConnectionPool,
+    l1_batch_number: L1BatchNumber,
+    l2_chain_id: L2ChainId,
+    tracer: TracerKind,
+) -> anyhow::Result<ReplayedL1Batch> {
+    let mut connection = rt_handle
+        .block_on(pool.access_storage_tagged("batch_replayer"))
+        .context("failed getting DB connection")?;
+    let miniblocks_execution_data = rt_handle
+        .block_on(
+            connection
+                .transactions_dal()
+                .get_miniblocks_to_execute_for_l1_batch(l1_batch_number),
+        )
+        .with_context(|| format!("failed loading miniblocks for L1 batch #{l1_batch_number}"))?;
+    anyhow::ensure!(
+        !miniblocks_execution_data.is_empty(),
+        "L1 batch #{l1_batch_number} has no persisted miniblocks"
+    );
+
+    let (mut vm, _storage_view) = create_vm(rt_handle, l1_batch_number, connection, l2_chain_id)
+        .context("failed creating VM")?;
+    tracing::info!("Started replaying L1 batch #{l1_batch_number}");
+
+    let mut replayed_batch = ReplayedL1Batch::default();
+    let next_miniblocks_data = miniblocks_execution_data
+        .iter()
+        .skip(1)
+        .map(Some)
+        .chain([None]);
+    let miniblocks_data = miniblocks_execution_data.iter().zip(next_miniblocks_data);
+    for (miniblock_data, next_miniblock_data) in miniblocks_data {
+        tracing::debug!(
+            "Replaying miniblock #{} with {} transactions",
+            miniblock_data.number,
+            miniblock_data.txs.len()
+        );
+        let mut replayed_miniblock = ReplayedMiniblock {
+            number: miniblock_data.number,
+            transaction_count: miniblock_data.txs.len(),
+            events: vec![],
+            storage_logs: vec![],
+        };
+        for tx in &miniblock_data.txs {
+            let (result, call_traces) = execute_tx(tx, &mut vm, tracer)
+                .with_context(|| format!("failed executing transaction {:?}", tx.hash()))?;
+            replayed_miniblock.events.extend(result.logs.events);
+            replayed_miniblock
+                .storage_logs
+                .extend(result.logs.storage_logs);
+            if let Some(call_traces) = call_traces {
+                replayed_batch.call_traces.insert(tx.hash(), call_traces);
+            }
+        }
+        if let Some(next_miniblock_data) = next_miniblock_data {
+            vm.start_new_l2_block(L2BlockEnv::from_miniblock_data(next_miniblock_data));
+        }
+        replayed_batch.miniblocks.push(replayed_miniblock);
+    }
+
+    let finished_batch = vm.finish_batch();
+    // The batch tip is executed in the context of the last (fictive) miniblock, so its outputs are stored with it.
+    let fictive_miniblock = replayed_batch.miniblocks.last_mut().unwrap();
+    let block_tip_logs = finished_batch.block_tip_execution_result.logs;
+    fictive_miniblock.events.extend(block_tip_logs.events);
+    fictive_miniblock
+        .storage_logs
+        .extend(block_tip_logs.storage_logs);
+    tracing::info!("Finished replaying L1 batch #{l1_batch_number}");
+    Ok(replayed_batch)
+}
+
+/// Executes a transaction in the same way as the state keeper does, i.e., falling back to execution
+/// without bytecode compression if compression fails.
+fn execute_tx<S: WriteStorage>(
+    tx: &Transaction,
+    vm: &mut VmInstance<S, HistoryEnabled>,
+    tracer: TracerKind,
+) -> anyhow::Result<(VmExecutionResultAndLogs, Option<Vec<Call>>)> {
+    vm.make_snapshot();
+    let call_tracer_result = Arc::new(OnceCell::default());
+    let tracers = create_tracers(tracer, &call_tracer_result);
+    let (compression_result, result) =
+        vm.inspect_transaction_with_bytecode_compression(tracers.into(), tx.clone(), true);
+    if compression_result.is_ok() {
+        vm.pop_snapshot_no_rollback();
+        return Ok((result, take_call_traces(tracer, call_tracer_result)));
+    }
+
+    vm.rollback_to_the_latest_snapshot();
+    let call_tracer_result = Arc::new(OnceCell::default());
+    let tracers = create_tracers(tracer, &call_tracer_result);
+    let (compression_result, result) =
+        vm.inspect_transaction_with_bytecode_compression(tracers.into(), tx.clone(), false);
+    anyhow::ensure!(
+        compression_result.is_ok(),
+        "compression can't fail if we don't apply it"
+    );
+    Ok((result, take_call_traces(tracer, call_tracer_result)))
+}
+
+fn create_tracers<S: WriteStorage>(
+    tracer: TracerKind,
+    call_tracer_result: &Arc<OnceCell<Vec<Call>>>,
+) -> Vec<MultiVmTracerPointer<S, HistoryEnabled>> {
+    match tracer {
+        TracerKind::None => vec![],
+        TracerKind::Call => {
+            vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
+        }
+    }
+}
+
+fn take_call_traces(
+    tracer: TracerKind,
+    call_tracer_result: Arc<OnceCell<Vec<Call>>>,
+) -> Option<Vec<Call>> {
+    (tracer == TracerKind::Call).then(|| {
+        Arc::try_unwrap(call_tracer_result)
+            .unwrap()
+            .take()
+            .unwrap_or_default()
+    })
+}
+
+/// Event data compared between the replayed and stored batch.
+#[derive(Debug, PartialEq)]
+pub(crate) struct EventData {
+    address: Address,
+    topics: Vec<H256>,
+    value: Vec<u8>,
+}
+
+impl From<VmEvent> for EventData {
+    fn from(event: VmEvent) -> Self {
+        Self {
+            address: event.address,
+            topics: event.indexed_topics,
+            value: event.value,
+        }
+    }
+}
+
+/// Discrepancy between the replayed and stored L1 batch data.
+#[derive(Debug)]
+pub(crate) enum Mismatch {
+    EventCount {
+        miniblock: MiniblockNumber,
+        replayed: usize,
+        stored: usize,
+    },
+    Event {
+        miniblock: MiniblockNumber,
+        index_in_block: usize,
+        replayed: EventData,
+        stored: EventData,
+    },
+    StorageValue {
+        key: StorageKey,
+        replayed: Option<H256>,
+        stored: Option<H256>,
+    },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EventCount {
+                miniblock,
+                replayed,
+                stored,
+            } => write!(
+                formatter,
+                "miniblock #{miniblock}: {replayed} events replayed, {stored} events stored"
+            ),
+            Self::Event {
+                miniblock,
+                index_in_block,
+                replayed,
+                stored,
+            } => write!(
+                formatter,
+                "miniblock #{miniblock}, event #{index_in_block}: replayed {replayed:?}, stored {stored:?}"
+            ),
+            Self::StorageValue {
+                key,
+                replayed,
+                stored,
+            } => write!(
+                formatter,
+                "storage slot {:?} of {:?}: replayed value {replayed:?}, stored value {stored:?}",
+                key.key(),
+                key.address()
+            ),
+        }
+    }
+}
+
+/// Compares events and storage writes of the replayed batch with the data stored in Postgres.
+pub(crate) async fn compare_with_storage(
+    pool: &ConnectionPool,
+    l1_batch_number: L1BatchNumber,
+    replayed_batch: ReplayedL1Batch,
+) -> anyhow::Result<Vec<Mismatch>> {
+    let mut storage = pool.access_storage_tagged("batch_replayer").await?;
+    let mut mismatches = vec![];
+
+    let replayed_writes = replayed_batch.storage_writes();
+    let stored_writes = storage
+        .storage_logs_dal()
+        .get_touched_slots_for_l1_batch(l1_batch_number)
+        .await
+        .context("failed loading stored storage writes")?;
+    let mut keys: Vec<_> = replayed_writes.keys().chain(stored_writes.keys()).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    for key in keys {
+        let replayed = replayed_writes.get(key).copied();
+        let stored = stored_writes.get(key).copied();
+        if replayed != stored {
+            mismatches.push(Mismatch::StorageValue {
+                key: *key,
+                replayed,
+                stored,
+            });
+        }
+    }
+
+    let (Some(first_miniblock), Some(last_miniblock)) = (
+        replayed_batch.miniblocks.first(),
+        replayed_batch.miniblocks.last(),
+    ) else {
+        return Ok(mismatches);
+    };
+    let filter = GetLogsFilter {
+        from_block: first_miniblock.number,
+        to_block: last_miniblock.number,
+        addresses: vec![],
+        topics: vec![],
+    };
+    let stored_logs = storage
+        .events_web3_dal()
+        .get_logs(filter, i32::MAX as usize)
+        .await
+        .context("failed loading stored events")?;
+    let mut stored_events = HashMap::<_, Vec<_>>::new();
+    for log in stored_logs {
+        let miniblock = MiniblockNumber(log.block_number.context("missing block number")?.as_u32());
+        stored_events.entry(miniblock).or_default().push(EventData {
+            address: log.address,
+            topics: log.topics,
+            value: log.data.0,
+        });
+    }
+
+    for miniblock in replayed_batch.miniblocks {
+        let stored = stored_events.remove(&miniblock.number).unwrap_or_default();
+        if miniblock.events.len() != stored.len() {
+            mismatches.push(Mismatch::EventCount {
+                miniblock: miniblock.number,
+                replayed: miniblock.events.len(),
+                stored: stored.len(),
+            });
+        }
+        let events = miniblock.events.into_iter().map(EventData::from);
+        let first_mismatch = events
+            .zip(stored)
+            .enumerate()
+            .find(|(_, (replayed, stored))| replayed != stored);
+        if let Some((index_in_block, (replayed, stored))) = first_mismatch {
+            mismatches.push(Mismatch::Event {
+                miniblock: miniblock.number,
+                index_in_block,
+                replayed,
+                stored,
+            });
+        }
+    }
+    Ok(mismatches)
+}
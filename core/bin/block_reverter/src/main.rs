@@ -100,6 +100,7 @@ async fn main() -> anyhow::Result<()> {
         postgres_config.master_url()?,
         postgres_config.max_connections()?,
     )
+    .set_schema(postgres_config.schema())
     .build()
     .await
     .context("failed to build a connection pool")?;
@@ -139,6 +139,7 @@ async fn main() -> anyhow::Result<()> {
             .master_url()
             .context("Master DB URL is absent")?,
     )
+    .set_schema(postgres_config.schema())
     .build()
     .await
     .unwrap();
@@ -0,0 +1,88 @@
+use anyhow::Context as _;
+use clap::Parser;
+use zksync_config::{configs::ObservabilityConfig, PostgresConfig};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_types::L1BatchNumber;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Recomputes aggregated L1 batch data from raw rows in Postgres and compares it with stored values",
+    long_about = None
+)]
+struct Cli {
+    /// Number of the first L1 batch to check.
+    #[arg(long, default_value_t = 0)]
+    from_l1_batch: u32,
+    /// Number of the last L1 batch to check (inclusive). If not specified, checks L1 batches
+    /// up to the last batch with a generated commitment.
+    #[arg(long)]
+    to_l1_batch: Option<u32>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Cli::parse();
+
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    // The checker only reads from the database, so it's safe to use a replica.
+    let pool = ConnectionPool::builder(postgres_config.replica_url()?, 1)
+        .set_schema(postgres_config.schema())
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    let mut storage = pool.access_storage_tagged("db_consistency_checker").await?;
+
+    let to_l1_batch = if let Some(number) = opt.to_l1_batch {
+        L1BatchNumber(number)
+    } else {
+        let last_l1_batch = storage
+            .consistency_dal()
+            .get_last_checkable_l1_batch()
+            .await
+            .context("get_last_checkable_l1_batch()")?;
+        last_l1_batch.context("no L1 batches with generated commitment in Postgres")?
+    };
+    let from_l1_batch = L1BatchNumber(opt.from_l1_batch);
+    anyhow::ensure!(
+        from_l1_batch <= to_l1_batch,
+        "invalid L1 batch range: #{from_l1_batch}..=#{to_l1_batch}"
+    );
+
+    let mut inconsistency_count = 0;
+    for number in from_l1_batch.0..=to_l1_batch.0 {
+        let l1_batch_number = L1BatchNumber(number);
+        let inconsistencies = storage
+            .consistency_dal()
+            .check_l1_batch(l1_batch_number)
+            .await
+            .with_context(|| format!("failed checking L1 batch #{l1_batch_number}"))?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not sealed"))?;
+        for inconsistency in &inconsistencies {
+            tracing::error!(
+                "Found inconsistency in L1 batch #{l1_batch_number}: {inconsistency:?}"
+            );
+        }
+        inconsistency_count += inconsistencies.len();
+        tracing::debug!("Checked L1 batch #{l1_batch_number}");
+    }
+
+    anyhow::ensure!(
+        inconsistency_count == 0,
+        "found {inconsistency_count} inconsistencies in L1 batches #{from_l1_batch}..=#{to_l1_batch}"
+    );
+    tracing::info!("L1 batches #{from_l1_batch}..=#{to_l1_batch} are consistent");
+    Ok(())
+}
@@ -1,14 +1,18 @@
-use std::{env, time::Duration};
+use std::{env, num::NonZeroU32, time::Duration};
 
 use anyhow::Context;
 use serde::Deserialize;
 use url::Url;
-use zksync_basic_types::{Address, L1ChainId, L2ChainId};
+use zksync_basic_types::{Address, L1BatchNumber, L1ChainId, L2ChainId};
 use zksync_config::ObjectStoreConfig;
 use zksync_core::{
     api_server::{
         tx_sender::TxSenderConfig,
-        web3::{state::InternalApiConfig, Namespace},
+        web3::{
+            backend_jsonrpsee::{ApiKeyLimits, CorsConfig, RpcAuthConfig},
+            state::InternalApiConfig,
+            Namespace,
+        },
     },
     consensus,
     temp_config_store::decode_yaml,
@@ -109,6 +113,30 @@ pub struct OptionalENConfig {
     /// Max possible limit of subscriptions to be in the API state at once.
     #[serde(default = "OptionalENConfig::default_subscriptions_limit")]
     pub subscriptions_limit: usize,
+    /// Max possible limit of active subscriptions per WebSocket connection.
+    #[serde(default = "OptionalENConfig::default_subscriptions_per_connection_limit")]
+    pub subscriptions_per_connection_limit: usize,
+    /// Max number of messages buffered for a WebSocket connection. Subscribers that cannot push notifications
+    /// to a full buffer for a while are dropped.
+    #[serde(default = "OptionalENConfig::default_websocket_message_buffer_capacity")]
+    pub websocket_message_buffer_capacity: usize,
+    /// Max number of compute units spent per second by a WebSocket connection. Each method has its own cost;
+    /// e.g., `eth_call` and `eth_getLogs` cost much more than `eth_chainId`.
+    pub websocket_compute_units_per_second_limit: Option<NonZeroU32>,
+    /// API keys accepted by the JSON-RPC servers. If set, each HTTP request and WebSocket connection must provide
+    /// one of the keys, either in the `X-API-Key` header or as the request path (`/<key>`).
+    pub api_keys: Option<Vec<String>>,
+    /// Max number of HTTP requests (including WebSocket handshakes) per second for each API key.
+    api_key_requests_per_second_limit: Option<NonZeroU32>,
+    /// Max number of compute units per second for each API key.
+    api_key_compute_units_per_second_limit: Option<NonZeroU32>,
+    /// Static bearer tokens accepted by the JSON-RPC servers in the `Authorization` header.
+    auth_bearer_tokens: Option<Vec<String>>,
+    /// Path to the file with a hex-encoded secret used to verify HS256-signed JWTs provided as bearer tokens.
+    auth_jwt_secret_path: Option<String>,
+    /// Namespaces (e.g., `debug`) calls to which require authentication. If not set, all calls require authentication.
+    /// Only applies if bearer tokens or a JWT secret are configured.
+    auth_protected_namespaces: Option<Vec<String>>,
     /// Max possible limit of entities to be requested via API at once.
     #[serde(default = "OptionalENConfig::default_req_entities_limit")]
     pub req_entities_limit: usize,
@@ -123,12 +151,60 @@ pub struct OptionalENConfig {
     /// Limit for fee history block range.
     #[serde(default = "OptionalENConfig::default_fee_history_limit")]
     pub fee_history_limit: u64,
+    /// Number of recent miniblocks considered when suggesting priority fees. Default is 20.
+    #[serde(default = "OptionalENConfig::default_gas_price_suggestion_blocks")]
+    pub gas_price_suggestion_blocks: u32,
+    /// Percentile (0..=100) of effective priority fees in each miniblock used for the suggestion. Default is 60.
+    #[serde(default = "OptionalENConfig::default_gas_price_suggestion_percentile")]
+    pub gas_price_suggestion_percentile: f64,
+    /// Smoothing factor (0..1) for the moving average over per-miniblock percentiles. Default is 0.5.
+    #[serde(default = "OptionalENConfig::default_gas_price_suggestion_smoothing")]
+    pub gas_price_suggestion_smoothing: f64,
     /// Maximum number of requests in a single batch JSON RPC request. Default is 500.
     #[serde(default = "OptionalENConfig::default_max_batch_request_size")]
     pub max_batch_request_size: usize,
     /// Maximum response body size in MiBs. Default is 10 MiB.
     #[serde(default = "OptionalENConfig::default_max_response_body_size_mb")]
     pub max_response_body_size_mb: usize,
+    /// Maximum aggregate size of responses to a batch HTTP request in MiBs. Responses exceeding the limit
+    /// are replaced with errors. Default is `max_response_body_size_mb`.
+    max_batch_response_size_mb: Option<usize>,
+    /// Time budget for processing a batch HTTP request in milliseconds. Calls not completed within the budget
+    /// are replaced with errors. Not limited by default.
+    batch_request_time_budget_ms: Option<u64>,
+    /// Minimum size of an HTTP response in bytes to be compressed with gzip or Brotli. If not set,
+    /// responses are not compressed.
+    pub http_response_compression_threshold: Option<usize>,
+    /// Time in milliseconds given to in-flight API calls to complete once the node receives a stop signal.
+    /// Default is 5 seconds.
+    shutdown_drain_timeout_ms: Option<u64>,
+    /// Origins allowed to make cross-origin requests to the HTTP JSON-RPC server. If not set, any origin is allowed.
+    cors_allowed_origins: Option<Vec<String>>,
+    /// Additional request headers allowed in cross-origin requests.
+    cors_allowed_headers: Option<Vec<String>>,
+    /// Time in seconds for which browsers may cache responses to CORS preflight requests. Default is 1 hour.
+    cors_max_age_sec: Option<u64>,
+    /// Maximum number of logs returned by `eth_getLogs` and log filters. Not limited by default.
+    pub max_logs_result_size: Option<usize>,
+    /// Timeout in milliseconds for re-executing transactions in `debug_*` tracing methods.
+    #[serde(default = "OptionalENConfig::default_trace_timeout_ms")]
+    pub trace_timeout_ms: u64,
+    /// Maximum depth of call traces returned by `debug_*` tracing methods. Not limited by default.
+    pub trace_max_call_depth: Option<usize>,
+    /// Maximum number of concurrently replayed miniblocks in `debug_traceBlockBy*` methods.
+    #[serde(default = "OptionalENConfig::default_trace_block_concurrency")]
+    pub trace_block_concurrency: usize,
+    /// Maximum size of a block trace returned by `debug_traceBlockBy*` methods, measured in call frames
+    /// or in account and storage slot entries, depending on the tracer.
+    #[serde(default = "OptionalENConfig::default_trace_block_max_result_size")]
+    pub trace_block_max_result_size: usize,
+    /// Whether `txpool_content` should omit calldata and signatures of pending transactions.
+    #[serde(default)]
+    pub txpool_redact_transactions: bool,
+    /// Path to the RocksDB instance retaining state for each miniblock. If set, `eth_call`, `eth_getBalance`
+    /// and `eth_getStorageAt` requests for historical blocks are served from this instance instead of Postgres.
+    /// Must differ from the state keeper cache path.
+    pub archive_state_path: Option<String>,
 
     // Other API config settings
     /// Interval between polling DB for pubsub (in ms).
@@ -154,6 +230,10 @@ pub struct OptionalENConfig {
     /// values cache will be disabled.
     #[serde(default = "OptionalENConfig::default_latest_values_cache_size_mb")]
     latest_values_cache_size_mb: usize,
+    /// Maximum number of `eth_call` results cached for calls on top of sealed miniblocks. The default value is 1,024.
+    /// If set to 0, the cache will be disabled.
+    #[serde(default = "OptionalENConfig::default_eth_call_cache_size")]
+    pub eth_call_cache_size: usize,
     /// Enabled JSON RPC API namespaces.
     api_namespaces: Option<Vec<Namespace>>,
     /// Whether to support methods installing filters and querying filter changes.
@@ -165,6 +245,12 @@ pub struct OptionalENConfig {
     /// different node.
     #[serde(default)]
     pub filters_disabled: bool,
+    /// Whether to persist installed filters in Postgres, so that they survive node restarts.
+    #[serde(default)]
+    pub persistent_filters: bool,
+    /// Time in seconds after which a persistent filter that was not polled is removed.
+    #[serde(default = "OptionalENConfig::default_filters_ttl_sec")]
+    filters_ttl_sec: u64,
 
     // Health checks
     /// Time limit in milliseconds to mark a health check as slow and log the corresponding warning.
@@ -244,10 +330,22 @@ impl OptionalENConfig {
         10_000
     }
 
+    const fn default_filters_ttl_sec() -> u64 {
+        300
+    }
+
     const fn default_subscriptions_limit() -> usize {
         10_000
     }
 
+    const fn default_subscriptions_per_connection_limit() -> usize {
+        1_024
+    }
+
+    const fn default_websocket_message_buffer_capacity() -> usize {
+        1_024
+    }
+
     const fn default_req_entities_limit() -> usize {
         1_024
     }
@@ -307,6 +405,10 @@ impl OptionalENConfig {
         128
     }
 
+    const fn default_eth_call_cache_size() -> usize {
+        1_024
+    }
+
     const fn default_merkle_tree_multi_get_chunk_size() -> usize {
         500
     }
@@ -327,6 +429,30 @@ impl OptionalENConfig {
         1_024
     }
 
+    const fn default_gas_price_suggestion_blocks() -> u32 {
+        20
+    }
+
+    const fn default_gas_price_suggestion_percentile() -> f64 {
+        60.0
+    }
+
+    const fn default_gas_price_suggestion_smoothing() -> f64 {
+        0.5
+    }
+
+    const fn default_trace_timeout_ms() -> u64 {
+        10_000
+    }
+
+    const fn default_trace_block_concurrency() -> usize {
+        2
+    }
+
+    const fn default_trace_block_max_result_size() -> usize {
+        100_000
+    }
+
     const fn default_max_batch_request_size() -> usize {
         500 // The default limit is chosen to be reasonably permissive.
     }
@@ -343,6 +469,10 @@ impl OptionalENConfig {
         10
     }
 
+    pub fn trace_timeout(&self) -> Duration {
+        Duration::from_millis(self.trace_timeout_ms)
+    }
+
     pub fn polling_interval(&self) -> Duration {
         Duration::from_millis(self.polling_interval)
     }
@@ -381,6 +511,10 @@ impl OptionalENConfig {
         Duration::from_secs(self.merkle_tree_stalled_writes_timeout_sec)
     }
 
+    pub fn filters_ttl(&self) -> Duration {
+        Duration::from_secs(self.filters_ttl_sec)
+    }
+
     pub fn long_connection_threshold(&self) -> Option<Duration> {
         self.database_long_connection_threshold_ms
             .map(Duration::from_millis)
@@ -391,6 +525,31 @@ impl OptionalENConfig {
             .map(Duration::from_millis)
     }
 
+    pub fn api_key_limits(&self) -> ApiKeyLimits {
+        ApiKeyLimits {
+            requests_per_second: self.api_key_requests_per_second_limit,
+            compute_units_per_second: self.api_key_compute_units_per_second_limit,
+        }
+    }
+
+    /// Returns authentication settings for the JSON-RPC servers, or `None` if authentication is not configured.
+    pub fn rpc_auth(&self) -> anyhow::Result<Option<RpcAuthConfig>> {
+        let jwt_secret = self
+            .auth_jwt_secret_path
+            .as_ref()
+            .map(|path| RpcAuthConfig::read_jwt_secret(path.as_ref()))
+            .transpose()?;
+        let bearer_tokens = self.auth_bearer_tokens.clone().unwrap_or_default();
+        if bearer_tokens.is_empty() && jwt_secret.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(RpcAuthConfig {
+            bearer_tokens,
+            jwt_secret,
+            protected_namespaces: self.auth_protected_namespaces.clone(),
+        }))
+    }
+
     pub fn api_namespaces(&self) -> Vec<Namespace> {
         self.api_namespaces
             .clone()
@@ -401,6 +560,35 @@ impl OptionalENConfig {
         self.max_response_body_size_mb * BYTES_IN_MEGABYTE
     }
 
+    pub fn max_batch_response_size(&self) -> usize {
+        self.max_batch_response_size_mb
+            .unwrap_or(self.max_response_body_size_mb)
+            * BYTES_IN_MEGABYTE
+    }
+
+    pub fn batch_request_time_budget(&self) -> Option<Duration> {
+        self.batch_request_time_budget_ms.map(Duration::from_millis)
+    }
+
+    pub fn shutdown_drain_timeout(&self) -> Option<Duration> {
+        self.shutdown_drain_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn cors_config(&self) -> anyhow::Result<CorsConfig> {
+        let mut cors = CorsConfig::default()
+            .with_allowed_headers(self.cors_allowed_headers.as_deref().unwrap_or_default())
+            .context("invalid CORS allowed headers")?;
+        if let Some(max_age_sec) = self.cors_max_age_sec {
+            cors = cors.with_max_age(Duration::from_secs(max_age_sec));
+        }
+        if let Some(origins) = &self.cors_allowed_origins {
+            cors = cors
+                .with_allowed_origins(origins)
+                .context("invalid CORS allowed origins")?;
+        }
+        Ok(cors)
+    }
+
     pub fn healthcheck_slow_time_limit(&self) -> Option<Duration> {
         self.healthcheck_slow_time_limit_ms
             .map(Duration::from_millis)
@@ -492,14 +680,26 @@ pub(crate) fn read_consensus_config() -> anyhow::Result<Option<consensus::Config
 #[derive(Debug, Clone)]
 pub struct SnapshotsRecoveryConfig {
     pub snapshots_object_store: ObjectStoreConfig,
+    /// L1 batch to recover from. If not specified, the newest snapshot on the main node is used.
+    pub l1_batch: Option<L1BatchNumber>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SnapshotsRecoveryEnvConfig {
+    #[serde(default)]
+    l1_batch: Option<u32>,
 }
 
 pub(crate) fn read_snapshots_recovery_config() -> anyhow::Result<SnapshotsRecoveryConfig> {
     let snapshots_object_store = envy::prefixed("EN_SNAPSHOTS_OBJECT_STORE_")
         .from_env::<ObjectStoreConfig>()
         .context("failed loading snapshot object store config from env variables")?;
+    let env_config = envy::prefixed("EN_SNAPSHOTS_RECOVERY_")
+        .from_env::<SnapshotsRecoveryEnvConfig>()
+        .context("failed loading snapshot recovery config from env variables")?;
     Ok(SnapshotsRecoveryConfig {
         snapshots_object_store,
+        l1_batch: env_config.l1_batch.map(L1BatchNumber),
     })
 }
 
@@ -612,8 +812,17 @@ impl From<ExternalNodeConfig> for InternalApiConfig {
             diamond_proxy_addr: config.remote.diamond_proxy_addr,
             l2_testnet_paymaster_addr: config.remote.l2_testnet_paymaster_addr,
             req_entities_limit: config.optional.req_entities_limit,
+            max_logs_result_size: config.optional.max_logs_result_size,
             fee_history_limit: config.optional.fee_history_limit,
+            gas_price_suggestion_blocks: config.optional.gas_price_suggestion_blocks,
+            gas_price_suggestion_percentile: config.optional.gas_price_suggestion_percentile,
+            gas_price_suggestion_smoothing: config.optional.gas_price_suggestion_smoothing,
             filters_disabled: config.optional.filters_disabled,
+            trace_timeout: config.optional.trace_timeout(),
+            trace_max_call_depth: config.optional.trace_max_call_depth,
+            trace_block_concurrency: config.optional.trace_block_concurrency,
+            trace_block_max_result_size: config.optional.trace_block_max_result_size,
+            txpool_redact_transactions: config.optional.txpool_redact_transactions,
         }
     }
 }
@@ -638,6 +847,7 @@ impl From<ExternalNodeConfig> for TxSenderConfig {
                 .optional
                 .l1_to_l2_transactions_compatibility_mode,
             max_pubdata_per_batch: config.remote.max_pubdata_per_batch,
+            eth_call_cache_size: config.optional.eth_call_cache_size,
         }
     }
 }
@@ -86,7 +86,10 @@ pub(crate) async fn ensure_storage_initialized(
                 .create_store()
                 .await;
 
-            let config = SnapshotsApplierConfig::default();
+            let config = SnapshotsApplierConfig {
+                l1_batch_number: recovery_config.l1_batch,
+                ..SnapshotsApplierConfig::default()
+            };
             app_health.insert_component(config.health_check());
             config
                 .run(pool, main_node_client, &blob_store)
@@ -10,6 +10,7 @@ use zksync_concurrency::{ctx, limiter, scope, time};
 use zksync_config::configs::database::MerkleTreeMode;
 use zksync_core::{
     api_server::{
+        archive_state::ArchiveStateUpdater,
         execution_sandbox::VmConcurrencyLimiter,
         healthcheck::HealthCheckHandle,
         tx_sender::{proxy::TxProxy, ApiContracts, TxSenderBuilder},
@@ -284,7 +285,13 @@ async fn init_tasks(
     let fee_params_fetcher_handle =
         tokio::spawn(fee_params_fetcher.clone().run(stop_receiver.clone()));
 
-    let (tx_sender, vm_barrier, cache_update_handle, proxy_cache_updater_handle) = {
+    let (
+        tx_sender,
+        vm_barrier,
+        cache_update_handle,
+        proxy_cache_updater_handle,
+        archive_state_handle,
+    ) = {
         let tx_proxy = TxProxy::new(main_node_client);
         let proxy_cache_updater_pool = singleton_pool_builder
             .build()
@@ -295,11 +302,19 @@ async fn init_tasks(
                 .run_account_nonce_sweeper(proxy_cache_updater_pool.clone(), stop_receiver.clone()),
         );
 
-        let tx_sender_builder = TxSenderBuilder::new(
+        let mut tx_sender_builder = TxSenderBuilder::new(
             config.clone().into(),
             connection_pool.clone(),
             Arc::new(tx_proxy),
         );
+        let archive_state_handle = if let Some(path) = &config.optional.archive_state_path {
+            let (updater, archive_state) =
+                ArchiveStateUpdater::new(path.as_ref(), connection_pool.clone()).await?;
+            tx_sender_builder = tx_sender_builder.with_archive_state(archive_state);
+            Some(tokio::spawn(updater.run(stop_receiver.clone())))
+        } else {
+            None
+        };
 
         if config.optional.transactions_per_sec_limit.is_some() {
             tracing::warn!("`transactions_per_sec_limit` option is deprecated and ignored");
@@ -333,31 +348,62 @@ async fn init_tasks(
             vm_barrier,
             cache_update_handle,
             proxy_cache_updater_handle,
+            archive_state_handle,
         )
     };
 
-    let http_server_handles =
+    let mut http_api_builder =
         ApiBuilder::jsonrpsee_backend(config.clone().into(), connection_pool.clone())
             .http(config.required.http_port)
             .with_filter_limit(config.optional.filters_limit)
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
+            .with_batch_response_size_limit(config.optional.max_batch_response_size())
             .with_tx_sender(tx_sender.clone())
             .with_vm_barrier(vm_barrier.clone())
             .with_sync_state(sync_state.clone())
             .with_tree_api(tree_reader.clone())
-            .enable_api_namespaces(config.optional.api_namespaces())
-            .build()
-            .context("failed to build HTTP JSON-RPC server")?
-            .run(stop_receiver.clone())
-            .await
-            .context("Failed initializing HTTP JSON-RPC server")?;
+            .with_cors(config.optional.cors_config()?)
+            .enable_api_namespaces(config.optional.api_namespaces());
+    if let Some(api_keys) = &config.optional.api_keys {
+        http_api_builder =
+            http_api_builder.with_api_keys(api_keys.clone(), config.optional.api_key_limits());
+    }
+    let rpc_auth = config.optional.rpc_auth()?;
+    if let Some(auth) = &rpc_auth {
+        http_api_builder = http_api_builder.with_auth(auth.clone());
+    }
+    if let Some(time_budget) = config.optional.batch_request_time_budget() {
+        http_api_builder = http_api_builder.with_batch_time_budget(time_budget);
+    }
+    if let Some(threshold) = config.optional.http_response_compression_threshold {
+        http_api_builder = http_api_builder.with_response_compression(threshold);
+    }
+    if let Some(timeout) = config.optional.shutdown_drain_timeout() {
+        http_api_builder = http_api_builder.with_drain_timeout(timeout);
+    }
+    if config.optional.persistent_filters {
+        http_api_builder = http_api_builder
+            .with_persistent_filters(connection_pool.clone(), config.optional.filters_ttl());
+    }
+    let http_server_handles = http_api_builder
+        .build()
+        .context("failed to build HTTP JSON-RPC server")?
+        .run(stop_receiver.clone())
+        .await
+        .context("Failed initializing HTTP JSON-RPC server")?;
 
-    let ws_server_handles =
+    let mut ws_api_builder =
         ApiBuilder::jsonrpsee_backend(config.clone().into(), connection_pool.clone())
             .ws(config.required.ws_port)
             .with_filter_limit(config.optional.filters_limit)
             .with_subscriptions_limit(config.optional.subscriptions_limit)
+            .with_subscriptions_per_connection_limit(
+                config.optional.subscriptions_per_connection_limit,
+            )
+            .with_websocket_message_buffer_capacity(
+                config.optional.websocket_message_buffer_capacity,
+            )
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
             .with_polling_interval(config.optional.polling_interval())
@@ -365,12 +411,30 @@ async fn init_tasks(
             .with_vm_barrier(vm_barrier)
             .with_sync_state(sync_state)
             .with_tree_api(tree_reader)
-            .enable_api_namespaces(config.optional.api_namespaces())
-            .build()
-            .context("failed to build WS JSON-RPC server")?
-            .run(stop_receiver.clone())
-            .await
-            .context("Failed initializing WS JSON-RPC server")?;
+            .enable_api_namespaces(config.optional.api_namespaces());
+    if let Some(api_keys) = &config.optional.api_keys {
+        ws_api_builder =
+            ws_api_builder.with_api_keys(api_keys.clone(), config.optional.api_key_limits());
+    }
+    if let Some(auth) = rpc_auth {
+        ws_api_builder = ws_api_builder.with_auth(auth);
+    }
+    if let Some(limit) = config.optional.websocket_compute_units_per_second_limit {
+        ws_api_builder = ws_api_builder.with_websocket_compute_units_per_second_limit(limit);
+    }
+    if let Some(timeout) = config.optional.shutdown_drain_timeout() {
+        ws_api_builder = ws_api_builder.with_drain_timeout(timeout);
+    }
+    if config.optional.persistent_filters {
+        ws_api_builder = ws_api_builder
+            .with_persistent_filters(connection_pool.clone(), config.optional.filters_ttl());
+    }
+    let ws_server_handles = ws_api_builder
+        .build()
+        .context("failed to build WS JSON-RPC server")?
+        .run(stop_receiver.clone())
+        .await
+        .context("Failed initializing WS JSON-RPC server")?;
 
     app_health.insert_component(ws_server_handles.health_check);
     app_health.insert_component(http_server_handles.health_check);
@@ -392,6 +456,7 @@ async fn init_tasks(
     task_handles.extend(http_server_handles.tasks);
     task_handles.extend(ws_server_handles.tasks);
     task_handles.extend(cache_update_handle);
+    task_handles.extend(archive_state_handle);
     task_handles.push(proxy_cache_updater_handle);
     task_handles.extend([
         sk_handle,
@@ -194,14 +194,24 @@ impl SnapshotCreator {
         latest_snapshot: Option<&SnapshotMetadata>,
         conn: &mut StorageProcessor<'_>,
     ) -> anyhow::Result<Option<SnapshotProgress>> {
-        // We subtract 1 so that after restore, EN node has at least one L1 batch to fetch
         let sealed_l1_batch_number = conn.blocks_dal().get_sealed_l1_batch_number().await?;
         let sealed_l1_batch_number = sealed_l1_batch_number.context("No L1 batches in Postgres")?;
-        anyhow::ensure!(
-            sealed_l1_batch_number != L1BatchNumber(0),
-            "Cannot create snapshot when only the genesis L1 batch is present in Postgres"
-        );
-        let l1_batch_number = sealed_l1_batch_number - 1;
+        let l1_batch_number = if let Some(requested_l1_batch_number) = config.l1_batch_number {
+            let requested_l1_batch_number = L1BatchNumber(requested_l1_batch_number);
+            anyhow::ensure!(
+                requested_l1_batch_number <= sealed_l1_batch_number,
+                "Requested L1 batch #{requested_l1_batch_number} for the snapshot is not sealed yet; \
+                 the last sealed L1 batch is #{sealed_l1_batch_number}"
+            );
+            requested_l1_batch_number
+        } else {
+            anyhow::ensure!(
+                sealed_l1_batch_number != L1BatchNumber(0),
+                "Cannot create snapshot when only the genesis L1 batch is present in Postgres"
+            );
+            // We subtract 1 so that after restore, EN node has at least one L1 batch to fetch
+            sealed_l1_batch_number - 1
+        };
 
         let latest_snapshot_l1_batch_number =
             latest_snapshot.map(|snapshot| snapshot.l1_batch_number);
@@ -239,10 +249,18 @@ impl SnapshotCreator {
             .master_pool
             .access_storage_tagged("snapshots_creator")
             .await?;
-        let latest_snapshot = master_conn
-            .snapshots_dal()
-            .get_newest_snapshot_metadata()
-            .await?;
+        // If the L1 batch is specified explicitly, we only care about the snapshot for this batch.
+        let latest_snapshot = if let Some(l1_batch_number) = config.l1_batch_number {
+            master_conn
+                .snapshots_dal()
+                .get_snapshot_metadata(L1BatchNumber(l1_batch_number))
+                .await?
+        } else {
+            master_conn
+                .snapshots_dal()
+                .get_newest_snapshot_metadata()
+                .await?
+        };
         drop(master_conn);
 
         let pending_snapshot = latest_snapshot
@@ -281,6 +299,17 @@ impl SnapshotCreator {
         };
 
         let mut conn = self.connect_to_replica().await?;
+        // Pruning removes overwritten storage logs, so a snapshot for a pruned L1 batch would contain
+        // values from later batches.
+        let pruning_info = conn.pruning_dal().get_pruning_info().await?;
+        if let Some(info) = pruning_info {
+            anyhow::ensure!(
+                progress.l1_batch_number > info.last_pruned_l1_batch,
+                "Cannot create snapshot for L1 batch #{}: storage logs are pruned up to and including L1 batch #{}",
+                progress.l1_batch_number,
+                info.last_pruned_l1_batch
+            );
+        }
         let (_, last_miniblock_number_in_batch) = conn
             .blocks_dal()
             .get_miniblock_range_of_l1_batch(progress.l1_batch_number)
@@ -85,10 +85,12 @@ async fn main() -> anyhow::Result<()> {
         postgres_config.replica_url()?,
         creator_config.concurrent_queries_count,
     )
+    .set_schema(postgres_config.schema())
     .build()
     .await?;
 
     let master_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+        .set_schema(postgres_config.schema())
         .build()
         .await?;
 
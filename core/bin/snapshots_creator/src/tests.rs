@@ -27,10 +27,12 @@ use super::*;
 const TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 10,
+    l1_batch_number: None,
 };
 const SEQUENTIAL_TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 1,
+    l1_batch_number: None,
 };
 
 #[derive(Debug)]
@@ -329,6 +331,66 @@ async fn persisting_snapshot_logs() {
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
 }
 
+#[tokio::test]
+async fn persisting_snapshot_at_specified_l1_batch() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.access_storage().await.unwrap();
+    // Expected outputs cover L1 batches #0..=5.
+    let expected_outputs = prepare_postgres(&mut rng, &mut conn, 7).await;
+    for block_number in 7..10 {
+        let logs = gen_storage_logs(&mut rng, 100);
+        create_miniblock(&mut conn, MiniblockNumber(block_number), logs.clone()).await;
+        create_l1_batch(&mut conn, L1BatchNumber(block_number), &logs).await;
+    }
+
+    let config = SnapshotsCreatorConfig {
+        l1_batch_number: Some(5),
+        ..TEST_CONFIG
+    };
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(config, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+
+    let snapshots = conn
+        .snapshots_dal()
+        .get_all_complete_snapshots()
+        .await
+        .unwrap();
+    assert_eq!(snapshots.snapshots_l1_batch_numbers, [L1BatchNumber(5)]);
+    let object_store = object_store_factory.create_store().await;
+    assert_storage_logs(&*object_store, L1BatchNumber(5), &expected_outputs).await;
+
+    // Requesting a non-sealed L1 batch should fail.
+    let config = SnapshotsCreatorConfig {
+        l1_batch_number: Some(100),
+        ..TEST_CONFIG
+    };
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(config, MIN_CHUNK_COUNT)
+        .await
+        .unwrap_err();
+
+    // Requesting a pruned L1 batch should fail as well.
+    conn.pruning_dal()
+        .prune_l1_batches(L1BatchNumber(7), MiniblockNumber(7))
+        .await
+        .unwrap();
+    let config = SnapshotsCreatorConfig {
+        l1_batch_number: Some(7),
+        ..TEST_CONFIG
+    };
+    let object_store = object_store_factory.create_store().await;
+    let err = SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(config, MIN_CHUNK_COUNT)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("pruned"), "{err}");
+}
+
 async fn assert_storage_logs(
     object_store: &dyn ObjectStore,
     snapshot_l1_batch_number: L1BatchNumber,
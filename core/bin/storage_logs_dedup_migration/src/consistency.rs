@@ -26,6 +26,7 @@ async fn main() {
     let config = PostgresConfig::from_env().unwrap();
     let opt = Cli::parse();
     let pool = ConnectionPool::singleton(config.replica_url().unwrap())
+        .set_schema(config.schema())
         .build()
         .await
         .unwrap();
@@ -9,6 +9,7 @@ use zksync_types::contract_verification_api::SourceCodeData;
 async fn main() {
     let config = PostgresConfig::from_env().unwrap();
     let pool = ConnectionPool::singleton(config.replica_url().unwrap())
+        .set_schema(config.schema())
         .build()
         .await
         .unwrap();
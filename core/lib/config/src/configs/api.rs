@@ -47,6 +47,10 @@ pub struct Web3JsonRpcConfig {
     pub subscriptions_limit: Option<u32>,
     /// Interval between polling db for pubsub (in ms).
     pub pubsub_polling_interval: Option<u64>,
+    /// Whether pubsub notifiers should be woken up by Postgres notifications about sealed miniblocks
+    /// in addition to polling. Requires the API server to have access to the master DB.
+    #[serde(default)]
+    pub pubsub_db_notifications: bool,
     /// Tx nonce: how far ahead from the committed nonce can it be.
     pub max_nonce_ahead: u32,
     /// The multiplier to use when suggesting gas price. Should be higher than one,
@@ -84,18 +88,116 @@ pub struct Web3JsonRpcConfig {
     /// Latest values cache size in MiBs. The default value is 128 MiB. If set to 0, the latest
     /// values cache will be disabled.
     pub latest_values_cache_size_mb: Option<usize>,
+    /// Maximum number of `eth_call` results cached for calls on top of sealed miniblocks. The default value is 1,024.
+    /// If set to 0, the cache will be disabled.
+    pub eth_call_cache_size: Option<usize>,
     /// Limit for fee history block range.
     pub fee_history_limit: Option<u64>,
     /// Maximum number of requests in a single batch JSON RPC request. Default is 500.
     pub max_batch_request_size: Option<usize>,
     /// Maximum response body size in MiBs. Default is 10 MiB.
     pub max_response_body_size_mb: Option<usize>,
+    /// Maximum aggregate size of responses to a batch HTTP request in MiBs. Responses exceeding the limit
+    /// are replaced with errors. Default is `max_response_body_size_mb`.
+    pub max_batch_response_size_mb: Option<usize>,
+    /// Time budget for processing a batch HTTP request in milliseconds. Calls not completed within the budget
+    /// are replaced with errors. Not limited by default.
+    pub batch_request_time_budget_ms: Option<u64>,
     /// Maximum number of requests per minute for the WebSocket server.
     /// The value is per active connection.
     /// Note: For HTTP, rate limiting is expected to be configured on the infra level.
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    /// Maximum number of compute units spent per second by the WebSocket server. The value is per active connection.
+    /// Each method has its own cost in compute units; e.g., `eth_call` and `eth_getLogs` cost much more than `eth_chainId`.
+    pub websocket_compute_units_per_second_limit: Option<NonZeroU32>,
+    /// Maximum number of active subscriptions per WebSocket connection. Default is 1,024.
+    pub subscriptions_per_connection_limit: Option<u32>,
+    /// Maximum number of messages buffered for a WebSocket connection, such as subscription notifications
+    /// not yet received by a slow client. Subscribers that cannot push notifications to a full buffer
+    /// for a while are dropped. Default is 1,024.
+    pub websocket_message_buffer_capacity: Option<u32>,
+    /// API keys accepted by the server. If set, each HTTP request and WebSocket connection must provide one of the keys,
+    /// either in the `X-API-Key` header or as the request path (`/<key>`).
+    pub api_keys: Option<Vec<String>>,
+    /// Maximum number of HTTP requests (including WebSocket handshakes) per second for each API key.
+    /// Only applies if `api_keys` are set.
+    pub api_key_requests_per_second_limit: Option<NonZeroU32>,
+    /// Maximum number of compute units per second for each API key. Only applies if `api_keys` are set.
+    pub api_key_compute_units_per_second_limit: Option<NonZeroU32>,
     /// Tree API url, currently used to proxy `getProof` calls to the tree
     pub tree_api_url: Option<String>,
+    /// Maximum number of logs returned by `eth_getLogs` and log filters. If not set, the number of logs
+    /// is only limited if the logs span multiple blocks (by `req_entities_limit`).
+    pub max_logs_result_size: Option<usize>,
+    /// Timeout in milliseconds for re-executing transactions in `debug_*` tracing methods. Default is 10 seconds.
+    pub trace_timeout_ms: Option<u64>,
+    /// Maximum depth of call traces returned by `debug_*` tracing methods; deeper calls are omitted.
+    /// If not set, call traces are not truncated.
+    pub trace_max_call_depth: Option<usize>,
+    /// Maximum number of concurrently replayed miniblocks in `debug_traceBlockBy*` methods. Default is 2.
+    pub trace_block_concurrency: Option<usize>,
+    /// Maximum size of a block trace returned by `debug_traceBlockBy*` methods, measured in call frames (for `callTracer`)
+    /// or in account and storage slot entries (for `prestateTracer`). Default is 100,000.
+    pub trace_block_max_result_size: Option<usize>,
+    /// Whether to enable the `txpool` namespace exposing pending transactions on the HTTP and WebSocket servers.
+    #[serde(default)]
+    pub txpool_namespace_enabled: bool,
+    /// Whether to strip calldata and signatures from transactions returned by `txpool_content`.
+    /// Recommended for public deployments.
+    #[serde(default)]
+    pub txpool_redact_transactions: bool,
+    /// Path to the RocksDB instance retaining state for each miniblock. If set, `eth_call`, `eth_getBalance`
+    /// and `eth_getStorageAt` requests for historical blocks are served from this instance instead of Postgres.
+    /// The instance is synchronized with Postgres by the API server; it must not be shared with the state keeper.
+    pub archive_state_path: Option<String>,
+    /// Whether to persist installed filters in Postgres, so that they survive API server restarts and can be polled
+    /// from any API server connected to the same database. Requires the API server to have access to the master DB.
+    #[serde(default)]
+    pub persistent_filters: bool,
+    /// Time in seconds after which a filter that was not polled is removed. Only applies to persistent filters.
+    /// Default is 5 minutes.
+    pub filters_ttl_sec: Option<u64>,
+    /// Static bearer tokens accepted by the server in the `Authorization` header. If this or `auth_jwt_secret_path`
+    /// is set, requests must be authenticated (either all of them, or only calls to `auth_protected_namespaces`).
+    #[serde(default)]
+    pub auth_bearer_tokens: Vec<String>,
+    /// Path to the file with a hex-encoded secret used to verify HS256-signed JWTs provided as bearer tokens,
+    /// like in the Engine API.
+    pub auth_jwt_secret_path: Option<String>,
+    /// Namespaces (e.g., `debug`) calls to which require authentication. If not set, all calls require authentication.
+    /// Only applies if authentication is configured.
+    pub auth_protected_namespaces: Option<Vec<String>>,
+    /// Namespaces enabled on the HTTP and WebSocket servers (e.g., `eth`, `zks`, `debug`). If set, this list replaces
+    /// the default namespaces and overrides `txpool_namespace_enabled`, so that sensitive namespaces can be exposed
+    /// only on an internal server instance.
+    pub api_namespaces: Option<Vec<String>>,
+    /// Minimum size of an HTTP response in bytes to be compressed with gzip or Brotli (the encoding is negotiated
+    /// using the `Accept-Encoding` request header). If not set, responses are not compressed.
+    pub http_response_compression_threshold: Option<usize>,
+    /// Number of recent miniblocks considered when suggesting priority fees for `eth_maxPriorityFeePerGas`
+    /// and `eth_gasPrice`. Default is 20.
+    pub gas_price_suggestion_blocks: Option<u32>,
+    /// Percentile (0..=100) of effective priority fees in each miniblock used for the suggestion,
+    /// weighted by gas used. Default is 60.
+    pub gas_price_suggestion_percentile: Option<f64>,
+    /// Smoothing factor (0..1) for the exponential moving average over per-miniblock percentiles; higher values
+    /// put more weight on older miniblocks. Default is 0.5; 0 means only the latest miniblock is used.
+    pub gas_price_suggestion_smoothing: Option<f64>,
+    /// Time in milliseconds given to in-flight calls to complete once the server receives a stop signal. During this time,
+    /// new requests are rejected and the server is reported as not ready. Default is 5 seconds.
+    pub shutdown_drain_timeout_ms: Option<u64>,
+    /// Origins allowed to make cross-origin requests to the HTTP server (e.g., `https://app.example.com`).
+    /// If not set, requests from any origin are allowed.
+    pub cors_allowed_origins: Option<Vec<String>>,
+    /// Additional request headers allowed in cross-origin requests. `Content-Type` and headers used by the server
+    /// itself (e.g., for authentication) are always allowed.
+    #[serde(default)]
+    pub cors_allowed_headers: Vec<String>,
+    /// Time in seconds for which browsers may cache responses to CORS preflight requests. Default is 1 hour.
+    pub cors_max_age_sec: Option<u64>,
+    /// Minimum percentage by which a transaction must raise `max_fee_per_gas` to replace a pending transaction
+    /// with the same initiator and nonce. Default is 10%.
+    pub tx_replacement_fee_bump_percent: Option<u32>,
 }
 
 impl Web3JsonRpcConfig {
@@ -113,6 +215,7 @@ impl Web3JsonRpcConfig {
             filters_limit: Some(10000),
             subscriptions_limit: Some(10000),
             pubsub_polling_interval: Some(200),
+            pubsub_db_notifications: false,
             max_nonce_ahead: 50,
             gas_price_scale_factor: 1.2,
             request_timeout: Default::default(),
@@ -126,11 +229,43 @@ impl Web3JsonRpcConfig {
             factory_deps_cache_size_mb: Default::default(),
             initial_writes_cache_size_mb: Default::default(),
             latest_values_cache_size_mb: Default::default(),
+            eth_call_cache_size: None,
             fee_history_limit: Default::default(),
             max_batch_request_size: Default::default(),
             max_response_body_size_mb: Default::default(),
+            max_batch_response_size_mb: None,
+            batch_request_time_budget_ms: None,
             websocket_requests_per_minute_limit: Default::default(),
+            websocket_compute_units_per_second_limit: None,
+            subscriptions_per_connection_limit: None,
+            websocket_message_buffer_capacity: None,
+            api_keys: None,
+            api_key_requests_per_second_limit: None,
+            api_key_compute_units_per_second_limit: None,
             tree_api_url: None,
+            max_logs_result_size: None,
+            trace_timeout_ms: None,
+            trace_max_call_depth: None,
+            trace_block_concurrency: None,
+            trace_block_max_result_size: None,
+            txpool_namespace_enabled: false,
+            txpool_redact_transactions: false,
+            archive_state_path: None,
+            persistent_filters: false,
+            filters_ttl_sec: None,
+            auth_bearer_tokens: vec![],
+            auth_jwt_secret_path: None,
+            auth_protected_namespaces: None,
+            api_namespaces: None,
+            http_response_compression_threshold: None,
+            gas_price_suggestion_blocks: None,
+            gas_price_suggestion_percentile: None,
+            gas_price_suggestion_smoothing: None,
+            shutdown_drain_timeout_ms: None,
+            cors_allowed_origins: None,
+            cors_allowed_headers: vec![],
+            cors_max_age_sec: None,
+            tx_replacement_fee_bump_percent: None,
         }
     }
 
@@ -150,6 +285,10 @@ impl Web3JsonRpcConfig {
         self.filters_limit.unwrap_or(10000) as usize
     }
 
+    pub fn filters_ttl(&self) -> Duration {
+        Duration::from_secs(self.filters_ttl_sec.unwrap_or(300))
+    }
+
     pub fn subscriptions_limit(&self) -> usize {
         self.subscriptions_limit.unwrap_or(10000) as usize
     }
@@ -188,10 +327,26 @@ impl Web3JsonRpcConfig {
         self.latest_values_cache_size_mb.unwrap_or(128) * super::BYTES_IN_MEGABYTE
     }
 
+    pub fn eth_call_cache_size(&self) -> usize {
+        self.eth_call_cache_size.unwrap_or(1_024)
+    }
+
     pub fn fee_history_limit(&self) -> u64 {
         self.fee_history_limit.unwrap_or(1024)
     }
 
+    pub fn gas_price_suggestion_blocks(&self) -> u32 {
+        self.gas_price_suggestion_blocks.unwrap_or(20)
+    }
+
+    pub fn gas_price_suggestion_percentile(&self) -> f64 {
+        self.gas_price_suggestion_percentile.unwrap_or(60.0)
+    }
+
+    pub fn gas_price_suggestion_smoothing(&self) -> f64 {
+        self.gas_price_suggestion_smoothing.unwrap_or(0.5)
+    }
+
     pub fn max_batch_request_size(&self) -> usize {
         // The default limit is chosen to be reasonably permissive.
         self.max_batch_request_size.unwrap_or(500)
@@ -201,15 +356,58 @@ impl Web3JsonRpcConfig {
         self.max_response_body_size_mb.unwrap_or(10) * super::BYTES_IN_MEGABYTE
     }
 
+    pub fn max_batch_response_size(&self) -> usize {
+        self.max_batch_response_size_mb.map_or_else(
+            || self.max_response_body_size(),
+            |mb| mb * super::BYTES_IN_MEGABYTE,
+        )
+    }
+
+    pub fn batch_request_time_budget(&self) -> Option<Duration> {
+        self.batch_request_time_budget_ms.map(Duration::from_millis)
+    }
+
+    pub fn shutdown_drain_timeout(&self) -> Option<Duration> {
+        self.shutdown_drain_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn cors_max_age(&self) -> Duration {
+        Duration::from_secs(self.cors_max_age_sec.unwrap_or(3_600))
+    }
+
+    pub fn tx_replacement_fee_bump_percent(&self) -> u32 {
+        self.tx_replacement_fee_bump_percent.unwrap_or(10)
+    }
+
     pub fn websocket_requests_per_minute_limit(&self) -> NonZeroU32 {
         // The default limit is chosen to be reasonably permissive.
         self.websocket_requests_per_minute_limit
             .unwrap_or(NonZeroU32::new(6000).unwrap())
     }
 
+    pub fn subscriptions_per_connection_limit(&self) -> usize {
+        self.subscriptions_per_connection_limit.unwrap_or(1_024) as usize
+    }
+
+    pub fn websocket_message_buffer_capacity(&self) -> usize {
+        self.websocket_message_buffer_capacity.unwrap_or(1_024) as usize
+    }
+
     pub fn tree_api_url(&self) -> Option<&str> {
         self.tree_api_url.as_deref()
     }
+
+    pub fn trace_timeout(&self) -> Duration {
+        Duration::from_millis(self.trace_timeout_ms.unwrap_or(10_000))
+    }
+
+    pub fn trace_block_concurrency(&self) -> usize {
+        self.trace_block_concurrency.unwrap_or(2)
+    }
+
+    pub fn trace_block_max_result_size(&self) -> usize {
+        self.trace_block_max_result_size.unwrap_or(100_000)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -1,7 +1,7 @@
 use std::{str::FromStr, time::Duration};
 
 use serde::Deserialize;
-use zksync_basic_types::{network::Network, Address, L2ChainId};
+use zksync_basic_types::{network::Network, Address, L2ChainId, H256};
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct NetworkConfig {
@@ -116,6 +116,57 @@ pub struct StateKeeperConfig {
 
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
     pub enum_index_migration_chunk_size: Option<usize>,
+
+    /// If set, the state keeper finishes the current miniblock and seals the open L1 batch when it receives a stop signal,
+    /// so that the batch doesn't need to be re-executed on the next start.
+    #[serde(default)]
+    pub seal_l1_batch_on_shutdown: bool,
+
+    /// Addresses that L2 transactions are not allowed to be initiated by, paid for by, or sent to.
+    /// Such transactions are rejected on mempool admission and before their inclusion into a miniblock.
+    #[serde(default)]
+    pub denied_addresses: Vec<Address>,
+    /// Hashes of bytecodes that L2 transactions are not allowed to deploy.
+    #[serde(default)]
+    pub denied_bytecode_hashes: Vec<H256>,
+
+    /// Postgres schema of the chain to mirror L2 transactions from. If set, the state keeper runs in the shadow mode:
+    /// it executes and seals batches as usual, but L2 transactions are copied from the mempool of the specified schema,
+    /// and Ethereum sender components are not allowed to run. The node must use a dedicated schema different
+    /// from the mirrored one.
+    #[serde(default)]
+    pub shadow_source_schema: Option<String>,
+
+    /// Number of L1 batches awaiting proof generation after which L1 batch sealing is slowed down. If exceeded,
+    /// the L1 batch commit deadline is increased proportionally to the backlog (up to 4x), so that fewer, larger
+    /// batches are produced until provers catch up. Only timeout-based sealing is affected, i.e., this only has
+    /// an effect when traffic is low; full batches are sealed regardless of the backlog. If not set, the proof backlog
+    /// doesn't affect sealing.
+    #[serde(default)]
+    pub proof_backlog_threshold: Option<u32>,
+
+    /// Per-transaction caps for L2 transactions enforced both on mempool admission and by the state keeper,
+    /// in addition to `max_allowed_l2_tx_gas_limit`. Unset caps are not enforced (except for the number of factory
+    /// dependencies, which is always capped by the protocol limit).
+    ///
+    /// Maximum `gas_per_pubdata_limit` of a transaction.
+    #[serde(default)]
+    pub max_tx_gas_per_pubdata: Option<u32>,
+    /// Maximum calldata size of a transaction in bytes.
+    #[serde(default)]
+    pub max_tx_calldata_size: Option<u32>,
+    /// Maximum number of factory dependencies of a transaction.
+    #[serde(default)]
+    pub max_tx_factory_deps: Option<u32>,
+    /// Maximum total size of factory dependencies of a transaction in bytes.
+    #[serde(default)]
+    pub max_tx_factory_deps_size: Option<u32>,
+
+    /// If set, the state keeper uploads a compact storage diff for each sealed L1 batch (touched accounts and written
+    /// slots with their enumeration indices) to the object store, so that downstream consumers don't need to query
+    /// Postgres for this data.
+    #[serde(default)]
+    pub upload_l1_batch_state_diffs: bool,
 }
 
 impl StateKeeperConfig {
@@ -150,6 +201,16 @@ impl StateKeeperConfig {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: None,
+            seal_l1_batch_on_shutdown: false,
+            denied_addresses: vec![],
+            denied_bytecode_hashes: vec![],
+            shadow_source_schema: None,
+            proof_backlog_threshold: None,
+            max_tx_gas_per_pubdata: None,
+            max_tx_calldata_size: None,
+            max_tx_factory_deps: None,
+            max_tx_factory_deps_size: None,
+            upload_l1_batch_state_diffs: false,
         }
     }
 
@@ -188,6 +249,18 @@ impl CircuitBreakerConfig {
     }
 }
 
+/// Order in which ready L2 transactions are taken from the mempool. Transactions of the same account
+/// are always taken in the nonce order.
+///  - `EffectivePriorityFee`, transactions paying the highest priority fee given the current base fee go first.
+///    Transactions with equal priority fees are taken in the order they were received.
+///  - `Fifo` (default), transactions are taken in the order they were received.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+pub enum MempoolTxOrdering {
+    EffectivePriorityFee,
+    #[default]
+    Fifo,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct MempoolConfig {
     pub sync_interval_ms: u64,
@@ -196,6 +269,16 @@ pub struct MempoolConfig {
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
     pub delay_interval: u64,
+    /// Maximum number of queued transactions (i.e., ones that cannot be executed because of a nonce gap)
+    /// kept in the mempool per account. If not set, the number of queued transactions is not limited.
+    pub max_queued_txs_per_account: Option<usize>,
+    /// Maximum total size of L2 transactions kept in the mempool in bytes. Once it is exceeded, accounts
+    /// are purged in the same way as when the mempool `capacity` is reached. If not set, only the number
+    /// of transactions is limited.
+    pub max_size_bytes: Option<usize>,
+    /// Order in which ready transactions are taken from the mempool.
+    #[serde(default)]
+    pub tx_ordering: MempoolTxOrdering,
 }
 
 impl MempoolConfig {
@@ -129,6 +129,11 @@ pub struct PostgresConfig {
     pub master_url: Option<String>,
     /// URL for the replica database.
     pub replica_url: Option<String>,
+    /// URLs for additional replica databases. If specified, read-only workloads (e.g., the API server)
+    /// are served by the least lagging of `replica_url` and these replicas; unavailable replicas are skipped
+    /// until they pass a health check. Requests fail over to `master_url` if none of the replicas
+    /// can provide a connection.
+    pub additional_replica_urls: Vec<String>,
     /// URL for the prover database.
     pub prover_url: Option<String>,
     /// Maximum size of the connection pool.
@@ -144,6 +149,25 @@ pub struct PostgresConfig {
     pub long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
     pub slow_query_threshold_ms: Option<u64>,
+
+    /// Number of master DB connections reserved for the API servers in a dedicated pool.
+    /// If not specified, the API servers use the main connection pool.
+    pub api_max_connections: Option<u32>,
+    /// Number of master DB connections reserved for the state keeper in a dedicated pool. Auxiliary state keeper tasks
+    /// (the miniblock sealer, mempool fetcher etc.) always use separate singleton pools. If not specified, the state keeper
+    /// uses a singleton pool.
+    pub state_keeper_max_connections: Option<u32>,
+    /// Number of master DB connections reserved for witness generation components (e.g., the basic witness input producer)
+    /// in a dedicated pool.
+    pub witness_generator_max_connections: Option<u32>,
+    /// Number of master DB connections reserved for the house keeper in a dedicated pool shared by its tasks
+    /// (e.g., the DB pruner and partition manager).
+    pub house_keeper_max_connections: Option<u32>,
+
+    /// Postgres schema containing the chain tables. Allows running multiple chains against a single database,
+    /// with each chain using a dedicated schema. If not specified, the default `search_path` is used.
+    /// Only applies to the main (master / replica) database; the prover database is not chain-scoped.
+    pub schema: Option<String>,
 }
 
 impl PostgresConfig {
@@ -161,6 +185,11 @@ impl PostgresConfig {
             .context("Replica DB URL is absent")
     }
 
+    /// Returns additional replica database URLs (i.e., ones except for [`Self::replica_url()`]).
+    pub fn additional_replica_urls(&self) -> impl Iterator<Item = &str> + '_ {
+        self.additional_replica_urls.iter().map(String::as_str)
+    }
+
     /// Returns a copy of the prover database URL as a `Result` to simplify error propagation.
     pub fn prover_url(&self) -> anyhow::Result<&str> {
         self.prover_url
@@ -190,4 +219,9 @@ impl PostgresConfig {
     pub fn slow_query_threshold(&self) -> Option<Duration> {
         self.slow_query_threshold_ms.map(Duration::from_millis)
     }
+
+    /// Returns the Postgres schema containing the chain tables.
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
 }
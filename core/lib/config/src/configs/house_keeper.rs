@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
 /// Configuration for the house keeper.
@@ -16,4 +18,48 @@ pub struct HouseKeeperConfig {
     pub fri_prover_stats_reporting_interval_ms: u64,
     pub fri_proof_compressor_job_retrying_interval_ms: u64,
     pub fri_proof_compressor_stats_reporting_interval_ms: u64,
+    /// Retention period in days for the data of L1 batches executed on L1. Events, call traces, overwritten
+    /// storage logs and factory deps in transaction data for older L1 batches are pruned.
+    /// If not specified, DB pruning is disabled.
+    pub db_pruning_retention_days: Option<u64>,
+    /// Interval between DB pruning iterations.
+    pub db_pruning_interval_ms: Option<u64>,
+    /// Size of `events` table partitions in miniblocks. If specified, partitions
+    /// are created ahead of the sealed miniblock; otherwise, partition management is disabled.
+    pub db_partition_size_miniblocks: Option<u32>,
+    /// Retention period in days for `events` partitions of L1 batches executed on L1. Older partitions
+    /// are detached from the table, so that they can be archived. If not specified, partitions are never detached.
+    pub db_events_archival_retention_days: Option<u64>,
+    /// Interval between DB partition management iterations.
+    pub db_partitioning_interval_ms: Option<u64>,
+    /// Interval between checks of derived L1 batch data in Postgres (transaction counts, logs blooms,
+    /// L2-to-L1 logs Merkle roots). If not specified, consistency checks are disabled.
+    pub db_consistency_check_interval_ms: Option<u64>,
+}
+
+impl HouseKeeperConfig {
+    const DEFAULT_DB_PRUNING_INTERVAL_MS: u64 = 60_000;
+    const DEFAULT_DB_PARTITIONING_INTERVAL_MS: u64 = 600_000;
+
+    /// Returns the retention period for DB pruning, or `None` if pruning is disabled.
+    pub fn db_pruning_retention(&self) -> Option<Duration> {
+        self.db_pruning_retention_days
+            .map(|days| Duration::from_secs(days * 24 * 3_600))
+    }
+
+    pub fn db_pruning_interval_ms(&self) -> u64 {
+        self.db_pruning_interval_ms
+            .unwrap_or(Self::DEFAULT_DB_PRUNING_INTERVAL_MS)
+    }
+
+    /// Returns the retention period for `events` partitions, or `None` if partitions should never be detached.
+    pub fn db_events_archival_retention(&self) -> Option<Duration> {
+        self.db_events_archival_retention_days
+            .map(|days| Duration::from_secs(days * 24 * 3_600))
+    }
+
+    pub fn db_partitioning_interval_ms(&self) -> u64 {
+        self.db_partitioning_interval_ms
+            .unwrap_or(Self::DEFAULT_DB_PARTITIONING_INTERVAL_MS)
+    }
 }
@@ -7,6 +7,11 @@ pub struct SnapshotsCreatorConfig {
 
     #[serde(default = "snapshots_creator_concurrent_queries_count")]
     pub concurrent_queries_count: u32,
+
+    /// L1 batch to create the snapshot for. If not set, the snapshot is created for the L1 batch
+    /// preceding the last sealed one, so that a node recovered from the snapshot has at least one L1 batch to fetch.
+    #[serde(default)]
+    pub l1_batch_number: Option<u32>,
 }
 
 fn snapshots_creator_storage_logs_chunk_size_default() -> u64 {
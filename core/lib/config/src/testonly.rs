@@ -160,6 +160,15 @@ impl RandomConfig for configs::chain::FeeModelVersion {
     }
 }
 
+impl RandomConfig for configs::chain::MempoolTxOrdering {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::EffectivePriorityFee,
+            _ => Self::Fifo,
+        }
+    }
+}
+
 impl RandomConfig for configs::AlertsConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
@@ -192,6 +201,7 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
             filters_limit: g.gen(),
             subscriptions_limit: g.gen(),
             pubsub_polling_interval: g.gen(),
+            pubsub_db_notifications: g.gen(),
             max_nonce_ahead: g.gen(),
             gas_price_scale_factor: g.gen(),
             request_timeout: g.gen(),
@@ -205,11 +215,43 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
             factory_deps_cache_size_mb: g.gen(),
             initial_writes_cache_size_mb: g.gen(),
             latest_values_cache_size_mb: g.gen(),
+            eth_call_cache_size: g.gen(),
             fee_history_limit: g.gen(),
             max_batch_request_size: g.gen(),
             max_response_body_size_mb: g.gen(),
+            max_batch_response_size_mb: g.gen(),
+            batch_request_time_budget_ms: g.gen(),
             websocket_requests_per_minute_limit: g.gen(),
+            websocket_compute_units_per_second_limit: g.gen(),
+            subscriptions_per_connection_limit: g.gen(),
+            websocket_message_buffer_capacity: g.gen(),
+            api_keys: g.gen(),
+            api_key_requests_per_second_limit: g.gen(),
+            api_key_compute_units_per_second_limit: g.gen(),
             tree_api_url: g.gen(),
+            max_logs_result_size: g.gen(),
+            trace_timeout_ms: g.gen(),
+            trace_max_call_depth: g.gen(),
+            trace_block_concurrency: g.gen(),
+            trace_block_max_result_size: g.gen(),
+            txpool_namespace_enabled: g.gen(),
+            txpool_redact_transactions: g.gen(),
+            archive_state_path: g.gen(),
+            persistent_filters: g.gen(),
+            filters_ttl_sec: g.gen(),
+            auth_bearer_tokens: g.gen(),
+            auth_jwt_secret_path: g.gen(),
+            auth_protected_namespaces: g.gen(),
+            api_namespaces: g.gen(),
+            http_response_compression_threshold: g.gen(),
+            gas_price_suggestion_blocks: g.gen(),
+            gas_price_suggestion_percentile: g.gen(),
+            gas_price_suggestion_smoothing: g.gen(),
+            shutdown_drain_timeout_ms: g.gen(),
+            cors_allowed_origins: g.gen(),
+            cors_allowed_headers: g.gen(),
+            cors_max_age_sec: g.gen(),
+            tx_replacement_fee_bump_percent: g.gen(),
         }
     }
 }
@@ -288,6 +330,16 @@ impl RandomConfig for configs::chain::StateKeeperConfig {
             virtual_blocks_per_miniblock: g.gen(),
             upload_witness_inputs_to_gcs: g.gen(),
             enum_index_migration_chunk_size: g.gen(),
+            seal_l1_batch_on_shutdown: g.gen(),
+            denied_addresses: g.gen(),
+            denied_bytecode_hashes: g.gen(),
+            shadow_source_schema: g.gen(),
+            proof_backlog_threshold: g.gen(),
+            max_tx_gas_per_pubdata: g.gen(),
+            max_tx_calldata_size: g.gen(),
+            max_tx_factory_deps: g.gen(),
+            max_tx_factory_deps_size: g.gen(),
+            upload_l1_batch_state_diffs: g.gen(),
         }
     }
 }
@@ -320,6 +372,9 @@ impl RandomConfig for configs::chain::MempoolConfig {
             stuck_tx_timeout: g.gen(),
             remove_stuck_txs: g.gen(),
             delay_interval: g.gen(),
+            max_queued_txs_per_account: g.gen(),
+            max_size_bytes: g.gen(),
+            tx_ordering: g.gen(),
         }
     }
 }
@@ -420,12 +475,18 @@ impl RandomConfig for configs::database::PostgresConfig {
         Self {
             master_url: g.gen(),
             replica_url: g.gen(),
+            additional_replica_urls: g.gen(),
             prover_url: g.gen(),
             max_connections: g.gen(),
             acquire_timeout_sec: g.gen(),
             statement_timeout_sec: g.gen(),
             long_connection_threshold_ms: g.gen(),
             slow_query_threshold_ms: g.gen(),
+            api_max_connections: g.gen(),
+            state_keeper_max_connections: g.gen(),
+            witness_generator_max_connections: g.gen(),
+            house_keeper_max_connections: g.gen(),
+            schema: g.gen(),
         }
     }
 }
@@ -658,6 +719,12 @@ impl RandomConfig for configs::house_keeper::HouseKeeperConfig {
             fri_prover_stats_reporting_interval_ms: g.gen(),
             fri_proof_compressor_job_retrying_interval_ms: g.gen(),
             fri_proof_compressor_stats_reporting_interval_ms: g.gen(),
+            db_pruning_retention_days: g.gen(),
+            db_pruning_interval_ms: g.gen(),
+            db_partition_size_miniblocks: g.gen(),
+            db_events_archival_retention_days: g.gen(),
+            db_partitioning_interval_ms: g.gen(),
+            db_consistency_check_interval_ms: g.gen(),
         }
     }
 }
@@ -716,6 +783,7 @@ impl RandomConfig for configs::SnapshotsCreatorConfig {
         Self {
             storage_logs_chunk_size: g.gen(),
             concurrent_queries_count: g.gen(),
+            l1_batch_number: g.gen(),
         }
     }
 }
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS pruning_log
+(
+    pruned_l1_batch  BIGINT    NOT NULL PRIMARY KEY,
+    pruned_miniblock BIGINT    NOT NULL,
+    created_at       TIMESTAMP NOT NULL,
+    updated_at       TIMESTAMP NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS miniblock_sealed_notify ON miniblocks;
+DROP FUNCTION IF EXISTS notify_miniblock_sealed;
@@ -0,0 +1,10 @@
+CREATE OR REPLACE FUNCTION notify_miniblock_sealed() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('miniblock_sealed', NEW.number::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER miniblock_sealed_notify
+    AFTER INSERT ON miniblocks
+    FOR EACH ROW EXECUTE FUNCTION notify_miniblock_sealed();
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS events_address_topic1_block_idx;
+
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS logs_bloom;
@@ -0,0 +1,4 @@
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS logs_bloom BYTEA;
+
+CREATE INDEX IF NOT EXISTS events_address_topic1_block_idx
+    ON events (address, topic1, miniblock_number, event_index_in_block);
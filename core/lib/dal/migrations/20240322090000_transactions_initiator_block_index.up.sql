@@ -0,0 +1,3 @@
+CREATE INDEX IF NOT EXISTS transactions_initiator_address_miniblock_idx
+    ON transactions (initiator_address, miniblock_number, index_in_block)
+    WHERE miniblock_number IS NOT NULL;
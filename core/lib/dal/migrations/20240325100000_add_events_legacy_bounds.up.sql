@@ -0,0 +1,24 @@
+-- First step of partitioning `events` by `miniblock_number` ranges. Existing data will be kept in the `events_legacy`
+-- partition; attaching it requires a valid `CHECK` constraint implying the partition bounds, so that `ATTACH PARTITION`
+-- doesn't scan the table while holding an `ACCESS EXCLUSIVE` lock.
+--
+-- The constraint is added as `NOT VALID`, which doesn't scan the table. It is validated by the next migration and is used
+-- for the cutover by the migration after that. Since `events` keeps receiving inserts until the cutover, the bound leaves
+-- headroom of at least 1,000,000 miniblocks; an empty table (e.g., a fresh database) doesn't need headroom.
+DO $$
+DECLARE
+    boundary BIGINT;
+BEGIN
+    SELECT
+        CASE
+            WHEN MAX(miniblock_number) IS NULL THEN 0
+            ELSE (MAX(miniblock_number) / 1000000 + 2) * 1000000
+        END
+    INTO boundary
+    FROM events;
+
+    EXECUTE format(
+        'ALTER TABLE events ADD CONSTRAINT events_legacy_bounds CHECK (miniblock_number IS NOT NULL AND miniblock_number < %s) NOT VALID',
+        boundary
+    );
+END $$;
@@ -0,0 +1 @@
+-- Validation cannot be reverted; the constraint is dropped when rolling back the migration that added it.
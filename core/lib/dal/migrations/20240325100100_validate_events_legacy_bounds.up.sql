@@ -0,0 +1,3 @@
+-- Scans `events` to validate the constraint added by the previous migration. Unlike adding a validated constraint,
+-- this only takes a `SHARE UPDATE EXCLUSIVE` lock, so reads and writes of `events` are not blocked during the scan.
+ALTER TABLE events VALIDATE CONSTRAINT events_legacy_bounds;
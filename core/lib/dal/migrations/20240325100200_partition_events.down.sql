@@ -0,0 +1,29 @@
+DROP PROCEDURE IF EXISTS detach_miniblocks_range_partition;
+DROP PROCEDURE IF EXISTS attach_miniblocks_range_partition;
+DROP PROCEDURE IF EXISTS create_miniblocks_range_partition;
+
+-- Moves all data back to the `events_legacy` partition and turns it into a regular table.
+DO $$
+DECLARE
+    part RECORD;
+    idx RECORD;
+BEGIN
+    ALTER TABLE events DETACH PARTITION events_legacy;
+    FOR part IN
+        SELECT inhrelid::regclass::TEXT AS name
+        FROM pg_inherits
+        WHERE inhparent = 'events'::regclass
+    LOOP
+        EXECUTE format('INSERT INTO events_legacy SELECT * FROM %s', part.name);
+    END LOOP;
+    DROP TABLE events;
+
+    ALTER TABLE events_legacy RENAME TO events;
+    FOR idx IN
+        SELECT indexname
+        FROM pg_indexes
+        WHERE schemaname = current_schema() AND tablename = 'events' AND indexname LIKE '%\_legacy'
+    LOOP
+        EXECUTE format('ALTER INDEX %I RENAME TO %I', idx.indexname, left(idx.indexname, -length('_legacy')));
+    END LOOP;
+END $$;
@@ -0,0 +1,100 @@
+-- Converts `events` into a table partitioned by `miniblock_number` ranges. Existing data is kept in the `events_legacy`
+-- partition bounded by the `events_legacy_bounds` constraint validated by the previous migration; newer data goes
+-- to the `events_default` partition until range partitions are created by the house keeper.
+--
+-- All statements below only touch catalogs or empty tables, so the `ACCESS EXCLUSIVE` lock taken by renaming `events`
+-- is held only briefly.
+DO $$
+DECLARE
+    idx RECORD;
+    con RECORD;
+    boundary BIGINT;
+BEGIN
+    SELECT substring(pg_get_constraintdef(oid) FROM '< ''?(\d+)')::BIGINT INTO boundary
+    FROM pg_constraint
+    WHERE conrelid = 'events'::regclass AND conname = 'events_legacy_bounds' AND convalidated;
+    IF boundary IS NULL THEN
+        RAISE EXCEPTION 'valid events_legacy_bounds constraint is not found';
+    END IF;
+
+    ALTER TABLE events RENAME TO events_legacy;
+    CREATE TABLE events (LIKE events_legacy INCLUDING DEFAULTS) PARTITION BY RANGE (miniblock_number);
+
+    -- Constraints and indexes are created on the parent table while it has no partitions. Equivalent constraints
+    -- and indexes of the legacy table are then reused when attaching it instead of being rebuilt.
+    FOR con IN
+        SELECT conname, pg_get_constraintdef(oid) AS condef
+        FROM pg_constraint
+        WHERE conrelid = 'events_legacy'::regclass AND contype IN ('p', 'f')
+        ORDER BY contype DESC
+    LOOP
+        IF con.conname = 'events_pkey' THEN
+            ALTER INDEX events_pkey RENAME TO events_pkey_legacy;
+        END IF;
+        EXECUTE format('ALTER TABLE events ADD CONSTRAINT %I %s', con.conname, con.condef);
+    END LOOP;
+
+    FOR idx IN
+        SELECT indexname, indexdef
+        FROM pg_indexes
+        WHERE schemaname = current_schema() AND tablename = 'events_legacy' AND indexname NOT LIKE '%\_legacy'
+    LOOP
+        EXECUTE format('ALTER INDEX %I RENAME TO %I', idx.indexname, idx.indexname || '_legacy');
+        EXECUTE replace(
+            idx.indexdef,
+            format(' ON %I.%I ', current_schema(), 'events_legacy'),
+            format(' ON %I.%I ', current_schema(), 'events')
+        );
+    END LOOP;
+
+    -- The validated constraint implies the partition bounds, so attaching doesn't scan the legacy table.
+    EXECUTE format('ALTER TABLE events ATTACH PARTITION events_legacy FOR VALUES FROM (MINVALUE) TO (%s)', boundary);
+    -- The constraint is redundant with the partition bounds after attaching.
+    ALTER TABLE events_legacy DROP CONSTRAINT events_legacy_bounds;
+    CREATE TABLE events_default PARTITION OF events DEFAULT;
+END $$;
+
+-- Procedures used by the DAL to manage range partitions. Identifiers are quoted server-side, so that the DAL
+-- doesn't need to build DDL statements itself.
+
+-- Creates a range partition for `parent_table` and moves rows in the range from the default partition to it.
+CREATE OR REPLACE PROCEDURE create_miniblocks_range_partition(
+    parent_table TEXT,
+    partition_table TEXT,
+    start_miniblock BIGINT,
+    end_miniblock BIGINT
+)
+LANGUAGE plpgsql AS $$
+BEGIN
+    EXECUTE format('CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS)', partition_table, parent_table);
+    EXECUTE format(
+        'WITH moved_rows AS ( '
+            'DELETE FROM %I WHERE miniblock_number >= $1 AND miniblock_number < $2 RETURNING * '
+        ') '
+        'INSERT INTO %I SELECT * FROM moved_rows',
+        parent_table || '_default', partition_table
+    ) USING start_miniblock, end_miniblock;
+    CALL attach_miniblocks_range_partition(parent_table, partition_table, start_miniblock, end_miniblock);
+END $$;
+
+-- Attaches a (previously detached) range partition to `parent_table`.
+CREATE OR REPLACE PROCEDURE attach_miniblocks_range_partition(
+    parent_table TEXT,
+    partition_table TEXT,
+    start_miniblock BIGINT,
+    end_miniblock BIGINT
+)
+LANGUAGE plpgsql AS $$
+BEGIN
+    EXECUTE format(
+        'ALTER TABLE %I ATTACH PARTITION %I FOR VALUES FROM (%L) TO (%L)',
+        parent_table, partition_table, start_miniblock, end_miniblock
+    );
+END $$;
+
+-- Detaches a partition from `parent_table`, retaining it as a standalone table.
+CREATE OR REPLACE PROCEDURE detach_miniblocks_range_partition(parent_table TEXT, partition_table TEXT)
+LANGUAGE plpgsql AS $$
+BEGIN
+    EXECUTE format('ALTER TABLE %I DETACH PARTITION %I', parent_table, partition_table);
+END $$;
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS l2_to_l1_log_proofs;
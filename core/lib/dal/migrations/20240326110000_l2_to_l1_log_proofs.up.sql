@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS l2_to_l1_log_proofs
+(
+    l1_batch_number BIGINT NOT NULL REFERENCES l1_batches (number) ON DELETE CASCADE,
+    log_index       INT    NOT NULL,
+    root            BYTEA  NOT NULL,
+    -- Concatenated 32-byte hashes of the Merkle path.
+    proof           BYTEA  NOT NULL,
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, log_index)
+);
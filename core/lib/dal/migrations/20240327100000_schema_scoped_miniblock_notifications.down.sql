@@ -0,0 +1,6 @@
+CREATE OR REPLACE FUNCTION notify_miniblock_sealed() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('miniblock_sealed', NEW.number::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,12 @@
+-- Notification channels are shared by all schemas in a database, so the channel for a non-default schema
+-- is prefixed with the schema name.
+CREATE OR REPLACE FUNCTION notify_miniblock_sealed() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_TABLE_SCHEMA = 'public' THEN
+        PERFORM pg_notify('miniblock_sealed', NEW.number::TEXT);
+    ELSE
+        PERFORM pg_notify(TG_TABLE_SCHEMA || '_miniblock_sealed', NEW.number::TEXT);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,6 @@
+-- Conditions of transactions submitted via `eth_sendRawTransactionConditional`.
+CREATE TABLE IF NOT EXISTS transaction_conditions
+(
+    hash       BYTEA PRIMARY KEY REFERENCES transactions (hash) ON DELETE CASCADE ON UPDATE CASCADE,
+    conditions JSONB NOT NULL
+);
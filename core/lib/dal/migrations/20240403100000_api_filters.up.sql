@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS api_filters
+(
+    filter_id      BYTEA     NOT NULL PRIMARY KEY,
+    filter         JSONB     NOT NULL,
+    created_at     TIMESTAMP NOT NULL,
+    last_polled_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS api_filters_last_polled_at_idx ON api_filters (last_polled_at);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS fee_account_updates
+(
+    id                  BIGSERIAL PRIMARY KEY,
+    fee_account_address BYTEA     NOT NULL,
+    created_at          TIMESTAMP NOT NULL
+);
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS idx_proof_generation_details_unproven;
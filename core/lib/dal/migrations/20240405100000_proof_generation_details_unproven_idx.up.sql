@@ -0,0 +1,4 @@
+-- Used to count L1 batches awaiting proof generation, which are expected to be a small fraction of the table.
+CREATE INDEX IF NOT EXISTS idx_proof_generation_details_unproven
+    ON proof_generation_details (l1_batch_number)
+    WHERE status NOT IN ('generated', 'skipped');
@@ -0,0 +1,6 @@
+DELETE FROM transactions WHERE replaced_by IS NOT NULL;
+
+DROP INDEX IF EXISTS transactions_initiator_address_nonce;
+CREATE UNIQUE INDEX IF NOT EXISTS transactions_initiator_address_nonce ON transactions (initiator_address, nonce);
+
+ALTER TABLE transactions DROP COLUMN IF EXISTS replaced_by;
@@ -0,0 +1,8 @@
+-- Hash of the transaction that replaced this one. Replaced transactions are kept in the table, so only
+-- transactions that are not replaced must have a unique nonce for each initiator.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS replaced_by BYTEA;
+
+DROP INDEX IF EXISTS transactions_initiator_address_nonce;
+CREATE UNIQUE INDEX IF NOT EXISTS transactions_initiator_address_nonce
+    ON transactions (initiator_address, nonce)
+    WHERE replaced_by IS NULL;
@@ -0,0 +1,2 @@
+-- Used to find the first L1 batch executed with each protocol version.
+CREATE INDEX IF NOT EXISTS l1_batches_protocol_version_number_idx ON l1_batches (protocol_version, number);
@@ -0,0 +1,272 @@
+use std::time::Duration;
+
+use zksync_types::H256;
+
+use crate::{instrument::InstrumentExt, time_utils::pg_interval_from_duration, StorageProcessor};
+
+/// DAL for filters installed via the Web3 API (`eth_newFilter` etc.). Filters are stored as opaque JSON
+/// so that their format is fully defined by the API server.
+#[derive(Debug)]
+pub struct ApiFiltersDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ApiFiltersDal<'_, '_> {
+    /// Inserts a new filter. Returns `false` if a filter with the same ID already exists.
+    pub async fn insert_filter(
+        &mut self,
+        filter_id: H256,
+        filter: &serde_json::Value,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+                api_filters (filter_id, filter, created_at, last_polled_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (filter_id) DO NOTHING
+            "#,
+            filter_id.as_bytes(),
+            filter
+        )
+        .instrument("insert_filter")
+        .with_arg("filter_id", &filter_id)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns the filter with the specified ID and marks it as polled. Filters not polled for more than `ttl`
+    /// are considered expired and are not returned.
+    pub async fn poll_filter(
+        &mut self,
+        filter_id: H256,
+        ttl: Duration,
+    ) -> sqlx::Result<Option<serde_json::Value>> {
+        let row = sqlx::query!(
+            r#"
+            UPDATE api_filters
+            SET
+                last_polled_at = NOW()
+            WHERE
+                filter_id = $1
+                AND last_polled_at >= NOW() - $2::INTERVAL
+            RETURNING
+                filter
+            "#,
+            filter_id.as_bytes(),
+            &pg_interval_from_duration(ttl)
+        )
+        .instrument("poll_filter")
+        .with_arg("filter_id", &filter_id)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| row.filter))
+    }
+
+    /// Replaces the filter with the specified ID. Does nothing if the filter was removed in the meantime.
+    pub async fn update_filter(
+        &mut self,
+        filter_id: H256,
+        filter: &serde_json::Value,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE api_filters
+            SET
+                filter = $2
+            WHERE
+                filter_id = $1
+            "#,
+            filter_id.as_bytes(),
+            filter
+        )
+        .instrument("update_filter")
+        .with_arg("filter_id", &filter_id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes the filter with the specified ID. Returns `false` if the filter does not exist.
+    pub async fn remove_filter(&mut self, filter_id: H256) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM api_filters
+            WHERE
+                filter_id = $1
+            "#,
+            filter_id.as_bytes()
+        )
+        .instrument("remove_filter")
+        .with_arg("filter_id", &filter_id)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Removes filters not polled for more than `ttl`, and then removes the least recently polled filters
+    /// so that at most `max_count` filters remain. Returns the number of removed filters.
+    pub async fn remove_stale_filters(
+        &mut self,
+        ttl: Duration,
+        max_count: Option<usize>,
+    ) -> sqlx::Result<u64> {
+        let expired_count = sqlx::query!(
+            r#"
+            DELETE FROM api_filters
+            WHERE
+                last_polled_at < NOW() - $1::INTERVAL
+            "#,
+            &pg_interval_from_duration(ttl)
+        )
+        .instrument("remove_stale_filters#expired")
+        .with_arg("ttl", &ttl)
+        .execute(self.storage)
+        .await?
+        .rows_affected();
+
+        let Some(max_count) = max_count else {
+            return Ok(expired_count);
+        };
+        let evicted_count = sqlx::query!(
+            r#"
+            DELETE FROM api_filters
+            WHERE
+                filter_id IN (
+                    SELECT
+                        filter_id
+                    FROM
+                        api_filters
+                    ORDER BY
+                        last_polled_at DESC
+                    OFFSET
+                        $1
+                )
+            "#,
+            max_count as i64
+        )
+        .instrument("remove_stale_filters#evicted")
+        .with_arg("max_count", &max_count)
+        .execute(self.storage)
+        .await?
+        .rows_affected();
+        Ok(expired_count + evicted_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn persisting_filters() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let ttl = Duration::from_secs(60);
+
+        let filter_id = H256::repeat_byte(1);
+        let filter = serde_json::json!({ "Blocks": 1 });
+        let inserted = conn
+            .api_filters_dal()
+            .insert_filter(filter_id, &filter)
+            .await
+            .unwrap();
+        assert!(inserted);
+        let inserted = conn
+            .api_filters_dal()
+            .insert_filter(filter_id, &serde_json::json!({ "Blocks": 2 }))
+            .await
+            .unwrap();
+        assert!(!inserted);
+
+        let polled = conn
+            .api_filters_dal()
+            .poll_filter(filter_id, ttl)
+            .await
+            .unwrap();
+        assert_eq!(polled, Some(filter));
+        let missing = conn
+            .api_filters_dal()
+            .poll_filter(H256::repeat_byte(2), ttl)
+            .await
+            .unwrap();
+        assert_eq!(missing, None);
+
+        let updated_filter = serde_json::json!({ "Blocks": 3 });
+        conn.api_filters_dal()
+            .update_filter(filter_id, &updated_filter)
+            .await
+            .unwrap();
+        let polled = conn
+            .api_filters_dal()
+            .poll_filter(filter_id, ttl)
+            .await
+            .unwrap();
+        assert_eq!(polled, Some(updated_filter));
+
+        assert!(conn
+            .api_filters_dal()
+            .remove_filter(filter_id)
+            .await
+            .unwrap());
+        assert!(!conn
+            .api_filters_dal()
+            .remove_filter(filter_id)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn removing_stale_filters() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        for i in 1..=3 {
+            conn.api_filters_dal()
+                .insert_filter(H256::repeat_byte(i), &serde_json::json!({ "Blocks": i }))
+                .await
+                .unwrap();
+        }
+        // Make the first filter the most recently polled one.
+        conn.api_filters_dal()
+            .poll_filter(H256::repeat_byte(1), Duration::from_secs(60))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let removed_count = conn
+            .api_filters_dal()
+            .remove_stale_filters(Duration::from_secs(60), None)
+            .await
+            .unwrap();
+        assert_eq!(removed_count, 0);
+        let removed_count = conn
+            .api_filters_dal()
+            .remove_stale_filters(Duration::from_secs(60), Some(1))
+            .await
+            .unwrap();
+        assert_eq!(removed_count, 2);
+        let polled = conn
+            .api_filters_dal()
+            .poll_filter(H256::repeat_byte(1), Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(polled.is_some());
+
+        // Expire all filters.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let polled = conn
+            .api_filters_dal()
+            .poll_filter(H256::repeat_byte(1), Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(polled, None);
+        let removed_count = conn
+            .api_filters_dal()
+            .remove_stale_filters(Duration::ZERO, None)
+            .await
+            .unwrap();
+        assert_eq!(removed_count, 1);
+    }
+}
@@ -1,11 +1,14 @@
 use std::time::{Duration, Instant};
 
+use futures::future::BoxFuture;
 use sqlx::postgres::types::PgInterval;
 use zksync_types::L1BatchNumber;
 
 use crate::{
+    fri_prover_dal::types::StuckJobs,
     instrument::InstrumentExt,
-    time_utils::{duration_to_naive_time, pg_interval_from_duration},
+    job_queue_dal::{JobQueueParams, QueuedJob},
+    time_utils::duration_to_naive_time,
     StorageProcessor,
 };
 
@@ -15,10 +18,10 @@ pub struct BasicWitnessInputProducerDal<'a, 'c> {
 }
 
 /// The amount of attempts to process a job before giving up.
-pub const JOB_MAX_ATTEMPT: i16 = 10;
+pub const JOB_MAX_ATTEMPT: u32 = 10;
 
 /// Time to wait for job to be processed
-const JOB_PROCESSING_TIMEOUT: PgInterval = pg_interval_from_duration(Duration::from_secs(10 * 60));
+const JOB_PROCESSING_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 
 /// Status of a job that the producer will work on.
 
@@ -45,6 +48,120 @@ pub enum BasicWitnessInputProducerJobStatus {
     Failed,
 }
 
+#[derive(Debug)]
+pub(crate) struct StorageBasicWitnessInputProducerJob {
+    pub l1_batch_number: i64,
+}
+
+impl QueuedJob for StorageBasicWitnessInputProducerJob {
+    type PickParams = JobQueueParams;
+
+    fn pick_next<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        params: &'a JobQueueParams,
+    ) -> BoxFuture<'a, sqlx::Result<Option<Self>>> {
+        let processing_timeout = params.pg_processing_timeout();
+        let max_attempts = params.max_attempts_or_zero() as i16;
+        Box::pin(async move {
+            sqlx::query_as!(
+                Self,
+                r#"
+                UPDATE basic_witness_input_producer_jobs
+                SET
+                    status = $1,
+                    attempts = attempts + 1,
+                    updated_at = NOW(),
+                    processing_started_at = NOW()
+                WHERE
+                    l1_batch_number = (
+                        SELECT
+                            l1_batch_number
+                        FROM
+                            basic_witness_input_producer_jobs
+                        WHERE
+                            status = $2
+                            OR (
+                                status = $1
+                                AND processing_started_at < NOW() - $4::INTERVAL
+                            )
+                            OR (
+                                status = $3
+                                AND attempts < $5
+                            )
+                        ORDER BY
+                            l1_batch_number ASC
+                        LIMIT
+                            1
+                        FOR UPDATE
+                            SKIP LOCKED
+                    )
+                RETURNING
+                    basic_witness_input_producer_jobs.l1_batch_number
+                "#,
+                BasicWitnessInputProducerJobStatus::InProgress
+                    as BasicWitnessInputProducerJobStatus,
+                BasicWitnessInputProducerJobStatus::Queued as BasicWitnessInputProducerJobStatus,
+                BasicWitnessInputProducerJobStatus::Failed as BasicWitnessInputProducerJobStatus,
+                &processing_timeout,
+                max_attempts,
+            )
+            .instrument("get_next_basic_witness_input_producer_job")
+            .fetch_optional(storage)
+            .await
+        })
+    }
+
+    fn requeue_stuck<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        processing_timeout: PgInterval,
+        max_attempts: u32,
+    ) -> BoxFuture<'a, sqlx::Result<Vec<StuckJobs>>> {
+        Box::pin(async move {
+            let rows = sqlx::query!(
+                r#"
+                UPDATE basic_witness_input_producer_jobs
+                SET
+                    status = $1,
+                    updated_at = NOW(),
+                    processing_started_at = NOW()
+                WHERE
+                    (
+                        status = $2
+                        AND processing_started_at <= NOW() - $4::INTERVAL
+                        AND attempts < $5
+                    )
+                    OR (
+                        status = $3
+                        AND attempts < $5
+                    )
+                RETURNING
+                    l1_batch_number,
+                    status::TEXT AS "status!",
+                    attempts
+                "#,
+                BasicWitnessInputProducerJobStatus::Queued as BasicWitnessInputProducerJobStatus,
+                BasicWitnessInputProducerJobStatus::InProgress
+                    as BasicWitnessInputProducerJobStatus,
+                BasicWitnessInputProducerJobStatus::Failed as BasicWitnessInputProducerJobStatus,
+                &processing_timeout,
+                max_attempts as i16,
+            )
+            .instrument("requeue_stuck_basic_witness_input_producer_jobs")
+            .fetch_all(storage)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| StuckJobs {
+                    id: row.l1_batch_number as u64,
+                    status: row.status,
+                    attempts: row.attempts as u64,
+                })
+                .collect())
+        })
+    }
+}
+
 impl BasicWitnessInputProducerDal<'_, '_> {
     pub async fn create_basic_witness_input_producer_job(
         &mut self,
@@ -62,7 +179,6 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             BasicWitnessInputProducerJobStatus::Queued as BasicWitnessInputProducerJobStatus,
         )
         .instrument("create_basic_witness_input_producer_job")
-        .report_latency()
         .execute(self.storage)
         .await?;
 
@@ -72,53 +188,13 @@ impl BasicWitnessInputProducerDal<'_, '_> {
     pub async fn get_next_basic_witness_input_producer_job(
         &mut self,
     ) -> sqlx::Result<Option<L1BatchNumber>> {
-        let l1_batch_number = sqlx::query!(
-            r#"
-            UPDATE basic_witness_input_producer_jobs
-            SET
-                status = $1,
-                attempts = attempts + 1,
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                l1_batch_number = (
-                    SELECT
-                        l1_batch_number
-                    FROM
-                        basic_witness_input_producer_jobs
-                    WHERE
-                        status = $2
-                        OR (
-                            status = $1
-                            AND processing_started_at < NOW() - $4::INTERVAL
-                        )
-                        OR (
-                            status = $3
-                            AND attempts < $5
-                        )
-                    ORDER BY
-                        l1_batch_number ASC
-                    LIMIT
-                        1
-                    FOR UPDATE
-                        SKIP LOCKED
-                )
-            RETURNING
-                basic_witness_input_producer_jobs.l1_batch_number
-            "#,
-            BasicWitnessInputProducerJobStatus::InProgress as BasicWitnessInputProducerJobStatus,
-            BasicWitnessInputProducerJobStatus::Queued as BasicWitnessInputProducerJobStatus,
-            BasicWitnessInputProducerJobStatus::Failed as BasicWitnessInputProducerJobStatus,
-            &JOB_PROCESSING_TIMEOUT,
-            JOB_MAX_ATTEMPT,
-        )
-        .instrument("get_next_basic_witness_input_producer_job")
-        .report_latency()
-        .fetch_optional(self.storage)
-        .await?
-        .map(|job| L1BatchNumber(job.l1_batch_number as u32));
-
-        Ok(l1_batch_number)
+        let params = JobQueueParams::new(JOB_PROCESSING_TIMEOUT).with_max_attempts(JOB_MAX_ATTEMPT);
+        let job = self
+            .storage
+            .job_queue_dal::<StorageBasicWitnessInputProducerJob>()
+            .pick_next_job(&params)
+            .await?;
+        Ok(job.map(|job| L1BatchNumber(job.l1_batch_number as u32)))
     }
 
     pub async fn get_basic_witness_input_producer_job_attempts(
@@ -136,7 +212,8 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_basic_witness_input_producer_job_attempts")
+        .fetch_optional(self.storage)
         .await?
         .map(|job| job.attempts as u32);
 
@@ -166,7 +243,6 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             object_path,
         )
         .instrument("mark_job_as_successful")
-        .report_latency()
         .execute(self.storage)
         .await?;
 
@@ -200,7 +276,6 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             BasicWitnessInputProducerJobStatus::Successful as BasicWitnessInputProducerJobStatus,
         )
         .instrument("mark_job_as_failed")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?
         .map(|job| job.attempts as u32);
@@ -217,7 +292,8 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             DELETE FROM basic_witness_input_producer_jobs
             "#
         )
-        .execute(self.storage.conn())
+        .instrument("delete_all_jobs")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -6,11 +6,15 @@ use std::{
 
 use anyhow::Context as _;
 use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use sqlx::postgres::PgListener;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::{BlockGasCount, L1BatchHeader, L1BatchTreeData, MiniblockHeader},
     circuit::CircuitStatistic,
-    commitment::{L1BatchCommitmentArtifacts, L1BatchWithMetadata},
+    commitment::{
+        L1BatchCommitmentArtifacts, L1BatchMetadata, L1BatchWithMetadata, SerializeCommitment,
+    },
+    l2_to_l1_log::L2ToL1Log,
     zk_evm_types::LogQuery,
     Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256, U256,
 };
@@ -18,15 +22,60 @@ use zksync_types::{
 use crate::{
     instrument::InstrumentExt,
     models::storage_block::{StorageL1Batch, StorageL1BatchHeader, StorageMiniblockHeader},
-    StorageProcessor,
+    ConnectionPool, StorageProcessor,
 };
 
+/// Composition of the pending (i.e., unsealed) L1 batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingL1BatchInfo {
+    /// Number of the pending L1 batch.
+    pub l1_batch_number: L1BatchNumber,
+    /// Miniblocks included into the batch so far, or `None` if no miniblocks were sealed after the last L1 batch.
+    pub miniblocks: Option<ops::RangeInclusive<MiniblockNumber>>,
+    /// Timestamp of the first miniblock in the batch.
+    pub timestamp: Option<u64>,
+    pub l1_tx_count: u32,
+    pub l2_tx_count: u32,
+    /// Number of storage slots written to for the first time in the batch.
+    pub initial_writes_count: u32,
+    /// Number of previously written storage slots written to in the batch.
+    pub repeated_writes_count: u32,
+    pub l2_to_l1_logs_count: u32,
+    /// Estimated pubdata size in bytes. The estimate is based on uncompressed state diffs and L2-to-L1 logs;
+    /// it doesn't include L2-to-L1 messages and published bytecodes.
+    pub estimated_pubdata: u64,
+}
+
+impl PendingL1BatchInfo {
+    /// Pubdata for an initial write: 32-byte derived key and 32-byte value.
+    const INITIAL_WRITE_PUBDATA_BYTES: u64 = 64;
+    /// Pubdata for a repeated write: 8-byte enumeration index and 32-byte value.
+    const REPEATED_WRITE_PUBDATA_BYTES: u64 = 40;
+
+    fn estimate_pubdata(
+        initial_writes_count: u32,
+        repeated_writes_count: u32,
+        l2_to_l1_logs_count: u32,
+    ) -> u64 {
+        u64::from(initial_writes_count) * Self::INITIAL_WRITE_PUBDATA_BYTES
+            + u64::from(repeated_writes_count) * Self::REPEATED_WRITE_PUBDATA_BYTES
+            + u64::from(l2_to_l1_logs_count) * L2ToL1Log::SERIALIZED_SIZE as u64
+    }
+
+    pub fn tx_count(&self) -> u32 {
+        self.l1_tx_count + self.l2_tx_count
+    }
+}
+
 #[derive(Debug)]
 pub struct BlocksDal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
 }
 
 impl BlocksDal<'_, '_> {
+    /// Maximum number of headers returned by [`Self::get_miniblock_headers_range()`].
+    pub const MAX_MINIBLOCK_HEADERS_IN_RANGE: u32 = 1_000;
+
     pub async fn is_genesis_needed(&mut self) -> sqlx::Result<bool> {
         let count = sqlx::query!(
             r#"
@@ -36,7 +85,8 @@ impl BlocksDal<'_, '_> {
                 l1_batches
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("is_genesis_needed")
+        .fetch_one(self.storage)
         .await?
         .count;
         Ok(count == 0)
@@ -52,7 +102,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_sealed_block_number")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -69,7 +118,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_sealed_miniblock_number")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -87,7 +135,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_earliest_l1_batch_number")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -108,7 +155,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_last_block_number_with_metadata")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -134,7 +180,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_next_l1_batch_ready_for_commitment_generation")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
@@ -157,7 +202,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_earliest_l1_batch_number_with_metadata")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -201,7 +245,10 @@ impl BlocksDal<'_, '_> {
         .fetch_all(self.storage)
         .await?;
 
-        Ok(l1_batches.into_iter().map(Into::into).collect())
+        l1_batches
+            .into_iter()
+            .map(|batch| L1BatchHeader::try_from(batch).map_err(Into::into))
+            .collect()
     }
 
     pub async fn get_storage_l1_batch(
@@ -261,7 +308,7 @@ impl BlocksDal<'_, '_> {
         &mut self,
         number: L1BatchNumber,
     ) -> sqlx::Result<Option<L1BatchHeader>> {
-        Ok(sqlx::query_as!(
+        let header = sqlx::query_as!(
             StorageL1BatchHeader,
             r#"
             SELECT
@@ -290,8 +337,12 @@ impl BlocksDal<'_, '_> {
         .instrument("get_l1_batch_header")
         .with_arg("number", &number)
         .fetch_optional(self.storage)
-        .await?
-        .map(Into::into))
+        .await?;
+
+        let Some(header) = header else {
+            return Ok(None);
+        };
+        Ok(Some(L1BatchHeader::try_from(header)?))
     }
 
     /// Returns initial bootloader heap content for the specified L1 batch.
@@ -311,7 +362,6 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_initial_bootloader_heap")
-        .report_latency()
         .with_arg("number", &number)
         .fetch_optional(self.storage)
         .await?
@@ -340,7 +390,6 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_storage_refunds")
-        .report_latency()
         .with_arg("number", &number)
         .fetch_optional(self.storage)
         .await?
@@ -372,7 +421,6 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_events_queue")
-        .report_latency()
         .with_arg("number", &number)
         .fetch_optional(self.storage)
         .await?
@@ -412,7 +460,8 @@ impl BlocksDal<'_, '_> {
                     number_range.start().0 as i64,
                     number_range.end().0 as i64
                 )
-                .execute(self.storage.conn())
+                .instrument("set_eth_tx_id#update_l1_batches_1")
+                .execute(self.storage)
                 .await?;
             }
             AggregatedActionType::PublishProofOnchain => {
@@ -429,7 +478,8 @@ impl BlocksDal<'_, '_> {
                     number_range.start().0 as i64,
                     number_range.end().0 as i64
                 )
-                .execute(self.storage.conn())
+                .instrument("set_eth_tx_id#update_l1_batches_2")
+                .execute(self.storage)
                 .await?;
             }
             AggregatedActionType::Execute => {
@@ -446,7 +496,8 @@ impl BlocksDal<'_, '_> {
                     number_range.start().0 as i64,
                     number_range.end().0 as i64
                 )
-                .execute(self.storage.conn())
+                .instrument("set_eth_tx_id#update_l1_batches_3")
+                .execute(self.storage)
                 .await?;
             }
         }
@@ -562,7 +613,8 @@ impl BlocksDal<'_, '_> {
             pubdata_input,
             serde_json::to_value(predicted_circuits_by_type).unwrap(),
         )
-        .execute(transaction.conn())
+        .instrument("insert_l1_batch#insert_l1_batches")
+        .execute(&mut transaction)
         .await?;
 
         let events_queue =
@@ -577,7 +629,8 @@ impl BlocksDal<'_, '_> {
             header.number.0 as i64,
             &events_queue
         )
-        .execute(transaction.conn())
+        .instrument("insert_l1_batch#insert_events_queue")
+        .execute(&mut transaction)
         .await?;
         transaction.commit().await?;
 
@@ -638,7 +691,8 @@ impl BlocksDal<'_, '_> {
             miniblock_header.virtual_blocks as i64,
             miniblock_header.batch_fee_input.fair_pubdata_price() as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("insert_miniblock")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -673,13 +727,14 @@ impl BlocksDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_last_sealed_miniblock_header")
+        .fetch_optional(self.storage)
         .await?;
 
         let Some(header) = header else {
             return Ok(None);
         };
-        let mut header = MiniblockHeader::from(header);
+        let mut header = MiniblockHeader::try_from(header)?;
         // FIXME (PLA-728): remove after 2nd phase of `fee_account_address` migration
         #[allow(deprecated)]
         self.maybe_load_fee_address(&mut header.fee_account_address, header.number)
@@ -718,13 +773,14 @@ impl BlocksDal<'_, '_> {
             "#,
             miniblock_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_miniblock_header")
+        .fetch_optional(self.storage)
         .await?;
 
         let Some(header) = header else {
             return Ok(None);
         };
-        let mut header = MiniblockHeader::from(header);
+        let mut header = MiniblockHeader::try_from(header)?;
         // FIXME (PLA-728): remove after 2nd phase of `fee_account_address` migration
         #[allow(deprecated)]
         self.maybe_load_fee_address(&mut header.fee_account_address, header.number)
@@ -733,6 +789,62 @@ impl BlocksDal<'_, '_> {
         Ok(Some(header))
     }
 
+    /// Returns headers for miniblocks in the specified range, ordered by miniblock number. Missing miniblocks
+    /// are skipped. At most [`Self::MAX_MINIBLOCK_HEADERS_IN_RANGE`] headers are returned, starting from
+    /// the range start; callers should paginate larger ranges.
+    pub async fn get_miniblock_headers_range(
+        &mut self,
+        numbers: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<MiniblockHeader>> {
+        let rows = sqlx::query_as!(
+            StorageMiniblockHeader,
+            r#"
+            SELECT
+                number,
+                timestamp,
+                hash,
+                l1_tx_count,
+                l2_tx_count,
+                fee_account_address AS "fee_account_address!",
+                base_fee_per_gas,
+                l1_gas_price,
+                l2_fair_gas_price,
+                gas_per_pubdata_limit,
+                bootloader_code_hash,
+                default_aa_code_hash,
+                protocol_version,
+                virtual_blocks,
+                fair_pubdata_price
+            FROM
+                miniblocks
+            WHERE
+                number BETWEEN $1 AND $2
+            ORDER BY
+                number
+            LIMIT
+                $3
+            "#,
+            i64::from(numbers.start().0),
+            i64::from(numbers.end().0),
+            i64::from(Self::MAX_MINIBLOCK_HEADERS_IN_RANGE)
+        )
+        .instrument("get_miniblock_headers_range")
+        .with_arg("numbers", &numbers)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut headers = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut header = MiniblockHeader::try_from(row)?;
+            // FIXME (PLA-728): remove after 2nd phase of `fee_account_address` migration
+            #[allow(deprecated)]
+            self.maybe_load_fee_address(&mut header.fee_account_address, header.number)
+                .await?;
+            headers.push(header);
+        }
+        Ok(headers)
+    }
+
     pub async fn mark_miniblocks_as_executed_in_l1_batch(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -747,7 +859,8 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i32,
         )
-        .execute(self.storage.conn())
+        .instrument("mark_miniblocks_as_executed_in_l1_batch")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -775,7 +888,6 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("save_batch_tree_data")
         .with_arg("number", &number)
-        .report_latency()
         .execute(self.storage)
         .await?;
 
@@ -798,7 +910,6 @@ impl BlocksDal<'_, '_> {
             )
             .instrument("get_matching_batch_hash")
             .with_arg("number", &number)
-            .report_latency()
             .fetch_one(self.storage)
             .await?
             .count;
@@ -814,6 +925,48 @@ impl BlocksDal<'_, '_> {
         Ok(())
     }
 
+    /// Saves Merkle proofs for all L2-to-L1 logs in the specified L1 batch. `merkle_paths` must be ordered
+    /// by the log index in the batch. Proofs are not overwritten if they are already present.
+    pub async fn save_l2_to_l1_log_proofs(
+        &mut self,
+        number: L1BatchNumber,
+        root: H256,
+        merkle_paths: &[Vec<H256>],
+    ) -> sqlx::Result<()> {
+        let log_indices: Vec<_> = (0..merkle_paths.len() as i32).collect();
+        let proofs: Vec<Vec<u8>> = merkle_paths
+            .iter()
+            .map(|path| path.iter().flat_map(|hash| hash.0).collect())
+            .collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l2_to_l1_log_proofs (l1_batch_number, log_index, root, proof, created_at, updated_at)
+            SELECT
+                $1,
+                u.log_index,
+                $2,
+                u.proof,
+                NOW(),
+                NOW()
+            FROM
+                UNNEST($3::INT[], $4::BYTEA[]) AS u (log_index, proof)
+            ON CONFLICT (l1_batch_number, log_index) DO NOTHING
+            "#,
+            i64::from(number.0),
+            root.as_bytes(),
+            &log_indices,
+            &proofs
+        )
+        .instrument("save_l2_to_l1_log_proofs")
+        .with_arg("number", &number)
+        .with_arg("proofs.len", &proofs.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn save_l1_batch_commitment_artifacts(
         &mut self,
         number: L1BatchNumber,
@@ -858,7 +1011,6 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("save_l1_batch_commitment_artifacts")
         .with_arg("number", &number)
-        .report_latency()
         .execute(&mut transaction)
         .await?;
         if update_result.rows_affected() == 0 {
@@ -882,7 +1034,6 @@ impl BlocksDal<'_, '_> {
             )
             .instrument("get_matching_batch_commitment")
             .with_arg("number", &number)
-            .report_latency()
             .fetch_one(&mut transaction)
             .await?
             .count;
@@ -911,7 +1062,6 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("save_batch_aux_commitments")
         .with_arg("number", &number)
-        .report_latency()
         .execute(&mut transaction)
         .await?;
 
@@ -1003,7 +1153,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_number_of_last_l1_batch_committed_on_eth")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| L1BatchNumber(row.number as u32)))
     }
@@ -1020,7 +1171,8 @@ impl BlocksDal<'_, '_> {
                 eth_prove_tx_id IS NOT NULL
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_last_l1_batch_with_prove_tx")
+        .fetch_one(self.storage)
         .await?;
 
         Ok(L1BatchNumber(row.number as u32))
@@ -1041,7 +1193,8 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_eth_commit_tx_id")
+        .fetch_optional(self.storage)
         .await?;
 
         Ok(row.and_then(|row| row.eth_commit_tx_id.map(|n| n as u64)))
@@ -1066,7 +1219,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_number_of_last_l1_batch_proven_on_eth")
+        .fetch_optional(self.storage)
         .await?
         .map(|record| L1BatchNumber(record.number as u32)))
     }
@@ -1090,7 +1244,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_number_of_last_l1_batch_executed_on_eth")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| L1BatchNumber(row.number as u32)))
     }
@@ -1192,7 +1347,8 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("set_skip_proof_for_l1_batch")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -1377,7 +1533,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("raw_ready_for_execute_l1_batches#l1_batches_1")
+        .fetch_optional(self.storage)
         .await?;
 
         let Some(row) = row else { return Ok(vec![]) };
@@ -1409,7 +1566,8 @@ impl BlocksDal<'_, '_> {
             "#,
             max_l1_batch_timestamp_seconds_bd,
         )
-        .fetch_one(self.storage.conn())
+        .instrument("raw_ready_for_execute_l1_batches#l1_batches_2")
+        .fetch_one(self.storage)
         .await?;
 
         Ok(if let Some(max_ready_to_send_block) = row.max {
@@ -1653,7 +1811,8 @@ impl BlocksDal<'_, '_> {
             "#,
             number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_state_root")
+        .fetch_optional(self.storage)
         .await?
         .and_then(|row| row.hash)
         .map(|hash| H256::from_slice(&hash)))
@@ -1675,7 +1834,8 @@ impl BlocksDal<'_, '_> {
             "#,
             number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_state_root_and_timestamp")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -1718,7 +1878,8 @@ impl BlocksDal<'_, '_> {
             "#,
             number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_tree_data")
+        .fetch_optional(self.storage)
         .await?;
         Ok(row.and_then(|row| {
             Some(L1BatchTreeData {
@@ -1736,8 +1897,9 @@ impl BlocksDal<'_, '_> {
             .get_l1_batch_factory_deps(L1BatchNumber(storage_batch.number as u32))
             .await
             .context("get_l1_batch_factory_deps()")?;
-        let header: L1BatchHeader = storage_batch.clone().into();
-        let Ok(metadata) = storage_batch.try_into() else {
+        let header = L1BatchHeader::try_from(storage_batch.clone())
+            .context("invalid L1 batch header in the DB")?;
+        let Ok(metadata) = L1BatchMetadata::try_from(storage_batch) else {
             return Ok(None);
         };
         let raw_published_bytecode_hashes = self
@@ -1771,7 +1933,8 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_l1_batch_factory_deps")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
@@ -1799,7 +1962,8 @@ impl BlocksDal<'_, '_> {
             "#,
             block_number
         )
-        .execute(self.storage.conn())
+        .instrument("delete_initial_writes_inner")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -1824,7 +1988,8 @@ impl BlocksDal<'_, '_> {
             "#,
             block_number
         )
-        .execute(self.storage.conn())
+        .instrument("delete_l1_batches_inner")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -1851,11 +2016,117 @@ impl BlocksDal<'_, '_> {
             "#,
             block_number
         )
-        .execute(self.storage.conn())
+        .instrument("delete_miniblocks_inner")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
 
+    /// Rolls back Postgres data so that `last_l1_batch_to_keep` is the last L1 batch left in the storage.
+    /// All changes are performed in a single DB transaction. The following data is removed:
+    ///
+    /// - L1 batches (together with their tree and commitment metadata) and miniblocks
+    /// - Events and L2-to-L1 logs
+    /// - Storage logs and initial writes
+    /// - Factory deps and created tokens
+    /// - `eth_txs` for the removed L1 batches
+    /// - Fee account updates scheduled after `last_l1_batch_to_keep` was sealed
+    ///
+    /// The pruning log is clamped to `last_l1_batch_to_keep`. Transactions included into removed miniblocks
+    /// are returned to the mempool together with their conditions.
+    pub async fn rollback_to_l1_batch(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        let (_, last_miniblock_to_keep) = transaction
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch_to_keep)
+            .await?
+            .with_context(|| format!("L1 batch #{last_l1_batch_to_keep} contains no miniblocks"))?;
+
+        tracing::info!("Rolling back transactions state...");
+        // Transaction conditions are intentionally retained; they were recorded on submission, so transactions
+        // reset to pending keep them and the state keeper re-checks them on re-execution.
+        transaction
+            .transactions_dal()
+            .reset_transactions_state(last_miniblock_to_keep)
+            .await;
+        tracing::info!("Rolling back events...");
+        transaction
+            .events_dal()
+            .rollback_events(last_miniblock_to_keep)
+            .await;
+        tracing::info!("Rolling back L2-to-L1 logs...");
+        transaction
+            .events_dal()
+            .rollback_l2_to_l1_logs(last_miniblock_to_keep)
+            .await;
+        tracing::info!("Rolling back created tokens...");
+        transaction
+            .tokens_dal()
+            .rollback_tokens(last_miniblock_to_keep)
+            .await
+            .context("rollback_tokens()")?;
+        tracing::info!("Rolling back factory deps...");
+        transaction
+            .factory_deps_dal()
+            .rollback_factory_deps(last_miniblock_to_keep)
+            .await
+            .context("rollback_factory_deps()")?;
+        tracing::info!("Rolling back storage...");
+        #[allow(deprecated)]
+        transaction
+            .storage_logs_dal()
+            .rollback_storage(last_miniblock_to_keep)
+            .await
+            .context("rollback_storage()")?;
+        tracing::info!("Rolling back storage logs...");
+        transaction
+            .storage_logs_dal()
+            .rollback_storage_logs(last_miniblock_to_keep)
+            .await
+            .context("rollback_storage_logs()")?;
+        tracing::info!("Rolling back eth_txs...");
+        transaction
+            .eth_sender_dal()
+            .delete_eth_txs(last_l1_batch_to_keep)
+            .await
+            .context("delete_eth_txs()")?;
+        tracing::info!("Rolling back fee account updates...");
+        transaction
+            .fee_account_dal()
+            .rollback_fee_account_updates(last_l1_batch_to_keep)
+            .await
+            .context("rollback_fee_account_updates()")?;
+        tracing::info!("Rolling back pruning log...");
+        transaction
+            .pruning_dal()
+            .rollback_pruning_log(last_l1_batch_to_keep, last_miniblock_to_keep)
+            .await
+            .context("rollback_pruning_log()")?;
+        tracing::info!("Rolling back L1 batches...");
+        transaction
+            .blocks_dal()
+            .delete_l1_batches(last_l1_batch_to_keep)
+            .await
+            .context("delete_l1_batches()")?;
+        transaction
+            .blocks_dal()
+            .delete_initial_writes(last_l1_batch_to_keep)
+            .await
+            .context("delete_initial_writes()")?;
+        tracing::info!("Rolling back miniblocks...");
+        transaction
+            .blocks_dal()
+            .delete_miniblocks(last_miniblock_to_keep)
+            .await
+            .context("delete_miniblocks()")?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
     /// Returns sum of predicted gas costs on the given L1 batch range.
     /// Panics if the sum doesn't fit into `u32`.
     pub async fn get_l1_batches_predicted_gas(
@@ -1883,7 +2154,8 @@ impl BlocksDal<'_, '_> {
         );
 
         query
-            .fetch_one(self.storage.conn())
+            .instrument("get_l1_batches_predicted_gas")
+            .fetch_one(self.storage)
             .await?
             .sum
             .to_u32()
@@ -1906,7 +2178,8 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_miniblock_range_of_l1_batch")
+        .fetch_one(self.storage)
         .await?;
         let Some(min) = row.min else { return Ok(None) };
         let Some(max) = row.max else { return Ok(None) };
@@ -1922,13 +2195,117 @@ impl BlocksDal<'_, '_> {
         let count = sqlx::query_scalar!(
             "SELECT COUNT(miniblocks.number) FROM miniblocks WHERE l1_batch_number IS NULL"
         )
-        .fetch_one(self.storage.conn())
+        .instrument("pending_batch_exists")
+        .fetch_one(self.storage)
         .await?
         .unwrap_or(0);
 
         Ok(count != 0)
     }
 
+    /// Returns the composition of the pending L1 batch, i.e., the batch that the next sealed miniblocks will belong to.
+    pub async fn pending_batch_info(&mut self) -> sqlx::Result<PendingL1BatchInfo> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(
+                    (
+                        SELECT
+                            MAX(number) + 1
+                        FROM
+                            l1_batches
+                    ),
+                    (
+                        SELECT
+                            MAX(l1_batch_number) + 1
+                        FROM
+                            snapshot_recovery
+                    ),
+                    0
+                ) AS "l1_batch_number!",
+                MIN(number) AS "first_miniblock?",
+                MAX(number) AS "last_miniblock?",
+                MIN(timestamp) AS "timestamp?",
+                COALESCE(SUM(l1_tx_count), 0) AS "l1_tx_count!",
+                COALESCE(SUM(l2_tx_count), 0) AS "l2_tx_count!"
+            FROM
+                miniblocks
+            WHERE
+                l1_batch_number IS NULL
+            "#
+        )
+        .instrument("pending_batch_info#miniblocks")
+        .fetch_one(self.storage)
+        .await?;
+
+        let mut info = PendingL1BatchInfo {
+            l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            miniblocks: None,
+            timestamp: row.timestamp.map(|timestamp| timestamp as u64),
+            l1_tx_count: row.l1_tx_count as u32,
+            l2_tx_count: row.l2_tx_count as u32,
+            initial_writes_count: 0,
+            repeated_writes_count: 0,
+            l2_to_l1_logs_count: 0,
+            estimated_pubdata: 0,
+        };
+        let (Some(first_miniblock), Some(last_miniblock)) =
+            (row.first_miniblock, row.last_miniblock)
+        else {
+            return Ok(info);
+        };
+        info.miniblocks =
+            Some(MiniblockNumber(first_miniblock as u32)..=MiniblockNumber(last_miniblock as u32));
+
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        l2_to_l1_logs
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                ) AS "l2_to_l1_logs_count!",
+                COUNT(*) FILTER (
+                    WHERE
+                        initial_writes.hashed_key IS NULL
+                ) AS "initial_writes_count!",
+                COUNT(*) FILTER (
+                    WHERE
+                        initial_writes.hashed_key IS NOT NULL
+                ) AS "repeated_writes_count!"
+            FROM
+                (
+                    SELECT DISTINCT
+                        hashed_key
+                    FROM
+                        storage_logs
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                ) AS written_keys
+                LEFT JOIN initial_writes ON initial_writes.hashed_key = written_keys.hashed_key
+            "#,
+            first_miniblock,
+            last_miniblock
+        )
+        .instrument("pending_batch_info#pubdata")
+        .with_arg("miniblocks", &info.miniblocks)
+        .fetch_one(self.storage)
+        .await?;
+
+        info.initial_writes_count = row.initial_writes_count as u32;
+        info.repeated_writes_count = row.repeated_writes_count as u32;
+        info.l2_to_l1_logs_count = row.l2_to_l1_logs_count as u32;
+        info.estimated_pubdata = PendingL1BatchInfo::estimate_pubdata(
+            info.initial_writes_count,
+            info.repeated_writes_count,
+            info.l2_to_l1_logs_count,
+        );
+        Ok(info)
+    }
+
     // methods used for measuring Eth tx stage transition latencies
     // and emitting metrics base on these measured data
     pub async fn oldest_uncommitted_batch_timestamp(&mut self) -> sqlx::Result<Option<u64>> {
@@ -1947,7 +2324,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("oldest_uncommitted_batch_timestamp")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.timestamp as u64))
     }
@@ -1968,7 +2346,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("oldest_unproved_batch_timestamp")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.timestamp as u64))
     }
@@ -1989,7 +2368,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("oldest_unexecuted_batch_timestamp")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.timestamp as u64))
     }
@@ -2009,7 +2389,8 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_batch_protocol_version_id")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -2020,6 +2401,50 @@ impl BlocksDal<'_, '_> {
         Ok(Some((v as u16).try_into()?))
     }
 
+    /// Returns the protocol version the specified L1 batch must be executed with. For sealed batches, this is
+    /// the version recorded for the batch; for the pending batch, it is the version of its first miniblock.
+    /// Returns `None` for batches without miniblocks; the version for such a batch is determined by its timestamp
+    /// (see [`ProtocolVersionsDal::protocol_version_id_by_timestamp()`]).
+    ///
+    /// [`ProtocolVersionsDal::protocol_version_id_by_timestamp()`]: crate::protocol_versions_dal::ProtocolVersionsDal::protocol_version_id_by_timestamp()
+    pub async fn get_l1_batch_execution_protocol_version_id(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<ProtocolVersionId>> {
+        if let Some(version) = self.get_batch_protocol_version_id(l1_batch_number).await? {
+            return Ok(Some(version));
+        }
+        let sealed_l1_batch_number = self.get_sealed_l1_batch_number().await?;
+        let pending_l1_batch_number =
+            sealed_l1_batch_number.map_or(L1BatchNumber(0), |number| number + 1);
+        if l1_batch_number != pending_l1_batch_number {
+            return Ok(None);
+        }
+
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                protocol_version
+            FROM
+                miniblocks
+            WHERE
+                l1_batch_number IS NULL
+            ORDER BY
+                number
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_l1_batch_execution_protocol_version_id")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+        let Some(version) = row.and_then(|row| row.protocol_version) else {
+            return Ok(None);
+        };
+        Ok(Some((version as u16).try_into()?))
+    }
+
     pub async fn get_miniblock_protocol_version_id(
         &mut self,
         miniblock_number: MiniblockNumber,
@@ -2035,7 +2460,8 @@ impl BlocksDal<'_, '_> {
             "#,
             miniblock_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_miniblock_protocol_version_id")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -2061,7 +2487,8 @@ impl BlocksDal<'_, '_> {
             "#,
             miniblock_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_miniblock_timestamp")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.timestamp as u64))
     }
@@ -2080,7 +2507,8 @@ impl BlocksDal<'_, '_> {
             "#,
             id as i32,
         )
-        .execute(self.storage.conn())
+        .instrument("set_protocol_version_for_pending_miniblocks")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -2116,7 +2544,8 @@ impl BlocksDal<'_, '_> {
             "#,
             number.0 as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("raw_fee_address_for_miniblock")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -2140,7 +2569,8 @@ impl BlocksDal<'_, '_> {
             "#,
             miniblock_number.0 as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_virtual_blocks_for_miniblock")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.virtual_blocks as u32))
     }
@@ -2173,7 +2603,8 @@ impl BlocksDal<'_, '_> {
             "#,
             miniblock_number.0 as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("maybe_load_fee_address")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(());
@@ -2217,7 +2648,8 @@ impl BlocksDal<'_, '_> {
                 AND fee_account_address = '\x0000000000000000000000000000000000000000'::bytea
             "#
         )
-        .execute(self.storage.conn())
+        .instrument("copy_fee_account_address_for_pending_miniblocks")
+        .execute(self.storage)
         .await?;
 
         Ok(execution_result.rows_affected())
@@ -2231,7 +2663,8 @@ impl BlocksDal<'_, '_> {
             WHERE table_name = 'l1_batches' AND column_name = 'fee_account_address'
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("check_l1_batches_have_fee_account_address")
+        .fetch_one(self.storage)
         .await?
         .unwrap_or(0);
 
@@ -2259,7 +2692,8 @@ impl BlocksDal<'_, '_> {
             numbers.start().0 as i64,
             numbers.end().0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("copy_fee_account_address_for_miniblocks")
+        .execute(self.storage)
         .await?;
 
         Ok(execution_result.rows_affected())
@@ -2282,7 +2716,8 @@ impl BlocksDal<'_, '_> {
             fee_account_address.as_bytes(),
             l1_batch.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("set_l1_batch_fee_address")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -2307,7 +2742,8 @@ impl BlocksDal<'_, '_> {
             hash.as_bytes(),
             batch_num.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("set_l1_batch_hash")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -2339,16 +2775,70 @@ impl BlocksDal<'_, '_> {
     }
 }
 
+/// Postgres channel notified by a trigger on each inserted miniblock; the payload is the miniblock number.
+/// For non-default schemas, the channel name is prefixed with the schema name since channels are shared
+/// by all schemas in a database.
+const MINIBLOCK_SEALED_CHANNEL: &str = "miniblock_sealed";
+
+/// Listener for notifications about sealed miniblocks pushed by Postgres via `LISTEN / NOTIFY`.
+///
+/// Notifications are only emitted by the master DB (they are not replicated), so the listener
+/// must be created from a pool connected to the master. Notifications should be treated as hints:
+/// they may be lost if the listener connection is re-established, so consumers should still poll
+/// the DB periodically (albeit with a much larger interval).
+#[derive(Debug)]
+pub struct SealedMiniblockListener {
+    inner: PgListener,
+    channel: String,
+}
+
+impl SealedMiniblockListener {
+    /// Creates a listener holding a dedicated connection from the main database of the `pool`.
+    pub async fn new(pool: &ConnectionPool) -> sqlx::Result<Self> {
+        let channel = match pool.schema() {
+            None | Some("public") => MINIBLOCK_SEALED_CHANNEL.to_owned(),
+            Some(schema) => format!("{schema}_{MINIBLOCK_SEALED_CHANNEL}"),
+        };
+        let mut inner = PgListener::connect_with(&pool.inner).await?;
+        inner.listen(&channel).await?;
+        Ok(Self { inner, channel })
+    }
+
+    /// Waits for the next sealed miniblock notification and returns the number of the sealed miniblock.
+    /// Notifications with malformed payloads are skipped.
+    pub async fn recv(&mut self) -> sqlx::Result<MiniblockNumber> {
+        loop {
+            let notification = self.inner.recv().await?;
+            match notification.payload().parse::<u32>() {
+                Ok(number) => return Ok(MiniblockNumber(number)),
+                Err(err) => {
+                    tracing::warn!(
+                        "Received invalid payload {:?} on channel `{}`: {err}",
+                        notification.payload(),
+                        self.channel
+                    );
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_contracts::BaseSystemContractsHashes;
     use zksync_types::{
+        api,
+        fee::TransactionExecutionMetrics,
         l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
-        Address, ProtocolVersion, ProtocolVersionId,
+        AccountTreeId, Address, ProtocolVersion, ProtocolVersionId, StorageKey, StorageLog,
     };
 
     use super::*;
-    use crate::{tests::create_miniblock_header, ConnectionPool};
+    use crate::{
+        pruning_dal::PruningInfo,
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
 
     #[tokio::test]
     async fn loading_l1_batch_header() {
@@ -2406,6 +2896,375 @@ mod tests {
             .is_none());
     }
 
+    #[tokio::test]
+    async fn getting_l1_batch_execution_protocol_version() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let next_version = ProtocolVersionId::next();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion {
+                id: next_version,
+                timestamp: 100,
+                ..ProtocolVersion::default()
+            })
+            .await;
+        let version = conn
+            .protocol_versions_dal()
+            .protocol_version_id_by_timestamp(10)
+            .await
+            .unwrap();
+        assert_eq!(version, Some(ProtocolVersionId::default()));
+        let upgrades = conn
+            .protocol_versions_dal()
+            .get_scheduled_upgrades(10)
+            .await
+            .unwrap();
+        assert_eq!(upgrades, [(next_version, 100)]);
+
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            10,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        let version = conn
+            .blocks_dal()
+            .get_l1_batch_execution_protocol_version_id(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(version, Some(ProtocolVersionId::default()));
+
+        // The pending batch has no miniblocks yet.
+        let version = conn
+            .blocks_dal()
+            .get_l1_batch_execution_protocol_version_id(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(version, None);
+
+        let mut miniblock_header = create_miniblock_header(1);
+        miniblock_header.protocol_version = Some(next_version);
+        conn.blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await
+            .unwrap();
+        let version = conn
+            .blocks_dal()
+            .get_l1_batch_execution_protocol_version_id(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(version, Some(next_version));
+        let version = conn
+            .blocks_dal()
+            .get_l1_batch_execution_protocol_version_id(L1BatchNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(version, None);
+    }
+
+    #[tokio::test]
+    async fn getting_pending_batch_info() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let info = conn.blocks_dal().pending_batch_info().await.unwrap();
+        assert_eq!(info.l1_batch_number, L1BatchNumber(0));
+        assert_eq!(info.miniblocks, None);
+        assert_eq!(info.estimated_pubdata, 0);
+
+        let account = AccountTreeId::new(Address::zero());
+        let existing_key = StorageKey::new(account, H256::zero());
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(0))
+            .await
+            .unwrap();
+        conn.storage_logs_dal()
+            .insert_storage_logs(
+                MiniblockNumber(0),
+                &[(
+                    H256::zero(),
+                    vec![StorageLog::new_write_log(
+                        existing_key,
+                        H256::repeat_byte(1),
+                    )],
+                )],
+            )
+            .await
+            .unwrap();
+        conn.storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(0), &[existing_key])
+            .await
+            .unwrap();
+        let header = L1BatchHeader::new(
+            L1BatchNumber(0),
+            0,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(0))
+            .await
+            .unwrap();
+
+        let new_key = StorageKey::new(account, H256::repeat_byte(1));
+        for number in 1..3 {
+            let mut miniblock_header = create_miniblock_header(number);
+            miniblock_header.l2_tx_count = 2;
+            conn.blocks_dal()
+                .insert_miniblock(&miniblock_header)
+                .await
+                .unwrap();
+            let logs = vec![
+                StorageLog::new_write_log(existing_key, H256::repeat_byte(2)),
+                StorageLog::new_write_log(new_key, H256::repeat_byte(3)),
+            ];
+            conn.storage_logs_dal()
+                .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+                .await
+                .unwrap();
+        }
+
+        let info = conn.blocks_dal().pending_batch_info().await.unwrap();
+        assert_eq!(
+            info,
+            PendingL1BatchInfo {
+                l1_batch_number: L1BatchNumber(1),
+                miniblocks: Some(MiniblockNumber(1)..=MiniblockNumber(2)),
+                timestamp: Some(1),
+                l1_tx_count: 0,
+                l2_tx_count: 4,
+                initial_writes_count: 1,
+                repeated_writes_count: 1,
+                l2_to_l1_logs_count: 0,
+                estimated_pubdata: 64 + 40,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn getting_miniblock_headers_range() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..5 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        let headers = conn
+            .blocks_dal()
+            .get_miniblock_headers_range(MiniblockNumber(1)..=MiniblockNumber(3))
+            .await
+            .unwrap();
+        let numbers: Vec<_> = headers.iter().map(|header| header.number.0).collect();
+        assert_eq!(numbers, [1, 2, 3]);
+        let expected_header = conn
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(2))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(headers[1].hash, expected_header.hash);
+        assert_eq!(headers[1].timestamp, expected_header.timestamp);
+
+        let headers = conn
+            .blocks_dal()
+            .get_miniblock_headers_range(MiniblockNumber(3)..=MiniblockNumber(100))
+            .await
+            .unwrap();
+        let numbers: Vec<_> = headers.iter().map(|header| header.number.0).collect();
+        assert_eq!(numbers, [3, 4]);
+
+        let headers = conn
+            .blocks_dal()
+            .get_miniblock_headers_range(MiniblockNumber(10)..=MiniblockNumber(20))
+            .await
+            .unwrap();
+        assert!(headers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn listening_to_sealed_miniblocks() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut listener = SealedMiniblockListener::new(&pool).await.unwrap();
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..3 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        for number in 0..3 {
+            let sealed_miniblock = listener.recv().await.unwrap();
+            assert_eq!(sealed_miniblock, MiniblockNumber(number));
+        }
+    }
+
+    #[tokio::test]
+    async fn rolling_back_to_l1_batch() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let initial_fee_account = Address::repeat_byte(1);
+        conn.fee_account_dal()
+            .schedule_fee_account(initial_fee_account)
+            .await
+            .unwrap();
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        let conditions = api::TransactionConditions {
+            block_number_max: Some(10.into()),
+            ..api::TransactionConditions::default()
+        };
+        conn.transactions_dal()
+            .insert_transaction_conditions(tx_hash, &conditions)
+            .await
+            .unwrap();
+
+        // Miniblock #0 is in L1 batch #0, miniblocks #1 and #2 are in L1 batch #1, miniblock #3 is pending.
+        for (miniblock_numbers, l1_batch_number) in [(0..1, Some(0)), (1..3, Some(1)), (3..4, None)]
+        {
+            for number in miniblock_numbers {
+                conn.blocks_dal()
+                    .insert_miniblock(&create_miniblock_header(number))
+                    .await
+                    .unwrap();
+                let key = StorageKey::new(
+                    AccountTreeId::new(Address::zero()),
+                    H256::from_low_u64_be(number.into()),
+                );
+                let log = StorageLog::new_write_log(key, H256::repeat_byte(1));
+                conn.storage_logs_dal()
+                    .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), vec![log])])
+                    .await
+                    .unwrap();
+                if number == 2 {
+                    conn.transactions_dal()
+                        .mark_txs_as_executed_in_miniblock(
+                            MiniblockNumber(number),
+                            &[mock_execution_result(tx.clone())],
+                            1.into(),
+                        )
+                        .await
+                        .unwrap();
+                }
+            }
+            if let Some(l1_batch_number) = l1_batch_number {
+                let l1_batch_number = L1BatchNumber(l1_batch_number);
+                let header = L1BatchHeader::new(
+                    l1_batch_number,
+                    l1_batch_number.0.into(),
+                    BaseSystemContractsHashes::default(),
+                    ProtocolVersionId::latest(),
+                );
+                conn.blocks_dal()
+                    .insert_mock_l1_batch(&header)
+                    .await
+                    .unwrap();
+                conn.blocks_dal()
+                    .mark_miniblocks_as_executed_in_l1_batch(l1_batch_number)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        conn.fee_account_dal()
+            .schedule_fee_account(Address::repeat_byte(2))
+            .await
+            .unwrap();
+        conn.pruning_dal()
+            .prune_l1_batches(L1BatchNumber(1), MiniblockNumber(2))
+            .await
+            .unwrap();
+
+        conn.blocks_dal()
+            .rollback_to_l1_batch(L1BatchNumber(0))
+            .await
+            .unwrap();
+
+        let sealed_l1_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_l1_batch, Some(L1BatchNumber(0)));
+        let sealed_miniblock = conn
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_miniblock, Some(MiniblockNumber(0)));
+        let logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].miniblock_number, MiniblockNumber(0));
+
+        let fee_account = conn
+            .fee_account_dal()
+            .get_scheduled_fee_account()
+            .await
+            .unwrap();
+        assert_eq!(fee_account, Some(initial_fee_account));
+        let pruning_info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(
+            pruning_info,
+            Some(PruningInfo {
+                last_pruned_l1_batch: L1BatchNumber(0),
+                last_pruned_miniblock: MiniblockNumber(0),
+            })
+        );
+        // The transaction is reset to pending, so its conditions must be retained.
+        let receipts = conn
+            .transactions_web3_dal()
+            .get_transaction_receipts(&[tx_hash])
+            .await
+            .unwrap();
+        assert!(receipts.is_empty());
+        let loaded_conditions = conn
+            .transactions_dal()
+            .get_transaction_conditions(&[tx_hash])
+            .await
+            .unwrap();
+        assert_eq!(loaded_conditions, HashMap::from([(tx_hash, conditions)]));
+
+        let err = conn
+            .blocks_dal()
+            .rollback_to_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("contains no miniblocks"), "{err}");
+    }
+
     #[tokio::test]
     async fn getting_predicted_gas() {
         let pool = ConnectionPool::test_pool().await;
@@ -1,17 +1,18 @@
+use std::{collections::HashMap, ops};
+
 use zksync_system_constants::EMPTY_UNCLES_HASH;
 use zksync_types::{
-    api,
-    l2_to_l1_log::L2ToL1Log,
-    vm_trace::Call,
-    web3::types::{BlockHeader, U64},
-    Bytes, L1BatchNumber, MiniblockNumber, H160, H2048, H256, U256,
+    api, l2_to_l1_log::L2ToL1Log, vm_trace::Call, L1BatchNumber, MiniblockNumber, H2048, H256, U256,
 };
 use zksync_utils::bigdecimal_to_u256;
 
 use crate::{
     instrument::InstrumentExt,
     models::{
-        storage_block::{ResolvedL1BatchForMiniblock, StorageBlockDetails, StorageL1BatchDetails},
+        storage_block::{
+            ResolvedL1BatchForMiniblock, StorageBlockDetails, StorageBlockFinality,
+            StorageL1BatchDetails, StorageL1BatchFinality,
+        },
         storage_transaction::CallTrace,
     },
     StorageProcessor,
@@ -19,6 +20,25 @@ use crate::{
 
 const BLOCK_GAS_LIMIT: u32 = u32::MAX;
 
+/// Fee parameters of a miniblock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MiniblockFeeParams {
+    pub number: MiniblockNumber,
+    pub base_fee_per_gas: U256,
+    pub l1_gas_price: u64,
+    pub l2_fair_gas_price: u64,
+    pub gas_per_pubdata_limit: u64,
+}
+
+/// Percentiles of fee parameters over a range of miniblocks. Each vector contains a value per requested percentile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeParamsPercentiles {
+    pub base_fee_per_gas: Vec<U256>,
+    pub l1_gas_price: Vec<u64>,
+    pub l2_fair_gas_price: Vec<u64>,
+    pub gas_per_pubdata_limit: Vec<u64>,
+}
+
 #[derive(Debug)]
 pub struct BlocksWeb3Dal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
@@ -37,6 +57,7 @@ impl BlocksWeb3Dal<'_, '_> {
                 miniblocks.l1_batch_number,
                 miniblocks.timestamp,
                 miniblocks.base_fee_per_gas,
+                miniblocks.logs_bloom,
                 prev_miniblock.hash AS "parent_hash?",
                 l1_batches.timestamp AS "l1_batch_timestamp?",
                 transactions.gas_limit AS "gas_limit?",
@@ -54,7 +75,8 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_api_block")
+        .fetch_all(self.storage)
         .await?;
 
         let block = rows.into_iter().fold(None, |prev_block, row| {
@@ -74,7 +96,10 @@ impl BlocksWeb3Dal<'_, '_> {
                     base_fee_per_gas: bigdecimal_to_u256(row.base_fee_per_gas),
                     timestamp: (row.timestamp as u64).into(),
                     l1_batch_timestamp: row.l1_batch_timestamp.map(U256::from),
-                    // TODO: include logs
+                    logs_bloom: row
+                        .logs_bloom
+                        .as_deref()
+                        .map_or_else(H2048::zero, H2048::from_slice),
                     ..api::Block::default()
                 }
             });
@@ -102,88 +127,78 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_block_tx_count")
+        .fetch_optional(self.storage)
         .await?
         .flatten();
 
         Ok(tx_count.map(|count| count as u64))
     }
 
-    /// Returns hashes of blocks with numbers starting from `from_block` and the number of the last block.
-    pub async fn get_block_hashes_since(
+    /// Returns logs blooms stored for miniblocks in the specified range. Miniblocks without a stored bloom
+    /// (e.g., ones sealed before blooms were persisted) are omitted from the returned map.
+    pub async fn get_logs_blooms(
         &mut self,
-        from_block: MiniblockNumber,
-        limit: usize,
-    ) -> sqlx::Result<(Vec<H256>, Option<MiniblockNumber>)> {
+        numbers: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<HashMap<MiniblockNumber, H2048>> {
         let rows = sqlx::query!(
             r#"
             SELECT
                 number,
-                hash
+                logs_bloom AS "logs_bloom!"
             FROM
                 miniblocks
             WHERE
-                number >= $1
-            ORDER BY
-                number ASC
-            LIMIT
-                $2
+                number BETWEEN $1 AND $2
+                AND logs_bloom IS NOT NULL
             "#,
-            from_block.0 as i64,
-            limit as i32
+            i64::from(numbers.start().0),
+            i64::from(numbers.end().0)
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_logs_blooms")
+        .with_arg("numbers", &numbers)
+        .fetch_all(self.storage)
         .await?;
 
-        let last_block_number = rows.last().map(|row| MiniblockNumber(row.number as u32));
-        let hashes = rows.iter().map(|row| H256::from_slice(&row.hash)).collect();
-        Ok((hashes, last_block_number))
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let number = MiniblockNumber(row.number as u32);
+                (number, H2048::from_slice(&row.logs_bloom))
+            })
+            .collect())
     }
 
-    /// Returns hashes of blocks with numbers greater than `from_block` and the number of the last block.
-    pub async fn get_block_headers_after(
+    /// Returns hashes of blocks with numbers starting from `from_block` and the number of the last block.
+    pub async fn get_block_hashes_since(
         &mut self,
         from_block: MiniblockNumber,
-    ) -> sqlx::Result<Vec<BlockHeader>> {
+        limit: usize,
+    ) -> sqlx::Result<(Vec<H256>, Option<MiniblockNumber>)> {
         let rows = sqlx::query!(
             r#"
             SELECT
-                hash,
                 number,
-                timestamp
+                hash
             FROM
                 miniblocks
             WHERE
-                number > $1
+                number >= $1
             ORDER BY
                 number ASC
+            LIMIT
+                $2
             "#,
             from_block.0 as i64,
+            limit as i32
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_block_hashes_since")
+        .fetch_all(self.storage)
         .await?;
 
-        let blocks = rows.into_iter().map(|row| BlockHeader {
-            hash: Some(H256::from_slice(&row.hash)),
-            parent_hash: H256::zero(),
-            uncles_hash: EMPTY_UNCLES_HASH,
-            author: H160::zero(),
-            state_root: H256::zero(),
-            transactions_root: H256::zero(),
-            receipts_root: H256::zero(),
-            number: Some(U64::from(row.number)),
-            gas_used: U256::zero(),
-            gas_limit: U256::zero(),
-            base_fee_per_gas: None,
-            extra_data: Bytes::default(),
-            // TODO: include logs
-            logs_bloom: H2048::default(),
-            timestamp: U256::from(row.timestamp),
-            difficulty: U256::zero(),
-            mix_hash: None,
-            nonce: None,
-        });
-        Ok(blocks.collect())
+        let last_block_number = rows.last().map(|row| MiniblockNumber(row.number as u32));
+        let hashes = rows.iter().map(|row| H256::from_slice(&row.hash)).collect();
+        Ok((hashes, last_block_number))
     }
 
     pub async fn resolve_block_id(
@@ -238,7 +253,10 @@ impl BlocksWeb3Dal<'_, '_> {
             }
         );
 
-        let row = query.fetch_optional(self.storage.conn()).await?;
+        let row = query
+            .instrument("resolve_block_id")
+            .fetch_optional(self.storage)
+            .await?;
         let block_number = row
             .and_then(|row| row.number)
             .map(|number| MiniblockNumber(number as u32));
@@ -269,7 +287,8 @@ impl BlocksWeb3Dal<'_, '_> {
                 "#,
                 i64::from(miniblock_l1_batch.0)
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_expected_l1_batch_timestamp#miniblocks_1")
+            .fetch_optional(self.storage)
             .await?
             .map(|row| row.timestamp as u64))
         } else {
@@ -310,7 +329,8 @@ impl BlocksWeb3Dal<'_, '_> {
                 "#,
                 i64::from(prev_l1_batch_number.0)
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_expected_l1_batch_timestamp#miniblocks_2")
+            .fetch_optional(self.storage)
             .await?
             .map(|row| row.timestamp as u64))
         }
@@ -331,7 +351,8 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_miniblock_hash")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| H256::from_slice(&row.hash));
         Ok(hash)
@@ -352,7 +373,8 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l2_to_l1_logs")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.l2_to_l1_logs)
         .unwrap_or_default();
@@ -363,6 +385,40 @@ impl BlocksWeb3Dal<'_, '_> {
             .collect())
     }
 
+    /// Returns the precomputed Merkle proof for the L2-to-L1 log with the specified index in the L1 batch.
+    /// Returns `None` if the proof wasn't computed yet, or if the log doesn't exist.
+    pub async fn get_l2_to_l1_log_proof(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        log_index: u32,
+    ) -> sqlx::Result<Option<api::L2ToL1LogProof>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                root,
+                proof
+            FROM
+                l2_to_l1_log_proofs
+            WHERE
+                l1_batch_number = $1
+                AND log_index = $2
+            "#,
+            i64::from(l1_batch_number.0),
+            log_index as i32
+        )
+        .instrument("get_l2_to_l1_log_proof")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("log_index", &log_index)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| api::L2ToL1LogProof {
+            proof: row.proof.chunks(32).map(H256::from_slice).collect(),
+            id: log_index,
+            root: H256::from_slice(&row.root),
+        }))
+    }
+
     pub async fn get_l1_batch_number_of_miniblock(
         &mut self,
         miniblock_number: MiniblockNumber,
@@ -378,7 +434,8 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             miniblock_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_number_of_miniblock")
+        .fetch_optional(self.storage)
         .await?
         .and_then(|row| row.l1_batch_number);
 
@@ -401,7 +458,8 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_miniblock_range_of_l1_batch")
+        .fetch_one(self.storage)
         .await?;
 
         Ok(match (row.min, row.max) {
@@ -429,7 +487,8 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             tx_hash.as_bytes()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_info_for_tx")
+        .fetch_optional(self.storage)
         .await?;
 
         let result = row.and_then(|row| match (row.l1_batch_number, row.l1_batch_tx_index) {
@@ -462,43 +521,183 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_traces_for_miniblock")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(Call::from)
         .collect())
     }
 
-    /// Returns `base_fee_per_gas` for miniblock range [min(newest_block - block_count + 1, 0), newest_block]
-    /// in descending order of miniblock numbers.
-    pub async fn get_fee_history(
+    /// Returns fee parameters for the specified miniblocks ordered by the miniblock number. Miniblocks missing
+    /// from the storage (e.g., not sealed yet) are skipped.
+    pub async fn get_fee_series(
         &mut self,
-        newest_block: MiniblockNumber,
-        block_count: u64,
-    ) -> sqlx::Result<Vec<U256>> {
-        let result: Vec<_> = sqlx::query!(
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<Vec<MiniblockFeeParams>> {
+        let rows = sqlx::query!(
             r#"
             SELECT
-                base_fee_per_gas
+                number,
+                base_fee_per_gas,
+                l1_gas_price,
+                l2_fair_gas_price,
+                gas_per_pubdata_limit
             FROM
                 miniblocks
             WHERE
-                number <= $1
+                number BETWEEN $1 AND $2
             ORDER BY
-                number DESC
-            LIMIT
-                $2
+                number
             "#,
-            newest_block.0 as i64,
-            block_count as i64
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0)
         )
-        .fetch_all(self.storage.conn())
-        .await?
-        .into_iter()
-        .map(|row| bigdecimal_to_u256(row.base_fee_per_gas))
-        .collect();
+        .instrument("get_fee_series")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_all(self.storage)
+        .await?;
 
-        Ok(result)
+        Ok(rows
+            .into_iter()
+            .map(|row| MiniblockFeeParams {
+                number: MiniblockNumber(row.number as u32),
+                base_fee_per_gas: bigdecimal_to_u256(row.base_fee_per_gas),
+                l1_gas_price: row.l1_gas_price as u64,
+                l2_fair_gas_price: row.l2_fair_gas_price as u64,
+                gas_per_pubdata_limit: row.gas_per_pubdata_limit as u64,
+            })
+            .collect())
+    }
+
+    /// Returns rewards (i.e., effective priority fees per gas) at the specified `percentiles` for each of the specified
+    /// miniblocks, as defined by the `eth_feeHistory` method: for each miniblock, transactions are sorted by their reward,
+    /// and percentiles are weighted by the gas used by transactions. `percentiles` must be monotonically increasing
+    /// and lie in the `0.0..=100.0` range. Rewards for empty miniblocks are zeros. Miniblocks missing
+    /// from the storage are skipped.
+    pub async fn get_fee_rewards(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+        percentiles: &[f64],
+    ) -> sqlx::Result<Vec<Vec<U256>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblocks.number,
+                miniblocks.base_fee_per_gas,
+                transactions.effective_gas_price AS "effective_gas_price?",
+                transactions.gas_limit AS "gas_limit?",
+                transactions.refunded_gas AS "refunded_gas?"
+            FROM
+                miniblocks
+                LEFT JOIN transactions ON transactions.miniblock_number = miniblocks.number
+            WHERE
+                miniblocks.number BETWEEN $1 AND $2
+            ORDER BY
+                miniblocks.number,
+                transactions.index_in_block
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0)
+        )
+        .instrument("get_fee_rewards")
+        .with_arg("miniblocks", &miniblocks)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut rewards = vec![];
+        let mut current_number = None;
+        let mut tx_rewards = vec![];
+        for row in rows {
+            if current_number != Some(row.number) {
+                if current_number.is_some() {
+                    rewards.push(reward_percentiles(&mut tx_rewards, percentiles));
+                }
+                current_number = Some(row.number);
+                tx_rewards.clear();
+            }
+            // `effective_gas_price` is NULL for the null row produced by `LEFT JOIN` for an empty miniblock.
+            let Some(effective_gas_price) = row.effective_gas_price else {
+                continue;
+            };
+            let base_fee_per_gas = bigdecimal_to_u256(row.base_fee_per_gas);
+            let reward = bigdecimal_to_u256(effective_gas_price).saturating_sub(base_fee_per_gas);
+            let gas_limit = row.gas_limit.map_or(U256::zero(), bigdecimal_to_u256);
+            let gas_used = gas_limit.saturating_sub(row.refunded_gas.unwrap_or(0).into());
+            let gas_used = gas_used.min(U256::from(u64::MAX)).as_u64();
+            tx_rewards.push((reward, gas_used));
+        }
+        if current_number.is_some() {
+            rewards.push(reward_percentiles(&mut tx_rewards, percentiles));
+        }
+        Ok(rewards)
+    }
+
+    /// Returns percentiles of fee parameters over the specified miniblocks. `percentiles` must be in the `0.0..=1.0`
+    /// range. Returns `None` if there are no miniblocks in the range.
+    pub async fn get_fee_percentiles(
+        &mut self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+        percentiles: &[f64],
+    ) -> sqlx::Result<Option<FeeParamsPercentiles>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                PERCENTILE_DISC($3::FLOAT8[]) WITHIN GROUP (
+                    ORDER BY
+                        base_fee_per_gas
+                ) AS "base_fee_per_gas?",
+                PERCENTILE_DISC($3::FLOAT8[]) WITHIN GROUP (
+                    ORDER BY
+                        l1_gas_price
+                ) AS "l1_gas_price?",
+                PERCENTILE_DISC($3::FLOAT8[]) WITHIN GROUP (
+                    ORDER BY
+                        l2_fair_gas_price
+                ) AS "l2_fair_gas_price?",
+                PERCENTILE_DISC($3::FLOAT8[]) WITHIN GROUP (
+                    ORDER BY
+                        gas_per_pubdata_limit
+                ) AS "gas_per_pubdata_limit?"
+            FROM
+                miniblocks
+            WHERE
+                number BETWEEN $1 AND $2
+            "#,
+            i64::from(miniblocks.start().0),
+            i64::from(miniblocks.end().0),
+            percentiles
+        )
+        .instrument("get_fee_percentiles")
+        .with_arg("miniblocks", &miniblocks)
+        .with_arg("percentiles", &percentiles)
+        .fetch_one(self.storage)
+        .await?;
+
+        let (
+            Some(base_fee_per_gas),
+            Some(l1_gas_price),
+            Some(l2_fair_gas_price),
+            Some(gas_per_pubdata_limit),
+        ) = (
+            row.base_fee_per_gas,
+            row.l1_gas_price,
+            row.l2_fair_gas_price,
+            row.gas_per_pubdata_limit,
+        )
+        else {
+            return Ok(None);
+        };
+        let to_u64s = |values: Vec<i64>| values.into_iter().map(|value| value as u64).collect();
+        Ok(Some(FeeParamsPercentiles {
+            base_fee_per_gas: base_fee_per_gas
+                .into_iter()
+                .map(bigdecimal_to_u256)
+                .collect(),
+            l1_gas_price: to_u64s(l1_gas_price),
+            l2_fair_gas_price: to_u64s(l2_fair_gas_price),
+            gas_per_pubdata_limit: to_u64s(gas_per_pubdata_limit),
+        }))
     }
 
     pub async fn get_block_details(
@@ -557,14 +756,13 @@ impl BlocksWeb3Dal<'_, '_> {
         )
         .instrument("get_block_details")
         .with_arg("block_number", &block_number)
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
         let Some(storage_block_details) = storage_block_details else {
             return Ok(None);
         };
-        let mut details = api::BlockDetails::from(storage_block_details);
+        let mut details = api::BlockDetails::try_from(storage_block_details)?;
 
         // FIXME (PLA-728): remove after 2nd phase of `fee_account_address` migration
         #[allow(deprecated)]
@@ -575,6 +773,102 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(Some(details))
     }
 
+    /// Returns the finality status of the specified miniblock based on the confirmed L1 transactions
+    /// for its L1 batch. Returns `None` if the miniblock is not sealed.
+    pub async fn get_miniblock_finality(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> sqlx::Result<Option<api::BlockFinality>> {
+        let storage_finality = sqlx::query_as!(
+            StorageBlockFinality,
+            r#"
+            SELECT
+                miniblocks.number,
+                miniblocks.l1_batch_number,
+                commit_tx.tx_hash AS "commit_tx_hash?",
+                commit_tx.confirmed_at AS "committed_at?",
+                prove_tx.tx_hash AS "prove_tx_hash?",
+                prove_tx.confirmed_at AS "proven_at?",
+                execute_tx.tx_hash AS "execute_tx_hash?",
+                execute_tx.confirmed_at AS "executed_at?"
+            FROM
+                miniblocks
+                LEFT JOIN l1_batches ON miniblocks.l1_batch_number = l1_batches.number
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                miniblocks.number = $1
+            "#,
+            i64::from(block_number.0)
+        )
+        .instrument("get_miniblock_finality")
+        .with_arg("block_number", &block_number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(storage_finality) = storage_finality else {
+            return Ok(None);
+        };
+        Ok(Some(storage_finality.try_into()?))
+    }
+
+    /// Returns the finality status of the specified L1 batch based on the confirmed L1 transactions for it.
+    /// Returns `None` if the batch is not sealed.
+    pub async fn get_l1_batch_finality(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<api::L1BatchFinality>> {
+        let storage_finality = sqlx::query_as!(
+            StorageL1BatchFinality,
+            r#"
+            SELECT
+                l1_batches.number,
+                commit_tx.tx_hash AS "commit_tx_hash?",
+                commit_tx.confirmed_at AS "committed_at?",
+                prove_tx.tx_hash AS "prove_tx_hash?",
+                prove_tx.confirmed_at AS "proven_at?",
+                execute_tx.tx_hash AS "execute_tx_hash?",
+                execute_tx.confirmed_at AS "executed_at?"
+            FROM
+                l1_batches
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                l1_batches.number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_l1_batch_finality")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(storage_finality) = storage_finality else {
+            return Ok(None);
+        };
+        Ok(Some(storage_finality.try_into()?))
+    }
+
     pub async fn get_l1_batch_details(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -632,39 +926,315 @@ impl BlocksWeb3Dal<'_, '_> {
         )
         .instrument("get_l1_batch_details")
         .with_arg("l1_batch_number", &l1_batch_number)
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
-        Ok(l1_batch_details.map(Into::into))
+        l1_batch_details
+            .map(api::L1BatchDetails::try_from)
+            .transpose()
+            .map_err(Into::into)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use zksync_types::{
-        block::{MiniblockHasher, MiniblockHeader},
-        fee::TransactionExecutionMetrics,
-        Address, MiniblockNumber, ProtocolVersion, ProtocolVersionId,
-    };
+    /// Returns details of L1 batches in the specified range ordered by L1 batch number. L1 batches missing
+    /// from the storage are skipped.
+    pub async fn get_l1_batch_details_range(
+        &mut self,
+        numbers: ops::RangeInclusive<L1BatchNumber>,
+    ) -> sqlx::Result<Vec<api::L1BatchDetails>> {
+        let l1_batch_details = sqlx::query_as!(
+            StorageL1BatchDetails,
+            r#"
+            SELECT
+                l1_batches.number,
+                l1_batches.timestamp,
+                l1_batches.l1_tx_count,
+                l1_batches.l2_tx_count,
+                l1_batches.hash AS "root_hash?",
+                commit_tx.tx_hash AS "commit_tx_hash?",
+                commit_tx.confirmed_at AS "committed_at?",
+                prove_tx.tx_hash AS "prove_tx_hash?",
+                prove_tx.confirmed_at AS "proven_at?",
+                execute_tx.tx_hash AS "execute_tx_hash?",
+                execute_tx.confirmed_at AS "executed_at?",
+                mb.l1_gas_price,
+                mb.l2_fair_gas_price,
+                l1_batches.bootloader_code_hash,
+                l1_batches.default_aa_code_hash
+            FROM
+                l1_batches
+                INNER JOIN LATERAL (
+                    SELECT
+                        l1_gas_price,
+                        l2_fair_gas_price
+                    FROM
+                        miniblocks
+                    WHERE
+                        l1_batch_number = l1_batches.number
+                    LIMIT
+                        1
+                ) AS mb ON TRUE
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                l1_batches.number BETWEEN $1 AND $2
+            ORDER BY
+                l1_batches.number
+            "#,
+            i64::from(numbers.start().0),
+            i64::from(numbers.end().0)
+        )
+        .instrument("get_l1_batch_details_range")
+        .with_arg("numbers", &numbers)
+        .fetch_all(self.storage)
+        .await?;
 
-    use super::*;
-    use crate::{
-        tests::{
-            create_miniblock_header, create_snapshot_recovery, mock_execution_result,
-            mock_l2_transaction,
-        },
-        ConnectionPool,
-    };
+        l1_batch_details
+            .into_iter()
+            .map(api::L1BatchDetails::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(Into::into)
+    }
 
-    #[tokio::test]
-    async fn getting_web3_block_and_tx_count() {
-        let connection_pool = ConnectionPool::test_pool().await;
-        let mut conn = connection_pool.access_storage().await.unwrap();
-        conn.blocks_dal()
-            .delete_miniblocks(MiniblockNumber(0))
-            .await
-            .unwrap();
+    /// Returns a page of miniblock details ordered by miniblock number in the descending order.
+    /// Unlike offset-based pagination, the cost of loading a page doesn't depend on its position.
+    ///
+    /// `before` is an exclusive upper bound on the miniblock numbers; pass `None` to get the first page, and
+    /// [`api::Page::next_cursor`] of the previous page to get the subsequent ones.
+    pub async fn get_block_details_page(
+        &mut self,
+        before: Option<MiniblockNumber>,
+        limit: usize,
+    ) -> sqlx::Result<api::Page<api::BlockDetails, MiniblockNumber>> {
+        let before = before.map_or(i64::MAX, |number| number.0.into());
+        let storage_block_details = sqlx::query_as!(
+            StorageBlockDetails,
+            r#"
+            SELECT
+                miniblocks.number,
+                COALESCE(
+                    miniblocks.l1_batch_number,
+                    (
+                        SELECT
+                            (MAX(number) + 1)
+                        FROM
+                            l1_batches
+                    )
+                ) AS "l1_batch_number!",
+                miniblocks.timestamp,
+                miniblocks.l1_tx_count,
+                miniblocks.l2_tx_count,
+                miniblocks.hash AS "root_hash?",
+                commit_tx.tx_hash AS "commit_tx_hash?",
+                commit_tx.confirmed_at AS "committed_at?",
+                prove_tx.tx_hash AS "prove_tx_hash?",
+                prove_tx.confirmed_at AS "proven_at?",
+                execute_tx.tx_hash AS "execute_tx_hash?",
+                execute_tx.confirmed_at AS "executed_at?",
+                miniblocks.l1_gas_price,
+                miniblocks.l2_fair_gas_price,
+                miniblocks.bootloader_code_hash,
+                miniblocks.default_aa_code_hash,
+                miniblocks.protocol_version,
+                miniblocks.fee_account_address
+            FROM
+                miniblocks
+                LEFT JOIN l1_batches ON miniblocks.l1_batch_number = l1_batches.number
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                miniblocks.number < $1
+            ORDER BY
+                miniblocks.number DESC
+            LIMIT
+                $2
+            "#,
+            before,
+            limit as i64
+        )
+        .instrument("get_block_details_page")
+        .with_arg("before", &before)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut items = Vec::with_capacity(storage_block_details.len());
+        for storage_details in storage_block_details {
+            let mut details = api::BlockDetails::try_from(storage_details)?;
+            // FIXME (PLA-728): remove after 2nd phase of `fee_account_address` migration
+            #[allow(deprecated)]
+            self.storage
+                .blocks_dal()
+                .maybe_load_fee_address(&mut details.operator_address, details.number)
+                .await?;
+            items.push(details);
+        }
+        let next_cursor =
+            Self::next_page_cursor(&items, limit, |details| details.number.0).map(MiniblockNumber);
+        Ok(api::Page { items, next_cursor })
+    }
+
+    /// Returns a page of L1 batch details ordered by L1 batch number in the descending order.
+    /// See [`Self::get_block_details_page()`] for the pagination semantics.
+    pub async fn get_l1_batch_details_page(
+        &mut self,
+        before: Option<L1BatchNumber>,
+        limit: usize,
+    ) -> sqlx::Result<api::Page<api::L1BatchDetails, L1BatchNumber>> {
+        let before = before.map_or(i64::MAX, |number| number.0.into());
+        let l1_batch_details = sqlx::query_as!(
+            StorageL1BatchDetails,
+            r#"
+            SELECT
+                l1_batches.number,
+                l1_batches.timestamp,
+                l1_batches.l1_tx_count,
+                l1_batches.l2_tx_count,
+                l1_batches.hash AS "root_hash?",
+                commit_tx.tx_hash AS "commit_tx_hash?",
+                commit_tx.confirmed_at AS "committed_at?",
+                prove_tx.tx_hash AS "prove_tx_hash?",
+                prove_tx.confirmed_at AS "proven_at?",
+                execute_tx.tx_hash AS "execute_tx_hash?",
+                execute_tx.confirmed_at AS "executed_at?",
+                mb.l1_gas_price,
+                mb.l2_fair_gas_price,
+                l1_batches.bootloader_code_hash,
+                l1_batches.default_aa_code_hash
+            FROM
+                l1_batches
+                INNER JOIN LATERAL (
+                    SELECT
+                        l1_gas_price,
+                        l2_fair_gas_price
+                    FROM
+                        miniblocks
+                    WHERE
+                        l1_batch_number = l1_batches.number
+                    LIMIT
+                        1
+                ) AS mb ON TRUE
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                l1_batches.number < $1
+            ORDER BY
+                l1_batches.number DESC
+            LIMIT
+                $2
+            "#,
+            before,
+            limit as i64
+        )
+        .instrument("get_l1_batch_details_page")
+        .with_arg("before", &before)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        let items = l1_batch_details
+            .into_iter()
+            .map(api::L1BatchDetails::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let next_cursor =
+            Self::next_page_cursor(&items, limit, |details| details.number.0).map(L1BatchNumber);
+        Ok(api::Page { items, next_cursor })
+    }
+
+    /// Returns the cursor for the page following `items`. A full page means that there may be more items;
+    /// since items are ordered by number descending, there are no more items after number 0.
+    fn next_page_cursor<T>(items: &[T], limit: usize, number: impl Fn(&T) -> u32) -> Option<u32> {
+        if items.len() < limit {
+            return None;
+        }
+        let last_number = number(items.last()?);
+        (last_number > 0).then_some(last_number)
+    }
+}
+
+/// Computes reward percentiles for a single miniblock in the same way as Geth does: transactions are sorted
+/// by reward, and each percentile selects the first transaction at which the cumulative gas used reaches
+/// the corresponding share of the total gas used.
+fn reward_percentiles(tx_rewards: &mut [(U256, u64)], percentiles: &[f64]) -> Vec<U256> {
+    if tx_rewards.is_empty() {
+        return vec![U256::zero(); percentiles.len()];
+    }
+    tx_rewards.sort_unstable_by_key(|&(reward, _)| reward);
+    let total_gas_used: u128 = tx_rewards.iter().map(|&(_, gas)| u128::from(gas)).sum();
+
+    let mut tx_index = 0;
+    let mut cumulative_gas_used = u128::from(tx_rewards[0].1);
+    percentiles
+        .iter()
+        .map(|&percentile| {
+            let threshold = (total_gas_used as f64 * percentile / 100.0) as u128;
+            while cumulative_gas_used < threshold && tx_index < tx_rewards.len() - 1 {
+                tx_index += 1;
+                cumulative_gas_used += u128::from(tx_rewards[tx_index].1);
+            }
+            tx_rewards[tx_index].0
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        aggregated_operations::AggregatedActionType,
+        block::{L1BatchHeader, MiniblockHasher, MiniblockHeader},
+        event::logs_bloom,
+        fee::TransactionExecutionMetrics,
+        fee_model::BatchFeeInput,
+        tx::IncludedTxLocation,
+        Address, MiniblockNumber, ProtocolVersion, ProtocolVersionId, VmEvent, U64,
+    };
+
+    use super::*;
+    use crate::{
+        tests::{
+            create_miniblock_header, create_snapshot_recovery, mock_execution_result,
+            mock_l2_transaction,
+        },
+        ConnectionPool,
+    };
+
+    #[tokio::test]
+    async fn getting_web3_block_and_tx_count() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.blocks_dal()
+            .delete_miniblocks(MiniblockNumber(0))
+            .await
+            .unwrap();
         conn.protocol_versions_dal()
             .save_protocol_version_with_tx(ProtocolVersion::default())
             .await;
@@ -705,6 +1275,116 @@ mod tests {
         assert_eq!(tx_count.unwrap(), None);
     }
 
+    #[tokio::test]
+    async fn getting_stored_logs_blooms() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=2 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(1),
+            indexed_topics: vec![H256::repeat_byte(2)],
+            value: vec![3],
+        };
+        let tx_location = IncludedTxLocation {
+            tx_hash: H256::repeat_byte(1),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::repeat_byte(2),
+        };
+        conn.events_dal()
+            .save_events(MiniblockNumber(1), &[(tx_location, vec![&event])])
+            .await
+            .unwrap();
+        let expected_bloom = logs_bloom([&event]);
+        assert_ne!(expected_bloom, H2048::zero());
+
+        let block = conn
+            .blocks_web3_dal()
+            .get_api_block(MiniblockNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.logs_bloom, expected_bloom);
+        // Miniblock 2 doesn't have a stored bloom.
+        let block = conn
+            .blocks_web3_dal()
+            .get_api_block(MiniblockNumber(2))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.logs_bloom, H2048::zero());
+
+        let blooms = conn
+            .blocks_web3_dal()
+            .get_logs_blooms(MiniblockNumber(0)..=MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            blooms,
+            HashMap::from([(MiniblockNumber(1), expected_bloom)])
+        );
+    }
+
+    #[tokio::test]
+    async fn saving_and_getting_l2_to_l1_log_proofs() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            Default::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+
+        let root = H256::repeat_byte(0xff);
+        let merkle_paths = [
+            vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+            vec![H256::repeat_byte(3), H256::repeat_byte(4)],
+        ];
+        conn.blocks_dal()
+            .save_l2_to_l1_log_proofs(L1BatchNumber(1), root, &merkle_paths)
+            .await
+            .unwrap();
+        // Proofs must not be overwritten.
+        conn.blocks_dal()
+            .save_l2_to_l1_log_proofs(L1BatchNumber(1), H256::zero(), &merkle_paths[..1])
+            .await
+            .unwrap();
+
+        for (i, path) in merkle_paths.iter().enumerate() {
+            let proof = conn
+                .blocks_web3_dal()
+                .get_l2_to_l1_log_proof(L1BatchNumber(1), i as u32)
+                .await
+                .unwrap()
+                .expect("no proof");
+            assert_eq!(proof.id, i as u32);
+            assert_eq!(proof.root, root);
+            assert_eq!(proof.proof, *path);
+        }
+        let missing_proof = conn
+            .blocks_web3_dal()
+            .get_l2_to_l1_log_proof(L1BatchNumber(1), 2)
+            .await
+            .unwrap();
+        assert!(missing_proof.is_none());
+    }
+
     #[tokio::test]
     async fn resolving_earliest_block_id() {
         let connection_pool = ConnectionPool::test_pool().await;
@@ -861,7 +1541,8 @@ mod tests {
         for (i, tx) in transactions.into_iter().enumerate() {
             conn.transactions_dal()
                 .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
-                .await;
+                .await
+                .unwrap();
             let mut tx_result = mock_execution_result(tx);
             tx_result.call_traces.push(Call {
                 from: Address::from_low_u64_be(i as u64),
@@ -873,7 +1554,8 @@ mod tests {
         }
         conn.transactions_dal()
             .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &tx_results, 1.into())
-            .await;
+            .await
+            .unwrap();
 
         let traces = conn
             .blocks_web3_dal()
@@ -886,4 +1568,335 @@ mod tests {
             assert_eq!(*trace, expected_trace);
         }
     }
+
+    #[tokio::test]
+    async fn getting_fee_series_and_percentiles() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..5 {
+            let mut header = create_miniblock_header(number);
+            header.base_fee_per_gas = u64::from(number) * 100;
+            header.batch_fee_input =
+                BatchFeeInput::l1_pegged(u64::from(number) * 1_000, u64::from(number) * 10);
+            conn.blocks_dal().insert_miniblock(&header).await.unwrap();
+        }
+
+        let series = conn
+            .blocks_web3_dal()
+            .get_fee_series(MiniblockNumber(3)..=MiniblockNumber(10))
+            .await
+            .unwrap();
+        assert_eq!(
+            series,
+            [3, 4].map(|number| MiniblockFeeParams {
+                number: MiniblockNumber(number),
+                base_fee_per_gas: U256::from(number * 100),
+                l1_gas_price: u64::from(number) * 1_000,
+                l2_fair_gas_price: u64::from(number) * 10,
+                gas_per_pubdata_limit: 100,
+            })
+        );
+
+        let percentiles = conn
+            .blocks_web3_dal()
+            .get_fee_percentiles(MiniblockNumber(1)..=MiniblockNumber(4), &[0.0, 0.5, 1.0])
+            .await
+            .unwrap()
+            .expect("no percentiles");
+        assert_eq!(
+            percentiles.base_fee_per_gas,
+            [100_u32, 200, 400].map(U256::from)
+        );
+        assert_eq!(percentiles.l1_gas_price, [1_000, 2_000, 4_000]);
+        assert_eq!(percentiles.l2_fair_gas_price, [10, 20, 40]);
+        assert_eq!(percentiles.gas_per_pubdata_limit, [100, 100, 100]);
+
+        let percentiles = conn
+            .blocks_web3_dal()
+            .get_fee_percentiles(MiniblockNumber(10)..=MiniblockNumber(20), &[0.5])
+            .await
+            .unwrap();
+        assert_eq!(percentiles, None);
+    }
+
+    #[test]
+    fn computing_reward_percentiles() {
+        let mut tx_rewards = [
+            (U256::from(30), 100),
+            (U256::from(10), 300),
+            (U256::from(20), 600),
+        ];
+        let rewards = reward_percentiles(&mut tx_rewards, &[0.0, 25.0, 30.0, 50.0, 90.0, 100.0]);
+        let expected_rewards = [10, 10, 10, 20, 20, 30].map(U256::from);
+        assert_eq!(rewards, expected_rewards);
+
+        let rewards = reward_percentiles(&mut [], &[10.0, 50.0]);
+        assert_eq!(rewards, [U256::zero(); 2]);
+    }
+
+    #[tokio::test]
+    async fn getting_fee_rewards() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=2 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        let tx = mock_l2_transaction();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        // The miniblock base fee is 100, so the effective priority fee is 50.
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(
+                MiniblockNumber(1),
+                &[mock_execution_result(tx)],
+                150.into(),
+            )
+            .await
+            .unwrap();
+
+        let rewards = conn
+            .blocks_web3_dal()
+            .get_fee_rewards(MiniblockNumber(1)..=MiniblockNumber(3), &[0.0, 100.0])
+            .await
+            .unwrap();
+        assert_eq!(rewards, [vec![U256::from(50); 2], vec![U256::zero(); 2]]);
+    }
+
+    #[tokio::test]
+    async fn getting_block_details_pages() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..5 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        let page = conn
+            .blocks_web3_dal()
+            .get_block_details_page(None, 2)
+            .await
+            .unwrap();
+        let numbers: Vec<_> = page.items.iter().map(|details| details.number.0).collect();
+        assert_eq!(numbers, [4, 3]);
+        assert_eq!(page.next_cursor, Some(MiniblockNumber(3)));
+
+        let page = conn
+            .blocks_web3_dal()
+            .get_block_details_page(page.next_cursor, 2)
+            .await
+            .unwrap();
+        let numbers: Vec<_> = page.items.iter().map(|details| details.number.0).collect();
+        assert_eq!(numbers, [2, 1]);
+        assert_eq!(page.next_cursor, Some(MiniblockNumber(1)));
+
+        let page = conn
+            .blocks_web3_dal()
+            .get_block_details_page(page.next_cursor, 2)
+            .await
+            .unwrap();
+        let numbers: Vec<_> = page.items.iter().map(|details| details.number.0).collect();
+        assert_eq!(numbers, [0]);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn getting_l1_batch_details_range() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..3 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                number.into(),
+                Default::default(),
+                ProtocolVersionId::default(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+            conn.blocks_dal()
+                .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+                .await
+                .unwrap();
+        }
+
+        let details = conn
+            .blocks_web3_dal()
+            .get_l1_batch_details_range(L1BatchNumber(1)..=L1BatchNumber(5))
+            .await
+            .unwrap();
+        let numbers: Vec<_> = details.iter().map(|details| details.number.0).collect();
+        assert_eq!(numbers, [1, 2]);
+        assert_eq!(details[0].base.timestamp, 1);
+
+        let details = conn
+            .blocks_web3_dal()
+            .get_l1_batch_details_range(L1BatchNumber(3)..=L1BatchNumber(5))
+            .await
+            .unwrap();
+        assert!(details.is_empty());
+    }
+
+    #[tokio::test]
+    async fn getting_miniblock_finality() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..2 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                number.into(),
+                Default::default(),
+                ProtocolVersionId::default(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+            conn.blocks_dal()
+                .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+                .await
+                .unwrap();
+        }
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(2))
+            .await
+            .unwrap();
+
+        let finality = conn
+            .blocks_web3_dal()
+            .get_miniblock_finality(MiniblockNumber(0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(finality.l1_batch_number, Some(L1BatchNumber(0)));
+        assert_eq!(finality.status, api::BlockFinalityStatus::Executed);
+        let finality = conn
+            .blocks_web3_dal()
+            .get_miniblock_finality(MiniblockNumber(2))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(finality.l1_batch_number, None);
+        assert_eq!(finality.status, api::BlockFinalityStatus::Sealed);
+        let finality = conn
+            .blocks_web3_dal()
+            .get_miniblock_finality(MiniblockNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(finality, None);
+
+        let finality = conn
+            .blocks_web3_dal()
+            .get_miniblock_finality(MiniblockNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(finality.l1_batch_number, Some(L1BatchNumber(1)));
+        assert_eq!(finality.status, api::BlockFinalityStatus::Sealed);
+
+        let eth_tx = conn
+            .eth_sender_dal()
+            .save_eth_tx(
+                0,
+                vec![],
+                AggregatedActionType::Commit,
+                Address::zero(),
+                0,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let commit_tx_hash = H256::repeat_byte(1);
+        conn.eth_sender_dal()
+            .insert_tx_history(eth_tx.id, 0, 0, None, commit_tx_hash, &[])
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .set_eth_tx_id(
+                L1BatchNumber(1)..=L1BatchNumber(1),
+                eth_tx.id,
+                AggregatedActionType::Commit,
+            )
+            .await
+            .unwrap();
+        // The commit transaction is not confirmed yet.
+        let finality = conn
+            .blocks_web3_dal()
+            .get_miniblock_finality(MiniblockNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(finality.status, api::BlockFinalityStatus::Sealed);
+
+        conn.eth_sender_dal()
+            .confirm_tx(commit_tx_hash, U256::zero())
+            .await
+            .unwrap();
+        let finality = conn
+            .blocks_web3_dal()
+            .get_miniblock_finality(MiniblockNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(finality.status, api::BlockFinalityStatus::Committed);
+        assert_eq!(finality.commit_tx_hash, Some(commit_tx_hash));
+        assert!(finality.committed_at.is_some());
+        assert_eq!(finality.prove_tx_hash, None);
+
+        let l1_batch_finality = conn
+            .blocks_web3_dal()
+            .get_l1_batch_finality(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(l1_batch_finality.number, L1BatchNumber(1));
+        assert_eq!(l1_batch_finality.status, finality.status);
+        assert_eq!(l1_batch_finality.commit_tx_hash, finality.commit_tx_hash);
+        assert_eq!(l1_batch_finality.committed_at, finality.committed_at);
+        let l1_batch_finality = conn
+            .blocks_web3_dal()
+            .get_l1_batch_finality(L1BatchNumber(0))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(l1_batch_finality.status, api::BlockFinalityStatus::Executed);
+        let l1_batch_finality = conn
+            .blocks_web3_dal()
+            .get_l1_batch_finality(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(l1_batch_finality, None);
+    }
 }
@@ -1,12 +1,13 @@
 use std::{
     env, fmt,
     future::Future,
+    iter,
     panic::Location,
     sync::{
-        atomic::{AtomicU64, Ordering},
-        Arc,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::Context as _;
@@ -15,21 +16,26 @@ use sqlx::{
     pool::PoolConnection,
     postgres::{PgConnectOptions, PgPool, PgPoolOptions, Postgres},
 };
+use vise::{EncodeLabelSet, EncodeLabelValue};
 
-pub use self::processor::StorageProcessor;
 pub(crate) use self::processor::StorageProcessorTags;
 use self::processor::TracedConnections;
+pub use self::{processor::StorageProcessor, retry::TransactionRetryPolicy};
 use crate::metrics::{PostgresMetrics, CONNECTION_METRICS};
 
 mod processor;
+mod retry;
 
 /// Builder for [`ConnectionPool`]s.
 #[derive(Clone)]
 pub struct ConnectionPoolBuilder {
     database_url: String,
+    replica_urls: Vec<String>,
+    failover_url: Option<String>,
     max_size: u32,
     acquire_timeout: Duration,
     statement_timeout: Option<Duration>,
+    schema: Option<String>,
 }
 
 impl fmt::Debug for ConnectionPoolBuilder {
@@ -37,9 +43,12 @@ impl fmt::Debug for ConnectionPoolBuilder {
         // Database URL is potentially sensitive, thus we omit it.
         formatter
             .debug_struct("ConnectionPoolBuilder")
+            .field("replicas_count", &self.replica_urls.len())
+            .field("has_failover", &self.failover_url.is_some())
             .field("max_size", &self.max_size)
             .field("acquire_timeout", &self.acquire_timeout)
             .field("statement_timeout", &self.statement_timeout)
+            .field("schema", &self.schema)
             .finish()
     }
 }
@@ -69,38 +78,206 @@ impl ConnectionPoolBuilder {
         self
     }
 
+    /// Sets the Postgres schema used by the pool. The schema is prepended to the `search_path` of all pool
+    /// connections, so that unqualified table names in queries resolve to this schema. This allows running
+    /// multiple chains against a single database, with each chain's tables living in a dedicated schema.
+    /// The schema must be created and migrated beforehand. If not specified, the default `search_path`
+    /// (i.e., normally the `public` schema) is used.
+    ///
+    /// The schema applies to all databases of the pool, including replicas and the failover database.
+    pub fn set_schema(&mut self, schema: Option<&str>) -> &mut Self {
+        self.schema = schema.map(String::from);
+        self
+    }
+
+    /// Adds replica databases to the pool. Connections are acquired from the least lagging of the main database
+    /// and replicas (as per their replication lag checked in the background), so that reads don't go back in time
+    /// when switching among databases; databases lagging by less than a small threshold are considered equally
+    /// up to date and are used in the round-robin fashion.
+    /// If a connection cannot be acquired from a database, the next one is tried, and the database is skipped
+    /// until a background health check succeeds.
+    ///
+    /// All databases must be interchangeable from the perspective of the pool users; thus, replicas
+    /// should only be added to pools used exclusively for reading data (e.g., by the API server).
+    pub fn add_replica_urls<'a>(&mut self, urls: impl IntoIterator<Item = &'a str>) -> &mut Self {
+        self.replica_urls.extend(urls.into_iter().map(String::from));
+        self
+    }
+
+    /// Sets the failover database for the pool (usually, the master database). The failover database
+    /// is only used if neither the main database nor replicas can provide a connection. To not duplicate
+    /// connections to the failover database, its pool is limited to a fraction of the pool size, and its idle
+    /// connections are closed shortly after failover ends. If the failover URL coincides with the main database
+    /// or a replica URL, it is ignored.
+    pub fn set_failover_url(&mut self, url: Option<&str>) -> &mut Self {
+        self.failover_url = url.map(String::from);
+        self
+    }
+
     /// Returns the maximum number of connections that can be allocated by the pool.
+    /// If the pool has replicas and / or a failover database, this limit applies to each database separately.
     pub fn max_size(&self) -> u32 {
         self.max_size
     }
 
-    /// Builds a connection pool from this builder.
-    pub async fn build(&self) -> anyhow::Result<ConnectionPool> {
-        let options = PgPoolOptions::new()
-            .max_connections(self.max_size)
-            .acquire_timeout(self.acquire_timeout);
-        let mut connect_options: PgConnectOptions = self
-            .database_url
+    fn connect_options(&self, database_url: &str) -> anyhow::Result<PgConnectOptions> {
+        let mut connect_options: PgConnectOptions = database_url
             .parse()
             .context("Failed parsing database URL")?;
         if let Some(timeout) = self.statement_timeout {
             let timeout_string = format!("{}s", timeout.as_secs());
             connect_options = connect_options.options([("statement_timeout", timeout_string)]);
         }
-        let pool = options
-            .connect_with(connect_options)
+        if let Some(schema) = &self.schema {
+            Self::validate_schema(schema)?;
+            connect_options = connect_options.options([("search_path", schema)]);
+        }
+        Ok(connect_options)
+    }
+
+    /// Checks that the schema name is a plain lowercase identifier, so that it doesn't need to be quoted
+    /// and cannot inject other connection options.
+    fn validate_schema(schema: &str) -> anyhow::Result<()> {
+        let is_valid = schema.starts_with(|ch: char| ch.is_ascii_lowercase() || ch == '_')
+            && schema
+                .chars()
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_');
+        anyhow::ensure!(
+            is_valid,
+            "Invalid Postgres schema name `{schema}`; only lowercase identifiers are supported"
+        );
+        Ok(())
+    }
+
+    fn pool_options(&self) -> PgPoolOptions {
+        PgPoolOptions::new()
+            .max_connections(self.max_size)
+            .acquire_timeout(self.acquire_timeout)
+    }
+
+    /// Builds a connection pool from this builder.
+    pub async fn build(&self) -> anyhow::Result<ConnectionPool> {
+        let connect_options = self.connect_options(&self.database_url)?;
+        let pool = self
+            .pool_options()
+            .connect_with(connect_options.clone())
             .await
             .context("Failed connecting to database")?;
+
+        // Replicas and the failover database are connected to lazily, so that an unavailable database
+        // doesn't prevent the pool from being created.
+        let replicas = self
+            .replica_urls
+            .iter()
+            .map(|url| {
+                let connect_options = self.connect_options(url)?;
+                Ok(self.pool_options().connect_lazy_with(connect_options))
+            })
+            .collect::<anyhow::Result<_>>()
+            .context("Failed creating replica pool")?;
+        let failover = self
+            .failover_url
+            .as_deref()
+            .filter(|&url| url != self.database_url && !self.replica_urls.iter().any(|u| u == url))
+            .map(|url| {
+                let connect_options = self.connect_options(url)?;
+                let pool_options = self
+                    .pool_options()
+                    .max_connections((self.max_size / FAILOVER_POOL_SIZE_DIVISOR).max(1))
+                    .min_connections(0)
+                    .idle_timeout(FAILOVER_IDLE_TIMEOUT);
+                anyhow::Ok(pool_options.connect_lazy_with(connect_options))
+            })
+            .transpose()
+            .context("Failed creating failover pool")?;
+
         tracing::info!("Created DB pool with parameters {self:?}");
         Ok(ConnectionPool {
             database_url: self.database_url.clone(),
+            connect_options,
             inner: pool,
+            replicas: Arc::new(ReplicaPools::new(replicas, failover)),
             max_size: self.max_size,
+            acquire_timeout: self.acquire_timeout,
+            schema: self.schema.clone(),
+            component: None,
             traced_connections: None,
         })
     }
 }
 
+/// Divisor of the pool size to get the size of the failover pool.
+const FAILOVER_POOL_SIZE_DIVISOR: u32 = 4;
+/// Timeout after which idle failover connections are closed.
+const FAILOVER_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Interval between health checks of an unavailable database.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// Interval between checks of replication lag of the balanced databases.
+const LAG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// Replication lag below which databases are considered equally up to date.
+const ACCEPTABLE_REPLICATION_LAG: Duration = Duration::from_millis(500);
+
+/// Status of a balanced database (the main database or a replica) of a [`ConnectionPool`].
+#[derive(Debug)]
+struct DbStatus {
+    /// Set if acquiring a connection from the database has failed. Unavailable databases are skipped
+    /// until a background health check succeeds.
+    is_unavailable: AtomicBool,
+    /// Replication lag of the database in milliseconds as of the last check. Always 0 for a database
+    /// that is not a replica.
+    replication_lag_ms: AtomicU64,
+}
+
+impl DbStatus {
+    fn new(replication_lag_ms: u64) -> Self {
+        Self {
+            is_unavailable: AtomicBool::new(false),
+            replication_lag_ms: AtomicU64::new(replication_lag_ms),
+        }
+    }
+
+    /// Returns the replication lag used to order databases. Lags below [`ACCEPTABLE_REPLICATION_LAG`]
+    /// are not distinguished, so that load is balanced among replicas that keep up with the master.
+    fn effective_lag_ms(&self) -> u64 {
+        let acceptable_lag_ms = ACCEPTABLE_REPLICATION_LAG.as_millis() as u64;
+        self.replication_lag_ms
+            .load(Ordering::Relaxed)
+            .max(acceptable_lag_ms)
+    }
+}
+
+/// Replica databases and the failover database for a [`ConnectionPool`].
+#[derive(Debug, Default)]
+struct ReplicaPools {
+    pools: Vec<PgPool>,
+    failover: Option<PgPool>,
+    /// Statuses of the balanced databases (0 is the main database, `1..` are replicas).
+    statuses: Vec<DbStatus>,
+    /// Rotating index used to balance load among equally up-to-date databases.
+    next_index: AtomicUsize,
+    last_lag_check: Mutex<Option<Instant>>,
+}
+
+impl ReplicaPools {
+    fn new(pools: Vec<PgPool>, failover: Option<PgPool>) -> Self {
+        // Replicas are considered maximally lagging until their lag is checked.
+        let replica_statuses = iter::repeat_with(|| DbStatus::new(u64::MAX)).take(pools.len());
+        Self {
+            statuses: iter::once(DbStatus::new(0))
+                .chain(replica_statuses)
+                .collect(),
+            pools,
+            failover,
+            next_index: AtomicUsize::new(0),
+            last_lag_check: Mutex::new(None),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pools.is_empty() && self.failover.is_none()
+    }
+}
+
 #[derive(Debug)]
 pub struct TestTemplate(url::Url);
 
@@ -228,11 +405,27 @@ impl GlobalConnectionPoolConfig {
     }
 }
 
+/// Server component that can be assigned a dedicated connection pool via [`ConnectionPool::sub_pool()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "component", rename_all = "snake_case")]
+pub enum PoolComponent {
+    Api,
+    StateKeeper,
+    WitnessGenerator,
+    HouseKeeper,
+}
+
 #[derive(Clone)]
 pub struct ConnectionPool {
     pub(crate) inner: PgPool,
+    replicas: Arc<ReplicaPools>,
     database_url: String,
+    /// Connection options for the main database, used to create sub-pools.
+    connect_options: PgConnectOptions,
     max_size: u32,
+    acquire_timeout: Duration,
+    schema: Option<String>,
+    component: Option<PoolComponent>,
     traced_connections: Option<Arc<TracedConnections>>,
 }
 
@@ -242,7 +435,11 @@ impl fmt::Debug for ConnectionPool {
         // sensitive information (e.g. database password).
         formatter
             .debug_struct("ConnectionPool")
+            .field("replicas_count", &self.replicas.pools.len())
+            .field("has_failover", &self.replicas.failover.is_some())
             .field("max_size", &self.max_size)
+            .field("schema", &self.schema)
+            .field("component", &self.component)
             .finish_non_exhaustive()
     }
 }
@@ -288,9 +485,12 @@ impl ConnectionPool {
     pub fn builder(database_url: &str, max_pool_size: u32) -> ConnectionPoolBuilder {
         ConnectionPoolBuilder {
             database_url: database_url.to_string(),
+            replica_urls: vec![],
+            failover_url: None,
             max_size: max_pool_size,
             acquire_timeout: Duration::from_secs(30), // Default value used by `sqlx`
             statement_timeout: None,
+            schema: None,
         }
     }
 
@@ -307,6 +507,50 @@ impl ConnectionPool {
         self.max_size
     }
 
+    /// Returns the Postgres schema used by this pool, or `None` if the pool uses the default `search_path`.
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Creates a dedicated pool for the specified component with up to `max_connections` connections
+    /// to the main database of this pool. The sub-pool inherits connection options (e.g., the statement timeout
+    /// and schema) and the acquire timeout of this pool, but doesn't share connections with it or with other sub-pools.
+    /// Thus, connections are reserved per component: a burst of requests from one component (e.g., the API)
+    /// cannot starve other components, and components cannot block each other by holding connections.
+    /// Note that Postgres must be configured to accept connections from all pools at once.
+    ///
+    /// The sub-pool connects lazily and doesn't use replicas or the failover database of this pool.
+    /// The returned pool should be cloned rather than re-created for each consumer within the component.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_connections` is zero, or if this pool is a sub-pool itself.
+    pub fn sub_pool(&self, component: PoolComponent, max_connections: u32) -> Self {
+        assert!(
+            max_connections > 0,
+            "Max number of connections for {component:?} must be positive"
+        );
+        assert!(
+            self.component.is_none(),
+            "Cannot create a sub-pool for {component:?} from another sub-pool"
+        );
+
+        let inner = PgPoolOptions::new()
+            .max_connections(max_connections)
+            .acquire_timeout(self.acquire_timeout)
+            .connect_lazy_with(self.connect_options.clone());
+        tracing::info!(
+            "Created dedicated DB pool for {component:?} with {max_connections} connections"
+        );
+        Self {
+            inner,
+            replicas: Arc::default(),
+            max_size: max_connections,
+            component: Some(component),
+            ..self.clone()
+        }
+    }
+
     /// Uses this pool to report Postgres-wide metrics (e.g., table sizes). Should be called sparingly to not spam
     /// identical metrics from multiple places. The returned future runs indefinitely and should be spawned as a Tokio task.
     pub async fn run_postgres_metrics_scraping(self, scrape_interval: Duration) {
@@ -359,6 +603,11 @@ impl ConnectionPool {
         if let Some(tags) = &tags {
             CONNECTION_METRICS.acquire_tagged[&tags.requester].observe(elapsed);
         }
+        if let Some(component) = self.component {
+            CONNECTION_METRICS.component_acquire[&component].observe(elapsed);
+            let connections_in_use = self.inner.size() as usize - self.inner.num_idle();
+            CONNECTION_METRICS.component_connections[&component].set(connections_in_use);
+        }
         Ok(StorageProcessor::from_pool(
             conn,
             tags,
@@ -379,7 +628,7 @@ impl ConnectionPool {
                 .observe(self.inner.size() as usize);
             CONNECTION_METRICS.pool_idle.observe(self.inner.num_idle());
 
-            let connection = self.inner.acquire().await;
+            let connection = self.acquire_connection().await;
             let connection_err = match connection {
                 Ok(connection) => return Ok(connection),
                 Err(err) => err,
@@ -397,7 +646,7 @@ impl ConnectionPool {
         }
 
         // Attempting to get the pooled connection for the last time
-        match self.inner.acquire().await {
+        match self.acquire_connection().await {
             Ok(conn) => Ok(conn),
             Err(err) => {
                 Self::report_connection_error(&err);
@@ -414,13 +663,170 @@ impl ConnectionPool {
         }
     }
 
+    /// Makes a single attempt to acquire a connection. If the pool has replicas, databases are tried
+    /// in the order returned by [`Self::balanced_db_order()`], and then the failover database is tried.
+    async fn acquire_connection(&self) -> sqlx::Result<PoolConnection<Postgres>> {
+        if self.replicas.is_empty() {
+            return self.inner.acquire().await;
+        }
+
+        self.check_replication_lag_if_stale();
+        let has_failover = self.replicas.failover.is_some();
+        let mut last_err = None;
+        for index in self.balanced_db_order() {
+            let status = &self.replicas.statuses[index];
+            if has_failover && status.is_unavailable.load(Ordering::Relaxed) {
+                // Unavailable databases are ordered last, so all available databases were already tried.
+                // Do not wait for an unavailable database to time out and fail over right away.
+                break;
+            }
+            match self.balanced_pool(index).acquire().await {
+                Ok(connection) => return Ok(connection),
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed to get connection from DB #{index}, trying next one: {err}"
+                    );
+                    Self::report_connection_error(&err);
+                    self.mark_unavailable(index);
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        let last_err = last_err.unwrap_or(sqlx::Error::PoolTimedOut);
+        if let Some(failover) = &self.replicas.failover {
+            tracing::warn!(
+                "Failed to get connection from main DB and all replicas, failing over: {last_err}"
+            );
+            CONNECTION_METRICS.pool_failover.inc();
+            failover.acquire().await
+        } else {
+            Err(last_err)
+        }
+    }
+
     fn report_connection_error(err: &sqlx::Error) {
         CONNECTION_METRICS.pool_acquire_error[&err.into()].inc();
     }
+
+    /// Returns a pool for a balanced database (0 is the main database, `1..` are replicas).
+    fn balanced_pool(&self, index: usize) -> &PgPool {
+        if index == 0 {
+            &self.inner
+        } else {
+            &self.replicas.pools[index - 1]
+        }
+    }
+
+    /// Returns indices of the balanced databases in the order they should be tried. Available databases go first,
+    /// the least lagging ones first, so that reads don't go back in time when switching among databases.
+    /// Equally up-to-date databases are rotated to balance load.
+    fn balanced_db_order(&self) -> Vec<usize> {
+        let statuses = &self.replicas.statuses;
+        let start_index = self.replicas.next_index.fetch_add(1, Ordering::Relaxed) % statuses.len();
+        let mut indices: Vec<_> = (0..statuses.len())
+            .map(|i| (start_index + i) % statuses.len())
+            .collect();
+        // The sort is stable, so the rotation is retained among equally ranked databases.
+        indices.sort_by_key(|&index| {
+            let status = &statuses[index];
+            (
+                status.is_unavailable.load(Ordering::Relaxed),
+                status.effective_lag_ms(),
+            )
+        });
+        indices
+    }
+
+    /// Marks a balanced database as unavailable and spawns a health check that marks it as available again
+    /// once a connection can be acquired from it.
+    fn mark_unavailable(&self, index: usize) {
+        let status = &self.replicas.statuses[index];
+        if status.is_unavailable.swap(true, Ordering::Relaxed) {
+            return; // The health check is already running
+        }
+
+        let replicas = Arc::downgrade(&self.replicas);
+        let pool = self.balanced_pool(index).clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+                let Some(replicas) = replicas.upgrade() else {
+                    return; // The connection pool is dropped
+                };
+                match pool.acquire().await {
+                    Ok(_) => {
+                        tracing::info!("DB #{index} is available again");
+                        replicas.statuses[index]
+                            .is_unavailable
+                            .store(false, Ordering::Relaxed);
+                        return;
+                    }
+                    Err(err) => tracing::debug!("DB #{index} is still unavailable: {err}"),
+                }
+            }
+        });
+    }
+
+    /// Spawns a background check of replication lag of the balanced databases if the last check is stale.
+    fn check_replication_lag_if_stale(&self) {
+        {
+            let mut last_check = self.replicas.last_lag_check.lock().unwrap();
+            if last_check.map_or(false, |at| at.elapsed() < LAG_CHECK_INTERVAL) {
+                return;
+            }
+            *last_check = Some(Instant::now());
+        }
+
+        let replicas = Arc::downgrade(&self.replicas);
+        let pools: Vec<_> = iter::once(&self.inner)
+            .chain(&self.replicas.pools)
+            .cloned()
+            .collect();
+        tokio::spawn(Self::check_replication_lag(replicas, pools));
+    }
+
+    /// Checks replication lag of the balanced databases. The lag is measured on each replica as the time
+    /// since the last replayed transaction was committed on the master, so that WAL positions
+    /// of different servers are never compared. A replica that has replayed all received WAL is considered
+    /// up to date, since otherwise an idle master would make replicas look increasingly lagging.
+    async fn check_replication_lag(replicas: Weak<ReplicaPools>, pools: Vec<PgPool>) {
+        for (index, pool) in pools.iter().enumerate() {
+            let Some(replicas) = replicas.upgrade() else {
+                return; // The connection pool is dropped
+            };
+            let status = &replicas.statuses[index];
+            if status.is_unavailable.load(Ordering::Relaxed) {
+                continue;
+            }
+            let lag_ms = sqlx::query_scalar::<_, Option<i64>>(
+                "SELECT CASE \
+                 WHEN NOT pg_is_in_recovery() THEN 0 \
+                 WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0 \
+                 ELSE (EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp()) * 1000)::BIGINT \
+                 END",
+            )
+            .fetch_one(pool)
+            .await;
+            match lag_ms {
+                Ok(Some(lag_ms)) => {
+                    status
+                        .replication_lag_ms
+                        .store(lag_ms.max(0) as u64, Ordering::Relaxed);
+                }
+                Ok(None) => { /* Replication lag is unknown; keep the last known value */ }
+                Err(err) => {
+                    tracing::debug!("Failed checking replication lag for DB #{index}: {err}");
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use assert_matches::assert_matches;
 
     use super::*;
@@ -451,4 +857,178 @@ mod tests {
             sqlx::Error::Database(db_err) if db_err.message().contains("statement timeout")
         );
     }
+
+    #[tokio::test]
+    async fn setting_schema() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db(1)
+            .await
+            .unwrap()
+            .database_url;
+
+        let pool = ConnectionPool::singleton(&db_url).build().await.unwrap();
+        let mut storage = pool.access_storage().await.unwrap();
+        sqlx::query("CREATE SCHEMA chain_2")
+            .execute(storage.conn())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let pool = ConnectionPool::singleton(&db_url)
+            .set_schema(Some("chain_2"))
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(pool.schema(), Some("chain_2"));
+        let mut storage = pool.access_storage().await.unwrap();
+        let schema: String = sqlx::query_scalar("SELECT current_schema()")
+            .fetch_one(storage.conn())
+            .await
+            .unwrap();
+        assert_eq!(schema, "chain_2");
+
+        let err = ConnectionPool::singleton(&db_url)
+            .set_schema(Some("chain_2 -c statement_timeout=0"))
+            .build()
+            .await
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Invalid Postgres schema"),
+            "{err:#}"
+        );
+    }
+
+    #[tokio::test]
+    async fn sub_pool_reserves_connections() {
+        let mut pool = ConnectionPool::constrained_test_pool(3).await;
+        pool.acquire_timeout = Duration::from_millis(100);
+        let api_pool = pool.sub_pool(PoolComponent::Api, 1);
+
+        let api_connection = api_pool.access_storage().await.unwrap();
+        let err = api_pool.access_storage().await.unwrap_err();
+        assert!(format!("{err:#}").contains("Run out of retries"), "{err:#}");
+        // Connections of the sub-pool are reserved, so the parent pool can still provide all its connections.
+        let mut connections = vec![];
+        for _ in 0..3 {
+            connections.push(pool.access_storage().await.unwrap());
+        }
+        assert_matches!(pool.access_storage().await, Err(_));
+
+        drop(api_connection);
+        let mut api_connection = api_pool.access_storage().await.unwrap();
+        sqlx::query("SELECT 1")
+            .execute(api_connection.conn())
+            .await
+            .unwrap();
+        let mut transaction = api_connection.start_transaction().await.unwrap();
+        sqlx::query("SELECT 1")
+            .execute(transaction.conn())
+            .await
+            .unwrap();
+    }
+
+    const UNAVAILABLE_DB_URL: &str = "postgres://postgres@localhost:1/unavailable";
+
+    #[tokio::test]
+    async fn unavailable_replica_is_skipped() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db(2)
+            .await
+            .unwrap()
+            .database_url;
+
+        let pool = ConnectionPool::builder(&db_url, 2)
+            .set_acquire_timeout(Some(Duration::from_secs(1)))
+            .add_replica_urls([UNAVAILABLE_DB_URL])
+            .build()
+            .await
+            .unwrap();
+        // Make the main DB look lagging, so that the replica is tried first.
+        pool.replicas.statuses[0]
+            .replication_lag_ms
+            .store(u64::MAX, Ordering::Relaxed);
+
+        // Connections should be acquired from the main DB regardless of the replica order.
+        for _ in 0..3 {
+            let mut storage = pool.access_storage().await.unwrap();
+            sqlx::query("SELECT 1")
+                .execute(storage.conn())
+                .await
+                .unwrap();
+        }
+        // The replica should be marked as unavailable and ordered last.
+        assert!(pool.replicas.statuses[1]
+            .is_unavailable
+            .load(Ordering::Relaxed));
+        assert_eq!(pool.balanced_db_order(), [0, 1]);
+    }
+
+    #[tokio::test]
+    async fn least_lagging_db_is_preferred() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db(2)
+            .await
+            .unwrap()
+            .database_url;
+
+        let pool = ConnectionPool::builder(&db_url, 2)
+            .add_replica_urls([db_url.as_str(), db_url.as_str()])
+            .build()
+            .await
+            .unwrap();
+        let statuses = &pool.replicas.statuses;
+        // Replicas are considered lagging until checked.
+        assert_eq!(pool.balanced_db_order()[0], 0);
+
+        statuses[0]
+            .replication_lag_ms
+            .store(5_000, Ordering::Relaxed);
+        statuses[1]
+            .replication_lag_ms
+            .store(1_000, Ordering::Relaxed);
+        statuses[2]
+            .replication_lag_ms
+            .store(3_000, Ordering::Relaxed);
+        for _ in 0..3 {
+            assert_eq!(pool.balanced_db_order(), [1, 2, 0]);
+        }
+
+        // Databases with an acceptable lag should be rotated.
+        statuses[1].replication_lag_ms.store(0, Ordering::Relaxed);
+        statuses[2].replication_lag_ms.store(100, Ordering::Relaxed);
+        let first_indices: HashSet<_> = (0..2).map(|_| pool.balanced_db_order()[0]).collect();
+        assert_eq!(first_indices, HashSet::from([1, 2]));
+
+        statuses[1].is_unavailable.store(true, Ordering::Relaxed);
+        assert_eq!(pool.balanced_db_order(), [2, 0, 1]);
+    }
+
+    #[tokio::test]
+    async fn failing_over_to_master() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db(2)
+            .await
+            .unwrap()
+            .database_url;
+
+        let pool = ConnectionPool::builder(&db_url, 2)
+            .set_acquire_timeout(Some(Duration::from_secs(1)))
+            .add_replica_urls([UNAVAILABLE_DB_URL])
+            .set_failover_url(Some(&format!("{db_url}?application_name=failover")))
+            .build()
+            .await
+            .unwrap();
+        // Make the main DB unavailable for the pool.
+        pool.inner.close().await;
+
+        let mut storage = pool.access_storage().await.unwrap();
+        sqlx::query("SELECT 1")
+            .execute(storage.conn())
+            .await
+            .unwrap();
+    }
 }
@@ -0,0 +1,243 @@
+//! Retries for DB transactions failing with transient errors.
+
+use std::{io, time::Duration};
+
+use futures::future::BoxFuture;
+use rand::Rng;
+
+use super::{ConnectionPool, StorageProcessor};
+use crate::metrics::REQUEST_METRICS;
+
+/// Postgres error code for serialization failures.
+const SERIALIZATION_FAILURE_CODE: &str = "40001";
+/// Postgres error code for detected deadlocks.
+const DEADLOCK_DETECTED_CODE: &str = "40P01";
+
+/// Policy for retrying DB transactions in [`ConnectionPool::transaction_with_retries()`].
+#[derive(Debug, Clone, Copy)]
+pub struct TransactionRetryPolicy {
+    /// Maximum number of attempts (including the initial one) to run a transaction.
+    pub max_attempts: usize,
+    /// Back-off interval after the first failed attempt. The interval is doubled after each subsequent failure.
+    pub initial_backoff: Duration,
+    /// Upper bound on the back-off interval.
+    pub max_backoff: Duration,
+}
+
+impl Default for TransactionRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl TransactionRetryPolicy {
+    /// Returns a randomized back-off interval after the specified failed attempt (1-based).
+    fn backoff(&self, attempt: usize) -> Duration {
+        let exponent = u32::try_from(attempt - 1).unwrap_or(u32::MAX).min(16);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+        // Slightly randomize back-off interval so that concurrent transactions don't retry in lockstep.
+        let jitter = rand::thread_rng().gen_range(0.8..1.2);
+        backoff.mul_f32(jitter)
+    }
+}
+
+/// Checks whether the error is transient, i.e., the failed transaction can be safely retried.
+/// Must only be applied to errors occurring before the transaction is committed; see [`is_commit_rejection()`].
+pub(crate) fn is_transient_error(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(db_err) => matches!(
+            db_err.code().as_deref(),
+            Some(SERIALIZATION_FAILURE_CODE | DEADLOCK_DETECTED_CODE)
+        ),
+        sqlx::Error::Io(err) => matches!(
+            err.kind(),
+            io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::BrokenPipe
+                | io::ErrorKind::UnexpectedEof
+        ),
+        sqlx::Error::PoolTimedOut => true,
+        _ => false,
+    }
+}
+
+/// Checks whether a `COMMIT` error is a definite rejection by Postgres, after which the transaction is rolled back.
+/// Other errors (most importantly, I/O errors) leave the transaction outcome unknown: the commit may have been applied
+/// before the connection broke, so retrying the transaction could apply it twice.
+pub(crate) fn is_commit_rejection(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(db_err) => matches!(
+            db_err.code().as_deref(),
+            Some(SERIALIZATION_FAILURE_CODE | DEADLOCK_DETECTED_CODE)
+        ),
+        _ => false,
+    }
+}
+
+/// Finds the underlying sqlx error in the error chain, if any.
+fn as_sqlx_error(err: &anyhow::Error) -> Option<&sqlx::Error> {
+    err.chain()
+        .find_map(|err| err.downcast_ref::<sqlx::Error>())
+}
+
+impl ConnectionPool {
+    /// Runs `action` in a DB transaction and commits it. If the transaction fails with a transient error
+    /// (a serialization failure, deadlock or a broken connection), it is rolled back and retried
+    /// on a new connection with exponential back-off according to `policy`. Other errors are returned immediately.
+    /// Errors returned by `action` are classified based on the [`sqlx::Error`] in their chain, if any.
+    ///
+    /// Errors when committing the transaction are only retried if Postgres has definitely rejected the commit
+    /// (i.e., on serialization failures and deadlocks). If the connection breaks during the commit, the transaction
+    /// may have been applied, so the error is returned without retries.
+    ///
+    /// Since `action` may be called multiple times, it must not have side effects outside the transaction.
+    /// `name` is used for logging and metrics.
+    pub async fn transaction_with_retries<T, F>(
+        &self,
+        name: &'static str,
+        policy: &TransactionRetryPolicy,
+        mut action: F,
+    ) -> anyhow::Result<T>
+    where
+        F: for<'t, 'c> FnMut(&'t mut StorageProcessor<'c>) -> BoxFuture<'t, anyhow::Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let mut storage = self.access_storage_tagged(name).await?;
+            let mut is_committing = false;
+            let result = async {
+                let mut transaction = storage.start_transaction().await?;
+                let output = action(&mut transaction).await?;
+                is_committing = true;
+                transaction.commit().await?;
+                anyhow::Ok(output)
+            }
+            .await;
+
+            let err = match result {
+                Ok(output) => return Ok(output),
+                Err(err) => err,
+            };
+            let is_retriable = as_sqlx_error(&err).map_or(false, |sqlx_err| {
+                if is_committing {
+                    is_commit_rejection(sqlx_err)
+                } else {
+                    is_transient_error(sqlx_err)
+                }
+            });
+            if attempt >= policy.max_attempts || !is_retriable {
+                return Err(err.context(format!(
+                    "transaction `{name}` failed after {attempt} attempt(s)"
+                )));
+            }
+
+            drop(storage);
+            let backoff = policy.backoff(attempt);
+            tracing::warn!(
+                "Transaction `{name}` failed with a transient error on attempt #{attempt}, \
+                 retrying in {backoff:?}: {err:#}"
+            );
+            REQUEST_METRICS.transaction_retry[&name].inc();
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    const TEST_POLICY: TransactionRetryPolicy = TransactionRetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(10),
+    };
+
+    #[test]
+    fn backoff_is_bounded() {
+        for attempt in 1..100 {
+            let backoff = TEST_POLICY.backoff(attempt);
+            assert!(
+                backoff <= TEST_POLICY.max_backoff.mul_f32(1.2),
+                "{backoff:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn classifying_errors() {
+        let io_err = io::Error::from(io::ErrorKind::ConnectionReset);
+        assert!(is_transient_error(&sqlx::Error::Io(io_err)));
+        assert!(is_transient_error(&sqlx::Error::PoolTimedOut));
+        assert!(!is_transient_error(&sqlx::Error::RowNotFound));
+        assert!(!is_transient_error(&sqlx::Error::PoolClosed));
+
+        let io_err = io::Error::from(io::ErrorKind::ConnectionReset);
+        assert!(!is_commit_rejection(&sqlx::Error::Io(io_err)));
+        assert!(!is_commit_rejection(&sqlx::Error::PoolTimedOut));
+
+        let err = anyhow::Error::new(sqlx::Error::PoolTimedOut).context("failed");
+        assert_matches!(as_sqlx_error(&err), Some(sqlx::Error::PoolTimedOut));
+        assert!(as_sqlx_error(&anyhow::anyhow!("failed")).is_none());
+    }
+
+    #[tokio::test]
+    async fn retrying_transient_errors() {
+        let pool = ConnectionPool::test_pool().await;
+        let attempts = AtomicUsize::new(0);
+        let output = pool
+            .transaction_with_retries("test", &TEST_POLICY, |transaction| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move {
+                    sqlx::query("SELECT 1").execute(transaction.conn()).await?;
+                    if attempt == 0 {
+                        let io_err = io::Error::from(io::ErrorKind::ConnectionReset);
+                        return Err(anyhow::Error::new(sqlx::Error::Io(io_err)).context("query"));
+                    }
+                    Ok(attempt)
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(output, 1);
+        assert_eq!(attempts.into_inner(), 2);
+    }
+
+    #[tokio::test]
+    async fn not_retrying_other_errors() {
+        let pool = ConnectionPool::test_pool().await;
+        let attempts = AtomicUsize::new(0);
+        pool.transaction_with_retries("test", &TEST_POLICY, |_| {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Err::<(), _>(sqlx::Error::RowNotFound.into()) })
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(attempts.into_inner(), 1);
+    }
+
+    #[tokio::test]
+    async fn running_out_of_attempts() {
+        let pool = ConnectionPool::test_pool().await;
+        let attempts = AtomicUsize::new(0);
+        pool.transaction_with_retries("test", &TEST_POLICY, |_| {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Err::<(), _>(sqlx::Error::PoolTimedOut.into()) })
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(attempts.into_inner(), TEST_POLICY.max_attempts);
+    }
+}
@@ -4,7 +4,7 @@ use zksync_consensus_storage::ReplicaState;
 use zksync_types::MiniblockNumber;
 
 pub use crate::models::consensus::Payload;
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 /// Storage access methods for `zksync_core::consensus` module.
 #[derive(Debug)]
@@ -25,7 +25,8 @@ impl ConsensusDal<'_, '_> {
                 fake_key
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("genesis")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -70,14 +71,16 @@ impl ConsensusDal<'_, '_> {
             DELETE FROM miniblocks_consensus
             "#
         )
-        .execute(txn.conn())
+        .instrument("try_update_genesis#delete_miniblocks_consensus")
+        .execute(&mut txn)
         .await?;
         sqlx::query!(
             r#"
             DELETE FROM consensus_replica_state
             "#
         )
-        .execute(txn.conn())
+        .instrument("try_update_genesis#delete_consensus_replica_state")
+        .execute(&mut txn)
         .await?;
         sqlx::query!(
             r#"
@@ -89,7 +92,8 @@ impl ConsensusDal<'_, '_> {
             genesis,
             state,
         )
-        .execute(txn.conn())
+        .instrument("try_update_genesis#insert_consensus_replica_state")
+        .execute(&mut txn)
         .await?;
         txn.commit().await?;
         Ok(())
@@ -135,7 +139,8 @@ impl ConsensusDal<'_, '_> {
                 fake_key
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("replica_state")
+        .fetch_one(self.storage)
         .await?;
         Ok(zksync_protobuf::serde::deserialize(row.state)?)
     }
@@ -154,7 +159,8 @@ impl ConsensusDal<'_, '_> {
             "#,
             state
         )
-        .execute(self.storage.conn())
+        .instrument("set_replica_state")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -175,7 +181,8 @@ impl ConsensusDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("last_certificate")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -199,7 +206,8 @@ impl ConsensusDal<'_, '_> {
             "#,
             i64::try_from(block_number.0)?
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("certificate")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -272,7 +280,8 @@ impl ConsensusDal<'_, '_> {
             header.number.0 as i64,
             zksync_protobuf::serde::serialize(cert, serde_json::value::Serializer).unwrap(),
         )
-        .execute(txn.conn())
+        .instrument("insert_certificate")
+        .execute(&mut txn)
         .await?;
         txn.commit().await?;
         Ok(())
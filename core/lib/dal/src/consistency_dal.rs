@@ -0,0 +1,379 @@
+//! Consistency checks recomputing aggregated L1 batch data from raw rows and comparing it to stored columns.
+
+use std::collections::HashMap;
+
+use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_types::{
+    event::logs_bloom,
+    l2_to_l1_log::{l2_to_l1_logs_tree_size, L2ToL1Log, UserL2ToL1Log},
+    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, VmEvent, H2048, H256,
+};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Discrepancy between a stored aggregate and the value recomputed from raw rows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inconsistency {
+    /// Transaction counts for an L1 batch differ from the number of transactions included into it.
+    TxCounts {
+        l1_batch_number: L1BatchNumber,
+        /// Stored L1 and L2 transaction counts.
+        stored: (u32, u32),
+        /// L1 and L2 transaction counts recomputed from the `transactions` table.
+        recomputed: (u32, u32),
+    },
+    /// Logs bloom for a miniblock differs from the bloom recomputed from its events.
+    LogsBloom {
+        miniblock_number: MiniblockNumber,
+        stored: H2048,
+        recomputed: H2048,
+    },
+    /// Merkle root of L2-to-L1 logs for an L1 batch differs from the root recomputed from the `l2_to_l1_logs` table.
+    L2ToL1LogsRoot {
+        l1_batch_number: L1BatchNumber,
+        stored: H256,
+        recomputed: H256,
+    },
+}
+
+#[derive(Debug)]
+pub struct ConsistencyDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ConsistencyDal<'_, '_> {
+    /// Returns the last L1 batch that has all derived data (including the commitment) generated,
+    /// i.e., the last L1 batch for which all checks are meaningful.
+    pub async fn get_last_checkable_l1_batch(&mut self) -> sqlx::Result<Option<L1BatchNumber>> {
+        let next_for_commitment = self
+            .storage
+            .blocks_dal()
+            .get_next_l1_batch_ready_for_commitment_generation()
+            .await?;
+        if let Some(next_for_commitment) = next_for_commitment {
+            return Ok(next_for_commitment.0.checked_sub(1).map(L1BatchNumber));
+        }
+        self.storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await
+    }
+
+    /// Checks derived columns of the specified sealed L1 batch. Returns `None` if the batch is not sealed;
+    /// otherwise, returns all found inconsistencies (i.e., an empty vector if the batch is consistent).
+    ///
+    /// Checks are only meaningful for L1 batches that are not pruned or archived (e.g., events of pruned batches
+    /// are removed, so their logs blooms cannot be recomputed). The L2-to-L1 logs Merkle root is only checked
+    /// if the batch commitment is generated.
+    pub async fn check_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<Vec<Inconsistency>>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                l1_tx_count,
+                l2_tx_count,
+                l2_l1_merkle_root,
+                protocol_version,
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        transactions
+                    WHERE
+                        l1_batch_number = $1
+                        AND priority_op_id IS NOT NULL
+                ) AS "recomputed_l1_tx_count!",
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        transactions
+                    WHERE
+                        l1_batch_number = $1
+                        AND priority_op_id IS NULL
+                ) AS "recomputed_l2_tx_count!"
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("check_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let mut inconsistencies = vec![];
+        let stored = (row.l1_tx_count as u32, row.l2_tx_count as u32);
+        let recomputed = (
+            row.recomputed_l1_tx_count as u32,
+            row.recomputed_l2_tx_count as u32,
+        );
+        if stored != recomputed {
+            inconsistencies.push(Inconsistency::TxCounts {
+                l1_batch_number,
+                stored,
+                recomputed,
+            });
+        }
+
+        self.check_logs_blooms(l1_batch_number, &mut inconsistencies)
+            .await?;
+
+        // The root for the genesis batch is set to zero rather than computed.
+        let stored_root = row
+            .l2_l1_merkle_root
+            .filter(|_| l1_batch_number > L1BatchNumber(0));
+        if let (Some(stored_root), Some(protocol_version)) = (stored_root, row.protocol_version) {
+            let protocol_version = ProtocolVersionId::try_from(protocol_version as u16)?;
+            let stored = H256::from_slice(&stored_root);
+            let recomputed = self
+                .recompute_l2_to_l1_logs_root(l1_batch_number, protocol_version)
+                .await?;
+            if stored != recomputed {
+                inconsistencies.push(Inconsistency::L2ToL1LogsRoot {
+                    l1_batch_number,
+                    stored,
+                    recomputed,
+                });
+            }
+        }
+        Ok(Some(inconsistencies))
+    }
+
+    async fn check_logs_blooms(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        inconsistencies: &mut Vec<Inconsistency>,
+    ) -> sqlx::Result<()> {
+        let miniblock_rows = sqlx::query!(
+            r#"
+            SELECT
+                number,
+                logs_bloom
+            FROM
+                miniblocks
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                number
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("check_logs_blooms#miniblocks")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+        let (Some(first_row), Some(last_row)) = (miniblock_rows.first(), miniblock_rows.last())
+        else {
+            return Ok(());
+        };
+
+        let event_rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number,
+                address,
+                topic1,
+                topic2,
+                topic3,
+                topic4
+            FROM
+                events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            first_row.number,
+            last_row.number
+        )
+        .instrument("check_logs_blooms#events")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut events_by_miniblock = HashMap::<i64, Vec<VmEvent>>::new();
+        for row in event_rows {
+            let topics = [row.topic1, row.topic2, row.topic3, row.topic4];
+            // Missing topics are stored as empty byte sequences.
+            let indexed_topics = topics
+                .iter()
+                .filter(|topic| !topic.is_empty())
+                .map(|topic| H256::from_slice(topic))
+                .collect();
+            let event = VmEvent {
+                location: (l1_batch_number, 0),
+                address: Address::from_slice(&row.address),
+                indexed_topics,
+                value: vec![],
+            };
+            events_by_miniblock
+                .entry(row.miniblock_number)
+                .or_default()
+                .push(event);
+        }
+
+        for row in miniblock_rows {
+            // Blooms are not stored for miniblocks sealed before they were introduced.
+            let Some(stored) = row.logs_bloom else {
+                continue;
+            };
+            let stored = H2048::from_slice(&stored);
+            let events = events_by_miniblock.remove(&row.number).unwrap_or_default();
+            let recomputed = logs_bloom(&events);
+            if stored != recomputed {
+                inconsistencies.push(Inconsistency::LogsBloom {
+                    miniblock_number: MiniblockNumber(row.number as u32),
+                    stored,
+                    recomputed,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    async fn recompute_l2_to_l1_logs_root(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        protocol_version: ProtocolVersionId,
+    ) -> sqlx::Result<H256> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                shard_id,
+                is_service,
+                tx_index_in_l1_batch,
+                sender,
+                key,
+                value
+            FROM
+                l2_to_l1_logs
+                JOIN miniblocks ON miniblocks.number = l2_to_l1_logs.miniblock_number
+            WHERE
+                miniblocks.l1_batch_number = $1
+            ORDER BY
+                l2_to_l1_logs.miniblock_number,
+                l2_to_l1_logs.log_index_in_miniblock
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("recompute_l2_to_l1_logs_root")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let leaves = rows.into_iter().map(|row| {
+            let log = UserL2ToL1Log(L2ToL1Log {
+                shard_id: row.shard_id as u8,
+                is_service: row.is_service,
+                tx_number_in_block: row.tx_index_in_l1_batch as u16,
+                sender: Address::from_slice(&row.sender),
+                key: H256::from_slice(&row.key),
+                value: H256::from_slice(&row.value),
+            });
+            log.0.to_bytes()
+        });
+        let tree_size = l2_to_l1_logs_tree_size(protocol_version);
+        Ok(MiniMerkleTree::new(leaves, Some(tree_size)).merkle_root())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{block::L1BatchHeader, tx::IncludedTxLocation, ProtocolVersion};
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    #[tokio::test]
+    async fn checking_l1_batch_consistency() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(1),
+            indexed_topics: vec![H256::repeat_byte(2)],
+            value: vec![3],
+        };
+        let tx_location = IncludedTxLocation {
+            tx_hash: H256::repeat_byte(1),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::repeat_byte(2),
+        };
+        conn.events_dal()
+            .save_events(MiniblockNumber(1), &[(tx_location, vec![&event])])
+            .await
+            .unwrap();
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            1,
+            Default::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+
+        let inconsistencies = conn
+            .consistency_dal()
+            .check_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(inconsistencies, Some(vec![]));
+        let inconsistencies = conn
+            .consistency_dal()
+            .check_l1_batch(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(inconsistencies, None);
+
+        sqlx::query("UPDATE l1_batches SET l2_tx_count = 5 WHERE number = 1")
+            .execute(conn.conn())
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM events WHERE miniblock_number = 1")
+            .execute(conn.conn())
+            .await
+            .unwrap();
+        let inconsistencies = conn
+            .consistency_dal()
+            .check_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(inconsistencies.len(), 2, "{inconsistencies:?}");
+        assert_eq!(
+            inconsistencies[0],
+            Inconsistency::TxCounts {
+                l1_batch_number: L1BatchNumber(1),
+                stored: (0, 5),
+                recomputed: (0, 0),
+            }
+        );
+        assert_eq!(
+            inconsistencies[1],
+            Inconsistency::LogsBloom {
+                miniblock_number: MiniblockNumber(1),
+                stored: logs_bloom([&event]),
+                recomputed: H2048::zero(),
+            }
+        );
+    }
+}
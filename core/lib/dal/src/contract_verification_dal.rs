@@ -4,6 +4,7 @@ use std::{
 };
 
 use anyhow::Context as _;
+use futures::future::BoxFuture;
 use sqlx::postgres::types::PgInterval;
 use zksync_types::{
     contract_verification_api::{
@@ -13,7 +14,13 @@ use zksync_types::{
     get_code_key, Address, CONTRACT_DEPLOYER_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH,
 };
 
-use crate::{models::storage_verification_request::StorageVerificationRequest, StorageProcessor};
+use crate::{
+    fri_prover_dal::types::StuckJobs,
+    instrument::InstrumentExt,
+    job_queue_dal::{JobQueueParams, QueuedJob},
+    models::storage_verification_request::StorageVerificationRequest,
+    StorageProcessor,
+};
 
 #[derive(Debug)]
 pub struct ContractVerificationDal<'a, 'c> {
@@ -39,6 +46,117 @@ impl Display for Compiler {
     }
 }
 
+impl QueuedJob for StorageVerificationRequest {
+    type PickParams = JobQueueParams;
+
+    fn pick_next<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        params: &'a JobQueueParams,
+    ) -> BoxFuture<'a, sqlx::Result<Option<Self>>> {
+        let processing_timeout = params.pg_processing_timeout();
+        let max_attempts = params.max_attempts_or_zero() as i32;
+        Box::pin(async move {
+            sqlx::query_as!(
+                StorageVerificationRequest,
+                r#"
+                UPDATE contract_verification_requests
+                SET
+                    status = 'in_progress',
+                    attempts = attempts + 1,
+                    updated_at = NOW(),
+                    processing_started_at = NOW()
+                WHERE
+                    id = (
+                        SELECT
+                            id
+                        FROM
+                            contract_verification_requests
+                        WHERE
+                            status = 'queued'
+                            OR (
+                                status = 'in_progress'
+                                AND processing_started_at < NOW() - $1::INTERVAL
+                            )
+                            OR (
+                                status = 'failed'
+                                AND attempts < $2
+                            )
+                        ORDER BY
+                            created_at
+                        LIMIT
+                            1
+                        FOR UPDATE
+                            SKIP LOCKED
+                    )
+                RETURNING
+                    id,
+                    contract_address,
+                    source_code,
+                    contract_name,
+                    zk_compiler_version,
+                    compiler_version,
+                    optimization_used,
+                    optimizer_mode,
+                    constructor_arguments,
+                    is_system,
+                    force_evmla
+                "#,
+                &processing_timeout,
+                max_attempts,
+            )
+            .instrument("get_next_queued_verification_request")
+            .fetch_optional(storage)
+            .await
+        })
+    }
+
+    fn requeue_stuck<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        processing_timeout: PgInterval,
+        max_attempts: u32,
+    ) -> BoxFuture<'a, sqlx::Result<Vec<StuckJobs>>> {
+        Box::pin(async move {
+            let rows = sqlx::query!(
+                r#"
+                UPDATE contract_verification_requests
+                SET
+                    status = 'queued',
+                    updated_at = NOW(),
+                    processing_started_at = NOW()
+                WHERE
+                    (
+                        status = 'in_progress'
+                        AND processing_started_at <= NOW() - $1::INTERVAL
+                        AND attempts < $2
+                    )
+                    OR (
+                        status = 'failed'
+                        AND attempts < $2
+                    )
+                RETURNING
+                    id,
+                    status,
+                    attempts
+                "#,
+                &processing_timeout,
+                max_attempts as i32,
+            )
+            .instrument("requeue_stuck_verification_requests")
+            .fetch_all(storage)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| StuckJobs {
+                    id: row.id as u64,
+                    status: row.status,
+                    attempts: row.attempts as u64,
+                })
+                .collect())
+        })
+    }
+}
+
 impl ContractVerificationDal<'_, '_> {
     pub async fn get_count_of_queued_verification_requests(&mut self) -> sqlx::Result<usize> {
         sqlx::query!(
@@ -51,7 +169,8 @@ impl ContractVerificationDal<'_, '_> {
                 status = 'queued'
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_count_of_queued_verification_requests")
+        .fetch_one(self.storage)
         .await
         .map(|row| row.count as usize)
     }
@@ -95,7 +214,8 @@ impl ContractVerificationDal<'_, '_> {
             query.is_system,
             query.force_evmla,
         )
-        .fetch_one(self.storage.conn())
+        .instrument("add_contract_verification_request")
+        .fetch_one(self.storage)
         .await
         .map(|row| row.id as usize)
     }
@@ -108,58 +228,13 @@ impl ContractVerificationDal<'_, '_> {
         &mut self,
         processing_timeout: Duration,
     ) -> sqlx::Result<Option<VerificationRequest>> {
-        let processing_timeout = PgInterval {
-            months: 0,
-            days: 0,
-            microseconds: processing_timeout.as_micros() as i64,
-        };
-        let result = sqlx::query_as!(
-            StorageVerificationRequest,
-            r#"
-            UPDATE contract_verification_requests
-            SET
-                status = 'in_progress',
-                attempts = attempts + 1,
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                id = (
-                    SELECT
-                        id
-                    FROM
-                        contract_verification_requests
-                    WHERE
-                        status = 'queued'
-                        OR (
-                            status = 'in_progress'
-                            AND processing_started_at < NOW() - $1::INTERVAL
-                        )
-                    ORDER BY
-                        created_at
-                    LIMIT
-                        1
-                    FOR UPDATE
-                        SKIP LOCKED
-                )
-            RETURNING
-                id,
-                contract_address,
-                source_code,
-                contract_name,
-                zk_compiler_version,
-                compiler_version,
-                optimization_used,
-                optimizer_mode,
-                constructor_arguments,
-                is_system,
-                force_evmla
-            "#,
-            &processing_timeout
-        )
-        .fetch_optional(self.storage.conn())
-        .await?
-        .map(Into::into);
-        Ok(result)
+        let params = JobQueueParams::new(processing_timeout);
+        let request = self
+            .storage
+            .job_queue_dal::<StorageVerificationRequest>()
+            .pick_next_job(&params)
+            .await?;
+        Ok(request.map(Into::into))
     }
 
     /// Updates the verification request status and inserts the verification info upon successful verification.
@@ -184,7 +259,8 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             verification_info.request.id as i64,
         )
-        .execute(transaction.conn())
+        .instrument("save_verification_info#update_contract_verification_requests")
+        .execute(&mut transaction)
         .await?;
 
         let address = verification_info.request.req.contract_address;
@@ -205,7 +281,8 @@ impl ContractVerificationDal<'_, '_> {
             address.as_bytes(),
             &verification_info_json
         )
-        .execute(transaction.conn())
+        .instrument("save_verification_info#insert_contracts_verification_info")
+        .execute(&mut transaction)
         .await?;
 
         transaction.commit().await.context("commit()")?;
@@ -236,7 +313,8 @@ impl ContractVerificationDal<'_, '_> {
             &compilation_errors,
             panic_message
         )
-        .execute(self.storage.conn())
+        .instrument("save_verification_error")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -258,7 +336,8 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             id as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_verification_request_status")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -315,7 +394,8 @@ impl ContractVerificationDal<'_, '_> {
             hashed_key.as_bytes(),
             FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_contract_info_for_verification")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -350,7 +430,8 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             address.as_bytes()
         )
-        .fetch_one(self.storage.conn())
+        .instrument("is_contract_verified")
+        .fetch_one(self.storage)
         .await?
         .count;
         Ok(count > 0)
@@ -371,7 +452,8 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             &compiler
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_compiler_versions")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| row.version)
@@ -415,7 +497,8 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             &compiler
         )
-        .execute(transaction.conn())
+        .instrument("set_compiler_versions#delete_compiler_versions")
+        .execute(&mut transaction)
         .await?;
 
         sqlx::query!(
@@ -434,7 +517,8 @@ impl ContractVerificationDal<'_, '_> {
             &versions,
             &compiler,
         )
-        .execute(transaction.conn())
+        .instrument("set_compiler_versions#insert_compiler_versions")
+        .execute(&mut transaction)
         .await?;
 
         transaction.commit().await.context("commit()")?;
@@ -482,7 +566,8 @@ impl ContractVerificationDal<'_, '_> {
                 id
             "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_all_successful_requests")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(Into::into)
@@ -505,7 +590,8 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             address.as_bytes(),
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_contract_verification_info")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
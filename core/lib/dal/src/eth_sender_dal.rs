@@ -9,6 +9,7 @@ use zksync_types::{
 };
 
 use crate::{
+    instrument::InstrumentExt,
     models::storage_eth_tx::{
         L1BatchEthSenderStats, StorageEthTx, StorageTxHistory, StorageTxHistoryToSend,
     },
@@ -43,7 +44,8 @@ impl EthSenderDal<'_, '_> {
                 id
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_inflight_txs")
+        .fetch_all(self.storage)
         .await?;
         Ok(txs.into_iter().map(|tx| tx.into()).collect())
     }
@@ -87,7 +89,12 @@ impl EthSenderDal<'_, '_> {
                         ),
                     }
                 );
-                tx_rows.extend(query.fetch_all(self.storage.conn()).await?);
+                tx_rows.extend(
+                    query
+                        .instrument("get_eth_l1_batches")
+                        .fetch_all(self.storage)
+                        .await?,
+                );
             }
 
             for row in tx_rows {
@@ -115,7 +122,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_tx_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_eth_tx")
+        .fetch_optional(self.storage)
         .await?
         .map(Into::into))
     }
@@ -142,7 +150,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             limit as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_new_eth_txs")
+        .fetch_all(self.storage)
         .await?;
         Ok(txs.into_iter().map(|tx| tx.into()).collect())
     }
@@ -169,7 +178,8 @@ impl EthSenderDal<'_, '_> {
                 eth_txs_history.id DESC
             "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_unsent_txs")
+        .fetch_all(self.storage)
         .await?;
         Ok(txs.into_iter().map(|tx| tx.into()).collect())
     }
@@ -215,7 +225,8 @@ impl EthSenderDal<'_, '_> {
             blob_sidecar.map(|sidecar| bincode::serialize(&sidecar)
                 .expect("can always bincode serialize EthTxBlobSidecar; qed")),
         )
-        .fetch_one(self.storage.conn())
+        .instrument("save_eth_tx")
+        .fetch_one(self.storage)
         .await?;
         Ok(eth_tx.into())
     }
@@ -261,7 +272,8 @@ impl EthSenderDal<'_, '_> {
             raw_signed_tx,
             blob_base_fee_per_gas.map(|v| v as i64),
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("insert_tx_history")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.id as u32))
     }
@@ -284,7 +296,8 @@ impl EthSenderDal<'_, '_> {
             eth_txs_history_id as i32,
             sent_at_block as i32
         )
-        .execute(self.storage.conn())
+        .instrument("set_sent_at_block")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -298,7 +311,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_txs_history_id as i64
         )
-        .execute(self.storage.conn())
+        .instrument("remove_tx_history")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -326,7 +340,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             tx_hash,
         )
-        .fetch_one(transaction.conn())
+        .instrument("confirm_tx#update_eth_txs_history")
+        .fetch_one(&mut transaction)
         .await?;
 
         sqlx::query!(
@@ -342,7 +357,8 @@ impl EthSenderDal<'_, '_> {
             ids.id,
             ids.eth_tx_id
         )
-        .execute(transaction.conn())
+        .instrument("confirm_tx#update_eth_txs")
+        .execute(&mut transaction)
         .await?;
 
         transaction.commit().await?;
@@ -365,7 +381,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_tx_id as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_confirmed_tx_hash_by_eth_tx_id")
+        .fetch_optional(self.storage)
         .await?;
 
         let Some(tx_hash) = tx_hash else {
@@ -406,7 +423,8 @@ impl EthSenderDal<'_, '_> {
             WHERE eth_txs_history.tx_hash = $1",
             tx_hash
         )
-        .fetch_optional(transaction.conn())
+        .instrument("insert_bogus_confirmed_eth_tx#get_eth_tx_id")
+        .fetch_optional(&mut transaction)
         .await?;
 
         // Check if the transaction with the corresponding hash already exists.
@@ -422,7 +440,8 @@ impl EthSenderDal<'_, '_> {
                 RETURNING id",
                 tx_type.to_string()
             )
-            .fetch_one(transaction.conn())
+            .instrument("insert_bogus_confirmed_eth_tx#insert_eth_txs")
+            .fetch_one(&mut transaction)
             .await?;
 
             // Insert a "sent transaction".
@@ -435,7 +454,8 @@ impl EthSenderDal<'_, '_> {
                 tx_hash,
                 confirmed_at.naive_utc()
             )
-            .fetch_one(transaction.conn())
+            .instrument("insert_bogus_confirmed_eth_tx#insert_eth_txs_history")
+            .fetch_one(&mut transaction)
             .await?;
 
             // Mark general entry as confirmed.
@@ -450,7 +470,8 @@ impl EthSenderDal<'_, '_> {
                 eth_history_id,
                 eth_tx_id
             )
-            .execute(transaction.conn())
+            .instrument("insert_bogus_confirmed_eth_tx#update_eth_txs")
+            .execute(&mut transaction)
             .await?;
 
             eth_tx_id
@@ -487,7 +508,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_tx_id as i32
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_tx_history_to_check")
+        .fetch_all(self.storage)
         .await?;
         Ok(tx_history.into_iter().map(|tx| tx.into()).collect())
     }
@@ -500,7 +522,8 @@ impl EthSenderDal<'_, '_> {
             "SELECT sent_at_block FROM eth_txs_history WHERE eth_tx_id = $1 AND sent_at_block IS NOT NULL ORDER BY created_at ASC LIMIT 1",
             eth_tx_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_block_number_on_first_sent_attempt")
+        .fetch_optional(self.storage)
         .await?;
         Ok(sent_at_block.flatten().map(|block| block as u32))
     }
@@ -527,7 +550,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_tx_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_last_sent_eth_tx")
+        .fetch_optional(self.storage)
         .await?;
         Ok(history_item.map(|tx| tx.into()))
     }
@@ -561,7 +585,8 @@ impl EthSenderDal<'_, '_> {
         );
 
         let nonce = query
-            .fetch_optional(self.storage.conn())
+            .instrument("get_next_nonce")
+            .fetch_optional(self.storage)
             .await?
             .map(|row| row.nonce as u64);
         Ok(nonce.map(|n| n + 1))
@@ -578,7 +603,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_tx_id as i32
         )
-        .execute(self.storage.conn())
+        .instrument("mark_failed_transaction")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -594,7 +620,8 @@ impl EthSenderDal<'_, '_> {
                 has_failed = TRUE
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_number_of_failed_transactions")
+        .fetch_one(self.storage)
         .await?
         .count
         .context("count field is missing")
@@ -615,7 +642,8 @@ impl EthSenderDal<'_, '_> {
                 )
             "#
         )
-        .execute(self.storage.conn())
+        .instrument("clear_failed_transactions")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -656,7 +684,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             last_batch_to_keep.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("delete_eth_txs")
+        .execute(self.storage)
         .await?;
 
         Ok(())
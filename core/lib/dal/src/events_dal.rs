@@ -4,13 +4,14 @@ use sqlx::types::chrono::Utc;
 use zksync_system_constants::L1_MESSENGER_ADDRESS;
 use zksync_types::{
     api,
-    event::L1_MESSENGER_BYTECODE_PUBLICATION_EVENT_SIGNATURE,
+    event::{logs_bloom, L1_MESSENGER_BYTECODE_PUBLICATION_EVENT_SIGNATURE},
     l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
     tx::IncludedTxLocation,
     L1BatchNumber, MiniblockNumber, VmEvent, H256,
 };
 
 use crate::{
+    instrument::InstrumentExt,
     models::storage_event::{StorageL2ToL1Log, StorageWeb3Log},
     SqlxError, StorageProcessor,
 };
@@ -40,7 +41,7 @@ impl EventsDal<'_, '_> {
         &mut self,
         block_number: MiniblockNumber,
         all_block_events: &[(IncludedTxLocation, Vec<&VmEvent>)],
-    ) {
+    ) -> sqlx::Result<()> {
         let mut copy = self
             .storage
             .conn()
@@ -54,8 +55,7 @@ impl EventsDal<'_, '_> {
                 )
                 FROM STDIN WITH (DELIMITER '|')",
             )
-            .await
-            .unwrap();
+            .await?;
 
         let mut buffer = String::new();
         let now = Utc::now().naive_utc().to_string();
@@ -91,9 +91,30 @@ impl EventsDal<'_, '_> {
                 event_index_in_block += 1;
             }
         }
-        copy.send(buffer.as_bytes()).await.unwrap();
-        // note: all the time spent in this function is spent in `copy.finish()`
-        copy.finish().await.unwrap();
+        copy.send(buffer.as_bytes()).await?;
+        // note: most of the time spent in this function is spent in `copy.finish()`
+        copy.finish().await?;
+
+        let events = all_block_events
+            .iter()
+            .flat_map(|(_, events)| events.iter().copied());
+        let bloom = logs_bloom(events);
+        sqlx::query!(
+            r#"
+            UPDATE miniblocks
+            SET
+                logs_bloom = $1
+            WHERE
+                number = $2
+            "#,
+            bloom.as_bytes(),
+            block_number.0 as i64
+        )
+        .instrument("save_events#update_miniblocks")
+        .with_arg("block_number", &block_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
     }
 
     /// Removes events with a block number strictly greater than the specified `block_number`.
@@ -106,7 +127,8 @@ impl EventsDal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_events")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -117,7 +139,7 @@ impl EventsDal<'_, '_> {
         &mut self,
         block_number: MiniblockNumber,
         all_block_l2_to_l1_logs: &[(IncludedTxLocation, Vec<&UserL2ToL1Log>)],
-    ) {
+    ) -> sqlx::Result<()> {
         let mut copy = self
             .storage
             .conn()
@@ -130,8 +152,7 @@ impl EventsDal<'_, '_> {
                 )
                 FROM STDIN WITH (DELIMITER '|')",
             )
-            .await
-            .unwrap();
+            .await?;
 
         let mut buffer = String::new();
         let now = Utc::now().naive_utc().to_string();
@@ -169,8 +190,9 @@ impl EventsDal<'_, '_> {
                 log_index_in_miniblock += 1;
             }
         }
-        copy.send(buffer.as_bytes()).await.unwrap();
-        copy.finish().await.unwrap();
+        copy.send(buffer.as_bytes()).await?;
+        copy.finish().await?;
+        Ok(())
     }
 
     /// Removes all L2-to-L1 logs with a miniblock number strictly greater than the specified `block_number`.
@@ -183,7 +205,8 @@ impl EventsDal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_l2_to_l1_logs")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -223,7 +246,8 @@ impl EventsDal<'_, '_> {
             "#,
             &hashes[..],
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_logs_by_tx_hashes")
+        .fetch_all(self.storage)
         .await?;
 
         let mut result = HashMap::<H256, Vec<api::Log>>::new();
@@ -268,7 +292,8 @@ impl EventsDal<'_, '_> {
             L1_MESSENGER_ADDRESS.as_bytes(),
             L1_MESSENGER_BYTECODE_PUBLICATION_EVENT_SIGNATURE.as_bytes()
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_l1_batch_raw_published_bytecode_hashes")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| H256::from_slice(&row.value))
@@ -312,7 +337,8 @@ impl EventsDal<'_, '_> {
             "#,
             &hashes[..]
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_l2_to_l1_logs_by_hashes")
+        .fetch_all(self.storage)
         .await?;
 
         let mut result = HashMap::<H256, Vec<api::L2ToL1Log>>::new();
@@ -385,7 +411,8 @@ mod tests {
         ];
         conn.events_dal()
             .save_events(MiniblockNumber(1), &all_events)
-            .await;
+            .await
+            .unwrap();
 
         let logs = conn
             .events_web3_dal()
@@ -463,7 +490,8 @@ mod tests {
         ];
         conn.events_dal()
             .save_user_l2_to_l1_logs(MiniblockNumber(1), &all_logs)
-            .await;
+            .await
+            .unwrap();
 
         let logs = conn
             .events_dal()
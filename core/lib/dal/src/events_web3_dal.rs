@@ -4,7 +4,8 @@ use sqlx::{
     Postgres, Row,
 };
 use zksync_types::{
-    api::{GetLogsFilter, Log},
+    api::{GetLogsFilter, Log, LogCursor},
+    event::input_bloom,
     Address, MiniblockNumber, H256,
 };
 
@@ -39,7 +40,9 @@ impl EventsWeb3Dal<'_, '_> {
                 where_sql, arg_index
             );
 
-            let mut query = sqlx::query(&query);
+            let mut query = sqlx::query(&query)
+                .bind(filter.from_block.0 as i64)
+                .bind(filter.to_block.0 as i64);
 
             // Bind address params - noop if there are no addresses
             query = Self::bind_params_for_optional_filter_query(
@@ -56,7 +59,6 @@ impl EventsWeb3Dal<'_, '_> {
             query = query.bind(offset as i32);
             let log = query
                 .instrument("get_log_block_number")
-                .report_latency()
                 .with_arg("filter", filter)
                 .with_arg("offset", &offset)
                 .fetch_optional(self.storage)
@@ -66,70 +68,231 @@ impl EventsWeb3Dal<'_, '_> {
         }
     }
 
+    /// Narrows the block range of the `filter` using logs blooms of miniblocks, so that the range starts and ends
+    /// with miniblocks that may contain matching logs. Returns `None` if no miniblock in the range may contain
+    /// matching logs. Miniblocks without a bloom (i.e., sealed before blooms were persisted) are conservatively
+    /// considered matching.
+    ///
+    /// Addresses in the filter are checked as a single group; topics are checked by their groups
+    /// (i.e., a miniblock matches if its bloom contains any of the addresses and, for each topic group,
+    /// any topic from the group). Topic positions are not accounted for since they are not recorded in the bloom.
+    pub async fn get_bloom_matching_range(
+        &mut self,
+        filter: &GetLogsFilter,
+    ) -> Result<Option<(MiniblockNumber, MiniblockNumber)>, SqlxError> {
+        let address_masks = Self::bloom_masks(filter.addresses.iter().map(Address::as_bytes));
+        let mut topic_masks: [Vec<String>; 4] = Default::default();
+        // Topic groups beyond the 4th are ignored, which only makes the check more conservative.
+        for (masks, (_, topics)) in topic_masks.iter_mut().zip(&filter.topics) {
+            *masks = Self::bloom_masks(topics.iter().map(H256::as_bytes));
+        }
+        let [topic1_masks, topic2_masks, topic3_masks, topic4_masks] = topic_masks;
+
+        let row = sqlx::query!(
+            r#"
+            WITH
+                blooms AS (
+                    SELECT
+                        number,
+                        ('x' || ENCODE(logs_bloom, 'hex'))::BIT(2048) AS bloom
+                    FROM
+                        miniblocks
+                    WHERE
+                        number BETWEEN $1 AND $2
+                )
+            SELECT
+                MIN(number) AS "from_block?",
+                MAX(number) AS "to_block?"
+            FROM
+                blooms
+            WHERE
+                bloom IS NULL
+                OR (
+                    (
+                        CARDINALITY($3::TEXT[]) = 0
+                        OR EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                UNNEST($3::TEXT[]) AS mask
+                            WHERE
+                                bloom & ('x' || mask)::BIT(2048) = ('x' || mask)::BIT(2048)
+                        )
+                    )
+                    AND (
+                        CARDINALITY($4::TEXT[]) = 0
+                        OR EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                UNNEST($4::TEXT[]) AS mask
+                            WHERE
+                                bloom & ('x' || mask)::BIT(2048) = ('x' || mask)::BIT(2048)
+                        )
+                    )
+                    AND (
+                        CARDINALITY($5::TEXT[]) = 0
+                        OR EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                UNNEST($5::TEXT[]) AS mask
+                            WHERE
+                                bloom & ('x' || mask)::BIT(2048) = ('x' || mask)::BIT(2048)
+                        )
+                    )
+                    AND (
+                        CARDINALITY($6::TEXT[]) = 0
+                        OR EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                UNNEST($6::TEXT[]) AS mask
+                            WHERE
+                                bloom & ('x' || mask)::BIT(2048) = ('x' || mask)::BIT(2048)
+                        )
+                    )
+                    AND (
+                        CARDINALITY($7::TEXT[]) = 0
+                        OR EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                UNNEST($7::TEXT[]) AS mask
+                            WHERE
+                                bloom & ('x' || mask)::BIT(2048) = ('x' || mask)::BIT(2048)
+                        )
+                    )
+                )
+            "#,
+            filter.from_block.0 as i64,
+            filter.to_block.0 as i64,
+            &address_masks,
+            &topic1_masks,
+            &topic2_masks,
+            &topic3_masks,
+            &topic4_masks
+        )
+        .instrument("get_bloom_matching_range")
+        .with_arg("filter", filter)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row
+            .from_block
+            .zip(row.to_block)
+            .map(|(from, to)| (MiniblockNumber(from as u32), MiniblockNumber(to as u32))))
+    }
+
+    /// Returns hex-encoded bloom masks for the provided inputs.
+    fn bloom_masks<'a>(inputs: impl Iterator<Item = &'a [u8]>) -> Vec<String> {
+        inputs
+            .map(|input| hex::encode(input_bloom(input).as_bytes()))
+            .collect()
+    }
+
     /// Returns logs for given filter.
-    #[allow(clippy::type_complexity)]
     pub async fn get_logs(
         &mut self,
         filter: GetLogsFilter,
         limit: usize,
     ) -> Result<Vec<Log>, SqlxError> {
-        {
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
+        self.get_logs_inner(&filter, None, limit).await
+    }
 
-            let query = format!(
-                r#"
-                WITH events_select AS (
-                    SELECT
-                        address, topic1, topic2, topic3, topic4, value,
-                        miniblock_number, tx_hash, tx_index_in_block,
-                        event_index_in_block, event_index_in_tx
-                    FROM events
-                    WHERE {}
-                    ORDER BY miniblock_number ASC, event_index_in_block ASC
-                    LIMIT ${}
-                )
-                SELECT miniblocks.hash as "block_hash", miniblocks.l1_batch_number as "l1_batch_number", events_select.*
-                FROM events_select
-                LEFT JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
-                ORDER BY miniblock_number ASC, event_index_in_block ASC
-                "#,
-                where_sql, arg_index
-            );
+    /// Returns logs for given filter following the position specified by the cursor (if any).
+    /// Logs are ordered by their position in the chain; at most `limit` logs are returned.
+    pub async fn get_logs_page(
+        &mut self,
+        filter: &GetLogsFilter,
+        after: Option<LogCursor>,
+        limit: usize,
+    ) -> Result<Vec<Log>, SqlxError> {
+        if let Some(cursor) = &after {
+            // Since the cursor is not trusted, we check it to prevent potential overflow below.
+            if cursor.log_index > i32::MAX as u32 {
+                return Ok(vec![]);
+            }
+        }
+        self.get_logs_inner(filter, after, limit).await
+    }
 
-            let mut query = sqlx::query_as(&query);
+    async fn get_logs_inner(
+        &mut self,
+        filter: &GetLogsFilter,
+        after: Option<LogCursor>,
+        limit: usize,
+    ) -> Result<Vec<Log>, SqlxError> {
+        let (mut where_sql, mut arg_index) = self.build_get_logs_where_clause(filter);
+        if after.is_some() {
+            where_sql += &format!(
+                " AND ((miniblock_number, event_index_in_block) > (${}, ${}))",
+                arg_index,
+                arg_index + 1
+            );
+            arg_index += 2;
+        }
 
-            // Bind address params - noop if there are no addresses
+        let query = format!(
+            r#"
+            WITH events_select AS (
+                SELECT
+                    address, topic1, topic2, topic3, topic4, value,
+                    miniblock_number, tx_hash, tx_index_in_block,
+                    event_index_in_block, event_index_in_tx
+                FROM events
+                WHERE {}
+                ORDER BY miniblock_number ASC, event_index_in_block ASC
+                LIMIT ${}
+            )
+            SELECT miniblocks.hash as "block_hash", miniblocks.l1_batch_number as "l1_batch_number", events_select.*
+            FROM events_select
+            LEFT JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
+            ORDER BY miniblock_number ASC, event_index_in_block ASC
+            "#,
+            where_sql, arg_index
+        );
+
+        let mut query = sqlx::query_as(&query)
+            .bind(filter.from_block.0 as i64)
+            .bind(filter.to_block.0 as i64);
+
+        // Bind address params - noop if there are no addresses
+        query = Self::bind_params_for_optional_filter_query_as(
+            query,
+            filter.addresses.iter().map(Address::as_bytes).collect(),
+        );
+        for (_, topics) in &filter.topics {
+            // Bind topic params - noop if there are no topics
             query = Self::bind_params_for_optional_filter_query_as(
                 query,
-                filter.addresses.iter().map(Address::as_bytes).collect(),
+                topics.iter().map(H256::as_bytes).collect(),
             );
-            for (_, topics) in &filter.topics {
-                // Bind topic params - noop if there are no topics
-                query = Self::bind_params_for_optional_filter_query_as(
-                    query,
-                    topics.iter().map(H256::as_bytes).collect(),
-                );
-            }
-            query = query.bind(limit as i32);
-
-            let db_logs: Vec<StorageWeb3Log> = query
-                .instrument("get_logs")
-                .report_latency()
-                .with_arg("filter", &filter)
-                .with_arg("limit", &limit)
-                .fetch_all(self.storage)
-                .await?;
-            let logs = db_logs.into_iter().map(Into::into).collect();
-            Ok(logs)
         }
+        if let Some(cursor) = &after {
+            query = query
+                .bind(cursor.block_number.0 as i64)
+                .bind(cursor.log_index as i32);
+        }
+        query = query.bind(limit as i32);
+
+        let db_logs: Vec<StorageWeb3Log> = query
+            .instrument("get_logs")
+            .with_arg("filter", filter)
+            .with_arg("after", &after)
+            .with_arg("limit", &limit)
+            .fetch_all(self.storage)
+            .await?;
+        let logs = db_logs.into_iter().map(Into::into).collect();
+        Ok(logs)
     }
 
+    /// Builds the `WHERE` clause for the logs filter. No values are interpolated into the returned SQL;
+    /// the block range is always bound as `$1` and `$2`, followed by address and topic args. Thus, the query text
+    /// only depends on the filter shape, which allows reusing prepared statements cached by `sqlx` per connection.
     fn build_get_logs_where_clause(&self, filter: &GetLogsFilter) -> (String, u8) {
-        let mut arg_index = 1;
-
-        let mut where_sql = format!("(miniblock_number >= {})", filter.from_block.0 as i64);
-
-        where_sql += &format!(" AND (miniblock_number <= {})", filter.to_block.0 as i64);
+        let mut where_sql = "(miniblock_number >= $1) AND (miniblock_number <= $2)".to_owned();
+        let mut arg_index = 3;
 
         // Add filters for address (like `address = ANY($1)` or `address = $1`)
         if let Some(filter_sql) =
@@ -247,7 +410,8 @@ impl EventsWeb3Dal<'_, '_> {
                 "#,
                 from_block.0 as i64
             )
-            .fetch_all(self.storage.conn())
+            .instrument("get_all_logs")
+            .fetch_all(self.storage)
             .await?;
             let logs = db_logs.into_iter().map(Into::into).collect();
             Ok(logs)
@@ -257,10 +421,12 @@ impl EventsWeb3Dal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::{Address, H256};
+    use zksync_types::{
+        tx::IncludedTxLocation, Address, L1BatchNumber, ProtocolVersion, VmEvent, H256,
+    };
 
     use super::*;
-    use crate::connection::ConnectionPool;
+    use crate::{connection::ConnectionPool, tests::create_miniblock_header};
 
     #[tokio::test]
     async fn test_build_get_logs_where_clause() {
@@ -274,8 +440,8 @@ mod tests {
             topics: vec![(0, vec![H256::from_low_u64_be(456)])],
         };
 
-        let expected_sql = "(miniblock_number >= 100) AND (miniblock_number <= 200) AND (address = $1) AND (topic0 = $2)";
-        let expected_arg_index = 3;
+        let expected_sql = "(miniblock_number >= $1) AND (miniblock_number <= $2) AND (address = $3) AND (topic0 = $4)";
+        let expected_arg_index = 5;
 
         let (actual_sql, actual_arg_index) = events_web3_dal.build_get_logs_where_clause(&filter);
 
@@ -308,8 +474,8 @@ mod tests {
             ],
         };
 
-        let expected_sql = "(miniblock_number >= 10) AND (miniblock_number <= 400) AND (address = ANY($1)) AND (topic0 = ANY($2)) AND (topic2 = $3)";
-        let expected_arg_index = 4;
+        let expected_sql = "(miniblock_number >= $1) AND (miniblock_number <= $2) AND (address = ANY($3)) AND (topic0 = ANY($4)) AND (topic2 = $5)";
+        let expected_arg_index = 6;
 
         let (actual_sql, actual_arg_index) = events_web3_dal.build_get_logs_where_clause(&filter);
 
@@ -330,12 +496,190 @@ mod tests {
         };
 
         let expected_sql =
-            "(miniblock_number >= 10) AND (miniblock_number <= 400) AND (topic2 = $1)";
-        let expected_arg_index = 2;
+            "(miniblock_number >= $1) AND (miniblock_number <= $2) AND (topic2 = $3)";
+        let expected_arg_index = 4;
 
         let (actual_sql, actual_arg_index) = events_web3_dal.build_get_logs_where_clause(&filter);
 
         assert_eq!(actual_sql, expected_sql);
         assert_eq!(actual_arg_index, expected_arg_index);
     }
+
+    #[tokio::test]
+    async fn get_logs_where_clause_does_not_depend_on_block_range() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let storage = &mut connection_pool.access_storage().await.unwrap();
+        let events_web3_dal = EventsWeb3Dal { storage };
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(10),
+            to_block: MiniblockNumber(400),
+            addresses: vec![Address::from_low_u64_be(123)],
+            topics: vec![],
+        };
+        let other_filter = GetLogsFilter {
+            from_block: MiniblockNumber(1_000),
+            to_block: MiniblockNumber(1_001),
+            ..filter.clone()
+        };
+
+        let (sql, _) = events_web3_dal.build_get_logs_where_clause(&filter);
+        let (other_sql, _) = events_web3_dal.build_get_logs_where_clause(&other_filter);
+        assert_eq!(sql, other_sql);
+    }
+
+    #[tokio::test]
+    async fn narrowing_logs_range_by_bloom() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..5 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+            let event = VmEvent {
+                location: (L1BatchNumber(1), number),
+                address: Address::repeat_byte(number as u8),
+                indexed_topics: vec![H256::repeat_byte(number as u8)],
+                value: vec![],
+            };
+            let location = IncludedTxLocation {
+                tx_hash: H256::repeat_byte(number as u8),
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Address::default(),
+            };
+            conn.events_dal()
+                .save_events(MiniblockNumber(number), &[(location, vec![&event])])
+                .await
+                .unwrap();
+        }
+
+        let mut filter = GetLogsFilter {
+            from_block: MiniblockNumber(0),
+            to_block: MiniblockNumber(4),
+            addresses: vec![Address::repeat_byte(1), Address::repeat_byte(3)],
+            topics: vec![],
+        };
+        let range = conn
+            .events_web3_dal()
+            .get_bloom_matching_range(&filter)
+            .await
+            .unwrap();
+        assert_eq!(range, Some((MiniblockNumber(1), MiniblockNumber(3))));
+
+        filter.topics = vec![(1, vec![H256::repeat_byte(3)])];
+        let range = conn
+            .events_web3_dal()
+            .get_bloom_matching_range(&filter)
+            .await
+            .unwrap();
+        assert_eq!(range, Some((MiniblockNumber(3), MiniblockNumber(3))));
+
+        filter.topics = vec![(1, vec![H256::repeat_byte(2)])];
+        let range = conn
+            .events_web3_dal()
+            .get_bloom_matching_range(&filter)
+            .await
+            .unwrap();
+        assert_eq!(range, None);
+    }
+
+    #[tokio::test]
+    async fn getting_logs_page() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let address = Address::repeat_byte(1);
+        for number in 0..3 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+            let events: Vec<_> = (0..2)
+                .map(|i| VmEvent {
+                    location: (L1BatchNumber(1), number),
+                    address: if number == 1 && i == 0 {
+                        Address::repeat_byte(2)
+                    } else {
+                        address
+                    },
+                    indexed_topics: vec![],
+                    value: vec![i as u8],
+                })
+                .collect();
+            let location = IncludedTxLocation {
+                tx_hash: H256::repeat_byte(number as u8),
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Address::default(),
+            };
+            conn.events_dal()
+                .save_events(
+                    MiniblockNumber(number),
+                    &[(location, events.iter().collect())],
+                )
+                .await
+                .unwrap();
+        }
+
+        let filter = GetLogsFilter {
+            from_block: MiniblockNumber(0),
+            to_block: MiniblockNumber(2),
+            addresses: vec![address],
+            topics: vec![],
+        };
+        let log_positions = |logs: &[Log]| -> Vec<_> {
+            logs.iter()
+                .map(|log| {
+                    (
+                        log.block_number.unwrap().as_u32(),
+                        log.log_index.unwrap().as_u32(),
+                    )
+                })
+                .collect()
+        };
+
+        let first_page = conn
+            .events_web3_dal()
+            .get_logs_page(&filter, None, 2)
+            .await
+            .unwrap();
+        assert_eq!(log_positions(&first_page), [(0, 0), (0, 1)]);
+
+        let cursor = LogCursor {
+            block_number: MiniblockNumber(0),
+            log_index: 1,
+        };
+        let second_page = conn
+            .events_web3_dal()
+            .get_logs_page(&filter, Some(cursor), 2)
+            .await
+            .unwrap();
+        assert_eq!(log_positions(&second_page), [(1, 1), (2, 0)]);
+
+        let cursor = LogCursor {
+            block_number: MiniblockNumber(2),
+            log_index: 0,
+        };
+        let last_page = conn
+            .events_web3_dal()
+            .get_logs_page(&filter, Some(cursor), 2)
+            .await
+            .unwrap();
+        assert_eq!(log_positions(&last_page), [(2, 1)]);
+
+        let cursor = LogCursor {
+            block_number: MiniblockNumber(2),
+            log_index: u32::MAX,
+        };
+        let logs = conn
+            .events_web3_dal()
+            .get_logs_page(&filter, Some(cursor), 2)
+            .await
+            .unwrap();
+        assert!(logs.is_empty());
+    }
 }
@@ -5,7 +5,7 @@ use zksync_contracts::{BaseSystemContracts, SystemContractCode};
 use zksync_types::{MiniblockNumber, H256, U256};
 use zksync_utils::{bytes_to_be_words, bytes_to_chunks};
 
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 /// DAL methods related to factory dependencies.
 #[derive(Debug)]
@@ -45,7 +45,8 @@ impl FactoryDepsDal<'_, '_> {
             &bytecodes as &[&[u8]],
             block_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("insert_factory_deps")
+        .execute(self.storage)
         .await?;
 
         Ok(())
@@ -64,7 +65,8 @@ impl FactoryDepsDal<'_, '_> {
             "#,
             hash.as_bytes(),
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_factory_dep")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.bytecode))
     }
@@ -119,7 +121,8 @@ impl FactoryDepsDal<'_, '_> {
             "#,
             &hashes_as_bytes as &[&[u8]],
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_factory_deps")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -149,7 +152,8 @@ impl FactoryDepsDal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_factory_deps_for_revert")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| H256::from_slice(&row.bytecode_hash))
@@ -169,7 +173,8 @@ impl FactoryDepsDal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_factory_deps")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
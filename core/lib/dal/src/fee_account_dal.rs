@@ -0,0 +1,112 @@
+use zksync_types::{Address, L1BatchNumber};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// DAL for fee account (aka operator address) updates. An update is applied by the state keeper
+/// at the start of the next L1 batch; the operator that has actually sealed each miniblock
+/// is recorded in `miniblocks.fee_account_address`.
+#[derive(Debug)]
+pub struct FeeAccountDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl FeeAccountDal<'_, '_> {
+    /// Schedules the fee account to be used starting from the next L1 batch.
+    pub async fn schedule_fee_account(&mut self, fee_account: Address) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                fee_account_updates (fee_account_address, created_at)
+            VALUES
+                ($1, NOW())
+            "#,
+            fee_account.as_bytes()
+        )
+        .instrument("schedule_fee_account")
+        .with_arg("fee_account", &fee_account)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the most recently scheduled fee account, or `None` if the fee account was never updated
+    /// (in which case the fee account from the state keeper config should be used).
+    pub async fn get_scheduled_fee_account(&mut self) -> sqlx::Result<Option<Address>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                fee_account_address
+            FROM
+                fee_account_updates
+            ORDER BY
+                id DESC
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_scheduled_fee_account")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| Address::from_slice(&row.fee_account_address)))
+    }
+
+    /// Removes fee account updates scheduled after the specified L1 batch was sealed, so that the fee account
+    /// in effect matches the one at the time of that batch. Used when rolling back L1 batches.
+    pub async fn rollback_fee_account_updates(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM fee_account_updates
+            WHERE
+                created_at > (
+                    SELECT
+                        created_at
+                    FROM
+                        l1_batches
+                    WHERE
+                        number = $1
+                )
+            "#,
+            i64::from(last_l1_batch_to_keep.0)
+        )
+        .instrument("rollback_fee_account_updates")
+        .with_arg("last_l1_batch_to_keep", &last_l1_batch_to_keep)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn scheduling_fee_account() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+
+        let fee_account = conn
+            .fee_account_dal()
+            .get_scheduled_fee_account()
+            .await
+            .unwrap();
+        assert_eq!(fee_account, None);
+
+        for fee_account in [Address::repeat_byte(1), Address::repeat_byte(2)] {
+            conn.fee_account_dal()
+                .schedule_fee_account(fee_account)
+                .await
+                .unwrap();
+            let scheduled = conn
+                .fee_account_dal()
+                .get_scheduled_fee_account()
+                .await
+                .unwrap();
+            assert_eq!(scheduled, Some(fee_account));
+        }
+    }
+}
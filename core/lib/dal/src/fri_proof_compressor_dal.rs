@@ -6,6 +6,7 @@ use zksync_types::L1BatchNumber;
 
 use crate::{
     fri_prover_dal::types::{JobCountStatistics, StuckJobs},
+    instrument::InstrumentExt,
     time_utils::{duration_to_naive_time, pg_interval_from_duration},
     StorageProcessor,
 };
@@ -49,7 +50,8 @@ impl FriProofCompressorDal<'_, '_> {
                 fri_proof_blob_url,
             ProofCompressionJobStatus::Queued.to_string(),
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("insert_proof_compression_job")
+            .fetch_optional(self.storage)
             .await
             .unwrap();
     }
@@ -66,7 +68,8 @@ impl FriProofCompressorDal<'_, '_> {
             block_number.0 as i64,
             ProofCompressionJobStatus::Skipped.to_string(),
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("skip_proof_compression_job")
+        .fetch_optional(self.storage)
         .await
         .unwrap();
     }
@@ -106,7 +109,8 @@ impl FriProofCompressorDal<'_, '_> {
             ProofCompressionJobStatus::Queued.to_string(),
             picked_by,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_proof_compression_job")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32))
@@ -127,7 +131,8 @@ impl FriProofCompressorDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_proof_compression_job_attempts")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.attempts as u32);
 
@@ -156,7 +161,8 @@ impl FriProofCompressorDal<'_, '_> {
             l1_proof_blob_url,
             block_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("mark_proof_compression_job_successful")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -180,7 +186,8 @@ impl FriProofCompressorDal<'_, '_> {
             error,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_proof_compression_job_failed")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -209,7 +216,8 @@ impl FriProofCompressorDal<'_, '_> {
             ProofCompressionJobStatus::Successful.to_string(),
             ProofCompressionJobStatus::Skipped.to_string()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_least_proven_block_number_not_sent_to_server")
+        .fetch_optional(self.storage)
         .await
         .ok()?;
         match row {
@@ -234,7 +242,8 @@ impl FriProofCompressorDal<'_, '_> {
             ProofCompressionJobStatus::SentToServer.to_string(),
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_proof_sent_to_server")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -245,7 +254,8 @@ impl FriProofCompressorDal<'_, '_> {
                  FROM proof_compression_jobs_fri \
                  GROUP BY status",
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_jobs_stats")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -275,7 +285,8 @@ impl FriProofCompressorDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_oldest_not_compressed_batch")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32));
@@ -315,7 +326,8 @@ impl FriProofCompressorDal<'_, '_> {
                 &processing_timeout,
                 max_attempts as i32,
             )
-            .fetch_all(self.storage.conn())
+            .instrument("requeue_stuck_jobs")
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
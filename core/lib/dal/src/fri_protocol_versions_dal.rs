@@ -2,7 +2,7 @@ use std::convert::TryFrom;
 
 use zksync_types::protocol_version::{FriProtocolVersionId, L1VerifierConfig};
 
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug)]
 pub struct FriProtocolVersionsDal<'a, 'c> {
@@ -47,7 +47,8 @@ impl FriProtocolVersionsDal<'_, '_> {
                 .recursion_circuits_set_vks_hash
                 .as_bytes(),
         )
-        .execute(self.storage.conn())
+        .instrument("save_prover_protocol_version")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -82,7 +83,8 @@ impl FriProtocolVersionsDal<'_, '_> {
                 .as_bytes(),
             vk_commitments.recursion_scheduler_level_vk_hash.as_bytes(),
         )
-        .fetch_all(self.storage.conn())
+        .instrument("protocol_version_for")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
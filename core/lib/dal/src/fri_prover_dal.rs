@@ -317,7 +317,8 @@ impl FriProverDal<'_, '_> {
             &protocol_versions[..],
             picked_by,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_job#update_prover_jobs_fri")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| FriProverJobMetadata {
@@ -405,7 +406,8 @@ impl FriProverDal<'_, '_> {
             &protocol_versions[..],
             picked_by,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_job_for_circuit_id_round")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| FriProverJobMetadata {
@@ -434,7 +436,8 @@ impl FriProverDal<'_, '_> {
                 error,
                 id as i64,
             )
-            .execute(self.storage.conn())
+            .instrument("save_proof_error")
+            .execute(self.storage)
             .await
             .unwrap();
         }
@@ -452,7 +455,8 @@ impl FriProverDal<'_, '_> {
             "#,
             id as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_prover_job_attempts")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.attempts as u32);
 
@@ -489,7 +493,6 @@ impl FriProverDal<'_, '_> {
             id as i64,
         )
         .instrument("save_fri_proof")
-        .report_latency()
         .with_arg("id", &id)
         .fetch_optional(self.storage)
         .await
@@ -552,7 +555,8 @@ impl FriProverDal<'_, '_> {
                 &processing_timeout,
                 max_attempts as i32,
             )
-            .fetch_all(self.storage.conn())
+            .instrument("requeue_stuck_jobs")
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
@@ -609,7 +613,8 @@ impl FriProverDal<'_, '_> {
             is_node_final_proof,
             protocol_version_id as i32,
         )
-            .execute(self.storage.conn())
+            .instrument("insert_prover_job")
+            .execute(self.storage)
             .await
             .unwrap();
     }
@@ -634,7 +639,8 @@ impl FriProverDal<'_, '_> {
                     status
                 "#
             )
-            .fetch_all(self.storage.conn())
+            .instrument("get_prover_jobs_stats")
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
@@ -687,7 +693,8 @@ impl FriProverDal<'_, '_> {
                     aggregation_round
                 "#
             )
-            .fetch_all(self.storage.conn())
+            .instrument("min_unproved_l1_batch_number")
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
@@ -722,7 +729,8 @@ impl FriProverDal<'_, '_> {
             "#,
             aggregation_round as i16
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("min_unproved_l1_batch_number_for_aggregation_round")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32))
@@ -741,7 +749,8 @@ impl FriProverDal<'_, '_> {
             status,
             id as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("update_status")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -758,7 +767,8 @@ impl FriProverDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("save_successful_sent_proof")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -781,7 +791,8 @@ impl FriProverDal<'_, '_> {
             l1_batch_number.0 as i64,
             AggregationRound::Scheduler as i16,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_scheduler_proof_job_id")
+        .fetch_optional(self.storage)
         .await
         .ok()?
         .map(|row| row.id as u32)
@@ -1,6 +1,6 @@
 use zksync_types::{basic_fri_types::FinalProofIds, L1BatchNumber};
 
-use crate::{fri_prover_dal::types, StorageProcessor};
+use crate::{fri_prover_dal::types, instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug)]
 pub struct FriSchedulerDependencyTrackerDal<'a, 'c> {
@@ -42,7 +42,8 @@ impl FriSchedulerDependencyTrackerDal<'_, '_> {
                 l1_batch_number;
             "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_l1_batches_ready_for_queuing")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -61,7 +62,8 @@ impl FriSchedulerDependencyTrackerDal<'_, '_> {
             "#,
             &l1_batches[..]
         )
-        .execute(self.storage.conn())
+        .instrument("mark_l1_batches_queued")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -98,7 +100,8 @@ impl FriSchedulerDependencyTrackerDal<'_, '_> {
         sqlx::query(&query)
             .bind(final_prover_job_id as i64)
             .bind(l1_batch_number.0 as i64)
-            .execute(self.storage.conn())
+            .instrument("set_final_prover_job_id_for_l1_batch")
+            .execute(self.storage)
             .await
             .unwrap();
     }
@@ -118,7 +121,8 @@ impl FriSchedulerDependencyTrackerDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_final_prover_job_ids_for")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
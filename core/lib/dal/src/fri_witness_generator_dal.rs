@@ -1,6 +1,7 @@
 use std::{collections::HashMap, convert::TryFrom, time::Duration};
 
-use sqlx::Row;
+use futures::future::BoxFuture;
+use sqlx::{postgres::types::PgInterval, Row};
 use zksync_types::{
     basic_fri_types::{AggregationRound, Eip4844Blobs},
     protocol_version::FriProtocolVersionId,
@@ -11,8 +12,10 @@ use crate::{
     fri_prover_dal::types::{
         JobCountStatistics, LeafAggregationJobMetadata, NodeAggregationJobMetadata, StuckJobs,
     },
+    instrument::InstrumentExt,
+    job_queue_dal::QueuedJob,
     metrics::MethodLatency,
-    time_utils::{duration_to_naive_time, pg_interval_from_duration},
+    time_utils::duration_to_naive_time,
     StorageProcessor,
 };
 
@@ -35,6 +38,443 @@ pub enum FriWitnessJobStatus {
     Queued,
 }
 
+/// Parameters of picking witness generator jobs.
+#[derive(Debug)]
+struct WitnessJobPickParams {
+    protocol_versions: Vec<i32>,
+    picked_by: String,
+}
+
+impl WitnessJobPickParams {
+    fn new(protocol_versions: &[FriProtocolVersionId], picked_by: &str) -> Self {
+        Self {
+            protocol_versions: protocol_versions.iter().map(|&id| id as i32).collect(),
+            picked_by: picked_by.to_owned(),
+        }
+    }
+}
+
+/// Parameters of picking basic circuit witness generator jobs.
+#[derive(Debug)]
+struct BasicWitnessJobPickParams {
+    last_l1_batch_to_process: u32,
+    inner: WitnessJobPickParams,
+}
+
+#[derive(Debug)]
+struct StorageBasicWitnessJob {
+    l1_batch_number: i64,
+    eip_4844_blobs: Option<Vec<u8>>,
+}
+
+impl QueuedJob for StorageBasicWitnessJob {
+    type PickParams = BasicWitnessJobPickParams;
+
+    fn pick_next<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        params: &'a Self::PickParams,
+    ) -> BoxFuture<'a, sqlx::Result<Option<Self>>> {
+        Box::pin(async move {
+            sqlx::query_as!(
+                StorageBasicWitnessJob,
+                r#"
+                UPDATE witness_inputs_fri
+                SET
+                    status = 'in_progress',
+                    attempts = attempts + 1,
+                    updated_at = NOW(),
+                    processing_started_at = NOW(),
+                    picked_by = $3
+                WHERE
+                    l1_batch_number = (
+                        SELECT
+                            l1_batch_number
+                        FROM
+                            witness_inputs_fri
+                        WHERE
+                            l1_batch_number <= $1
+                            AND status = 'queued'
+                            AND protocol_version = ANY ($2)
+                        ORDER BY
+                            l1_batch_number ASC
+                        LIMIT
+                            1
+                        FOR UPDATE
+                            SKIP LOCKED
+                    )
+                RETURNING
+                    l1_batch_number,
+                    eip_4844_blobs
+                "#,
+                params.last_l1_batch_to_process as i64,
+                &params.inner.protocol_versions[..],
+                &params.inner.picked_by,
+            )
+            .instrument("get_next_basic_circuit_witness_job")
+            .fetch_optional(storage)
+            .await
+        })
+    }
+
+    fn requeue_stuck<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        processing_timeout: PgInterval,
+        max_attempts: u32,
+    ) -> BoxFuture<'a, sqlx::Result<Vec<StuckJobs>>> {
+        Box::pin(async move {
+            let rows = sqlx::query!(
+                r#"
+                UPDATE witness_inputs_fri
+                SET
+                    status = 'queued',
+                    updated_at = NOW(),
+                    processing_started_at = NOW()
+                WHERE
+                    (
+                        status = 'in_progress'
+                        AND processing_started_at <= NOW() - $1::INTERVAL
+                        AND attempts < $2
+                    )
+                    OR (
+                        status = 'in_gpu_proof'
+                        AND processing_started_at <= NOW() - $1::INTERVAL
+                        AND attempts < $2
+                    )
+                    OR (
+                        status = 'failed'
+                        AND attempts < $2
+                    )
+                RETURNING
+                    l1_batch_number,
+                    status,
+                    attempts
+                "#,
+                &processing_timeout,
+                max_attempts as i16,
+            )
+            .instrument("requeue_stuck_jobs")
+            .fetch_all(storage)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| StuckJobs {
+                    id: row.l1_batch_number as u64,
+                    status: row.status,
+                    attempts: row.attempts as u64,
+                })
+                .collect())
+        })
+    }
+}
+
+#[derive(Debug)]
+struct StorageLeafAggregationJob {
+    id: i64,
+    l1_batch_number: i64,
+    circuit_id: i16,
+}
+
+impl QueuedJob for StorageLeafAggregationJob {
+    type PickParams = WitnessJobPickParams;
+
+    fn pick_next<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        params: &'a Self::PickParams,
+    ) -> BoxFuture<'a, sqlx::Result<Option<Self>>> {
+        Box::pin(async move {
+            sqlx::query_as!(
+                StorageLeafAggregationJob,
+                r#"
+                UPDATE leaf_aggregation_witness_jobs_fri
+                SET
+                    status = 'in_progress',
+                    attempts = attempts + 1,
+                    updated_at = NOW(),
+                    processing_started_at = NOW(),
+                    picked_by = $2
+                WHERE
+                    id = (
+                        SELECT
+                            id
+                        FROM
+                            leaf_aggregation_witness_jobs_fri
+                        WHERE
+                            status = 'queued'
+                            AND protocol_version = ANY ($1)
+                        ORDER BY
+                            l1_batch_number ASC,
+                            id ASC
+                        LIMIT
+                            1
+                        FOR UPDATE
+                            SKIP LOCKED
+                    )
+                RETURNING
+                    id,
+                    l1_batch_number,
+                    circuit_id
+                "#,
+                &params.protocol_versions[..],
+                &params.picked_by,
+            )
+            .instrument("get_next_leaf_aggregation_job")
+            .fetch_optional(storage)
+            .await
+        })
+    }
+
+    fn requeue_stuck<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        processing_timeout: PgInterval,
+        max_attempts: u32,
+    ) -> BoxFuture<'a, sqlx::Result<Vec<StuckJobs>>> {
+        Box::pin(async move {
+            let rows = sqlx::query!(
+                r#"
+                UPDATE leaf_aggregation_witness_jobs_fri
+                SET
+                    status = 'queued',
+                    updated_at = NOW(),
+                    processing_started_at = NOW()
+                WHERE
+                    (
+                        status = 'in_progress'
+                        AND processing_started_at <= NOW() - $1::INTERVAL
+                        AND attempts < $2
+                    )
+                    OR (
+                        status = 'failed'
+                        AND attempts < $2
+                    )
+                RETURNING
+                    id,
+                    status,
+                    attempts
+                "#,
+                &processing_timeout,
+                max_attempts as i16,
+            )
+            .instrument("requeue_stuck_leaf_aggregations_jobs")
+            .fetch_all(storage)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| StuckJobs {
+                    id: row.id as u64,
+                    status: row.status,
+                    attempts: row.attempts as u64,
+                })
+                .collect())
+        })
+    }
+}
+
+#[derive(Debug)]
+struct StorageNodeAggregationJob {
+    id: i64,
+    l1_batch_number: i64,
+    circuit_id: i16,
+    depth: i32,
+}
+
+impl QueuedJob for StorageNodeAggregationJob {
+    type PickParams = WitnessJobPickParams;
+
+    fn pick_next<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        params: &'a Self::PickParams,
+    ) -> BoxFuture<'a, sqlx::Result<Option<Self>>> {
+        Box::pin(async move {
+            sqlx::query_as!(
+                StorageNodeAggregationJob,
+                r#"
+                UPDATE node_aggregation_witness_jobs_fri
+                SET
+                    status = 'in_progress',
+                    attempts = attempts + 1,
+                    updated_at = NOW(),
+                    processing_started_at = NOW(),
+                    picked_by = $2
+                WHERE
+                    id = (
+                        SELECT
+                            id
+                        FROM
+                            node_aggregation_witness_jobs_fri
+                        WHERE
+                            status = 'queued'
+                            AND protocol_version = ANY ($1)
+                        ORDER BY
+                            l1_batch_number ASC,
+                            depth ASC,
+                            id ASC
+                        LIMIT
+                            1
+                        FOR UPDATE
+                            SKIP LOCKED
+                    )
+                RETURNING
+                    id,
+                    l1_batch_number,
+                    circuit_id,
+                    depth
+                "#,
+                &params.protocol_versions[..],
+                &params.picked_by,
+            )
+            .instrument("get_next_node_aggregation_job")
+            .fetch_optional(storage)
+            .await
+        })
+    }
+
+    fn requeue_stuck<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        processing_timeout: PgInterval,
+        max_attempts: u32,
+    ) -> BoxFuture<'a, sqlx::Result<Vec<StuckJobs>>> {
+        Box::pin(async move {
+            let rows = sqlx::query!(
+                r#"
+                UPDATE node_aggregation_witness_jobs_fri
+                SET
+                    status = 'queued',
+                    updated_at = NOW(),
+                    processing_started_at = NOW()
+                WHERE
+                    (
+                        status = 'in_progress'
+                        AND processing_started_at <= NOW() - $1::INTERVAL
+                        AND attempts < $2
+                    )
+                    OR (
+                        status = 'failed'
+                        AND attempts < $2
+                    )
+                RETURNING
+                    id,
+                    status,
+                    attempts
+                "#,
+                &processing_timeout,
+                max_attempts as i16,
+            )
+            .instrument("requeue_stuck_node_aggregations_jobs")
+            .fetch_all(storage)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| StuckJobs {
+                    id: row.id as u64,
+                    status: row.status,
+                    attempts: row.attempts as u64,
+                })
+                .collect())
+        })
+    }
+}
+
+#[derive(Debug)]
+struct StorageSchedulerWitnessJob {
+    l1_batch_number: i64,
+}
+
+impl QueuedJob for StorageSchedulerWitnessJob {
+    type PickParams = WitnessJobPickParams;
+
+    fn pick_next<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        params: &'a Self::PickParams,
+    ) -> BoxFuture<'a, sqlx::Result<Option<Self>>> {
+        Box::pin(async move {
+            sqlx::query_as!(
+                StorageSchedulerWitnessJob,
+                r#"
+                UPDATE scheduler_witness_jobs_fri
+                SET
+                    status = 'in_progress',
+                    attempts = attempts + 1,
+                    updated_at = NOW(),
+                    processing_started_at = NOW(),
+                    picked_by = $2
+                WHERE
+                    l1_batch_number = (
+                        SELECT
+                            l1_batch_number
+                        FROM
+                            scheduler_witness_jobs_fri
+                        WHERE
+                            status = 'queued'
+                            AND protocol_version = ANY ($1)
+                        ORDER BY
+                            l1_batch_number ASC
+                        LIMIT
+                            1
+                        FOR UPDATE
+                            SKIP LOCKED
+                    )
+                RETURNING
+                    l1_batch_number
+                "#,
+                &params.protocol_versions[..],
+                &params.picked_by,
+            )
+            .instrument("get_next_scheduler_witness_job")
+            .fetch_optional(storage)
+            .await
+        })
+    }
+
+    fn requeue_stuck<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        processing_timeout: PgInterval,
+        max_attempts: u32,
+    ) -> BoxFuture<'a, sqlx::Result<Vec<StuckJobs>>> {
+        Box::pin(async move {
+            let rows = sqlx::query!(
+                r#"
+                UPDATE scheduler_witness_jobs_fri
+                SET
+                    status = 'queued',
+                    updated_at = NOW(),
+                    processing_started_at = NOW()
+                WHERE
+                    (
+                        status = 'in_progress'
+                        AND processing_started_at <= NOW() - $1::INTERVAL
+                        AND attempts < $2
+                    )
+                    OR (
+                        status = 'failed'
+                        AND attempts < $2
+                    )
+                RETURNING
+                    l1_batch_number,
+                    status,
+                    attempts
+                "#,
+                &processing_timeout,
+                max_attempts as i16,
+            )
+            .instrument("requeue_stuck_scheduler_jobs")
+            .fetch_all(storage)
+            .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| StuckJobs {
+                    id: row.l1_batch_number as u64,
+                    status: row.status,
+                    attempts: row.attempts as u64,
+                })
+                .collect())
+        })
+    }
+}
+
 impl FriWitnessGeneratorDal<'_, '_> {
     pub async fn save_witness_inputs(
         &mut self,
@@ -65,7 +505,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             protocol_version_id as i32,
             blobs_raw,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("save_witness_inputs")
+        .fetch_optional(self.storage)
         .await
         .unwrap();
     }
@@ -78,51 +519,22 @@ impl FriWitnessGeneratorDal<'_, '_> {
         protocol_versions: &[FriProtocolVersionId],
         picked_by: &str,
     ) -> Option<(L1BatchNumber, Eip4844Blobs)> {
-        let protocol_versions: Vec<i32> = protocol_versions.iter().map(|&id| id as i32).collect();
-        sqlx::query!(
-            r#"
-            UPDATE witness_inputs_fri
-            SET
-                status = 'in_progress',
-                attempts = attempts + 1,
-                updated_at = NOW(),
-                processing_started_at = NOW(),
-                picked_by = $3
-            WHERE
-                l1_batch_number = (
-                    SELECT
-                        l1_batch_number
-                    FROM
-                        witness_inputs_fri
-                    WHERE
-                        l1_batch_number <= $1
-                        AND status = 'queued'
-                        AND protocol_version = ANY ($2)
-                    ORDER BY
-                        l1_batch_number ASC
-                    LIMIT
-                        1
-                    FOR UPDATE
-                        SKIP LOCKED
-                )
-            RETURNING
-                witness_inputs_fri.*
-            "#,
-            last_l1_batch_to_process as i64,
-            &protocol_versions[..],
-            picked_by,
-        )
-        .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()
-        .map(|row| {
-            (
-                L1BatchNumber(row.l1_batch_number as u32),
-                row.eip_4844_blobs
-                    .expect("missing eip 4844 blobs from the database")
-                    .into(),
-            )
-        })
+        let params = BasicWitnessJobPickParams {
+            last_l1_batch_to_process,
+            inner: WitnessJobPickParams::new(protocol_versions, picked_by),
+        };
+        let job = self
+            .storage
+            .job_queue_dal::<StorageBasicWitnessJob>()
+            .pick_next_job(&params)
+            .await
+            .unwrap()?;
+        Some((
+            L1BatchNumber(job.l1_batch_number as u32),
+            job.eip_4844_blobs
+                .expect("missing eip 4844 blobs from the database")
+                .into(),
+        ))
     }
 
     pub async fn get_basic_circuit_witness_job_attempts(
@@ -140,7 +552,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_basic_circuit_witness_job_attempts")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.attempts as u32);
 
@@ -164,7 +577,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             format!("{}", status),
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_witness_job")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -187,7 +601,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             duration_to_naive_time(time_taken),
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_witness_job_as_successful")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -206,7 +621,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             error,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_witness_job_failed")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -225,7 +641,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             error,
             id as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_leaf_aggregation_job_failed")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -244,7 +661,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             duration_to_naive_time(time_taken),
             id as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_leaf_aggregation_as_successful")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -254,47 +672,11 @@ impl FriWitnessGeneratorDal<'_, '_> {
         processing_timeout: Duration,
         max_attempts: u32,
     ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        sqlx::query!(
-            r#"
-            UPDATE witness_inputs_fri
-            SET
-                status = 'queued',
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                (
-                    status = 'in_progress'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'in_gpu_proof'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'failed'
-                    AND attempts < $2
-                )
-            RETURNING
-                l1_batch_number,
-                status,
-                attempts
-            "#,
-            &processing_timeout,
-            max_attempts as i32,
-        )
-        .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
-        .into_iter()
-        .map(|row| StuckJobs {
-            id: row.l1_batch_number as u64,
-            status: row.status,
-            attempts: row.attempts as u64,
-        })
-        .collect()
+        self.storage
+            .job_queue_dal::<StorageBasicWitnessJob>()
+            .requeue_stuck_jobs(processing_timeout, max_attempts)
+            .await
+            .unwrap()
     }
 
     pub async fn create_aggregation_jobs(
@@ -336,7 +718,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
                     *number_of_basic_circuits as i32,
                     protocol_version_id as i32,
                 )
-                .execute(self.storage.conn())
+                .instrument("create_aggregation_jobs#insert_leaf_aggregation_witness_jobs_fri")
+                .execute(self.storage)
                 .await
                 .unwrap();
 
@@ -353,44 +736,46 @@ impl FriWitnessGeneratorDal<'_, '_> {
 
             sqlx::query!(
                 r#"
-                INSERT INTO
-                    scheduler_witness_jobs_fri (
-                        l1_batch_number,
-                        scheduler_partial_input_blob_url,
-                        protocol_version,
-                        status,
-                        created_at,
-                        updated_at
-                    )
-                VALUES
-                    ($1, $2, $3, 'waiting_for_proofs', NOW(), NOW())
-                ON CONFLICT (l1_batch_number) DO
-                UPDATE
-                SET
-                    updated_at = NOW()
+            INSERT INTO
+                scheduler_witness_jobs_fri (
+                    l1_batch_number,
+                    scheduler_partial_input_blob_url,
+                    protocol_version,
+                    status,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, 'waiting_for_proofs', NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                updated_at = NOW()
                 "#,
                 block_number.0 as i64,
                 scheduler_partial_input_blob_url,
                 protocol_version_id as i32,
             )
-            .execute(self.storage.conn())
+            .instrument("create_aggregation_jobs#insert_scheduler_witness_jobs_fri")
+            .execute(self.storage)
             .await
             .unwrap();
 
             sqlx::query!(
                 r#"
-                INSERT INTO
-                    scheduler_dependency_tracker_fri (l1_batch_number, status, created_at, updated_at)
-                VALUES
-                    ($1, 'waiting_for_proofs', NOW(), NOW())
-                ON CONFLICT (l1_batch_number) DO
-                UPDATE
-                SET
-                    updated_at = NOW()
+            INSERT INTO
+                scheduler_dependency_tracker_fri (l1_batch_number, status, created_at, updated_at)
+            VALUES
+                ($1, 'waiting_for_proofs', NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                updated_at = NOW()
                 "#,
                 block_number.0 as i64,
             )
-            .execute(self.storage.conn())
+            .instrument("create_aggregation_jobs#insert_scheduler_dependency_tracker_fri")
+            .execute(self.storage)
             .await
             .unwrap();
 
@@ -403,42 +788,13 @@ impl FriWitnessGeneratorDal<'_, '_> {
         protocol_versions: &[FriProtocolVersionId],
         picked_by: &str,
     ) -> Option<LeafAggregationJobMetadata> {
-        let protocol_versions: Vec<i32> = protocol_versions.iter().map(|&id| id as i32).collect();
-        let row = sqlx::query!(
-            r#"
-            UPDATE leaf_aggregation_witness_jobs_fri
-            SET
-                status = 'in_progress',
-                attempts = attempts + 1,
-                updated_at = NOW(),
-                processing_started_at = NOW(),
-                picked_by = $2
-            WHERE
-                id = (
-                    SELECT
-                        id
-                    FROM
-                        leaf_aggregation_witness_jobs_fri
-                    WHERE
-                        status = 'queued'
-                        AND protocol_version = ANY ($1)
-                    ORDER BY
-                        l1_batch_number ASC,
-                        id ASC
-                    LIMIT
-                        1
-                    FOR UPDATE
-                        SKIP LOCKED
-                )
-            RETURNING
-                leaf_aggregation_witness_jobs_fri.*
-            "#,
-            &protocol_versions[..],
-            picked_by,
-        )
-        .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()?;
+        let params = WitnessJobPickParams::new(protocol_versions, picked_by);
+        let row = self
+            .storage
+            .job_queue_dal::<StorageLeafAggregationJob>()
+            .pick_next_job(&params)
+            .await
+            .unwrap()?;
 
         let block_number = L1BatchNumber(row.l1_batch_number as u32);
         let proof_job_ids = self
@@ -472,7 +828,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             id as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_leaf_aggregation_job_attempts")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| row.attempts as u32);
@@ -507,7 +864,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             round as i16,
             depth as i32,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("prover_job_ids_for")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -518,35 +876,36 @@ impl FriWitnessGeneratorDal<'_, '_> {
     pub async fn move_leaf_aggregation_jobs_from_waiting_to_queued(&mut self) -> Vec<(i64, u8)> {
         sqlx::query!(
                 r#"
-                UPDATE leaf_aggregation_witness_jobs_fri
-                SET
-                    status = 'queued'
-                WHERE
-                    (l1_batch_number, circuit_id) IN (
-                        SELECT
-                            prover_jobs_fri.l1_batch_number,
-                            prover_jobs_fri.circuit_id
-                        FROM
-                            prover_jobs_fri
-                            JOIN leaf_aggregation_witness_jobs_fri lawj ON prover_jobs_fri.l1_batch_number = lawj.l1_batch_number
-                            AND prover_jobs_fri.circuit_id = lawj.circuit_id
-                        WHERE
-                            lawj.status = 'waiting_for_proofs'
-                            AND prover_jobs_fri.status = 'successful'
-                            AND prover_jobs_fri.aggregation_round = 0
-                        GROUP BY
-                            prover_jobs_fri.l1_batch_number,
-                            prover_jobs_fri.circuit_id,
-                            lawj.number_of_basic_circuits
-                        HAVING
-                            COUNT(*) = lawj.number_of_basic_circuits
-                    )
-                RETURNING
-                    l1_batch_number,
-                    circuit_id;
+            UPDATE leaf_aggregation_witness_jobs_fri
+            SET
+                status = 'queued'
+            WHERE
+                (l1_batch_number, circuit_id) IN (
+                    SELECT
+                        prover_jobs_fri.l1_batch_number,
+                        prover_jobs_fri.circuit_id
+                    FROM
+                        prover_jobs_fri
+                        JOIN leaf_aggregation_witness_jobs_fri lawj ON prover_jobs_fri.l1_batch_number = lawj.l1_batch_number
+                        AND prover_jobs_fri.circuit_id = lawj.circuit_id
+                    WHERE
+                        lawj.status = 'waiting_for_proofs'
+                        AND prover_jobs_fri.status = 'successful'
+                        AND prover_jobs_fri.aggregation_round = 0
+                    GROUP BY
+                        prover_jobs_fri.l1_batch_number,
+                        prover_jobs_fri.circuit_id,
+                        lawj.number_of_basic_circuits
+                    HAVING
+                        COUNT(*) = lawj.number_of_basic_circuits
+                )
+            RETURNING
+                l1_batch_number,
+                circuit_id;
                 "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("move_leaf_aggregation_jobs_from_waiting_to_queued")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -580,7 +939,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             depth as i32,
             number_of_dependent_jobs as i32,
         )
-        .execute(self.storage.conn())
+        .instrument("update_node_aggregation_jobs_url")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -590,43 +950,13 @@ impl FriWitnessGeneratorDal<'_, '_> {
         protocol_versions: &[FriProtocolVersionId],
         picked_by: &str,
     ) -> Option<NodeAggregationJobMetadata> {
-        let protocol_versions: Vec<i32> = protocol_versions.iter().map(|&id| id as i32).collect();
-        let row = sqlx::query!(
-            r#"
-            UPDATE node_aggregation_witness_jobs_fri
-            SET
-                status = 'in_progress',
-                attempts = attempts + 1,
-                updated_at = NOW(),
-                processing_started_at = NOW(),
-                picked_by = $2
-            WHERE
-                id = (
-                    SELECT
-                        id
-                    FROM
-                        node_aggregation_witness_jobs_fri
-                    WHERE
-                        status = 'queued'
-                        AND protocol_version = ANY ($1)
-                    ORDER BY
-                        l1_batch_number ASC,
-                        depth ASC,
-                        id ASC
-                    LIMIT
-                        1
-                    FOR UPDATE
-                        SKIP LOCKED
-                )
-            RETURNING
-                node_aggregation_witness_jobs_fri.*
-            "#,
-            &protocol_versions[..],
-            picked_by,
-        )
-        .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()?;
+        let params = WitnessJobPickParams::new(protocol_versions, picked_by);
+        let row = self
+            .storage
+            .job_queue_dal::<StorageNodeAggregationJob>()
+            .pick_next_job(&params)
+            .await
+            .unwrap()?;
         let depth = row.depth as u16;
 
         let round = match depth {
@@ -664,7 +994,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             id as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_node_aggregation_job_attempts")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| row.attempts as u32);
@@ -686,7 +1017,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             error,
             id as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_node_aggregation_job_failed")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -705,7 +1037,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             duration_to_naive_time(time_taken),
             id as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_node_aggregation_as_successful")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -747,7 +1080,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             number_of_dependent_jobs,
             protocol_version_id as i32,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("insert_node_aggregation_jobs")
+        .fetch_optional(self.storage)
         .await
         .unwrap();
     }
@@ -755,40 +1089,41 @@ impl FriWitnessGeneratorDal<'_, '_> {
     pub async fn move_depth_zero_node_aggregation_jobs(&mut self) -> Vec<(i64, u8, u16)> {
         sqlx::query!(
                 r#"
-                UPDATE node_aggregation_witness_jobs_fri
-                SET
-                    status = 'queued'
-                WHERE
-                    (l1_batch_number, circuit_id, depth) IN (
-                        SELECT
-                            prover_jobs_fri.l1_batch_number,
-                            prover_jobs_fri.circuit_id,
-                            prover_jobs_fri.depth
-                        FROM
-                            prover_jobs_fri
-                            JOIN node_aggregation_witness_jobs_fri nawj ON prover_jobs_fri.l1_batch_number = nawj.l1_batch_number
-                            AND prover_jobs_fri.circuit_id = nawj.circuit_id
-                            AND prover_jobs_fri.depth = nawj.depth
-                        WHERE
-                            nawj.status = 'waiting_for_proofs'
-                            AND prover_jobs_fri.status = 'successful'
-                            AND prover_jobs_fri.aggregation_round = 1
-                            AND prover_jobs_fri.depth = 0
-                        GROUP BY
-                            prover_jobs_fri.l1_batch_number,
-                            prover_jobs_fri.circuit_id,
-                            prover_jobs_fri.depth,
-                            nawj.number_of_dependent_jobs
-                        HAVING
-                            COUNT(*) = nawj.number_of_dependent_jobs
-                    )
-                RETURNING
-                    l1_batch_number,
-                    circuit_id,
-                    depth;
+            UPDATE node_aggregation_witness_jobs_fri
+            SET
+                status = 'queued'
+            WHERE
+                (l1_batch_number, circuit_id, depth) IN (
+                    SELECT
+                        prover_jobs_fri.l1_batch_number,
+                        prover_jobs_fri.circuit_id,
+                        prover_jobs_fri.depth
+                    FROM
+                        prover_jobs_fri
+                        JOIN node_aggregation_witness_jobs_fri nawj ON prover_jobs_fri.l1_batch_number = nawj.l1_batch_number
+                        AND prover_jobs_fri.circuit_id = nawj.circuit_id
+                        AND prover_jobs_fri.depth = nawj.depth
+                    WHERE
+                        nawj.status = 'waiting_for_proofs'
+                        AND prover_jobs_fri.status = 'successful'
+                        AND prover_jobs_fri.aggregation_round = 1
+                        AND prover_jobs_fri.depth = 0
+                    GROUP BY
+                        prover_jobs_fri.l1_batch_number,
+                        prover_jobs_fri.circuit_id,
+                        prover_jobs_fri.depth,
+                        nawj.number_of_dependent_jobs
+                    HAVING
+                        COUNT(*) = nawj.number_of_dependent_jobs
+                )
+            RETURNING
+                l1_batch_number,
+                circuit_id,
+                depth;
                 "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("move_depth_zero_node_aggregation_jobs")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -799,39 +1134,40 @@ impl FriWitnessGeneratorDal<'_, '_> {
     pub async fn move_depth_non_zero_node_aggregation_jobs(&mut self) -> Vec<(i64, u8, u16)> {
         sqlx::query!(
                 r#"
-                UPDATE node_aggregation_witness_jobs_fri
-                SET
-                    status = 'queued'
-                WHERE
-                    (l1_batch_number, circuit_id, depth) IN (
-                        SELECT
-                            prover_jobs_fri.l1_batch_number,
-                            prover_jobs_fri.circuit_id,
-                            prover_jobs_fri.depth
-                        FROM
-                            prover_jobs_fri
-                            JOIN node_aggregation_witness_jobs_fri nawj ON prover_jobs_fri.l1_batch_number = nawj.l1_batch_number
-                            AND prover_jobs_fri.circuit_id = nawj.circuit_id
-                            AND prover_jobs_fri.depth = nawj.depth
-                        WHERE
-                            nawj.status = 'waiting_for_proofs'
-                            AND prover_jobs_fri.status = 'successful'
-                            AND prover_jobs_fri.aggregation_round = 2
-                        GROUP BY
-                            prover_jobs_fri.l1_batch_number,
-                            prover_jobs_fri.circuit_id,
-                            prover_jobs_fri.depth,
-                            nawj.number_of_dependent_jobs
-                        HAVING
-                            COUNT(*) = nawj.number_of_dependent_jobs
-                    )
-                RETURNING
-                    l1_batch_number,
-                    circuit_id,
-                    depth;
+            UPDATE node_aggregation_witness_jobs_fri
+            SET
+                status = 'queued'
+            WHERE
+                (l1_batch_number, circuit_id, depth) IN (
+                    SELECT
+                        prover_jobs_fri.l1_batch_number,
+                        prover_jobs_fri.circuit_id,
+                        prover_jobs_fri.depth
+                    FROM
+                        prover_jobs_fri
+                        JOIN node_aggregation_witness_jobs_fri nawj ON prover_jobs_fri.l1_batch_number = nawj.l1_batch_number
+                        AND prover_jobs_fri.circuit_id = nawj.circuit_id
+                        AND prover_jobs_fri.depth = nawj.depth
+                    WHERE
+                        nawj.status = 'waiting_for_proofs'
+                        AND prover_jobs_fri.status = 'successful'
+                        AND prover_jobs_fri.aggregation_round = 2
+                    GROUP BY
+                        prover_jobs_fri.l1_batch_number,
+                        prover_jobs_fri.circuit_id,
+                        prover_jobs_fri.depth,
+                        nawj.number_of_dependent_jobs
+                    HAVING
+                        COUNT(*) = nawj.number_of_dependent_jobs
+                )
+            RETURNING
+                l1_batch_number,
+                circuit_id,
+                depth;
                 "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("move_depth_non_zero_node_aggregation_jobs")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -844,42 +1180,11 @@ impl FriWitnessGeneratorDal<'_, '_> {
         processing_timeout: Duration,
         max_attempts: u32,
     ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        sqlx::query!(
-            r#"
-            UPDATE leaf_aggregation_witness_jobs_fri
-            SET
-                status = 'queued',
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                (
-                    status = 'in_progress'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'failed'
-                    AND attempts < $2
-                )
-            RETURNING
-                id,
-                status,
-                attempts
-            "#,
-            &processing_timeout,
-            max_attempts as i32,
-        )
-        .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
-        .into_iter()
-        .map(|row| StuckJobs {
-            id: row.id as u64,
-            status: row.status,
-            attempts: row.attempts as u64,
-        })
-        .collect()
+        self.storage
+            .job_queue_dal::<StorageLeafAggregationJob>()
+            .requeue_stuck_jobs(processing_timeout, max_attempts)
+            .await
+            .unwrap()
     }
 
     pub async fn requeue_stuck_node_aggregations_jobs(
@@ -887,42 +1192,11 @@ impl FriWitnessGeneratorDal<'_, '_> {
         processing_timeout: Duration,
         max_attempts: u32,
     ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        sqlx::query!(
-            r#"
-            UPDATE node_aggregation_witness_jobs_fri
-            SET
-                status = 'queued',
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                (
-                    status = 'in_progress'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'failed'
-                    AND attempts < $2
-                )
-            RETURNING
-                id,
-                status,
-                attempts
-            "#,
-            &processing_timeout,
-            max_attempts as i32,
-        )
-        .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
-        .into_iter()
-        .map(|row| StuckJobs {
-            id: row.id as u64,
-            status: row.status,
-            attempts: row.attempts as u64,
-        })
-        .collect()
+        self.storage
+            .job_queue_dal::<StorageNodeAggregationJob>()
+            .requeue_stuck_jobs(processing_timeout, max_attempts)
+            .await
+            .unwrap()
     }
 
     pub async fn mark_scheduler_jobs_as_queued(&mut self, l1_batch_number: i64) {
@@ -938,7 +1212,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             l1_batch_number
         )
-        .execute(self.storage.conn())
+        .instrument("mark_scheduler_jobs_as_queued")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -948,42 +1223,11 @@ impl FriWitnessGeneratorDal<'_, '_> {
         processing_timeout: Duration,
         max_attempts: u32,
     ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        sqlx::query!(
-            r#"
-            UPDATE scheduler_witness_jobs_fri
-            SET
-                status = 'queued',
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                (
-                    status = 'in_progress'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'failed'
-                    AND attempts < $2
-                )
-            RETURNING
-                l1_batch_number,
-                status,
-                attempts
-            "#,
-            &processing_timeout,
-            max_attempts as i32,
-        )
-        .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
-        .into_iter()
-        .map(|row| StuckJobs {
-            id: row.l1_batch_number as u64,
-            status: row.status,
-            attempts: row.attempts as u64,
-        })
-        .collect()
+        self.storage
+            .job_queue_dal::<StorageSchedulerWitnessJob>()
+            .requeue_stuck_jobs(processing_timeout, max_attempts)
+            .await
+            .unwrap()
     }
 
     pub async fn get_next_scheduler_witness_job(
@@ -991,42 +1235,14 @@ impl FriWitnessGeneratorDal<'_, '_> {
         protocol_versions: &[FriProtocolVersionId],
         picked_by: &str,
     ) -> Option<L1BatchNumber> {
-        let protocol_versions: Vec<i32> = protocol_versions.iter().map(|&id| id as i32).collect();
-        sqlx::query!(
-            r#"
-            UPDATE scheduler_witness_jobs_fri
-            SET
-                status = 'in_progress',
-                attempts = attempts + 1,
-                updated_at = NOW(),
-                processing_started_at = NOW(),
-                picked_by = $2
-            WHERE
-                l1_batch_number = (
-                    SELECT
-                        l1_batch_number
-                    FROM
-                        scheduler_witness_jobs_fri
-                    WHERE
-                        status = 'queued'
-                        AND protocol_version = ANY ($1)
-                    ORDER BY
-                        l1_batch_number ASC
-                    LIMIT
-                        1
-                    FOR UPDATE
-                        SKIP LOCKED
-                )
-            RETURNING
-                scheduler_witness_jobs_fri.*
-            "#,
-            &protocol_versions[..],
-            picked_by,
-        )
-        .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()
-        .map(|row| L1BatchNumber(row.l1_batch_number as u32))
+        let params = WitnessJobPickParams::new(protocol_versions, picked_by);
+        let job = self
+            .storage
+            .job_queue_dal::<StorageSchedulerWitnessJob>()
+            .pick_next_job(&params)
+            .await
+            .unwrap()?;
+        Some(L1BatchNumber(job.l1_batch_number as u32))
     }
 
     pub async fn get_scheduler_witness_job_attempts(
@@ -1044,7 +1260,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_scheduler_witness_job_attempts")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.attempts as u32);
 
@@ -1069,7 +1286,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             duration_to_naive_time(time_taken),
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_scheduler_job_as_successful")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -1088,7 +1306,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             error,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_scheduler_job_failed")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -1107,7 +1326,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             table_name
         );
         let mut results: HashMap<String, i64> = sqlx::query(&sql)
-            .fetch_all(self.storage.conn())
+            .instrument("get_witness_jobs_stats")
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
@@ -1146,7 +1366,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_one(self.storage.conn())
+        .instrument("protocol_version_for_l1_batch")
+        .fetch_one(self.storage)
         .await
         .unwrap()
         .protocol_version
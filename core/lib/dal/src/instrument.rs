@@ -2,20 +2,24 @@
 //!
 //! Query instrumentation allows to:
 //!
-//! - Report query latency as a metric
+//! - Report query latency as a metric (labeled by the query name)
 //! - Report slow and failing queries as metrics
 //! - Log slow and failing queries together with their arguments, which makes it easier to debug.
 //!
 //! The entry point for instrumentation is the [`InstrumentExt`] trait. After it is imported into the scope,
 //! its `instrument()` method can be placed on the output of `query*` functions or macros. You can then call
-//! [`Instrumented`] methods on the returned struct, e.g. to [report query latency](Instrumented::report_latency())
-//! and/or [to add logged args](Instrumented::with_arg()) for a query.
+//! [`Instrumented`] methods on the returned struct, e.g. [to add logged args](Instrumented::with_arg()) for a query.
+//! All DAL queries should be instrumented, so that a regression in a specific query can be pinpointed using metrics
+//! and logs without enabling statement logging on the Postgres side.
+//!
+//! The slow query threshold is configured globally via [`ConnectionPool::global_config()`].
 
 use std::{fmt, future::Future, panic::Location};
 
 use sqlx::{
     postgres::{PgQueryResult, PgRow},
     query::{Map, Query, QueryAs},
+    query_scalar::QueryScalar,
     FromRow, IntoArguments, Postgres,
 };
 use tokio::time::Instant;
@@ -82,6 +86,19 @@ where
     }
 }
 
+impl<'q, O, A> InstrumentExt for QueryScalar<'q, Postgres, O, A>
+where
+    A: 'q + IntoArguments<'q, Postgres>,
+{
+    #[track_caller]
+    fn instrument(self, name: &'static str) -> Instrumented<'static, Self> {
+        Instrumented {
+            query: self,
+            data: InstrumentedData::new(name, Location::caller()),
+        }
+    }
+}
+
 impl<'q, F, O, A> InstrumentExt for Map<'q, Postgres, F, A>
 where
     F: FnMut(PgRow) -> Result<O, sqlx::Error> + Send,
@@ -102,7 +119,6 @@ struct InstrumentedData<'a> {
     name: &'static str,
     location: &'static Location<'static>,
     args: QueryArgs<'a>,
-    report_latency: bool,
     slow_query_reporting_enabled: bool,
 }
 
@@ -112,11 +128,13 @@ impl<'a> InstrumentedData<'a> {
             name,
             location,
             args: QueryArgs::default(),
-            report_latency: false,
             slow_query_reporting_enabled: true,
         }
     }
 
+    // The span is nested in the span of the caller (e.g., an API server call with the request ID), so logs produced
+    // while executing the query can be correlated with the caller.
+    #[tracing::instrument(name = "query", level = "debug", skip_all, fields(name = self.name))]
     async fn fetch<R>(
         self,
         connection_tags: Option<&StorageProcessorTags>,
@@ -126,7 +144,6 @@ impl<'a> InstrumentedData<'a> {
             name,
             location,
             args,
-            report_latency,
             slow_query_reporting_enabled,
         } = self;
         let started_at = Instant::now();
@@ -154,9 +171,7 @@ impl<'a> InstrumentedData<'a> {
         };
 
         let elapsed = started_at.elapsed();
-        if report_latency {
-            REQUEST_METRICS.request[&name].observe(elapsed);
-        }
+        REQUEST_METRICS.request[&name].observe(elapsed);
 
         let connection_tags = StorageProcessorTags::display(connection_tags);
         if let Err(err) = &output {
@@ -182,6 +197,7 @@ impl<'a> InstrumentedData<'a> {
 ///
 /// The following instrumentation logic is included:
 ///
+/// - Query latency is reported as a metric (`sql.request`) with the query name as a label.
 /// - If the query executes for too long, it is logged with a `WARN` level. The logged info includes
 ///   the query name, its args provided via [Self::with_arg()`] and the caller location.
 /// - If the query returns an error, it is logged with a `WARN` level. The logged info is everything
@@ -195,12 +211,7 @@ pub(crate) struct Instrumented<'a, Q> {
 }
 
 impl<'a, Q> Instrumented<'a, Q> {
-    /// Indicates that latency should be reported for all calls.
-    pub fn report_latency(mut self) -> Self {
-        self.data.report_latency = true;
-        self
-    }
-
+    /// Indicates that the query is expected to be slow, so it shouldn't be logged or reported as slow.
     pub fn expect_slow_query(mut self) -> Self {
         self.data.slow_query_reporting_enabled = false;
         self
@@ -232,6 +243,18 @@ where
         let (conn, tags) = storage.conn_and_tags();
         self.data.fetch(tags, self.query.fetch_optional(conn)).await
     }
+
+    /// Fetches a single row using this query.
+    pub async fn fetch_one(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<PgRow> {
+        let (conn, tags) = storage.conn_and_tags();
+        self.data.fetch(tags, self.query.fetch_one(conn)).await
+    }
+
+    /// Fetches all rows using this query and collects them into a `Vec`.
+    pub async fn fetch_all(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<Vec<PgRow>> {
+        let (conn, tags) = storage.conn_and_tags();
+        self.data.fetch(tags, self.query.fetch_all(conn)).await
+    }
 }
 
 impl<'q, O, A> Instrumented<'_, QueryAs<'q, Postgres, O, A>>
@@ -246,6 +269,28 @@ where
     }
 }
 
+impl<'q, O, A> Instrumented<'_, QueryScalar<'q, Postgres, O, A>>
+where
+    A: 'q + IntoArguments<'q, Postgres>,
+    O: Send + Unpin,
+    (O,): Send + Unpin + for<'r> FromRow<'r, PgRow>,
+{
+    /// Fetches an optional value using this query.
+    pub async fn fetch_optional(
+        self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> sqlx::Result<Option<O>> {
+        let (conn, tags) = storage.conn_and_tags();
+        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+    }
+
+    /// Fetches a single value using this query.
+    pub async fn fetch_one(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<O> {
+        let (conn, tags) = storage.conn_and_tags();
+        self.data.fetch(tags, self.query.fetch_one(conn)).await
+    }
+}
+
 impl<'q, F, O, A> Instrumented<'_, Map<'q, Postgres, F, A>>
 where
     F: FnMut(PgRow) -> Result<O, sqlx::Error> + Send,
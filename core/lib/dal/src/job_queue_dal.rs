@@ -0,0 +1,158 @@
+//! Generic job queue backed by a Postgres table.
+
+use std::{marker::PhantomData, time::Duration};
+
+use futures::future::BoxFuture;
+use sqlx::postgres::types::PgInterval;
+
+use crate::{
+    fri_prover_dal::types::StuckJobs, time_utils::pg_interval_from_duration, StorageProcessor,
+};
+
+/// Job stored in a Postgres table used as a job queue.
+///
+/// Queries are implemented for each table separately using `sqlx::query!` macros, so that they are checked
+/// at compile time; [`JobQueueDal`] provides a uniform interface on top of them. Jobs must be picked
+/// with `FOR UPDATE SKIP LOCKED`, so that concurrent workers never pick the same job.
+pub trait QueuedJob: Sized + Send {
+    /// Parameters of picking a job, such as timeouts or protocol versions supported by the worker.
+    type PickParams: Send + Sync;
+
+    /// Picks the next job in the priority order and marks it as in progress, incrementing its attempts.
+    fn pick_next<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        params: &'a Self::PickParams,
+    ) -> BoxFuture<'a, sqlx::Result<Option<Self>>>;
+
+    /// Returns jobs in progress for longer than `processing_timeout`, and failed jobs, back to the queue
+    /// provided that they have fewer than `max_attempts` attempts.
+    fn requeue_stuck<'a, 'c>(
+        storage: &'a mut StorageProcessor<'c>,
+        processing_timeout: PgInterval,
+        max_attempts: u32,
+    ) -> BoxFuture<'a, sqlx::Result<Vec<StuckJobs>>>;
+}
+
+/// Parameters of picking jobs for queues that pick stale and failed jobs along with queued ones.
+#[derive(Debug, Clone, Copy)]
+pub struct JobQueueParams {
+    /// Jobs in progress for longer than this timeout are considered stale (e.g., because the processing
+    /// component has crashed) and can be picked again.
+    pub processing_timeout: Duration,
+    /// Maximum number of attempts to process a job. Failed jobs with fewer attempts are retried;
+    /// if set to `None`, failed jobs are never retried.
+    pub max_attempts: Option<u32>,
+}
+
+impl JobQueueParams {
+    pub fn new(processing_timeout: Duration) -> Self {
+        Self {
+            processing_timeout,
+            max_attempts: None,
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    pub(crate) fn pg_processing_timeout(&self) -> PgInterval {
+        pg_interval_from_duration(self.processing_timeout)
+    }
+
+    /// Returns the maximum number of attempts to use in queries; 0 means that failed jobs are never retried.
+    pub(crate) fn max_attempts_or_zero(&self) -> u32 {
+        self.max_attempts.unwrap_or(0)
+    }
+}
+
+/// DAL for a job queue storing jobs of type `T`.
+#[derive(Debug)]
+pub struct JobQueueDal<'a, 'c, T> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+    pub(crate) _job: PhantomData<T>,
+}
+
+impl<T: QueuedJob> JobQueueDal<'_, '_, T> {
+    /// Picks the next job and marks it as in progress.
+    pub async fn pick_next_job(&mut self, params: &T::PickParams) -> sqlx::Result<Option<T>> {
+        T::pick_next(self.storage, params).await
+    }
+
+    /// Returns stuck jobs back to the queue. Returns the requeued jobs.
+    pub async fn requeue_stuck_jobs(
+        &mut self,
+        processing_timeout: Duration,
+        max_attempts: u32,
+    ) -> sqlx::Result<Vec<StuckJobs>> {
+        let processing_timeout = pg_interval_from_duration(processing_timeout);
+        T::requeue_stuck(self.storage, processing_timeout, max_attempts).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use zksync_types::L1BatchNumber;
+
+    use super::*;
+    use crate::{
+        basic_witness_input_producer_dal::StorageBasicWitnessInputProducerJob as TestJob,
+        ConnectionPool,
+    };
+
+    async fn pick_next_job(
+        conn: &mut StorageProcessor<'_>,
+        params: &JobQueueParams,
+    ) -> Option<i64> {
+        let job = conn
+            .job_queue_dal::<TestJob>()
+            .pick_next_job(params)
+            .await
+            .unwrap();
+        job.map(|job| job.l1_batch_number)
+    }
+
+    #[tokio::test]
+    async fn picking_and_requeuing_jobs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        for number in 1..=2 {
+            conn.basic_witness_input_producer_dal()
+                .create_basic_witness_input_producer_job(L1BatchNumber(number))
+                .await
+                .unwrap();
+        }
+        let params = JobQueueParams::new(Duration::from_secs(3_600)).with_max_attempts(2);
+        assert_eq!(pick_next_job(&mut conn, &params).await, Some(1));
+        assert_eq!(pick_next_job(&mut conn, &params).await, Some(2));
+        assert_eq!(pick_next_job(&mut conn, &params).await, None);
+
+        conn.basic_witness_input_producer_dal()
+            .mark_job_as_failed(L1BatchNumber(2), Instant::now(), "oops".to_owned())
+            .await
+            .unwrap();
+        assert_eq!(pick_next_job(&mut conn, &params).await, Some(2));
+        conn.basic_witness_input_producer_dal()
+            .mark_job_as_failed(L1BatchNumber(2), Instant::now(), "oops".to_owned())
+            .await
+            .unwrap();
+        // The job has exhausted its attempts.
+        assert_eq!(pick_next_job(&mut conn, &params).await, None);
+
+        // Job #1 is still in progress.
+        let requeued_jobs = conn
+            .job_queue_dal::<TestJob>()
+            .requeue_stuck_jobs(Duration::ZERO, 2)
+            .await
+            .unwrap();
+        assert_eq!(requeued_jobs.len(), 1);
+        assert_eq!(requeued_jobs[0].id, 1);
+        assert_eq!(pick_next_job(&mut conn, &params).await, Some(1));
+
+        let params = JobQueueParams::new(Duration::ZERO);
+        assert_eq!(pick_next_job(&mut conn, &params).await, Some(1));
+    }
+}
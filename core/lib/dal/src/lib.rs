@@ -1,20 +1,26 @@
 //! Data access layer (DAL) for zkSync Era.
 
+use std::marker::PhantomData;
+
 pub use sqlx::{types::BigDecimal, Error as SqlxError};
 
-pub use crate::connection::{ConnectionPool, StorageProcessor};
+pub use crate::connection::{
+    ConnectionPool, PoolComponent, StorageProcessor, TransactionRetryPolicy,
+};
 use crate::{
-    basic_witness_input_producer_dal::BasicWitnessInputProducerDal, blocks_dal::BlocksDal,
-    blocks_web3_dal::BlocksWeb3Dal, consensus_dal::ConsensusDal,
-    contract_verification_dal::ContractVerificationDal, eth_sender_dal::EthSenderDal,
-    events_dal::EventsDal, events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
+    api_filters_dal::ApiFiltersDal, basic_witness_input_producer_dal::BasicWitnessInputProducerDal,
+    blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal, consensus_dal::ConsensusDal,
+    consistency_dal::ConsistencyDal, contract_verification_dal::ContractVerificationDal,
+    eth_sender_dal::EthSenderDal, events_dal::EventsDal, events_web3_dal::EventsWeb3Dal,
+    factory_deps_dal::FactoryDepsDal, fee_account_dal::FeeAccountDal,
     fri_gpu_prover_queue_dal::FriGpuProverQueueDal,
     fri_proof_compressor_dal::FriProofCompressorDal,
     fri_protocol_versions_dal::FriProtocolVersionsDal, fri_prover_dal::FriProverDal,
     fri_scheduler_dependency_tracker_dal::FriSchedulerDependencyTrackerDal,
-    fri_witness_generator_dal::FriWitnessGeneratorDal, proof_generation_dal::ProofGenerationDal,
+    fri_witness_generator_dal::FriWitnessGeneratorDal, job_queue_dal::JobQueueDal,
+    partitions_dal::PartitionsDal, proof_generation_dal::ProofGenerationDal,
     protocol_versions_dal::ProtocolVersionsDal,
-    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal,
+    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, pruning_dal::PruningDal,
     snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
     snapshots_dal::SnapshotsDal, storage_logs_dal::StorageLogsDal,
     storage_logs_dedup_dal::StorageLogsDedupDal, storage_web3_dal::StorageWeb3Dal,
@@ -25,16 +31,19 @@ use crate::{
 
 #[macro_use]
 mod macro_utils;
+pub mod api_filters_dal;
 pub mod basic_witness_input_producer_dal;
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
 pub mod connection;
 pub mod consensus_dal;
+pub mod consistency_dal;
 pub mod contract_verification_dal;
 pub mod eth_sender_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
 pub mod factory_deps_dal;
+pub mod fee_account_dal;
 pub mod fri_gpu_prover_queue_dal;
 pub mod fri_proof_compressor_dal;
 pub mod fri_protocol_versions_dal;
@@ -43,11 +52,14 @@ pub mod fri_scheduler_dependency_tracker_dal;
 pub mod fri_witness_generator_dal;
 pub mod healthcheck;
 mod instrument;
+pub mod job_queue_dal;
 mod metrics;
 mod models;
+pub mod partitions_dal;
 pub mod proof_generation_dal;
 pub mod protocol_versions_dal;
 pub mod protocol_versions_web3_dal;
+pub mod pruning_dal;
 pub mod snapshot_recovery_dal;
 pub mod snapshots_creator_dal;
 pub mod snapshots_dal;
@@ -137,6 +149,10 @@ impl<'a> StorageProcessor<'a> {
         ContractVerificationDal { storage: self }
     }
 
+    pub fn consistency_dal(&mut self) -> ConsistencyDal<'_, 'a> {
+        ConsistencyDal { storage: self }
+    }
+
     pub fn protocol_versions_dal(&mut self) -> ProtocolVersionsDal<'_, 'a> {
         ProtocolVersionsDal { storage: self }
     }
@@ -183,6 +199,21 @@ impl<'a> StorageProcessor<'a> {
         SystemDal { storage: self }
     }
 
+    pub fn pruning_dal(&mut self) -> PruningDal<'_, 'a> {
+        PruningDal { storage: self }
+    }
+
+    pub fn partitions_dal(&mut self) -> PartitionsDal<'_, 'a> {
+        PartitionsDal { storage: self }
+    }
+
+    pub fn job_queue_dal<T: job_queue_dal::QueuedJob>(&mut self) -> JobQueueDal<'_, 'a, T> {
+        JobQueueDal {
+            storage: self,
+            _job: PhantomData,
+        }
+    }
+
     pub fn snapshots_dal(&mut self) -> SnapshotsDal<'_, 'a> {
         SnapshotsDal { storage: self }
     }
@@ -194,4 +225,12 @@ impl<'a> StorageProcessor<'a> {
     pub fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a> {
         SnapshotRecoveryDal { storage: self }
     }
+
+    pub fn api_filters_dal(&mut self) -> ApiFiltersDal<'_, 'a> {
+        ApiFiltersDal { storage: self }
+    }
+
+    pub fn fee_account_dal(&mut self) -> FeeAccountDal<'_, 'a> {
+        FeeAccountDal { storage: self }
+    }
 }
@@ -8,7 +8,7 @@ use vise::{
     LatencyObserver, Metrics, Unit,
 };
 
-use crate::ConnectionPool;
+use crate::{connection::PoolComponent, ConnectionPool};
 
 /// Request-related DB metrics.
 #[derive(Debug, Metrics)]
@@ -23,13 +23,16 @@ pub(crate) struct RequestMetrics {
     /// Counter of errored DB requests.
     #[metrics(labels = ["method"])]
     pub request_error: LabeledFamily<&'static str, Counter>,
+    /// Counter of DB transaction retries after transient errors.
+    #[metrics(labels = ["transaction"])]
+    pub transaction_retry: LabeledFamily<&'static str, Counter>,
 }
 
 #[vise::register]
 pub(crate) static REQUEST_METRICS: vise::Global<RequestMetrics> = vise::Global::new();
 
 /// Reporter of latency for DAL methods consisting of multiple DB queries. If there's a single query,
-/// use `.instrument().report_latency()` on it instead.
+/// use `.instrument()` on it instead.
 ///
 /// Should be created at the start of the relevant method and dropped when the latency needs to be reported.
 #[derive(Debug)]
@@ -92,9 +95,16 @@ pub(crate) struct ConnectionMetrics {
     pub pool_idle: Histogram<usize>,
     /// Number of errors occurred when acquiring a DB connection.
     pub pool_acquire_error: Family<ConnectionErrorKind, Counter>,
+    /// Number of times a connection was acquired from the failover database because all replicas were unavailable.
+    pub pool_failover: Counter,
     /// Lifetime of a DB connection, tagged with the requester label.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds, labels = ["requester"])]
     pub lifetime: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Latency of acquiring a connection from a dedicated component pool.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub component_acquire: Family<PoolComponent, Histogram<Duration>>,
+    /// Number of connections held by a dedicated component pool, sampled when a connection is acquired.
+    pub component_connections: Family<PoolComponent, Gauge<usize>>,
 }
 
 #[vise::register]
@@ -1,4 +1,4 @@
-use std::{convert::TryInto, str::FromStr};
+use std::str::FromStr;
 
 use bigdecimal::{BigDecimal, ToPrimitive};
 use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
@@ -10,13 +10,113 @@ use zksync_types::{
     commitment::{L1BatchMetaParameters, L1BatchMetadata},
     fee_model::{BatchFeeInput, L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput},
     l2_to_l1_log::{L2ToL1Log, SystemL2ToL1Log, UserL2ToL1Log},
-    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H2048, H256,
+    Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H2048, H256, U256,
 };
 
+/// Errors that can occur when converting raw DB rows to domain types.
+///
+/// These errors are mostly caused by rows created before a certain protocol upgrade (e.g., with `NULL`s
+/// in columns that are populated for all new rows), so they should be reported rather than panicking.
 #[derive(Debug, Error)]
-pub enum StorageL1BatchConvertError {
-    #[error("Incomplete L1 batch")]
-    Incomplete,
+pub enum StorageConversionError {
+    #[error("column `{0}` is unexpectedly NULL")]
+    MissingField(&'static str),
+    #[error("column `{field}` has invalid length for a hash: expected 32 bytes, got {len}")]
+    InvalidHashLength { field: &'static str, len: usize },
+    #[error("column `{field}` has invalid length for an address: expected 20 bytes, got {len}")]
+    InvalidAddressLength { field: &'static str, len: usize },
+    #[error("column `{field}` has invalid length for a bloom: expected 256 bytes, got {len}")]
+    InvalidBloomLength { field: &'static str, len: usize },
+    #[error("column `{field}` contains malformed transaction hash `{value}`")]
+    InvalidTxHash { field: &'static str, value: String },
+    #[error("column `{field}` contains unknown protocol version {value}")]
+    UnknownProtocolVersion { field: &'static str, value: i32 },
+    #[error("column `{field}` contains invalid JSON: {source}")]
+    InvalidJson {
+        field: &'static str,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("column `{field}` has out-of-range value: {value}")]
+    OutOfRange { field: &'static str, value: String },
+}
+
+impl From<StorageConversionError> for sqlx::Error {
+    fn from(err: StorageConversionError) -> Self {
+        Self::Decode(Box::new(err))
+    }
+}
+
+fn parse_hash(field: &'static str, bytes: &[u8]) -> Result<H256, StorageConversionError> {
+    if bytes.len() != 32 {
+        return Err(StorageConversionError::InvalidHashLength {
+            field,
+            len: bytes.len(),
+        });
+    }
+    Ok(H256::from_slice(bytes))
+}
+
+fn parse_required_hash(
+    field: &'static str,
+    bytes: Option<Vec<u8>>,
+) -> Result<H256, StorageConversionError> {
+    let bytes = bytes.ok_or(StorageConversionError::MissingField(field))?;
+    parse_hash(field, &bytes)
+}
+
+fn parse_bloom(field: &'static str, bytes: &[u8]) -> Result<H2048, StorageConversionError> {
+    if bytes.len() != 256 {
+        return Err(StorageConversionError::InvalidBloomLength {
+            field,
+            len: bytes.len(),
+        });
+    }
+    Ok(H2048::from_slice(bytes))
+}
+
+fn parse_address(field: &'static str, bytes: &[u8]) -> Result<Address, StorageConversionError> {
+    if bytes.len() != 20 {
+        return Err(StorageConversionError::InvalidAddressLength {
+            field,
+            len: bytes.len(),
+        });
+    }
+    Ok(Address::from_slice(bytes))
+}
+
+fn parse_tx_hash(
+    field: &'static str,
+    hash: Option<String>,
+) -> Result<Option<H256>, StorageConversionError> {
+    hash.map(|hash| {
+        H256::from_str(&hash)
+            .map_err(|_| StorageConversionError::InvalidTxHash { field, value: hash })
+    })
+    .transpose()
+}
+
+fn parse_protocol_version(
+    field: &'static str,
+    version: Option<i32>,
+) -> Result<Option<ProtocolVersionId>, StorageConversionError> {
+    version
+        .map(|value| {
+            u16::try_from(value)
+                .ok()
+                .and_then(|v| ProtocolVersionId::try_from(v).ok())
+                .ok_or(StorageConversionError::UnknownProtocolVersion { field, value })
+        })
+        .transpose()
+}
+
+fn parse_used_contract_hashes(
+    value: serde_json::Value,
+) -> Result<Vec<U256>, StorageConversionError> {
+    serde_json::from_value(value).map_err(|source| StorageConversionError::InvalidJson {
+        field: "used_contract_hashes",
+        source,
+    })
 }
 
 /// Projection of the `l1_batches` table corresponding to [`L1BatchHeader`].
@@ -45,8 +145,10 @@ pub struct StorageL1BatchHeader {
     pub pubdata_input: Option<Vec<u8>>,
 }
 
-impl From<StorageL1BatchHeader> for L1BatchHeader {
-    fn from(l1_batch: StorageL1BatchHeader) -> Self {
+impl TryFrom<StorageL1BatchHeader> for L1BatchHeader {
+    type Error = StorageConversionError;
+
+    fn try_from(l1_batch: StorageL1BatchHeader) -> Result<Self, Self::Error> {
         let priority_ops_onchain_data: Vec<_> = l1_batch
             .priority_ops_onchain_data
             .into_iter()
@@ -56,7 +158,7 @@ impl From<StorageL1BatchHeader> for L1BatchHeader {
         let system_logs = convert_l2_to_l1_logs(l1_batch.system_logs);
         let user_l2_to_l1_logs = convert_l2_to_l1_logs(l1_batch.l2_to_l1_logs);
 
-        L1BatchHeader {
+        Ok(L1BatchHeader {
             number: L1BatchNumber(l1_batch.number as u32),
             timestamp: l1_batch.timestamp as u64,
             priority_ops_onchain_data,
@@ -65,19 +167,19 @@ impl From<StorageL1BatchHeader> for L1BatchHeader {
             l2_to_l1_logs: user_l2_to_l1_logs.into_iter().map(UserL2ToL1Log).collect(),
             l2_to_l1_messages: l1_batch.l2_to_l1_messages,
 
-            bloom: H2048::from_slice(&l1_batch.bloom),
-            used_contract_hashes: serde_json::from_value(l1_batch.used_contract_hashes)
-                .expect("invalid value for used_contract_hashes in the DB"),
+            bloom: parse_bloom("bloom", &l1_batch.bloom)?,
+            used_contract_hashes: parse_used_contract_hashes(l1_batch.used_contract_hashes)?,
             base_system_contracts_hashes: convert_base_system_contracts_hashes(
                 l1_batch.bootloader_code_hash,
                 l1_batch.default_aa_code_hash,
-            ),
+            )?,
             system_logs: system_logs.into_iter().map(SystemL2ToL1Log).collect(),
-            protocol_version: l1_batch
-                .protocol_version
-                .map(|v| (v as u16).try_into().unwrap()),
+            protocol_version: parse_protocol_version(
+                "protocol_version",
+                l1_batch.protocol_version,
+            )?,
             pubdata_input: l1_batch.pubdata_input,
-        }
+        })
     }
 }
 
@@ -92,15 +194,11 @@ fn convert_l2_to_l1_logs(raw_logs: Vec<Vec<u8>>) -> Vec<L2ToL1Log> {
 fn convert_base_system_contracts_hashes(
     bootloader_code_hash: Option<Vec<u8>>,
     default_aa_code_hash: Option<Vec<u8>>,
-) -> BaseSystemContractsHashes {
-    BaseSystemContractsHashes {
-        bootloader: bootloader_code_hash
-            .map(|hash| H256::from_slice(&hash))
-            .expect("should not be none"),
-        default_aa: default_aa_code_hash
-            .map(|hash| H256::from_slice(&hash))
-            .expect("should not be none"),
-    }
+) -> Result<BaseSystemContractsHashes, StorageConversionError> {
+    Ok(BaseSystemContractsHashes {
+        bootloader: parse_required_hash("bootloader_code_hash", bootloader_code_hash)?,
+        default_aa: parse_required_hash("default_aa_code_hash", default_aa_code_hash)?,
+    })
 }
 
 /// Projection of the columns corresponding to [`L1BatchHeader`] + [`L1BatchMetadata`].
@@ -148,8 +246,10 @@ pub struct StorageL1Batch {
     pub pubdata_input: Option<Vec<u8>>,
 }
 
-impl From<StorageL1Batch> for L1BatchHeader {
-    fn from(l1_batch: StorageL1Batch) -> Self {
+impl TryFrom<StorageL1Batch> for L1BatchHeader {
+    type Error = StorageConversionError;
+
+    fn try_from(l1_batch: StorageL1Batch) -> Result<Self, Self::Error> {
         let priority_ops_onchain_data: Vec<_> = l1_batch
             .priority_ops_onchain_data
             .into_iter()
@@ -159,7 +259,7 @@ impl From<StorageL1Batch> for L1BatchHeader {
         let system_logs = convert_l2_to_l1_logs(l1_batch.system_logs);
         let user_l2_to_l1_logs = convert_l2_to_l1_logs(l1_batch.l2_to_l1_logs);
 
-        L1BatchHeader {
+        Ok(L1BatchHeader {
             number: L1BatchNumber(l1_batch.number as u32),
             timestamp: l1_batch.timestamp as u64,
             priority_ops_onchain_data,
@@ -168,84 +268,67 @@ impl From<StorageL1Batch> for L1BatchHeader {
             l2_to_l1_logs: user_l2_to_l1_logs.into_iter().map(UserL2ToL1Log).collect(),
             l2_to_l1_messages: l1_batch.l2_to_l1_messages,
 
-            bloom: H2048::from_slice(&l1_batch.bloom),
-            used_contract_hashes: serde_json::from_value(l1_batch.used_contract_hashes)
-                .expect("invalid value for used_contract_hashes in the DB"),
+            bloom: parse_bloom("bloom", &l1_batch.bloom)?,
+            used_contract_hashes: parse_used_contract_hashes(l1_batch.used_contract_hashes)?,
             base_system_contracts_hashes: convert_base_system_contracts_hashes(
                 l1_batch.bootloader_code_hash,
                 l1_batch.default_aa_code_hash,
-            ),
+            )?,
             system_logs: system_logs.into_iter().map(SystemL2ToL1Log).collect(),
-            protocol_version: l1_batch
-                .protocol_version
-                .map(|v| (v as u16).try_into().unwrap()),
+            protocol_version: parse_protocol_version(
+                "protocol_version",
+                l1_batch.protocol_version,
+            )?,
             pubdata_input: l1_batch.pubdata_input,
-        }
+        })
     }
 }
 
-impl TryInto<L1BatchMetadata> for StorageL1Batch {
-    type Error = StorageL1BatchConvertError;
+impl TryFrom<StorageL1Batch> for L1BatchMetadata {
+    type Error = StorageConversionError;
 
-    fn try_into(self) -> Result<L1BatchMetadata, Self::Error> {
+    fn try_from(batch: StorageL1Batch) -> Result<Self, Self::Error> {
         Ok(L1BatchMetadata {
-            root_hash: H256::from_slice(&self.hash.ok_or(StorageL1BatchConvertError::Incomplete)?),
-            rollup_last_leaf_index: self
-                .rollup_last_leaf_index
-                .ok_or(StorageL1BatchConvertError::Incomplete)?
-                as u64,
-            merkle_root_hash: H256::from_slice(
-                &self
-                    .merkle_root_hash
-                    .ok_or(StorageL1BatchConvertError::Incomplete)?,
-            ),
-            initial_writes_compressed: self.compressed_initial_writes,
-            repeated_writes_compressed: self.compressed_repeated_writes,
-            l2_l1_merkle_root: H256::from_slice(
-                &self
-                    .l2_l1_merkle_root
-                    .ok_or(StorageL1BatchConvertError::Incomplete)?,
-            ),
-            aux_data_hash: H256::from_slice(
-                &self
-                    .aux_data_hash
-                    .ok_or(StorageL1BatchConvertError::Incomplete)?,
-            ),
-            meta_parameters_hash: H256::from_slice(
-                &self
-                    .meta_parameters_hash
-                    .ok_or(StorageL1BatchConvertError::Incomplete)?,
-            ),
-            pass_through_data_hash: H256::from_slice(
-                &self
-                    .pass_through_data_hash
-                    .ok_or(StorageL1BatchConvertError::Incomplete)?,
-            ),
-            commitment: H256::from_slice(
-                &self
-                    .commitment
-                    .ok_or(StorageL1BatchConvertError::Incomplete)?,
-            ),
+            root_hash: parse_required_hash("hash", batch.hash)?,
+            rollup_last_leaf_index: batch.rollup_last_leaf_index.ok_or(
+                StorageConversionError::MissingField("rollup_last_leaf_index"),
+            )? as u64,
+            merkle_root_hash: parse_required_hash("merkle_root_hash", batch.merkle_root_hash)?,
+            initial_writes_compressed: batch.compressed_initial_writes,
+            repeated_writes_compressed: batch.compressed_repeated_writes,
+            l2_l1_merkle_root: parse_required_hash("l2_l1_merkle_root", batch.l2_l1_merkle_root)?,
+            aux_data_hash: parse_required_hash("aux_data_hash", batch.aux_data_hash)?,
+            meta_parameters_hash: parse_required_hash(
+                "meta_parameters_hash",
+                batch.meta_parameters_hash,
+            )?,
+            pass_through_data_hash: parse_required_hash(
+                "pass_through_data_hash",
+                batch.pass_through_data_hash,
+            )?,
+            commitment: parse_required_hash("commitment", batch.commitment)?,
             block_meta_params: L1BatchMetaParameters {
-                zkporter_is_available: self
-                    .zkporter_is_available
-                    .ok_or(StorageL1BatchConvertError::Incomplete)?,
-                bootloader_code_hash: H256::from_slice(
-                    &self
-                        .bootloader_code_hash
-                        .ok_or(StorageL1BatchConvertError::Incomplete)?,
-                ),
-                default_aa_code_hash: H256::from_slice(
-                    &self
-                        .default_aa_code_hash
-                        .ok_or(StorageL1BatchConvertError::Incomplete)?,
-                ),
+                zkporter_is_available: batch.zkporter_is_available.ok_or(
+                    StorageConversionError::MissingField("zkporter_is_available"),
+                )?,
+                bootloader_code_hash: parse_required_hash(
+                    "bootloader_code_hash",
+                    batch.bootloader_code_hash,
+                )?,
+                default_aa_code_hash: parse_required_hash(
+                    "default_aa_code_hash",
+                    batch.default_aa_code_hash,
+                )?,
             },
-            state_diffs_compressed: self.compressed_state_diffs.unwrap_or_default(),
-            events_queue_commitment: self.events_queue_commitment.map(|v| H256::from_slice(&v)),
-            bootloader_initial_content_commitment: self
+            state_diffs_compressed: batch.compressed_state_diffs.unwrap_or_default(),
+            events_queue_commitment: batch
+                .events_queue_commitment
+                .map(|v| parse_hash("events_queue_commitment", &v))
+                .transpose()?,
+            bootloader_initial_content_commitment: batch
                 .bootloader_initial_content_commitment
-                .map(|v| H256::from_slice(&v)),
+                .map(|v| parse_hash("bootloader_initial_content_commitment", &v))
+                .transpose()?,
         })
     }
 }
@@ -274,8 +357,10 @@ pub struct StorageBlockDetails {
     pub protocol_version: Option<i32>,
 }
 
-impl From<StorageBlockDetails> for api::BlockDetails {
-    fn from(details: StorageBlockDetails) -> Self {
+impl TryFrom<StorageBlockDetails> for api::BlockDetails {
+    type Error = StorageConversionError;
+
+    fn try_from(details: StorageBlockDetails) -> Result<Self, Self::Error> {
         let status = if details.number == 0 || details.execute_tx_hash.is_some() {
             api::BlockStatus::Verified
         } else {
@@ -287,25 +372,20 @@ impl From<StorageBlockDetails> for api::BlockDetails {
             l1_tx_count: details.l1_tx_count as usize,
             l2_tx_count: details.l2_tx_count as usize,
             status,
-            root_hash: details.root_hash.as_deref().map(H256::from_slice),
-            commit_tx_hash: details
-                .commit_tx_hash
+            root_hash: details
+                .root_hash
                 .as_deref()
-                .map(|hash| H256::from_str(hash).expect("Incorrect commit_tx hash")),
+                .map(|hash| parse_hash("root_hash", hash))
+                .transpose()?,
+            commit_tx_hash: parse_tx_hash("commit_tx_hash", details.commit_tx_hash)?,
             committed_at: details
                 .committed_at
                 .map(|committed_at| DateTime::from_naive_utc_and_offset(committed_at, Utc)),
-            prove_tx_hash: details
-                .prove_tx_hash
-                .as_deref()
-                .map(|hash| H256::from_str(hash).expect("Incorrect prove_tx hash")),
+            prove_tx_hash: parse_tx_hash("prove_tx_hash", details.prove_tx_hash)?,
             proven_at: details
                 .proven_at
                 .map(|proven_at| DateTime::<Utc>::from_naive_utc_and_offset(proven_at, Utc)),
-            execute_tx_hash: details
-                .execute_tx_hash
-                .as_deref()
-                .map(|hash| H256::from_str(hash).expect("Incorrect execute_tx hash")),
+            execute_tx_hash: parse_tx_hash("execute_tx_hash", details.execute_tx_hash)?,
             executed_at: details
                 .executed_at
                 .map(|executed_at| DateTime::<Utc>::from_naive_utc_and_offset(executed_at, Utc)),
@@ -314,17 +394,115 @@ impl From<StorageBlockDetails> for api::BlockDetails {
             base_system_contracts_hashes: convert_base_system_contracts_hashes(
                 details.bootloader_code_hash,
                 details.default_aa_code_hash,
-            ),
+            )?,
         };
-        api::BlockDetails {
+        Ok(api::BlockDetails {
             base,
             number: MiniblockNumber(details.number as u32),
             l1_batch_number: L1BatchNumber(details.l1_batch_number as u32),
-            operator_address: Address::from_slice(&details.fee_account_address),
-            protocol_version: details
-                .protocol_version
-                .map(|v| (v as u16).try_into().unwrap()),
-        }
+            operator_address: parse_address("fee_account_address", &details.fee_account_address)?,
+            protocol_version: parse_protocol_version("protocol_version", details.protocol_version)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageBlockFinality {
+    pub number: i64,
+    pub l1_batch_number: Option<i64>,
+    pub commit_tx_hash: Option<String>,
+    pub committed_at: Option<NaiveDateTime>,
+    pub prove_tx_hash: Option<String>,
+    pub proven_at: Option<NaiveDateTime>,
+    pub execute_tx_hash: Option<String>,
+    pub executed_at: Option<NaiveDateTime>,
+}
+
+impl TryFrom<StorageBlockFinality> for api::BlockFinality {
+    type Error = StorageConversionError;
+
+    fn try_from(finality: StorageBlockFinality) -> Result<Self, Self::Error> {
+        let to_utc = |time| DateTime::<Utc>::from_naive_utc_and_offset(time, Utc);
+        let commit_tx_hash = parse_tx_hash("commit_tx_hash", finality.commit_tx_hash)?;
+        let prove_tx_hash = parse_tx_hash("prove_tx_hash", finality.prove_tx_hash)?;
+        let execute_tx_hash = parse_tx_hash("execute_tx_hash", finality.execute_tx_hash)?;
+        let status = finality_status(
+            finality.number == 0,
+            commit_tx_hash,
+            prove_tx_hash,
+            execute_tx_hash,
+        );
+
+        Ok(api::BlockFinality {
+            number: MiniblockNumber(finality.number as u32),
+            l1_batch_number: finality
+                .l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+            status,
+            commit_tx_hash,
+            committed_at: finality.committed_at.map(to_utc),
+            prove_tx_hash,
+            proven_at: finality.proven_at.map(to_utc),
+            execute_tx_hash,
+            executed_at: finality.executed_at.map(to_utc),
+        })
+    }
+}
+
+fn finality_status(
+    is_genesis: bool,
+    commit_tx_hash: Option<H256>,
+    prove_tx_hash: Option<H256>,
+    execute_tx_hash: Option<H256>,
+) -> api::BlockFinalityStatus {
+    // The genesis L1 batch is not sent to L1, but is considered final.
+    if is_genesis || execute_tx_hash.is_some() {
+        api::BlockFinalityStatus::Executed
+    } else if prove_tx_hash.is_some() {
+        api::BlockFinalityStatus::Proven
+    } else if commit_tx_hash.is_some() {
+        api::BlockFinalityStatus::Committed
+    } else {
+        api::BlockFinalityStatus::Sealed
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageL1BatchFinality {
+    pub number: i64,
+    pub commit_tx_hash: Option<String>,
+    pub committed_at: Option<NaiveDateTime>,
+    pub prove_tx_hash: Option<String>,
+    pub proven_at: Option<NaiveDateTime>,
+    pub execute_tx_hash: Option<String>,
+    pub executed_at: Option<NaiveDateTime>,
+}
+
+impl TryFrom<StorageL1BatchFinality> for api::L1BatchFinality {
+    type Error = StorageConversionError;
+
+    fn try_from(finality: StorageL1BatchFinality) -> Result<Self, Self::Error> {
+        let to_utc = |time| DateTime::<Utc>::from_naive_utc_and_offset(time, Utc);
+        let commit_tx_hash = parse_tx_hash("commit_tx_hash", finality.commit_tx_hash)?;
+        let prove_tx_hash = parse_tx_hash("prove_tx_hash", finality.prove_tx_hash)?;
+        let execute_tx_hash = parse_tx_hash("execute_tx_hash", finality.execute_tx_hash)?;
+        let status = finality_status(
+            finality.number == 0,
+            commit_tx_hash,
+            prove_tx_hash,
+            execute_tx_hash,
+        );
+
+        Ok(api::L1BatchFinality {
+            number: L1BatchNumber(finality.number as u32),
+            status,
+            commit_tx_hash,
+            committed_at: finality.committed_at.map(to_utc),
+            prove_tx_hash,
+            proven_at: finality.proven_at.map(to_utc),
+            execute_tx_hash,
+            executed_at: finality.executed_at.map(to_utc),
+        })
     }
 }
 
@@ -347,8 +525,10 @@ pub struct StorageL1BatchDetails {
     pub default_aa_code_hash: Option<Vec<u8>>,
 }
 
-impl From<StorageL1BatchDetails> for api::L1BatchDetails {
-    fn from(details: StorageL1BatchDetails) -> Self {
+impl TryFrom<StorageL1BatchDetails> for api::L1BatchDetails {
+    type Error = StorageConversionError;
+
+    fn try_from(details: StorageL1BatchDetails) -> Result<Self, Self::Error> {
         let status = if details.number == 0 || details.execute_tx_hash.is_some() {
             api::BlockStatus::Verified
         } else {
@@ -360,25 +540,20 @@ impl From<StorageL1BatchDetails> for api::L1BatchDetails {
             l1_tx_count: details.l1_tx_count as usize,
             l2_tx_count: details.l2_tx_count as usize,
             status,
-            root_hash: details.root_hash.as_deref().map(H256::from_slice),
-            commit_tx_hash: details
-                .commit_tx_hash
+            root_hash: details
+                .root_hash
                 .as_deref()
-                .map(|hash| H256::from_str(hash).expect("Incorrect commit_tx hash")),
+                .map(|hash| parse_hash("root_hash", hash))
+                .transpose()?,
+            commit_tx_hash: parse_tx_hash("commit_tx_hash", details.commit_tx_hash)?,
             committed_at: details
                 .committed_at
                 .map(|committed_at| DateTime::<Utc>::from_naive_utc_and_offset(committed_at, Utc)),
-            prove_tx_hash: details
-                .prove_tx_hash
-                .as_deref()
-                .map(|hash| H256::from_str(hash).expect("Incorrect prove_tx hash")),
+            prove_tx_hash: parse_tx_hash("prove_tx_hash", details.prove_tx_hash)?,
             proven_at: details
                 .proven_at
                 .map(|proven_at| DateTime::<Utc>::from_naive_utc_and_offset(proven_at, Utc)),
-            execute_tx_hash: details
-                .execute_tx_hash
-                .as_deref()
-                .map(|hash| H256::from_str(hash).expect("Incorrect execute_tx hash")),
+            execute_tx_hash: parse_tx_hash("execute_tx_hash", details.execute_tx_hash)?,
             executed_at: details
                 .executed_at
                 .map(|executed_at| DateTime::<Utc>::from_naive_utc_and_offset(executed_at, Utc)),
@@ -387,12 +562,12 @@ impl From<StorageL1BatchDetails> for api::L1BatchDetails {
             base_system_contracts_hashes: convert_base_system_contracts_hashes(
                 details.bootloader_code_hash,
                 details.default_aa_code_hash,
-            ),
+            )?,
         };
-        api::L1BatchDetails {
+        Ok(api::L1BatchDetails {
             base,
             number: L1BatchNumber(details.number as u32),
-        }
+        })
     }
 }
 
@@ -423,46 +598,53 @@ pub struct StorageMiniblockHeader {
     pub virtual_blocks: i64,
 }
 
-impl From<StorageMiniblockHeader> for MiniblockHeader {
-    fn from(row: StorageMiniblockHeader) -> Self {
-        let protocol_version = row.protocol_version.map(|v| (v as u16).try_into().unwrap());
-
-        let fee_input = protocol_version
-            .filter(|version: &ProtocolVersionId| version.is_post_1_4_1())
-            .map(|_| {
-                BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
-                    fair_pubdata_price: row
-                        .fair_pubdata_price
-                        .expect("No fair pubdata price for 1.4.1 miniblock")
-                        as u64,
-                    fair_l2_gas_price: row.l2_fair_gas_price as u64,
-                    l1_gas_price: row.l1_gas_price as u64,
-                })
+impl TryFrom<StorageMiniblockHeader> for MiniblockHeader {
+    type Error = StorageConversionError;
+
+    fn try_from(row: StorageMiniblockHeader) -> Result<Self, Self::Error> {
+        let protocol_version = parse_protocol_version("protocol_version", row.protocol_version)?;
+
+        let fee_input = if protocol_version.map_or(false, |version| version.is_post_1_4_1()) {
+            let fair_pubdata_price = row
+                .fair_pubdata_price
+                .ok_or(StorageConversionError::MissingField("fair_pubdata_price"))?;
+            BatchFeeInput::PubdataIndependent(PubdataIndependentBatchFeeModelInput {
+                fair_pubdata_price: fair_pubdata_price as u64,
+                fair_l2_gas_price: row.l2_fair_gas_price as u64,
+                l1_gas_price: row.l1_gas_price as u64,
+            })
+        } else {
+            BatchFeeInput::L1Pegged(L1PeggedBatchFeeModelInput {
+                fair_l2_gas_price: row.l2_fair_gas_price as u64,
+                l1_gas_price: row.l1_gas_price as u64,
             })
-            .unwrap_or_else(|| {
-                BatchFeeInput::L1Pegged(L1PeggedBatchFeeModelInput {
-                    fair_l2_gas_price: row.l2_fair_gas_price as u64,
-                    l1_gas_price: row.l1_gas_price as u64,
-                })
-            });
-
-        MiniblockHeader {
+        };
+
+        let base_fee_per_gas =
+            row.base_fee_per_gas
+                .to_u64()
+                .ok_or_else(|| StorageConversionError::OutOfRange {
+                    field: "base_fee_per_gas",
+                    value: row.base_fee_per_gas.to_string(),
+                })?;
+
+        Ok(MiniblockHeader {
             number: MiniblockNumber(row.number as u32),
             timestamp: row.timestamp as u64,
-            hash: H256::from_slice(&row.hash),
+            hash: parse_hash("hash", &row.hash)?,
             l1_tx_count: row.l1_tx_count as u16,
             l2_tx_count: row.l2_tx_count as u16,
-            fee_account_address: Address::from_slice(&row.fee_account_address),
-            base_fee_per_gas: row.base_fee_per_gas.to_u64().unwrap(),
+            fee_account_address: parse_address("fee_account_address", &row.fee_account_address)?,
+            base_fee_per_gas,
             batch_fee_input: fee_input,
             base_system_contracts_hashes: convert_base_system_contracts_hashes(
                 row.bootloader_code_hash,
                 row.default_aa_code_hash,
-            ),
+            )?,
             gas_per_pubdata_limit: row.gas_per_pubdata_limit as u64,
             protocol_version,
             virtual_blocks: row.virtual_blocks as u32,
-        }
+        })
     }
 }
 
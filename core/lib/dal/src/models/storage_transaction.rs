@@ -63,6 +63,7 @@ pub struct StorageTransaction {
     pub l1_tx_refund_recipient: Option<Vec<u8>>,
 
     pub upgrade_id: Option<i32>,
+    pub replaced_by: Option<Vec<u8>>,
 
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -1,15 +1,24 @@
+use assert_matches::assert_matches;
 use chrono::Utc;
 use zksync_types::{
+    api,
+    block::L1BatchHeader,
     fee::Fee,
     l1::{OpProcessingType, PriorityQueueType},
     l2::TransactionType,
-    Address, Bytes, Execute, ExecuteTransactionCommon, Transaction, EIP_1559_TX_TYPE,
-    EIP_2930_TX_TYPE, EIP_712_TX_TYPE, H160, H256, PRIORITY_OPERATION_L2_TX_TYPE,
+    Address, Bytes, Execute, ExecuteTransactionCommon, ProtocolVersionId, Transaction,
+    EIP_1559_TX_TYPE, EIP_2930_TX_TYPE, EIP_712_TX_TYPE, H160, H256, PRIORITY_OPERATION_L2_TX_TYPE,
     PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
 use zksync_utils::bigdecimal_to_u256;
 
-use crate::{models::storage_transaction::StorageTransaction, BigDecimal};
+use crate::{
+    models::{
+        storage_block::{StorageBlockDetails, StorageConversionError, StorageL1BatchHeader},
+        storage_transaction::StorageTransaction,
+    },
+    BigDecimal,
+};
 
 fn default_execute() -> Execute {
     Execute {
@@ -310,3 +319,136 @@ fn storage_tx_to_l2_tx_unsupported_tx_type() {
     let stx = l2_storage_tx(1984);
     _ = Transaction::from(stx.clone());
 }
+
+fn storage_block_details() -> StorageBlockDetails {
+    StorageBlockDetails {
+        number: 1,
+        l1_batch_number: 1,
+        timestamp: 100,
+        l1_tx_count: 0,
+        l2_tx_count: 1,
+        root_hash: Some(H256::repeat_byte(1).as_bytes().to_vec()),
+        commit_tx_hash: Some(format!("{:?}", H256::repeat_byte(2))),
+        committed_at: None,
+        prove_tx_hash: None,
+        proven_at: None,
+        execute_tx_hash: None,
+        executed_at: None,
+        l1_gas_price: 100,
+        l2_fair_gas_price: 10,
+        bootloader_code_hash: Some(H256::repeat_byte(3).as_bytes().to_vec()),
+        default_aa_code_hash: Some(H256::repeat_byte(4).as_bytes().to_vec()),
+        fee_account_address: Address::repeat_byte(5).as_bytes().to_vec(),
+        protocol_version: Some(ProtocolVersionId::latest() as i32),
+    }
+}
+
+#[test]
+fn storage_block_details_to_api_block_details() {
+    let details = api::BlockDetails::try_from(storage_block_details()).unwrap();
+    assert_eq!(details.base.root_hash, Some(H256::repeat_byte(1)));
+    assert_eq!(details.base.commit_tx_hash, Some(H256::repeat_byte(2)));
+    assert_eq!(
+        details.base.base_system_contracts_hashes.bootloader,
+        H256::repeat_byte(3)
+    );
+    assert_eq!(details.operator_address, Address::repeat_byte(5));
+    assert_eq!(details.protocol_version, Some(ProtocolVersionId::latest()));
+}
+
+#[test]
+fn storage_block_details_with_missing_fields() {
+    let details = StorageBlockDetails {
+        bootloader_code_hash: None,
+        ..storage_block_details()
+    };
+    let err = api::BlockDetails::try_from(details).unwrap_err();
+    assert_matches!(
+        err,
+        StorageConversionError::MissingField("bootloader_code_hash")
+    );
+
+    let details = StorageBlockDetails {
+        commit_tx_hash: Some("not a hash".to_owned()),
+        ..storage_block_details()
+    };
+    let err = api::BlockDetails::try_from(details).unwrap_err();
+    assert_matches!(
+        err,
+        StorageConversionError::InvalidTxHash {
+            field: "commit_tx_hash",
+            ..
+        }
+    );
+
+    let details = StorageBlockDetails {
+        protocol_version: Some(i32::MAX),
+        ..storage_block_details()
+    };
+    let err = api::BlockDetails::try_from(details).unwrap_err();
+    assert_matches!(
+        err,
+        StorageConversionError::UnknownProtocolVersion {
+            value: i32::MAX,
+            ..
+        }
+    );
+}
+
+#[test]
+fn storage_l1_batch_header_with_malformed_hash() {
+    let header = StorageL1BatchHeader {
+        number: 1,
+        timestamp: 100,
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        l2_to_l1_logs: vec![],
+        l2_to_l1_messages: vec![],
+        bloom: vec![0; 256],
+        priority_ops_onchain_data: vec![],
+        used_contract_hashes: serde_json::json!([]),
+        bootloader_code_hash: Some(vec![1; 32]),
+        default_aa_code_hash: Some(vec![2; 16]),
+        protocol_version: None,
+        system_logs: vec![],
+        compressed_state_diffs: None,
+        pubdata_input: None,
+    };
+    let err = L1BatchHeader::try_from(header).unwrap_err();
+    assert_matches!(
+        err,
+        StorageConversionError::InvalidHashLength {
+            field: "default_aa_code_hash",
+            len: 16
+        }
+    );
+}
+
+#[test]
+fn storage_l1_batch_header_with_malformed_bloom() {
+    let header = StorageL1BatchHeader {
+        number: 1,
+        timestamp: 100,
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        l2_to_l1_logs: vec![],
+        l2_to_l1_messages: vec![],
+        bloom: vec![0; 32],
+        priority_ops_onchain_data: vec![],
+        used_contract_hashes: serde_json::json!([]),
+        bootloader_code_hash: Some(vec![1; 32]),
+        default_aa_code_hash: Some(vec![2; 32]),
+        protocol_version: None,
+        system_logs: vec![],
+        compressed_state_diffs: None,
+        pubdata_input: None,
+    };
+    let err = L1BatchHeader::try_from(header).unwrap_err();
+    assert_matches!(
+        err,
+        StorageConversionError::InvalidBloomLength {
+            field: "bloom",
+            len: 32
+        }
+    );
+}
@@ -0,0 +1,457 @@
+//! Management of range partitions for tables partitioned by miniblock number.
+
+use std::ops;
+
+use zksync_types::MiniblockNumber;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Table partitioned by miniblock number ranges.
+///
+/// `storage_logs` is intentionally not partitioned: its partitions could never be detached since they are required
+/// to access the latest storage state, and point lookups by hashed key would have to probe every partition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartitionedTable {
+    Events,
+}
+
+impl PartitionedTable {
+    pub const ALL: [Self; 1] = [Self::Events];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Events => "events",
+        }
+    }
+
+    /// Returns the name of a range partition. Detached partitions retain their names, which allows to re-attach them
+    /// knowing only the miniblock range.
+    pub fn partition_name(self, miniblocks: &ops::Range<MiniblockNumber>) -> String {
+        format!("{}_{}_{}", self.name(), miniblocks.start, miniblocks.end)
+    }
+
+    /// Parses the miniblock range from a range partition name produced by [`Self::partition_name()`].
+    fn parse_partition_name(self, name: &str) -> Option<ops::Range<MiniblockNumber>> {
+        let (start, end) = name
+            .strip_prefix(self.name())?
+            .strip_prefix('_')?
+            .split_once('_')?;
+        let start = MiniblockNumber(start.parse().ok()?);
+        let end = MiniblockNumber(end.parse().ok()?);
+        Some(start..end)
+    }
+}
+
+/// Miniblocks covered by a table partition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionBounds {
+    /// Partition covering the specified miniblock range. The range start is `None` for the partition without
+    /// a lower bound, which is used for data inserted before partitioning was introduced.
+    Range {
+        start: Option<MiniblockNumber>,
+        end: MiniblockNumber,
+    },
+    /// Default partition receiving data not covered by range partitions.
+    Default,
+}
+
+impl PartitionBounds {
+    fn parse(raw: &str) -> Option<Self> {
+        if raw == "DEFAULT" {
+            return Some(Self::Default);
+        }
+        let (start, end) = raw
+            .strip_prefix("FOR VALUES FROM (")?
+            .strip_suffix(')')?
+            .split_once(") TO (")?;
+        let start = if start == "MINVALUE" {
+            None
+        } else {
+            Some(Self::parse_bound(start)?)
+        };
+        let end = Self::parse_bound(end)?;
+        Some(Self::Range { start, end })
+    }
+
+    fn parse_bound(raw: &str) -> Option<MiniblockNumber> {
+        let raw = raw.strip_prefix('\'')?.strip_suffix('\'')?;
+        Some(MiniblockNumber(raw.parse().ok()?))
+    }
+}
+
+/// Returns miniblock ranges not covered by the range partitions, i.e., ranges for which partitions were detached.
+/// Only ranges before the last range partition are returned; newer miniblocks are covered by the default partition.
+fn uncovered_ranges(partitions: &[TablePartition]) -> Vec<ops::Range<MiniblockNumber>> {
+    let mut uncovered_ranges = vec![];
+    let mut covered_until = MiniblockNumber(0);
+    for partition in partitions {
+        let PartitionBounds::Range { start, end } = partition.bounds else {
+            continue;
+        };
+        let start = start.unwrap_or(MiniblockNumber(0));
+        if start > covered_until {
+            uncovered_ranges.push(covered_until..start);
+        }
+        covered_until = covered_until.max(end);
+    }
+    uncovered_ranges
+}
+
+/// Partition of a [`PartitionedTable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TablePartition {
+    pub name: String,
+    pub bounds: PartitionBounds,
+}
+
+#[derive(Debug)]
+pub struct PartitionsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl PartitionsDal<'_, '_> {
+    /// Returns partitions attached to the specified table. Range partitions are ordered by their bounds
+    /// and are followed by the default partition.
+    pub async fn get_partitions(
+        &mut self,
+        table: PartitionedTable,
+    ) -> sqlx::Result<Vec<TablePartition>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                pg_class.relname::TEXT AS "name!",
+                pg_get_expr(pg_class.relpartbound, pg_class.oid) AS "bounds!"
+            FROM
+                pg_inherits
+                JOIN pg_class ON pg_class.oid = pg_inherits.inhrelid
+            WHERE
+                pg_inherits.inhparent = $1::TEXT::REGCLASS
+            "#,
+            table.name()
+        )
+        .instrument("get_partitions")
+        .with_arg("table", &table)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut partitions = rows
+            .into_iter()
+            .map(|row| {
+                let bounds = PartitionBounds::parse(&row.bounds).ok_or_else(|| {
+                    let msg = format!(
+                        "unexpected bounds for partition {}: {}",
+                        row.name, row.bounds
+                    );
+                    sqlx::Error::Decode(msg.into())
+                })?;
+                Ok(TablePartition {
+                    name: row.name,
+                    bounds,
+                })
+            })
+            .collect::<sqlx::Result<Vec<_>>>()?;
+        partitions.sort_unstable_by_key(|partition| match partition.bounds {
+            PartitionBounds::Range { start, .. } => (false, start),
+            PartitionBounds::Default => (true, None),
+        });
+        Ok(partitions)
+    }
+
+    /// Returns miniblock ranges for which data is unavailable because the corresponding range partitions
+    /// are detached from the table. The ranges are ordered and non-overlapping.
+    pub async fn get_detached_ranges(
+        &mut self,
+        table: PartitionedTable,
+    ) -> sqlx::Result<Vec<ops::Range<MiniblockNumber>>> {
+        let partitions = self.get_partitions(table).await?;
+        Ok(uncovered_ranges(&partitions))
+    }
+
+    /// Returns miniblock ranges of detached range partitions that are still present in the database
+    /// as standalone tables, and thus can be re-attached using [`Self::attach_partition()`].
+    /// The ranges are ordered by their start.
+    pub async fn get_detached_partitions(
+        &mut self,
+        table: PartitionedTable,
+    ) -> sqlx::Result<Vec<ops::Range<MiniblockNumber>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                relname::TEXT AS "name!"
+            FROM
+                pg_class
+            WHERE
+                relnamespace = CURRENT_SCHEMA()::REGNAMESPACE
+                AND relkind = 'r'
+                AND NOT relispartition
+                AND relname LIKE $1 || '\_%'
+            "#,
+            table.name()
+        )
+        .instrument("get_detached_partitions")
+        .with_arg("table", &table)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut ranges: Vec<_> = rows
+            .iter()
+            .filter_map(|row| table.parse_partition_name(&row.name))
+            .collect();
+        ranges.sort_unstable_by_key(|range| range.start);
+        Ok(ranges)
+    }
+
+    /// Creates and attaches a range partition for the specified miniblocks. Rows in this range are moved
+    /// from the default partition to the created one.
+    pub async fn create_partition(
+        &mut self,
+        table: PartitionedTable,
+        miniblocks: ops::Range<MiniblockNumber>,
+    ) -> sqlx::Result<()> {
+        let partition_name = table.partition_name(&miniblocks);
+        sqlx::query!(
+            r#"
+            CALL create_miniblocks_range_partition ($1, $2, $3, $4)
+            "#,
+            table.name(),
+            partition_name,
+            i64::from(miniblocks.start.0),
+            i64::from(miniblocks.end.0)
+        )
+        .instrument("create_partition")
+        .with_arg("table", &table)
+        .with_arg("miniblocks", &miniblocks)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Attaches a previously detached range partition for the specified miniblocks.
+    pub async fn attach_partition(
+        &mut self,
+        table: PartitionedTable,
+        miniblocks: ops::Range<MiniblockNumber>,
+    ) -> sqlx::Result<()> {
+        let partition_name = table.partition_name(&miniblocks);
+        sqlx::query!(
+            r#"
+            CALL attach_miniblocks_range_partition ($1, $2, $3, $4)
+            "#,
+            table.name(),
+            partition_name,
+            i64::from(miniblocks.start.0),
+            i64::from(miniblocks.end.0)
+        )
+        .instrument("attach_partition")
+        .with_arg("table", &table)
+        .with_arg("miniblocks", &miniblocks)
+        .expect_slow_query()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Detaches the specified partition from its table. The detached partition is retained as a standalone table;
+    /// it can be archived and dropped, or re-attached using [`Self::attach_partition()`].
+    pub async fn detach_partition(
+        &mut self,
+        table: PartitionedTable,
+        partition_name: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            CALL detach_miniblocks_range_partition ($1, $2)
+            "#,
+            table.name(),
+            partition_name
+        )
+        .instrument("detach_partition")
+        .with_arg("table", &table)
+        .with_arg("partition_name", &partition_name)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        tx::IncludedTxLocation, Address, L1BatchNumber, ProtocolVersion, VmEvent, H256,
+    };
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    #[test]
+    fn parsing_partition_bounds() {
+        assert_eq!(
+            PartitionBounds::parse("DEFAULT"),
+            Some(PartitionBounds::Default)
+        );
+        assert_eq!(
+            PartitionBounds::parse("FOR VALUES FROM (MINVALUE) TO ('6')"),
+            Some(PartitionBounds::Range {
+                start: None,
+                end: MiniblockNumber(6),
+            })
+        );
+        assert_eq!(
+            PartitionBounds::parse("FOR VALUES FROM ('1000') TO ('2000')"),
+            Some(PartitionBounds::Range {
+                start: Some(MiniblockNumber(1000)),
+                end: MiniblockNumber(2000),
+            })
+        );
+        assert_eq!(PartitionBounds::parse("FOR VALUES IN ('1')"), None);
+    }
+
+    #[test]
+    fn parsing_partition_names() {
+        let table = PartitionedTable::Events;
+        let range = MiniblockNumber(1000)..MiniblockNumber(2000);
+        let name = table.partition_name(&range);
+        assert_eq!(name, "events_1000_2000");
+        assert_eq!(table.parse_partition_name(&name), Some(range));
+        assert_eq!(table.parse_partition_name("events_legacy"), None);
+        assert_eq!(table.parse_partition_name("events_default"), None);
+        assert_eq!(table.parse_partition_name("events_queue"), None);
+    }
+
+    #[test]
+    fn computing_uncovered_ranges() {
+        let range_partition = |start: Option<u32>, end: u32| TablePartition {
+            name: String::new(),
+            bounds: PartitionBounds::Range {
+                start: start.map(MiniblockNumber),
+                end: MiniblockNumber(end),
+            },
+        };
+        let default_partition = TablePartition {
+            name: String::new(),
+            bounds: PartitionBounds::Default,
+        };
+
+        let partitions = [
+            range_partition(None, 10),
+            range_partition(Some(10), 20),
+            default_partition.clone(),
+        ];
+        assert_eq!(uncovered_ranges(&partitions), []);
+
+        let partitions = [
+            range_partition(Some(10), 20),
+            range_partition(Some(30), 40),
+            default_partition,
+        ];
+        assert_eq!(
+            uncovered_ranges(&partitions),
+            [
+                MiniblockNumber(0)..MiniblockNumber(10),
+                MiniblockNumber(20)..MiniblockNumber(30)
+            ]
+        );
+    }
+
+    async fn all_logged_miniblocks(conn: &mut StorageProcessor<'_>) -> Vec<MiniblockNumber> {
+        let logs = conn
+            .events_web3_dal()
+            .get_all_logs(MiniblockNumber(0))
+            .await
+            .unwrap();
+        logs.iter()
+            .map(|log| MiniblockNumber(log.block_number.unwrap().as_u32()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn creating_and_detaching_partitions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(1),
+            indexed_topics: vec![H256::repeat_byte(1)],
+            value: vec![1],
+        };
+        for number in 0..4 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+            let location = IncludedTxLocation {
+                tx_hash: H256::from_low_u64_be(number.into()),
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Address::zero(),
+            };
+            conn.events_dal()
+                .save_events(MiniblockNumber(number), &[(location, vec![&event])])
+                .await
+                .unwrap();
+        }
+
+        let table = PartitionedTable::Events;
+        let partitions = conn.partitions_dal().get_partitions(table).await.unwrap();
+        let default_partition = partitions.last().unwrap();
+        assert_eq!(default_partition.bounds, PartitionBounds::Default);
+
+        let range = MiniblockNumber(0)..MiniblockNumber(2);
+        conn.partitions_dal()
+            .create_partition(table, range.clone())
+            .await
+            .unwrap();
+        let partitions = conn.partitions_dal().get_partitions(table).await.unwrap();
+        let created_partition = &partitions[partitions.len() - 2];
+        assert_eq!(created_partition.name, "events_0_2");
+        assert_eq!(
+            created_partition.bounds,
+            PartitionBounds::Range {
+                start: Some(MiniblockNumber(0)),
+                end: MiniblockNumber(2),
+            }
+        );
+        assert_eq!(all_logged_miniblocks(&mut conn).await.len(), 4);
+
+        conn.partitions_dal()
+            .detach_partition(table, &created_partition.name)
+            .await
+            .unwrap();
+        let mut miniblock_numbers = all_logged_miniblocks(&mut conn).await;
+        miniblock_numbers.sort_unstable();
+        assert_eq!(miniblock_numbers, [MiniblockNumber(2), MiniblockNumber(3)]);
+
+        // Detached ranges are only reported before the last range partition.
+        conn.partitions_dal()
+            .create_partition(table, MiniblockNumber(2)..MiniblockNumber(4))
+            .await
+            .unwrap();
+        let detached_ranges = conn
+            .partitions_dal()
+            .get_detached_ranges(table)
+            .await
+            .unwrap();
+        assert_eq!(detached_ranges, [range.clone()]);
+        let detached_partitions = conn
+            .partitions_dal()
+            .get_detached_partitions(table)
+            .await
+            .unwrap();
+        assert_eq!(detached_partitions, [range.clone()]);
+
+        conn.partitions_dal()
+            .attach_partition(table, range)
+            .await
+            .unwrap();
+        let detached_ranges = conn
+            .partitions_dal()
+            .get_detached_ranges(table)
+            .await
+            .unwrap();
+        assert_eq!(detached_ranges, []);
+        assert_eq!(all_logged_miniblocks(&mut conn).await.len(), 4);
+    }
+}
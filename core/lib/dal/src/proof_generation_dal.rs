@@ -3,7 +3,9 @@ use std::time::Duration;
 use strum::{Display, EnumString};
 use zksync_types::L1BatchNumber;
 
-use crate::{time_utils::pg_interval_from_duration, SqlxError, StorageProcessor};
+use crate::{
+    instrument::InstrumentExt, time_utils::pg_interval_from_duration, SqlxError, StorageProcessor,
+};
 
 #[derive(Debug)]
 pub struct ProofGenerationDal<'a, 'c> {
@@ -59,7 +61,8 @@ impl ProofGenerationDal<'_, '_> {
             "#,
             &processing_timeout,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_block_to_be_proven")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32));
@@ -85,7 +88,8 @@ impl ProofGenerationDal<'_, '_> {
             proof_blob_url,
             block_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("save_proof_artifacts_metadata")
+        .execute(self.storage)
         .await?
         .rows_affected()
         .eq(&1)
@@ -109,7 +113,8 @@ impl ProofGenerationDal<'_, '_> {
             block_number.0 as i64,
             proof_gen_data_blob_url,
         )
-        .execute(self.storage.conn())
+        .instrument("insert_proof_generation_details")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -130,7 +135,8 @@ impl ProofGenerationDal<'_, '_> {
             ProofGenerationJobStatus::Skipped.to_string(),
             block_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("mark_proof_generation_job_as_skipped")
+        .execute(self.storage)
         .await?
         .rows_affected()
         .eq(&1)
@@ -153,7 +159,8 @@ impl ProofGenerationDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_oldest_unpicked_batch")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32));
@@ -176,11 +183,32 @@ impl ProofGenerationDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_oldest_not_generated_batch")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32));
 
         result
     }
+
+    /// Returns the number of L1 batches queued for proof generation, but not yet proven.
+    /// Uses a partial index on unproven batches, so it doesn't scan the entire table.
+    pub async fn get_unproven_l1_batch_count(&mut self) -> sqlx::Result<usize> {
+        let count = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                proof_generation_details
+            WHERE
+                status NOT IN ('generated', 'skipped')
+            "#
+        )
+        .instrument("get_unproven_l1_batch_count")
+        .fetch_one(self.storage)
+        .await?
+        .count;
+        Ok(count as usize)
+    }
 }
@@ -8,6 +8,7 @@ use zksync_types::{
 };
 
 use crate::{
+    instrument::InstrumentExt,
     models::storage_protocol_version::{protocol_version_from_storage, StorageProtocolVersion},
     StorageProcessor,
 };
@@ -18,6 +19,8 @@ pub struct ProtocolVersionsDal<'a, 'c> {
 }
 
 impl ProtocolVersionsDal<'_, '_> {
+    /// Records a protocol version. The version becomes active for L1 batches with timestamps not less than
+    /// `timestamp`; thus, a version with a future timestamp denotes a scheduled protocol upgrade.
     pub async fn save_protocol_version(
         &mut self,
         id: ProtocolVersionId,
@@ -68,7 +71,8 @@ impl ProtocolVersionsDal<'_, '_> {
             verifier_address.as_bytes(),
             tx_hash.as_ref().map(H256::as_bytes),
         )
-        .execute(self.storage.conn())
+        .instrument("save_protocol_version")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -81,7 +85,8 @@ impl ProtocolVersionsDal<'_, '_> {
             db_transaction
                 .transactions_dal()
                 .insert_system_transaction(tx)
-                .await;
+                .await
+                .unwrap();
         }
 
         db_transaction
@@ -111,7 +116,8 @@ impl ProtocolVersionsDal<'_, '_> {
             tx_hash.as_ref().map(H256::as_bytes),
             id as i32,
         )
-        .execute(self.storage.conn())
+        .instrument("save_genesis_upgrade_tx_hash")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -130,7 +136,8 @@ impl ProtocolVersionsDal<'_, '_> {
         db_transaction
             .transactions_dal()
             .insert_system_transaction(tx)
-            .await;
+            .await
+            .unwrap();
 
         db_transaction
             .protocol_versions_dal()
@@ -161,7 +168,8 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             current_timestamp as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("base_system_contracts_by_timestamp")
+        .fetch_one(self.storage)
         .await
         .context("cannot fetch system contract hashes")?;
 
@@ -179,6 +187,76 @@ impl ProtocolVersionsDal<'_, '_> {
         Ok((contracts, protocol_version))
     }
 
+    /// Returns the ID of the protocol version active at the specified timestamp, i.e., the latest version
+    /// with the activation timestamp not exceeding it. This is the version a new L1 batch with this timestamp
+    /// must be executed with.
+    pub async fn protocol_version_id_by_timestamp(
+        &mut self,
+        timestamp: u64,
+    ) -> anyhow::Result<Option<ProtocolVersionId>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                id
+            FROM
+                protocol_versions
+            WHERE
+                timestamp <= $1
+            ORDER BY
+                id DESC
+            LIMIT
+                1
+            "#,
+            timestamp as i64
+        )
+        .instrument("protocol_version_id_by_timestamp")
+        .with_arg("timestamp", &timestamp)
+        .fetch_optional(self.storage)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let id = (row.id as u16)
+            .try_into()
+            .context("bogus protocol version ID")?;
+        Ok(Some(id))
+    }
+
+    /// Returns protocol upgrades scheduled after the specified timestamp, together with their activation timestamps.
+    /// Upgrades are ordered by the version ID.
+    pub async fn get_scheduled_upgrades(
+        &mut self,
+        timestamp: u64,
+    ) -> anyhow::Result<Vec<(ProtocolVersionId, u64)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                timestamp
+            FROM
+                protocol_versions
+            WHERE
+                timestamp > $1
+            ORDER BY
+                id
+            "#,
+            timestamp as i64
+        )
+        .instrument("get_scheduled_upgrades")
+        .with_arg("timestamp", &timestamp)
+        .fetch_all(self.storage)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let id = (row.id as u16)
+                    .try_into()
+                    .context("bogus protocol version ID")?;
+                Ok((id, row.timestamp as u64))
+            })
+            .collect()
+    }
+
     pub async fn load_base_system_contracts_by_version_id(
         &mut self,
         version_id: u16,
@@ -195,7 +273,8 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("load_base_system_contracts_by_version_id")
+        .fetch_optional(self.storage)
         .await
         .context("cannot fetch system contract hashes")?;
 
@@ -234,7 +313,8 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("load_previous_version")
+        .fetch_optional(self.storage)
         .await
         .unwrap()?;
         let tx = self
@@ -260,7 +340,8 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_protocol_version")
+        .fetch_optional(self.storage)
         .await
         .unwrap()?;
         let tx = self.get_protocol_upgrade_tx(version_id).await;
@@ -286,7 +367,8 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("l1_verifier_config_for_version")
+        .fetch_optional(self.storage)
         .await
         .unwrap()?;
         Some(L1VerifierConfig {
@@ -312,7 +394,8 @@ impl ProtocolVersionsDal<'_, '_> {
                 protocol_versions
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("last_version_id")
+        .fetch_optional(self.storage)
         .await
         .unwrap()?
         .max?;
@@ -332,7 +415,8 @@ impl ProtocolVersionsDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("last_used_version_id")
+        .fetch_optional(self.storage)
         .await
         .unwrap()?
         .protocol_version?;
@@ -349,7 +433,8 @@ impl ProtocolVersionsDal<'_, '_> {
                 protocol_versions
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("all_version_ids")
+        .fetch_all(self.storage)
         .await
         .unwrap();
         rows.into_iter()
@@ -372,7 +457,8 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             protocol_version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_protocol_upgrade_tx")
+        .fetch_optional(self.storage)
         .await
         .unwrap()?;
         if let Some(hash) = row.upgrade_tx_hash {
@@ -1,6 +1,12 @@
-use zksync_types::api::ProtocolVersion;
+use zksync_types::{
+    api::{ProtocolVersion, ProtocolVersionDetails},
+    L1BatchNumber,
+};
 
-use crate::{models::storage_protocol_version::StorageProtocolVersion, StorageProcessor};
+use crate::{
+    instrument::InstrumentExt, models::storage_protocol_version::StorageProtocolVersion,
+    StorageProcessor,
+};
 
 #[derive(Debug)]
 pub struct ProtocolVersionsWeb3Dal<'a, 'c> {
@@ -21,7 +27,8 @@ impl ProtocolVersionsWeb3Dal<'_, '_> {
             "#,
             version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_protocol_version_by_id")
+        .fetch_optional(self.storage)
         .await
         .unwrap();
 
@@ -42,10 +49,121 @@ impl ProtocolVersionsWeb3Dal<'_, '_> {
                 1
             "#,
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_latest_protocol_version")
+        .fetch_one(self.storage)
         .await
         .unwrap();
 
         ProtocolVersion::from(storage_protocol_version)
     }
+
+    /// Returns all protocol versions ordered by ID, together with the first L1 batch executed with each version.
+    pub async fn get_protocol_versions(&mut self) -> sqlx::Result<Vec<ProtocolVersionDetails>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                protocol_versions.*,
+                first_batch.number AS "activation_l1_batch?"
+            FROM
+                protocol_versions
+                LEFT JOIN LATERAL (
+                    SELECT
+                        number
+                    FROM
+                        l1_batches
+                    WHERE
+                        l1_batches.protocol_version = protocol_versions.id
+                    ORDER BY
+                        number
+                    LIMIT
+                        1
+                ) AS first_batch ON TRUE
+            ORDER BY
+                protocol_versions.id
+            "#
+        )
+        .instrument("get_protocol_versions")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let storage_protocol_version = StorageProtocolVersion {
+                    id: row.id,
+                    timestamp: row.timestamp,
+                    recursion_scheduler_level_vk_hash: row.recursion_scheduler_level_vk_hash,
+                    recursion_node_level_vk_hash: row.recursion_node_level_vk_hash,
+                    recursion_leaf_level_vk_hash: row.recursion_leaf_level_vk_hash,
+                    recursion_circuits_set_vks_hash: row.recursion_circuits_set_vks_hash,
+                    bootloader_code_hash: row.bootloader_code_hash,
+                    default_account_code_hash: row.default_account_code_hash,
+                    verifier_address: row.verifier_address,
+                    created_at: row.created_at,
+                    upgrade_tx_hash: row.upgrade_tx_hash,
+                };
+                ProtocolVersionDetails {
+                    version: storage_protocol_version.into(),
+                    activation_l1_batch: row
+                        .activation_l1_batch
+                        .map(|number| L1BatchNumber(number as u32)),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{block::L1BatchHeader, ProtocolVersionId};
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn getting_protocol_versions_with_activation_batches() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(Default::default())
+            .await;
+        let next_version = ProtocolVersionId::next();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(zksync_types::ProtocolVersion {
+                id: next_version,
+                timestamp: 100,
+                ..Default::default()
+            })
+            .await;
+        for number in 1..=2 {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                number.into(),
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::default(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+        }
+
+        let versions = conn
+            .protocol_versions_web3_dal()
+            .get_protocol_versions()
+            .await
+            .unwrap();
+        let versions: Vec<_> = versions
+            .iter()
+            .map(|details| (details.version.version_id, details.activation_l1_batch))
+            .collect();
+        assert_eq!(
+            versions,
+            [
+                (ProtocolVersionId::default() as u16, Some(L1BatchNumber(1))),
+                (next_version as u16, None)
+            ]
+        );
+    }
 }
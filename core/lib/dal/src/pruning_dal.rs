@@ -0,0 +1,343 @@
+use std::time::Duration;
+
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+
+use crate::{instrument::InstrumentExt, time_utils::pg_interval_from_duration, StorageProcessor};
+
+#[derive(Debug)]
+pub struct PruningDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Information about the last pruned L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruningInfo {
+    pub last_pruned_l1_batch: L1BatchNumber,
+    pub last_pruned_miniblock: MiniblockNumber,
+}
+
+/// Number of rows affected by pruning a range of L1 batches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningStats {
+    pub deleted_events: u64,
+    pub deleted_call_traces: u64,
+    pub deleted_storage_logs: u64,
+    pub pruned_transactions: u64,
+}
+
+impl PruningDal<'_, '_> {
+    pub async fn get_pruning_info(&mut self) -> sqlx::Result<Option<PruningInfo>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                pruned_l1_batch,
+                pruned_miniblock
+            FROM
+                pruning_log
+            ORDER BY
+                pruned_l1_batch DESC
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_pruning_info")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| PruningInfo {
+            last_pruned_l1_batch: L1BatchNumber(row.pruned_l1_batch as u32),
+            last_pruned_miniblock: MiniblockNumber(row.pruned_miniblock as u32),
+        }))
+    }
+
+    /// Returns the number of the last L1 batch for which the execute transaction was confirmed on L1
+    /// more than `retention` ago. Only such batches are safe to prune.
+    pub async fn get_last_l1_batch_executed_before(
+        &mut self,
+        retention: Duration,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batches.number) AS "number?"
+            FROM
+                l1_batches
+                JOIN eth_txs_history ON l1_batches.eth_execute_tx_id = eth_txs_history.eth_tx_id
+            WHERE
+                eth_txs_history.confirmed_at IS NOT NULL
+                AND eth_txs_history.confirmed_at < NOW() - $1::INTERVAL
+            "#,
+            &pg_interval_from_duration(retention)
+        )
+        .instrument("get_last_l1_batch_executed_before")
+        .with_arg("retention", &retention)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Prunes data for all L1 batches up to and including `last_l1_batch`, which must end with `last_miniblock`.
+    /// Pruning is idempotent; data for previously pruned batches is not revisited, except for storage logs
+    /// overwritten in the pruned range.
+    ///
+    /// The following data is pruned:
+    ///
+    /// - Events and call traces.
+    /// - Storage logs overwritten by later logs with the same key in the pruned range. The latest value
+    ///   for each key is retained, so the current state is not affected, but historical state
+    ///   for pruned miniblocks becomes unavailable.
+    /// - Factory deps in transaction data (the deps themselves are persisted in the `factory_deps` table).
+    ///
+    /// The caller is responsible for ensuring that pruned L1 batches are executed on L1.
+    pub async fn prune_l1_batches(
+        &mut self,
+        last_l1_batch: L1BatchNumber,
+        last_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<PruningStats> {
+        let mut transaction = self.storage.start_transaction().await?;
+        let first_miniblock = transaction
+            .pruning_dal()
+            .get_pruning_info()
+            .await?
+            .map_or(MiniblockNumber(0), |info| info.last_pruned_miniblock + 1);
+        let miniblock_range = first_miniblock.0 as i64..=last_miniblock.0 as i64;
+
+        let deleted_events = sqlx::query!(
+            r#"
+            DELETE FROM events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            miniblock_range.start(),
+            miniblock_range.end()
+        )
+        .instrument("prune_l1_batches#events")
+        .with_arg("miniblock_range", &miniblock_range)
+        .execute(&mut transaction)
+        .await?
+        .rows_affected();
+
+        let deleted_call_traces = sqlx::query!(
+            r#"
+            DELETE FROM call_traces
+            WHERE
+                tx_hash IN (
+                    SELECT
+                        hash
+                    FROM
+                        transactions
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                )
+            "#,
+            miniblock_range.start(),
+            miniblock_range.end()
+        )
+        .instrument("prune_l1_batches#call_traces")
+        .with_arg("miniblock_range", &miniblock_range)
+        .execute(&mut transaction)
+        .await?
+        .rows_affected();
+
+        // Deletes all logs preceding the last log for each key written in the pruned range.
+        // Logs from previously pruned ranges are covered as well.
+        let deleted_storage_logs = sqlx::query!(
+            r#"
+            DELETE FROM storage_logs USING (
+                SELECT
+                    hashed_key,
+                    MAX(ARRAY[miniblock_number, operation_number]) AS op
+                FROM
+                    storage_logs
+                WHERE
+                    miniblock_number BETWEEN $1 AND $2
+                GROUP BY
+                    hashed_key
+            ) AS last_storage_logs
+            WHERE
+                storage_logs.miniblock_number <= $2
+                AND last_storage_logs.hashed_key = storage_logs.hashed_key
+                AND ARRAY[storage_logs.miniblock_number, storage_logs.operation_number] < last_storage_logs.op
+            "#,
+            miniblock_range.start(),
+            miniblock_range.end()
+        )
+        .instrument("prune_l1_batches#storage_logs")
+        .with_arg("miniblock_range", &miniblock_range)
+        .expect_slow_query()
+        .execute(&mut transaction)
+        .await?
+        .rows_affected();
+
+        let pruned_transactions = sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                data = data - 'factoryDeps',
+                updated_at = NOW()
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+                AND JSONB_TYPEOF(data -> 'factoryDeps') = 'array'
+            "#,
+            miniblock_range.start(),
+            miniblock_range.end()
+        )
+        .instrument("prune_l1_batches#transactions")
+        .with_arg("miniblock_range", &miniblock_range)
+        .execute(&mut transaction)
+        .await?
+        .rows_affected();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                pruning_log (pruned_l1_batch, pruned_miniblock, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            "#,
+            i64::from(last_l1_batch.0),
+            i64::from(last_miniblock.0)
+        )
+        .instrument("prune_l1_batches#insert_pruning_log")
+        .with_arg("last_l1_batch", &last_l1_batch)
+        .execute(&mut transaction)
+        .await?;
+
+        transaction.commit().await?;
+        Ok(PruningStats {
+            deleted_events,
+            deleted_call_traces,
+            deleted_storage_logs,
+            pruned_transactions,
+        })
+    }
+
+    /// Clamps the pruning log to the specified L1 batch, which must end with `last_miniblock_to_keep`.
+    /// If L1 batches after it were pruned, the batch itself is recorded as the last pruned one, since data
+    /// for it is pruned as well. Used when rolling back L1 batches.
+    pub async fn rollback_pruning_log(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        last_miniblock_to_keep: MiniblockNumber,
+    ) -> sqlx::Result<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        let deleted_entries = sqlx::query!(
+            r#"
+            DELETE FROM pruning_log
+            WHERE
+                pruned_l1_batch > $1
+            "#,
+            i64::from(last_l1_batch_to_keep.0)
+        )
+        .instrument("rollback_pruning_log#delete")
+        .with_arg("last_l1_batch_to_keep", &last_l1_batch_to_keep)
+        .execute(&mut transaction)
+        .await?
+        .rows_affected();
+
+        if deleted_entries > 0 {
+            sqlx::query!(
+                r#"
+                INSERT INTO
+                    pruning_log (pruned_l1_batch, pruned_miniblock, created_at, updated_at)
+                VALUES
+                    ($1, $2, NOW(), NOW())
+                ON CONFLICT (pruned_l1_batch) DO NOTHING
+                "#,
+                i64::from(last_l1_batch_to_keep.0),
+                i64::from(last_miniblock_to_keep.0)
+            )
+            .instrument("rollback_pruning_log#insert")
+            .with_arg("last_l1_batch_to_keep", &last_l1_batch_to_keep)
+            .execute(&mut transaction)
+            .await?;
+        }
+        transaction.commit().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{AccountTreeId, Address, ProtocolVersion, StorageKey, StorageLog, H256};
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    async fn insert_miniblock(conn: &mut StorageProcessor<'_>, number: u32, logs: Vec<StorageLog>) {
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await
+            .unwrap();
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn pruning_overwritten_storage_logs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let key = StorageKey::new(account, H256::zero());
+        let other_key = StorageKey::new(account, H256::from_low_u64_be(1));
+        insert_miniblock(
+            &mut conn,
+            0,
+            vec![
+                StorageLog::new_write_log(key, H256::repeat_byte(1)),
+                StorageLog::new_write_log(other_key, H256::repeat_byte(1)),
+            ],
+        )
+        .await;
+        for number in 1..3 {
+            let log = StorageLog::new_write_log(key, H256::repeat_byte(number as u8 + 1));
+            insert_miniblock(&mut conn, number, vec![log]).await;
+        }
+
+        let stats = conn
+            .pruning_dal()
+            .prune_l1_batches(L1BatchNumber(0), MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(stats.deleted_storage_logs, 1);
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(
+            info,
+            Some(PruningInfo {
+                last_pruned_l1_batch: L1BatchNumber(0),
+                last_pruned_miniblock: MiniblockNumber(1),
+            })
+        );
+
+        // The log from the previously pruned miniblock #1 should be pruned as well.
+        let stats = conn
+            .pruning_dal()
+            .prune_l1_batches(L1BatchNumber(1), MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(stats.deleted_storage_logs, 1);
+
+        let mut logs = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await;
+        logs.sort_unstable_by_key(|log| log.miniblock_number);
+        let logs: Vec<_> = logs
+            .into_iter()
+            .map(|log| (log.key, log.value, log.miniblock_number))
+            .collect();
+        assert_eq!(
+            logs,
+            [
+                (*other_key.key(), H256::repeat_byte(1), MiniblockNumber(0)),
+                (*key.key(), H256::repeat_byte(3), MiniblockNumber(2)),
+            ]
+        );
+    }
+}
@@ -32,7 +32,6 @@ impl SnapshotsCreatorDal<'_, '_> {
             l1_batch_number.0 as i32
         )
         .instrument("get_storage_logs_count")
-        .report_latency()
         .expect_slow_query()
         .fetch_one(self.storage)
         .await?
@@ -60,7 +59,6 @@ impl SnapshotsCreatorDal<'_, '_> {
         )
         .instrument("get_storage_logs_row_count")
         .with_arg("miniblock_number", &at_miniblock)
-        .report_latency()
         .expect_slow_query()
         .fetch_one(self.storage)
         .await?;
@@ -117,7 +115,6 @@ impl SnapshotsCreatorDal<'_, '_> {
         .with_arg("miniblock_number", &miniblock_number)
         .with_arg("min_hashed_key", &hashed_keys_range.start())
         .with_arg("max_hashed_key", &hashed_keys_range.end())
-        .report_latency()
         .expect_slow_query()
         .fetch_all(self.storage)
         .await?
@@ -153,7 +150,6 @@ impl SnapshotsCreatorDal<'_, '_> {
             miniblock_number.0 as i64,
         )
         .instrument("get_all_factory_deps")
-        .report_latency()
         .expect_slow_query()
         .fetch_all(self.storage)
         .await?;
@@ -56,7 +56,6 @@ impl SnapshotsDal<'_, '_> {
             factory_deps_filepaths,
         )
         .instrument("add_snapshot")
-        .report_latency()
         .execute(self.storage)
         .await?;
         Ok(())
@@ -81,7 +80,8 @@ impl SnapshotsDal<'_, '_> {
             chunk_id as i32 + 1,
             storage_logs_filepath,
         )
-        .execute(self.storage.conn())
+        .instrument("add_storage_logs_filepath_for_snapshot")
+        .execute(self.storage)
         .await?;
 
         Ok(())
@@ -101,7 +101,6 @@ impl SnapshotsDal<'_, '_> {
             "#
         )
         .instrument("get_all_complete_snapshots")
-        .report_latency()
         .fetch_all(self.storage)
         .await?;
 
@@ -132,7 +131,6 @@ impl SnapshotsDal<'_, '_> {
             "#
         )
         .instrument("get_newest_snapshot_metadata")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
@@ -158,7 +156,6 @@ impl SnapshotsDal<'_, '_> {
             l1_batch_number.0 as i32
         )
         .instrument("get_snapshot_metadata")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
@@ -1,5 +1,13 @@
-use std::{collections::HashMap, ops, time::Instant};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops,
+    time::Instant,
+};
 
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt as _,
+};
 use sqlx::{types::chrono::Utc, Row};
 use zksync_types::{
     get_code_key, snapshots::SnapshotStorageLog, AccountTreeId, Address, L1BatchNumber,
@@ -124,7 +132,8 @@ impl StorageLogsDal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("append_storage_logs")
+        .fetch_one(self.storage)
         .await?
         .max
         .map(|max| max as u32 + 1)
@@ -187,7 +196,8 @@ impl StorageLogsDal<'_, '_> {
             "#,
             &keys_to_delete as &[&[u8]],
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_storage#delete_storage")
+        .execute(self.storage)
         .await?;
 
         tracing::info!(
@@ -210,7 +220,8 @@ impl StorageLogsDal<'_, '_> {
             &keys_to_update as &[&[u8]],
             &values_to_update as &[&[u8]],
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_storage#update_storage")
+        .execute(self.storage)
         .await?;
 
         tracing::info!(
@@ -242,7 +253,8 @@ impl StorageLogsDal<'_, '_> {
             "#,
             miniblock_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("modified_keys_since_miniblock")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| H256::from_slice(&row.hashed_key))
@@ -262,7 +274,8 @@ impl StorageLogsDal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_storage_logs")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -293,7 +306,8 @@ impl StorageLogsDal<'_, '_> {
             hashed_key.as_bytes(),
             FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes(),
         )
-        .fetch_one(self.storage.conn())
+        .instrument("is_contract_deployed_at_address")
+        .fetch_one(self.storage)
         .await
         .unwrap();
 
@@ -335,7 +349,8 @@ impl StorageLogsDal<'_, '_> {
             &bytecode_hashed_keys as &[_],
             max_miniblock_number as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("filter_deployed_contracts")
+        .fetch_all(self.storage)
         .await?;
 
         let deployment_data = rows.into_iter().filter_map(|row| {
@@ -385,7 +400,8 @@ impl StorageLogsDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_touched_slots_for_l1_batch")
+        .fetch_all(self.storage)
         .await?;
 
         let touched_slots = rows.into_iter().map(|row| {
@@ -398,6 +414,151 @@ impl StorageLogsDal<'_, '_> {
         Ok(touched_slots.collect())
     }
 
+    /// Same as [`Self::get_touched_slots_for_l1_batch()`], but returns latest values for each miniblock
+    /// in the L1 batch separately. Miniblocks without storage logs are omitted.
+    pub async fn get_touched_slots_by_miniblock_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<BTreeMap<MiniblockNumber, HashMap<StorageKey, H256>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number,
+                address,
+                key,
+                value
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number BETWEEN (
+                    SELECT
+                        MIN(number)
+                    FROM
+                        miniblocks
+                    WHERE
+                        l1_batch_number = $1
+                ) AND (
+                    SELECT
+                        MAX(number)
+                    FROM
+                        miniblocks
+                    WHERE
+                        l1_batch_number = $1
+                )
+            ORDER BY
+                miniblock_number,
+                operation_number
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_touched_slots_by_miniblock_for_l1_batch")
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut touched_slots = BTreeMap::<_, HashMap<_, _>>::new();
+        for row in rows {
+            let key = StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            );
+            touched_slots
+                .entry(MiniblockNumber(row.miniblock_number as u32))
+                .or_default()
+                .insert(key, H256::from_slice(&row.value));
+        }
+        Ok(touched_slots)
+    }
+
+    /// Streams storage logs for the specified L1 batch in chunks of at most `chunk_size` logs. Logs are ordered
+    /// in the same way they were applied (i.e., by miniblock and operation number).
+    ///
+    /// Logs are read using a server-side cursor, so memory consumption is bounded regardless of the L1 batch size.
+    /// The cursor requires a DB transaction, which is held until the returned stream is exhausted or dropped.
+    pub fn stream_storage_logs_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        chunk_size: usize,
+    ) -> BoxStream<'_, sqlx::Result<Vec<StorageLog>>> {
+        assert!(chunk_size > 0, "Chunk size must be positive");
+
+        enum CursorState<'s, 'c> {
+            NotDeclared(&'s mut StorageProcessor<'c>),
+            Declared(StorageProcessor<'s>),
+            Exhausted,
+        }
+
+        let initial_state = CursorState::NotDeclared(&mut *self.storage);
+        stream::try_unfold(initial_state, move |state| async move {
+            let mut transaction = match state {
+                CursorState::NotDeclared(storage) => {
+                    let mut transaction = storage.start_transaction().await?;
+                    let miniblock_range = transaction
+                        .blocks_dal()
+                        .get_miniblock_range_of_l1_batch(l1_batch_number)
+                        .await?;
+                    let Some((first_miniblock, last_miniblock)) = miniblock_range else {
+                        return Ok(None);
+                    };
+                    sqlx::query!(
+                        r#"
+                        DECLARE storage_logs_cursor NO SCROLL CURSOR FOR
+                        SELECT
+                            address,
+                            key,
+                            value
+                        FROM
+                            storage_logs
+                        WHERE
+                            miniblock_number BETWEEN $1 AND $2
+                        ORDER BY
+                            miniblock_number,
+                            operation_number
+                        "#,
+                        first_miniblock.0 as i64,
+                        last_miniblock.0 as i64
+                    )
+                    .instrument("stream_storage_logs_for_l1_batch#declare_cursor")
+                    .execute(&mut transaction)
+                    .await?;
+                    transaction
+                }
+                CursorState::Declared(transaction) => transaction,
+                CursorState::Exhausted => return Ok(None),
+            };
+
+            // `FETCH` doesn't support parameterizing the row count, so we have to use a dynamic query.
+            let fetch_query = format!("FETCH FORWARD {chunk_size} FROM storage_logs_cursor");
+            let rows = sqlx::query(&fetch_query)
+                .instrument("stream_storage_logs_for_l1_batch#fetch")
+                .fetch_all(&mut transaction)
+                .await?;
+            if rows.is_empty() {
+                return Ok(None);
+            }
+            let logs = rows
+                .iter()
+                .map(|row| {
+                    let address: Vec<u8> = row.try_get("address")?;
+                    let key: Vec<u8> = row.try_get("key")?;
+                    let value: Vec<u8> = row.try_get("value")?;
+                    let key = StorageKey::new(
+                        AccountTreeId::new(Address::from_slice(&address)),
+                        H256::from_slice(&key),
+                    );
+                    Ok(StorageLog::new_write_log(key, H256::from_slice(&value)))
+                })
+                .collect::<sqlx::Result<Vec<_>>>()?;
+
+            let next_state = if rows.len() < chunk_size {
+                CursorState::Exhausted
+            } else {
+                CursorState::Declared(transaction)
+            };
+            Ok(Some((logs, next_state)))
+        })
+        .boxed()
+    }
+
     /// Returns (hashed) storage keys and the corresponding values that need to be applied to a storage
     /// in order to revert it to the specified L1 batch. Deduplication is taken into account.
     pub async fn get_storage_logs_for_revert(
@@ -505,7 +666,6 @@ impl StorageLogsDal<'_, '_> {
             &hashed_keys as &[&[u8]],
         )
         .instrument("get_l1_batches_and_indices_for_initial_writes")
-        .report_latency()
         .fetch_all(self.storage)
         .await?;
 
@@ -582,7 +742,8 @@ impl StorageLogsDal<'_, '_> {
             &hashed_keys as &[&[u8]],
             miniblock_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_storage_values")
+        .fetch_all(self.storage)
         .await?;
 
         Ok(rows
@@ -611,7 +772,8 @@ impl StorageLogsDal<'_, '_> {
                 storage_logs
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("dump_all_storage_logs_for_tests")
+        .fetch_all(self.storage)
         .await
         .expect("get_all_storage_logs_for_tests");
 
@@ -646,7 +808,8 @@ impl StorageLogsDal<'_, '_> {
             "SELECT COUNT(*) FROM storage_logs WHERE miniblock_number = $1",
             miniblock_number.0 as i32
         )
-        .fetch_one(self.storage.conn())
+        .instrument("count_miniblock_storage_logs")
+        .fetch_one(self.storage)
         .await?;
         Ok(count.unwrap_or(0) as u64)
     }
@@ -696,7 +859,8 @@ impl StorageLogsDal<'_, '_> {
             &start_keys as &[&[u8]],
             &end_keys as &[&[u8]],
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_chunk_starts_for_miniblock")
+        .fetch_all(self.storage)
         .await?;
 
         let rows = rows.into_iter().map(|row| {
@@ -736,7 +900,8 @@ impl StorageLogsDal<'_, '_> {
             key_range.start().as_bytes(),
             key_range.end().as_bytes()
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_tree_entries_for_miniblock")
+        .fetch_all(self.storage)
         .await?;
 
         let rows = rows.into_iter().map(|row| StorageRecoveryLogEntry {
@@ -762,7 +927,8 @@ impl StorageLogsDal<'_, '_> {
             miniblock_number.0 as i64,
             &operation_numbers
         )
-        .execute(self.storage.conn())
+        .instrument("retain_storage_logs")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -781,7 +947,8 @@ impl StorageLogsDal<'_, '_> {
             ORDER BY operation_number"
         ))
         .bind(miniblock_number.0 as i64)
-        .fetch_all(self.storage.conn())
+        .instrument("get_miniblock_storage_logs_from_table")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -811,7 +978,8 @@ impl StorageLogsDal<'_, '_> {
         sqlx::query(&query_str)
             .bind(hashed_key.as_bytes())
             .bind(miniblock_number.0 as i64)
-            .fetch_optional(self.storage.conn())
+            .instrument("get_storage_value_from_table")
+            .fetch_optional(self.storage)
             .await
             .unwrap()
             .map(|row| H256::from_slice(row.get("value")))
@@ -826,7 +994,8 @@ impl StorageLogsDal<'_, '_> {
             VACUUM storage_logs
             "#
         )
-        .execute(self.storage.conn())
+        .instrument("vacuum_storage_logs")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -834,6 +1003,7 @@ impl StorageLogsDal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
+    use futures::TryStreamExt as _;
     use zksync_contracts::BaseSystemContractsHashes;
     use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
 
@@ -915,6 +1085,68 @@ mod tests {
         test_rollback(&mut conn, first_key, second_key).await;
     }
 
+    #[tokio::test]
+    async fn getting_touched_slots_by_miniblock() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let first_key = StorageKey::new(account, H256::zero());
+        let second_key = StorageKey::new(account, H256::from_low_u64_be(1));
+        let miniblock_logs = [
+            vec![
+                StorageLog::new_write_log(first_key, H256::repeat_byte(1)),
+                StorageLog::new_write_log(second_key, H256::repeat_byte(2)),
+                StorageLog::new_write_log(first_key, H256::repeat_byte(3)),
+            ],
+            vec![],
+            vec![StorageLog::new_write_log(second_key, H256::repeat_byte(4))],
+        ];
+        for (number, logs) in (1..).zip(miniblock_logs) {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+            conn.storage_logs_dal()
+                .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+                .await
+                .unwrap();
+        }
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            0,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+
+        let touched_slots = conn
+            .storage_logs_dal()
+            .get_touched_slots_by_miniblock_for_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(touched_slots.len(), 2);
+        let first_miniblock_slots = &touched_slots[&MiniblockNumber(1)];
+        assert_eq!(first_miniblock_slots.len(), 2);
+        assert_eq!(first_miniblock_slots[&first_key], H256::repeat_byte(3));
+        assert_eq!(first_miniblock_slots[&second_key], H256::repeat_byte(2));
+        let last_miniblock_slots = &touched_slots[&MiniblockNumber(3)];
+        assert_eq!(
+            *last_miniblock_slots,
+            HashMap::from([(second_key, H256::repeat_byte(4))])
+        );
+    }
+
     async fn test_rollback(
         conn: &mut StorageProcessor<'_>,
         key: StorageKey,
@@ -992,6 +1224,42 @@ mod tests {
         assert_eq!(value, H256::zero());
     }
 
+    #[tokio::test]
+    async fn streaming_storage_logs_for_l1_batch() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let logs: Vec<_> = (0_u64..5)
+            .map(|i| {
+                let key = StorageKey::new(account, H256::from_low_u64_be(i));
+                StorageLog::new_write_log(key, H256::repeat_byte(i as u8 + 1))
+            })
+            .collect();
+        insert_miniblock(&mut conn, 1, logs.clone()).await;
+
+        let chunks: Vec<_> = conn
+            .storage_logs_dal()
+            .stream_storage_logs_for_l1_batch(L1BatchNumber(1), 2)
+            .try_collect()
+            .await
+            .unwrap();
+        let chunk_lengths: Vec<_> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(chunk_lengths, [2, 2, 1]);
+        assert_eq!(chunks.concat(), logs);
+
+        let chunks: Vec<_> = conn
+            .storage_logs_dal()
+            .stream_storage_logs_for_l1_batch(L1BatchNumber(2), 2)
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(chunks.is_empty());
+    }
+
     #[tokio::test]
     async fn getting_storage_logs_for_revert() {
         let pool = ConnectionPool::test_pool().await;
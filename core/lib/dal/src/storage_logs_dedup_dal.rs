@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use sqlx::types::chrono::Utc;
 use zksync_types::{
@@ -8,7 +8,7 @@ use zksync_types::{
 use zksync_utils::u256_to_h256;
 
 pub use crate::models::storage_log::DbInitialWrite;
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug)]
 pub struct StorageLogsDedupDal<'a, 'c> {
@@ -112,7 +112,8 @@ impl StorageLogsDedupDal<'_, '_> {
             &indices,
             l1_batch_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("insert_initial_writes")
+        .execute(self.storage)
         .await?;
 
         Ok(())
@@ -134,7 +135,8 @@ impl StorageLogsDedupDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_protective_reads_for_l1_batch")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -156,7 +158,8 @@ impl StorageLogsDedupDal<'_, '_> {
                 initial_writes
             "#,
         )
-        .fetch_one(self.storage.conn())
+        .instrument("max_enumeration_index")
+        .fetch_one(self.storage)
         .await
         .unwrap()
         .max
@@ -181,7 +184,8 @@ impl StorageLogsDedupDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("initial_writes_for_batch")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -201,7 +205,8 @@ impl StorageLogsDedupDal<'_, '_> {
             "#,
             key.hashed_key().0.to_vec()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_enumeration_index_for_key")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| row.index as u64)
@@ -221,7 +226,8 @@ impl StorageLogsDedupDal<'_, '_> {
             "#,
             &hashed_keys as &[&[u8]],
         )
-        .fetch_all(self.storage.conn())
+        .instrument("filter_written_slots")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -229,6 +235,40 @@ impl StorageLogsDedupDal<'_, '_> {
         .collect()
     }
 
+    /// Returns enumeration indices for the specified `hashed_keys` together with the numbers of L1 batches
+    /// in which the keys were initially written to. Keys that were not written to yet are not present in the returned map.
+    pub async fn get_enumeration_indices(
+        &mut self,
+        hashed_keys: &[H256],
+    ) -> sqlx::Result<HashMap<H256, (u64, L1BatchNumber)>> {
+        let hashed_keys: Vec<_> = hashed_keys.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hashed_key,
+                INDEX,
+                l1_batch_number
+            FROM
+                initial_writes
+            WHERE
+                hashed_key = ANY ($1)
+            "#,
+            &hashed_keys as &[&[u8]],
+        )
+        .instrument("get_enumeration_indices")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let index = row.index as u64;
+                let l1_batch_number = L1BatchNumber(row.l1_batch_number as u32);
+                (H256::from_slice(&row.hashed_key), (index, l1_batch_number))
+            })
+            .collect())
+    }
+
     /// Retrieves all initial write entries for testing purposes.
     pub async fn dump_all_initial_writes_for_tests(&mut self) -> Vec<DbInitialWrite> {
         let rows = sqlx::query!(
@@ -241,7 +281,8 @@ impl StorageLogsDedupDal<'_, '_> {
                 initial_writes
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("dump_all_initial_writes_for_tests")
+        .fetch_all(self.storage)
         .await
         .expect("get_all_initial_writes_for_tests");
 
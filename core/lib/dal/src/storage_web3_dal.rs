@@ -91,6 +91,8 @@ impl StorageWeb3Dal<'_, '_> {
 
     /// This method does not check if a block with this number exists in the database.
     /// It will return the current value if the block is in the future.
+    /// It also doesn't check whether the block is pruned (see `PruningDal::get_pruning_info()`); for pruned blocks,
+    /// the returned value is meaningless, so callers must perform this check themselves.
     pub async fn get_historical_value_unchecked(
         &mut self,
         key: &StorageKey,
@@ -120,7 +122,6 @@ impl StorageWeb3Dal<'_, '_> {
             block_number.0 as i64
         )
         .instrument("get_historical_value_unchecked")
-        .report_latency()
         .with_arg("key", &hashed_key)
         .fetch_optional(self.storage)
         .await
@@ -167,7 +168,8 @@ impl StorageWeb3Dal<'_, '_> {
             "#,
             miniblock_number.0 as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("resolve_l1_batch_number_of_miniblock")
+        .fetch_one(self.storage)
         .await?;
 
         Ok(ResolvedL1BatchForMiniblock {
@@ -193,7 +195,6 @@ impl StorageWeb3Dal<'_, '_> {
             hashed_key.as_bytes(),
         )
         .instrument("get_l1_batch_number_for_initial_write")
-        .report_latency()
         .with_arg("key", &hashed_key)
         .fetch_optional(self.storage)
         .await?;
@@ -219,7 +220,8 @@ impl StorageWeb3Dal<'_, '_> {
             miniblock_numbers.start().0 as i64,
             miniblock_numbers.end().0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("modified_keys_in_miniblocks")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -263,7 +265,8 @@ impl StorageWeb3Dal<'_, '_> {
                 block_number.0 as i64,
                 FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes(),
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_contract_code_unchecked")
+            .fetch_optional(self.storage)
             .await
             .map(|option_row| option_row.map(|row| row.bytecode))
         }
@@ -290,7 +293,8 @@ impl StorageWeb3Dal<'_, '_> {
                 hash.as_bytes(),
                 block_number.0 as i64
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_factory_dep_unchecked")
+            .fetch_optional(self.storage)
             .await
             .map(|option_row| option_row.map(|row| row.bytecode))
         }
@@ -164,7 +164,8 @@ mod tests {
         let tx = mock_l2_transaction();
         conn.transactions_dal()
             .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
-            .await;
+            .await
+            .unwrap();
         conn.blocks_dal()
             .insert_miniblock(&miniblock_header)
             .await
@@ -175,7 +176,8 @@ mod tests {
                 &[mock_execution_result(tx.clone())],
                 1.into(),
             )
-            .await;
+            .await
+            .unwrap();
 
         let block = conn
             .sync_dal()
@@ -27,7 +27,8 @@ impl SystemDal<'_, '_> {
                  pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() AS synced, \
                  EXTRACT(SECONDS FROM now() - pg_last_xact_replay_timestamp())::int AS lag",
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_replication_lag_sec")
+        .fetch_one(self.storage)
         .await
         .unwrap();
 
@@ -43,18 +44,17 @@ impl SystemDal<'_, '_> {
             r#"
             SELECT
                 table_name,
-                PG_TABLE_SIZE(('public.' || QUOTE_IDENT(table_name))::regclass) AS table_size,
-                PG_INDEXES_SIZE(('public.' || QUOTE_IDENT(table_name))::regclass) AS indexes_size,
-                PG_RELATION_SIZE(('public.' || QUOTE_IDENT(table_name))::regclass) AS relation_size,
-                PG_TOTAL_RELATION_SIZE(('public.' || QUOTE_IDENT(table_name))::regclass) AS total_size
+                PG_TABLE_SIZE((QUOTE_IDENT(table_schema) || '.' || QUOTE_IDENT(table_name))::regclass) AS table_size,
+                PG_INDEXES_SIZE((QUOTE_IDENT(table_schema) || '.' || QUOTE_IDENT(table_name))::regclass) AS indexes_size,
+                PG_RELATION_SIZE((QUOTE_IDENT(table_schema) || '.' || QUOTE_IDENT(table_name))::regclass) AS relation_size,
+                PG_TOTAL_RELATION_SIZE((QUOTE_IDENT(table_schema) || '.' || QUOTE_IDENT(table_name))::regclass) AS total_size
             FROM
                 information_schema.tables
             WHERE
-                table_schema = 'public'
+                table_schema = CURRENT_SCHEMA()
             "#
         )
         .instrument("get_table_sizes")
-        .report_latency()
         .fetch_all(self.storage)
         .await?;
 
@@ -171,13 +171,15 @@ async fn workflow_with_submit_tx_equal_hashes() {
     let tx = mock_l2_transaction();
     let result = transactions_dal
         .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(result, L2TxSubmissionResult::Added);
 
     let result = transactions_dal
         .insert_transaction_l2(tx, mock_tx_execution_metrics())
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(result, L2TxSubmissionResult::Duplicate);
 }
@@ -195,7 +197,8 @@ async fn workflow_with_submit_tx_diff_hashes() {
 
     let result = transactions_dal
         .insert_transaction_l2(tx, mock_tx_execution_metrics())
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(result, L2TxSubmissionResult::Added);
 
@@ -204,7 +207,8 @@ async fn workflow_with_submit_tx_diff_hashes() {
     tx.common_data.initiator_address = initiator_address;
     let result = transactions_dal
         .insert_transaction_l2(tx, mock_tx_execution_metrics())
-        .await;
+        .await
+        .unwrap();
 
     assert_eq!(result, L2TxSubmissionResult::Replaced);
 }
@@ -226,19 +230,22 @@ async fn remove_stuck_txs() {
     tx.received_timestamp_ms = unix_timestamp_ms() - Duration::new(1000, 0).as_millis() as u64;
     transactions_dal
         .insert_transaction_l2(tx, mock_tx_execution_metrics())
-        .await;
+        .await
+        .unwrap();
     // Tx in mempool
     let tx = mock_l2_transaction();
     transactions_dal
         .insert_transaction_l2(tx, mock_tx_execution_metrics())
-        .await;
+        .await
+        .unwrap();
 
     // Stuck L1 tx. We should never ever remove L1 tx
     let mut tx = mock_l1_execute();
     tx.received_timestamp_ms = unix_timestamp_ms() - Duration::new(1000, 0).as_millis() as u64;
     transactions_dal
         .insert_transaction_l1(tx, L1BlockNumber(1))
-        .await;
+        .await
+        .unwrap();
 
     // Old executed tx
     let mut executed_tx = mock_l2_transaction();
@@ -246,7 +253,8 @@ async fn remove_stuck_txs() {
         unix_timestamp_ms() - Duration::new(1000, 0).as_millis() as u64;
     transactions_dal
         .insert_transaction_l2(executed_tx.clone(), mock_tx_execution_metrics())
-        .await;
+        .await
+        .unwrap();
 
     // Get all txs
     transactions_dal.reset_mempool().await.unwrap();
@@ -269,7 +277,8 @@ async fn remove_stuck_txs() {
             &[mock_execution_result(executed_tx.clone())],
             U256::from(1),
         )
-        .await;
+        .await
+        .unwrap();
 
     // Get all txs
     transactions_dal.reset_mempool().await.unwrap();
@@ -302,3 +311,45 @@ async fn remove_stuck_txs() {
 
     assert_eq!(receipts.len(), 1);
 }
+
+#[tokio::test]
+async fn remove_evicted_txs() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    let mut transactions_dal = TransactionsDal { storage };
+
+    let evicted_tx = mock_l2_transaction();
+    let kept_tx = mock_l2_transaction();
+    let not_in_mempool_tx = mock_l2_transaction();
+    for tx in [&evicted_tx, &kept_tx] {
+        transactions_dal
+            .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
+            .await
+            .unwrap();
+    }
+    let txs = transactions_dal
+        .sync_mempool(&[], &[], 0, 0, 1000)
+        .await
+        .unwrap();
+    assert_eq!(txs.len(), 2);
+    transactions_dal
+        .insert_transaction_l2(not_in_mempool_tx.clone(), mock_tx_execution_metrics())
+        .await
+        .unwrap();
+
+    let removed_txs = transactions_dal
+        .remove_evicted_txs(&[evicted_tx.hash(), not_in_mempool_tx.hash()])
+        .await
+        .unwrap();
+    assert_eq!(removed_txs, 1);
+
+    transactions_dal.reset_mempool().await.unwrap();
+    let txs = transactions_dal
+        .sync_mempool(&[], &[], 0, 0, 1000)
+        .await
+        .unwrap();
+    let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+    assert_eq!(tx_hashes.len(), 2);
+    assert!(tx_hashes.contains(&kept_tx.hash()));
+    assert!(tx_hashes.contains(&not_in_mempool_tx.hash()));
+}
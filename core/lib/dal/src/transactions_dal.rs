@@ -5,6 +5,7 @@ use bigdecimal::BigDecimal;
 use itertools::Itertools;
 use sqlx::{error, types::chrono::NaiveDateTime};
 use zksync_types::{
+    api,
     block::MiniblockExecutionData,
     fee::TransactionExecutionMetrics,
     l1::L1Tx,
@@ -12,10 +13,10 @@ use zksync_types::{
     protocol_version::ProtocolUpgradeTx,
     tx::{tx_execution_info::TxExecutionStatus, TransactionExecutionResult},
     vm_trace::Call,
-    Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, PriorityOpId,
-    Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
+    Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, Nonce,
+    PriorityOpId, Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
-use zksync_utils::u256_to_big_decimal;
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
 
 use crate::{
     instrument::InstrumentExt,
@@ -31,6 +32,9 @@ pub enum L2TxSubmissionResult {
     AlreadyExecuted,
     Duplicate,
     Proxied,
+    /// Transaction would replace a pending transaction, but doesn't raise `max_fee_per_gas` sufficiently.
+    /// Contains the minimum `max_fee_per_gas` required for the replacement.
+    ReplacementUnderpriced(U256),
 }
 
 impl fmt::Display for L2TxSubmissionResult {
@@ -41,6 +45,7 @@ impl fmt::Display for L2TxSubmissionResult {
             Self::AlreadyExecuted => "already_executed",
             Self::Duplicate => "duplicate",
             Self::Proxied => "proxied",
+            Self::ReplacementUnderpriced(_) => "replacement_underpriced",
         })
     }
 }
@@ -52,8 +57,22 @@ pub struct TransactionsDal<'c, 'a> {
 
 type TxLocations = Vec<(MiniblockNumber, Vec<(H256, u32, u16)>)>;
 
+/// Returns the minimum `max_fee_per_gas` of a transaction replacing a pending transaction with the specified fee.
+fn min_replacement_fee(pending_max_fee_per_gas: U256, fee_bump_percent: u32) -> U256 {
+    let multiplier = U256::from(100) + U256::from(fee_bump_percent);
+    // Round up, so that the returned fee always passes the check in `insert_transaction_l2()`.
+    pending_max_fee_per_gas
+        .saturating_mul(multiplier)
+        .saturating_add(99.into())
+        / 100
+}
+
 impl TransactionsDal<'_, '_> {
-    pub async fn insert_transaction_l1(&mut self, tx: L1Tx, l1_block_number: L1BlockNumber) {
+    pub async fn insert_transaction_l1(
+        &mut self,
+        tx: L1Tx,
+        l1_block_number: L1BlockNumber,
+    ) -> sqlx::Result<()> {
         {
             let contract_address = tx.execute.contract_address.as_bytes();
             let tx_hash = tx.hash();
@@ -149,13 +168,14 @@ impl TransactionsDal<'_, '_> {
                 refund_recipient,
                 received_at,
             )
-            .fetch_optional(self.storage.conn())
-            .await
-            .unwrap();
+            .instrument("insert_transaction_l1")
+            .execute(self.storage)
+            .await?;
         }
+        Ok(())
     }
 
-    pub async fn insert_system_transaction(&mut self, tx: ProtocolUpgradeTx) {
+    pub async fn insert_system_transaction(&mut self, tx: ProtocolUpgradeTx) -> sqlx::Result<()> {
         {
             let contract_address = tx.execute.contract_address.as_bytes().to_vec();
             let tx_hash = tx.common_data.hash().0.to_vec();
@@ -243,17 +263,40 @@ impl TransactionsDal<'_, '_> {
                 refund_recipient,
                 received_at,
             )
-            .fetch_optional(self.storage.conn())
-            .await
-            .unwrap();
+            .instrument("insert_system_transaction")
+            .execute(self.storage)
+            .await?;
         }
+        Ok(())
     }
 
     pub async fn insert_transaction_l2(
         &mut self,
         tx: L2Tx,
         exec_info: TransactionExecutionMetrics,
-    ) -> L2TxSubmissionResult {
+    ) -> sqlx::Result<L2TxSubmissionResult> {
+        self.insert_transaction_l2_inner(tx, exec_info, None).await
+    }
+
+    /// Same as [`Self::insert_transaction_l2()`], but a pending transaction with the same initiator and nonce
+    /// is only replaced if `tx` raises `max_fee_per_gas` by at least `fee_bump_percent`. The check is performed
+    /// atomically with the replacement. Rejected pending transactions can be replaced regardless of the fee.
+    pub async fn insert_transaction_l2_with_fee_bump(
+        &mut self,
+        tx: L2Tx,
+        exec_info: TransactionExecutionMetrics,
+        fee_bump_percent: u32,
+    ) -> sqlx::Result<L2TxSubmissionResult> {
+        self.insert_transaction_l2_inner(tx, exec_info, Some(fee_bump_percent))
+            .await
+    }
+
+    async fn insert_transaction_l2_inner(
+        &mut self,
+        tx: L2Tx,
+        exec_info: TransactionExecutionMetrics,
+        fee_bump_percent: Option<u32>,
+    ) -> sqlx::Result<L2TxSubmissionResult> {
         {
             let tx_hash = tx.hash();
             let is_duplicate = sqlx::query!(
@@ -267,14 +310,14 @@ impl TransactionsDal<'_, '_> {
                 "#,
                 tx_hash.as_bytes(),
             )
-            .fetch_optional(self.storage.conn())
-            .await
-            .unwrap()
+            .instrument("insert_transaction_l2#transactions")
+            .fetch_optional(self.storage)
+            .await?
             .is_some();
 
             if is_duplicate {
                 tracing::debug!("Prevented inserting duplicate L2 transaction {tx_hash:?} to DB");
-                return L2TxSubmissionResult::Duplicate;
+                return Ok(L2TxSubmissionResult::Duplicate);
             }
 
             let initiator_address = tx.initiator_account();
@@ -297,149 +340,214 @@ impl TransactionsDal<'_, '_> {
             let secs = (tx.received_timestamp_ms / 1000) as i64;
             let nanosecs = ((tx.received_timestamp_ms % 1000) * 1_000_000) as u32;
             let received_at = NaiveDateTime::from_timestamp_opt(secs, nanosecs).unwrap();
-            // Besides just adding or updating(on conflict) the record, we want to extract some info
-            // from the query below, to indicate what actually happened:
-            // 1) transaction is added
-            // 2) transaction is replaced
-            // 3) WHERE clause conditions for DO UPDATE block were not met, so the transaction can't be replaced
-            // the subquery in RETURNING clause looks into pre-UPDATE state of the table. So if the subquery will return NULL
-            // transaction is fresh and was added to db(the second condition of RETURNING clause checks it).
-            // Otherwise, if the subquery won't return NULL it means that there is already tx with such nonce and `initiator_address` in DB
-            // and we can replace it WHERE clause conditions are met.
-            // It is worth mentioning that if WHERE clause conditions are not met, None will be returned.
-            let query_result = sqlx::query!(
-                r#"
-                INSERT INTO
-                    transactions (
-                        hash,
-                        is_priority,
-                        initiator_address,
-                        nonce,
-                        signature,
-                        gas_limit,
-                        max_fee_per_gas,
-                        max_priority_fee_per_gas,
-                        gas_per_pubdata_limit,
-                        input,
-                        data,
-                        tx_format,
-                        contract_address,
-                        value,
-                        paymaster,
-                        paymaster_input,
-                        execution_info,
-                        received_at,
-                        created_at,
-                        updated_at
-                    )
-                VALUES
-                    (
-                        $1,
-                        FALSE,
-                        $2,
-                        $3,
-                        $4,
-                        $5,
-                        $6,
-                        $7,
-                        $8,
-                        $9,
-                        $10,
-                        $11,
-                        $12,
-                        $13,
-                        $14,
-                        $15,
-                        JSONB_BUILD_OBJECT('gas_used', $16::BIGINT, 'storage_writes', $17::INT, 'contracts_used', $18::INT),
-                        $19,
-                        NOW(),
-                        NOW()
-                    )
-                ON CONFLICT (initiator_address, nonce) DO
-                UPDATE
-                SET
-                    hash = $1,
-                    signature = $4,
-                    gas_limit = $5,
-                    max_fee_per_gas = $6,
-                    max_priority_fee_per_gas = $7,
-                    gas_per_pubdata_limit = $8,
-                    input = $9,
-                    data = $10,
-                    tx_format = $11,
-                    contract_address = $12,
-                    value = $13,
-                    paymaster = $14,
-                    paymaster_input = $15,
-                    execution_info = JSONB_BUILD_OBJECT('gas_used', $16::BIGINT, 'storage_writes', $17::INT, 'contracts_used', $18::INT),
-                    in_mempool = FALSE,
-                    received_at = $19,
-                    created_at = NOW(),
-                    updated_at = NOW(),
-                    error = NULL
-                WHERE
-                    transactions.is_priority = FALSE
-                    AND transactions.miniblock_number IS NULL
-                RETURNING
-                    (
-                        SELECT
-                            hash
-                        FROM
-                            transactions
-                        WHERE
-                            transactions.initiator_address = $2
-                            AND transactions.nonce = $3
-                    ) IS NOT NULL AS "is_replaced!"
-                "#,
-                tx_hash.as_bytes(),
-                initiator_address.as_bytes(),
-                nonce,
-                &signature,
-                gas_limit,
-                max_fee_per_gas,
-                max_priority_fee_per_gas,
-                gas_per_pubdata_limit,
-                input_data,
-                &json_data,
-                tx_format,
-                contract_address,
-                value,
-                &paymaster,
-                &paymaster_input,
-                exec_info.gas_used as i64,
-                (exec_info.initial_storage_writes + exec_info.repeated_storage_writes) as i32,
-                exec_info.contracts_used as i32,
-                received_at
-            )
-                .fetch_optional(self.storage.conn())
-                .await
-                .map(|option_record| option_record.map(|record| record.is_replaced));
-
-            let l2_tx_insertion_result = match query_result {
-                Ok(option_query_result) => match option_query_result {
-                    Some(true) => L2TxSubmissionResult::Replaced,
-                    Some(false) => L2TxSubmissionResult::Added,
-                    None => L2TxSubmissionResult::AlreadyExecuted,
-                },
-                Err(err) => {
-                    // So, we consider a tx hash to be a primary key of the transaction
-                    // Based on the idea that we can't have two transactions with the same hash
-                    // We assume that if there already exists some transaction with some tx hash
-                    // another tx with the same tx hash is supposed to have the same data
-                    // In this case we identify it as Duplicate
-                    // Note, this error can happen because of the race condition (tx can be taken by several
-                    // API servers, that simultaneously start execute it and try to inserted to DB)
-                    if let error::Error::Database(ref error) = err {
-                        if let Some(constraint) = error.constraint() {
-                            if constraint == "transactions_pkey" {
-                                tracing::debug!("Attempted to insert duplicate L2 transaction {tx_hash:?} to DB");
-                                return L2TxSubmissionResult::Duplicate;
+            let mut transaction = self.storage.start_transaction().await?;
+            let l2_tx_insertion_result = loop {
+                // Mark the pending transaction with the same initiator and nonce (if any) as replaced by `tx`,
+                // provided that it can be replaced (i.e., it's not executed, and `tx` raises its fee sufficiently).
+                // The pending transaction is locked, so the returned row reflects the state the replacement decision
+                // was based on. If there's no transaction with the same initiator and nonce, no rows are returned.
+                let pending_tx = sqlx::query!(
+                    r#"
+                    WITH
+                        pending AS (
+                            SELECT
+                                hash,
+                                is_priority,
+                                miniblock_number,
+                                error,
+                                max_fee_per_gas
+                            FROM
+                                transactions
+                            WHERE
+                                initiator_address = $1
+                                AND nonce = $2
+                                AND replaced_by IS NULL
+                            FOR UPDATE
+                        ),
+                        replaced AS (
+                            UPDATE transactions
+                            SET
+                                replaced_by = $3,
+                                in_mempool = FALSE,
+                                error = COALESCE(transactions.error, 'replaced by 0x' || ENCODE($3, 'hex')),
+                                updated_at = NOW()
+                            FROM
+                                pending
+                            WHERE
+                                transactions.hash = pending.hash
+                                AND pending.is_priority = FALSE
+                                AND pending.miniblock_number IS NULL
+                                AND (
+                                    $4::INT IS NULL
+                                    OR pending.error IS NOT NULL
+                                    OR pending.max_fee_per_gas IS NULL
+                                    OR $5::NUMERIC * 100 >= pending.max_fee_per_gas * (100 + $4::INT)
+                                )
+                            RETURNING
+                                transactions.hash
+                        )
+                    SELECT
+                        pending.hash AS "hash!",
+                        (
+                            pending.is_priority = FALSE
+                            AND pending.miniblock_number IS NULL
+                        ) AS "is_pending!",
+                        pending.max_fee_per_gas,
+                        EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                replaced
+                        ) AS "is_replaced!"
+                    FROM
+                        pending
+                    "#,
+                    initiator_address.as_bytes(),
+                    nonce,
+                    tx_hash.as_bytes(),
+                    fee_bump_percent.map(|percent| percent as i32),
+                    &max_fee_per_gas
+                )
+                .instrument("insert_transaction_l2#replace_transaction")
+                .fetch_optional(&mut transaction)
+                .await?;
+
+                let replaced_hash = match pending_tx {
+                    Some(row) if row.is_replaced => Some(H256::from_slice(&row.hash)),
+                    Some(row) => {
+                        // The transaction with the same initiator and nonce is either executed,
+                        // or the replacement doesn't raise the fee sufficiently.
+                        break match (row.is_pending, row.max_fee_per_gas, fee_bump_percent) {
+                            (true, Some(pending_max_fee_per_gas), Some(percent)) => {
+                                L2TxSubmissionResult::ReplacementUnderpriced(min_replacement_fee(
+                                    bigdecimal_to_u256(pending_max_fee_per_gas),
+                                    percent,
+                                ))
+                            }
+                            _ => L2TxSubmissionResult::AlreadyExecuted,
+                        };
+                    }
+                    None => None,
+                };
+
+                let insertion_result = sqlx::query!(
+                    r#"
+                    INSERT INTO
+                        transactions (
+                            hash,
+                            is_priority,
+                            initiator_address,
+                            nonce,
+                            signature,
+                            gas_limit,
+                            max_fee_per_gas,
+                            max_priority_fee_per_gas,
+                            gas_per_pubdata_limit,
+                            input,
+                            data,
+                            tx_format,
+                            contract_address,
+                            value,
+                            paymaster,
+                            paymaster_input,
+                            execution_info,
+                            received_at,
+                            created_at,
+                            updated_at
+                        )
+                    VALUES
+                        (
+                            $1,
+                            FALSE,
+                            $2,
+                            $3,
+                            $4,
+                            $5,
+                            $6,
+                            $7,
+                            $8,
+                            $9,
+                            $10,
+                            $11,
+                            $12,
+                            $13,
+                            $14,
+                            $15,
+                            JSONB_BUILD_OBJECT('gas_used', $16::BIGINT, 'storage_writes', $17::INT, 'contracts_used', $18::INT),
+                            $19,
+                            NOW(),
+                            NOW()
+                        )
+                    ON CONFLICT (initiator_address, nonce)
+                    WHERE
+                        replaced_by IS NULL DO NOTHING
+                    RETURNING
+                        hash
+                    "#,
+                    tx_hash.as_bytes(),
+                    initiator_address.as_bytes(),
+                    nonce,
+                    &signature,
+                    &gas_limit,
+                    &max_fee_per_gas,
+                    &max_priority_fee_per_gas,
+                    &gas_per_pubdata_limit,
+                    &input_data,
+                    &json_data,
+                    tx_format,
+                    contract_address,
+                    &value,
+                    &paymaster,
+                    &paymaster_input,
+                    exec_info.gas_used as i64,
+                    (exec_info.initial_storage_writes + exec_info.repeated_storage_writes) as i32,
+                    exec_info.contracts_used as i32,
+                    received_at
+                )
+                .instrument("insert_transaction_l2#insert_transactions")
+                .fetch_optional(&mut transaction)
+                .await;
+
+                match insertion_result {
+                    Ok(Some(_)) => {
+                        break if let Some(replaced_hash) = replaced_hash {
+                            tracing::info!(
+                                "Replaced pending L2 transaction {replaced_hash:?} with {tx_hash:?} (initiator {initiator_address:?}, nonce {nonce})"
+                            );
+                            L2TxSubmissionResult::Replaced
+                        } else {
+                            L2TxSubmissionResult::Added
+                        };
+                    }
+                    Ok(None) => {
+                        // A transaction with the same initiator and nonce was inserted concurrently. Since the insertion
+                        // waits for the concurrent DB transaction to commit, the next iteration will observe it.
+                        tracing::debug!(
+                            "Concurrent insertion of L2 transaction with initiator {initiator_address:?} and nonce {nonce}; retrying"
+                        );
+                    }
+                    Err(err) => {
+                        // So, we consider a tx hash to be a primary key of the transaction
+                        // Based on the idea that we can't have two transactions with the same hash
+                        // We assume that if there already exists some transaction with some tx hash
+                        // another tx with the same tx hash is supposed to have the same data
+                        // In this case we identify it as Duplicate
+                        // Note, this error can happen because of the race condition (tx can be taken by several
+                        // API servers, that simultaneously start execute it and try to inserted to DB)
+                        if let error::Error::Database(ref error) = err {
+                            if let Some(constraint) = error.constraint() {
+                                if constraint == "transactions_pkey" {
+                                    tracing::debug!("Attempted to insert duplicate L2 transaction {tx_hash:?} to DB");
+                                    return Ok(L2TxSubmissionResult::Duplicate);
+                                }
                             }
                         }
+                        return Err(err);
                     }
-                    panic!("{}", err);
                 }
             };
+            transaction.commit().await?;
+
             tracing::debug!(
                 "{:?} l2 transaction {:?} to DB. init_acc {:?} nonce {:?} returned option {:?}",
                 l2_tx_insertion_result,
@@ -449,15 +557,135 @@ impl TransactionsDal<'_, '_> {
                 l2_tx_insertion_result
             );
 
-            l2_tx_insertion_result
+            Ok(l2_tx_insertion_result)
         }
     }
 
+    /// Returns the hash and `max_fee_per_gas` of a pending L2 transaction with the specified initiator and nonce,
+    /// i.e. the transaction that would be replaced by [`Self::insert_transaction_l2()`]. Rejected transactions
+    /// are not considered pending.
+    pub async fn get_pending_transaction_fee(
+        &mut self,
+        initiator_address: Address,
+        nonce: Nonce,
+    ) -> sqlx::Result<Option<(H256, U256)>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                max_fee_per_gas
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND nonce = $2
+                AND replaced_by IS NULL
+                AND is_priority = FALSE
+                AND miniblock_number IS NULL
+                AND error IS NULL
+            "#,
+            initiator_address.as_bytes(),
+            i64::from(nonce.0)
+        )
+        .instrument("get_pending_transaction_fee")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("nonce", &nonce)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.and_then(|row| {
+            let max_fee_per_gas = bigdecimal_to_u256(row.max_fee_per_gas?);
+            Some((H256::from_slice(&row.hash), max_fee_per_gas))
+        }))
+    }
+
+    #[cfg(test)]
+    pub(crate) async fn get_replacement_hash(
+        &mut self,
+        tx_hash: H256,
+    ) -> sqlx::Result<Option<H256>> {
+        use sqlx::Row as _;
+
+        let row = sqlx::query("SELECT replaced_by FROM transactions WHERE hash = $1")
+            .bind(tx_hash.as_bytes())
+            .instrument("get_replacement_hash")
+            .fetch_one(self.storage)
+            .await?;
+        let replaced_by: Option<Vec<u8>> = row.get("replaced_by");
+        Ok(replaced_by.map(|hash| H256::from_slice(&hash)))
+    }
+
+    /// Saves conditions for a pending L2 transaction submitted via `eth_sendRawTransactionConditional`.
+    /// Should be called in the same DB transaction as [`Self::insert_transaction_l2()`].
+    pub async fn insert_transaction_conditions(
+        &mut self,
+        tx_hash: H256,
+        conditions: &api::TransactionConditions,
+    ) -> sqlx::Result<()> {
+        let conditions =
+            serde_json::to_value(conditions).expect("failed serializing transaction conditions");
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                transaction_conditions (hash, conditions)
+            VALUES
+                ($1, $2)
+            ON CONFLICT (hash) DO
+            UPDATE
+            SET
+                conditions = $2
+            "#,
+            tx_hash.as_bytes(),
+            &conditions
+        )
+        .instrument("insert_transaction_conditions")
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Loads conditions for the specified transactions. Transactions without conditions are not present
+    /// in the returned map.
+    pub async fn get_transaction_conditions(
+        &mut self,
+        tx_hashes: &[H256],
+    ) -> sqlx::Result<HashMap<H256, api::TransactionConditions>> {
+        let hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                conditions
+            FROM
+                transaction_conditions
+            WHERE
+                hash = ANY ($1)
+            "#,
+            &hashes as &[&[u8]]
+        )
+        .instrument("get_transaction_conditions")
+        .with_arg("tx_hashes.len", &tx_hashes.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let tx_hash = H256::from_slice(&row.hash);
+                let conditions = serde_json::from_value(row.conditions).map_err(|err| {
+                    let msg = format!("invalid conditions for transaction {tx_hash:?}: {err}");
+                    sqlx::Error::Decode(msg.into())
+                })?;
+                Ok((tx_hash, conditions))
+            })
+            .collect()
+    }
+
     pub async fn mark_txs_as_executed_in_l1_batch(
         &mut self,
         block_number: L1BatchNumber,
         transactions: &[TransactionExecutionResult],
-    ) {
+    ) -> sqlx::Result<()> {
         {
             let hashes: Vec<_> = transactions.iter().map(|tx| tx.hash.as_bytes()).collect();
             let l1_batch_tx_indexes: Vec<_> = (0..transactions.len() as i32).collect();
@@ -481,10 +709,11 @@ impl TransactionsDal<'_, '_> {
                 &hashes as &[&[u8]],
                 block_number.0 as i64
             )
-            .execute(self.storage.conn())
-            .await
-            .unwrap();
+            .instrument("mark_txs_as_executed_in_l1_batch")
+            .execute(self.storage)
+            .await?;
         }
+        Ok(())
     }
 
     pub async fn mark_txs_as_executed_in_miniblock(
@@ -492,9 +721,9 @@ impl TransactionsDal<'_, '_> {
         miniblock_number: MiniblockNumber,
         transactions: &[TransactionExecutionResult],
         block_base_fee_per_gas: U256,
-    ) {
+    ) -> sqlx::Result<()> {
         {
-            let mut transaction = self.storage.start_transaction().await.unwrap();
+            let mut transaction = self.storage.start_transaction().await?;
             let mut l1_hashes = Vec::with_capacity(transactions.len());
             let mut l1_indices_in_block = Vec::with_capacity(transactions.len());
             let mut l1_errors = Vec::with_capacity(transactions.len());
@@ -620,9 +849,47 @@ impl TransactionsDal<'_, '_> {
             if !l2_hashes.is_empty() {
                 // Update L2 txs
 
-                // Due to the current tx replacement model, it's possible that tx has been replaced,
-                // but the original was executed in memory,
+                // It's possible that tx has been replaced, but the original was executed in memory.
+                // In this case, the replacement is marked as replaced by the executed tx, and the row of the executed tx
+                // is restored. If there's no such row, the pending tx with the same initiator and nonce is overwritten,
                 // so we have to update all fields for tx from fields stored in memory.
+                sqlx::query!(
+                    r#"
+                    UPDATE transactions
+                    SET
+                        replaced_by = data_table.hash,
+                        in_mempool = FALSE,
+                        error = COALESCE(transactions.error, 'replaced by 0x' || ENCODE(data_table.hash, 'hex')),
+                        updated_at = NOW()
+                    FROM
+                        (
+                            SELECT
+                                UNNEST($1::bytea[]) AS initiator_address,
+                                UNNEST($2::INT[]) AS nonce,
+                                UNNEST($3::bytea[]) AS hash
+                        ) AS data_table
+                    WHERE
+                        transactions.initiator_address = data_table.initiator_address
+                        AND transactions.nonce = data_table.nonce
+                        AND transactions.hash != data_table.hash
+                        AND transactions.replaced_by IS NULL
+                        AND EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                transactions AS executed
+                            WHERE
+                                executed.hash = data_table.hash
+                        )
+                    "#,
+                    &l2_initiators,
+                    &l2_nonces,
+                    &l2_hashes
+                )
+                .instrument("mark_txs_as_executed_in_miniblock#mark_replacements")
+                .execute(&mut transaction)
+                .await?;
+
                 // Note, that transactions are updated in order of their hashes to avoid deadlocks with other UPDATE queries.
                 sqlx::query!(
                     r#"
@@ -648,6 +915,7 @@ impl TransactionsDal<'_, '_> {
                         paymaster = data_table.paymaster,
                         paymaster_input = data_table.paymaster_input,
                         in_mempool = FALSE,
+                        replaced_by = NULL,
                         updated_at = NOW()
                     FROM
                         (
@@ -679,12 +947,20 @@ impl TransactionsDal<'_, '_> {
                                 ) AS data_table_temp
                                 JOIN transactions ON transactions.initiator_address = data_table_temp.initiator_address
                                 AND transactions.nonce = data_table_temp.nonce
+                                AND (
+                                    transactions.hash = data_table_temp.hash
+                                    OR transactions.replaced_by IS NULL
+                                )
                             ORDER BY
                                 transactions.hash
                         ) AS data_table
                     WHERE
                         transactions.initiator_address = data_table.initiator_address
                         AND transactions.nonce = data_table.nonce
+                        AND (
+                            transactions.hash = data_table.hash
+                            OR transactions.replaced_by IS NULL
+                        )
                     "#,
                     &l2_initiators,
                     &l2_nonces,
@@ -708,9 +984,9 @@ impl TransactionsDal<'_, '_> {
                     &l2_paymaster_input,
                     miniblock_number.0 as i32,
                 )
-                .execute(transaction.conn())
-                .await
-                .unwrap();
+                .instrument("mark_txs_as_executed_in_miniblock#update_transactions_1")
+                .execute(&mut transaction)
+                .await?;
             }
 
             // We can't replace l1 transaction, so we simply write the execution result
@@ -748,9 +1024,9 @@ impl TransactionsDal<'_, '_> {
                     &l1_refunded_gas,
                     &l1_effective_gas_prices,
                 )
-                .execute(transaction.conn())
-                .await
-                .unwrap();
+                .instrument("mark_txs_as_executed_in_miniblock#update_transactions_2")
+                .execute(&mut transaction)
+                .await?;
             }
 
             if !upgrade_hashes.is_empty() {
@@ -787,9 +1063,9 @@ impl TransactionsDal<'_, '_> {
                     &upgrade_refunded_gas,
                     &upgrade_effective_gas_prices,
                 )
-                .execute(transaction.conn())
-                .await
-                .unwrap();
+                .instrument("mark_txs_as_executed_in_miniblock#update_transactions_3")
+                .execute(&mut transaction)
+                .await?;
             }
 
             if !bytea_call_traces.is_empty() {
@@ -807,12 +1083,10 @@ impl TransactionsDal<'_, '_> {
                     &bytea_call_traces
                 )
                 .instrument("insert_call_tracer")
-                .report_latency()
                 .execute(&mut transaction)
-                .await
-                .unwrap();
+                .await?;
             }
-            transaction.commit().await.unwrap();
+            transaction.commit().await
         }
     }
 
@@ -833,7 +1107,8 @@ impl TransactionsDal<'_, '_> {
                 error,
                 transaction_hash.0.to_vec()
             )
-            .execute(self.storage.conn())
+            .instrument("mark_tx_as_rejected")
+            .execute(self.storage)
             .await
             .unwrap();
         }
@@ -857,7 +1132,8 @@ impl TransactionsDal<'_, '_> {
                 "#,
                 miniblock_number.0 as i64
             )
-            .fetch_all(self.storage.conn())
+            .instrument("reset_transactions_state#update_transactions")
+            .fetch_all(self.storage)
             .await
             .unwrap();
             sqlx::query!(
@@ -871,7 +1147,8 @@ impl TransactionsDal<'_, '_> {
                     .map(|tx| tx.hash.clone())
                     .collect::<Vec<Vec<u8>>>()
             )
-            .execute(self.storage.conn())
+            .instrument("reset_transactions_state#delete_call_traces")
+            .execute(self.storage)
             .await
             .unwrap();
         }
@@ -892,12 +1169,35 @@ impl TransactionsDal<'_, '_> {
             "#,
             stuck_tx_timeout
         )
-        .fetch_all(self.storage.conn())
+        .instrument("remove_stuck_txs")
+        .fetch_all(self.storage)
         .await?;
 
         Ok(rows.len())
     }
 
+    /// Removes pending transactions with the specified hashes that were evicted from the mempool.
+    /// Returns the number of removed transactions.
+    pub async fn remove_evicted_txs(&mut self, tx_hashes: &[H256]) -> sqlx::Result<usize> {
+        let tx_hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE
+                in_mempool = TRUE
+                AND miniblock_number IS NULL
+                AND hash = ANY ($1)
+            "#,
+            &tx_hashes as &[&[u8]]
+        )
+        .instrument("remove_evicted_txs")
+        .with_arg("tx_hashes.len", &tx_hashes.len())
+        .execute(self.storage)
+        .await?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
     /// Fetches new updates for mempool. Returns new transactions and current nonces for related accounts;
     /// the latter are only used to bootstrap mempool for given account.
     pub async fn sync_mempool(
@@ -922,7 +1222,8 @@ impl TransactionsDal<'_, '_> {
             "#,
             &stashed_addresses as &[&[u8]],
         )
-        .execute(self.storage.conn())
+        .instrument("sync_mempool#update_transactions_1")
+        .execute(self.storage)
         .await?;
 
         let purged_addresses: Vec<_> = purged_accounts.iter().map(Address::as_bytes).collect();
@@ -935,7 +1236,8 @@ impl TransactionsDal<'_, '_> {
             "#,
             &purged_addresses as &[&[u8]]
         )
-        .execute(self.storage.conn())
+        .instrument("sync_mempool#delete_transactions")
+        .execute(self.storage)
         .await?;
 
         // Note, that transactions are updated in order of their hashes to avoid deadlocks with other UPDATE queries.
@@ -987,7 +1289,8 @@ impl TransactionsDal<'_, '_> {
             BigDecimal::from(gas_per_pubdata),
             PROTOCOL_UPGRADE_TX_TYPE as i32,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("sync_mempool#update_transactions_2")
+        .fetch_all(self.storage)
         .await?;
 
         let transactions = transactions.into_iter().map(|tx| tx.into()).collect();
@@ -1004,11 +1307,98 @@ impl TransactionsDal<'_, '_> {
                 in_mempool = TRUE
             "#
         )
-        .execute(self.storage.conn())
+        .instrument("reset_mempool")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
 
+    /// Returns L2 transactions received after the transaction with the specified receipt timestamp and hash
+    /// (exclusive), ordered by the receipt timestamp and hash. Unlike [`Self::sync_mempool()`], this method
+    /// doesn't modify any data, so it can be used to mirror transactions from the schema of another chain.
+    /// Rows that cannot be converted to L2 transactions are logged and skipped.
+    pub async fn get_l2_transactions_received_after(
+        &mut self,
+        received_timestamp_ms: u64,
+        tx_hash: H256,
+        limit: usize,
+    ) -> anyhow::Result<Vec<L2Tx>> {
+        let received_at = i64::try_from(received_timestamp_ms)
+            .ok()
+            .and_then(NaiveDateTime::from_timestamp_millis)
+            .with_context(|| format!("invalid receipt timestamp: {received_timestamp_ms}"))?;
+        let transactions = sqlx::query_as!(
+            StorageTransaction,
+            r#"
+            SELECT
+                *
+            FROM
+                transactions
+            WHERE
+                is_priority = FALSE
+                AND tx_format != $4
+                AND (received_at, hash) > ($1, $2)
+            ORDER BY
+                received_at,
+                hash
+            LIMIT
+                $3
+            "#,
+            received_at,
+            tx_hash.as_bytes(),
+            limit as i64,
+            PROTOCOL_UPGRADE_TX_TYPE as i32
+        )
+        .instrument("get_l2_transactions_received_after")
+        .with_arg("received_at", &received_at)
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(transactions
+            .into_iter()
+            .filter_map(Self::storage_transaction_to_l2_tx)
+            .collect())
+    }
+
+    fn storage_transaction_to_l2_tx(tx: StorageTransaction) -> Option<L2Tx> {
+        let tx_hash = H256::from_slice(&tx.hash);
+        match L2Tx::try_from(Transaction::from(tx)) {
+            Ok(tx) => Some(tx),
+            Err(err) => {
+                tracing::warn!("Skipped non-L2 transaction {tx_hash:?}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Returns the most recently received L2 transaction.
+    pub async fn get_last_received_l2_transaction(&mut self) -> sqlx::Result<Option<L2Tx>> {
+        let transaction = sqlx::query_as!(
+            StorageTransaction,
+            r#"
+            SELECT
+                *
+            FROM
+                transactions
+            WHERE
+                is_priority = FALSE
+                AND tx_format != $1
+            ORDER BY
+                received_at DESC,
+                hash DESC
+            LIMIT
+                1
+            "#,
+            PROTOCOL_UPGRADE_TX_TYPE as i32
+        )
+        .instrument("get_last_received_l2_transaction")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(transaction.and_then(Self::storage_transaction_to_l2_tx))
+    }
+
     pub async fn get_last_processed_l1_block(&mut self) -> Option<L1BlockNumber> {
         {
             sqlx::query!(
@@ -1025,7 +1415,8 @@ impl TransactionsDal<'_, '_> {
                     1
                 "#
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_last_processed_l1_block")
+            .fetch_optional(self.storage)
             .await
             .unwrap()
             .and_then(|x| x.l1_block_number.map(|block| L1BlockNumber(block as u32)))
@@ -1044,7 +1435,8 @@ impl TransactionsDal<'_, '_> {
                     is_priority = TRUE
                 "#
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("last_priority_id")
+            .fetch_optional(self.storage)
             .await
             .unwrap()?
             .op_id?;
@@ -1065,7 +1457,8 @@ impl TransactionsDal<'_, '_> {
                     AND miniblock_number IS NOT NULL
                 "#
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("next_priority_id")
+            .fetch_optional(self.storage)
             .await
             .unwrap()
             .and_then(|row| row.op_id)
@@ -1096,7 +1489,8 @@ impl TransactionsDal<'_, '_> {
                 index_in_block
             "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_miniblocks_to_reexecute")
+        .fetch_all(self.storage)
         .await?;
 
         self.map_transactions_to_execution_data(transactions).await
@@ -1124,7 +1518,8 @@ impl TransactionsDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_miniblocks_to_execute_for_l1_batch")
+        .fetch_all(self.storage)
         .await?;
 
         self.map_transactions_to_execution_data(transactions).await
@@ -1167,7 +1562,8 @@ impl TransactionsDal<'_, '_> {
             from_miniblock.0 as i64,
             to_miniblock.0 as i64,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("map_transactions_to_execution_data#miniblocks_1")
+        .fetch_all(self.storage)
         .await?;
 
         anyhow::ensure!(
@@ -1190,7 +1586,8 @@ impl TransactionsDal<'_, '_> {
             from_miniblock.0 as i64 - 1,
             to_miniblock.0 as i64 - 1,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("map_transactions_to_execution_data#miniblocks_2")
+        .fetch_all(self.storage)
         .await?;
 
         let prev_miniblock_hashes: HashMap<_, _> = prev_miniblock_hashes
@@ -1223,7 +1620,8 @@ impl TransactionsDal<'_, '_> {
                         "#,
                         prev_miniblock_number.0 as i32
                     )
-                    .fetch_optional(self.storage.conn())
+                    .instrument("map_transactions_to_execution_data#snapshot_recovery")
+                    .fetch_optional(self.storage)
                     .await?
                     .with_context(|| {
                         format!(
@@ -1265,7 +1663,8 @@ impl TransactionsDal<'_, '_> {
                 "#,
                 l1_batch_number.0 as i64
             )
-            .fetch_all(self.storage.conn())
+            .instrument("get_tx_locations")
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
@@ -1301,7 +1700,8 @@ impl TransactionsDal<'_, '_> {
             "#,
             tx_hash.as_bytes()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_call_trace")
+        .fetch_optional(self.storage)
         .await?
         .map(Into::into))
     }
@@ -1319,7 +1719,8 @@ impl TransactionsDal<'_, '_> {
             "#,
             hash.as_bytes()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_tx_by_hash")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|tx| tx.into())
@@ -1336,6 +1737,247 @@ mod tests {
         ConnectionPool,
     };
 
+    #[tokio::test]
+    async fn storing_transaction_conditions() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        let conditions = api::TransactionConditions {
+            block_number_max: Some(10.into()),
+            known_accounts: [(
+                Address::repeat_byte(1),
+                api::KnownAccountState::Slots([(H256::zero(), H256::repeat_byte(2))].into()),
+            )]
+            .into(),
+            ..api::TransactionConditions::default()
+        };
+        conn.transactions_dal()
+            .insert_transaction_conditions(tx_hash, &conditions)
+            .await
+            .unwrap();
+
+        let unknown_hash = H256::repeat_byte(0xff);
+        let loaded = conn
+            .transactions_dal()
+            .get_transaction_conditions(&[tx_hash, unknown_hash])
+            .await
+            .unwrap();
+        assert_eq!(loaded, HashMap::from([(tx_hash, conditions.clone())]));
+
+        // Conditions must stay with the replaced transaction rather than be carried over to the replacement.
+        let mut replacement = mock_l2_transaction();
+        replacement.common_data.initiator_address = tx.initiator_account();
+        replacement.common_data.nonce = tx.common_data.nonce;
+        let replacement_hash = replacement.hash();
+        let result = conn
+            .transactions_dal()
+            .insert_transaction_l2(replacement, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        assert_eq!(result, L2TxSubmissionResult::Replaced);
+        let loaded = conn
+            .transactions_dal()
+            .get_transaction_conditions(&[tx_hash, replacement_hash])
+            .await
+            .unwrap();
+        assert_eq!(loaded, HashMap::from([(tx_hash, conditions)]));
+    }
+
+    #[tokio::test]
+    async fn getting_pending_transaction_fee() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        let tx = mock_l2_transaction();
+        let initiator = tx.initiator_account();
+        let nonce = tx.common_data.nonce;
+        let tx_hash = tx.hash();
+        let max_fee_per_gas = tx.common_data.fee.max_fee_per_gas;
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+
+        let fee = conn
+            .transactions_dal()
+            .get_pending_transaction_fee(initiator, nonce)
+            .await
+            .unwrap();
+        assert_eq!(fee, Some((tx_hash, max_fee_per_gas)));
+        let fee = conn
+            .transactions_dal()
+            .get_pending_transaction_fee(initiator, nonce + 1)
+            .await
+            .unwrap();
+        assert_eq!(fee, None);
+
+        conn.transactions_dal()
+            .mark_tx_as_rejected(tx_hash, "rejected")
+            .await;
+        let fee = conn
+            .transactions_dal()
+            .get_pending_transaction_fee(initiator, nonce)
+            .await
+            .unwrap();
+        assert_eq!(fee, None);
+    }
+
+    #[tokio::test]
+    async fn replacing_transaction_with_fee_bump() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        let tx = mock_l2_transaction();
+        let pending_max_fee_per_gas = tx.common_data.fee.max_fee_per_gas;
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+
+        let replacement_tx = |max_fee_per_gas: U256| {
+            let mut replacement = mock_l2_transaction();
+            replacement.common_data.initiator_address = tx.initiator_account();
+            replacement.common_data.nonce = tx.common_data.nonce;
+            replacement.common_data.fee.max_fee_per_gas = max_fee_per_gas;
+            replacement
+        };
+        let min_max_fee_per_gas = pending_max_fee_per_gas * 110 / 100;
+        let underpriced_tx = replacement_tx(min_max_fee_per_gas - 1);
+        let result = conn
+            .transactions_dal()
+            .insert_transaction_l2_with_fee_bump(
+                underpriced_tx.clone(),
+                TransactionExecutionMetrics::default(),
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            L2TxSubmissionResult::ReplacementUnderpriced(min_max_fee_per_gas)
+        );
+        let fee = conn
+            .transactions_dal()
+            .get_pending_transaction_fee(tx.initiator_account(), tx.common_data.nonce)
+            .await
+            .unwrap();
+        assert_eq!(fee, Some((tx.hash(), pending_max_fee_per_gas)));
+
+        let replacement = replacement_tx(min_max_fee_per_gas);
+        let result = conn
+            .transactions_dal()
+            .insert_transaction_l2_with_fee_bump(
+                replacement.clone(),
+                TransactionExecutionMetrics::default(),
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result, L2TxSubmissionResult::Replaced);
+        let fee = conn
+            .transactions_dal()
+            .get_pending_transaction_fee(tx.initiator_account(), tx.common_data.nonce)
+            .await
+            .unwrap();
+        assert_eq!(fee, Some((replacement.hash(), min_max_fee_per_gas)));
+        // The replaced transaction must be kept.
+        let replaced_by = conn
+            .transactions_dal()
+            .get_replacement_hash(tx.hash())
+            .await
+            .unwrap();
+        assert_eq!(replaced_by, Some(replacement.hash()));
+        let replaced_by = conn
+            .transactions_dal()
+            .get_replacement_hash(replacement.hash())
+            .await
+            .unwrap();
+        assert_eq!(replaced_by, None);
+
+        // Rejected transactions can be replaced regardless of the fee.
+        conn.transactions_dal()
+            .mark_tx_as_rejected(replacement.hash(), "rejected")
+            .await;
+        let result = conn
+            .transactions_dal()
+            .insert_transaction_l2_with_fee_bump(
+                underpriced_tx,
+                TransactionExecutionMetrics::default(),
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result, L2TxSubmissionResult::Replaced);
+    }
+
+    #[tokio::test]
+    async fn executing_replaced_transaction() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+
+        let tx = mock_l2_transaction();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        let mut replacement = mock_l2_transaction();
+        replacement.common_data.initiator_address = tx.initiator_account();
+        replacement.common_data.nonce = tx.common_data.nonce;
+        let result = conn
+            .transactions_dal()
+            .insert_transaction_l2(replacement.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        assert_eq!(result, L2TxSubmissionResult::Replaced);
+
+        // The replaced transaction was executed by the state keeper before the replacement.
+        let tx_result = mock_execution_result(tx.clone());
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], 1.into())
+            .await
+            .unwrap();
+
+        let replaced_by = conn
+            .transactions_dal()
+            .get_replacement_hash(tx.hash())
+            .await
+            .unwrap();
+        assert_eq!(replaced_by, None);
+        let replaced_by = conn
+            .transactions_dal()
+            .get_replacement_hash(replacement.hash())
+            .await
+            .unwrap();
+        assert_eq!(replaced_by, Some(tx.hash()));
+        let fee = conn
+            .transactions_dal()
+            .get_pending_transaction_fee(tx.initiator_account(), tx.common_data.nonce)
+            .await
+            .unwrap();
+        assert_eq!(fee, None);
+    }
+
+    #[test]
+    fn min_replacement_fee_does_not_overflow() {
+        assert_eq!(min_replacement_fee(100.into(), 10), 110.into());
+        assert_eq!(min_replacement_fee(101.into(), 10), 112.into());
+        assert_eq!(
+            min_replacement_fee(100.into(), u32::MAX),
+            U256::from(u32::MAX) + 100
+        );
+        assert_eq!(min_replacement_fee(U256::MAX, 10), U256::MAX / 100);
+    }
+
     #[tokio::test]
     async fn getting_call_trace_for_transaction() {
         let connection_pool = ConnectionPool::test_pool().await;
@@ -1352,7 +1994,8 @@ mod tests {
         let tx_hash = tx.hash();
         conn.transactions_dal()
             .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
-            .await;
+            .await
+            .unwrap();
         let mut tx_result = mock_execution_result(tx);
         tx_result.call_traces.push(Call {
             from: Address::from_low_u64_be(1),
@@ -1363,7 +2006,8 @@ mod tests {
         let expected_call_trace = tx_result.call_trace().unwrap();
         conn.transactions_dal()
             .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], 1.into())
-            .await;
+            .await
+            .unwrap();
 
         let call_trace = conn
             .transactions_dal()
@@ -17,6 +17,18 @@ use crate::{
 enum TransactionSelector<'a> {
     Hashes(&'a [H256]),
     Position(MiniblockNumber, u32),
+    Initiator {
+        address: &'a Address,
+        after: Option<api::TransactionCursor>,
+        limit: u32,
+    },
+    Pending {
+        limit: u32,
+    },
+    PendingFromInitiator {
+        address: &'a Address,
+        limit: u32,
+    },
 }
 
 #[derive(Debug)]
@@ -77,12 +89,90 @@ impl TransactionsWeb3Dal<'_, '_> {
             FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes(),
             &hash_bytes as &[&[u8]]
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_transaction_receipts")
+        .fetch_all(self.storage)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+        self.fill_receipt_logs(hashes, &mut receipts).await?;
+        Ok(receipts)
+    }
+
+    /// Returns receipts for all transactions in the specified miniblock, ordered by their index in the miniblock.
+    /// Returns an empty vector if the miniblock doesn't exist or has no transactions.
+    pub async fn get_block_receipts(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<Vec<TransactionReceipt>> {
+        let mut receipts: Vec<TransactionReceipt> = sqlx::query_as!(
+            StorageTransactionReceipt,
+            r#"
+            WITH
+                sl AS (
+                    SELECT DISTINCT
+                        ON (storage_logs.tx_hash) *
+                    FROM
+                        storage_logs
+                    WHERE
+                        storage_logs.address = $1
+                        AND storage_logs.miniblock_number = $3
+                    ORDER BY
+                        storage_logs.tx_hash,
+                        storage_logs.operation_number DESC
+                )
+            SELECT
+                transactions.hash AS tx_hash,
+                transactions.index_in_block AS index_in_block,
+                transactions.l1_batch_tx_index AS l1_batch_tx_index,
+                transactions.miniblock_number AS "block_number!",
+                transactions.error AS error,
+                transactions.effective_gas_price AS effective_gas_price,
+                transactions.initiator_address AS initiator_address,
+                transactions.data -> 'to' AS "transfer_to?",
+                transactions.data -> 'contractAddress' AS "execute_contract_address?",
+                transactions.tx_format AS "tx_format?",
+                transactions.refunded_gas AS refunded_gas,
+                transactions.gas_limit AS gas_limit,
+                miniblocks.hash AS "block_hash",
+                miniblocks.l1_batch_number AS "l1_batch_number?",
+                sl.key AS "contract_address?"
+            FROM
+                transactions
+                JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+                LEFT JOIN sl ON sl.value != $2
+                AND sl.tx_hash = transactions.hash
+            WHERE
+                transactions.miniblock_number = $3
+            ORDER BY
+                transactions.index_in_block
+            "#,
+            ACCOUNT_CODE_STORAGE_ADDRESS.as_bytes(),
+            FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes(),
+            i64::from(miniblock_number.0)
+        )
+        .instrument("get_block_receipts")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(Into::into)
         .collect();
 
+        let hashes: Vec<_> = receipts
+            .iter()
+            .map(|receipt| receipt.transaction_hash)
+            .collect();
+        self.fill_receipt_logs(&hashes, &mut receipts).await?;
+        Ok(receipts)
+    }
+
+    async fn fill_receipt_logs(
+        &mut self,
+        hashes: &[H256],
+        receipts: &mut [TransactionReceipt],
+    ) -> sqlx::Result<()> {
         let mut logs = self
             .storage
             .events_dal()
@@ -95,7 +185,7 @@ impl TransactionsWeb3Dal<'_, '_> {
             .get_l2_to_l1_logs_by_hashes(hashes)
             .await?;
 
-        for receipt in &mut receipts {
+        for receipt in receipts {
             let logs_for_tx = logs.remove(&receipt.transaction_hash);
 
             if let Some(logs) = logs_for_tx {
@@ -122,7 +212,7 @@ impl TransactionsWeb3Dal<'_, '_> {
             }
         }
 
-        Ok(receipts)
+        Ok(())
     }
 
     /// Obtains transactions with the specified hashes. Transactions are returned in no particular order; if some hashes
@@ -186,10 +276,42 @@ impl TransactionsWeb3Dal<'_, '_> {
                     block_number.0 as i64,
                     idx as i32
                 ),
+                TransactionSelector::Initiator { address, after, limit } => (
+                    "transactions.initiator_address = $1 \
+                     AND transactions.miniblock_number IS NOT NULL \
+                     AND (transactions.miniblock_number, transactions.index_in_block) > ($2, $3) \
+                     ORDER BY transactions.miniblock_number, transactions.index_in_block \
+                     LIMIT $4";
+                    address.as_bytes(),
+                    after.map_or(-1, |cursor| i64::from(cursor.block_number.0)),
+                    after.map_or(-1, |cursor| cursor.index_in_block as i32),
+                    i64::from(limit)
+                ),
+                TransactionSelector::Pending { limit } => (
+                    "transactions.miniblock_number IS NULL \
+                     AND transactions.is_priority = FALSE \
+                     AND transactions.error IS NULL \
+                     ORDER BY transactions.initiator_address, transactions.nonce \
+                     LIMIT $1";
+                    i64::from(limit)
+                ),
+                TransactionSelector::PendingFromInitiator { address, limit } => (
+                    "transactions.initiator_address = $1 \
+                     AND transactions.miniblock_number IS NULL \
+                     AND transactions.is_priority = FALSE \
+                     AND transactions.error IS NULL \
+                     ORDER BY transactions.nonce \
+                     LIMIT $2";
+                    address.as_bytes(),
+                    i64::from(limit)
+                ),
             }
         );
 
-        let rows = query.fetch_all(self.storage.conn()).await?;
+        let rows = query
+            .instrument("get_transactions_inner")
+            .fetch_all(self.storage)
+            .await?;
         Ok(rows.into_iter().map(|row| row.into_api(chain_id)).collect())
     }
 
@@ -221,6 +343,110 @@ impl TransactionsWeb3Dal<'_, '_> {
             .next())
     }
 
+    /// Returns transactions sent by the specified `initiator` that are included into miniblocks, ordered
+    /// by their position in the chain. If `after` is specified, only transactions following the cursor
+    /// are returned. At most `limit` transactions are returned.
+    pub async fn get_transactions_by_initiator(
+        &mut self,
+        initiator: Address,
+        after: Option<api::TransactionCursor>,
+        limit: u32,
+        chain_id: L2ChainId,
+    ) -> sqlx::Result<Vec<api::Transaction>> {
+        if let Some(cursor) = &after {
+            // Since index is not trusted, we check it to prevent potential overflow below.
+            if cursor.index_in_block > i32::MAX as u32 {
+                return Ok(vec![]);
+            }
+        }
+
+        let selector = TransactionSelector::Initiator {
+            address: &initiator,
+            after,
+            limit,
+        };
+        self.get_transactions_inner(selector, chain_id).await
+    }
+
+    /// Returns L2 transactions that are not rejected and not yet included into miniblocks, ordered by their initiator
+    /// and nonce. At most `limit` transactions are returned.
+    pub async fn get_pending_transactions(
+        &mut self,
+        limit: u32,
+        chain_id: L2ChainId,
+    ) -> sqlx::Result<Vec<api::Transaction>> {
+        self.get_transactions_inner(TransactionSelector::Pending { limit }, chain_id)
+            .await
+    }
+
+    /// Same as [`Self::get_pending_transactions()`], but only returns transactions sent by the specified `initiator`.
+    /// Transactions are ordered by nonce.
+    pub async fn get_pending_transactions_by_initiator(
+        &mut self,
+        initiator: Address,
+        limit: u32,
+        chain_id: L2ChainId,
+    ) -> sqlx::Result<Vec<api::Transaction>> {
+        let selector = TransactionSelector::PendingFromInitiator {
+            address: &initiator,
+            limit,
+        };
+        self.get_transactions_inner(selector, chain_id).await
+    }
+
+    /// Returns the original signed bytes of an L2 transaction with the specified hash, as submitted to the API server.
+    /// Returns `None` if the transaction is not found or doesn't have signed bytes (e.g., it's an L1 transaction).
+    pub async fn get_raw_transaction_by_hash(
+        &mut self,
+        hash: H256,
+    ) -> sqlx::Result<Option<Vec<u8>>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                input
+            FROM
+                transactions
+            WHERE
+                hash = $1
+            "#,
+            hash.as_bytes()
+        )
+        .instrument("get_raw_transaction_by_hash")
+        .with_arg("hash", &hash)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.and_then(|row| row.input))
+    }
+
+    /// Same as [`Self::get_raw_transaction_by_hash()`], but selects the transaction by its position in a miniblock.
+    pub async fn get_raw_transaction_by_position(
+        &mut self,
+        block_number: MiniblockNumber,
+        index_in_block: u32,
+    ) -> sqlx::Result<Option<Vec<u8>>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                input
+            FROM
+                transactions
+            WHERE
+                miniblock_number = $1
+                AND index_in_block = $2
+            "#,
+            i64::from(block_number.0),
+            index_in_block as i32
+        )
+        .instrument("get_raw_transaction_by_position")
+        .with_arg("block_number", &block_number)
+        .with_arg("index_in_block", &index_in_block)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.and_then(|row| row.input))
+    }
+
     pub async fn get_transaction_details(
         &mut self,
         hash: H256,
@@ -298,7 +524,8 @@ impl TransactionsWeb3Dal<'_, '_> {
             from_timestamp,
             limit.map(|limit| limit as i64)
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_pending_txs_hashes_after")
+        .fetch_all(self.storage)
         .await?;
 
         let last_loc = records.last().map(|record| record.received_at);
@@ -318,7 +545,7 @@ impl TransactionsWeb3Dal<'_, '_> {
         // Get nonces of non-rejected transactions, starting from the 'latest' nonce.
         // `latest` nonce is used, because it is guaranteed that there are no gaps before it.
         // `(miniblock_number IS NOT NULL OR error IS NULL)` is the condition that filters non-rejected transactions.
-        // Query is fast because we have an index on (`initiator_address`, `nonce`)
+        // Query is fast because we have an index on (`initiator_address`, `nonce`) for transactions that are not replaced
         // and it cannot return more than `max_nonce_ahead` nonces.
         let non_rejected_nonces: Vec<u64> = sqlx::query!(
             r#"
@@ -329,6 +556,7 @@ impl TransactionsWeb3Dal<'_, '_> {
             WHERE
                 initiator_address = $1
                 AND nonce >= $2
+                AND replaced_by IS NULL
                 AND is_priority = FALSE
                 AND (
                     miniblock_number IS NOT NULL
@@ -340,7 +568,8 @@ impl TransactionsWeb3Dal<'_, '_> {
             initiator_address.as_bytes(),
             committed_next_nonce as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("next_nonce_by_initiator_account")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| row.nonce as u64)
@@ -379,7 +608,8 @@ impl TransactionsWeb3Dal<'_, '_> {
             "#,
             miniblock.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_raw_miniblock_transactions")
+        .fetch_all(self.storage)
         .await?;
 
         Ok(rows.into_iter().map(Into::into).collect())
@@ -407,7 +637,8 @@ mod tests {
         for tx in &txs {
             conn.transactions_dal()
                 .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
-                .await;
+                .await
+                .unwrap();
         }
         conn.blocks_dal()
             .insert_miniblock(&create_miniblock_header(0))
@@ -427,7 +658,8 @@ mod tests {
 
         conn.transactions_dal()
             .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &tx_results, U256::from(1))
-            .await;
+            .await
+            .unwrap();
     }
 
     #[tokio::test]
@@ -480,6 +712,164 @@ mod tests {
         assert!(web3_tx.unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn getting_raw_transaction() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        let raw_tx = tx.common_data.input_data().unwrap().to_vec();
+        prepare_transactions(&mut conn, vec![tx]).await;
+
+        let raw_tx_by_hash = conn
+            .transactions_web3_dal()
+            .get_raw_transaction_by_hash(tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(raw_tx_by_hash, Some(raw_tx.clone()));
+        let raw_tx_by_position = conn
+            .transactions_web3_dal()
+            .get_raw_transaction_by_position(MiniblockNumber(1), 0)
+            .await
+            .unwrap();
+        assert_eq!(raw_tx_by_position, Some(raw_tx));
+
+        let missing_tx = conn
+            .transactions_web3_dal()
+            .get_raw_transaction_by_hash(H256::zero())
+            .await
+            .unwrap();
+        assert_eq!(missing_tx, None);
+        let missing_tx = conn
+            .transactions_web3_dal()
+            .get_raw_transaction_by_position(MiniblockNumber(1), 1)
+            .await
+            .unwrap();
+        assert_eq!(missing_tx, None);
+    }
+
+    #[tokio::test]
+    async fn getting_transactions_by_initiator() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let initiator = Address::repeat_byte(1);
+        let txs: Vec<_> = (0..5)
+            .map(|i| {
+                let mut tx = mock_l2_transaction();
+                // Changing transaction fields invalidates its signature, but it's OK for test purposes
+                if i % 2 == 0 {
+                    tx.common_data.nonce = Nonce(i);
+                    tx.common_data.initiator_address = initiator;
+                }
+                tx
+            })
+            .collect();
+        let expected_hashes: Vec<_> = txs.iter().step_by(2).map(L2Tx::hash).collect();
+        prepare_transactions(&mut conn, txs).await;
+
+        let chain_id = L2ChainId::from(270);
+        let first_page = conn
+            .transactions_web3_dal()
+            .get_transactions_by_initiator(initiator, None, 2, chain_id)
+            .await
+            .unwrap();
+        let first_page_hashes: Vec<_> = first_page.iter().map(|tx| tx.hash).collect();
+        assert_eq!(first_page_hashes, expected_hashes[..2]);
+
+        let last_tx = first_page.last().unwrap();
+        let cursor = api::TransactionCursor {
+            block_number: MiniblockNumber(last_tx.block_number.unwrap().as_u32()),
+            index_in_block: last_tx.transaction_index.unwrap().as_u32(),
+        };
+        let second_page = conn
+            .transactions_web3_dal()
+            .get_transactions_by_initiator(initiator, Some(cursor), 2, chain_id)
+            .await
+            .unwrap();
+        let second_page_hashes: Vec<_> = second_page.iter().map(|tx| tx.hash).collect();
+        assert_eq!(second_page_hashes, expected_hashes[2..]);
+
+        let other_txs = conn
+            .transactions_web3_dal()
+            .get_transactions_by_initiator(Address::repeat_byte(2), None, 10, chain_id)
+            .await
+            .unwrap();
+        assert!(other_txs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn getting_pending_transactions() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let included_tx = mock_l2_transaction();
+        prepare_transactions(&mut conn, vec![included_tx]).await;
+
+        let initiator = Address::repeat_byte(1);
+        let pending_txs: Vec<_> = [2, 0, 5]
+            .into_iter()
+            .map(|nonce| {
+                let mut tx = mock_l2_transaction();
+                // Changing transaction fields invalidates its signature, but it's OK for test purposes
+                tx.common_data.nonce = Nonce(nonce);
+                tx.common_data.initiator_address = initiator;
+                tx
+            })
+            .collect();
+        for tx in &pending_txs {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+        }
+
+        let chain_id = L2ChainId::from(270);
+        let txs = conn
+            .transactions_web3_dal()
+            .get_pending_transactions(10, chain_id)
+            .await
+            .unwrap();
+        let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash).collect();
+        let expected_hashes = [
+            pending_txs[1].hash(),
+            pending_txs[0].hash(),
+            pending_txs[2].hash(),
+        ];
+        assert_eq!(tx_hashes, expected_hashes);
+        assert!(txs.iter().all(|tx| tx.block_number.is_none()));
+
+        let txs = conn
+            .transactions_web3_dal()
+            .get_pending_transactions(1, chain_id)
+            .await
+            .unwrap();
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].hash, expected_hashes[0]);
+
+        let txs = conn
+            .transactions_web3_dal()
+            .get_pending_transactions_by_initiator(initiator, 10, chain_id)
+            .await
+            .unwrap();
+        let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash).collect();
+        assert_eq!(tx_hashes, expected_hashes);
+        let txs = conn
+            .transactions_web3_dal()
+            .get_pending_transactions_by_initiator(Address::repeat_byte(0xff), 10, chain_id)
+            .await
+            .unwrap();
+        assert!(txs.is_empty());
+    }
+
     #[tokio::test]
     async fn getting_receipts() {
         let connection_pool = ConnectionPool::test_pool().await;
@@ -506,6 +896,20 @@ mod tests {
         assert_eq!(receipts.len(), 2);
         assert_eq!(receipts[0].transaction_hash, tx1_hash);
         assert_eq!(receipts[1].transaction_hash, tx2_hash);
+
+        let block_receipts = conn
+            .transactions_web3_dal()
+            .get_block_receipts(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(block_receipts, receipts);
+
+        let block_receipts = conn
+            .transactions_web3_dal()
+            .get_block_receipts(MiniblockNumber(0))
+            .await
+            .unwrap();
+        assert!(block_receipts.is_empty());
     }
 
     #[tokio::test]
@@ -560,7 +964,8 @@ mod tests {
             tx_by_nonce.insert(nonce, tx.clone());
             conn.transactions_dal()
                 .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
-                .await;
+                .await
+                .unwrap();
         }
 
         let next_nonce = conn
@@ -594,7 +999,8 @@ mod tests {
         ];
         conn.transactions_dal()
             .mark_txs_as_executed_in_miniblock(miniblock.number, &executed_txs, 1.into())
-            .await;
+            .await
+            .unwrap();
 
         let next_nonce = conn
             .transactions_web3_dal()
@@ -623,7 +1029,8 @@ mod tests {
         tx.common_data.initiator_address = initiator;
         conn.transactions_dal()
             .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
-            .await;
+            .await
+            .unwrap();
 
         let next_nonce = conn
             .transactions_web3_dal()
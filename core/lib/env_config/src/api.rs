@@ -67,6 +67,7 @@ mod tests {
                 filters_limit: Some(10000),
                 subscriptions_limit: Some(10000),
                 pubsub_polling_interval: Some(200),
+                pubsub_db_notifications: true,
                 max_nonce_ahead: 5,
                 request_timeout: Some(10),
                 account_pks: Some(vec![
@@ -83,11 +84,51 @@ mod tests {
                 factory_deps_cache_size_mb: Some(128),
                 initial_writes_cache_size_mb: Some(32),
                 latest_values_cache_size_mb: Some(256),
+                eth_call_cache_size: Some(512),
                 fee_history_limit: Some(100),
                 max_batch_request_size: Some(200),
                 max_response_body_size_mb: Some(10),
+                max_batch_response_size_mb: Some(20),
+                batch_request_time_budget_ms: Some(30_000),
                 websocket_requests_per_minute_limit: Some(NonZeroU32::new(10).unwrap()),
+                websocket_compute_units_per_second_limit: Some(NonZeroU32::new(1_000).unwrap()),
+                subscriptions_per_connection_limit: Some(100),
+                websocket_message_buffer_capacity: Some(512),
+                api_keys: Some(vec!["key1".to_owned(), "key2".to_owned()]),
+                api_key_requests_per_second_limit: Some(NonZeroU32::new(20).unwrap()),
+                api_key_compute_units_per_second_limit: Some(NonZeroU32::new(500).unwrap()),
                 tree_api_url: None,
+                max_logs_result_size: Some(50_000),
+                trace_timeout_ms: Some(5_000),
+                trace_max_call_depth: Some(64),
+                trace_block_concurrency: Some(4),
+                trace_block_max_result_size: Some(10_000),
+                txpool_namespace_enabled: true,
+                txpool_redact_transactions: true,
+                archive_state_path: Some("./db/archive_state".to_owned()),
+                persistent_filters: true,
+                filters_ttl_sec: Some(600),
+                auth_bearer_tokens: vec!["token1".to_owned(), "token2".to_owned()],
+                auth_jwt_secret_path: Some("./jwt.hex".to_owned()),
+                auth_protected_namespaces: Some(vec!["debug".to_owned(), "txpool".to_owned()]),
+                api_namespaces: Some(vec![
+                    "eth".to_owned(),
+                    "net".to_owned(),
+                    "web3".to_owned(),
+                    "debug".to_owned(),
+                ]),
+                http_response_compression_threshold: Some(1_024),
+                gas_price_suggestion_blocks: Some(10),
+                gas_price_suggestion_percentile: Some(50.0),
+                gas_price_suggestion_smoothing: Some(0.25),
+                shutdown_drain_timeout_ms: Some(10_000),
+                cors_allowed_origins: Some(vec![
+                    "https://app.example.com".to_owned(),
+                    "http://localhost:8080".to_owned(),
+                ]),
+                cors_allowed_headers: vec!["x-client-version".to_owned()],
+                cors_max_age_sec: Some(600),
+                tx_replacement_fee_bump_percent: Some(20),
             },
             contract_verification: ContractVerificationApiConfig {
                 port: 3070,
@@ -120,6 +161,7 @@ mod tests {
             API_WEB3_JSON_RPC_FILTERS_LIMIT=10000
             API_WEB3_JSON_RPC_SUBSCRIPTIONS_LIMIT=10000
             API_WEB3_JSON_RPC_PUBSUB_POLLING_INTERVAL=200
+            API_WEB3_JSON_RPC_PUBSUB_DB_NOTIFICATIONS=true
             API_WEB3_JSON_RPC_MAX_NONCE_AHEAD=5
             API_WEB3_JSON_RPC_GAS_PRICE_SCALE_FACTOR=1.2
             API_WEB3_JSON_RPC_REQUEST_TIMEOUT=10
@@ -132,12 +174,44 @@ mod tests {
             API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_SIZE_MB=128
             API_WEB3_JSON_RPC_INITIAL_WRITES_CACHE_SIZE_MB=32
             API_WEB3_JSON_RPC_LATEST_VALUES_CACHE_SIZE_MB=256
+            API_WEB3_JSON_RPC_ETH_CALL_CACHE_SIZE=512
             API_WEB3_JSON_RPC_FEE_HISTORY_LIMIT=100
             API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
+            API_WEB3_JSON_RPC_WEBSOCKET_COMPUTE_UNITS_PER_SECOND_LIMIT=1000
+            API_WEB3_JSON_RPC_SUBSCRIPTIONS_PER_CONNECTION_LIMIT=100
+            API_WEB3_JSON_RPC_WEBSOCKET_MESSAGE_BUFFER_CAPACITY=512
+            API_WEB3_JSON_RPC_API_KEYS="key1,key2"
+            API_WEB3_JSON_RPC_API_KEY_REQUESTS_PER_SECOND_LIMIT=20
+            API_WEB3_JSON_RPC_API_KEY_COMPUTE_UNITS_PER_SECOND_LIMIT=500
+            API_WEB3_JSON_RPC_MAX_LOGS_RESULT_SIZE=50000
+            API_WEB3_JSON_RPC_TRACE_TIMEOUT_MS=5000
+            API_WEB3_JSON_RPC_TRACE_MAX_CALL_DEPTH=64
+            API_WEB3_JSON_RPC_TRACE_BLOCK_CONCURRENCY=4
+            API_WEB3_JSON_RPC_TRACE_BLOCK_MAX_RESULT_SIZE=10000
+            API_WEB3_JSON_RPC_TXPOOL_NAMESPACE_ENABLED=true
+            API_WEB3_JSON_RPC_TXPOOL_REDACT_TRANSACTIONS=true
+            API_WEB3_JSON_RPC_ARCHIVE_STATE_PATH="./db/archive_state"
+            API_WEB3_JSON_RPC_PERSISTENT_FILTERS=true
+            API_WEB3_JSON_RPC_FILTERS_TTL_SEC=600
+            API_WEB3_JSON_RPC_AUTH_BEARER_TOKENS="token1,token2"
+            API_WEB3_JSON_RPC_AUTH_JWT_SECRET_PATH="./jwt.hex"
+            API_WEB3_JSON_RPC_AUTH_PROTECTED_NAMESPACES="debug,txpool"
+            API_WEB3_JSON_RPC_API_NAMESPACES="eth,net,web3,debug"
+            API_WEB3_JSON_RPC_HTTP_RESPONSE_COMPRESSION_THRESHOLD=1024
+            API_WEB3_JSON_RPC_GAS_PRICE_SUGGESTION_BLOCKS=10
+            API_WEB3_JSON_RPC_GAS_PRICE_SUGGESTION_PERCENTILE=50
+            API_WEB3_JSON_RPC_GAS_PRICE_SUGGESTION_SMOOTHING=0.25
+            API_WEB3_JSON_RPC_SHUTDOWN_DRAIN_TIMEOUT_MS=10000
+            API_WEB3_JSON_RPC_CORS_ALLOWED_ORIGINS="https://app.example.com,http://localhost:8080"
+            API_WEB3_JSON_RPC_CORS_ALLOWED_HEADERS="x-client-version"
+            API_WEB3_JSON_RPC_CORS_MAX_AGE_SEC=600
+            API_WEB3_JSON_RPC_TX_REPLACEMENT_FEE_BUMP_PERCENT=20
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_WEB3_JSON_RPC_MAX_RESPONSE_BODY_SIZE_MB=10
+            API_WEB3_JSON_RPC_MAX_BATCH_RESPONSE_SIZE_MB=20
+            API_WEB3_JSON_RPC_BATCH_REQUEST_TIME_BUDGET_MS=30000
             API_PROMETHEUS_LISTENER_PORT="3312"
             API_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
             API_PROMETHEUS_PUSH_INTERVAL_MS=100
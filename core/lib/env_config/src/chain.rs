@@ -37,7 +37,7 @@ impl FromEnv for MempoolConfig {
 #[cfg(test)]
 mod tests {
     use zksync_basic_types::L2ChainId;
-    use zksync_config::configs::chain::FeeModelVersion;
+    use zksync_config::configs::chain::{FeeModelVersion, MempoolTxOrdering};
 
     use super::*;
     use crate::test_utils::{addr, EnvMutex};
@@ -94,6 +94,16 @@ mod tests {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: Some(2_000),
+            seal_l1_batch_on_shutdown: true,
+            denied_addresses: vec![addr("0000000000000000000000000000000000000bad")],
+            denied_bytecode_hashes: vec![],
+            shadow_source_schema: Some("public".to_owned()),
+            proof_backlog_threshold: Some(10),
+            max_tx_gas_per_pubdata: Some(50_000),
+            max_tx_calldata_size: Some(100_000),
+            max_tx_factory_deps: None,
+            max_tx_factory_deps_size: Some(500_000),
+            upload_l1_batch_state_diffs: true,
         }
     }
 
@@ -125,6 +135,14 @@ mod tests {
             CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
             CHAIN_STATE_KEEPER_UPLOAD_WITNESS_INPUTS_TO_GCS="false"
             CHAIN_STATE_KEEPER_ENUM_INDEX_MIGRATION_CHUNK_SIZE="2000"
+            CHAIN_STATE_KEEPER_SEAL_L1_BATCH_ON_SHUTDOWN="true"
+            CHAIN_STATE_KEEPER_DENIED_ADDRESSES="0x0000000000000000000000000000000000000bad"
+            CHAIN_STATE_KEEPER_SHADOW_SOURCE_SCHEMA="public"
+            CHAIN_STATE_KEEPER_PROOF_BACKLOG_THRESHOLD="10"
+            CHAIN_STATE_KEEPER_MAX_TX_GAS_PER_PUBDATA="50000"
+            CHAIN_STATE_KEEPER_MAX_TX_CALLDATA_SIZE="100000"
+            CHAIN_STATE_KEEPER_MAX_TX_FACTORY_DEPS_SIZE="500000"
+            CHAIN_STATE_KEEPER_UPLOAD_L1_BATCH_STATE_DIFFS="true"
             CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_PER_MINIBLOCK="1"
             CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_INTERVAL="1"
         "#;
@@ -160,6 +178,9 @@ mod tests {
             stuck_tx_timeout: 10,
             remove_stuck_txs: true,
             delay_interval: 100,
+            max_queued_txs_per_account: Some(64),
+            max_size_bytes: Some(100_000_000),
+            tx_ordering: MempoolTxOrdering::Fifo,
         }
     }
 
@@ -173,6 +194,9 @@ mod tests {
             CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
             CHAIN_MEMPOOL_DELAY_INTERVAL="100"
             CHAIN_MEMPOOL_CAPACITY="1000000"
+            CHAIN_MEMPOOL_MAX_QUEUED_TXS_PER_ACCOUNT="64"
+            CHAIN_MEMPOOL_MAX_SIZE_BYTES="100000000"
+            CHAIN_MEMPOOL_TX_ORDERING="Fifo"
         "#;
         lock.set_env(config);
 
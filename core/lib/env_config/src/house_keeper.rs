@@ -30,6 +30,12 @@ mod tests {
             fri_prover_stats_reporting_interval_ms: 30_000,
             fri_proof_compressor_job_retrying_interval_ms: 30_000,
             fri_proof_compressor_stats_reporting_interval_ms: 30_000,
+            db_pruning_retention_days: Some(30),
+            db_pruning_interval_ms: None,
+            db_partition_size_miniblocks: Some(1_000_000),
+            db_events_archival_retention_days: None,
+            db_partitioning_interval_ms: None,
+            db_consistency_check_interval_ms: Some(60_000),
         }
     }
 
@@ -50,6 +56,9 @@ mod tests {
             HOUSE_KEEPER_FRI_PROVER_STATS_REPORTING_INTERVAL_MS="30000"
             HOUSE_KEEPER_FRI_PROOF_COMPRESSOR_STATS_REPORTING_INTERVAL_MS="30000"
             HOUSE_KEEPER_FRI_PROOF_COMPRESSOR_JOB_RETRYING_INTERVAL_MS="30000"
+            HOUSE_KEEPER_DB_PRUNING_RETENTION_DAYS="30"
+            HOUSE_KEEPER_DB_PARTITION_SIZE_MINIBLOCKS="1000000"
+            HOUSE_KEEPER_DB_CONSISTENCY_CHECK_INTERVAL_MS="60000"
         "#;
         lock.set_env(config);
 
@@ -1,24 +1,61 @@
-use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
+use std::{
+    collections::{hash_map, BTreeSet, HashMap, HashSet},
+    ops::Bound,
+};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
+    U256,
 };
 
-use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
+use crate::types::{tx_size_in_bytes, AccountTransactions, L2TxFilter, L2TxOrdering, MempoolScore};
 
 #[derive(Debug)]
 pub struct MempoolInfo {
     pub stashed_accounts: Vec<Address>,
     pub purged_accounts: Vec<Address>,
+    /// Hashes of L2 transactions evicted because their initiator had too many queued transactions.
+    pub evicted_transactions: Vec<H256>,
 }
 
 #[derive(Debug)]
 pub struct MempoolStats {
     pub l1_transaction_count: usize,
     pub l2_transaction_count: u64,
+    pub l2_transactions_size_in_bytes: usize,
     pub l2_priority_queue_size: usize,
 }
 
+/// Secondary index over the L2 priority queue ordering transactions by their effective priority fee
+/// for a specific base fee. Used with [`L2TxOrdering::EffectivePriorityFee`]; since effective fees depend
+/// on the base fee, the index is rebuilt once the base fee changes.
+#[derive(Debug)]
+struct EffectiveFeeIndex {
+    base_fee: U256,
+    /// Later received transactions are lesser according to the [`MempoolScore`] ordering, so the last entry
+    /// corresponds to the earliest received transaction among ones with the highest effective fee.
+    scores: BTreeSet<(U256, MempoolScore)>,
+}
+
+impl EffectiveFeeIndex {
+    fn new<'a>(base_fee: U256, scores: impl Iterator<Item = &'a MempoolScore>) -> Self {
+        let scores = scores
+            .map(|score| (score.effective_priority_fee(base_fee), score.clone()))
+            .collect();
+        Self { base_fee, scores }
+    }
+
+    fn insert(&mut self, score: MempoolScore) {
+        let fee = score.effective_priority_fee(self.base_fee);
+        self.scores.insert((fee, score));
+    }
+
+    fn remove(&mut self, score: &MempoolScore) {
+        let fee = score.effective_priority_fee(self.base_fee);
+        self.scores.remove(&(fee, score.clone()));
+    }
+}
+
 #[derive(Debug)]
 pub struct MempoolStore {
     /// Pending L1 transactions
@@ -27,12 +64,25 @@ pub struct MempoolStore {
     l2_transactions_per_account: HashMap<Address, AccountTransactions>,
     /// Global priority queue for L2 transactions. Used for scoring
     l2_priority_queue: BTreeSet<MempoolScore>,
+    /// Index over `l2_priority_queue` by effective priority fee. Only maintained for
+    /// [`L2TxOrdering::EffectivePriorityFee`] once the first transaction is requested.
+    effective_fee_index: Option<EffectiveFeeIndex>,
     /// Next priority operation
     next_priority_id: PriorityOpId,
     stashed_accounts: Vec<Address>,
     /// Number of L2 transactions in the mempool.
     size: u64,
     capacity: u64,
+    /// Total size of L2 transactions in the mempool in bytes.
+    size_in_bytes: usize,
+    max_size_in_bytes: Option<usize>,
+    /// Base fee per gas from the last filter used to request a transaction. Used to rank transactions
+    /// by their effective fee when purging accounts from a full mempool.
+    last_base_fee: Option<U256>,
+    /// Maximum number of queued transactions (i.e., ones following a nonce gap) per account.
+    max_queued_txs_per_account: Option<usize>,
+    evicted_transactions: Vec<H256>,
+    tx_ordering: L2TxOrdering,
 }
 
 impl MempoolStore {
@@ -41,13 +91,40 @@ impl MempoolStore {
             l1_transactions: HashMap::new(),
             l2_transactions_per_account: HashMap::new(),
             l2_priority_queue: BTreeSet::new(),
+            effective_fee_index: None,
             next_priority_id,
             stashed_accounts: vec![],
             size: 0,
             capacity,
+            size_in_bytes: 0,
+            max_size_in_bytes: None,
+            last_base_fee: None,
+            max_queued_txs_per_account: None,
+            evicted_transactions: vec![],
+            tx_ordering: L2TxOrdering::default(),
         }
     }
 
+    /// Sets the order in which ready L2 transactions are returned by [`Self::next_transaction()`].
+    pub fn with_tx_ordering(mut self, tx_ordering: L2TxOrdering) -> Self {
+        self.tx_ordering = tx_ordering;
+        self
+    }
+
+    /// Limits the number of queued transactions (i.e., ones that cannot be executed because of a nonce gap)
+    /// per account. Once the limit is exceeded, queued transactions with the highest nonces are evicted.
+    pub fn with_max_queued_txs_per_account(mut self, limit: usize) -> Self {
+        self.max_queued_txs_per_account = Some(limit);
+        self
+    }
+
+    /// Limits the total size of L2 transactions in the mempool in bytes. Once the limit is exceeded,
+    /// accounts are purged in the same way as when the mempool capacity is reached.
+    pub fn with_max_size_in_bytes(mut self, limit: usize) -> Self {
+        self.max_size_in_bytes = Some(limit);
+        self
+    }
+
     /// Inserts batch of new transactions to mempool
     /// `initial_nonces` provides current committed nonce information to mempool
     /// variable is used only if account is not present in mempool yet and we have to bootstrap it
@@ -101,6 +178,10 @@ impl MempoolStore {
         initial_nonces: &HashMap<Address, Nonce>,
     ) {
         let account = transaction.initiator_account();
+        let account_size_before = self
+            .l2_transactions_per_account
+            .get(&account)
+            .map_or(0, AccountTransactions::size_in_bytes);
 
         let metadata = match self.l2_transactions_per_account.entry(account) {
             hash_map::Entry::Occupied(mut txs) => txs.get_mut().insert(transaction),
@@ -112,14 +193,34 @@ impl MempoolStore {
             }
         };
         if let Some(score) = metadata.previous_score {
-            self.l2_priority_queue.remove(&score);
+            self.remove_score(&score);
         }
         if let Some(score) = metadata.new_score {
-            self.l2_priority_queue.insert(score);
+            self.insert_score(score);
         }
         if metadata.is_new {
             self.size += 1;
         }
+
+        if let Some(limit) = self.max_queued_txs_per_account {
+            let evicted = self
+                .l2_transactions_per_account
+                .get_mut(&account)
+                .expect("account was just inserted")
+                .evict_queued(limit);
+            if !evicted.is_empty() {
+                tracing::debug!(
+                    "evicted {} queued transactions of account {account:?}",
+                    evicted.len()
+                );
+            }
+            self.size -= evicted.len() as u64;
+            self.evicted_transactions
+                .extend(evicted.iter().map(L2Tx::hash));
+        }
+
+        let account_size_after = self.l2_transactions_per_account[&account].size_in_bytes();
+        self.size_in_bytes = self.size_in_bytes - account_size_before + account_size_after;
     }
 
     /// Returns `true` if there is a transaction in the mempool satisfying the filter.
@@ -140,25 +241,42 @@ impl MempoolStore {
         }
 
         let mut removed = 0;
+        let mut removed_bytes = 0;
+        self.last_base_fee = Some(U256::from(filter.fee_per_gas));
         // We want to fetch the next transaction that would match the fee requirements.
-        let tx_pointer = self
-            .l2_priority_queue
-            .iter()
-            .rfind(|el| el.matches_filter(filter))?
-            .clone();
+        let tx_pointer = match self.tx_ordering {
+            L2TxOrdering::Fifo => self
+                .l2_priority_queue
+                .iter()
+                .rfind(|el| el.matches_filter(filter))?,
+            L2TxOrdering::EffectivePriorityFee => {
+                let base_fee = U256::from(filter.fee_per_gas);
+                self.effective_fee_index(base_fee)
+                    .scores
+                    .iter()
+                    .rev()
+                    .map(|(_, score)| score)
+                    .find(|el| el.matches_filter(filter))?
+            }
+        }
+        .clone();
+        self.remove_score(&tx_pointer);
 
-        // Stash all observed transactions that don't meet criteria
-        for stashed_pointer in self
+        // Stash all transactions preceding the selected one in the FIFO order that don't meet criteria
+        let stashed_pointers: Vec<_> = self
             .l2_priority_queue
-            .split_off(&tx_pointer)
-            .into_iter()
-            .skip(1)
-        {
-            removed += self
+            .range((Bound::Excluded(&tx_pointer), Bound::Unbounded))
+            .filter(|el| !el.matches_filter(filter))
+            .cloned()
+            .collect();
+        for stashed_pointer in stashed_pointers {
+            self.remove_score(&stashed_pointer);
+            let stashed = self
                 .l2_transactions_per_account
                 .remove(&stashed_pointer.account)
-                .expect("mempool: dangling pointer in priority queue")
-                .len();
+                .expect("mempool: dangling pointer in priority queue");
+            removed += stashed.len();
+            removed_bytes += stashed.size_in_bytes();
 
             self.stashed_accounts.push(stashed_pointer.account);
         }
@@ -170,12 +288,13 @@ impl MempoolStore {
             .next();
 
         if let Some(score) = score {
-            self.l2_priority_queue.insert(score);
+            self.insert_score(score);
         }
         self.size = self
             .size
             .checked_sub((removed + 1) as u64)
             .expect("mempool size can't be negative");
+        self.size_in_bytes -= removed_bytes + tx_size_in_bytes(&transaction);
         Some(transaction.into())
     }
 
@@ -196,7 +315,7 @@ impl MempoolStore {
                     .expect("account is not available in mempool")
                     .reset(tx)
                 {
-                    self.l2_priority_queue.remove(&score);
+                    self.remove_score(&score);
                 }
             }
             ExecuteTransactionCommon::ProtocolUpgrade(_) => {
@@ -205,10 +324,39 @@ impl MempoolStore {
         }
     }
 
+    fn insert_score(&mut self, score: MempoolScore) {
+        if let Some(index) = &mut self.effective_fee_index {
+            index.insert(score.clone());
+        }
+        self.l2_priority_queue.insert(score);
+    }
+
+    fn remove_score(&mut self, score: &MempoolScore) {
+        if let Some(index) = &mut self.effective_fee_index {
+            index.remove(score);
+        }
+        self.l2_priority_queue.remove(score);
+    }
+
+    /// Returns the effective fee index for the specified base fee, rebuilding it if necessary.
+    fn effective_fee_index(&mut self, base_fee: U256) -> &EffectiveFeeIndex {
+        let is_stale = self
+            .effective_fee_index
+            .as_ref()
+            .map_or(true, |index| index.base_fee != base_fee);
+        if is_stale {
+            tracing::trace!("rebuilding effective fee index for base fee {base_fee}");
+            let index = EffectiveFeeIndex::new(base_fee, self.l2_priority_queue.iter());
+            self.effective_fee_index = Some(index);
+        }
+        self.effective_fee_index.as_ref().unwrap()
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
         MempoolInfo {
             stashed_accounts: std::mem::take(&mut self.stashed_accounts),
             purged_accounts: self.gc(),
+            evicted_transactions: std::mem::take(&mut self.evicted_transactions),
         }
     }
 
@@ -216,28 +364,76 @@ impl MempoolStore {
         MempoolStats {
             l1_transaction_count: self.l1_transactions.len(),
             l2_transaction_count: self.size,
+            l2_transactions_size_in_bytes: self.size_in_bytes,
             l2_priority_queue_size: self.l2_priority_queue.len(),
         }
     }
 
+    /// Checks whether the mempool is full, either by the number of transactions or by their total size.
+    fn is_full(&self) -> bool {
+        self.size >= self.capacity
+            || self
+                .max_size_in_bytes
+                .map_or(false, |limit| self.size_in_bytes > limit)
+    }
+
+    /// Purges accounts from the mempool if it's full. Accounts without executable transactions
+    /// (i.e., ones with a nonce gap) are purged first. If this is not enough, accounts are purged in the order
+    /// of increasing effective fee per gas of their next transaction (ties are broken by evicting the most recently
+    /// received transactions first) until the mempool is no longer full. The effective fee is computed using
+    /// the base fee of the last transaction request; before the first request, the max fee per gas is used instead.
     fn gc(&mut self) -> Vec<Address> {
-        if self.size >= self.capacity {
-            let index: HashSet<_> = self
+        if !self.is_full() {
+            return vec![];
+        }
+
+        let index: HashSet<_> = self
+            .l2_priority_queue
+            .iter()
+            .map(|pointer| pointer.account)
+            .collect();
+        let transactions = std::mem::take(&mut self.l2_transactions_per_account);
+        let (kept, drained): (HashMap<_, _>, HashMap<_, _>) = transactions
+            .into_iter()
+            .partition(|(address, _)| index.contains(address));
+        self.l2_transactions_per_account = kept;
+        self.size = self
+            .l2_transactions_per_account
+            .iter()
+            .fold(0, |agg, (_, tnxs)| agg + tnxs.len() as u64);
+        self.size_in_bytes = self
+            .l2_transactions_per_account
+            .values()
+            .map(AccountTransactions::size_in_bytes)
+            .sum();
+        let mut purged_accounts: Vec<_> = drained.into_keys().collect();
+
+        if self.is_full() {
+            let base_fee = self.last_base_fee.unwrap_or(U256::MAX);
+            let mut scores: Vec<_> = self
                 .l2_priority_queue
                 .iter()
-                .map(|pointer| pointer.account)
+                .map(|score| (score.effective_fee(base_fee), score.clone()))
                 .collect();
-            let transactions = std::mem::take(&mut self.l2_transactions_per_account);
-            let (kept, drained) = transactions
-                .into_iter()
-                .partition(|(address, _)| index.contains(address));
-            self.l2_transactions_per_account = kept;
-            self.size = self
-                .l2_transactions_per_account
-                .iter()
-                .fold(0, |agg, (_, tnxs)| agg + tnxs.len() as u64);
-            return drained.into_keys().collect();
+            scores.sort_by(|(a_fee, a), (b_fee, b)| {
+                a_fee
+                    .cmp(b_fee)
+                    .then_with(|| b.received_at_ms.cmp(&a.received_at_ms))
+            });
+            for (_, score) in scores {
+                if !self.is_full() {
+                    break;
+                }
+                self.remove_score(&score);
+                let removed = self
+                    .l2_transactions_per_account
+                    .remove(&score.account)
+                    .expect("mempool: dangling pointer in priority queue");
+                self.size -= removed.len() as u64;
+                self.size_in_bytes -= removed.size_in_bytes();
+                purged_accounts.push(score.account);
+            }
         }
-        vec![]
+        purged_accounts
     }
 }
@@ -12,7 +12,10 @@ use zksync_types::{
     H256, U256,
 };
 
-use crate::{mempool_store::MempoolStore, types::L2TxFilter};
+use crate::{
+    mempool_store::MempoolStore,
+    types::{L2TxFilter, L2TxOrdering},
+};
 
 #[test]
 fn basic_flow() {
@@ -365,6 +368,237 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn evicting_queued_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_max_queued_txs_per_account(2);
+    let account = Address::random();
+    let transactions: Vec<_> = [0, 1, 3, 4, 5, 6]
+        .into_iter()
+        .map(|nonce| gen_l2_tx_with_hash(account, Nonce(nonce)))
+        .collect();
+    let evicted_hashes: HashSet<_> = transactions[4..].iter().map(Transaction::hash).collect();
+    mempool.insert(transactions, HashMap::new());
+
+    assert_eq!(mempool.stats().l2_transaction_count, 4);
+    let info = mempool.get_mempool_info();
+    assert_eq!(
+        HashSet::from_iter(info.evicted_transactions),
+        evicted_hashes
+    );
+    assert!(mempool.get_mempool_info().evicted_transactions.is_empty());
+
+    // Filling the nonce gap makes queued transactions executable, so they are no longer subject to the limit.
+    mempool.insert(
+        vec![
+            gen_l2_tx_with_hash(account, Nonce(2)),
+            gen_l2_tx_with_hash(account, Nonce(5)),
+        ],
+        HashMap::new(),
+    );
+    assert_eq!(mempool.stats().l2_transaction_count, 6);
+    assert!(mempool.get_mempool_info().evicted_transactions.is_empty());
+    for nonce in 0..6 {
+        assert_eq!(
+            view(mempool.next_transaction(&L2TxFilter::default())),
+            (account, nonce)
+        );
+    }
+}
+
+#[test]
+fn purging_accounts_with_lowest_fees() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 4);
+    let accounts: Vec<_> = (0..3).map(|_| Address::random()).collect();
+    let transactions = vec![
+        gen_l2_tx_with_fee(accounts[0], Nonce(0), 300),
+        gen_l2_tx_with_fee(accounts[0], Nonce(1), 300),
+        gen_l2_tx_with_fee(accounts[1], Nonce(0), 100),
+        gen_l2_tx_with_fee(accounts[1], Nonce(1), 100),
+        gen_l2_tx_with_fee(accounts[2], Nonce(0), 200),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    // Purging `accounts[1]` is enough to free up capacity.
+    assert_eq!(mempool.get_mempool_info().purged_accounts, [accounts[1]]);
+    assert_eq!(mempool.stats().l2_transaction_count, 3);
+    assert!(mempool.get_mempool_info().purged_accounts.is_empty());
+
+    let mut remaining_accounts = HashSet::new();
+    while let Some(tx) = mempool.next_transaction(&L2TxFilter::default()) {
+        remaining_accounts.insert(tx.initiator_account());
+    }
+    assert_eq!(
+        remaining_accounts,
+        HashSet::from([accounts[0], accounts[2]])
+    );
+}
+
+#[test]
+fn purging_accounts_with_lowest_effective_fees() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 3);
+    let accounts: Vec<_> = (0..3).map(|_| Address::random()).collect();
+    let gen_tx = |account, nonce, max_fee_per_gas: u64, max_priority_fee: u64| {
+        let mut tx = gen_l2_tx(account, Nonce(nonce));
+        match &mut tx.common_data {
+            ExecuteTransactionCommon::L2(data) => {
+                data.fee.max_fee_per_gas = max_fee_per_gas.into();
+                data.fee.max_priority_fee_per_gas = max_priority_fee.into();
+            }
+            _ => unreachable!(),
+        }
+        tx
+    };
+    mempool.insert(vec![gen_tx(accounts[0], 0, 1_000, 1_000)], HashMap::new());
+    // Sets the base fee used to compute effective fees.
+    let filter = L2TxFilter {
+        fee_per_gas: 100,
+        ..L2TxFilter::default()
+    };
+    assert!(mempool.next_transaction(&filter).is_some());
+
+    let transactions = vec![
+        // Effective fee: min(300, 100 + 0) = 100
+        gen_tx(accounts[1], 0, 300, 0),
+        gen_tx(accounts[1], 1, 300, 0),
+        // Effective fee: min(200, 100 + 50) = 150
+        gen_tx(accounts[2], 0, 200, 50),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    // `accounts[0]` has no transactions left, and `accounts[1]` has the highest max fee, but the lowest effective fee.
+    assert_eq!(
+        HashSet::<_>::from_iter(mempool.get_mempool_info().purged_accounts),
+        HashSet::from([accounts[0], accounts[1]])
+    );
+    assert_eq!(mempool.stats().l2_transaction_count, 1);
+}
+
+#[test]
+fn purging_accounts_exceeding_size_limit() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_max_size_in_bytes(250);
+    let accounts: Vec<_> = (0..3).map(|_| Address::random()).collect();
+    let gen_tx = |account, nonce, max_fee_per_gas: u64| {
+        let mut tx = gen_l2_tx_with_fee(account, Nonce(nonce), max_fee_per_gas);
+        tx.raw_bytes = Some(vec![0; 100].into());
+        tx
+    };
+    mempool.insert(
+        vec![gen_tx(accounts[0], 0, 300), gen_tx(accounts[1], 0, 100)],
+        HashMap::new(),
+    );
+    assert_eq!(mempool.stats().l2_transactions_size_in_bytes, 200);
+    assert!(mempool.get_mempool_info().purged_accounts.is_empty());
+
+    mempool.insert(vec![gen_tx(accounts[2], 0, 200)], HashMap::new());
+    assert_eq!(mempool.stats().l2_transactions_size_in_bytes, 300);
+    assert_eq!(mempool.get_mempool_info().purged_accounts, [accounts[1]]);
+    assert_eq!(mempool.stats().l2_transaction_count, 2);
+    assert_eq!(mempool.stats().l2_transactions_size_in_bytes, 200);
+
+    mempool.next_transaction(&L2TxFilter::default()).unwrap();
+    assert_eq!(mempool.stats().l2_transactions_size_in_bytes, 100);
+}
+
+#[test]
+fn effective_priority_fee_ordering() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100)
+        .with_tx_ordering(L2TxOrdering::EffectivePriorityFee);
+    let accounts: Vec<_> = (0..5).map(|_| Address::random()).collect();
+    let gen_tx = |account, nonce, received_at_ms, max_fee_per_gas: u64, max_priority_fee: u64| {
+        let mut tx = gen_l2_tx_with_timestamp(account, Nonce(nonce), received_at_ms);
+        match &mut tx.common_data {
+            ExecuteTransactionCommon::L2(data) => {
+                data.fee.max_fee_per_gas = max_fee_per_gas.into();
+                data.fee.max_priority_fee_per_gas = max_priority_fee.into();
+            }
+            _ => unreachable!(),
+        }
+        tx
+    };
+    let transactions = vec![
+        // Doesn't match the filter and precedes all other transactions, so it will be stashed.
+        gen_tx(accounts[0], 0, 0, 90, 90),
+        gen_tx(accounts[1], 0, 1, 200, 50),
+        gen_tx(accounts[2], 0, 2, 120, 100),
+        gen_tx(accounts[3], 0, 3, 300, 80),
+        gen_tx(accounts[3], 1, 3, 300, 0),
+        gen_tx(accounts[4], 0, 4, 150, 50),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let filter = L2TxFilter {
+        fee_per_gas: 100,
+        ..L2TxFilter::default()
+    };
+    let mut order = vec![];
+    while let Some(tx) = mempool.next_transaction(&filter) {
+        order.push(view(Some(tx)));
+    }
+    assert_eq!(
+        order,
+        [
+            (accounts[3], 0),
+            (accounts[1], 0),
+            (accounts[4], 0),
+            (accounts[2], 0),
+            (accounts[3], 1),
+        ]
+    );
+    assert_eq!(mempool.get_mempool_info().stashed_accounts, [accounts[0]]);
+}
+
+#[test]
+fn effective_priority_fee_ordering_with_changing_base_fee() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100)
+        .with_tx_ordering(L2TxOrdering::EffectivePriorityFee);
+    let accounts: Vec<_> = (0..3).map(|_| Address::random()).collect();
+    let gen_tx = |account, nonce, max_fee_per_gas: u64, max_priority_fee: u64| {
+        let mut tx = gen_l2_tx_with_timestamp(account, Nonce(nonce), u64::from(nonce));
+        match &mut tx.common_data {
+            ExecuteTransactionCommon::L2(data) => {
+                data.fee.max_fee_per_gas = max_fee_per_gas.into();
+                data.fee.max_priority_fee_per_gas = max_priority_fee.into();
+            }
+            _ => unreachable!(),
+        }
+        tx
+    };
+    let transactions = vec![
+        gen_tx(accounts[0], 0, 200, 50),
+        gen_tx(accounts[0], 1, 200, 50),
+        gen_tx(accounts[1], 0, 130, 100),
+        gen_tx(accounts[1], 1, 130, 100),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    let filter = |fee_per_gas| L2TxFilter {
+        fee_per_gas,
+        ..L2TxFilter::default()
+    };
+
+    // With base fee 100, effective priority fees are 50 for `accounts[0]` and 30 for `accounts[1]`.
+    assert_eq!(
+        view(mempool.next_transaction(&filter(100))),
+        (accounts[0], 0)
+    );
+    // With base fee 0, effective priority fees are 50 and 100, respectively.
+    assert_eq!(view(mempool.next_transaction(&filter(0))), (accounts[1], 0));
+
+    // Transactions inserted after the index is built must be taken into account.
+    mempool.insert(vec![gen_tx(accounts[2], 0, 1_000, 1_000)], HashMap::new());
+    assert_eq!(view(mempool.next_transaction(&filter(0))), (accounts[2], 0));
+
+    assert_eq!(
+        view(mempool.next_transaction(&filter(100))),
+        (accounts[0], 1)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&filter(100))),
+        (accounts[1], 1)
+    );
+    assert_eq!(mempool.next_transaction(&filter(100)), None);
+    assert_eq!(mempool.stats().l2_priority_queue_size, 0);
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -384,6 +618,24 @@ fn gen_l2_tx_with_timestamp(address: Address, nonce: Nonce, received_at_ms: u64)
     txn.into()
 }
 
+fn gen_l2_tx_with_hash(address: Address, nonce: Nonce) -> Transaction {
+    let mut txn = gen_l2_tx(address, nonce);
+    match &mut txn.common_data {
+        ExecuteTransactionCommon::L2(data) => data.set_input(vec![], H256::random()),
+        _ => unreachable!(),
+    }
+    txn
+}
+
+fn gen_l2_tx_with_fee(address: Address, nonce: Nonce, max_fee_per_gas: u64) -> Transaction {
+    let mut txn = gen_l2_tx(address, nonce);
+    match &mut txn.common_data {
+        ExecuteTransactionCommon::L2(data) => data.fee.max_fee_per_gas = max_fee_per_gas.into(),
+        _ => unreachable!(),
+    }
+    txn
+}
+
 fn gen_l1_tx(priority_id: PriorityOpId) -> Transaction {
     let execute = Execute {
         contract_address: Address::repeat_byte(0x11),
@@ -12,6 +12,8 @@ pub(crate) struct AccountTransactions {
     /// account nonce in mempool
     /// equals to committed nonce in db + number of transactions sent to state keeper
     nonce: Nonce,
+    /// Total size of transactions in bytes
+    size_in_bytes: usize,
 }
 
 impl AccountTransactions {
@@ -19,6 +21,7 @@ impl AccountTransactions {
         Self {
             transactions: HashMap::new(),
             nonce,
+            size_in_bytes: 0,
         }
     }
 
@@ -31,10 +34,11 @@ impl AccountTransactions {
             return metadata;
         }
         let new_score = Self::score_for_transaction(&transaction);
-        let previous_score = self
-            .transactions
-            .insert(nonce, transaction)
-            .map(|tx| Self::score_for_transaction(&tx));
+        self.size_in_bytes += tx_size_in_bytes(&transaction);
+        let previous_score = self.transactions.insert(nonce, transaction).map(|tx| {
+            self.size_in_bytes -= tx_size_in_bytes(&tx);
+            Self::score_for_transaction(&tx)
+        });
         metadata.is_new = previous_score.is_none();
         if nonce == self.nonce {
             metadata.new_score = Some(new_score);
@@ -50,6 +54,7 @@ impl AccountTransactions {
             .transactions
             .remove(&self.nonce)
             .expect("missing transaction in mempool");
+        self.size_in_bytes -= tx_size_in_bytes(&transaction);
         self.nonce += 1;
         let score = self
             .transactions
@@ -70,10 +75,39 @@ impl AccountTransactions {
             .map(Self::score_for_transaction)
     }
 
+    /// Evicts queued transactions (i.e., ones following a nonce gap) so that at most `limit` of them remain.
+    /// Transactions with the highest nonces are evicted first. Returns evicted transactions.
+    pub fn evict_queued(&mut self, limit: usize) -> Vec<L2Tx> {
+        let mut first_gap = self.nonce;
+        while self.transactions.contains_key(&first_gap) {
+            first_gap += 1;
+        }
+        let mut queued_nonces: Vec<_> = self
+            .transactions
+            .keys()
+            .copied()
+            .filter(|&nonce| nonce > first_gap)
+            .collect();
+        if queued_nonces.len() <= limit {
+            return vec![];
+        }
+        queued_nonces.sort_unstable();
+        let evicted: Vec<_> = queued_nonces[limit..]
+            .iter()
+            .map(|nonce| self.transactions.remove(nonce).unwrap())
+            .collect();
+        self.size_in_bytes -= evicted.iter().map(tx_size_in_bytes).sum::<usize>();
+        evicted
+    }
+
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
 
+    pub fn size_in_bytes(&self) -> usize {
+        self.size_in_bytes
+    }
+
     fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
         MempoolScore {
             account: transaction.initiator_account(),
@@ -83,6 +117,15 @@ impl AccountTransactions {
     }
 }
 
+/// Returns the size of an L2 transaction accounted against the mempool size limit. This is the size
+/// of the raw transaction bytes if they are available, or of the calldata and factory deps otherwise.
+pub(crate) fn tx_size_in_bytes(transaction: &L2Tx) -> usize {
+    match &transaction.raw_bytes {
+        Some(raw_bytes) => raw_bytes.0.len(),
+        None => transaction.execute.calldata.len() + transaction.factory_deps_len() as usize,
+    }
+}
+
 /// Mempool score of transaction. Used to prioritize L2 transactions in mempool
 /// Currently trivial ordering is used based on received at timestamp
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
@@ -101,6 +144,19 @@ impl MempoolScore {
         self.fee_data.max_fee_per_gas >= U256::from(filter.fee_per_gas)
             && self.fee_data.gas_per_pubdata_limit >= U256::from(filter.gas_per_pubdata)
     }
+
+    /// Returns the fee per gas effectively paid by the transaction given the base fee per gas, i.e.
+    /// `min(max_fee_per_gas, base_fee + max_priority_fee_per_gas)`.
+    pub fn effective_fee(&self, base_fee: U256) -> U256 {
+        let fee = base_fee.saturating_add(self.fee_data.max_priority_fee_per_gas);
+        self.fee_data.max_fee_per_gas.min(fee)
+    }
+
+    /// Returns the priority fee per gas effectively paid by the transaction given the base fee per gas.
+    pub fn effective_priority_fee(&self, base_fee: U256) -> U256 {
+        let max_priority_fee = self.fee_data.max_fee_per_gas.saturating_sub(base_fee);
+        self.fee_data.max_priority_fee_per_gas.min(max_priority_fee)
+    }
 }
 
 impl Ord for MempoolScore {
@@ -126,6 +182,18 @@ pub(crate) struct InsertionMetadata {
     pub is_new: bool,
 }
 
+/// Order in which ready L2 transactions are returned from the mempool. Transactions of the same account
+/// are always returned in the nonce order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum L2TxOrdering {
+    /// Transactions are returned in the order they were received.
+    #[default]
+    Fifo,
+    /// Transactions paying the highest priority fee given the base fee from [`L2TxFilter`] are returned first.
+    /// Transactions with equal priority fees are returned in the order they were received.
+    EffectivePriorityFee,
+}
+
 /// Structure that can be used by state keeper to describe
 /// criteria for transaction it wants to fetch.
 #[derive(Debug, Default, PartialEq, Eq)]
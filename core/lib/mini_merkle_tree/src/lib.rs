@@ -97,36 +97,40 @@ where
             let depth = tree_depth_by_size(self.binary_tree_size);
             self.hasher.empty_subtree_hash(depth)
         } else {
-            self.compute_merkle_root_and_path(0, None)
+            self.compute_merkle_root_and_paths(&[]).0
         }
     }
 
     /// Returns the root hash and the Merkle proof for a leaf with the specified 0-based `index`.
     pub fn merkle_root_and_path(self, index: usize) -> (H256, Vec<H256>) {
-        let mut merkle_path = vec![];
-        let root_hash = self.compute_merkle_root_and_path(index, Some(&mut merkle_path));
-        (root_hash, merkle_path)
+        assert!(index < self.hashes.len(), "invalid tree leaf index");
+        let (root_hash, mut merkle_paths) = self.compute_merkle_root_and_paths(&[index]);
+        (root_hash, merkle_paths.pop().unwrap())
     }
 
-    fn compute_merkle_root_and_path(
-        self,
-        mut index: usize,
-        mut merkle_path: Option<&mut Vec<H256>>,
-    ) -> H256 {
-        assert!(index < self.hashes.len(), "invalid tree leaf index");
+    /// Returns the root hash and Merkle proofs for all leaves in the tree, in the leaf order.
+    /// This is more efficient than calling [`Self::merkle_root_and_path()`] for each leaf.
+    pub fn merkle_root_and_paths(self) -> (H256, Vec<Vec<H256>>) {
+        if self.hashes.is_empty() {
+            return (self.merkle_root(), vec![]);
+        }
+        let indices: Vec<_> = (0..self.hashes.len()).collect();
+        self.compute_merkle_root_and_paths(&indices)
+    }
+
+    fn compute_merkle_root_and_paths(self, indices: &[usize]) -> (H256, Vec<Vec<H256>>) {
+        assert!(!self.hashes.is_empty(), "tree has no leaves");
 
         let depth = tree_depth_by_size(self.binary_tree_size);
-        if let Some(merkle_path) = merkle_path.as_deref_mut() {
-            merkle_path.reserve(depth);
-        }
+        let mut merkle_paths = vec![Vec::with_capacity(depth); indices.len()];
 
         let mut hashes = self.hashes;
         let mut level_len = hashes.len();
         for level in 0..depth {
             let empty_hash_at_level = self.hasher.empty_subtree_hash(level);
 
-            if let Some(merkle_path) = merkle_path.as_deref_mut() {
-                let adjacent_idx = index ^ 1;
+            for (&index, merkle_path) in indices.iter().zip(&mut merkle_paths) {
+                let adjacent_idx = (index >> level) ^ 1;
                 let adjacent_hash = if adjacent_idx < level_len {
                     hashes[adjacent_idx]
                 } else {
@@ -144,10 +148,9 @@ where
                     .compress(&hashes[level_len - 1], &empty_hash_at_level);
             }
 
-            index /= 2;
             level_len = level_len / 2 + level_len % 2;
         }
-        hashes[0]
+        (hashes[0], merkle_paths)
     }
 }
 
@@ -197,6 +197,27 @@ fn merkle_proofs_are_valid_in_very_large_tree() {
     }
 }
 
+#[test]
+fn computing_all_merkle_proofs() {
+    for item_count in [1_u8, 2, 5, 50] {
+        let leaves = (1..=item_count).map(|byte| [byte; 88]);
+        let tree = MiniMerkleTree::new(leaves.clone(), Some(64));
+
+        let (merkle_root, paths) = tree.clone().merkle_root_and_paths();
+        assert_eq!(merkle_root, tree.clone().merkle_root());
+        assert_eq!(paths.len(), usize::from(item_count));
+        for (i, (item, path)) in leaves.zip(&paths).enumerate() {
+            assert_eq!(*path, tree.clone().merkle_root_and_path(i).1);
+            verify_merkle_proof(&item, i, 64, path, merkle_root);
+        }
+    }
+
+    let empty_tree = MiniMerkleTree::<88>::new(iter::empty(), Some(64));
+    let (merkle_root, paths) = empty_tree.clone().merkle_root_and_paths();
+    assert_eq!(merkle_root, empty_tree.merkle_root());
+    assert!(paths.is_empty());
+}
+
 #[test]
 fn merkle_proofs_are_valid_in_very_small_trees() {
     for item_count in 1..=20 {
@@ -18,10 +18,7 @@ impl GlueFrom<crate::vm_m5::vm_instance::VmPartialExecutionResult>
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
             },
-            refunds: crate::interface::Refunds {
-                gas_refunded: 0,
-                operator_suggested_refund: 0,
-            },
+            refunds: crate::interface::Refunds::default(),
         }
     }
 }
@@ -44,10 +41,7 @@ impl GlueFrom<crate::vm_m6::vm_instance::VmPartialExecutionResult>
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
             },
-            refunds: crate::interface::Refunds {
-                gas_refunded: 0,
-                operator_suggested_refund: 0,
-            },
+            refunds: crate::interface::Refunds::default(),
         }
     }
 }
@@ -70,10 +64,7 @@ impl GlueFrom<crate::vm_1_3_2::vm_instance::VmPartialExecutionResult>
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
             },
-            refunds: crate::interface::Refunds {
-                gas_refunded: 0,
-                operator_suggested_refund: 0,
-            },
+            refunds: crate::interface::Refunds::default(),
         }
     }
 }
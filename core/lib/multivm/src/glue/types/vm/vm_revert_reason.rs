@@ -1,5 +1,20 @@
 use crate::glue::GlueFrom;
 
+/// Legacy VMs don't parse some errors (e.g., Solidity panics), so we restore raw revert bytes
+/// and parse them again.
+fn unknown_revert_reason(
+    function_selector: Vec<u8>,
+    data: Vec<u8>,
+) -> crate::interface::VmRevertReason {
+    // Depending on the parsing path, `data` may or may not include the function selector.
+    let raw_bytes = if data.starts_with(&function_selector) {
+        data
+    } else {
+        [function_selector, data].concat()
+    };
+    crate::interface::VmRevertReason::from(raw_bytes.as_slice())
+}
+
 impl GlueFrom<crate::vm_m5::errors::VmRevertReason> for crate::interface::VmRevertReason {
     fn glue_from(value: crate::vm_m5::errors::VmRevertReason) -> Self {
         match value {
@@ -12,10 +27,7 @@ impl GlueFrom<crate::vm_m5::errors::VmRevertReason> for crate::interface::VmReve
             crate::vm_m5::errors::VmRevertReason::Unknown {
                 function_selector,
                 data,
-            } => Self::Unknown {
-                function_selector,
-                data,
-            },
+            } => unknown_revert_reason(function_selector, data),
         }
     }
 }
@@ -31,10 +43,7 @@ impl GlueFrom<crate::vm_m6::errors::VmRevertReason> for crate::interface::VmReve
             crate::vm_m6::errors::VmRevertReason::Unknown {
                 function_selector,
                 data,
-            } => Self::Unknown {
-                function_selector,
-                data,
-            },
+            } => unknown_revert_reason(function_selector, data),
         }
     }
 }
@@ -50,10 +59,7 @@ impl GlueFrom<crate::vm_1_3_2::errors::VmRevertReason> for crate::interface::VmR
             crate::vm_1_3_2::errors::VmRevertReason::Unknown {
                 function_selector,
                 data,
-            } => Self::Unknown {
-                function_selector,
-                data,
-            },
+            } => unknown_revert_reason(function_selector, data),
         }
     }
 }
@@ -15,6 +15,8 @@ impl GlueFrom<crate::vm_m5::vm_instance::VmTxExecutionResult> for VmExecutionRes
         result.refunds = Refunds {
             gas_refunded: value.gas_refunded,
             operator_suggested_refund: value.operator_suggested_refund,
+            // Legacy VMs don't track the refund breakdown.
+            ..Refunds::default()
         };
         result
     }
@@ -30,6 +32,8 @@ impl GlueFrom<crate::vm_m6::vm_instance::VmTxExecutionResult> for VmExecutionRes
         result.refunds = Refunds {
             gas_refunded: value.gas_refunded,
             operator_suggested_refund: value.operator_suggested_refund,
+            // Legacy VMs don't track the refund breakdown.
+            ..Refunds::default()
         };
         result
     }
@@ -45,6 +49,8 @@ impl GlueFrom<crate::vm_1_3_2::vm_instance::VmTxExecutionResult> for VmExecution
         result.refunds = Refunds {
             gas_refunded: value.gas_refunded,
             operator_suggested_refund: value.operator_suggested_refund,
+            // Legacy VMs don't track the refund breakdown.
+            ..Refunds::default()
         };
         result
     }
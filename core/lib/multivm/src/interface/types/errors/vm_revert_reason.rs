@@ -19,8 +19,14 @@ pub enum VmRevertReason {
         msg: String,
         data: Vec<u8>,
     },
+    /// Solidity panic (i.e., `Panic(uint256)` error) raised e.g. on arithmetic overflow or a failed `assert`.
+    Panic {
+        code: U256,
+        data: Vec<u8>,
+    },
     InnerTxError,
     VmError,
+    /// Custom error or an error that cannot be parsed. `data` contains raw revert bytes including the selector.
     Unknown {
         function_selector: Vec<u8>,
         data: Vec<u8>,
@@ -29,6 +35,8 @@ pub enum VmRevertReason {
 
 impl VmRevertReason {
     const GENERAL_ERROR_SELECTOR: &'static [u8] = &[0x08, 0xc3, 0x79, 0xa0];
+    const PANIC_SELECTOR: &'static [u8] = &[0x4e, 0x48, 0x7b, 0x71];
+
     fn parse_general_error(raw_bytes: &[u8]) -> Result<Self, VmRevertReasonParsingError> {
         let bytes = &raw_bytes[4..];
         if bytes.len() < 32 {
@@ -66,6 +74,39 @@ impl VmRevertReason {
         })
     }
 
+    fn parse_panic(raw_bytes: &[u8]) -> Result<Self, VmRevertReasonParsingError> {
+        let bytes = &raw_bytes[4..];
+        if bytes.len() != 32 {
+            return Err(VmRevertReasonParsingError::IncorrectStringLength(
+                bytes.to_vec(),
+            ));
+        }
+        Ok(Self::Panic {
+            code: U256::from_big_endian(bytes),
+            data: raw_bytes.to_vec(),
+        })
+    }
+
+    /// Returns a description of a Solidity panic code. Descriptions are the same as in Geth.
+    fn panic_description(code: U256) -> Option<&'static str> {
+        if code > U256::from(u8::MAX) {
+            return None;
+        }
+        Some(match code.as_u32() {
+            0x00 => "generic panic",
+            0x01 => "assert(false)",
+            0x11 => "arithmetic underflow or overflow",
+            0x12 => "division or modulo by zero",
+            0x21 => "enum overflow",
+            0x22 => "invalid encoded storage byte array accessed",
+            0x31 => "out-of-bounds array access; popping on an empty array",
+            0x32 => "out-of-bounds access of an array or bytesN",
+            0x41 => "out of memory",
+            0x51 => "uninitialized function",
+            _ => return None,
+        })
+    }
+
     pub fn to_user_friendly_string(&self) -> String {
         match self {
             // In case of `Unknown` reason we suppress it to prevent verbose `Error function_selector = 0x{}`
@@ -79,6 +120,7 @@ impl VmRevertReason {
         match self {
             VmRevertReason::Unknown { data, .. } => data.clone(),
             VmRevertReason::General { data, .. } => data.clone(),
+            VmRevertReason::Panic { data, .. } => data.clone(),
             _ => vec![],
         }
     }
@@ -106,6 +148,7 @@ impl VmRevertReason {
         let function_selector = &bytes[0..4];
         match function_selector {
             VmRevertReason::GENERAL_ERROR_SELECTOR => Self::parse_general_error(bytes),
+            VmRevertReason::PANIC_SELECTOR => Self::parse_panic(bytes),
             _ => {
                 let result = VmRevertReason::Unknown {
                     function_selector: function_selector.to_vec(),
@@ -129,15 +172,9 @@ impl From<&[u8]> for VmRevertReason {
                     error_msg.to_vec()
                 };
 
-                let data = if error_msg.len() > 4 {
-                    error_msg[4..].to_vec()
-                } else {
-                    vec![]
-                };
-
                 VmRevertReason::Unknown {
                     function_selector,
-                    data,
+                    data: error_msg.to_vec(),
                 }
             }
         }
@@ -146,10 +183,14 @@ impl From<&[u8]> for VmRevertReason {
 
 impl Display for VmRevertReason {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use VmRevertReason::{General, InnerTxError, Unknown, VmError};
+        use VmRevertReason::{General, InnerTxError, Panic, Unknown, VmError};
 
         match self {
             General { msg, .. } => write!(f, "{}", msg),
+            Panic { code, .. } => match Self::panic_description(*code) {
+                Some(description) => write!(f, "{description}"),
+                None => write!(f, "unknown panic code: {code:#x}"),
+            },
             VmError => write!(f, "VM Error",),
             InnerTxError => write!(f, "Bootloader-based tx failed"),
             Unknown {
@@ -205,6 +246,48 @@ mod tests {
         assert!(matches!(reason, VmRevertReason::Unknown { .. }));
     }
 
+    #[test]
+    fn panic_revert_reason_parsing() {
+        let mut msg = vec![0x4e, 0x48, 0x7b, 0x71];
+        msg.extend_from_slice(&[0; 31]);
+        msg.push(0x11);
+        let reason = VmRevertReason::from(msg.as_slice());
+        assert_eq!(
+            reason,
+            VmRevertReason::Panic {
+                code: 0x11.into(),
+                data: msg.clone(),
+            }
+        );
+        assert_eq!(reason.to_string(), "arithmetic underflow or overflow");
+        assert_eq!(reason.encoded_data(), msg);
+
+        *msg.last_mut().unwrap() = 0xff;
+        let reason = VmRevertReason::from(msg.as_slice());
+        assert_eq!(reason.to_string(), "unknown panic code: 0xff");
+
+        // Panic with malformed code.
+        msg.pop();
+        let reason = VmRevertReason::from(msg.as_slice());
+        assert!(matches!(reason, VmRevertReason::Unknown { .. }));
+        assert_eq!(reason.encoded_data(), msg);
+    }
+
+    #[test]
+    fn custom_revert_reason_parsing() {
+        let msg = [0xde, 0xad, 0xbe, 0xef, 0, 0, 0, 1];
+        let reason = VmRevertReason::from(msg.as_slice());
+        assert_eq!(
+            reason,
+            VmRevertReason::Unknown {
+                function_selector: msg[..4].to_vec(),
+                data: msg.to_vec(),
+            }
+        );
+        assert_eq!(reason.to_user_friendly_string(), "");
+        assert_eq!(reason.encoded_data(), msg);
+    }
+
     #[test]
     fn revert_reason_with_wrong_data_offset() {
         let msg = vec![
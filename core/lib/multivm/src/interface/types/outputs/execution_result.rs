@@ -14,6 +14,14 @@ use crate::interface::{Halt, VmExecutionStatistics, VmRevertReason};
 pub struct Refunds {
     pub gas_refunded: u32,
     pub operator_suggested_refund: u32,
+    // The refund breakdown below is only tracked by the latest VM version; it's zero for older versions.
+    /// Refund calculated by the bootloader itself before the operator was asked for a refund.
+    pub bootloader_suggested_refund: u32,
+    /// Part of `operator_suggested_refund` caused by charging more gas for pubdata than its fair price
+    /// (e.g., because of pubdata price fluctuations or storage slots written multiple times).
+    pub pubdata_refund: u32,
+    /// Part of `operator_suggested_refund` returning the batch overhead charged by the bootloader.
+    pub batch_overhead_refund: u32,
 }
 
 /// Events/storage logs/l2->l1 logs created within transaction execution.
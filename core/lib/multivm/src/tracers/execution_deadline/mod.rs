@@ -0,0 +1,38 @@
+use std::time::Instant;
+
+use crate::glue::tracers::IntoOldVmTracer;
+
+pub mod vm_1_4_1;
+pub mod vm_boojum_integration;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Tracer stopping the VM execution once the specified deadline has passed.
+///
+/// Not supported for VM versions before virtual blocks; for these versions, the execution is not limited.
+#[derive(Debug, Clone)]
+pub struct ExecutionDeadline {
+    deadline: Instant,
+    cycles: usize,
+}
+
+impl ExecutionDeadline {
+    /// Number of VM cycles between consecutive deadline checks.
+    const CHECK_INTERVAL_CYCLES: usize = 1_024;
+
+    pub fn new(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            cycles: 0,
+        }
+    }
+
+    /// Registers a VM cycle and returns whether the deadline has passed.
+    fn is_expired(&mut self) -> bool {
+        self.cycles += 1;
+        self.cycles % Self::CHECK_INTERVAL_CYCLES == 0 && Instant::now() >= self.deadline
+    }
+}
+
+impl IntoOldVmTracer for ExecutionDeadline {}
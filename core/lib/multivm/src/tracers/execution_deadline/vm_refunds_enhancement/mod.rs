@@ -0,0 +1,28 @@
+use zksync_state::WriteStorage;
+
+use crate::{
+    interface::{
+        tracer::{TracerExecutionStatus, TracerExecutionStopReason},
+        traits::tracers::dyn_tracers::vm_1_3_3::DynTracer,
+        Halt,
+    },
+    tracers::execution_deadline::ExecutionDeadline,
+    vm_refunds_enhancement::{BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState},
+};
+
+impl<S, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for ExecutionDeadline {}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for ExecutionDeadline {
+    fn finish_cycle(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &mut BootloaderState,
+    ) -> TracerExecutionStatus {
+        if self.is_expired() {
+            return TracerExecutionStatus::Stop(TracerExecutionStopReason::Abort(
+                Halt::TracerCustom("Execution deadline exceeded".to_string()),
+            ));
+        }
+        TracerExecutionStatus::Continue
+    }
+}
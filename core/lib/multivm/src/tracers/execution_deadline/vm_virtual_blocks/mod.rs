@@ -0,0 +1,24 @@
+use std::time::Instant;
+
+use zksync_state::WriteStorage;
+
+use crate::{
+    interface::dyn_tracers::vm_1_3_3::DynTracer,
+    tracers::execution_deadline::ExecutionDeadline,
+    vm_virtual_blocks::{
+        BootloaderState, ExecutionEndTracer, ExecutionProcessing, HistoryMode, SimpleMemory,
+        VmTracer, ZkSyncVmState,
+    },
+};
+
+impl<H: HistoryMode> ExecutionEndTracer<H> for ExecutionDeadline {
+    fn should_stop_execution(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for ExecutionDeadline {}
+
+impl<S: WriteStorage, H: HistoryMode> ExecutionProcessing<S, H> for ExecutionDeadline {}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for ExecutionDeadline {}
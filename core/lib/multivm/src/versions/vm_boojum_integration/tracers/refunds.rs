@@ -92,6 +92,8 @@ impl<S> RefundsTracer<S> {
         Refunds {
             gas_refunded: self.refund_gas,
             operator_suggested_refund: self.operator_refund.unwrap_or_default(),
+            // Legacy VMs don't track the refund breakdown.
+            ..Refunds::default()
         }
     }
 
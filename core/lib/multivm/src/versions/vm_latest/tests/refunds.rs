@@ -164,3 +164,25 @@ fn test_predetermined_refunded_gas() {
         current_state_without_predefined_refunds.used_contract_hashes
     );
 }
+
+#[test]
+fn refunds_breakdown() {
+    let mut vm = VmTesterBuilder::new(HistoryEnabled)
+        .with_empty_in_memory_storage()
+        .with_execution_mode(TxExecutionMode::VerifyExecute)
+        .with_random_rich_accounts(1)
+        .build();
+
+    let counter = read_test_contract();
+    let account = &mut vm.rich_accounts[0];
+    let DeployContractsTx { tx, .. } = account.get_deploy_tx(&counter, None, TxType::L2);
+    vm.vm.push_transaction(tx);
+    let result = vm.vm.execute(VmExecutionMode::OneTx);
+    assert!(!result.result.is_failed());
+
+    let refunds = &result.refunds;
+    assert!(refunds.bootloader_suggested_refund > 0);
+    assert!(refunds.pubdata_refund <= refunds.operator_suggested_refund);
+    // The bootloader doesn't charge batch overhead in the current fee model.
+    assert_eq!(refunds.batch_overhead_refund, 0);
+}
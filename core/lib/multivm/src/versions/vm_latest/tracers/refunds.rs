@@ -45,6 +45,9 @@ pub(crate) struct RefundsTracer<S> {
     pending_operator_refund: Option<u32>,
     refund_gas: u32,
     operator_refund: Option<u32>,
+    bootloader_suggested_refund: u32,
+    pubdata_refund: u32,
+    batch_overhead_refund: u32,
     timestamp_initial: Timestamp,
     timestamp_before_cycle: Timestamp,
     gas_remaining_before: u32,
@@ -61,6 +64,9 @@ impl<S> RefundsTracer<S> {
             pending_operator_refund: None,
             refund_gas: 0,
             operator_refund: None,
+            bootloader_suggested_refund: 0,
+            pubdata_refund: 0,
+            batch_overhead_refund: 0,
             timestamp_initial: Timestamp(0),
             timestamp_before_cycle: Timestamp(0),
             gas_remaining_before: 0,
@@ -90,9 +96,52 @@ impl<S> RefundsTracer<S> {
         Refunds {
             gas_refunded: self.refund_gas,
             operator_suggested_refund: self.operator_refund.unwrap_or_default(),
+            bootloader_suggested_refund: self.bootloader_suggested_refund,
+            pubdata_refund: self.pubdata_refund,
+            batch_overhead_refund: self.batch_overhead_refund,
         }
     }
 
+    /// Returns the price per pubdata byte (in wei) used to calculate the fair fee for a transaction.
+    fn eth_price_per_pubdata_byte_for_calculation(
+        &self,
+        current_ergs_per_pubdata_byte: u32,
+    ) -> U256 {
+        // For now, bootloader charges only for base fee.
+        let effective_gas_price = get_batch_base_fee(&self.l1_batch);
+
+        let bootloader_eth_price_per_pubdata_byte =
+            U256::from(effective_gas_price) * U256::from(current_ergs_per_pubdata_byte);
+
+        let fair_eth_price_per_pubdata_byte =
+            U256::from(self.l1_batch.fee_input.fair_pubdata_price());
+
+        // For now, L1 originated transactions are allowed to pay less than fair fee per pubdata,
+        // so we should take it into account.
+        std::cmp::min(
+            bootloader_eth_price_per_pubdata_byte,
+            fair_eth_price_per_pubdata_byte,
+        )
+    }
+
+    /// Returns the part of the transaction refund caused by charging more gas for pubdata than its fair price.
+    fn pubdata_refund(
+        &self,
+        gas_spent_on_pubdata: u32,
+        current_ergs_per_pubdata_byte: u32,
+        pubdata_published: u32,
+    ) -> u32 {
+        let effective_gas_price = get_batch_base_fee(&self.l1_batch);
+        let pre_paid_eth = U256::from(gas_spent_on_pubdata) * U256::from(effective_gas_price);
+        let fair_fee_eth = U256::from(pubdata_published)
+            * self.eth_price_per_pubdata_byte_for_calculation(current_ergs_per_pubdata_byte);
+        let refund_eth = pre_paid_eth.saturating_sub(fair_fee_eth);
+        // The refund is capped by the transaction body refund anyway, so saturating on overflow is fine.
+        ceil_div_u256(refund_eth, effective_gas_price.into())
+            .try_into()
+            .unwrap_or(u32::MAX)
+    }
+
     pub(crate) fn tx_body_refund(
         &self,
         bootloader_refund: u32,
@@ -118,18 +167,8 @@ impl<S> RefundsTracer<S> {
         // For now, bootloader charges only for base fee.
         let effective_gas_price = get_batch_base_fee(&self.l1_batch);
 
-        let bootloader_eth_price_per_pubdata_byte =
-            U256::from(effective_gas_price) * U256::from(current_ergs_per_pubdata_byte);
-
-        let fair_eth_price_per_pubdata_byte =
-            U256::from(self.l1_batch.fee_input.fair_pubdata_price());
-
-        // For now, L1 originated transactions are allowed to pay less than fair fee per pubdata,
-        // so we should take it into account.
-        let eth_price_per_pubdata_byte_for_calculation = std::cmp::min(
-            bootloader_eth_price_per_pubdata_byte,
-            fair_eth_price_per_pubdata_byte,
-        );
+        let eth_price_per_pubdata_byte_for_calculation =
+            self.eth_price_per_pubdata_byte_for_calculation(current_ergs_per_pubdata_byte);
 
         let fair_fee_eth = U256::from(gas_spent_on_computation)
             * U256::from(self.l1_batch.fee_input.fair_l2_gas_price())
@@ -275,7 +314,8 @@ impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for RefundsTracer<S> {
                 );
             }
 
-            let refund_to_propose = tx_body_refund + self.block_overhead_refund();
+            let batch_overhead_refund = self.block_overhead_refund();
+            let refund_to_propose = tx_body_refund + batch_overhead_refund;
 
             let refund_slot = OPERATOR_REFUNDS_OFFSET + current_tx_index;
 
@@ -288,6 +328,15 @@ impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for RefundsTracer<S> {
 
             bootloader_state.set_refund_for_current_tx(refund_to_propose);
             self.operator_refund = Some(refund_to_propose);
+            self.bootloader_suggested_refund = bootloader_refund;
+            self.pubdata_refund = self
+                .pubdata_refund(
+                    gas_spent_on_pubdata,
+                    current_ergs_per_pubdata_byte,
+                    pubdata_published,
+                )
+                .min(tx_body_refund);
+            self.batch_overhead_refund = batch_overhead_refund;
             self.set_refund_as_done();
 
             if tx_gas_limit < bootloader_refund {
@@ -87,6 +87,8 @@ impl RefundsTracer {
         Refunds {
             gas_refunded: self.refund_gas,
             operator_suggested_refund: self.operator_refund.unwrap_or_default(),
+            // Legacy VMs don't track the refund breakdown.
+            ..Refunds::default()
         }
     }
 
@@ -73,6 +73,8 @@ impl RefundsTracer {
         Refunds {
             gas_refunded: self.refund_gas,
             operator_suggested_refund: self.operator_refund.unwrap_or_default(),
+            // Legacy VMs don't track the refund breakdown.
+            ..Refunds::default()
         }
     }
 
@@ -398,6 +400,8 @@ impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for RefundsTracer {
         result.refunds = Refunds {
             gas_refunded: self.refund_gas,
             operator_suggested_refund: self.operator_refund.unwrap_or_default(),
+            // Legacy VMs don't track the refund breakdown.
+            ..Refunds::default()
         };
         result.statistics.pubdata_published = self.pubdata_published;
     }
@@ -10,7 +10,7 @@ use zksync_types::{
     snapshots::{
         SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
     },
-    storage::witness_block_state::WitnessBlockState,
+    storage::{storage_diff::L1BatchStorageDiff, witness_block_state::WitnessBlockState},
     L1BatchNumber,
 };
 
@@ -129,6 +129,17 @@ impl StoredObject for WitnessBlockState {
     serialize_using_bincode!();
 }
 
+impl StoredObject for L1BatchStorageDiff {
+    const BUCKET: Bucket = Bucket::L1BatchStorageDiffs;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("storage_diff_for_l1_batch_{key}.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
 impl dyn ObjectStore + '_ {
     /// Fetches the value for the given key if it exists.
     ///
@@ -168,6 +179,7 @@ impl dyn ObjectStore + '_ {
 mod tests {
     use zksync_types::{
         snapshots::{SnapshotFactoryDependency, SnapshotStorageLog},
+        storage::storage_diff::StorageDiffWrite,
         AccountTreeId, Bytes, StorageKey, H160, H256,
     };
 
@@ -248,4 +260,23 @@ mod tests {
         let reconstructed_factory_deps = store.get(key).await.unwrap();
         assert_eq!(factory_deps, reconstructed_factory_deps);
     }
+
+    #[tokio::test]
+    async fn test_storage_diff_can_be_serialized_and_deserialized() {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        let key = L1BatchNumber(123);
+        let address = H160::random();
+        let storage_diff = L1BatchStorageDiff {
+            touched_accounts: vec![address],
+            writes: vec![StorageDiffWrite {
+                key: StorageKey::new(AccountTreeId::new(address), H256::random()),
+                value: H256::random(),
+                enumeration_index: 42,
+                is_initial: true,
+            }],
+        };
+        store.put(key, &storage_diff).await.unwrap();
+        let reconstructed_storage_diff = store.get(key).await.unwrap();
+        assert_eq!(storage_diff, reconstructed_storage_diff);
+    }
 }
@@ -48,6 +48,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             filters_limit: self.filters_limit,
             subscriptions_limit: self.subscriptions_limit,
             pubsub_polling_interval: self.pubsub_polling_interval,
+            pubsub_db_notifications: self.pubsub_db_notifications.unwrap_or(false),
             max_nonce_ahead: *required(&self.max_nonce_ahead).context("max_nonce_ahead")?,
             gas_price_scale_factor: *required(&self.gas_price_scale_factor)
                 .context("gas_price_scale_factor")?,
@@ -103,6 +104,11 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("latests_values_cache_size_mb")?,
+            eth_call_cache_size: self
+                .eth_call_cache_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("eth_call_cache_size")?,
             fee_history_limit: self.fee_history_limit,
             max_batch_request_size: self
                 .max_batch_request_size
@@ -114,12 +120,88 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("max_response_body_size_mb")?,
+            max_batch_response_size_mb: self
+                .max_batch_response_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_batch_response_size_mb")?,
+            batch_request_time_budget_ms: self.batch_request_time_budget_ms,
             websocket_requests_per_minute_limit: self
                 .websocket_requests_per_minute_limit
                 .map(|x| x.try_into())
                 .transpose()
                 .context("websocket_requests_per_minute_limit")?,
+            websocket_compute_units_per_second_limit: self
+                .websocket_compute_units_per_second_limit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("websocket_compute_units_per_second_limit")?,
+            subscriptions_per_connection_limit: self.subscriptions_per_connection_limit,
+            websocket_message_buffer_capacity: self.websocket_message_buffer_capacity,
+            api_keys: self.api_keys.as_ref().map(|keys| keys.keys.clone()),
+            api_key_requests_per_second_limit: self
+                .api_key_requests_per_second_limit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("api_key_requests_per_second_limit")?,
+            api_key_compute_units_per_second_limit: self
+                .api_key_compute_units_per_second_limit
+                .map(|x| x.try_into())
+                .transpose()
+                .context("api_key_compute_units_per_second_limit")?,
             tree_api_url: self.tree_api_url.clone(),
+            max_logs_result_size: self
+                .max_logs_result_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_logs_result_size")?,
+            trace_timeout_ms: self.trace_timeout_ms,
+            trace_max_call_depth: self
+                .trace_max_call_depth
+                .map(|x| x.try_into())
+                .transpose()
+                .context("trace_max_call_depth")?,
+            trace_block_concurrency: self
+                .trace_block_concurrency
+                .map(|x| x.try_into())
+                .transpose()
+                .context("trace_block_concurrency")?,
+            trace_block_max_result_size: self
+                .trace_block_max_result_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("trace_block_max_result_size")?,
+            txpool_namespace_enabled: self.txpool_namespace_enabled.unwrap_or(false),
+            txpool_redact_transactions: self.txpool_redact_transactions.unwrap_or(false),
+            archive_state_path: self.archive_state_path.clone(),
+            persistent_filters: self.persistent_filters.unwrap_or(false),
+            filters_ttl_sec: self.filters_ttl_sec,
+            auth_bearer_tokens: self.auth_bearer_tokens.clone(),
+            auth_jwt_secret_path: self.auth_jwt_secret_path.clone(),
+            auth_protected_namespaces: self
+                .auth_protected_namespaces
+                .as_ref()
+                .map(|namespaces| namespaces.namespaces.clone()),
+            api_namespaces: self
+                .api_namespaces
+                .as_ref()
+                .map(|namespaces| namespaces.namespaces.clone()),
+            http_response_compression_threshold: self
+                .http_response_compression_threshold
+                .map(|x| x.try_into())
+                .transpose()
+                .context("http_response_compression_threshold")?,
+            gas_price_suggestion_blocks: self.gas_price_suggestion_blocks,
+            gas_price_suggestion_percentile: self.gas_price_suggestion_percentile,
+            gas_price_suggestion_smoothing: self.gas_price_suggestion_smoothing,
+            shutdown_drain_timeout_ms: self.shutdown_drain_timeout_ms,
+            cors_allowed_origins: self
+                .cors_allowed_origins
+                .as_ref()
+                .map(|origins| origins.origins.clone()),
+            cors_allowed_headers: self.cors_allowed_headers.clone(),
+            cors_max_age_sec: self.cors_max_age_sec,
+            tx_replacement_fee_bump_percent: self.tx_replacement_fee_bump_percent,
         })
     }
     fn build(this: &Self::Type) -> Self {
@@ -133,6 +215,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             filters_limit: this.filters_limit,
             subscriptions_limit: this.subscriptions_limit,
             pubsub_polling_interval: this.pubsub_polling_interval,
+            pubsub_db_notifications: Some(this.pubsub_db_notifications),
             max_nonce_ahead: Some(this.max_nonce_ahead),
             gas_price_scale_factor: Some(this.gas_price_scale_factor),
             request_timeout: this.request_timeout,
@@ -160,15 +243,75 @@ impl ProtoRepr for proto::Web3JsonRpc {
             latest_values_cache_size_mb: this
                 .latest_values_cache_size_mb
                 .map(|x| x.try_into().unwrap()),
+            eth_call_cache_size: this.eth_call_cache_size.map(|x| x.try_into().unwrap()),
             fee_history_limit: this.fee_history_limit,
             max_batch_request_size: this.max_batch_request_size.map(|x| x.try_into().unwrap()),
             max_response_body_size_mb: this
                 .max_response_body_size_mb
                 .map(|x| x.try_into().unwrap()),
+            max_batch_response_size_mb: this
+                .max_batch_response_size_mb
+                .map(|x| x.try_into().unwrap()),
+            batch_request_time_budget_ms: this.batch_request_time_budget_ms,
             websocket_requests_per_minute_limit: this
                 .websocket_requests_per_minute_limit
                 .map(|x| x.into()),
+            websocket_compute_units_per_second_limit: this
+                .websocket_compute_units_per_second_limit
+                .map(|x| x.into()),
+            subscriptions_per_connection_limit: this.subscriptions_per_connection_limit,
+            websocket_message_buffer_capacity: this.websocket_message_buffer_capacity,
+            api_keys: this
+                .api_keys
+                .as_ref()
+                .map(|keys| proto::ApiKeys { keys: keys.clone() }),
+            api_key_requests_per_second_limit: this
+                .api_key_requests_per_second_limit
+                .map(|x| x.into()),
+            api_key_compute_units_per_second_limit: this
+                .api_key_compute_units_per_second_limit
+                .map(|x| x.into()),
             tree_api_url: this.tree_api_url.clone(),
+            max_logs_result_size: this.max_logs_result_size.map(|x| x.try_into().unwrap()),
+            trace_timeout_ms: this.trace_timeout_ms,
+            trace_max_call_depth: this.trace_max_call_depth.map(|x| x.try_into().unwrap()),
+            trace_block_concurrency: this.trace_block_concurrency.map(|x| x.try_into().unwrap()),
+            trace_block_max_result_size: this
+                .trace_block_max_result_size
+                .map(|x| x.try_into().unwrap()),
+            txpool_namespace_enabled: Some(this.txpool_namespace_enabled),
+            txpool_redact_transactions: Some(this.txpool_redact_transactions),
+            archive_state_path: this.archive_state_path.clone(),
+            persistent_filters: Some(this.persistent_filters),
+            filters_ttl_sec: this.filters_ttl_sec,
+            auth_bearer_tokens: this.auth_bearer_tokens.clone(),
+            auth_jwt_secret_path: this.auth_jwt_secret_path.clone(),
+            auth_protected_namespaces: this.auth_protected_namespaces.as_ref().map(|namespaces| {
+                proto::Namespaces {
+                    namespaces: namespaces.clone(),
+                }
+            }),
+            api_namespaces: this
+                .api_namespaces
+                .as_ref()
+                .map(|namespaces| proto::Namespaces {
+                    namespaces: namespaces.clone(),
+                }),
+            http_response_compression_threshold: this
+                .http_response_compression_threshold
+                .map(|x| x.try_into().unwrap()),
+            gas_price_suggestion_blocks: this.gas_price_suggestion_blocks,
+            gas_price_suggestion_percentile: this.gas_price_suggestion_percentile,
+            gas_price_suggestion_smoothing: this.gas_price_suggestion_smoothing,
+            shutdown_drain_timeout_ms: this.shutdown_drain_timeout_ms,
+            cors_allowed_origins: this.cors_allowed_origins.as_ref().map(|origins| {
+                proto::CorsOrigins {
+                    origins: origins.clone(),
+                }
+            }),
+            cors_allowed_headers: this.cors_allowed_headers.clone(),
+            cors_max_age_sec: this.cors_max_age_sec,
+            tx_replacement_fee_bump_percent: this.tx_replacement_fee_bump_percent,
         }
     }
 }
@@ -3,7 +3,7 @@ use zksync_basic_types::network::Network;
 use zksync_config::configs;
 use zksync_protobuf::{repr::ProtoRepr, required};
 
-use crate::{parse_h160, proto::chain as proto};
+use crate::{parse_h160, parse_h256, proto::chain as proto};
 
 impl proto::Network {
     fn new(n: &Network) -> Self {
@@ -51,6 +51,24 @@ impl proto::FeeModelVersion {
     }
 }
 
+impl proto::MempoolTxOrdering {
+    fn new(n: &configs::chain::MempoolTxOrdering) -> Self {
+        use configs::chain::MempoolTxOrdering as From;
+        match n {
+            From::EffectivePriorityFee => Self::EffectivePriorityFee,
+            From::Fifo => Self::Fifo,
+        }
+    }
+
+    fn parse(&self) -> configs::chain::MempoolTxOrdering {
+        use configs::chain::MempoolTxOrdering as To;
+        match self {
+            Self::EffectivePriorityFee => To::EffectivePriorityFee,
+            Self::Fifo => To::Fifo,
+        }
+    }
+}
+
 impl ProtoRepr for proto::EthNetwork {
     type Type = configs::chain::NetworkConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -140,6 +158,28 @@ impl ProtoRepr for proto::StateKeeper {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("enum_index_migration_chunk_size")?,
+            seal_l1_batch_on_shutdown: self.seal_l1_batch_on_shutdown.unwrap_or_default(),
+            denied_addresses: self
+                .denied_addresses
+                .iter()
+                .enumerate()
+                .map(|(i, x)| parse_h160(x).context(i))
+                .collect::<Result<_, _>>()
+                .context("denied_addresses")?,
+            denied_bytecode_hashes: self
+                .denied_bytecode_hashes
+                .iter()
+                .enumerate()
+                .map(|(i, x)| parse_h256(x).context(i))
+                .collect::<Result<_, _>>()
+                .context("denied_bytecode_hashes")?,
+            shadow_source_schema: self.shadow_source_schema.clone(),
+            proof_backlog_threshold: self.proof_backlog_threshold,
+            max_tx_gas_per_pubdata: self.max_tx_gas_per_pubdata,
+            max_tx_calldata_size: self.max_tx_calldata_size,
+            max_tx_factory_deps: self.max_tx_factory_deps,
+            max_tx_factory_deps_size: self.max_tx_factory_deps_size,
+            upload_l1_batch_state_diffs: self.upload_l1_batch_state_diffs.unwrap_or_default(),
         })
     }
 
@@ -176,6 +216,24 @@ impl ProtoRepr for proto::StateKeeper {
                 .enum_index_migration_chunk_size
                 .as_ref()
                 .map(|x| (*x).try_into().unwrap()),
+            seal_l1_batch_on_shutdown: Some(this.seal_l1_batch_on_shutdown),
+            denied_addresses: this
+                .denied_addresses
+                .iter()
+                .map(|x| x.as_bytes().into())
+                .collect(),
+            denied_bytecode_hashes: this
+                .denied_bytecode_hashes
+                .iter()
+                .map(|x| x.as_bytes().into())
+                .collect(),
+            shadow_source_schema: this.shadow_source_schema.clone(),
+            proof_backlog_threshold: this.proof_backlog_threshold,
+            max_tx_gas_per_pubdata: this.max_tx_gas_per_pubdata,
+            max_tx_calldata_size: this.max_tx_calldata_size,
+            max_tx_factory_deps: this.max_tx_factory_deps,
+            max_tx_factory_deps_size: this.max_tx_factory_deps_size,
+            upload_l1_batch_state_diffs: Some(this.upload_l1_batch_state_diffs),
         }
     }
 }
@@ -207,6 +265,23 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: *required(&self.stuck_tx_timeout).context("stuck_tx_timeout")?,
             remove_stuck_txs: *required(&self.remove_stuck_txs).context("remove_stuck_txs")?,
             delay_interval: *required(&self.delay_interval).context("delay_interval")?,
+            max_queued_txs_per_account: self
+                .max_queued_txs_per_account
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_queued_txs_per_account")?,
+            max_size_bytes: self
+                .max_size_bytes
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_size_bytes")?,
+            tx_ordering: self
+                .tx_ordering
+                .map(proto::MempoolTxOrdering::try_from)
+                .transpose()
+                .context("tx_ordering")?
+                .map(|x| x.parse())
+                .unwrap_or_default(),
         })
     }
 
@@ -218,6 +293,11 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: Some(this.stuck_tx_timeout),
             remove_stuck_txs: Some(this.remove_stuck_txs),
             delay_interval: Some(this.delay_interval),
+            max_queued_txs_per_account: this
+                .max_queued_txs_per_account
+                .map(|x| x.try_into().unwrap()),
+            max_size_bytes: this.max_size_bytes.map(|x| x.try_into().unwrap()),
+            tx_ordering: Some(proto::MempoolTxOrdering::new(&this.tx_ordering).into()),
         }
     }
 }
@@ -90,12 +90,18 @@ impl ProtoRepr for proto::Postgres {
         Ok(Self::Type {
             master_url: self.master_url.clone(),
             replica_url: self.replica_url.clone(),
+            additional_replica_urls: self.additional_replica_urls.clone(),
             prover_url: self.prover_url.clone(),
             max_connections: self.max_connections,
             acquire_timeout_sec: self.acquire_timeout_sec,
             statement_timeout_sec: self.statement_timeout_sec,
             long_connection_threshold_ms: self.long_connection_threshold_ms,
             slow_query_threshold_ms: self.slow_query_threshold_ms,
+            api_max_connections: self.api_max_connections,
+            state_keeper_max_connections: self.state_keeper_max_connections,
+            witness_generator_max_connections: self.witness_generator_max_connections,
+            house_keeper_max_connections: self.house_keeper_max_connections,
+            schema: self.schema.clone(),
         })
     }
 
@@ -103,12 +109,18 @@ impl ProtoRepr for proto::Postgres {
         Self {
             master_url: this.master_url.clone(),
             replica_url: this.replica_url.clone(),
+            additional_replica_urls: this.additional_replica_urls.clone(),
             prover_url: this.prover_url.clone(),
             max_connections: this.max_connections,
             acquire_timeout_sec: this.acquire_timeout_sec,
             statement_timeout_sec: this.statement_timeout_sec,
             long_connection_threshold_ms: this.long_connection_threshold_ms,
             slow_query_threshold_ms: this.slow_query_threshold_ms,
+            api_max_connections: this.api_max_connections,
+            state_keeper_max_connections: this.state_keeper_max_connections,
+            witness_generator_max_connections: this.witness_generator_max_connections,
+            house_keeper_max_connections: this.house_keeper_max_connections,
+            schema: this.schema.clone(),
         }
     }
 }
@@ -50,6 +50,12 @@ impl ProtoRepr for proto::HouseKeeper {
                 &self.fri_proof_compressor_stats_reporting_interval_ms,
             )
             .context("fri_proof_compressor_stats_reporting_interval_ms")?,
+            db_pruning_retention_days: self.db_pruning_retention_days,
+            db_pruning_interval_ms: self.db_pruning_interval_ms,
+            db_partition_size_miniblocks: self.db_partition_size_miniblocks,
+            db_events_archival_retention_days: self.db_events_archival_retention_days,
+            db_partitioning_interval_ms: self.db_partitioning_interval_ms,
+            db_consistency_check_interval_ms: self.db_consistency_check_interval_ms,
         })
     }
 
@@ -82,6 +88,12 @@ impl ProtoRepr for proto::HouseKeeper {
             fri_proof_compressor_stats_reporting_interval_ms: Some(
                 this.fri_proof_compressor_stats_reporting_interval_ms,
             ),
+            db_pruning_retention_days: this.db_pruning_retention_days,
+            db_pruning_interval_ms: this.db_pruning_interval_ms,
+            db_partition_size_miniblocks: this.db_partition_size_miniblocks,
+            db_events_archival_retention_days: this.db_events_archival_retention_days,
+            db_partitioning_interval_ms: this.db_partitioning_interval_ms,
+            db_consistency_check_interval_ms: this.db_consistency_check_interval_ms,
         }
     }
 }
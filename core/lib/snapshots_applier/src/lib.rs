@@ -98,6 +98,11 @@ pub trait SnapshotsApplierMainNodeClient: fmt::Debug + Send + Sync {
 
     async fn fetch_newest_snapshot(&self) -> EnrichedClientResult<Option<SnapshotHeader>>;
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>>;
+
     async fn fetch_tokens(
         &self,
         at_miniblock: MiniblockNumber,
@@ -130,6 +135,16 @@ impl SnapshotsApplierMainNodeClient for HttpClient {
             .await
     }
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        self.get_snapshot_by_l1_batch_number(l1_batch_number)
+            .rpc_context("get_snapshot_by_l1_batch_number")
+            .with_arg("number", &l1_batch_number)
+            .await
+    }
+
     async fn fetch_tokens(
         &self,
         at_miniblock: MiniblockNumber,
@@ -147,6 +162,8 @@ pub struct SnapshotsApplierConfig {
     pub retry_count: usize,
     pub initial_retry_backoff: Duration,
     pub retry_backoff_multiplier: f32,
+    /// L1 batch of the snapshot to recover from. If not set, the newest snapshot on the main node is used.
+    pub l1_batch_number: Option<L1BatchNumber>,
     health_updater: HealthUpdater,
 }
 
@@ -156,6 +173,7 @@ impl Default for SnapshotsApplierConfig {
             retry_count: 5,
             initial_retry_backoff: Duration::from_secs(2),
             retry_backoff_multiplier: 2.0,
+            l1_batch_number: None,
             health_updater: ReactiveHealthCheck::new("snapshot_recovery").1,
         }
     }
@@ -183,6 +201,8 @@ impl SnapshotsApplierConfig {
     /// or under any of the following conditions:
     ///
     /// - There are no snapshots on the main node
+    /// - The snapshot for the requested L1 batch is missing on the main node, or differs from the snapshot
+    ///   recovery of which was started previously
     /// - Storage contains at least one L1 batch
     pub async fn run(
         self,
@@ -197,6 +217,7 @@ impl SnapshotsApplierConfig {
                 connection_pool,
                 main_node_client,
                 blob_store,
+                self.l1_batch_number,
                 &self.health_updater,
             )
             .await;
@@ -248,6 +269,7 @@ impl<'a> SnapshotsApplier<'a> {
     async fn prepare_applied_snapshot_status(
         storage: &mut StorageProcessor<'_>,
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
+        requested_l1_batch: Option<L1BatchNumber>,
     ) -> Result<(SnapshotRecoveryStatus, bool), SnapshotsApplierError> {
         let latency =
             METRICS.initial_stage_duration[&InitialStage::FetchMetadataFromMainNode].start();
@@ -261,6 +283,15 @@ impl<'a> SnapshotsApplier<'a> {
             })?;
 
         if let Some(applied_snapshot_status) = applied_snapshot_status {
+            if let Some(requested_l1_batch) = requested_l1_batch {
+                if applied_snapshot_status.l1_batch_number != requested_l1_batch {
+                    let err = anyhow::anyhow!(
+                        "snapshot recovery was started for L1 batch #{}, but L1 batch #{requested_l1_batch} is requested",
+                        applied_snapshot_status.l1_batch_number
+                    );
+                    return Err(SnapshotsApplierError::Fatal(err));
+                }
+            }
             let latency = latency.observe();
             tracing::info!("Re-initialized snapshots applier after reset/failure in {latency:?}");
 
@@ -281,8 +312,11 @@ impl<'a> SnapshotsApplier<'a> {
                 return Err(SnapshotsApplierError::Fatal(err));
             }
 
-            let recovery_status =
-                SnapshotsApplier::create_fresh_recovery_status(main_node_client).await?;
+            let recovery_status = SnapshotsApplier::create_fresh_recovery_status(
+                main_node_client,
+                requested_l1_batch,
+            )
+            .await?;
 
             let storage_logs_count = storage
                 .snapshots_creator_dal()
@@ -310,6 +344,7 @@ impl<'a> SnapshotsApplier<'a> {
         connection_pool: &'a ConnectionPool,
         main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
         blob_store: &'a dyn ObjectStore,
+        requested_l1_batch: Option<L1BatchNumber>,
         health_updater: &'a HealthUpdater,
     ) -> Result<(), SnapshotsApplierError> {
         health_updater.update(HealthStatus::Ready.into());
@@ -322,8 +357,12 @@ impl<'a> SnapshotsApplier<'a> {
         })?;
 
         let (applied_snapshot_status, created_from_scratch) =
-            Self::prepare_applied_snapshot_status(&mut storage_transaction, main_node_client)
-                .await?;
+            Self::prepare_applied_snapshot_status(
+                &mut storage_transaction,
+                main_node_client,
+                requested_l1_batch,
+            )
+            .await?;
 
         let mut this = Self {
             connection_pool,
@@ -373,11 +412,23 @@ impl<'a> SnapshotsApplier<'a> {
 
     async fn create_fresh_recovery_status(
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
+        requested_l1_batch: Option<L1BatchNumber>,
     ) -> Result<SnapshotRecoveryStatus, SnapshotsApplierError> {
-        let snapshot_response = main_node_client.fetch_newest_snapshot().await?;
-
-        let snapshot = snapshot_response
-            .context("no snapshots on main node; snapshot recovery is impossible")?;
+        let snapshot = if let Some(requested_l1_batch) = requested_l1_batch {
+            main_node_client
+                .fetch_snapshot(requested_l1_batch)
+                .await?
+                .with_context(|| {
+                    format!(
+                        "no snapshot for L1 batch #{requested_l1_batch} on main node; snapshot recovery is impossible"
+                    )
+                })?
+        } else {
+            main_node_client
+                .fetch_newest_snapshot()
+                .await?
+                .context("no snapshots on main node; snapshot recovery is impossible")?
+        };
         let l1_batch_number = snapshot.l1_batch_number;
         let miniblock_number = snapshot.miniblock_number;
         tracing::info!(
@@ -165,6 +165,41 @@ async fn applier_errors_without_snapshots() {
         .unwrap_err();
 }
 
+#[tokio::test]
+async fn recovering_snapshot_for_requested_l1_batch() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    let config = SnapshotsApplierConfig {
+        l1_batch_number: Some(expected_status.l1_batch_number + 1),
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    let err = config.run(&pool, &client, &object_store).await.unwrap_err();
+    assert!(format!("{err:#}").contains("no snapshot"), "{err:#}");
+
+    let config = SnapshotsApplierConfig {
+        l1_batch_number: Some(expected_status.l1_batch_number),
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    config.run(&pool, &client, &object_store).await.unwrap();
+    let mut storage = pool.access_storage().await.unwrap();
+    let current_db_status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(current_db_status.unwrap(), expected_status);
+
+    // Resuming recovery for another L1 batch must fail.
+    let config = SnapshotsApplierConfig {
+        l1_batch_number: Some(expected_status.l1_batch_number - 1),
+        ..SnapshotsApplierConfig::for_tests()
+    };
+    config.run(&pool, &client, &object_store).await.unwrap_err();
+}
+
 #[tokio::test]
 async fn applier_returns_error_on_fatal_object_store_error() {
     let pool = ConnectionPool::test_pool().await;
@@ -41,6 +41,16 @@ impl SnapshotsApplierMainNodeClient for MockMainNodeClient {
         Ok(self.fetch_newest_snapshot_response.clone())
     }
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        let snapshot = self.fetch_newest_snapshot_response.as_ref();
+        Ok(snapshot
+            .filter(|snapshot| snapshot.l1_batch_number == l1_batch_number)
+            .cloned())
+    }
+
     async fn fetch_tokens(
         &self,
         _at_miniblock: MiniblockNumber,
@@ -30,7 +30,7 @@ mod witness;
 pub use self::{
     in_memory::{InMemoryStorage, IN_MEMORY_STORAGE_DEFAULT_NETWORK_ID},
     postgres::{PostgresStorage, PostgresStorageCaches},
-    rocksdb::{RocksbStorageBuilder, RocksdbStorage},
+    rocksdb::{HistoricalRocksdbStorage, RocksbStorageBuilder, RocksdbArchive, RocksdbStorage},
     shadow_storage::ShadowStorage,
     storage_view::{StorageView, StorageViewMetrics},
     witness::WitnessStorage,
@@ -0,0 +1,316 @@
+//! Archive mode for [`RocksdbStorage`], in which the storage retains per-miniblock state changes
+//! so that it can serve state at historical miniblocks.
+//!
+//! For each storage change, the archive stores the value of the changed key *before* the change, keyed by
+//! the hashed storage key and the number of the miniblock in which the change happened (i.e., the archive
+//! is an undo log). The value of a key after miniblock N is the pre-change value of the earliest change
+//! of the key after N, or the current value if there are no such changes.
+
+use std::{collections::HashMap, ops};
+
+use anyhow::Context as _;
+use zksync_dal::StorageProcessor;
+use zksync_storage::{db::WriteBatch, RocksDB};
+use zksync_types::{L1BatchNumber, MiniblockNumber, StorageKey, StorageValue, H256};
+
+use super::{RocksdbStorage, StateKeeperColumnFamily, StateValue};
+use crate::ReadStorage;
+
+/// Changes to the archived state accumulated for an L1 batch.
+#[derive(Debug, Default)]
+pub(super) struct ArchivePatch {
+    /// Values of storage keys before each change, keyed by the hashed storage key and the number of the miniblock
+    /// in which the change happened. `None` corresponds to keys that did not exist before the change.
+    prev_values: Vec<(H256, MiniblockNumber, Option<StateValue>)>,
+    /// Range of miniblocks with archived state after the patch is applied.
+    miniblocks: Option<ops::RangeInclusive<MiniblockNumber>>,
+}
+
+fn serialize_history_key(hashed_key: H256, miniblock_number: MiniblockNumber) -> [u8; 36] {
+    let mut buffer = [0_u8; 36];
+    buffer[..32].copy_from_slice(hashed_key.as_bytes());
+    // Big-endian encoding ensures that changes of each key are ordered by the miniblock number.
+    buffer[32..].copy_from_slice(&miniblock_number.0.to_be_bytes());
+    buffer
+}
+
+fn serialize_prev_value(prev_value: Option<StateValue>) -> Vec<u8> {
+    prev_value.map_or_else(Vec::new, |value| value.serialize())
+}
+
+fn deserialize_prev_value(bytes: &[u8]) -> Option<StateValue> {
+    (!bytes.is_empty()).then(|| StateValue::deserialize(bytes))
+}
+
+fn serialize_miniblock_range(range: &ops::RangeInclusive<MiniblockNumber>) -> [u8; 8] {
+    let mut buffer = [0_u8; 8];
+    buffer[..4].copy_from_slice(&range.start().0.to_le_bytes());
+    buffer[4..].copy_from_slice(&range.end().0.to_le_bytes());
+    buffer
+}
+
+fn deserialize_miniblock_range(bytes: &[u8]) -> ops::RangeInclusive<MiniblockNumber> {
+    let bytes: [u8; 8] = bytes
+        .try_into()
+        .expect("incorrect archived miniblocks format");
+    let start = u32::from_le_bytes(bytes[..4].try_into().unwrap());
+    let end = u32::from_le_bytes(bytes[4..].try_into().unwrap());
+    MiniblockNumber(start)..=MiniblockNumber(end)
+}
+
+fn read_miniblock_range(
+    db: &RocksDB<StateKeeperColumnFamily>,
+) -> Option<ops::RangeInclusive<MiniblockNumber>> {
+    let bytes = db
+        .get_cf(
+            StateKeeperColumnFamily::State,
+            RocksdbStorage::ARCHIVE_MINIBLOCKS_KEY,
+        )
+        .expect("failed reading archived miniblocks from RocksDB")?;
+    Some(deserialize_miniblock_range(&bytes))
+}
+
+fn read_archived_state_value(
+    db: &RocksDB<StateKeeperColumnFamily>,
+    hashed_key: H256,
+    miniblock_number: MiniblockNumber,
+) -> Option<StateValue> {
+    // The current value must be read before the history. If the storage is updated concurrently,
+    // the value before the update will be present in the history, so the returned value will still be correct.
+    let current_value = RocksdbStorage::read_state_value(db, hashed_key);
+    let history_key = serialize_history_key(hashed_key, miniblock_number + 1);
+    let next_change = db
+        .from_iterator_cf(StateKeeperColumnFamily::StateHistory, &history_key)
+        .next();
+    match next_change {
+        Some((key, prev_value)) if key.starts_with(hashed_key.as_bytes()) => {
+            deserialize_prev_value(&prev_value)
+        }
+        _ => current_value,
+    }
+}
+
+impl RocksdbStorage {
+    /// Loads storage logs for the specified L1 batch and applies them to the storage, recording
+    /// per-miniblock changes in the archive.
+    pub(super) async fn apply_archived_storage_logs(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let (first_miniblock, last_miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed fetching miniblock range for L1 batch #{l1_batch_number}")
+            })?
+            .with_context(|| format!("L1 batch #{l1_batch_number} contains no miniblocks"))?;
+        let logs_by_miniblock = storage
+            .storage_logs_dal()
+            .get_touched_slots_by_miniblock_for_l1_batch(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed loading touched slots for L1 batch {l1_batch_number}")
+            })?;
+
+        let storage_logs = logs_by_miniblock
+            .values()
+            .flat_map(|logs| logs.iter().map(|(key, value)| (*key, *value)))
+            .collect();
+        self.apply_storage_logs(storage_logs, storage).await?;
+
+        let new_enum_indices: HashMap<_, _> = self
+            .pending_patch
+            .state
+            .iter()
+            .map(|(&hashed_key, &(_, enum_index))| (hashed_key, enum_index))
+            .collect();
+        let db = self.db.clone();
+        let archive_patch = tokio::task::spawn_blocking(move || {
+            let prev_values =
+                Self::process_archived_logs(&db, logs_by_miniblock, &new_enum_indices);
+            let miniblocks = match read_miniblock_range(&db) {
+                Some(range) if range.end().0 + 1 == first_miniblock.0 => {
+                    *range.start()..=last_miniblock
+                }
+                _ => {
+                    // The archive is empty or has a gap; the state before the L1 batch is the earliest
+                    // state that can be recovered.
+                    let start = MiniblockNumber(first_miniblock.0.saturating_sub(1));
+                    tracing::info!("Starting state archive from miniblock #{start}");
+                    start..=last_miniblock
+                }
+            };
+            ArchivePatch {
+                prev_values,
+                miniblocks: Some(miniblocks),
+            }
+        })
+        .await
+        .context("panicked processing archived storage logs")?;
+        self.pending_archive_patch = archive_patch;
+        Ok(())
+    }
+
+    /// Returns values of storage keys before each change in the provided logs.
+    fn process_archived_logs(
+        db: &RocksDB<StateKeeperColumnFamily>,
+        logs_by_miniblock: impl IntoIterator<Item = (MiniblockNumber, HashMap<StorageKey, H256>)>,
+        new_enum_indices: &HashMap<H256, u64>,
+    ) -> Vec<(H256, MiniblockNumber, Option<StateValue>)> {
+        let mut prev_values = vec![];
+        let mut latest_values = HashMap::<H256, StateValue>::new();
+        for (miniblock_number, logs) in logs_by_miniblock {
+            for (key, value) in logs {
+                let hashed_key = key.hashed_key();
+                let prev_value = latest_values
+                    .get(&hashed_key)
+                    .copied()
+                    .or_else(|| Self::read_state_value(db, hashed_key));
+                if prev_value.is_none() && value.is_zero() {
+                    continue; // The key is not created by a zero write
+                }
+                let enum_index = prev_value
+                    .and_then(|prev_value| prev_value.enum_index)
+                    .or_else(|| new_enum_indices.get(&hashed_key).copied());
+                prev_values.push((hashed_key, miniblock_number, prev_value));
+                latest_values.insert(hashed_key, StateValue::new(value, enum_index));
+            }
+        }
+        prev_values
+    }
+
+    pub(super) fn write_archive_patch(
+        batch: &mut WriteBatch<'_, StateKeeperColumnFamily>,
+        patch: ArchivePatch,
+    ) {
+        let cf = StateKeeperColumnFamily::StateHistory;
+        for (hashed_key, miniblock_number, prev_value) in patch.prev_values {
+            batch.put_cf(
+                cf,
+                &serialize_history_key(hashed_key, miniblock_number),
+                &serialize_prev_value(prev_value),
+            );
+        }
+        if let Some(miniblocks) = &patch.miniblocks {
+            batch.put_cf(
+                StateKeeperColumnFamily::State,
+                Self::ARCHIVE_MINIBLOCKS_KEY,
+                &serialize_miniblock_range(miniblocks),
+            );
+        }
+    }
+
+    /// Removes archived changes for the specified hashed keys after `last_miniblock_to_keep`.
+    pub(super) fn rollback_archive(
+        db: &RocksDB<StateKeeperColumnFamily>,
+        batch: &mut WriteBatch<'_, StateKeeperColumnFamily>,
+        hashed_keys: impl Iterator<Item = H256>,
+        last_miniblock_to_keep: MiniblockNumber,
+    ) {
+        let Some(miniblocks) = read_miniblock_range(db) else {
+            return;
+        };
+        if last_miniblock_to_keep < *miniblocks.start() {
+            // None of the archived state can be recovered after the rollback.
+            batch.delete_cf(StateKeeperColumnFamily::State, Self::ARCHIVE_MINIBLOCKS_KEY);
+        } else if last_miniblock_to_keep < *miniblocks.end() {
+            let new_range = *miniblocks.start()..=last_miniblock_to_keep;
+            batch.put_cf(
+                StateKeeperColumnFamily::State,
+                Self::ARCHIVE_MINIBLOCKS_KEY,
+                &serialize_miniblock_range(&new_range),
+            );
+        }
+
+        let cf = StateKeeperColumnFamily::StateHistory;
+        for hashed_key in hashed_keys {
+            let start = serialize_history_key(hashed_key, last_miniblock_to_keep + 1);
+            let end = serialize_history_key(hashed_key, MiniblockNumber(u32::MAX));
+            batch.delete_range_cf(cf, &start[..]..&end[..]);
+        }
+    }
+}
+
+/// Read-only handle to the state archived by a [`RocksdbStorage`] in the archive mode.
+///
+/// The handle remains valid while the storage is being updated, and reflects the updates.
+#[derive(Debug, Clone)]
+pub struct RocksdbArchive {
+    db: RocksDB<StateKeeperColumnFamily>,
+}
+
+impl RocksdbArchive {
+    pub(super) fn new(db: RocksDB<StateKeeperColumnFamily>) -> Self {
+        Self { db }
+    }
+
+    /// Returns the range of miniblocks for which the state is archived, or `None` if the archive is empty.
+    ///
+    /// This method is blocking.
+    pub fn miniblock_range(&self) -> Option<ops::RangeInclusive<MiniblockNumber>> {
+        read_miniblock_range(&self.db)
+    }
+
+    /// Returns storage reflecting the state after the specified miniblock, or `None` if the state
+    /// for the miniblock is not archived.
+    ///
+    /// This method is blocking.
+    pub fn storage_at(
+        &self,
+        miniblock_number: MiniblockNumber,
+    ) -> Option<HistoricalRocksdbStorage> {
+        let miniblocks = self.miniblock_range()?;
+        miniblocks
+            .contains(&miniblock_number)
+            .then(|| HistoricalRocksdbStorage {
+                db: self.db.clone(),
+                miniblock_number,
+            })
+    }
+}
+
+/// [`ReadStorage`] implementation returning the state after a certain miniblock archived in RocksDB.
+/// Created using [`RocksdbArchive::storage_at()`].
+#[derive(Debug, Clone)]
+pub struct HistoricalRocksdbStorage {
+    db: RocksDB<StateKeeperColumnFamily>,
+    miniblock_number: MiniblockNumber,
+}
+
+impl HistoricalRocksdbStorage {
+    /// Returns the miniblock that this storage corresponds to.
+    pub fn miniblock_number(&self) -> MiniblockNumber {
+        self.miniblock_number
+    }
+
+    fn read_state_value(&self, key: &StorageKey) -> Option<StateValue> {
+        read_archived_state_value(&self.db, key.hashed_key(), self.miniblock_number)
+    }
+}
+
+impl ReadStorage for HistoricalRocksdbStorage {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        self.read_state_value(key)
+            .map_or_else(H256::zero, |state_value| state_value.value)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.read_state_value(key).is_none()
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        // Factory deps are content-addressed, so returning deps added after the miniblock is fine:
+        // they cannot be referenced by the state at the miniblock.
+        let cf = StateKeeperColumnFamily::FactoryDeps;
+        self.db
+            .get_cf(cf, hash.as_bytes())
+            .expect("failed to read RocksDB state value")
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.read_state_value(key)
+            .and_then(|state_value| state_value.enum_index)
+    }
+}
@@ -2,11 +2,12 @@
 //!
 //! ## Storage layout
 //!
-//! This database has 3 column families:
+//! This database has 4 column families:
 //!
 //! - State
 //! - Contracts
 //! - Factory dependencies
+//! - State history (only populated in the archive mode)
 //!
 //! | Column       | Key                             | Value                           | Description                               |
 //! | ------------ | ------------------------------- | ------------------------------- | ----------------------------------------- |
@@ -14,10 +15,13 @@
 //! | State        | 'enum_index_migration_cursor'   | serialized hashed key or empty  | If key is not present it means that the migration hasn't started.                   |
 //! |              |                                 | bytes                           | If value is of length 32 then it represents hashed_key migration should start from. |
 //! |              |                                 |                                 | If value is empty then it means the migration has finished                          |
+//! | State        | 'archive_miniblocks'            | 2 serialized miniblock numbers  | Range of miniblocks with archived state   |
 //! | State        | hashed `StorageKey`             | 32 bytes value ++ 8 bytes index | State value for the given key             |
 //! |              |                                 |                    (big-endian) |                                           |
 //! | Contracts    | address (20 bytes)              | `Vec<u8>`                       | Contract contents                         |
 //! | Factory deps | hash (32 bytes)                 | `Vec<u8>`                       | Bytecodes for new contracts that a certain contract may deploy. |
+//! | History      | hashed `StorageKey` ++          | state value before the change   | Value of the key before it was changed in the miniblock, |
+//! |              | miniblock number (big-endian)   | or empty bytes                  | or empty bytes if the key didn't exist    |
 
 use std::{
     collections::HashMap,
@@ -35,11 +39,13 @@ use zksync_storage::{db::NamedColumnFamily, RocksDB};
 use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256, U256};
 use zksync_utils::{h256_to_u256, u256_to_h256};
 
-use self::metrics::METRICS;
+pub use self::archive::{HistoricalRocksdbStorage, RocksdbArchive};
 #[cfg(test)]
 use self::tests::RocksdbStorageEventListener;
+use self::{archive::ArchivePatch, metrics::METRICS};
 use crate::{InMemoryStorage, ReadStorage};
 
+mod archive;
 mod metrics;
 mod recovery;
 #[cfg(test)]
@@ -59,17 +65,24 @@ enum StateKeeperColumnFamily {
     State,
     Contracts,
     FactoryDeps,
+    StateHistory,
 }
 
 impl NamedColumnFamily for StateKeeperColumnFamily {
     const DB_NAME: &'static str = "state_keeper";
-    const ALL: &'static [Self] = &[Self::State, Self::Contracts, Self::FactoryDeps];
+    const ALL: &'static [Self] = &[
+        Self::State,
+        Self::Contracts,
+        Self::FactoryDeps,
+        Self::StateHistory,
+    ];
 
     fn name(&self) -> &'static str {
         match self {
             Self::State => "state",
             Self::Contracts => "contracts",
             Self::FactoryDeps => "factory_deps",
+            Self::StateHistory => "state_history",
         }
     }
 }
@@ -128,6 +141,8 @@ pub struct RocksdbStorage {
     db: RocksDB<StateKeeperColumnFamily>,
     pending_patch: InMemoryStorage,
     enum_index_migration_chunk_size: usize,
+    archive_mode: bool,
+    pending_archive_patch: ArchivePatch,
     /// Test-only listeners to events produced by the storage.
     #[cfg(test)]
     listener: RocksdbStorageEventListener,
@@ -144,6 +159,13 @@ impl RocksbStorageBuilder {
         self.0.enum_index_migration_chunk_size = chunk_size;
     }
 
+    /// Enables the archive mode, in which the storage retains state changes for each miniblock
+    /// processed after the mode is enabled. Returns a handle to access the archived state.
+    pub fn enable_archive_mode(&mut self) -> RocksdbArchive {
+        self.0.archive_mode = true;
+        RocksdbArchive::new(self.0.db.clone())
+    }
+
     /// Returns the last processed l1 batch number + 1.
     ///
     /// # Panics
@@ -182,7 +204,7 @@ impl RocksbStorageBuilder {
     ///
     /// Propagates RocksDB and Postgres errors.
     pub async fn rollback(
-        mut self,
+        &mut self,
         storage: &mut StorageProcessor<'_>,
         last_l1_batch_to_keep: L1BatchNumber,
     ) -> anyhow::Result<()> {
@@ -193,6 +215,7 @@ impl RocksbStorageBuilder {
 impl RocksdbStorage {
     const L1_BATCH_NUMBER_KEY: &'static [u8] = b"block_number";
     const ENUM_INDEX_MIGRATION_CURSOR: &'static [u8] = b"enum_index_migration_cursor";
+    const ARCHIVE_MINIBLOCKS_KEY: &'static [u8] = b"archive_miniblocks";
 
     /// Desired size of log chunks loaded from Postgres during snapshot recovery.
     /// This is intentionally not configurable because chunks must be the same for the entire recovery
@@ -200,7 +223,9 @@ impl RocksdbStorage {
     const DESIRED_LOG_CHUNK_SIZE: u64 = 200_000;
 
     fn is_special_key(key: &[u8]) -> bool {
-        key == Self::L1_BATCH_NUMBER_KEY || key == Self::ENUM_INDEX_MIGRATION_CURSOR
+        key == Self::L1_BATCH_NUMBER_KEY
+            || key == Self::ENUM_INDEX_MIGRATION_CURSOR
+            || key == Self::ARCHIVE_MINIBLOCKS_KEY
     }
 
     /// Creates a new storage builder with the provided RocksDB `path`.
@@ -214,12 +239,19 @@ impl RocksdbStorage {
             .map(RocksbStorageBuilder)
     }
 
+    /// Converts this storage back into a builder, e.g. to synchronize it with Postgres again.
+    pub fn into_builder(self) -> RocksbStorageBuilder {
+        RocksbStorageBuilder(self)
+    }
+
     async fn new(path: PathBuf) -> anyhow::Result<Self> {
         tokio::task::spawn_blocking(move || {
             Ok(Self {
                 db: RocksDB::new(&path).context("failed initializing state keeper RocksDB")?,
                 pending_patch: InMemoryStorage::default(),
                 enum_index_migration_chunk_size: 100,
+                archive_mode: false,
+                pending_archive_patch: ArchivePatch::default(),
                 #[cfg(test)]
                 listener: RocksdbStorageEventListener::default(),
             })
@@ -265,14 +297,21 @@ impl RocksdbStorage {
             METRICS.lag.set(current_lag.into());
 
             tracing::debug!("Loading state changes for l1 batch {current_l1_batch_number}");
-            let storage_logs = storage
-                .storage_logs_dal()
-                .get_touched_slots_for_l1_batch(current_l1_batch_number)
-                .await
-                .with_context(|| {
-                    format!("failed loading touched slots for L1 batch {current_l1_batch_number}")
-                })?;
-            self.apply_storage_logs(storage_logs, storage).await?;
+            if self.archive_mode {
+                self.apply_archived_storage_logs(storage, current_l1_batch_number)
+                    .await?;
+            } else {
+                let storage_logs = storage
+                    .storage_logs_dal()
+                    .get_touched_slots_for_l1_batch(current_l1_batch_number)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "failed loading touched slots for L1 batch {current_l1_batch_number}"
+                        )
+                    })?;
+                self.apply_storage_logs(storage_logs, storage).await?;
+            }
 
             tracing::debug!("Loading factory deps for L1 batch {current_l1_batch_number}");
             let factory_deps = storage
@@ -528,6 +567,8 @@ impl RocksdbStorage {
         let db = self.db.clone();
         tokio::task::spawn_blocking(move || {
             let mut batch = db.new_write_batch();
+            let hashed_keys = logs.iter().map(|(key, _)| *key);
+            Self::rollback_archive(&db, &mut batch, hashed_keys, last_miniblock_to_keep);
 
             let cf = StateKeeperColumnFamily::State;
             for (key, maybe_value) in logs {
@@ -562,6 +603,8 @@ impl RocksdbStorage {
     /// Saves the pending changes to RocksDB. Must be executed on a Tokio thread.
     async fn save(&mut self, l1_batch_number: Option<L1BatchNumber>) -> anyhow::Result<()> {
         let pending_patch = mem::take(&mut self.pending_patch);
+        let archive_patch = mem::take(&mut self.pending_archive_patch);
+        let archive_mode = self.archive_mode;
 
         let db = self.db.clone();
         let save_task = tokio::task::spawn_blocking(move || {
@@ -573,6 +616,13 @@ impl RocksdbStorage {
                     Self::L1_BATCH_NUMBER_KEY,
                     &serialize_l1_batch_number(l1_batch_number.0),
                 );
+                if !archive_mode {
+                    // The archived state would have a gap if it were retained.
+                    batch.delete_cf(cf, Self::ARCHIVE_MINIBLOCKS_KEY);
+                }
+            }
+            if archive_mode {
+                Self::write_archive_patch(&mut batch, archive_patch);
             }
             for (key, (value, enum_index)) in pending_patch.state {
                 batch.put_cf(
@@ -240,6 +240,150 @@ async fn rocksdb_storage_revert() {
     }
 }
 
+#[tokio::test]
+async fn rocksdb_storage_archive() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut conn = pool.access_storage().await.unwrap();
+    prepare_postgres(&mut conn).await;
+    let genesis_logs = gen_storage_logs(0..20);
+    let storage_logs = gen_storage_logs(20..40);
+    create_miniblock(&mut conn, MiniblockNumber(1), storage_logs.clone()).await;
+    let inserted_storage_logs = gen_storage_logs(40..50);
+    let replaced_storage_logs: Vec<_> = storage_logs
+        .iter()
+        .chain(&genesis_logs)
+        .step_by(3)
+        .map(|&log| StorageLog {
+            value: H256::repeat_byte(0xf0),
+            ..log
+        })
+        .collect();
+    let mut new_storage_logs = inserted_storage_logs.clone();
+    new_storage_logs.extend_from_slice(&replaced_storage_logs);
+    create_miniblock(&mut conn, MiniblockNumber(2), new_storage_logs).await;
+    let mut initial_writes = storage_logs.clone();
+    initial_writes.extend_from_slice(&inserted_storage_logs);
+    create_l1_batch(&mut conn, L1BatchNumber(1), &initial_writes).await;
+
+    let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let mut builder = RocksdbStorage::builder(dir.path()).await.unwrap();
+    let archive = builder.enable_archive_mode();
+    assert_eq!(archive.miniblock_range(), None);
+    let mut storage = builder
+        .synchronize(&mut conn, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("Storage synchronization unexpectedly stopped");
+    assert_eq!(
+        archive.miniblock_range(),
+        Some(MiniblockNumber(0)..=MiniblockNumber(2))
+    );
+    assert!(archive.storage_at(MiniblockNumber(3)).is_none());
+
+    let mut genesis_storage = archive.storage_at(MiniblockNumber(0)).unwrap();
+    for log in &genesis_logs {
+        assert_eq!(genesis_storage.read_value(&log.key), log.value);
+        assert!(!genesis_storage.is_write_initial(&log.key));
+    }
+    for log in storage_logs.iter().chain(&inserted_storage_logs) {
+        assert_eq!(genesis_storage.read_value(&log.key), H256::zero());
+        assert!(genesis_storage.is_write_initial(&log.key));
+    }
+
+    let mut first_storage = archive.storage_at(MiniblockNumber(1)).unwrap();
+    for log in genesis_logs.iter().chain(&storage_logs) {
+        assert_eq!(first_storage.read_value(&log.key), log.value);
+        assert!(!first_storage.is_write_initial(&log.key));
+    }
+    for log in &inserted_storage_logs {
+        assert_eq!(first_storage.read_value(&log.key), H256::zero());
+        assert!(first_storage.is_write_initial(&log.key));
+    }
+
+    let mut last_storage = archive.storage_at(MiniblockNumber(2)).unwrap();
+    for log in genesis_logs.iter().chain(&storage_logs) {
+        assert_eq!(
+            last_storage.read_value(&log.key),
+            storage.read_value(&log.key)
+        );
+        assert_eq!(
+            last_storage.get_enumeration_index(&log.key),
+            storage.get_enumeration_index(&log.key)
+        );
+    }
+    for log in inserted_storage_logs.iter().chain(&replaced_storage_logs) {
+        assert_eq!(last_storage.read_value(&log.key), log.value);
+    }
+
+    storage.rollback(&mut conn, L1BatchNumber(0)).await.unwrap();
+    assert_eq!(
+        archive.miniblock_range(),
+        Some(MiniblockNumber(0)..=MiniblockNumber(0))
+    );
+    assert!(archive.storage_at(MiniblockNumber(1)).is_none());
+    for log in &genesis_logs {
+        assert_eq!(genesis_storage.read_value(&log.key), log.value);
+    }
+    for log in &storage_logs {
+        assert!(genesis_storage.is_write_initial(&log.key));
+    }
+}
+
+#[tokio::test]
+async fn enabling_archive_mode_for_existing_storage() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut conn = pool.access_storage().await.unwrap();
+    prepare_postgres(&mut conn).await;
+    let storage_logs = gen_storage_logs(20..40);
+    create_miniblock(&mut conn, MiniblockNumber(1), storage_logs.clone()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(1), &storage_logs).await;
+
+    let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
+    let storage = sync_test_storage(&dir, &mut conn).await;
+    drop(storage);
+
+    let replaced_storage_logs: Vec<_> = storage_logs
+        .iter()
+        .map(|&log| StorageLog {
+            value: H256::repeat_byte(0xf0),
+            ..log
+        })
+        .collect();
+    create_miniblock(&mut conn, MiniblockNumber(2), replaced_storage_logs.clone()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(2), &[]).await;
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let mut builder = RocksdbStorage::builder(dir.path()).await.unwrap();
+    let archive = builder.enable_archive_mode();
+    let storage = builder
+        .synchronize(&mut conn, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("Storage synchronization unexpectedly stopped");
+    assert_eq!(
+        archive.miniblock_range(),
+        Some(MiniblockNumber(1)..=MiniblockNumber(2))
+    );
+    let mut historical_storage = archive.storage_at(MiniblockNumber(1)).unwrap();
+    for log in &storage_logs {
+        assert_eq!(historical_storage.read_value(&log.key), log.value);
+    }
+    assert!(archive.storage_at(MiniblockNumber(0)).is_none());
+
+    // Syncing the storage in the normal mode invalidates the archive.
+    create_miniblock(&mut conn, MiniblockNumber(3), vec![]).await;
+    create_l1_batch(&mut conn, L1BatchNumber(3), &[]).await;
+    let mut builder = storage.into_builder();
+    builder.0.archive_mode = false;
+    builder
+        .synchronize(&mut conn, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("Storage synchronization unexpectedly stopped");
+    assert_eq!(archive.miniblock_range(), None);
+}
+
 #[tokio::test]
 async fn rocksdb_enum_index_migration() {
     let pool = ConnectionPool::test_pool().await;
@@ -133,6 +133,12 @@ impl<S: ReadStorage + fmt::Debug> StorageView<S> {
     pub fn to_rc_ptr(self) -> Rc<RefCell<Self>> {
         Rc::new(RefCell::new(self))
     }
+
+    /// Discards all values written via this view, while retaining cached reads. This allows executing
+    /// several independent transactions on the same state without re-reading storage.
+    pub fn discard_modifications(&mut self) {
+        self.modified_storage_keys.clear();
+    }
 }
 
 impl<S: ReadStorage + fmt::Debug> ReadStorage for StorageView<S> {
@@ -251,4 +257,21 @@ mod test {
         assert_eq!(metrics.get_value_storage_invocations, 3);
         assert_eq!(metrics.set_value_storage_invocations, 2);
     }
+
+    #[test]
+    fn discarding_modifications() {
+        let account: AccountTreeId = AccountTreeId::new(Address::from([0xfe; 20]));
+        let key = StorageKey::new(account, H256::from_low_u64_be(61));
+        let value = H256::from_low_u64_be(73);
+        let mut raw_storage = InMemoryStorage::default();
+        raw_storage.set_value(key, value);
+        let mut storage_view = StorageView::new(&raw_storage);
+
+        storage_view.set_value(key, H256::from_low_u64_be(74));
+        storage_view.discard_modifications();
+        assert!(storage_view.modified_storage_keys().is_empty());
+        assert_eq!(storage_view.read_value(&key), value);
+        // The original value was cached when it was overwritten.
+        assert_eq!(storage_view.metrics().storage_invocations_missed, 1);
+    }
 }
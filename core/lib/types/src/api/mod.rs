@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::{DateTime, Utc};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use strum::Display;
@@ -14,10 +16,11 @@ use crate::{
     protocol_version::L1VerifierConfig,
     vm_trace::{Call, CallType},
     web3::types::{AccessList, Index, H2048},
-    Address, MiniblockNumber, ProtocolVersionId, VmEvent,
+    AccountTreeId, Address, MiniblockNumber, ProtocolVersionId, StorageKey, VmEvent,
 };
 
 pub mod en;
+pub mod proof;
 
 /// Block Number
 #[derive(Copy, Clone, Debug, PartialEq, Display)]
@@ -577,6 +580,196 @@ pub struct TransactionDetails {
     pub eth_execute_tx_hash: Option<H256>,
 }
 
+/// Position of a transaction in the chain. Used as a cursor when paginating transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionCursor {
+    pub block_number: MiniblockNumber,
+    pub index_in_block: u32,
+}
+
+/// Position of a log in the chain. Used as a cursor when paginating logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogCursor {
+    pub block_number: MiniblockNumber,
+    /// Index of the log in the block.
+    pub log_index: u32,
+}
+
+/// Pending transactions grouped by their initiator and keyed by nonce.
+pub type TxpoolTransactions<T> = BTreeMap<Address, BTreeMap<u64, T>>;
+
+/// Contents of the transaction pool as returned by `txpool_content` (with `T = Transaction`)
+/// and `txpool_inspect` (with `T = String`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolContent<T> {
+    /// Transactions that can be executed, i.e. ones with nonces following the current account nonce without gaps.
+    pub pending: TxpoolTransactions<T>,
+    /// Transactions that cannot be executed yet because of a nonce gap.
+    pub queued: TxpoolTransactions<T>,
+}
+
+impl<T> Default for TxpoolContent<T> {
+    fn default() -> Self {
+        Self {
+            pending: BTreeMap::new(),
+            queued: BTreeMap::new(),
+        }
+    }
+}
+
+/// Pending transactions of a single account as returned by `txpool_contentFrom`, keyed by nonce.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolAccountContent<T> {
+    /// Transactions that can be executed, i.e. ones with nonces following the current account nonce without gaps.
+    pub pending: BTreeMap<u64, T>,
+    /// Transactions that cannot be executed yet because of a nonce gap.
+    pub queued: BTreeMap<u64, T>,
+}
+
+impl<T> Default for TxpoolAccountContent<T> {
+    fn default() -> Self {
+        Self {
+            pending: BTreeMap::new(),
+            queued: BTreeMap::new(),
+        }
+    }
+}
+
+/// Number of transactions in the transaction pool as returned by `txpool_status`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolStatus {
+    pub pending: U64,
+    pub queued: U64,
+}
+
+/// Expected state of an account in [`TransactionConditions`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KnownAccountState {
+    /// Storage root of the account. Not supported since accounts do not have individual storage roots in zkSync.
+    StorageRoot(H256),
+    /// Values of storage slots of the account.
+    Slots(BTreeMap<H256, H256>),
+}
+
+/// Conditions of a transaction submitted via `eth_sendRawTransactionConditional`. The transaction
+/// is rejected if the conditions do not hold both at submission and immediately before its execution.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionConditions {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub known_accounts: BTreeMap<Address, KnownAccountState>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number_min: Option<U64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number_max: Option<U64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_min: Option<U64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_max: Option<U64>,
+}
+
+/// Reason why [`TransactionConditions`] do not hold.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum TransactionConditionsError {
+    #[error("block number {0} is out of the allowed range")]
+    BlockNumberOutOfRange(MiniblockNumber),
+    #[error("block timestamp {0} is out of the allowed range")]
+    TimestampOutOfRange(u64),
+    #[error("storage slot {slot:?} of account {address:?} has unexpected value {actual:?}")]
+    StorageSlotMismatch {
+        address: Address,
+        slot: H256,
+        actual: H256,
+    },
+}
+
+impl TransactionConditions {
+    /// Returns the number of storage slots checked by the conditions. Each storage root is counted as a single slot.
+    pub fn slot_count(&self) -> usize {
+        self.known_accounts
+            .values()
+            .map(|state| match state {
+                KnownAccountState::StorageRoot(_) => 1,
+                KnownAccountState::Slots(slots) => slots.len(),
+            })
+            .sum()
+    }
+
+    /// Checks whether the conditions contain a storage root check.
+    pub fn has_storage_roots(&self) -> bool {
+        self.known_accounts
+            .values()
+            .any(|state| matches!(state, KnownAccountState::StorageRoot(_)))
+    }
+
+    /// Returns storage slots checked by the conditions together with their expected values.
+    /// Storage root conditions are ignored.
+    pub fn storage_slots(&self) -> impl Iterator<Item = (StorageKey, H256)> + '_ {
+        self.known_accounts
+            .iter()
+            .filter_map(|(address, state)| match state {
+                KnownAccountState::StorageRoot(_) => None,
+                KnownAccountState::Slots(slots) => Some((address, slots)),
+            })
+            .flat_map(|(address, slots)| {
+                let account = AccountTreeId::new(*address);
+                slots
+                    .iter()
+                    .map(move |(slot, value)| (StorageKey::new(account, *slot), *value))
+            })
+    }
+
+    /// Checks block number and timestamp conditions for a block with the specified params.
+    pub fn check_block(
+        &self,
+        number: MiniblockNumber,
+        timestamp: u64,
+    ) -> Result<(), TransactionConditionsError> {
+        let block_number = U64::from(number.0);
+        if self
+            .block_number_min
+            .map_or(false, |min| block_number < min)
+            || self
+                .block_number_max
+                .map_or(false, |max| block_number > max)
+        {
+            return Err(TransactionConditionsError::BlockNumberOutOfRange(number));
+        }
+        let block_timestamp = U64::from(timestamp);
+        if self
+            .timestamp_min
+            .map_or(false, |min| block_timestamp < min)
+            || self
+                .timestamp_max
+                .map_or(false, |max| block_timestamp > max)
+        {
+            return Err(TransactionConditionsError::TimestampOutOfRange(timestamp));
+        }
+        Ok(())
+    }
+
+    /// Checks storage slot conditions against the provided storage values. Missing values are treated as zeros.
+    pub fn check_storage(
+        &self,
+        values: &HashMap<StorageKey, H256>,
+    ) -> Result<(), TransactionConditionsError> {
+        for (key, expected) in self.storage_slots() {
+            let actual = values.get(&key).copied().unwrap_or_default();
+            if actual != expected {
+                return Err(TransactionConditionsError::StorageSlotMismatch {
+                    address: *key.address(),
+                    slot: *key.key(),
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GetLogsFilter {
     pub from_block: MiniblockNumber,
@@ -593,6 +786,75 @@ pub struct ResultDebugCall {
     pub result: DebugCall,
 }
 
+/// Response of `eth_createAccessList`.
+///
+/// zkSync doesn't support transactions with non-empty access lists, so the access list is informational
+/// and should not be included into submitted transactions.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListWithGasUsed {
+    /// Contracts and storage slots accessed by the call, sorted by address and storage key.
+    pub access_list: AccessList,
+    /// Gas estimate for the call, same as returned by `eth_estimateGas`.
+    pub gas_used: U256,
+}
+
+/// Result of a single call executed via `zks_batchCall`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchCallResult {
+    /// Whether the call has succeeded.
+    pub success: bool,
+    /// Returned data for successful calls, or revert data (possibly empty) for failed ones.
+    pub output: Bytes,
+    /// Human-readable error for failed calls (e.g., a decoded revert reason).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Override of the account state applied before simulating transactions via `zks_simulateBundle`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+    /// Base token balance of the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// Bytecode deployed at the account; must be a valid zkEVM bytecode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Overridden storage slots of the account. Other slots retain their values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<HashMap<H256, H256>>,
+}
+
+/// State overrides keyed by the account address.
+pub type StateOverride = HashMap<Address, AccountOverride>;
+
+/// Result of a single transaction simulated via `zks_simulateBundle`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleTxResult {
+    /// Whether the transaction has succeeded.
+    pub success: bool,
+    /// Returned data for successful transactions, or revert data (possibly empty) for failed ones.
+    pub output: Bytes,
+    /// Human-readable error for failed transactions (e.g., a decoded revert reason).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub gas_used: U256,
+    /// Events emitted by the transaction. Block-related fields are not set.
+    pub logs: Vec<Log>,
+    /// Call trace of the transaction.
+    pub trace: DebugCall,
+}
+
+/// Trace of a transaction in a block as returned by `debug_traceBlockBy*` methods.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultTransactionTrace {
+    pub result: TransactionTrace,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum DebugCallType {
     Call,
@@ -641,6 +903,27 @@ impl From<Call> for DebugCall {
     }
 }
 
+/// Account state before executing a transaction as returned by `prestateTracer`. Only the state accessed
+/// by the transaction is included.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrestateAccount {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+/// Trace of a single transaction; the variant depends on the requested tracer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionTrace {
+    Call(DebugCall),
+    Prestate(BTreeMap<Address, PrestateAccount>),
+}
+
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
 pub struct ProtocolVersion {
     /// Protocol version ID
@@ -655,10 +938,20 @@ pub struct ProtocolVersion {
     pub l2_system_upgrade_tx_hash: Option<H256>,
 }
 
+/// Protocol version together with the L1 batch it was activated in.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ProtocolVersionDetails {
+    #[serde(flatten)]
+    pub version: ProtocolVersion,
+    /// First L1 batch executed with this protocol version; `None` if the version isn't activated yet
+    pub activation_l1_batch: Option<L1BatchNumber>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum SupportedTracers {
     CallTracer,
+    PrestateTracer,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -712,6 +1005,72 @@ pub struct BlockDetails {
     pub protocol_version: Option<ProtocolVersionId>,
 }
 
+/// Finality stage of a miniblock or an L1 batch determined by the L1 transactions for the batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BlockFinalityStatus {
+    /// The miniblock / L1 batch is sealed, but the batch is not committed on L1 (or the batch is not sealed yet).
+    Sealed,
+    /// The L1 batch is committed on L1.
+    Committed,
+    /// The L1 batch proof is verified on L1.
+    Proven,
+    /// The L1 batch is executed on L1. This is the final stage.
+    Executed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockFinality {
+    pub number: MiniblockNumber,
+    /// L1 batch containing the miniblock; `None` if the batch is not sealed yet.
+    pub l1_batch_number: Option<L1BatchNumber>,
+    pub status: BlockFinalityStatus,
+    pub commit_tx_hash: Option<H256>,
+    pub committed_at: Option<DateTime<Utc>>,
+    pub prove_tx_hash: Option<H256>,
+    pub proven_at: Option<DateTime<Utc>>,
+    pub execute_tx_hash: Option<H256>,
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchFinality {
+    pub number: L1BatchNumber,
+    pub status: BlockFinalityStatus,
+    pub commit_tx_hash: Option<H256>,
+    pub committed_at: Option<DateTime<Utc>>,
+    pub prove_tx_hash: Option<H256>,
+    pub proven_at: Option<DateTime<Utc>>,
+    pub execute_tx_hash: Option<H256>,
+    pub executed_at: Option<DateTime<Utc>>,
+}
+
+/// Sync progress returned by `eth_syncing`. Unlike the standard Web3 response, which is `false` for synced nodes,
+/// progress is always returned; the standard fields (`startingBlock`, `currentBlock` and `highestBlock`) are retained.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncInfo {
+    /// First miniblock available on the node. Non-zero if the node was recovered from a snapshot.
+    pub starting_block: U64,
+    /// Latest miniblock sealed by the node.
+    pub current_block: U64,
+    /// Latest miniblock known to the node. For external nodes, this is the latest miniblock on the main node;
+    /// for the main node, this is the same as `current_block`.
+    pub highest_block: U64,
+    /// Whether the node is caught up with the main node. Always `true` for the main node.
+    pub is_synced: bool,
+    /// Latest L1 batch sealed by the node.
+    pub current_l1_batch: Option<L1BatchNumber>,
+    /// Latest L1 batch committed on L1.
+    pub committed_l1_batch: Option<L1BatchNumber>,
+    /// Latest L1 batch with the proof verified on L1.
+    pub proven_l1_batch: Option<L1BatchNumber>,
+    /// Latest L1 batch executed on L1.
+    pub executed_l1_batch: Option<L1BatchNumber>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct L1BatchDetails {
@@ -720,6 +1079,16 @@ pub struct L1BatchDetails {
     pub base: BlockDetailsBase,
 }
 
+/// Page of items returned by a method with cursor-based (aka keyset) pagination. Item ordering
+/// and the cursor type are specific to the method returning the page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    /// Cursor to pass to get the next page. `None` if there are no more items.
+    pub next_cursor: Option<C>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StorageProof {
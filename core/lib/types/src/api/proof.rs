@@ -0,0 +1,322 @@
+//! Merkle proofs returned by `eth_getProof` and their verification.
+//!
+//! # Proof format
+//!
+//! zkSync state is stored in a single sparse Merkle tree of depth 256 instead of per-account tries.
+//! Each storage slot `(address, key)` is mapped to a tree key `blake2s(address_padded_to_32_bytes ++ key)`
+//! interpreted as a little-endian 256-bit integer. Bit `i` of the tree key (counting from the least significant bit)
+//! determines whether the slot is in the left (0) or right (1) subtree at depth `i` counted from the leaves.
+//!
+//! - A leaf is hashed as `blake2s(leaf_index as u64 in big-endian ++ value)`, where `leaf_index` is
+//!   the 1-based index assigned to the slot when it was first written. Missing slots have zero index and value.
+//! - An internal node is hashed as `blake2s(left_hash ++ right_hash)`.
+//! - Empty subtrees have fixed hashes: the empty subtree of depth 0 is `blake2s([0; 40])`, and
+//!   the empty subtree of depth `d + 1` is the hash of two empty subtrees of depth `d`.
+//!
+//! The Merkle path in a [`StorageProof`] lists sibling hashes in the root-to-leaf order. Siblings
+//! adjacent to the leaf that are empty subtrees may be omitted; the verifier restores them,
+//! so the path may contain fewer than 256 hashes.
+//!
+//! Account data is not stored in a dedicated account trie; instead, it lives in slots of system contracts.
+//! An [`AccountProof`] thus includes proofs for the balance, nonce and bytecode hash slots
+//! in addition to the requested storage slots of the account.
+
+use blake2::{Blake2s256, Digest};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{web3::types::U256, L1BatchNumber, H256};
+use zksync_utils::h256_to_u256;
+
+use super::StorageProof;
+use crate::{
+    get_code_key, get_nonce_key,
+    utils::{decompose_full_nonce, storage_key_for_eth_balance},
+    AccountTreeId, Address, StorageKey,
+};
+
+/// Depth of the zkSync Merkle tree.
+const TREE_DEPTH: usize = 256;
+
+fn hash_bytes(bytes: &[u8]) -> H256 {
+    H256(Blake2s256::digest(bytes).into())
+}
+
+fn hash_leaf(value: &H256, leaf_index: u64) -> H256 {
+    let mut bytes = [0_u8; 40];
+    bytes[..8].copy_from_slice(&leaf_index.to_be_bytes());
+    bytes[8..].copy_from_slice(value.as_bytes());
+    hash_bytes(&bytes)
+}
+
+fn hash_branch(lhs: &H256, rhs: &H256) -> H256 {
+    let mut bytes = [0_u8; 64];
+    bytes[..32].copy_from_slice(lhs.as_bytes());
+    bytes[32..].copy_from_slice(rhs.as_bytes());
+    hash_bytes(&bytes)
+}
+
+fn empty_subtree_hash(depth: usize) -> H256 {
+    static EMPTY_SUBTREE_HASHES: Lazy<Vec<H256>> = Lazy::new(|| {
+        let empty_leaf_hash = hash_bytes(&[0_u8; 40]);
+        std::iter::successors(Some(empty_leaf_hash), |hash| Some(hash_branch(hash, hash)))
+            .take(TREE_DEPTH + 1)
+            .collect()
+    });
+    EMPTY_SUBTREE_HASHES[depth]
+}
+
+/// Errors that can occur when verifying a Merkle proof.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ProofError {
+    #[error("Merkle path for key {key:?} has {len} hashes, while the tree depth is {TREE_DEPTH}")]
+    PathTooLong { key: H256, len: usize },
+    #[error(
+        "root hash {computed:?} computed for key {key:?} differs from the expected {expected:?}"
+    )]
+    RootHashMismatch {
+        key: H256,
+        computed: H256,
+        expected: H256,
+    },
+    #[error("account proof is malformed: {0}")]
+    MalformedAccountProof(&'static str),
+    #[error("account {0} does not match the proven value")]
+    AccountFieldMismatch(&'static str),
+}
+
+impl StorageProof {
+    /// Computes the root hash of the Merkle tree implied by this proof for a slot of the contract
+    /// with the specified `address`. See the [module docs](self) for the details on the proof format.
+    pub fn root_hash(&self, address: Address) -> Result<H256, ProofError> {
+        let path_len = self.proof.len();
+        if path_len > TREE_DEPTH {
+            return Err(ProofError::PathTooLong {
+                key: self.key,
+                len: path_len,
+            });
+        }
+
+        let tree_key = StorageKey::new(AccountTreeId::new(address), self.key).hashed_key_u256();
+        let empty_hashes = (0..TREE_DEPTH - path_len).map(empty_subtree_hash);
+        let leaf_to_root_path = empty_hashes.chain(self.proof.iter().rev().copied());
+
+        let mut hash = hash_leaf(&self.value, self.index);
+        for (depth, adjacent_hash) in leaf_to_root_path.enumerate() {
+            hash = if tree_key.bit(depth) {
+                hash_branch(&adjacent_hash, &hash)
+            } else {
+                hash_branch(&hash, &adjacent_hash)
+            };
+        }
+        Ok(hash)
+    }
+
+    /// Verifies this proof for a slot of the contract with the specified `address` against the tree `root_hash`.
+    pub fn verify(&self, address: Address, root_hash: H256) -> Result<(), ProofError> {
+        let computed = self.root_hash(address)?;
+        if computed == root_hash {
+            Ok(())
+        } else {
+            Err(ProofError::RootHashMismatch {
+                key: self.key,
+                computed,
+                expected: root_hash,
+            })
+        }
+    }
+}
+
+/// Account state together with Merkle proofs of its inclusion into the state tree
+/// after the specified L1 batch. Returned by `eth_getProof`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    pub address: Address,
+    /// L1 batch the proof is generated for.
+    pub l1_batch_number: L1BatchNumber,
+    /// Root hash of the Merkle tree after the L1 batch. This hash is committed to L1 as a part of the batch.
+    pub root_hash: H256,
+    /// ETH balance of the account.
+    pub balance: U256,
+    /// Transaction nonce of the account.
+    pub nonce: U256,
+    /// Hash of the account bytecode; zero for accounts without deployed code.
+    pub code_hash: H256,
+    /// Proofs for the balance, nonce and bytecode hash slots (in this order). Unlike storage proofs,
+    /// these slots belong to system contracts rather than to the account itself.
+    pub account_proof: Vec<StorageProof>,
+    /// Proofs for the requested storage slots of the account.
+    pub storage_proof: Vec<StorageProof>,
+}
+
+impl AccountProof {
+    /// Returns storage keys of account slots proven by [`Self::account_proof`], in the order of proofs.
+    pub fn account_keys(address: &Address) -> [StorageKey; 3] {
+        [
+            storage_key_for_eth_balance(address),
+            get_nonce_key(address),
+            get_code_key(address),
+        ]
+    }
+
+    /// Verifies all proofs against [`Self::root_hash`] and checks that account fields match the proven values.
+    /// The caller is responsible for checking that the root hash is committed on L1 for the L1 batch.
+    pub fn verify(&self) -> Result<(), ProofError> {
+        let account_keys = Self::account_keys(&self.address);
+        if self.account_proof.len() != account_keys.len() {
+            return Err(ProofError::MalformedAccountProof(
+                "unexpected number of account slot proofs",
+            ));
+        }
+        for (proof, key) in self.account_proof.iter().zip(&account_keys) {
+            if proof.key != *key.key() {
+                return Err(ProofError::MalformedAccountProof(
+                    "account slot proof has unexpected key",
+                ));
+            }
+            proof.verify(*key.address(), self.root_hash)?;
+        }
+        for proof in &self.storage_proof {
+            proof.verify(self.address, self.root_hash)?;
+        }
+
+        if h256_to_u256(self.account_proof[0].value) != self.balance {
+            return Err(ProofError::AccountFieldMismatch("balance"));
+        }
+        let (nonce, _) = decompose_full_nonce(h256_to_u256(self.account_proof[1].value));
+        if nonce != self.nonce {
+            return Err(ProofError::AccountFieldMismatch("nonce"));
+        }
+        if self.account_proof[2].value != self.code_hash {
+            return Err(ProofError::AccountFieldMismatch("code hash"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Computes the root hash of a tree with a single `(address, key) -> value` entry.
+    fn single_entry_tree(address: Address, key: H256, value: H256) -> (H256, StorageProof) {
+        let proof = StorageProof {
+            key,
+            proof: vec![],
+            value,
+            index: 1,
+        };
+        (proof.root_hash(address).unwrap(), proof)
+    }
+
+    #[test]
+    fn empty_tree_proof() {
+        let proof = StorageProof {
+            key: H256::repeat_byte(1),
+            proof: vec![],
+            value: H256::zero(),
+            index: 0,
+        };
+        let root_hash = proof.root_hash(Address::repeat_byte(1)).unwrap();
+        assert_eq!(root_hash, empty_subtree_hash(TREE_DEPTH));
+
+        // The full path consisting of empty subtree hashes must be equivalent to the trimmed one.
+        let full_path = (0..TREE_DEPTH).rev().map(empty_subtree_hash).collect();
+        let full_proof = StorageProof {
+            proof: full_path,
+            ..proof.clone()
+        };
+        full_proof
+            .verify(Address::repeat_byte(1), root_hash)
+            .unwrap();
+        // The proof is valid for any key.
+        let other_proof = StorageProof {
+            key: H256::repeat_byte(2),
+            ..proof
+        };
+        other_proof
+            .verify(Address::repeat_byte(2), root_hash)
+            .unwrap();
+    }
+
+    #[test]
+    fn tampered_proofs_are_rejected() {
+        let address = Address::repeat_byte(1);
+        let (root_hash, proof) =
+            single_entry_tree(address, H256::repeat_byte(2), H256::repeat_byte(3));
+        proof.verify(address, root_hash).unwrap();
+
+        let err = proof
+            .verify(Address::repeat_byte(2), root_hash)
+            .unwrap_err();
+        assert!(
+            matches!(err, ProofError::RootHashMismatch { .. }),
+            "{err:?}"
+        );
+        let tampered_proof = StorageProof {
+            value: H256::repeat_byte(4),
+            ..proof.clone()
+        };
+        let err = tampered_proof.verify(address, root_hash).unwrap_err();
+        assert!(
+            matches!(err, ProofError::RootHashMismatch { .. }),
+            "{err:?}"
+        );
+        let tampered_proof = StorageProof {
+            index: 2,
+            ..proof.clone()
+        };
+        let err = tampered_proof.verify(address, root_hash).unwrap_err();
+        assert!(
+            matches!(err, ProofError::RootHashMismatch { .. }),
+            "{err:?}"
+        );
+
+        let too_long_proof = StorageProof {
+            proof: vec![H256::zero(); TREE_DEPTH + 1],
+            ..proof
+        };
+        let err = too_long_proof.verify(address, root_hash).unwrap_err();
+        assert!(matches!(err, ProofError::PathTooLong { .. }), "{err:?}");
+    }
+
+    #[test]
+    fn verifying_account_proof_for_empty_tree() {
+        let address = Address::repeat_byte(1);
+        let empty_proof = |key: &StorageKey| StorageProof {
+            key: *key.key(),
+            proof: vec![],
+            value: H256::zero(),
+            index: 0,
+        };
+        let mut proof = AccountProof {
+            address,
+            l1_batch_number: L1BatchNumber(1),
+            root_hash: empty_subtree_hash(TREE_DEPTH),
+            balance: U256::zero(),
+            nonce: U256::zero(),
+            code_hash: H256::zero(),
+            account_proof: AccountProof::account_keys(&address)
+                .iter()
+                .map(empty_proof)
+                .collect(),
+            storage_proof: vec![empty_proof(&StorageKey::new(
+                AccountTreeId::new(address),
+                H256::repeat_byte(2),
+            ))],
+        };
+        proof.verify().unwrap();
+
+        proof.balance = 1.into();
+        let err = proof.verify().unwrap_err();
+        assert_eq!(err, ProofError::AccountFieldMismatch("balance"));
+        proof.balance = 0.into();
+
+        proof.account_proof.swap(0, 1);
+        let err = proof.verify().unwrap_err();
+        assert!(
+            matches!(err, ProofError::MalformedAccountProof(_)),
+            "{err:?}"
+        );
+    }
+}
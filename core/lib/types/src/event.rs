@@ -9,7 +9,8 @@ use crate::{
     ethabi,
     l2_to_l1_log::L2ToL1Log,
     tokens::{TokenInfo, TokenMetadata},
-    Address, L1BatchNumber, CONTRACT_DEPLOYER_ADDRESS, H256, KNOWN_CODES_STORAGE_ADDRESS,
+    web3::signing::keccak256,
+    Address, L1BatchNumber, CONTRACT_DEPLOYER_ADDRESS, H2048, H256, KNOWN_CODES_STORAGE_ADDRESS,
     L1_MESSENGER_ADDRESS, U256,
 };
 
@@ -33,6 +34,51 @@ impl VmEvent {
     }
 }
 
+/// Returns byte indices and masks of the bits set in a logs bloom for the specified `input`
+/// (an event address or topic). Follows the Ethereum spec: the bloom sets 3 bits, each specified
+/// by the lower 11 bits of a pair of bytes in `keccak256(input)`.
+fn bloom_bits(input: &[u8]) -> [(usize, u8); 3] {
+    let hash = keccak256(input);
+    [0, 2, 4].map(|i| {
+        let bit_index = usize::from(u16::from_be_bytes([hash[i], hash[i + 1]]) & 2_047);
+        (H2048::len_bytes() - 1 - bit_index / 8, 1 << (bit_index % 8))
+    })
+}
+
+/// Computes the logs bloom with bits set only for the specified `input` (an event address or topic).
+/// Can be used as a mask to check whether a logs bloom may contain the input.
+pub fn input_bloom(input: &[u8]) -> H2048 {
+    let mut bloom = H2048::zero();
+    for (byte_index, mask) in bloom_bits(input) {
+        bloom.0[byte_index] |= mask;
+    }
+    bloom
+}
+
+/// Computes the Ethereum-compatible logs bloom for the provided events.
+pub fn logs_bloom<'a>(events: impl IntoIterator<Item = &'a VmEvent>) -> H2048 {
+    let mut bloom = H2048::zero();
+    for event in events {
+        let inputs = [event.address.as_bytes()]
+            .into_iter()
+            .chain(event.indexed_topics.iter().map(H256::as_bytes));
+        for input in inputs {
+            for (byte_index, mask) in bloom_bits(input) {
+                bloom.0[byte_index] |= mask;
+            }
+        }
+    }
+    bloom
+}
+
+/// Checks whether the logs `bloom` may contain the specified `input` (an event address or topic).
+/// False positives are possible, but false negatives are not.
+pub fn bloom_may_contain(bloom: &H2048, input: &[u8]) -> bool {
+    bloom_bits(input)
+        .into_iter()
+        .all(|(byte_index, mask)| bloom.0[byte_index] & mask != 0)
+}
+
 pub static DEPLOY_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     ethabi::long_signature(
         "ContractDeployed",
@@ -351,8 +397,8 @@ mod tests {
     use zksync_utils::u256_to_h256;
 
     use super::{
-        extract_bytecode_publication_requests_from_l1_messenger,
-        extract_l2tol1logs_from_l1_messenger, L1MessengerBytecodePublicationRequest,
+        bloom_may_contain, extract_bytecode_publication_requests_from_l1_messenger,
+        extract_l2tol1logs_from_l1_messenger, logs_bloom, L1MessengerBytecodePublicationRequest,
         L1MessengerL2ToL1Log,
     };
     use crate::VmEvent;
@@ -502,4 +548,41 @@ mod tests {
 
         assert_eq!(expected, logs);
     }
+
+    #[test]
+    fn computing_logs_bloom() {
+        let event = VmEvent {
+            address: Address::repeat_byte(1),
+            indexed_topics: vec![u256_to_h256(U256::from(2))],
+            ..VmEvent::default()
+        };
+        let bloom = logs_bloom([&event]);
+        assert!(bloom_may_contain(&bloom, event.address.as_bytes()));
+        assert!(bloom_may_contain(
+            &bloom,
+            event.indexed_topics[0].as_bytes()
+        ));
+        assert!(!bloom_may_contain(
+            &bloom,
+            Address::repeat_byte(3).as_bytes()
+        ));
+        assert_eq!(bloom.0.iter().map(|byte| byte.count_ones()).sum::<u32>(), 6);
+    }
+
+    #[test]
+    fn logs_bloom_bit_positions() {
+        let event = VmEvent {
+            address: Address::repeat_byte(1),
+            ..VmEvent::default()
+        };
+        let bloom = logs_bloom([&event]);
+        let set_bytes: Vec<_> = bloom
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte != 0)
+            .map(|(i, &byte)| (i, byte))
+            .collect();
+        assert_eq!(set_bytes, [(182, 0x20), (211, 0x20), (215, 0x08)]);
+    }
 }
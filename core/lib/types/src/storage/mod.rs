@@ -7,6 +7,7 @@ use zksync_basic_types::{web3::signing::keccak256, L2ChainId};
 use crate::{AccountTreeId, Address, H160, H256, U256};
 
 pub mod log;
+pub mod storage_diff;
 pub mod witness_block_state;
 pub mod writes;
 
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Address, StorageKey, H256};
+
+/// Deduplicated write to a storage slot performed in an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageDiffWrite {
+    pub key: StorageKey,
+    /// Value of the slot after the L1 batch.
+    pub value: H256,
+    /// Enumeration index of the slot in the Merkle tree.
+    pub enumeration_index: u64,
+    /// Whether the slot was written to for the first time in this L1 batch.
+    pub is_initial: bool,
+}
+
+/// Compact storage diff of an L1 batch uploaded by the state keeper, so that downstream consumers
+/// (e.g., witness generation or snapshot tooling) don't need to query Postgres for this data.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct L1BatchStorageDiff {
+    /// Accounts whose storage was read or written in the L1 batch, sorted.
+    pub touched_accounts: Vec<Address>,
+    /// Deduplicated storage writes, sorted by the storage key.
+    pub writes: Vec<StorageDiffWrite>,
+}
@@ -9,6 +9,7 @@ use std::{
     mem,
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use jsonrpsee::core::ClientError;
@@ -29,6 +30,10 @@ pub enum Web3Error {
     ProxyError(#[from] EnrichedClientError),
     #[error("{0}")]
     SubmitTransactionError(String, Vec<u8>),
+    /// Transaction exceeds one of per-transaction limits. Details of the exceeded limit are returned
+    /// as the error data.
+    #[error("{0}")]
+    TxLimitExceeded(String, serde_json::Value),
     #[error("Failed to serialize transaction: {0}")]
     SerializationError(#[from] SerializationTransactionError),
     #[error("More than four topics in filter")]
@@ -37,8 +42,22 @@ pub enum Web3Error {
     FilterNotFound,
     #[error("Query returned more than {0} results. Try with this block range [{1:#x}, {2:#x}].")]
     LogsLimitExceeded(usize, u32, u32),
+    #[error("Query returned more than {0} results. Try with a more specific filter.")]
+    TooManyLogs(usize),
+    #[error("Logs for blocks {0} to {1} (exclusive) are archived and unavailable on this node")]
+    LogsRangeUnavailable(MiniblockNumber, MiniblockNumber),
     #[error("invalid filter: if blockHash is supplied fromBlock and toBlock must not be")]
     InvalidFilterBlockHash,
+    #[error("invalid reward percentiles: must be monotonically increasing and lie in the [0, 100] range")]
+    InvalidRewardPercentiles,
+    #[error("Transaction tracing timed out after {0:?}")]
+    TraceTimeout(Duration),
+    #[error("Block trace exceeds the limit of {0} entries; try tracing individual transactions")]
+    TraceTooLarge(usize),
+    #[error("Batch contains more than {0} calls")]
+    TooManyCalls(usize),
+    #[error("Invalid state override: {0}")]
+    InvalidStateOverride(String),
     #[error("Not implemented")]
     NotImplemented,
 
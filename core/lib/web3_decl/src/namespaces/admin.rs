@@ -0,0 +1,22 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::Address;
+
+/// Administrative methods of the main node. Must not be exposed publicly.
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "admin")
+)]
+pub trait AdminNamespace {
+    /// Sets the fee account (aka operator address) used by the state keeper. The change takes effect
+    /// starting from the next L1 batch.
+    #[method(name = "setFeeAccount")]
+    async fn set_fee_account(&self, address: Address) -> RpcResult<()>;
+}
@@ -3,14 +3,17 @@ use jsonrpsee::{
     proc_macros::rpc,
 };
 use zksync_types::{
-    api::{BlockId, BlockIdVariant, BlockNumber, Transaction, TransactionVariant},
+    api::{
+        proof::AccountProof, AccessListWithGasUsed, BlockId, BlockIdVariant, BlockNumber, SyncInfo,
+        Transaction, TransactionConditions, TransactionVariant,
+    },
     transaction_request::CallRequest,
-    Address, H256,
+    Address, L1BatchNumber, H256,
 };
 
 use crate::types::{
-    Block, Bytes, FeeHistory, Filter, FilterChanges, Index, Log, PubSubFilter, SyncState,
-    TransactionReceipt, U256, U64,
+    Block, Bytes, FeeHistory, Filter, FilterChanges, Index, Log, PubSubFilter, TransactionReceipt,
+    U256, U64,
 };
 
 #[cfg_attr(
@@ -38,9 +41,19 @@ pub trait EthNamespace {
     #[method(name = "estimateGas")]
     async fn estimate_gas(&self, req: CallRequest, _block: Option<BlockNumber>) -> RpcResult<U256>;
 
+    #[method(name = "createAccessList")]
+    async fn create_access_list(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<AccessListWithGasUsed>;
+
     #[method(name = "gasPrice")]
     async fn gas_price(&self) -> RpcResult<U256>;
 
+    #[method(name = "maxPriorityFeePerGas")]
+    async fn max_priority_fee_per_gas(&self) -> RpcResult<U256>;
+
     #[method(name = "newFilter")]
     async fn new_filter(&self, filter: Filter) -> RpcResult<U256>;
 
@@ -109,6 +122,14 @@ pub trait EthNamespace {
         block: Option<BlockIdVariant>,
     ) -> RpcResult<H256>;
 
+    #[method(name = "getProof")]
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<AccountProof>>;
+
     #[method(name = "getTransactionCount")]
     async fn get_transaction_count(
         &self,
@@ -133,6 +154,16 @@ pub trait EthNamespace {
         index: Index,
     ) -> RpcResult<Option<Transaction>>;
 
+    #[method(name = "getRawTransactionByHash")]
+    async fn get_raw_transaction_by_hash(&self, hash: H256) -> RpcResult<Option<Bytes>>;
+
+    #[method(name = "getRawTransactionByBlockNumberAndIndex")]
+    async fn get_raw_transaction_by_block_number_and_index(
+        &self,
+        block_number: BlockNumber,
+        index: Index,
+    ) -> RpcResult<Option<Bytes>>;
+
     #[method(name = "getTransactionReceipt")]
     async fn get_transaction_receipt(&self, hash: H256) -> RpcResult<Option<TransactionReceipt>>;
 
@@ -142,8 +173,15 @@ pub trait EthNamespace {
     #[method(name = "sendRawTransaction")]
     async fn send_raw_transaction(&self, tx_bytes: Bytes) -> RpcResult<H256>;
 
+    #[method(name = "sendRawTransactionConditional")]
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx_bytes: Bytes,
+        conditions: TransactionConditions,
+    ) -> RpcResult<H256>;
+
     #[method(name = "syncing")]
-    async fn syncing(&self) -> RpcResult<SyncState>;
+    async fn syncing(&self) -> RpcResult<SyncInfo>;
 
     #[method(name = "accounts")]
     async fn accounts(&self) -> RpcResult<Vec<Address>>;
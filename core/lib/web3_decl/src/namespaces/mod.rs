@@ -1,21 +1,25 @@
+pub mod admin;
 pub mod debug;
 pub mod en;
 pub mod eth;
 pub mod eth_subscribe;
 pub mod net;
 pub mod snapshots;
+pub mod txpool;
 pub mod web3;
 pub mod zks;
 
 #[cfg(feature = "client")]
 pub use self::{
-    debug::DebugNamespaceClient, en::EnNamespaceClient, eth::EthNamespaceClient,
-    net::NetNamespaceClient, snapshots::SnapshotsNamespaceServer, web3::Web3NamespaceClient,
-    zks::ZksNamespaceClient,
+    admin::AdminNamespaceClient, debug::DebugNamespaceClient, en::EnNamespaceClient,
+    eth::EthNamespaceClient, net::NetNamespaceClient, snapshots::SnapshotsNamespaceClient,
+    txpool::TxpoolNamespaceClient, web3::Web3NamespaceClient, zks::ZksNamespaceClient,
+    zks::ZksPubSubClient,
 };
 #[cfg(feature = "server")]
 pub use self::{
-    debug::DebugNamespaceServer, en::EnNamespaceServer, eth::EthNamespaceServer,
-    eth::EthPubSubServer, net::NetNamespaceServer, snapshots::SnapshotsNamespaceClient,
-    web3::Web3NamespaceServer, zks::ZksNamespaceServer,
+    admin::AdminNamespaceServer, debug::DebugNamespaceServer, en::EnNamespaceServer,
+    eth::EthNamespaceServer, eth::EthPubSubServer, net::NetNamespaceServer,
+    snapshots::SnapshotsNamespaceServer, txpool::TxpoolNamespaceServer, web3::Web3NamespaceServer,
+    zks::ZksNamespaceServer, zks::ZksPubSubServer,
 };
@@ -0,0 +1,31 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{
+    api::{Transaction, TxpoolAccountContent, TxpoolContent, TxpoolStatus},
+    Address,
+};
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "txpool")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "txpool")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "txpool")
+)]
+pub trait TxpoolNamespace {
+    #[method(name = "content")]
+    async fn content(&self) -> RpcResult<TxpoolContent<Transaction>>;
+
+    #[method(name = "contentFrom")]
+    async fn content_from(&self, address: Address) -> RpcResult<TxpoolAccountContent<Transaction>>;
+
+    #[method(name = "status")]
+    async fn status(&self) -> RpcResult<TxpoolStatus>;
+
+    #[method(name = "inspect")]
+    async fn inspect(&self) -> RpcResult<TxpoolContent<String>>;
+}
@@ -1,9 +1,14 @@
 use std::collections::HashMap;
 
-use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionResult},
+    proc_macros::rpc,
+};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
+        BatchCallResult, BlockDetails, BlockFinality, BlockIdVariant, BridgeAddresses,
+        BundleTxResult, L1BatchDetails, L1BatchFinality, L2ToL1LogProof, LogCursor, Page, Proof,
+        ProtocolVersion, ProtocolVersionDetails, StateOverride, Transaction, TransactionCursor,
         TransactionDetails,
     },
     fee::Fee,
@@ -12,7 +17,7 @@ use zksync_types::{
     Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
 
-use crate::types::Token;
+use crate::types::{Filter, Log, Token};
 
 #[cfg_attr(
     all(feature = "client", feature = "server"),
@@ -83,6 +88,19 @@ pub trait ZksNamespace {
         block_number: MiniblockNumber,
     ) -> RpcResult<Option<BlockDetails>>;
 
+    #[method(name = "getBlockFinality")]
+    async fn get_block_finality(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<BlockFinality>>;
+
+    #[method(name = "getBlockDetailsPage")]
+    async fn get_block_details_page(
+        &self,
+        before: Option<MiniblockNumber>,
+        limit: Option<u32>,
+    ) -> RpcResult<Page<BlockDetails, MiniblockNumber>>;
+
     #[method(name = "getTransactionDetails")]
     async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>>;
 
@@ -92,10 +110,38 @@ pub trait ZksNamespace {
         block_number: MiniblockNumber,
     ) -> RpcResult<Vec<zksync_types::Transaction>>;
 
+    #[method(name = "getTransactionsByInitiator")]
+    async fn get_transactions_by_initiator(
+        &self,
+        initiator: Address,
+        cursor: Option<TransactionCursor>,
+        limit: Option<u32>,
+    ) -> RpcResult<Page<Transaction, TransactionCursor>>;
+
+    #[method(name = "getLogsPaginated")]
+    async fn get_logs_paginated(
+        &self,
+        filter: Filter,
+        cursor: Option<LogCursor>,
+    ) -> RpcResult<Page<Log, LogCursor>>;
+
     #[method(name = "getL1BatchDetails")]
     async fn get_l1_batch_details(&self, batch: L1BatchNumber)
         -> RpcResult<Option<L1BatchDetails>>;
 
+    #[method(name = "getL1BatchFinality")]
+    async fn get_l1_batch_finality(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchFinality>>;
+
+    #[method(name = "getL1BatchDetailsPage")]
+    async fn get_l1_batch_details_page(
+        &self,
+        before: Option<L1BatchNumber>,
+        limit: Option<u32>,
+    ) -> RpcResult<Page<L1BatchDetails, L1BatchNumber>>;
+
     #[method(name = "getBytecodeByHash")]
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>>;
 
@@ -111,6 +157,9 @@ pub trait ZksNamespace {
         version_id: Option<u16>,
     ) -> RpcResult<Option<ProtocolVersion>>;
 
+    #[method(name = "getProtocolVersions")]
+    async fn get_protocol_versions(&self) -> RpcResult<Vec<ProtocolVersionDetails>>;
+
     #[method(name = "getProof")]
     async fn get_proof(
         &self,
@@ -118,4 +167,44 @@ pub trait ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Option<Proof>>;
+
+    #[method(name = "batchCall")]
+    async fn batch_call(
+        &self,
+        requests: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<Vec<BatchCallResult>>;
+
+    #[method(name = "simulateBundle")]
+    async fn simulate_bundle(
+        &self,
+        requests: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Vec<BundleTxResult>>;
+}
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "zks")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "zks")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "zks")
+)]
+pub trait ZksPubSub {
+    /// Streams logs matching the filter starting from `filter.fromBlock` (the latest miniblock by default),
+    /// and then logs from newly sealed miniblocks. `toBlock` and `blockHash` are not supported.
+    #[subscription(name = "streamLogs" => "subscription", unsubscribe = "unsubscribeStreamLogs", item = Log)]
+    async fn stream_logs(&self, filter: Filter) -> SubscriptionResult;
+
+    /// Notifies about L1 batches reaching a certain stage, specified by `topic`: `l1BatchSealed`, `l1BatchVerified`
+    /// (the batch proof is confirmed on L1) or `l1BatchExecuted`. Each notification contains L1 batch details
+    /// as of reaching the stage.
+    #[subscription(name = "subscribe" => "subscription", unsubscribe = "unsubscribe", item = L1BatchDetails)]
+    async fn subscribe_l1_batches(&self, topic: String) -> SubscriptionResult;
 }
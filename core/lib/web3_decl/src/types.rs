@@ -255,6 +255,14 @@ pub struct PubSubFilter {
     pub address: Option<ValueOrArray<H160>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub topics: Option<Vec<Option<ValueOrArray<H256>>>>,
+    /// Whether `newPendingTransactions` notifications should contain full transactions instead of their hashes.
+    /// Ignored for other subscription types.
+    #[serde(
+        default,
+        rename = "includeTransactions",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub include_transactions: Option<bool>,
 }
 
 impl PubSubFilter {
@@ -323,6 +331,12 @@ impl PubSubFilterBuilder {
         )
     }
 
+    /// Requests full transactions in `newPendingTransactions` notifications
+    pub fn set_include_transactions(mut self, include_transactions: bool) -> Self {
+        self.filter.include_transactions = Some(include_transactions);
+        self
+    }
+
     /// Returns filter
     pub fn build(&self) -> PubSubFilter {
         self.filter.clone()
@@ -344,8 +358,10 @@ fn topic_to_option<T>(topic: ethabi::Topic<T>) -> Option<Vec<T>> {
 pub enum PubSubResult {
     Header(BlockHeader),
     Log(Log),
+    Transaction(zksync_types::api::Transaction),
     TxHash(H256),
     Syncing(bool),
+    L1Batch(zksync_types::api::L1BatchDetails),
 }
 
 #[cfg(test)]
@@ -442,4 +458,16 @@ mod tests {
         let restored_value: ValueOrArray<Address> = serde_json::from_value(json).unwrap();
         assert_eq!(restored_value, value);
     }
+
+    #[test]
+    fn deserializing_pub_sub_filter() {
+        let filter: PubSubFilter =
+            serde_json::from_value(serde_json::json!({ "includeTransactions": true })).unwrap();
+        assert_eq!(filter.include_transactions, Some(true));
+        assert_eq!(filter.address, None);
+        assert_eq!(filter.topics, None);
+
+        let json = serde_json::to_value(PubSubFilter::default()).unwrap();
+        assert_eq!(json, serde_json::json!({}));
+    }
 }
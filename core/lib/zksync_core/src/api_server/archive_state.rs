@@ -0,0 +1,83 @@
+//! Archived VM state used by the API server to serve requests for historical blocks.
+
+use std::{path::Path, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_state::{RocksbStorageBuilder, RocksdbArchive, RocksdbStorage};
+
+/// Keeps the RocksDB state archive synchronized with Postgres.
+#[derive(Debug)]
+pub struct ArchiveStateUpdater {
+    builder: RocksbStorageBuilder,
+    pool: ConnectionPool,
+    poll_interval: Duration,
+}
+
+impl ArchiveStateUpdater {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Opens the state archive at the specified path. The returned archive handle can be used immediately;
+    /// the archived state is extended as the updater runs.
+    pub async fn new(path: &Path, pool: ConnectionPool) -> anyhow::Result<(Self, RocksdbArchive)> {
+        let mut builder = RocksdbStorage::builder(path)
+            .await
+            .context("failed initializing state archive")?;
+        let archive = builder.enable_archive_mode();
+        let this = Self {
+            builder,
+            pool,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        };
+        Ok((this, archive))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let Self {
+            mut builder,
+            pool,
+            poll_interval,
+        } = self;
+
+        loop {
+            let mut storage = pool.access_storage_tagged("api").await?;
+            let sealed_l1_batch_number = storage
+                .blocks_dal()
+                .get_sealed_l1_batch_number()
+                .await
+                .context("failed getting sealed L1 batch number")?;
+            if let Some(sealed_l1_batch_number) = sealed_l1_batch_number {
+                // Postgres may have been reverted since the last update.
+                if builder.l1_batch_number().await > Some(sealed_l1_batch_number + 1) {
+                    tracing::info!(
+                        "Rolling back state archive to L1 batch #{sealed_l1_batch_number}"
+                    );
+                    builder
+                        .rollback(&mut storage, sealed_l1_batch_number)
+                        .await
+                        .context("failed rolling back state archive")?;
+                }
+            }
+
+            let Some(synced_storage) = builder
+                .synchronize(&mut storage, &stop_receiver)
+                .await
+                .context("failed synchronizing state archive")?
+            else {
+                break; // The synchronization was interrupted by the stop signal
+            };
+            drop(storage);
+            builder = synced_storage.into_builder();
+
+            if tokio::time::timeout(poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, state archive updater is shutting down");
+        Ok(())
+    }
+}
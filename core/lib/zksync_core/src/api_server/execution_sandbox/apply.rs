@@ -17,7 +17,9 @@ use multivm::{
 };
 use tokio::runtime::Handle;
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_state::{PostgresStorage, ReadStorage, StoragePtr, StorageView, WriteStorage};
+use zksync_state::{
+    HistoricalRocksdbStorage, PostgresStorage, ReadStorage, StoragePtr, StorageView, WriteStorage,
+};
 use zksync_system_constants::{
     SYSTEM_CONTEXT_ADDRESS, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
     SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION, ZKPORTER_IS_AVAILABLE,
@@ -35,10 +37,134 @@ use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
 
 use super::{
     vm_metrics::{self, SandboxStage, SANDBOX_METRICS},
-    BlockArgs, TxExecutionArgs, TxSharedArgs, VmPermit,
+    BlockArgs, EstimationStorageCache, StorageOverrides, TxExecutionArgs, TxSharedArgs, VmPermit,
 };
 
-type BoxedVm<'a> = Box<VmInstance<StorageView<PostgresStorage<'a>>, HistoryDisabled>>;
+type BoxedVm<'a> = Box<VmInstance<StorageView<SandboxStorage<'a>>, HistoryDisabled>>;
+
+/// Source of the state for the sandbox VM.
+#[derive(Debug)]
+enum StorageSource<'a> {
+    Postgres(PostgresStorage<'a>),
+    Archive(HistoricalRocksdbStorage),
+}
+
+impl ReadStorage for StorageSource<'_> {
+    fn read_value(&mut self, key: &StorageKey) -> H256 {
+        match self {
+            Self::Postgres(storage) => storage.read_value(key),
+            Self::Archive(storage) => storage.read_value(key),
+        }
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        match self {
+            Self::Postgres(storage) => storage.is_write_initial(key),
+            Self::Archive(storage) => storage.is_write_initial(key),
+        }
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        match self {
+            Self::Postgres(storage) => storage.load_factory_dep(hash),
+            Self::Archive(storage) => storage.load_factory_dep(hash),
+        }
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        match self {
+            Self::Postgres(storage) => storage.get_enumeration_index(key),
+            Self::Archive(storage) => storage.get_enumeration_index(key),
+        }
+    }
+}
+
+/// Storage used by the sandbox VM. Historical state is read from the state archive if it is available,
+/// and from Postgres otherwise. Storage overrides take precedence over the state read from the source.
+/// If a cache is provided, data read from the source is cached in it.
+#[derive(Debug)]
+pub(super) struct SandboxStorage<'a> {
+    source: StorageSource<'a>,
+    overrides: StorageOverrides,
+    cache: Option<EstimationStorageCache>,
+}
+
+impl<'a> SandboxStorage<'a> {
+    async fn new(
+        connection: StorageProcessor<'a>,
+        shared_args: &TxSharedArgs,
+        miniblock_number: MiniblockNumber,
+        use_archive: bool,
+        overrides: StorageOverrides,
+        cache: Option<EstimationStorageCache>,
+    ) -> anyhow::Result<Self> {
+        let archived_storage = shared_args
+            .archive_state
+            .as_ref()
+            .filter(|_| use_archive)
+            .and_then(|archive| archive.storage_at(miniblock_number));
+        let source = if let Some(storage) = archived_storage {
+            StorageSource::Archive(storage)
+        } else {
+            let storage =
+                PostgresStorage::new_async(Handle::current(), connection, miniblock_number, false)
+                    .await
+                    .context("cannot create `PostgresStorage`")?
+                    .with_caches(shared_args.caches.clone());
+            StorageSource::Postgres(storage)
+        };
+        if let Some(cache) = &cache {
+            cache.bind(miniblock_number);
+        }
+        Ok(Self {
+            source,
+            overrides,
+            cache,
+        })
+    }
+}
+
+impl ReadStorage for SandboxStorage<'_> {
+    fn read_value(&mut self, key: &StorageKey) -> H256 {
+        if let Some(value) = self.overrides.read_value(key) {
+            return value;
+        }
+        let source = &mut self.source;
+        match &self.cache {
+            Some(cache) => cache.read_value(key, |key| source.read_value(key)),
+            None => source.read_value(key),
+        }
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        let source = &mut self.source;
+        match &self.cache {
+            Some(cache) => cache.is_write_initial(key, |key| source.is_write_initial(key)),
+            None => source.is_write_initial(key),
+        }
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(bytecode) = self.overrides.load_factory_dep(hash) {
+            return Some(bytecode);
+        }
+        let source = &mut self.source;
+        match &self.cache {
+            Some(cache) => cache.load_factory_dep(hash, |hash| source.load_factory_dep(hash)),
+            None => source.load_factory_dep(hash),
+        }
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        let source = &mut self.source;
+        match &self.cache {
+            Some(cache) => {
+                cache.get_enumeration_index(key, |key| source.get_enumeration_index(key))
+            }
+            None => source.get_enumeration_index(key),
+        }
+    }
+}
 
 #[derive(Debug)]
 struct Sandbox<'a> {
@@ -46,7 +172,7 @@ struct Sandbox<'a> {
     l1_batch_env: L1BatchEnv,
     execution_args: &'a TxExecutionArgs,
     l2_block_info_to_reset: Option<StoredL2BlockInfo>,
-    storage_view: StorageView<PostgresStorage<'a>>,
+    storage_view: StorageView<SandboxStorage<'a>>,
 }
 
 impl<'a> Sandbox<'a> {
@@ -55,6 +181,7 @@ impl<'a> Sandbox<'a> {
         shared_args: TxSharedArgs,
         execution_args: &'a TxExecutionArgs,
         block_args: BlockArgs,
+        storage_overrides: StorageOverrides,
     ) -> anyhow::Result<Sandbox<'a>> {
         let resolve_started_at = Instant::now();
         let resolved_block_info = block_args
@@ -80,15 +207,16 @@ impl<'a> Sandbox<'a> {
         )
         .await?;
 
-        let storage = PostgresStorage::new_async(
-            Handle::current(),
+        // The latest state is served from Postgres, since it is covered by the storage caches.
+        let storage = SandboxStorage::new(
             connection,
+            &shared_args,
             resolved_block_info.state_l2_block_number,
-            false,
+            !block_args.resolves_to_latest_sealed_miniblock(),
+            storage_overrides,
+            execution_args.storage_cache.clone(),
         )
-        .await
-        .context("cannot create `PostgresStorage`")?
-        .with_caches(shared_args.caches.clone());
+        .await?;
 
         let storage_view = StorageView::new(storage);
         let (system_env, l1_batch_env) = Self::prepare_env(
@@ -107,6 +235,96 @@ impl<'a> Sandbox<'a> {
         })
     }
 
+    /// Creates a sandbox to replay transactions from the specified sealed miniblock. The VM is initialized with the state
+    /// after the previous miniblock, and with the environment (including base system contracts) of the replayed miniblock.
+    async fn new_for_replay(
+        mut connection: StorageProcessor<'a>,
+        shared_args: TxSharedArgs,
+        execution_args: &'a TxExecutionArgs,
+        miniblock_number: MiniblockNumber,
+    ) -> anyhow::Result<Sandbox<'a>> {
+        let state_miniblock_number = miniblock_number - 1;
+        let miniblock_header = connection
+            .blocks_dal()
+            .get_miniblock_header(miniblock_number)
+            .await
+            .context("failed getting replayed miniblock header")?
+            .with_context(|| format!("miniblock #{miniblock_number} is not in storage"))?;
+        let prev_block_hash = connection
+            .blocks_web3_dal()
+            .get_miniblock_hash(state_miniblock_number)
+            .await
+            .context("failed getting previous miniblock hash")?
+            .with_context(|| format!("miniblock #{state_miniblock_number} is not in storage"))?;
+        let l1_batch = connection
+            .storage_web3_dal()
+            .resolve_l1_batch_number_of_miniblock(miniblock_number)
+            .await
+            .context("failed resolving L1 batch for miniblock")?;
+        let l1_batch_timestamp = connection
+            .blocks_web3_dal()
+            .get_expected_l1_batch_timestamp(&l1_batch)
+            .await
+            .context("failed getting L1 batch timestamp")?
+            .context("missing timestamp for replayed miniblock")?;
+        let base_system_contracts_hashes = miniblock_header.base_system_contracts_hashes;
+        let base_system_contracts = connection
+            .factory_deps_dal()
+            .get_base_system_contracts(
+                base_system_contracts_hashes.bootloader,
+                base_system_contracts_hashes.default_aa,
+            )
+            .await
+            .context("failed loading base system contracts for miniblock")?;
+
+        let resolved_block_info = ResolvedBlockInfo {
+            state_l2_block_number: state_miniblock_number,
+            state_l2_block_hash: prev_block_hash,
+            vm_l1_batch_number: l1_batch.expected_l1_batch(),
+            l1_batch_timestamp,
+            // TODO: remove `unwrap_or` when protocol version ID will be assigned for each block.
+            protocol_version: miniblock_header
+                .protocol_version
+                .unwrap_or(ProtocolVersionId::last_potentially_undefined()),
+            historical_fee_input: Some(miniblock_header.batch_fee_input),
+        };
+        // Unlike other sandbox executions, the replayed miniblock directly follows the state miniblock,
+        // so L2 block info in the storage doesn't need to be reset.
+        let l2_block_env = L2BlockEnv {
+            number: miniblock_number.0,
+            timestamp: miniblock_header.timestamp,
+            prev_block_hash,
+            max_virtual_blocks_to_create: miniblock_header.virtual_blocks,
+        };
+
+        let storage = SandboxStorage::new(
+            connection,
+            &shared_args,
+            state_miniblock_number,
+            true,
+            StorageOverrides::default(),
+            None,
+        )
+        .await?;
+
+        let (mut system_env, mut l1_batch_env) = Self::prepare_env(
+            shared_args,
+            execution_args,
+            &resolved_block_info,
+            l2_block_env,
+        );
+        system_env.base_system_smart_contracts = base_system_contracts;
+        l1_batch_env.fee_account = miniblock_header.fee_account_address;
+
+        Ok(Self {
+            system_env,
+            l1_batch_env,
+            storage_view: StorageView::new(storage),
+            execution_args,
+            l2_block_info_to_reset: None,
+        })
+    }
+
     async fn load_l2_block_info(
         connection: &mut StorageProcessor<'_>,
         is_pending_block: bool,
@@ -165,26 +383,29 @@ impl<'a> Sandbox<'a> {
     }
 
     /// This method is blocking.
-    fn setup_storage_view(&mut self, tx: &Transaction) {
+    fn setup_storage_view(
+        storage_view: &mut StorageView<SandboxStorage<'_>>,
+        execution_args: &TxExecutionArgs,
+        l2_block_info_to_reset: Option<StoredL2BlockInfo>,
+        tx: &Transaction,
+    ) {
         let storage_view_setup_started_at = Instant::now();
-        if let Some(nonce) = self.execution_args.enforced_nonce {
+        if let Some(nonce) = execution_args.enforced_nonce {
             let nonce_key = get_nonce_key(&tx.initiator_account());
-            let full_nonce = self.storage_view.read_value(&nonce_key);
+            let full_nonce = storage_view.read_value(&nonce_key);
             let (_, deployment_nonce) = decompose_full_nonce(h256_to_u256(full_nonce));
             let enforced_full_nonce = nonces_to_full_nonce(U256::from(nonce.0), deployment_nonce);
-            self.storage_view
-                .set_value(nonce_key, u256_to_h256(enforced_full_nonce));
+            storage_view.set_value(nonce_key, u256_to_h256(enforced_full_nonce));
         }
 
         let payer = tx.payer();
         let balance_key = storage_key_for_eth_balance(&payer);
-        let mut current_balance = h256_to_u256(self.storage_view.read_value(&balance_key));
-        current_balance += self.execution_args.added_balance;
-        self.storage_view
-            .set_value(balance_key, u256_to_h256(current_balance));
+        let mut current_balance = h256_to_u256(storage_view.read_value(&balance_key));
+        current_balance += execution_args.added_balance;
+        storage_view.set_value(balance_key, u256_to_h256(current_balance));
 
         // Reset L2 block info if necessary.
-        if let Some(l2_block_info_to_reset) = self.l2_block_info_to_reset {
+        if let Some(l2_block_info_to_reset) = l2_block_info_to_reset {
             let l2_block_info_key = StorageKey::new(
                 AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
                 SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
@@ -193,14 +414,13 @@ impl<'a> Sandbox<'a> {
                 l2_block_info_to_reset.l2_block_number as u64,
                 l2_block_info_to_reset.l2_block_timestamp,
             );
-            self.storage_view
-                .set_value(l2_block_info_key, u256_to_h256(l2_block_info));
+            storage_view.set_value(l2_block_info_key, u256_to_h256(l2_block_info));
 
             let l2_block_txs_rolling_hash_key = StorageKey::new(
                 AccountTreeId::new(SYSTEM_CONTEXT_ADDRESS),
                 SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION,
             );
-            self.storage_view.set_value(
+            storage_view.set_value(
                 l2_block_txs_rolling_hash_key,
                 l2_block_info_to_reset.txs_rolling_hash,
             );
@@ -259,8 +479,13 @@ impl<'a> Sandbox<'a> {
         mut self,
         tx: &Transaction,
         adjust_pubdata_price: bool,
-    ) -> (BoxedVm<'a>, StoragePtr<StorageView<PostgresStorage<'a>>>) {
-        self.setup_storage_view(tx);
+    ) -> (BoxedVm<'a>, StoragePtr<StorageView<SandboxStorage<'a>>>) {
+        Self::setup_storage_view(
+            &mut self.storage_view,
+            self.execution_args,
+            self.l2_block_info_to_reset,
+            tx,
+        );
         let protocol_version = self.system_env.version;
         if adjust_pubdata_price {
             self.l1_batch_env.fee_input = adjust_pubdata_price_for_tx(
@@ -270,7 +495,12 @@ impl<'a> Sandbox<'a> {
                 protocol_version.into(),
             );
         };
+        self.build_vm()
+    }
 
+    /// Creates a VM without any storage or environment adjustments.
+    fn build_vm(self) -> (BoxedVm<'a>, StoragePtr<StorageView<SandboxStorage<'a>>>) {
+        let protocol_version = self.system_env.version;
         let storage_view = self.storage_view.to_rc_ptr();
         let vm = Box::new(VmInstance::new_with_specific_version(
             self.l1_batch_env,
@@ -295,7 +525,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
     tx: Transaction,
     block_args: BlockArgs,
     apply: impl FnOnce(
-        &mut VmInstance<StorageView<PostgresStorage<'_>>, HistoryDisabled>,
+        &mut VmInstance<StorageView<SandboxStorage<'_>>, HistoryDisabled>,
         Transaction,
     ) -> T,
 ) -> anyhow::Result<T> {
@@ -317,6 +547,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
         shared_args,
         execution_args,
         block_args,
+        StorageOverrides::default(),
     ))?;
     let (mut vm, storage_view) = sandbox.into_vm(&tx, adjust_pubdata_price);
 
@@ -342,6 +573,201 @@ pub(super) fn apply_vm_in_sandbox<T>(
     Ok(result)
 }
 
+/// Executes independent calls on the same state. Unlike with [`apply_vm_in_sandbox()`], calls share the storage view,
+/// so that each storage slot is read from the storage only once. Each call is executed in a fresh VM, and changes
+/// made by it are discarded afterwards. Like for single calls, the base fee is set to `max_fee_per_gas` of each call.
+/// If the execution deadline is exceeded, the remaining calls are not executed.
+pub(super) fn apply_vm_in_sandbox_for_calls<T>(
+    vm_permit: VmPermit,
+    shared_args: TxSharedArgs,
+    execution_args: &TxExecutionArgs,
+    connection_pool: &ConnectionPool,
+    txs: Vec<Transaction>,
+    block_args: BlockArgs,
+    mut apply: impl FnMut(
+        &mut VmInstance<StorageView<SandboxStorage<'_>>, HistoryDisabled>,
+        Transaction,
+    ) -> T,
+) -> anyhow::Result<Vec<T>> {
+    let stage_started_at = Instant::now();
+    let span = tracing::debug_span!("initialization").entered();
+
+    let rt_handle = vm_permit.rt_handle();
+    let connection = rt_handle
+        .block_on(connection_pool.access_storage_tagged("api"))
+        .context("failed acquiring DB connection")?;
+    let Sandbox {
+        system_env,
+        l1_batch_env,
+        execution_args,
+        l2_block_info_to_reset,
+        storage_view,
+    } = rt_handle.block_on(Sandbox::new(
+        connection,
+        shared_args,
+        execution_args,
+        block_args,
+        StorageOverrides::default(),
+    ))?;
+    let protocol_version = system_env.version;
+    let storage_view = storage_view.to_rc_ptr();
+
+    SANDBOX_METRICS.sandbox[&SandboxStage::Initialization].observe(stage_started_at.elapsed());
+    span.exit();
+
+    let mut results = Vec::with_capacity(txs.len());
+    for tx in txs {
+        {
+            let mut storage_view = storage_view.borrow_mut();
+            storage_view.discard_modifications();
+            Sandbox::setup_storage_view(
+                &mut storage_view,
+                execution_args,
+                l2_block_info_to_reset,
+                &tx,
+            );
+        }
+        let l1_batch_env = L1BatchEnv {
+            enforced_base_fee: Some(tx.max_fee_per_gas().as_u64()),
+            ..l1_batch_env.clone()
+        };
+        let mut vm = VmInstance::new_with_specific_version(
+            l1_batch_env,
+            system_env.clone(),
+            storage_view.clone(),
+            protocol_version.into_api_vm_version(),
+        );
+
+        let tx_id = format!(
+            "{:?}-{}",
+            tx.initiator_account(),
+            tx.nonce().unwrap_or(Nonce(0))
+        );
+        let execution_latency = SANDBOX_METRICS.sandbox[&SandboxStage::Execution].start();
+        results.push(apply(&mut vm, tx));
+        let vm_execution_took = execution_latency.observe();
+
+        let memory_metrics = vm.record_vm_memory_metrics();
+        vm_metrics::report_vm_memory_metrics(
+            &tx_id,
+            &memory_metrics,
+            vm_execution_took,
+            storage_view.as_ref().borrow_mut().metrics(),
+        );
+        if execution_args.is_past_deadline() {
+            break;
+        }
+    }
+    Ok(results)
+}
+
+/// Executes transactions sequentially in a single VM, so that each transaction observes changes made by the previous ones.
+/// `storage_overrides` are applied to the state before executing the first transaction. If the execution deadline
+/// is exceeded, the remaining transactions are not executed.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn apply_vm_in_sandbox_for_bundle<T>(
+    vm_permit: VmPermit,
+    shared_args: TxSharedArgs,
+    execution_args: &TxExecutionArgs,
+    connection_pool: &ConnectionPool,
+    txs: Vec<Transaction>,
+    block_args: BlockArgs,
+    storage_overrides: StorageOverrides,
+    mut apply: impl FnMut(
+        &mut VmInstance<StorageView<SandboxStorage<'_>>, HistoryDisabled>,
+        Transaction,
+    ) -> T,
+) -> anyhow::Result<Vec<T>> {
+    let Some(first_tx) = txs.first() else {
+        return Ok(vec![]);
+    };
+
+    let stage_started_at = Instant::now();
+    let span = tracing::debug_span!("initialization").entered();
+
+    let rt_handle = vm_permit.rt_handle();
+    let connection = rt_handle
+        .block_on(connection_pool.access_storage_tagged("api"))
+        .context("failed acquiring DB connection")?;
+    let sandbox = rt_handle.block_on(Sandbox::new(
+        connection,
+        shared_args,
+        execution_args,
+        block_args,
+        storage_overrides,
+    ))?;
+    let bundle_id = format!(
+        "bundle-{:?}-{}",
+        first_tx.initiator_account(),
+        first_tx.nonce().unwrap_or(Nonce(0))
+    );
+    let (mut vm, storage_view) = sandbox.into_vm(first_tx, false);
+
+    SANDBOX_METRICS.sandbox[&SandboxStage::Initialization].observe(stage_started_at.elapsed());
+    span.exit();
+
+    let mut results = Vec::with_capacity(txs.len());
+    let execution_latency = SANDBOX_METRICS.sandbox[&SandboxStage::Execution].start();
+    for tx in txs {
+        results.push(apply(&mut vm, tx));
+        if execution_args.is_past_deadline() {
+            break;
+        }
+    }
+    let vm_execution_took = execution_latency.observe();
+
+    let memory_metrics = vm.record_vm_memory_metrics();
+    vm_metrics::report_vm_memory_metrics(
+        &bundle_id,
+        &memory_metrics,
+        vm_execution_took,
+        storage_view.as_ref().borrow_mut().metrics(),
+    );
+    Ok(results)
+}
+
+/// Replays transactions from a sealed miniblock. Unlike [`apply_vm_in_sandbox()`], `apply` is responsible
+/// for executing all transactions it needs (e.g., all transactions in the miniblock preceding the traced one).
+pub(super) fn replay_vm_in_sandbox<T>(
+    vm_permit: VmPermit,
+    shared_args: TxSharedArgs,
+    execution_args: &TxExecutionArgs,
+    connection_pool: &ConnectionPool,
+    miniblock_number: MiniblockNumber,
+    apply: impl FnOnce(&mut VmInstance<StorageView<SandboxStorage<'_>>, HistoryDisabled>) -> T,
+) -> anyhow::Result<T> {
+    let stage_started_at = Instant::now();
+    let span = tracing::debug_span!("initialization").entered();
+
+    let rt_handle = vm_permit.rt_handle();
+    let connection = rt_handle
+        .block_on(connection_pool.access_storage_tagged("api"))
+        .context("failed acquiring DB connection")?;
+    let sandbox = rt_handle.block_on(Sandbox::new_for_replay(
+        connection,
+        shared_args,
+        execution_args,
+        miniblock_number,
+    ))?;
+    let (mut vm, storage_view) = sandbox.build_vm();
+
+    SANDBOX_METRICS.sandbox[&SandboxStage::Initialization].observe(stage_started_at.elapsed());
+    span.exit();
+
+    let execution_latency = SANDBOX_METRICS.sandbox[&SandboxStage::Execution].start();
+    let result = apply(&mut vm);
+    let vm_execution_took = execution_latency.observe();
+
+    let memory_metrics = vm.record_vm_memory_metrics();
+    vm_metrics::report_vm_memory_metrics(
+        &format!("replay-{miniblock_number}"),
+        &memory_metrics,
+        vm_execution_took,
+        storage_view.as_ref().borrow_mut().metrics(),
+    );
+    Ok(result)
+}
+
 #[derive(Debug, Clone, Copy)]
 struct StoredL2BlockInfo {
     l2_block_number: u32,
@@ -416,7 +842,7 @@ impl BlockArgs {
         )
     }
 
-    fn is_estimate_like(&self) -> bool {
+    pub(super) fn is_estimate_like(&self) -> bool {
         matches!(
             self.block_id,
             api::BlockId::Number(api::BlockNumber::Pending)
@@ -0,0 +1,173 @@
+//! Cache for `eth_call` results.
+//!
+//! The output of a call executed on top of a sealed miniblock is fully determined by the miniblock and the call itself,
+//! so it can be reused for identical calls. This absorbs hot read traffic, such as dapps polling the same view functions.
+//! Calls on top of the pending block (or `latest` / `committed` blocks, which use the current fee input) are not cached.
+
+use std::{num::NonZeroUsize, sync::Mutex};
+
+use anyhow::Context as _;
+use lru::LruCache;
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+use zksync_dal::ConnectionPool;
+use zksync_types::{l2::L2Tx, web3::signing::keccak256, H256};
+
+use super::BlockArgs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+enum CacheOutcome {
+    Hit,
+    Miss,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_web3_eth_call_cache")]
+struct CallCacheMetrics {
+    /// Number of cache lookups grouped by the outcome. Only calls that can be cached are counted.
+    requests: Family<CacheOutcome, Counter>,
+    /// Current number of entries in the cache.
+    len: Gauge<usize>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<CallCacheMetrics> = vise::Global::new();
+
+/// Key of a [`CallResultCache`] entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct CallCacheKey {
+    block_hash: H256,
+    call_hash: H256,
+}
+
+impl CallCacheKey {
+    fn new(block_hash: H256, tx: &L2Tx) -> anyhow::Result<Self> {
+        // `received_timestamp_ms` and `raw_bytes` do not influence execution, so they are excluded from the hash.
+        let call_bytes = serde_json::to_vec(&(&tx.execute, &tx.common_data))
+            .context("failed serializing call")?;
+        Ok(Self {
+            block_hash,
+            call_hash: H256(keccak256(&call_bytes)),
+        })
+    }
+}
+
+/// LRU cache for `eth_call` results keyed by the miniblock hash and the call. See the [module docs](self) for details.
+#[derive(Debug)]
+pub(crate) struct CallResultCache {
+    inner: Option<Mutex<LruCache<CallCacheKey, Vec<u8>>>>,
+}
+
+impl CallResultCache {
+    /// Creates a cache with the specified capacity measured in entries. If `capacity` is 0, the cache is disabled.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: NonZeroUsize::new(capacity).map(|capacity| Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    /// Returns the cache key for a call, or `None` if the call cannot be cached (e.g., it is executed
+    /// on top of the pending block, or the cache is disabled).
+    pub async fn key(
+        &self,
+        pool: &ConnectionPool,
+        block_args: &BlockArgs,
+        tx: &L2Tx,
+    ) -> anyhow::Result<Option<CallCacheKey>> {
+        if self.inner.is_none() || block_args.is_estimate_like() {
+            return Ok(None);
+        }
+        let mut connection = pool
+            .access_storage_tagged("api")
+            .await
+            .context("failed acquiring connection to replica DB")?;
+        let block_number = block_args.resolved_block_number();
+        let block_hash = connection
+            .blocks_web3_dal()
+            .get_miniblock_hash(block_number)
+            .await
+            .with_context(|| format!("failed getting hash for miniblock #{block_number}"))?;
+        let Some(block_hash) = block_hash else {
+            return Ok(None);
+        };
+        CallCacheKey::new(block_hash, tx).map(Some)
+    }
+
+    pub fn get(&self, key: &CallCacheKey) -> Option<Vec<u8>> {
+        let mut inner = self.inner.as_ref()?.lock().expect("call cache is poisoned");
+        let output = inner.get(key).cloned();
+        let outcome = if output.is_some() {
+            CacheOutcome::Hit
+        } else {
+            CacheOutcome::Miss
+        };
+        METRICS.requests[&outcome].inc();
+        output
+    }
+
+    pub fn insert(&self, key: CallCacheKey, output: Vec<u8>) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        let mut inner = inner.lock().expect("call cache is poisoned");
+        inner.put(key, output);
+        METRICS.len.set(inner.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{Address, Nonce};
+
+    use super::*;
+
+    fn call(calldata: &[u8]) -> L2Tx {
+        L2Tx::new(
+            Address::repeat_byte(1),
+            calldata.to_vec(),
+            Nonce(0),
+            Default::default(),
+            Address::repeat_byte(2),
+            0.into(),
+            None,
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn call_cache_basics() {
+        let cache = CallResultCache::new(2);
+        let block_hash = H256::repeat_byte(1);
+        let key = CallCacheKey::new(block_hash, &call(b"first")).unwrap();
+        assert_eq!(cache.get(&key), None);
+        cache.insert(key, b"output".to_vec());
+        assert_eq!(cache.get(&key).unwrap(), b"output");
+
+        // The receipt timestamp must not influence the key.
+        let mut same_call = call(b"first");
+        same_call.received_timestamp_ms += 1_000;
+        assert_eq!(CallCacheKey::new(block_hash, &same_call).unwrap(), key);
+
+        let other_block_key = CallCacheKey::new(H256::repeat_byte(2), &call(b"first")).unwrap();
+        assert_ne!(other_block_key, key);
+        assert_eq!(cache.get(&other_block_key), None);
+        let other_call_key = CallCacheKey::new(block_hash, &call(b"second")).unwrap();
+        assert_ne!(other_call_key, key);
+
+        // Check LRU eviction.
+        cache.insert(other_block_key, vec![]);
+        cache.get(&key).unwrap();
+        cache.insert(other_call_key, vec![]);
+        assert_eq!(cache.get(&other_block_key), None);
+        assert!(cache.get(&key).is_some());
+        assert!(cache.get(&other_call_key).is_some());
+    }
+
+    #[test]
+    fn disabled_call_cache() {
+        let cache = CallResultCache::new(0);
+        let key = CallCacheKey::new(H256::zero(), &call(b"first")).unwrap();
+        cache.insert(key, b"output".to_vec());
+        assert_eq!(cache.get(&key), None);
+    }
+}
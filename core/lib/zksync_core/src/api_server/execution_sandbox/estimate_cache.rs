@@ -0,0 +1,144 @@
+//! Storage cache shared among VM executions within a single gas estimation.
+//!
+//! Gas estimation executes the same transaction many times with different gas limits, and all these executions
+//! read (mostly) the same storage slots and bytecodes. Caching the read data avoids repeatedly querying Postgres
+//! for it, which dominates estimation latency for heavy transactions.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use zksync_types::{MiniblockNumber, StorageKey, H256};
+
+#[derive(Debug, Default)]
+struct CacheState {
+    /// Miniblock the cached state corresponds to.
+    miniblock_number: Option<MiniblockNumber>,
+    values: HashMap<StorageKey, H256>,
+    initial_writes: HashMap<StorageKey, bool>,
+    enumeration_indices: HashMap<StorageKey, Option<u64>>,
+    factory_deps: HashMap<H256, Option<Vec<u8>>>,
+}
+
+/// Read-through cache for the sandbox storage. The cache is bound to the state after a certain miniblock;
+/// if it's used for the state after another miniblock (e.g., because a new miniblock was sealed between executions),
+/// the cache is reset.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EstimationStorageCache(Arc<Mutex<CacheState>>);
+
+impl EstimationStorageCache {
+    /// Binds the cache to the state after the specified miniblock.
+    pub(super) fn bind(&self, miniblock_number: MiniblockNumber) {
+        let mut state = self.0.lock().expect("estimation cache is poisoned");
+        if state.miniblock_number != Some(miniblock_number) {
+            *state = CacheState {
+                miniblock_number: Some(miniblock_number),
+                ..CacheState::default()
+            };
+        }
+    }
+
+    /// Returns the number of cached storage slots.
+    pub fn slot_count(&self) -> usize {
+        self.0
+            .lock()
+            .expect("estimation cache is poisoned")
+            .values
+            .len()
+    }
+
+    pub(super) fn read_value(
+        &self,
+        key: &StorageKey,
+        read: impl FnOnce(&StorageKey) -> H256,
+    ) -> H256 {
+        Self::get_or_insert(&self.0, |state| &mut state.values, *key, || read(key))
+    }
+
+    pub(super) fn is_write_initial(
+        &self,
+        key: &StorageKey,
+        read: impl FnOnce(&StorageKey) -> bool,
+    ) -> bool {
+        Self::get_or_insert(
+            &self.0,
+            |state| &mut state.initial_writes,
+            *key,
+            || read(key),
+        )
+    }
+
+    pub(super) fn get_enumeration_index(
+        &self,
+        key: &StorageKey,
+        read: impl FnOnce(&StorageKey) -> Option<u64>,
+    ) -> Option<u64> {
+        Self::get_or_insert(
+            &self.0,
+            |state| &mut state.enumeration_indices,
+            *key,
+            || read(key),
+        )
+    }
+
+    pub(super) fn load_factory_dep(
+        &self,
+        hash: H256,
+        load: impl FnOnce(H256) -> Option<Vec<u8>>,
+    ) -> Option<Vec<u8>> {
+        Self::get_or_insert(
+            &self.0,
+            |state| &mut state.factory_deps,
+            hash,
+            || load(hash),
+        )
+    }
+
+    fn get_or_insert<K: Eq + std::hash::Hash, V: Clone>(
+        state: &Mutex<CacheState>,
+        map: impl Fn(&mut CacheState) -> &mut HashMap<K, V>,
+        key: K,
+        read: impl FnOnce() -> V,
+    ) -> V {
+        let mut state = state.lock().expect("estimation cache is poisoned");
+        if let Some(value) = map(&mut *state).get(&key) {
+            return value.clone();
+        }
+        // The lock is held while reading from the underlying storage; this is fine since the cache
+        // is only used by sequential executions within a single estimation.
+        let value = read();
+        map(&mut *state).insert(key, value.clone());
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{AccountTreeId, Address};
+
+    use super::*;
+
+    #[test]
+    fn estimation_cache_basics() {
+        let cache = EstimationStorageCache::default();
+        cache.bind(MiniblockNumber(1));
+        let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+
+        let value = cache.read_value(&key, |_| H256::repeat_byte(1));
+        assert_eq!(value, H256::repeat_byte(1));
+        let value = cache.read_value(&key, |_| unreachable!("value should be cached"));
+        assert_eq!(value, H256::repeat_byte(1));
+        assert_eq!(cache.slot_count(), 1);
+
+        // Binding to the same miniblock should retain the cache.
+        cache.bind(MiniblockNumber(1));
+        let value = cache.read_value(&key, |_| unreachable!("value should be cached"));
+        assert_eq!(value, H256::repeat_byte(1));
+
+        cache.bind(MiniblockNumber(2));
+        assert_eq!(cache.slot_count(), 0);
+        let value = cache.read_value(&key, |_| H256::repeat_byte(2));
+        assert_eq!(value, H256::repeat_byte(2));
+    }
+}
@@ -1,22 +1,27 @@
 //! Implementation of "executing" methods, e.g. `eth_call`.
 
+use std::time::Instant;
+
 use anyhow::Context as _;
 use multivm::{
     interface::{TxExecutionMode, VmExecutionResultAndLogs, VmInterface},
-    tracers::StorageInvocations,
+    tracers::{ExecutionDeadline, StorageInvocations},
     vm_latest::constants::ETH_CALL_GAS_LIMIT,
     MultiVMTracer,
 };
 use tracing::{span, Level};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
-    fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, Nonce,
+    fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, MiniblockNumber, Nonce,
     PackedEthSignature, Transaction, U256,
 };
 
 #[cfg(test)]
 use super::testonly::MockTransactionExecutor;
-use super::{apply, vm_metrics, ApiTracer, BlockArgs, TxSharedArgs, VmPermit};
+use super::{
+    apply, vm_metrics, ApiTracer, BlockArgs, EstimationStorageCache, StorageOverrides,
+    TxSharedArgs, VmPermit,
+};
 
 #[derive(Debug)]
 pub(crate) struct TxExecutionArgs {
@@ -25,6 +30,10 @@ pub(crate) struct TxExecutionArgs {
     pub added_balance: U256,
     pub enforced_base_fee: Option<u64>,
     pub missed_storage_invocation_limit: usize,
+    /// If set, the VM execution is aborted once this instant has passed.
+    pub execution_deadline: Option<Instant>,
+    /// Cache for storage reads shared with other executions of the same transaction (e.g., during gas estimation).
+    pub storage_cache: Option<EstimationStorageCache>,
 }
 
 impl TxExecutionArgs {
@@ -35,9 +44,30 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(tx.common_data.fee.max_fee_per_gas.as_u64()),
             missed_storage_invocation_limit: usize::MAX,
+            execution_deadline: None,
+            storage_cache: None,
         }
     }
 
+    /// Arguments to replay transactions from a sealed miniblock. Transactions are executed as-is,
+    /// with the base fee derived from the miniblock fee input, same as in the state keeper.
+    pub fn for_replay(execution_deadline: Instant) -> Self {
+        Self {
+            execution_mode: TxExecutionMode::VerifyExecute,
+            enforced_nonce: None,
+            added_balance: U256::zero(),
+            enforced_base_fee: None,
+            missed_storage_invocation_limit: usize::MAX,
+            execution_deadline: Some(execution_deadline),
+            storage_cache: None,
+        }
+    }
+
+    pub fn is_past_deadline(&self) -> bool {
+        self.execution_deadline
+            .map_or(false, |deadline| Instant::now() >= deadline)
+    }
+
     fn for_eth_call(
         enforced_base_fee: u64,
         vm_execution_cache_misses_limit: Option<usize>,
@@ -49,6 +79,8 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(enforced_base_fee),
             missed_storage_invocation_limit,
+            execution_deadline: None,
+            storage_cache: None,
         }
     }
 
@@ -72,6 +104,8 @@ impl TxExecutionArgs {
             enforced_nonce: tx.nonce(),
             added_balance,
             enforced_base_fee: Some(base_fee),
+            execution_deadline: None,
+            storage_cache: None,
         }
     }
 }
@@ -124,7 +158,10 @@ impl TransactionExecutor {
             .as_ref()
             .map_or(0, |deps| deps.len() as u16);
 
+        // Propagate the tracing span (e.g., with the RPC request ID) to the blocking thread.
+        let parent_span = tracing::Span::current();
         let (published_bytecodes, execution_result) = tokio::task::spawn_blocking(move || {
+            let _parent_span = parent_span.entered();
             let span = span!(Level::DEBUG, "execute_in_sandbox").entered();
             let result = apply::apply_vm_in_sandbox(
                 vm_permit,
@@ -137,10 +174,14 @@ impl TransactionExecutor {
                 |vm, tx| {
                     let storage_invocation_tracer =
                         StorageInvocations::new(execution_args.missed_storage_invocation_limit);
+                    let deadline_tracer = execution_args
+                        .execution_deadline
+                        .map(|deadline| ExecutionDeadline::new(deadline).into_tracer_pointer());
                     let custom_tracers: Vec<_> = custom_tracers
                         .into_iter()
                         .map(|tracer| tracer.into_boxed())
                         .chain(vec![storage_invocation_tracer.into_tracer_pointer()])
+                        .chain(deadline_tracer)
                         .collect();
                     vm.inspect_transaction_with_bytecode_compression(
                         custom_tracers.into(),
@@ -164,6 +205,74 @@ impl TransactionExecutor {
         })
     }
 
+    /// Re-executes transactions from a sealed miniblock specified by `block_args`. `txs` must be a prefix
+    /// of the miniblock transactions, each accompanied by tracers applied to it. If the execution deadline
+    /// is exceeded, the remaining transactions are not executed, so fewer results than `txs` may be returned.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip_all)]
+    pub async fn replay_txs_in_sandbox(
+        &self,
+        vm_permit: VmPermit,
+        shared_args: TxSharedArgs,
+        execution_args: TxExecutionArgs,
+        connection_pool: ConnectionPool,
+        block_args: BlockArgs,
+        txs: Vec<(Transaction, Vec<ApiTracer>)>,
+    ) -> anyhow::Result<Vec<VmExecutionResultAndLogs>> {
+        #[cfg(test)]
+        if let Self::Mock(mock_executor) = self {
+            return txs
+                .iter()
+                .map(|(tx, _)| Ok(mock_executor.execute_tx(tx, &block_args)?.vm))
+                .collect();
+        }
+
+        let miniblock_number = block_args.resolved_block_number();
+        anyhow::ensure!(
+            miniblock_number > MiniblockNumber(0),
+            "genesis miniblock cannot be replayed"
+        );
+        let parent_span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _parent_span = parent_span.entered();
+            let span = span!(Level::DEBUG, "replay_in_sandbox").entered();
+            let result = apply::replay_vm_in_sandbox(
+                vm_permit,
+                shared_args,
+                &execution_args,
+                &connection_pool,
+                miniblock_number,
+                |vm| {
+                    let mut results = Vec::with_capacity(txs.len());
+                    for (tx, custom_tracers) in txs {
+                        let deadline_tracer = execution_args
+                            .execution_deadline
+                            .map(|deadline| ExecutionDeadline::new(deadline).into_tracer_pointer());
+                        let custom_tracers: Vec<_> = custom_tracers
+                            .into_iter()
+                            .map(|tracer| tracer.into_boxed())
+                            .chain(deadline_tracer)
+                            .collect();
+                        let (_, result) = vm.inspect_transaction_with_bytecode_compression(
+                            custom_tracers.into(),
+                            tx,
+                            true,
+                        );
+                        results.push(result);
+                        if execution_args.is_past_deadline() {
+                            break;
+                        }
+                    }
+                    results
+                },
+            );
+            span.exit();
+            result
+        })
+        .await
+        .context("transaction replay panicked")?
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn execute_tx_eth_call(
         &self,
@@ -201,4 +310,152 @@ impl TransactionExecutor {
             .await?;
         Ok(output.vm)
     }
+
+    /// Executes multiple independent calls on the same state, sharing storage reads among them.
+    /// The cache misses limit applies to all calls combined.
+    pub async fn execute_txs_eth_call(
+        &self,
+        vm_permit: VmPermit,
+        shared_args: TxSharedArgs,
+        connection_pool: ConnectionPool,
+        txs: Vec<L2Tx>,
+        block_args: BlockArgs,
+        vm_execution_cache_misses_limit: Option<usize>,
+    ) -> anyhow::Result<Vec<VmExecutionResultAndLogs>> {
+        let txs: Vec<Transaction> = txs
+            .into_iter()
+            .map(|mut tx| {
+                if tx.common_data.signature.is_empty() {
+                    tx.common_data.signature =
+                        PackedEthSignature::default().serialize_packed().into();
+                }
+                // Same protection against infinite-loop calls as in `execute_tx_eth_call()`.
+                tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
+                tx.into()
+            })
+            .collect();
+
+        #[cfg(test)]
+        if let Self::Mock(mock_executor) = self {
+            return txs
+                .iter()
+                .map(|tx| Ok(mock_executor.execute_tx(tx, &block_args)?.vm))
+                .collect();
+        }
+
+        // The base fee is overridden for each call.
+        let execution_args = TxExecutionArgs::for_eth_call(0, vm_execution_cache_misses_limit);
+        let parent_span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _parent_span = parent_span.entered();
+            let span = span!(Level::DEBUG, "execute_calls_in_sandbox").entered();
+            let result = apply::apply_vm_in_sandbox_for_calls(
+                vm_permit,
+                shared_args,
+                &execution_args,
+                &connection_pool,
+                txs,
+                block_args,
+                |vm, tx| {
+                    let storage_invocation_tracer =
+                        StorageInvocations::new(execution_args.missed_storage_invocation_limit);
+                    let (_, result) = vm.inspect_transaction_with_bytecode_compression(
+                        vec![storage_invocation_tracer.into_tracer_pointer()].into(),
+                        tx,
+                        true,
+                    );
+                    result
+                },
+            );
+            span.exit();
+            result
+        })
+        .await
+        .context("calls execution panicked")?
+    }
+
+    /// Executes calls sequentially on top of the state with `storage_overrides` applied, so that each call observes
+    /// changes made by the previous ones. Each call is accompanied by tracers applied to it. The base fee is set
+    /// to the minimum `max_fee_per_gas` among calls, so that it's acceptable for all of them. If the execution deadline
+    /// is exceeded, the remaining calls are not executed, so fewer results than `txs` may be returned.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute_bundle_in_sandbox(
+        &self,
+        vm_permit: VmPermit,
+        shared_args: TxSharedArgs,
+        connection_pool: ConnectionPool,
+        txs: Vec<(L2Tx, Vec<ApiTracer>)>,
+        block_args: BlockArgs,
+        storage_overrides: StorageOverrides,
+        vm_execution_cache_misses_limit: Option<usize>,
+    ) -> anyhow::Result<Vec<VmExecutionResultAndLogs>> {
+        let enforced_base_fee = txs
+            .iter()
+            .map(|(tx, _)| tx.common_data.fee.max_fee_per_gas.as_u64())
+            .min()
+            .unwrap_or(0);
+        let (txs, custom_tracers): (Vec<Transaction>, Vec<_>) = txs
+            .into_iter()
+            .map(|(mut tx, custom_tracers)| {
+                if tx.common_data.signature.is_empty() {
+                    tx.common_data.signature =
+                        PackedEthSignature::default().serialize_packed().into();
+                }
+                // Same protection against infinite-loop calls as in `execute_tx_eth_call()`.
+                tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
+                (tx.into(), custom_tracers)
+            })
+            .unzip();
+
+        #[cfg(test)]
+        if let Self::Mock(mock_executor) = self {
+            return txs
+                .iter()
+                .map(|tx| Ok(mock_executor.execute_tx(tx, &block_args)?.vm))
+                .collect();
+        }
+
+        let execution_args =
+            TxExecutionArgs::for_eth_call(enforced_base_fee, vm_execution_cache_misses_limit);
+        let parent_span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _parent_span = parent_span.entered();
+            let span = span!(Level::DEBUG, "execute_bundle_in_sandbox").entered();
+            let mut custom_tracers = custom_tracers.into_iter();
+            let result = apply::apply_vm_in_sandbox_for_bundle(
+                vm_permit,
+                shared_args,
+                &execution_args,
+                &connection_pool,
+                txs,
+                block_args,
+                storage_overrides,
+                |vm, tx| {
+                    let storage_invocation_tracer =
+                        StorageInvocations::new(execution_args.missed_storage_invocation_limit);
+                    let deadline_tracer = execution_args
+                        .execution_deadline
+                        .map(|deadline| ExecutionDeadline::new(deadline).into_tracer_pointer());
+                    let tx_tracers: Vec<_> = custom_tracers
+                        .next()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|tracer| tracer.into_boxed())
+                        .chain(vec![storage_invocation_tracer.into_tracer_pointer()])
+                        .chain(deadline_tracer)
+                        .collect();
+                    let (_, result) = vm.inspect_transaction_with_bytecode_compression(
+                        tx_tracers.into(),
+                        tx,
+                        true,
+                    );
+                    result
+                },
+            );
+            span.exit();
+            result
+        })
+        .await
+        .context("bundle execution panicked")?
+    }
 }
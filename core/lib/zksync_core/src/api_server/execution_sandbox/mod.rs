@@ -3,7 +3,9 @@ use std::{sync::Arc, time::Duration};
 use anyhow::Context as _;
 use tokio::runtime::Handle;
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_state::{PostgresStorage, PostgresStorageCaches, ReadStorage, StorageView};
+use zksync_state::{
+    PostgresStorage, PostgresStorageCaches, ReadStorage, RocksdbArchive, StorageView,
+};
 use zksync_system_constants::PUBLISH_BYTECODE_OVERHEAD;
 use zksync_types::{
     api, fee_model::BatchFeeInput, AccountTreeId, L1BatchNumber, L2ChainId, MiniblockNumber,
@@ -12,8 +14,11 @@ use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
 
 use self::vm_metrics::SandboxStage;
 pub(super) use self::{
+    call_cache::CallResultCache,
     error::SandboxExecutionError,
+    estimate_cache::EstimationStorageCache,
     execute::{TransactionExecutor, TxExecutionArgs},
+    overrides::StorageOverrides,
     tracers::ApiTracer,
     validate::ValidationError,
     vm_metrics::{SubmitTxStage, SANDBOX_METRICS},
@@ -22,8 +27,11 @@ use super::tx_sender::MultiVMBaseSystemContracts;
 
 // Note: keep the modules private, and instead re-export functions that make public interface.
 mod apply;
+mod call_cache;
 mod error;
+mod estimate_cache;
 mod execute;
+mod overrides;
 #[cfg(test)]
 pub(super) mod testonly;
 #[cfg(test)]
@@ -182,7 +190,9 @@ pub(super) async fn get_pubdata_for_factory_deps(
     let rt_handle = Handle::current();
     let connection_pool = connection_pool.clone();
     let factory_deps = factory_deps.to_vec();
+    let parent_span = tracing::Span::current();
     tokio::task::spawn_blocking(move || {
+        let _parent_span = parent_span.entered();
         let connection = rt_handle
             .block_on(connection_pool.access_storage_tagged("api"))
             .context("failed acquiring DB connection")?;
@@ -215,6 +225,8 @@ pub(crate) struct TxSharedArgs {
     pub fee_input: BatchFeeInput,
     pub base_system_contracts: MultiVMBaseSystemContracts,
     pub caches: PostgresStorageCaches,
+    /// Archived state used for executions in historical blocks instead of Postgres, if available.
+    pub archive_state: Option<RocksdbArchive>,
     pub validation_computational_gas_limit: u32,
     pub chain_id: L2ChainId,
 }
@@ -227,6 +239,7 @@ impl TxSharedArgs {
             fee_input: BatchFeeInput::l1_pegged(55, 555),
             base_system_contracts,
             caches: PostgresStorageCaches::new(1, 1),
+            archive_state: None,
             validation_computational_gas_limit: u32::MAX,
             chain_id: L2ChainId::default(),
         }
@@ -275,6 +288,36 @@ impl BlockStartInfo {
             _ => Ok(()),
         }
     }
+
+    /// Returns the first miniblock for which storage logs and events are retained. Unlike [`Self::first_miniblock`],
+    /// this accounts for L1 batches pruned by the DB pruner, so the returned value can grow over time
+    /// and is loaded from storage on each call.
+    pub async fn first_miniblock_with_state(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<MiniblockNumber> {
+        let pruning_info = storage
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .context("failed getting pruning info")?;
+        Ok(pruning_info.map_or(self.first_miniblock, |info| {
+            self.first_miniblock.max(info.last_pruned_miniblock + 1)
+        }))
+    }
+
+    /// Checks whether storage logs and events for the specified miniblock are pruned and returns an error if they are.
+    pub async fn ensure_state_not_pruned(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        number: MiniblockNumber,
+    ) -> Result<(), BlockArgsError> {
+        let first_miniblock = self.first_miniblock_with_state(storage).await?;
+        if number < first_miniblock {
+            return Err(BlockArgsError::Pruned(first_miniblock));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -330,6 +373,10 @@ impl BlockArgs {
         let Some(resolved_block_number) = resolved_block_number else {
             return Err(BlockArgsError::Missing);
         };
+        // The VM reads historical storage for the block, which is unavailable if the block was pruned after startup.
+        start_info
+            .ensure_state_not_pruned(connection, resolved_block_number)
+            .await?;
 
         let l1_batch = connection
             .storage_web3_dal()
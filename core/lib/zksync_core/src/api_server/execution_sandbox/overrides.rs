@@ -0,0 +1,100 @@
+//! State overrides for sandboxed execution.
+
+use std::collections::HashMap;
+
+use zksync_types::{
+    api::StateOverride, get_code_key, get_known_code_key, utils::storage_key_for_eth_balance,
+    AccountTreeId, StorageKey, H256, U256,
+};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode, InvalidBytecodeError},
+    u256_to_h256,
+};
+
+/// Storage slots and bytecodes overridden for sandboxed execution. Overrides are applied on top
+/// of the sandbox storage, so they are visible to all transactions executed in the sandbox.
+#[derive(Debug, Default)]
+pub(crate) struct StorageOverrides {
+    values: HashMap<StorageKey, H256>,
+    factory_deps: HashMap<H256, Vec<u8>>,
+}
+
+impl StorageOverrides {
+    /// Converts API state overrides into storage overrides. Errors if an overridden bytecode is invalid.
+    pub fn new(state_override: &StateOverride) -> Result<Self, InvalidBytecodeError> {
+        let mut this = Self::default();
+        for (address, account_override) in state_override {
+            if let Some(balance) = account_override.balance {
+                let balance_key = storage_key_for_eth_balance(address);
+                this.values.insert(balance_key, u256_to_h256(balance));
+            }
+            if let Some(code) = &account_override.code {
+                validate_bytecode(&code.0)?;
+                let bytecode_hash = hash_bytecode(&code.0);
+                this.values.insert(get_code_key(address), bytecode_hash);
+                this.values.insert(
+                    get_known_code_key(&bytecode_hash),
+                    u256_to_h256(U256::one()),
+                );
+                this.factory_deps.insert(bytecode_hash, code.0.clone());
+            }
+            if let Some(state_diff) = &account_override.state_diff {
+                let account = AccountTreeId::new(*address);
+                for (&slot, &value) in state_diff {
+                    this.values.insert(StorageKey::new(account, slot), value);
+                }
+            }
+        }
+        Ok(this)
+    }
+
+    pub fn read_value(&self, key: &StorageKey) -> Option<H256> {
+        self.values.get(key).copied()
+    }
+
+    pub fn load_factory_dep(&self, hash: H256) -> Option<Vec<u8>> {
+        self.factory_deps.get(&hash).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_types::{api::AccountOverride, Address};
+
+    use super::*;
+
+    #[test]
+    fn converting_state_overrides() {
+        let address = Address::repeat_byte(1);
+        let code = vec![1_u8; 32];
+        let account_override = AccountOverride {
+            balance: Some(U256::from(1_000)),
+            code: Some(code.clone().into()),
+            state_diff: Some(HashMap::from([(H256::zero(), H256::repeat_byte(2))])),
+        };
+        let state_override = StateOverride::from([(address, account_override)]);
+        let overrides = StorageOverrides::new(&state_override).unwrap();
+
+        let balance = overrides.read_value(&storage_key_for_eth_balance(&address));
+        assert_eq!(balance, Some(u256_to_h256(1_000.into())));
+        let bytecode_hash = overrides.read_value(&get_code_key(&address)).unwrap();
+        assert_eq!(bytecode_hash, hash_bytecode(&code));
+        let is_known = overrides.read_value(&get_known_code_key(&bytecode_hash));
+        assert_eq!(is_known, Some(u256_to_h256(U256::one())));
+        assert_eq!(overrides.load_factory_dep(bytecode_hash), Some(code));
+        let slot_key = StorageKey::new(AccountTreeId::new(address), H256::zero());
+        assert_eq!(overrides.read_value(&slot_key), Some(H256::repeat_byte(2)));
+    }
+
+    #[test]
+    fn invalid_bytecode_override() {
+        let account_override = AccountOverride {
+            code: Some(vec![1_u8; 64].into()),
+            ..AccountOverride::default()
+        };
+        let state_override = StateOverride::from([(Address::repeat_byte(1), account_override)]);
+        let err = StorageOverrides::new(&state_override).unwrap_err();
+        assert_matches!(err, InvalidBytecodeError::BytecodeLengthInWordsIsEven);
+    }
+}
@@ -59,7 +59,9 @@ impl TransactionExecutor {
         let execution_args = TxExecutionArgs::for_validation(&tx);
         let tx: Transaction = tx.into();
 
+        let parent_span = tracing::Span::current();
         let validation_result = tokio::task::spawn_blocking(move || {
+            let _parent_span = parent_span.entered();
             let span = tracing::debug_span!("validate_in_sandbox").entered();
             let result = apply::apply_vm_in_sandbox(
                 vm_permit,
@@ -1,5 +1,6 @@
 // Everywhere in this module the word "block" actually means "miniblock".
 
+pub mod archive_state;
 pub mod contract_verification;
 pub mod execution_sandbox;
 pub mod healthcheck;
@@ -5,6 +5,7 @@ use std::net::Ipv4Addr;
 use assert_matches::assert_matches;
 use tempfile::TempDir;
 use zksync_dal::ConnectionPool;
+use zksync_types::api;
 
 use super::*;
 use crate::metadata_calculator::tests::{
@@ -33,17 +34,15 @@ async fn merkle_tree_api() {
     let api_client = TreeApiHttpClient::new(&format!("http://{local_addr}"));
 
     // Wait until the calculator processes initial L1 batches.
-    calculator_task.await.unwrap();
+    let root_hash = calculator_task.await.unwrap();
 
     // Query the API.
     let tree_info = api_client.get_info().await.unwrap();
     assert!(tree_info.leaf_count > 20);
     assert_eq!(tree_info.next_l1_batch_number, L1BatchNumber(6));
 
-    let mut hashed_keys: Vec<_> = gen_storage_logs(20..30, 1)[0]
-        .iter()
-        .map(|log| log.key.hashed_key_u256())
-        .collect();
+    let logs = gen_storage_logs(20..30, 1).pop().unwrap();
+    let mut hashed_keys: Vec<_> = logs.iter().map(|log| log.key.hashed_key_u256()).collect();
     // Extend with some non-existing keys.
     hashed_keys.extend((0_u8..10).map(|byte| U256::from_big_endian(&[byte; 32])));
 
@@ -52,12 +51,23 @@ async fn merkle_tree_api() {
         .await
         .unwrap();
     assert_eq!(proofs.len(), 20);
-    for (i, proof) in proofs.into_iter().enumerate() {
+    for (i, proof) in proofs.iter().enumerate() {
         let should_be_present = i < 10;
         assert_eq!(proof.index == 0, !should_be_present);
         assert!(!proof.merkle_path.is_empty());
     }
 
+    // Check that proofs can be verified by light clients.
+    for (log, proof) in logs.iter().zip(proofs) {
+        let api_proof = api::StorageProof {
+            key: *log.key.key(),
+            proof: proof.merkle_path,
+            value: proof.value,
+            index: proof.index,
+        };
+        api_proof.verify(*log.key.address(), root_hash).unwrap();
+    }
+
     let err = api_client
         .get_proofs(L1BatchNumber(10), vec![])
         .await
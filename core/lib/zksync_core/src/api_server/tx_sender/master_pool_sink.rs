@@ -1,5 +1,6 @@
+use anyhow::Context as _;
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
-use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx};
+use zksync_types::{api::TransactionConditions, fee::TransactionExecutionMetrics, l2::L2Tx};
 
 use super::{tx_sink::TxSink, SubmitTxError};
 use crate::metrics::{TxStage, APP_METRICS};
@@ -8,11 +9,26 @@ use crate::metrics::{TxStage, APP_METRICS};
 #[derive(Debug)]
 pub struct MasterPoolSink {
     master_pool: ConnectionPool,
+    /// Minimum percentage by which a transaction must raise `max_fee_per_gas` to replace a pending transaction.
+    replacement_fee_bump_percent: u32,
 }
 
 impl MasterPoolSink {
-    pub fn new(master_pool: ConnectionPool) -> Self {
-        Self { master_pool }
+    pub fn new(master_pool: ConnectionPool, replacement_fee_bump_percent: u32) -> Self {
+        Self {
+            master_pool,
+            replacement_fee_bump_percent,
+        }
+    }
+
+    fn check_submission_result(
+        result: L2TxSubmissionResult,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        if let L2TxSubmissionResult::ReplacementUnderpriced(min_max_fee_per_gas) = result {
+            return Err(SubmitTxError::ReplacementUnderpriced(min_max_fee_per_gas));
+        }
+        APP_METRICS.processed_txs[&TxStage::Mempool(result)].inc();
+        Ok(result)
     }
 }
 
@@ -22,16 +38,52 @@ impl TxSink for MasterPoolSink {
         &self,
         tx: L2Tx,
         execution_metrics: TransactionExecutionMetrics,
+        conditions: Option<&TransactionConditions>,
     ) -> Result<L2TxSubmissionResult, SubmitTxError> {
-        let submission_res_handle = self
-            .master_pool
-            .access_storage_tagged("api")
-            .await?
-            .transactions_dal()
-            .insert_transaction_l2(tx, execution_metrics)
-            .await;
+        let mut storage = self.master_pool.access_storage_tagged("api").await?;
+        let Some(conditions) = conditions else {
+            let submission_res_handle = storage
+                .transactions_dal()
+                .insert_transaction_l2_with_fee_bump(
+                    tx,
+                    execution_metrics,
+                    self.replacement_fee_bump_percent,
+                )
+                .await
+                .context("failed inserting L2 transaction")?;
+            return Self::check_submission_result(submission_res_handle);
+        };
 
-        APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
-        Ok(submission_res_handle)
+        // Conditions must be inserted atomically with the transaction; otherwise, the transaction
+        // may be loaded into the mempool without them.
+        let tx_hash = tx.hash();
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .context("failed starting DB transaction")?;
+        let submission_res_handle = transaction
+            .transactions_dal()
+            .insert_transaction_l2_with_fee_bump(
+                tx,
+                execution_metrics,
+                self.replacement_fee_bump_percent,
+            )
+            .await
+            .context("failed inserting L2 transaction")?;
+        if matches!(
+            submission_res_handle,
+            L2TxSubmissionResult::Added | L2TxSubmissionResult::Replaced
+        ) {
+            transaction
+                .transactions_dal()
+                .insert_transaction_conditions(tx_hash, conditions)
+                .await
+                .context("failed inserting transaction conditions")?;
+        }
+        transaction
+            .commit()
+            .await
+            .context("failed committing DB transaction")?;
+        Self::check_submission_result(submission_res_handle)
     }
 }
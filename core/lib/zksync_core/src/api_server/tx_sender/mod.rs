@@ -1,6 +1,11 @@
 //! Helper module to submit transactions into the zkSync Network.
 
-use std::{cmp, sync::Arc, time::Instant};
+use std::{
+    cmp,
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::Instant,
+};
 
 use anyhow::Context as _;
 use multivm::{
@@ -8,36 +13,40 @@ use multivm::{
     utils::{adjust_pubdata_price_for_tx, derive_base_fee_and_gas_per_pubdata, derive_overhead},
     vm_latest::constants::BLOCK_GAS_LIMIT,
 };
+use once_cell::sync::OnceCell;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, StorageProcessor};
-use zksync_state::PostgresStorageCaches;
+use zksync_state::{PostgresStorageCaches, RocksdbArchive};
 use zksync_types::{
+    api::TransactionConditions,
     fee::{Fee, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
     get_code_key, get_intrinsic_constants,
     l1::is_l1_tx_type,
     l2::{error::TxCheckError::TxDuplication, L2Tx},
     utils::storage_key_for_eth_balance,
+    vm_trace::Call,
+    web3::types::{AccessList, AccessListItem},
     AccountTreeId, Address, ExecuteTransactionCommon, L2ChainId, MiniblockNumber, Nonce,
-    PackedEthSignature, ProtocolVersionId, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
-    MAX_NEW_FACTORY_DEPS, U256,
+    PackedEthSignature, ProtocolVersionId, StorageKey, StorageLogQuery, Transaction, VmVersion,
+    H160, H256, MAX_L2_TX_GAS_LIMIT, MAX_NEW_FACTORY_DEPS, U256,
 };
-use zksync_utils::h256_to_u256;
+use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
 
-pub(super) use self::result::SubmitTxError;
+pub(super) use self::result::{ApiCallResult, SubmitTxError};
 use self::tx_sink::TxSink;
 use crate::{
-    api_server::{
-        execution_sandbox::{
-            get_pubdata_for_factory_deps, BlockArgs, BlockStartInfo, SubmitTxStage,
-            TransactionExecutor, TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit,
-            SANDBOX_METRICS,
-        },
-        tx_sender::result::ApiCallResult,
+    api_server::execution_sandbox::{
+        get_pubdata_for_factory_deps, ApiTracer, BlockArgs, BlockStartInfo, CallResultCache,
+        EstimationStorageCache, StorageOverrides, SubmitTxStage, TransactionExecutor,
+        TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit, SANDBOX_METRICS,
     },
     fee_model::BatchFeeModelInputProvider,
-    state_keeper::seal_criteria::{ConditionalSealer, NoopSealer, SealData},
+    state_keeper::{
+        seal_criteria::{ConditionalSealer, NoopSealer, SealData},
+        tx_filter::{NoopTransactionFilter, TransactionFilter, TxLimits},
+    },
     utils::pending_protocol_version,
 };
 
@@ -48,6 +57,9 @@ mod result;
 pub(crate) mod tests;
 pub mod tx_sink;
 
+/// Maximum number of storage slots checked by conditions of a single transaction.
+const MAX_CONDITIONS_STORAGE_SLOTS: usize = 1_000;
+
 #[derive(Debug, Clone)]
 pub struct MultiVMBaseSystemContracts {
     /// Contracts to be used for pre-virtual-blocks protocol versions.
@@ -150,6 +162,12 @@ pub struct TxSenderBuilder {
     tx_sink: Arc<dyn TxSink>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Option<Arc<dyn ConditionalSealer>>,
+    /// Filter used to reject transactions on submission.
+    tx_filter: Option<Arc<dyn TransactionFilter>>,
+    /// Per-transaction limits checked on submission.
+    tx_limits: TxLimits,
+    /// Archived state used to serve requests for historical blocks.
+    archive_state: Option<RocksdbArchive>,
 }
 
 impl TxSenderBuilder {
@@ -163,6 +181,9 @@ impl TxSenderBuilder {
             replica_connection_pool,
             tx_sink,
             sealer: None,
+            tx_filter: None,
+            tx_limits: TxLimits::default(),
+            archive_state: None,
         }
     }
 
@@ -171,6 +192,21 @@ impl TxSenderBuilder {
         self
     }
 
+    pub fn with_tx_filter(mut self, tx_filter: Arc<dyn TransactionFilter>) -> Self {
+        self.tx_filter = Some(tx_filter);
+        self
+    }
+
+    pub fn with_tx_limits(mut self, tx_limits: TxLimits) -> Self {
+        self.tx_limits = tx_limits;
+        self
+    }
+
+    pub fn with_archive_state(mut self, archive_state: RocksdbArchive) -> Self {
+        self.archive_state = Some(archive_state);
+        self
+    }
+
     pub async fn build(
         self,
         batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
@@ -180,7 +216,12 @@ impl TxSenderBuilder {
     ) -> TxSender {
         // Use noop sealer if no sealer was explicitly provided.
         let sealer = self.sealer.unwrap_or_else(|| Arc::new(NoopSealer));
+        // Use noop filter if no filter was explicitly provided.
+        let tx_filter = self
+            .tx_filter
+            .unwrap_or_else(|| Arc::new(NoopTransactionFilter));
 
+        let call_cache = CallResultCache::new(self.config.eth_call_cache_size);
         TxSender(Arc::new(TxSenderInner {
             sender_config: self.config,
             tx_sink: self.tx_sink,
@@ -189,8 +230,12 @@ impl TxSenderBuilder {
             api_contracts,
             vm_concurrency_limiter,
             storage_caches,
+            archive_state: self.archive_state,
             sealer,
+            tx_filter,
+            tx_limits: self.tx_limits,
             executor: TransactionExecutor::Real,
+            call_cache,
         }))
     }
 }
@@ -210,6 +255,8 @@ pub struct TxSenderConfig {
     pub l1_to_l2_transactions_compatibility_mode: bool,
     pub chain_id: L2ChainId,
     pub max_pubdata_per_batch: u64,
+    /// Maximum number of `eth_call` results cached for sealed miniblocks. 0 disables the cache.
+    pub eth_call_cache_size: usize,
 }
 
 impl TxSenderConfig {
@@ -230,6 +277,7 @@ impl TxSenderConfig {
                 .l1_to_l2_transactions_compatibility_mode,
             chain_id,
             max_pubdata_per_batch: state_keeper_config.max_pubdata_per_batch,
+            eth_call_cache_size: web3_json_config.eth_call_cache_size(),
         }
     }
 }
@@ -246,9 +294,17 @@ pub struct TxSenderInner {
     pub(super) vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     // Caches used in VM execution.
     storage_caches: PostgresStorageCaches,
+    /// Archived state used to serve requests for historical blocks.
+    archive_state: Option<RocksdbArchive>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Arc<dyn ConditionalSealer>,
+    /// Filter used to reject transactions on submission.
+    tx_filter: Arc<dyn TransactionFilter>,
+    /// Per-transaction limits checked on submission.
+    tx_limits: TxLimits,
     pub(super) executor: TransactionExecutor,
+    /// Cache for `eth_call` results on top of sealed miniblocks.
+    call_cache: CallResultCache,
 }
 
 #[derive(Clone)]
@@ -269,6 +325,10 @@ impl TxSender {
         self.0.storage_caches.clone()
     }
 
+    pub(crate) fn archive_state(&self) -> Option<&RocksdbArchive> {
+        self.0.archive_state.as_ref()
+    }
+
     async fn acquire_replica_connection(&self) -> anyhow::Result<StorageProcessor<'_>> {
         self.0
             .replica_connection_pool
@@ -279,6 +339,26 @@ impl TxSender {
 
     #[tracing::instrument(skip(self, tx))]
     pub async fn submit_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        self.submit_tx_inner(tx, None).await
+    }
+
+    /// Submits a transaction with conditions that must hold both now and immediately before the transaction
+    /// is executed by the state keeper (`eth_sendRawTransactionConditional`).
+    #[tracing::instrument(skip(self, tx, conditions))]
+    pub async fn submit_conditional_tx(
+        &self,
+        tx: L2Tx,
+        conditions: TransactionConditions,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        self.check_tx_conditions(&conditions).await?;
+        self.submit_tx_inner(tx, Some(&conditions)).await
+    }
+
+    async fn submit_tx_inner(
+        &self,
+        tx: L2Tx,
+        conditions: Option<&TransactionConditions>,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
         let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::Validate].start();
         self.validate_tx(&tx).await?;
         stage_latency.observe();
@@ -345,7 +425,7 @@ impl TxSender {
         let submission_res_handle = self
             .0
             .tx_sink
-            .submit_tx(tx, execution_output.metrics)
+            .submit_tx(tx, execution_output.metrics, conditions)
             .await?;
 
         match submission_res_handle {
@@ -376,12 +456,60 @@ impl TxSender {
         }
     }
 
+    /// Checks transaction conditions against the latest sealed state. Since the transaction can only be included
+    /// into one of the following miniblocks, block number conditions are checked for the next miniblock.
+    async fn check_tx_conditions(
+        &self,
+        conditions: &TransactionConditions,
+    ) -> Result<(), SubmitTxError> {
+        if conditions.has_storage_roots() {
+            return Err(SubmitTxError::InvalidConditions(
+                "storage root conditions are not supported".to_owned(),
+            ));
+        }
+        let slot_count = conditions.slot_count();
+        if slot_count > MAX_CONDITIONS_STORAGE_SLOTS {
+            return Err(SubmitTxError::InvalidConditions(format!(
+                "too many storage slots: {slot_count}, max allowed: {MAX_CONDITIONS_STORAGE_SLOTS}"
+            )));
+        }
+
+        let mut connection = self.acquire_replica_connection().await?;
+        let sealed_miniblock_number = connection
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("failed getting sealed miniblock number")?
+            .unwrap_or_default();
+        conditions.check_block(sealed_miniblock_number + 1, seconds_since_epoch())?;
+
+        let keys: Vec<_> = conditions.storage_slots().map(|(key, _)| key).collect();
+        if !keys.is_empty() {
+            let hashed_keys: Vec<_> = keys.iter().map(StorageKey::hashed_key).collect();
+            let mut values = connection
+                .storage_web3_dal()
+                .get_values(&hashed_keys)
+                .await
+                .context("failed getting storage values")?;
+            let values = keys
+                .into_iter()
+                .map(|key| {
+                    let value = values.remove(&key.hashed_key()).unwrap_or_default();
+                    (key, value)
+                })
+                .collect();
+            conditions.check_storage(&values)?;
+        }
+        Ok(())
+    }
+
     async fn shared_args(&self) -> TxSharedArgs {
         TxSharedArgs {
             operator_account: AccountTreeId::new(self.0.sender_config.fee_account_addr),
             fee_input: self.0.batch_fee_input_provider.get_batch_fee_input().await,
             base_system_contracts: self.0.api_contracts.eth_call.clone(),
             caches: self.storage_caches(),
+            archive_state: self.0.archive_state.clone(),
             validation_computational_gas_limit: self
                 .0
                 .sender_config
@@ -391,6 +519,13 @@ impl TxSender {
     }
 
     async fn validate_tx(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let transaction: Transaction = tx.clone().into();
+        self.0
+            .tx_filter
+            .check(&transaction)
+            .map_err(SubmitTxError::TransactionFiltered)?;
+        self.0.tx_limits.check_limits(&transaction)?;
+
         let max_gas = U256::from(u32::MAX);
         if tx.common_data.fee.gas_limit > max_gas
             || tx.common_data.fee.gas_per_pubdata_limit > max_gas
@@ -559,6 +694,7 @@ impl TxSender {
         block_args: BlockArgs,
         base_fee: u64,
         vm_version: VmVersion,
+        storage_cache: &EstimationStorageCache,
     ) -> anyhow::Result<(VmExecutionResultAndLogs, TransactionExecutionMetrics)> {
         let gas_limit_with_overhead = tx_gas_limit
             + derive_overhead(
@@ -591,8 +727,9 @@ impl TxSender {
 
         let shared_args = self.shared_args_for_gas_estimate(fee_model_params);
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        let execution_args =
+        let mut execution_args =
             TxExecutionArgs::for_gas_estimate(vm_execution_cache_misses_limit, &tx, base_fee);
+        execution_args.storage_cache = Some(storage_cache.clone());
         let execution_output = self
             .0
             .executor
@@ -620,6 +757,7 @@ impl TxSender {
             validation_computational_gas_limit: BLOCK_GAS_LIMIT,
             base_system_contracts: self.0.api_contracts.estimate_gas.clone(),
             caches: self.storage_caches(),
+            archive_state: self.0.archive_state.clone(),
             chain_id: config.chain_id,
         }
     }
@@ -751,6 +889,9 @@ impl TxSender {
             estimation_started_at.elapsed(),
         );
 
+        // Storage reads are shared among all executions, so that the transaction doesn't re-read the same data
+        // from Postgres on each iteration.
+        let storage_cache = EstimationStorageCache::default();
         let mut number_of_iterations = 0usize;
         while lower_bound + acceptable_overestimation < upper_bound {
             let mid = (lower_bound + upper_bound) / 2;
@@ -769,6 +910,7 @@ impl TxSender {
                     block_args,
                     base_fee,
                     protocol_version.into(),
+                    &storage_cache,
                 )
                 .await
                 .context("estimate_gas step failed")?;
@@ -792,6 +934,10 @@ impl TxSender {
         SANDBOX_METRICS
             .estimate_gas_binary_search_iterations
             .observe(number_of_iterations);
+        tracing::trace!(
+            "fee estimation tx {tx_id:?}: binary search finished; {} storage slots cached",
+            storage_cache.slot_count()
+        );
 
         let tx_body_gas_limit = cmp::min(
             MAX_L2_TX_GAS_LIMIT as u32,
@@ -809,6 +955,7 @@ impl TxSender {
                 block_args,
                 base_fee,
                 protocol_version.into(),
+                &storage_cache,
             )
             .await
             .context("final estimate_gas step failed")?;
@@ -864,11 +1011,21 @@ impl TxSender {
         block_args: BlockArgs,
         tx: L2Tx,
     ) -> Result<Vec<u8>, SubmitTxError> {
+        let cache_key = self
+            .0
+            .call_cache
+            .key(&self.0.replica_connection_pool, &block_args, &tx)
+            .await?;
+        if let Some(output) = cache_key.and_then(|key| self.0.call_cache.get(&key)) {
+            return Ok(output);
+        }
+
         let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
         let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
 
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        self.0
+        let output = self
+            .0
             .executor
             .execute_tx_eth_call(
                 vm_permit,
@@ -880,7 +1037,116 @@ impl TxSender {
                 vec![],
             )
             .await?
-            .into_api_call_result()
+            .into_api_call_result()?;
+        if let Some(key) = cache_key {
+            self.0.call_cache.insert(key, output.clone());
+        }
+        Ok(output)
+    }
+
+    /// Executes independent calls on the same state. Returns the result for each call; if the call cache misses limit
+    /// is configured, it applies to all calls combined.
+    pub(super) async fn eth_call_batch(
+        &self,
+        block_args: BlockArgs,
+        txs: Vec<L2Tx>,
+    ) -> Result<Vec<Result<Vec<u8>, SubmitTxError>>, SubmitTxError> {
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+
+        let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        let results = self
+            .0
+            .executor
+            .execute_txs_eth_call(
+                vm_permit,
+                self.shared_args().await,
+                self.0.replica_connection_pool.clone(),
+                txs,
+                block_args,
+                vm_execution_cache_misses_limit,
+            )
+            .await?;
+        Ok(results
+            .into_iter()
+            .map(ApiCallResult::into_api_call_result)
+            .collect())
+    }
+
+    /// Executes calls sequentially on top of the state with `storage_overrides` applied, so that each call observes
+    /// changes made by the previous ones. Returns the VM output together with the call trace for each executed call.
+    pub(super) async fn simulate_bundle(
+        &self,
+        block_args: BlockArgs,
+        txs: Vec<L2Tx>,
+        storage_overrides: StorageOverrides,
+    ) -> Result<Vec<(VmExecutionResultAndLogs, Vec<Call>)>, SubmitTxError> {
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+
+        let call_tracer_results: Vec<_> = txs.iter().map(|_| Arc::new(OnceCell::new())).collect();
+        let txs = txs
+            .into_iter()
+            .zip(&call_tracer_results)
+            .map(|(tx, result)| (tx, vec![ApiTracer::CallTracer(result.clone())]))
+            .collect();
+        let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        let results = self
+            .0
+            .executor
+            .execute_bundle_in_sandbox(
+                vm_permit,
+                self.shared_args().await,
+                self.0.replica_connection_pool.clone(),
+                txs,
+                block_args,
+                storage_overrides,
+                vm_execution_cache_misses_limit,
+            )
+            .await?;
+
+        Ok(results
+            .into_iter()
+            .zip(call_tracer_results)
+            .map(|(result, calls)| {
+                let calls = Arc::try_unwrap(calls)
+                    .ok()
+                    .and_then(OnceCell::into_inner)
+                    .unwrap_or_default();
+                (result, calls)
+            })
+            .collect())
+    }
+
+    /// Executes a call and returns all contracts and storage slots accessed by it as an access list.
+    pub(super) async fn create_access_list(
+        &self,
+        block_args: BlockArgs,
+        tx: L2Tx,
+    ) -> Result<AccessList, SubmitTxError> {
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+
+        let call_tracer_result = Arc::new(OnceCell::default());
+        let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        let result = self
+            .0
+            .executor
+            .execute_tx_eth_call(
+                vm_permit,
+                self.shared_args().await,
+                self.0.replica_connection_pool.clone(),
+                tx,
+                block_args,
+                vm_execution_cache_misses_limit,
+                vec![ApiTracer::CallTracer(call_tracer_result.clone())],
+            )
+            .await?;
+
+        let calls = call_tracer_result.get().map_or(&[][..], Vec::as_slice);
+        let access_list = build_access_list(calls, &result.logs.storage_logs);
+        result.into_api_call_result()?;
+        Ok(access_list)
     }
 
     pub async fn gas_price(&self) -> anyhow::Result<u64> {
@@ -976,3 +1242,34 @@ fn derive_pessimistic_overhead(
         current_overhead
     }
 }
+
+/// Builds an access list from the calls and storage logs produced by a transaction. Contracts called by the transaction
+/// are included even if the transaction hasn't accessed their storage. Items are sorted by address and storage key.
+fn build_access_list(calls: &[Call], storage_logs: &[StorageLogQuery]) -> AccessList {
+    fn collect_called_contracts(
+        calls: &[Call],
+        access_list: &mut BTreeMap<Address, BTreeSet<H256>>,
+    ) {
+        for call in calls {
+            access_list.entry(call.to).or_default();
+            collect_called_contracts(&call.calls, access_list);
+        }
+    }
+
+    let mut access_list = BTreeMap::<_, BTreeSet<_>>::new();
+    collect_called_contracts(calls, &mut access_list);
+    for log in storage_logs {
+        let log = &log.log_query;
+        access_list
+            .entry(log.address)
+            .or_default()
+            .insert(u256_to_h256(log.key));
+    }
+    access_list
+        .into_iter()
+        .map(|(address, storage_keys)| AccessListItem {
+            address,
+            storage_keys: storage_keys.into_iter().collect(),
+        })
+        .collect()
+}
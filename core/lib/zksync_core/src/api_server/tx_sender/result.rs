@@ -1,9 +1,12 @@
 use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
 use thiserror::Error;
-use zksync_types::{l2::error::TxCheckError, U256};
+use zksync_types::{api::TransactionConditionsError, l2::error::TxCheckError, U256};
 use zksync_web3_decl::error::EnrichedClientError;
 
-use crate::api_server::execution_sandbox::{SandboxExecutionError, ValidationError};
+use crate::{
+    api_server::execution_sandbox::{SandboxExecutionError, ValidationError},
+    state_keeper::tx_filter::TxLimitError,
+};
 
 /// Errors that con occur submitting a transaction or estimating gas for its execution.
 #[derive(Debug, Error)]
@@ -12,6 +15,8 @@ pub enum SubmitTxError {
     NonceIsTooHigh(u32, u32, u32),
     #[error("nonce too low. allowed nonce range: {0} - {1}, actual: {2}")]
     NonceIsTooLow(u32, u32, u32),
+    #[error("replacement transaction underpriced. max fee per gas must be at least {0}")]
+    ReplacementUnderpriced(U256),
     #[error("{0}")]
     IncorrectTx(#[from] TxCheckError),
     #[error("insufficient funds for gas + value. balance: {0}, fee: {1}, value: {2}")]
@@ -70,6 +75,14 @@ pub enum SubmitTxError {
     ProxyError(#[from] EnrichedClientError),
     #[error("not enough gas to publish compressed bytecodes")]
     FailedToPublishCompressedBytecodes,
+    #[error("invalid transaction conditions: {0}")]
+    InvalidConditions(String),
+    #[error("transaction conditions are not met: {0}")]
+    ConditionsNotMet(#[from] TransactionConditionsError),
+    #[error("transaction is filtered: {0}")]
+    TransactionFiltered(String),
+    #[error("{0}")]
+    TxLimitExceeded(#[from] TxLimitError),
     /// Catch-all internal error (e.g., database error) that should not be exposed to the caller.
     #[error("internal error")]
     Internal(#[from] anyhow::Error),
@@ -80,6 +93,7 @@ impl SubmitTxError {
         match self {
             Self::NonceIsTooHigh(_, _, _) => "nonce-is-too-high",
             Self::NonceIsTooLow(_, _, _) => "nonce-is-too-low",
+            Self::ReplacementUnderpriced(_) => "replacement-underpriced",
             Self::IncorrectTx(_) => "incorrect-tx",
             Self::NotEnoughBalanceForFeeValue(_, _, _) => "not-enough-balance-for-fee",
             Self::ExecutionReverted(_, _) => "execution-reverted",
@@ -104,6 +118,10 @@ impl SubmitTxError {
             Self::IntrinsicGas => "intrinsic-gas",
             Self::ProxyError(_) => "proxy-error",
             Self::FailedToPublishCompressedBytecodes => "failed-to-publish-compressed-bytecodes",
+            Self::InvalidConditions(_) => "invalid-conditions",
+            Self::ConditionsNotMet(_) => "conditions-not-met",
+            Self::TransactionFiltered(_) => "transaction-filtered",
+            Self::TxLimitExceeded(_) => "tx-limit-exceeded",
             Self::Internal(_) => "internal",
         }
     }
@@ -1,12 +1,23 @@
 //! Tests for the transaction sender.
 
-use zksync_types::{get_nonce_key, L1BatchNumber, StorageLog};
+use assert_matches::assert_matches;
+use zksync_types::{
+    api::{KnownAccountState, TransactionConditionsError},
+    get_nonce_key,
+    zk_evm_types::{LogQuery, Timestamp},
+    L1BatchNumber, StorageLog, StorageLogQueryType,
+};
+use zksync_web3_decl::error::Web3Error;
 
-use super::*;
+use super::{master_pool_sink::MasterPoolSink, *};
 use crate::{
     api_server::execution_sandbox::{testonly::MockTransactionExecutor, VmConcurrencyBarrier},
     genesis::{ensure_genesis_state, GenesisParams},
-    utils::testonly::{create_miniblock, prepare_recovery_snapshot, MockBatchFeeParamsProvider},
+    state_keeper::tx_filter::{DenyListFilter, TxLimitKind, TxLimits},
+    utils::testonly::{
+        create_l2_transaction, create_miniblock, prepare_recovery_snapshot,
+        MockBatchFeeParamsProvider,
+    },
 };
 
 pub(crate) async fn create_test_tx_sender(
@@ -28,6 +39,7 @@ pub(crate) async fn create_test_tx_sender(
         pool,
         batch_fee_model_input_provider,
         storage_caches,
+        None,
     )
     .await;
 
@@ -139,3 +151,201 @@ async fn getting_nonce_for_account_after_snapshot_recovery() {
     let nonce = tx_sender.get_expected_nonce(missing_address).await.unwrap();
     assert_eq!(nonce, Nonce(0));
 }
+
+#[tokio::test]
+async fn checking_transaction_conditions() {
+    let l2_chain_id = L2ChainId::default();
+    let test_address = Address::repeat_byte(1);
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, l2_chain_id, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let nonce_key = get_nonce_key(&test_address);
+    let nonce_log = StorageLog::new_write_log(nonce_key, H256::from_low_u64_be(123));
+    storage
+        .storage_logs_dal()
+        .append_storage_logs(MiniblockNumber(0), &[(H256::default(), vec![nonce_log])])
+        .await
+        .unwrap();
+
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (tx_sender, _) = create_test_tx_sender(pool.clone(), l2_chain_id, tx_executor).await;
+
+    let slot_conditions = |value: H256| TransactionConditions {
+        known_accounts: [(
+            *nonce_key.address(),
+            KnownAccountState::Slots([(*nonce_key.key(), value)].into()),
+        )]
+        .into(),
+        ..TransactionConditions::default()
+    };
+    tx_sender
+        .check_tx_conditions(&slot_conditions(H256::from_low_u64_be(123)))
+        .await
+        .unwrap();
+    let err = tx_sender
+        .check_tx_conditions(&slot_conditions(H256::zero()))
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::ConditionsNotMet(TransactionConditionsError::StorageSlotMismatch { actual, .. })
+            if actual == H256::from_low_u64_be(123)
+    );
+
+    // The next miniblock has number 1.
+    let block_conditions = TransactionConditions {
+        block_number_max: Some(0.into()),
+        ..TransactionConditions::default()
+    };
+    let err = tx_sender
+        .check_tx_conditions(&block_conditions)
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::ConditionsNotMet(TransactionConditionsError::BlockNumberOutOfRange(
+            MiniblockNumber(1)
+        ))
+    );
+
+    let root_conditions = TransactionConditions {
+        known_accounts: [(test_address, KnownAccountState::StorageRoot(H256::zero()))].into(),
+        ..TransactionConditions::default()
+    };
+    let err = tx_sender
+        .check_tx_conditions(&root_conditions)
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::InvalidConditions(_));
+}
+
+#[tokio::test]
+async fn submitting_replacement_transactions() {
+    let pool = ConnectionPool::test_pool().await;
+    let sink = MasterPoolSink::new(pool, 10);
+    let pending_tx = create_l2_transaction(100, 50);
+    let result = sink
+        .submit_tx(pending_tx.clone(), Default::default(), None)
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Added);
+    let result = sink
+        .submit_tx(pending_tx.clone(), Default::default(), None)
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Duplicate);
+
+    let replacement_tx = |fee_per_gas| {
+        let mut tx = create_l2_transaction(fee_per_gas, 50);
+        tx.common_data.initiator_address = pending_tx.initiator_account();
+        tx
+    };
+    let err = sink
+        .submit_tx(replacement_tx(105), Default::default(), None)
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::ReplacementUnderpriced(fee) if fee == 110.into());
+    let result = sink
+        .submit_tx(replacement_tx(110), Default::default(), None)
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Replaced);
+}
+
+#[tokio::test]
+async fn filtering_transactions() {
+    let l2_chain_id = L2ChainId::default();
+    let pool = ConnectionPool::test_pool().await;
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (mut tx_sender, _) = create_test_tx_sender(pool, l2_chain_id, tx_executor).await;
+
+    let tx = create_l2_transaction(10, 50);
+    let filter = DenyListFilter::new([tx.initiator_account()], []);
+    Arc::get_mut(&mut tx_sender.0).unwrap().tx_filter = Arc::new(filter);
+
+    let err = tx_sender.validate_tx(&tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::TransactionFiltered(reason) if reason.contains("initiator"));
+}
+
+#[tokio::test]
+async fn enforcing_tx_limits() {
+    let l2_chain_id = L2ChainId::default();
+    let pool = ConnectionPool::test_pool().await;
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (mut tx_sender, _) = create_test_tx_sender(pool, l2_chain_id, tx_executor).await;
+
+    let config = StateKeeperConfig {
+        max_tx_gas_per_pubdata: Some(40),
+        ..StateKeeperConfig::for_tests()
+    };
+    Arc::get_mut(&mut tx_sender.0).unwrap().tx_limits = TxLimits::from_config(&config).unwrap();
+
+    let tx = create_l2_transaction(10, 50);
+    let err = tx_sender.validate_tx(&tx).await.unwrap_err();
+    assert_matches!(
+        &err,
+        SubmitTxError::TxLimitExceeded(err) if err.kind == TxLimitKind::GasPerPubdata
+    );
+    let err = Web3Error::from(err);
+    assert_matches!(
+        err,
+        Web3Error::TxLimitExceeded(_, details)
+            if details == serde_json::json!({ "kind": "gas_per_pubdata", "value": 50, "max": 40 })
+    );
+}
+
+#[test]
+fn building_access_list() {
+    let storage_log = |address: Address, key: u64| StorageLogQuery {
+        log_query: LogQuery {
+            timestamp: Timestamp(0),
+            tx_number_in_block: 0,
+            aux_byte: 0,
+            shard_id: 0,
+            address,
+            key: key.into(),
+            read_value: U256::zero(),
+            written_value: U256::zero(),
+            rw_flag: false,
+            rollback: false,
+            is_service: false,
+        },
+        log_type: StorageLogQueryType::Read,
+    };
+    let call = |to: Address, calls: Vec<Call>| Call {
+        to,
+        calls,
+        ..Call::default()
+    };
+
+    let calls = [call(
+        Address::repeat_byte(2),
+        vec![call(Address::repeat_byte(3), vec![])],
+    )];
+    let storage_logs = [
+        storage_log(Address::repeat_byte(2), 5),
+        storage_log(Address::repeat_byte(1), 1),
+        storage_log(Address::repeat_byte(2), 3),
+        storage_log(Address::repeat_byte(2), 5),
+    ];
+    let access_list = build_access_list(&calls, &storage_logs);
+
+    let expected_access_list = [
+        (Address::repeat_byte(1), vec![H256::from_low_u64_be(1)]),
+        (
+            Address::repeat_byte(2),
+            vec![H256::from_low_u64_be(3), H256::from_low_u64_be(5)],
+        ),
+        (Address::repeat_byte(3), vec![]),
+    ];
+    let expected_access_list: Vec<_> = expected_access_list
+        .into_iter()
+        .map(|(address, storage_keys)| AccessListItem {
+            address,
+            storage_keys,
+        })
+        .collect();
+    assert_eq!(access_list, expected_access_list);
+}
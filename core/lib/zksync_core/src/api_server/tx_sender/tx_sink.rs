@@ -1,6 +1,6 @@
 use zksync_dal::transactions_dal::L2TxSubmissionResult;
 use zksync_types::{
-    api::{Transaction, TransactionDetails, TransactionId},
+    api::{Transaction, TransactionConditions, TransactionDetails, TransactionId},
     fee::TransactionExecutionMetrics,
     l2::L2Tx,
     Address, Nonce, H256,
@@ -23,11 +23,13 @@ use super::SubmitTxError;
 /// and may be implemented as no-ops.
 #[async_trait::async_trait]
 pub trait TxSink: std::fmt::Debug + Send + Sync + 'static {
-    /// Ensures that transaction is propagated to the mempool.
+    /// Ensures that transaction is propagated to the mempool. If `conditions` are specified, they must be
+    /// propagated together with the transaction so that they are re-checked before its execution.
     async fn submit_tx(
         &self,
         tx: L2Tx,
         execution_metrics: TransactionExecutionMetrics,
+        conditions: Option<&TransactionConditions>,
     ) -> Result<L2TxSubmissionResult, SubmitTxError>;
 
     /// Attempts to look up the pending nonce for the account in the sink-specific storage.
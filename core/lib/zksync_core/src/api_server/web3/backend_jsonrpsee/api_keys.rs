@@ -0,0 +1,432 @@
+//! HTTP-level middleware authenticating requests with API keys and enforcing per-key quotas.
+//!
+//! The middleware is applied to HTTP requests before they are processed by `jsonrpsee`, so it covers both
+//! HTTP JSON-RPC requests and WebSocket handshakes. For WebSocket connections, the API key is thus checked
+//! (and the request quota is consumed) once per connection; messages sent over an established connection
+//! are limited by the per-connection rate limit instead.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    num::NonZeroU32,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use governor::{
+    clock::{Clock, DefaultClock},
+    middleware::NoOpMiddleware,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
+use hyper::{
+    header::{self, HeaderName, HeaderValue},
+    http::uri::PathAndQuery,
+    Body, Method, Request, Response, StatusCode, Uri,
+};
+use tower::{Layer, Service};
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Metrics};
+
+use super::{compute_units::request_compute_units, request_body::read_request_body};
+
+/// Header containing the API key.
+pub(crate) const API_KEY_HEADER: HeaderName = HeaderName::from_static("x-api-key");
+
+type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+enum RejectionReason {
+    Unauthorized,
+    RequestsQuota,
+    ComputeUnitsQuota,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_api_keys")]
+struct ApiKeyMetrics {
+    /// Number of requests rejected by the API key middleware.
+    rejected: Family<RejectionReason, Counter>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ApiKeyMetrics> = vise::Global::new();
+
+/// Quotas applied to each API key accepted by the server.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApiKeyLimits {
+    /// Maximum number of HTTP requests per second, including WebSocket handshakes.
+    pub requests_per_second: Option<NonZeroU32>,
+    /// Maximum number of compute units per second. Each JSON-RPC call in an HTTP request costs a method-specific
+    /// number of units (e.g., `eth_chainId` costs 1 unit, and `eth_call` costs 50 units); for batch requests,
+    /// costs of all calls in the batch are summed.
+    pub compute_units_per_second: Option<NonZeroU32>,
+}
+
+struct KeyQuotas {
+    requests: Option<DirectRateLimiter>,
+    compute_units: Option<DirectRateLimiter>,
+}
+
+impl KeyQuotas {
+    fn new(limits: ApiKeyLimits) -> Self {
+        Self {
+            requests: limits
+                .requests_per_second
+                .map(|limit| RateLimiter::direct(Quota::per_second(limit))),
+            compute_units: limits
+                .compute_units_per_second
+                .map(|limit| RateLimiter::direct(Quota::per_second(limit))),
+        }
+    }
+}
+
+/// Error returned when a quota is exceeded.
+#[derive(Debug, PartialEq)]
+struct QuotaExceeded {
+    /// Time after which the request can be retried. `None` if the request can never succeed
+    /// (e.g., a batch costs more compute units than allowed per second).
+    retry_after: Option<Duration>,
+}
+
+fn check_quota(limiter: &DirectRateLimiter, cost: u32) -> Result<(), QuotaExceeded> {
+    let Some(cost) = NonZeroU32::new(cost) else {
+        return Ok(());
+    };
+    match limiter.check_n(cost) {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(not_until)) => Err(QuotaExceeded {
+            retry_after: Some(not_until.wait_time_from(DefaultClock::default().now())),
+        }),
+        Err(_) => Err(QuotaExceeded { retry_after: None }),
+    }
+}
+
+/// Extracts the API key from the request. The key can be provided either in the [`API_KEY_HEADER`],
+/// or as the request path (`/<key>`). In the latter case, the path is rewritten to `/`.
+fn extract_api_key(request: &mut Request<Body>) -> Option<String> {
+    if let Some(key) = request.headers().get(API_KEY_HEADER) {
+        return key.to_str().ok().map(str::to_owned);
+    }
+
+    let key = request.uri().path().strip_prefix('/')?;
+    if key.is_empty() || key.contains('/') {
+        return None;
+    }
+    let key = key.to_owned();
+
+    let mut uri_parts = request.uri().clone().into_parts();
+    let path_and_query = match request.uri().query() {
+        Some(query) => PathAndQuery::try_from(format!("/?{query}")),
+        None => Ok(PathAndQuery::from_static("/")),
+    };
+    uri_parts.path_and_query = Some(path_and_query.ok()?);
+    *request.uri_mut() = Uri::from_parts(uri_parts).ok()?;
+    Some(key)
+}
+
+pub(super) fn error_response(status: StatusCode, retry_after: Option<Duration>) -> Response<Body> {
+    let message = status.canonical_reason().unwrap_or("Request rejected");
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": status.as_u16(),
+            "message": message,
+        },
+        "id": null,
+    });
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    if let Some(retry_after) = retry_after {
+        // `Retry-After` is specified in whole seconds; round up so that the retried request is not rejected again.
+        let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(secs.max(1)));
+    }
+    response
+}
+
+fn quota_exceeded_response(reason: RejectionReason, err: QuotaExceeded) -> Response<Body> {
+    METRICS.rejected[&reason].inc();
+    error_response(StatusCode::TOO_MANY_REQUESTS, err.retry_after)
+}
+
+/// Layer authenticating requests with API keys. See the [module docs](self) for details.
+#[derive(Clone)]
+pub(crate) struct ApiKeyLayer {
+    quotas: Arc<HashMap<String, KeyQuotas>>,
+    max_request_body_size: usize,
+}
+
+impl fmt::Debug for ApiKeyLayer {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ApiKeyLayer")
+            .field("keys_count", &self.quotas.len())
+            .field("max_request_body_size", &self.max_request_body_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ApiKeyLayer {
+    /// Creates a new layer. Bodies of requests spending compute units are buffered to estimate their cost;
+    /// requests with bodies larger than `max_request_body_size` bytes are rejected.
+    pub(crate) fn new(
+        keys: impl IntoIterator<Item = String>,
+        limits: ApiKeyLimits,
+        max_request_body_size: usize,
+    ) -> Self {
+        let quotas = keys
+            .into_iter()
+            .map(|key| (key, KeyQuotas::new(limits)))
+            .collect();
+        Self {
+            quotas: Arc::new(quotas),
+            max_request_body_size,
+        }
+    }
+}
+
+impl<S> Layer<S> for ApiKeyLayer {
+    type Service = ApiKeyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiKeyService {
+            inner,
+            quotas: self.quotas.clone(),
+            max_request_body_size: self.max_request_body_size,
+        }
+    }
+}
+
+/// Service produced by [`ApiKeyLayer`].
+#[derive(Clone)]
+pub(crate) struct ApiKeyService<S> {
+    inner: S,
+    quotas: Arc<HashMap<String, KeyQuotas>>,
+    max_request_body_size: usize,
+}
+
+impl<S: fmt::Debug> fmt::Debug for ApiKeyService<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ApiKeyService")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S> Service<Request<Body>> for ApiKeyService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Send,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Body>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let key = extract_api_key(&mut request);
+        let Some(quotas) = key.as_ref().and_then(|key| self.quotas.get(key)) else {
+            METRICS.rejected[&RejectionReason::Unauthorized].inc();
+            let response = error_response(StatusCode::UNAUTHORIZED, None);
+            return futures::future::ready(Ok(response)).boxed();
+        };
+
+        if let Some(limiter) = &quotas.requests {
+            if let Err(err) = check_quota(limiter, 1) {
+                let response = quota_exceeded_response(RejectionReason::RequestsQuota, err);
+                return futures::future::ready(Ok(response)).boxed();
+            }
+        }
+        // Compute units are only spent by JSON-RPC calls in HTTP requests; WebSocket handshakes use `GET`.
+        if quotas.compute_units.is_none() || request.method() != Method::POST {
+            return self.inner.call(request).boxed();
+        }
+
+        // The inner service was polled to readiness, so we need to use it rather than its clone.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let quotas = self.quotas.clone();
+        let key = key.unwrap(); // `quotas` were found, so the key is present
+        let max_request_body_size = self.max_request_body_size;
+        async move {
+            let (parts, body) = request.into_parts();
+            let body = match read_request_body(&parts, body, max_request_body_size).await {
+                Ok(body) => body,
+                Err(err) => return Ok(error_response(err.status(), None)),
+            };
+            let limiter = quotas[&key].compute_units.as_ref().unwrap();
+            if let Err(err) = check_quota(limiter, request_compute_units(&body)) {
+                return Ok(quota_exceeded_response(
+                    RejectionReason::ComputeUnitsQuota,
+                    err,
+                ));
+            }
+            inner
+                .call(Request::from_parts(parts, Body::from(body)))
+                .await
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    const MAX_REQUEST_BODY_SIZE: usize = 1_024;
+
+    fn limits(requests_per_second: u32, compute_units_per_second: u32) -> ApiKeyLimits {
+        ApiKeyLimits {
+            requests_per_second: NonZeroU32::new(requests_per_second),
+            compute_units_per_second: NonZeroU32::new(compute_units_per_second),
+        }
+    }
+
+    /// Creates a service echoing the request URI and body.
+    fn echo_service(
+        limits: ApiKeyLimits,
+    ) -> impl Service<Request<Body>, Response = Response<Body>, Error = Infallible> {
+        let inner = tower::service_fn(|request: Request<Body>| async move {
+            let uri = request.uri().to_string();
+            let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+            let mut response = Response::new(Body::from(body));
+            response
+                .headers_mut()
+                .insert("x-uri", HeaderValue::try_from(uri).unwrap());
+            Ok::<_, Infallible>(response)
+        });
+        ApiKeyLayer::new(["key".to_owned()], limits, MAX_REQUEST_BODY_SIZE).layer(inner)
+    }
+
+    fn post(uri: &str, api_key: Option<&str>, body: impl Into<Body>) -> Request<Body> {
+        let mut builder = Request::post(uri);
+        if let Some(api_key) = api_key {
+            builder = builder.header(API_KEY_HEADER, api_key);
+        }
+        builder.body(Body::from(body)).unwrap()
+    }
+
+    async fn call(
+        service: &mut impl Service<Request<Body>, Response = Response<Body>, Error = Infallible>,
+        request: Request<Body>,
+    ) -> Response<Body> {
+        futures::future::poll_fn(|cx| service.poll_ready(cx))
+            .await
+            .unwrap();
+        service.call(request).await.unwrap()
+    }
+
+    const CALL: &str = r#"{"jsonrpc":"2.0","id":1,"method":"eth_chainId","params":[]}"#;
+
+    #[tokio::test]
+    async fn authenticating_requests() {
+        let mut service = echo_service(ApiKeyLimits::default());
+
+        let response = call(&mut service, post("/", None, CALL)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = call(&mut service, post("/", Some("wrong"), CALL)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = call(&mut service, post("/wrong", None, CALL)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = call(&mut service, post("/", Some("key"), CALL)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-uri"], "/");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, CALL.as_bytes());
+
+        let response = call(&mut service, post("/key?test=1", None, CALL)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-uri"], "/?test=1");
+    }
+
+    #[tokio::test]
+    async fn limiting_requests_per_key() {
+        let mut service = echo_service(limits(2, 0));
+        for _ in 0..2 {
+            let response = call(&mut service, post("/key", None, CALL)).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = call(&mut service, post("/key", None, CALL)).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"]["code"], 429);
+    }
+
+    #[tokio::test]
+    async fn limiting_compute_units_per_key() {
+        let mut service = echo_service(limits(0, 5));
+        let batch = format!("[{CALL},{CALL},{CALL}]");
+
+        let response = call(&mut service, post("/key", None, batch.clone())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, batch.as_bytes());
+
+        let response = call(&mut service, post("/key", None, batch)).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        // A single call still fits into the quota.
+        let response = call(&mut service, post("/key", None, CALL)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Batches exceeding the quota can never succeed.
+        let large_batch = format!("[{CALL},{CALL},{CALL},{CALL},{CALL},{CALL}]");
+        let response = call(&mut service, post("/key", None, large_batch)).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(!response.headers().contains_key(header::RETRY_AFTER));
+
+        // WebSocket handshakes don't spend compute units.
+        let request = Request::get("/key").body(Body::empty()).unwrap();
+        let response = call(&mut service, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn compute_units_depend_on_method() {
+        let mut service = echo_service(limits(0, 60));
+        let eth_call = r#"{"jsonrpc":"2.0","id":1,"method":"eth_call","params":[]}"#;
+
+        let response = call(&mut service, post("/key", None, eth_call)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = call(&mut service, post("/key", None, eth_call)).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        // Cheap calls still fit into the remaining quota.
+        for _ in 0..10 {
+            let response = call(&mut service, post("/key", None, CALL)).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejecting_oversized_requests_spending_compute_units() {
+        let mut service = echo_service(limits(0, 1_000));
+        let large_batch = format!("[{}]", vec![CALL; 50].join(","));
+        assert!(large_batch.len() > MAX_REQUEST_BODY_SIZE);
+
+        let response = call(&mut service, post("/key", None, large_batch)).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        // The quota is not spent by the rejected request.
+        let response = call(&mut service, post("/key", None, CALL)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
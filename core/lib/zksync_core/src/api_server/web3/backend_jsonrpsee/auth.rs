@@ -0,0 +1,415 @@
+//! HTTP-level middleware authenticating requests with bearer tokens.
+//!
+//! Clients authenticate by providing a token in the `Authorization: Bearer <token>` header. A token is accepted
+//! if it is one of the static tokens configured for the server, or if it is a JWT signed with the shared secret
+//! using HS256 and its `iat` (issued at) claim is within [`JWT_IAT_TOLERANCE`] of the current time. The latter
+//! is the same scheme as used by the Engine API of Ethereum execution clients.
+//!
+//! Authentication can be required for all calls, or only for calls to protected namespaces (e.g., `debug`).
+//! The middleware only sees HTTP requests, so calls sent over an established WebSocket connection are not inspected.
+//! Instead, unauthenticated WebSocket handshakes are rejected if the server exposes any protected methods.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    path::Path,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context as _;
+use futures::{future::BoxFuture, FutureExt};
+use hyper::{header, Body, Method, Request, Response, StatusCode};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use tower::{Layer, Service};
+use vise::{Counter, Metrics};
+
+use super::{
+    api_keys::error_response, compute_units::request_methods, request_body::read_request_body,
+};
+
+/// Maximum allowed difference between the `iat` claim of a JWT and the current time.
+pub(crate) const JWT_IAT_TOLERANCE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_auth")]
+struct AuthMetrics {
+    /// Number of HTTP requests rejected because they call protected methods without authentication.
+    rejected_requests: Counter,
+    /// Number of unauthenticated WebSocket handshakes rejected.
+    rejected_connections: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<AuthMetrics> = vise::Global::new();
+
+/// Authentication settings for the JSON-RPC servers.
+#[derive(Clone, Default)]
+pub struct RpcAuthConfig {
+    /// Static bearer tokens accepted by the server.
+    pub bearer_tokens: Vec<String>,
+    /// Shared secret used to verify HS256-signed JWTs.
+    pub jwt_secret: Option<Vec<u8>>,
+    /// Namespaces (e.g., `debug`) calls to which require authentication. If not set, all calls require authentication.
+    pub protected_namespaces: Option<Vec<String>>,
+}
+
+impl fmt::Debug for RpcAuthConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RpcAuthConfig")
+            .field("bearer_tokens_count", &self.bearer_tokens.len())
+            .field("has_jwt_secret", &self.jwt_secret.is_some())
+            .field("protected_namespaces", &self.protected_namespaces)
+            .finish()
+    }
+}
+
+impl RpcAuthConfig {
+    /// Reads a JWT secret from a file containing its hex encoding (optionally prefixed with `0x`),
+    /// like the `jwt.hex` file used by Ethereum execution clients.
+    pub fn read_jwt_secret(path: &Path) -> anyhow::Result<Vec<u8>> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed reading JWT secret from `{}`", path.display()))?;
+        let contents = contents.trim();
+        let contents = contents.strip_prefix("0x").unwrap_or(contents);
+        let secret = hex::decode(contents).context("JWT secret is not hex-encoded")?;
+        anyhow::ensure!(!secret.is_empty(), "JWT secret is empty");
+        Ok(secret)
+    }
+
+    /// Checks whether a call to the specified method requires authentication.
+    pub(crate) fn is_protected(&self, method: &str) -> bool {
+        let Some(namespaces) = &self.protected_namespaces else {
+            return true;
+        };
+        let namespace = method
+            .split_once('_')
+            .map_or(method, |(namespace, _)| namespace);
+        namespaces.iter().any(|protected| protected == namespace)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JwtClaims {
+    iat: u64,
+}
+
+struct Authenticator {
+    bearer_tokens: HashSet<String>,
+    jwt_key: Option<DecodingKey>,
+    config: RpcAuthConfig,
+    reject_unauthenticated_connections: bool,
+    max_request_body_size: usize,
+}
+
+impl Authenticator {
+    fn is_authenticated(&self, request: &Request<Body>) -> bool {
+        let token = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        let Some(token) = token else {
+            return false;
+        };
+        if self.bearer_tokens.contains(token) {
+            return true;
+        }
+        self.jwt_key
+            .as_ref()
+            .is_some_and(|key| Self::is_valid_jwt(key, token))
+    }
+
+    fn is_valid_jwt(key: &DecodingKey, token: &str) -> bool {
+        let mut validation = Validation::new(Algorithm::HS256);
+        // Like in the Engine API, JWTs are only required to have the `iat` claim.
+        validation.required_spec_claims.clear();
+        validation.validate_exp = false;
+        let Ok(token) = jsonwebtoken::decode::<JwtClaims>(token, key, &validation) else {
+            return false;
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |timestamp| timestamp.as_secs());
+        now.abs_diff(token.claims.iat) <= JWT_IAT_TOLERANCE.as_secs()
+    }
+}
+
+/// Layer authenticating requests with bearer tokens. See the [module docs](self) for details.
+#[derive(Clone)]
+pub(crate) struct AuthLayer {
+    authenticator: Arc<Authenticator>,
+}
+
+impl fmt::Debug for AuthLayer {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AuthLayer")
+            .field("config", &self.authenticator.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuthLayer {
+    /// Creates a new layer. If `reject_unauthenticated_connections` is set, unauthenticated WebSocket handshakes
+    /// will be rejected; this should be set if the server exposes any protected methods. Bodies of unauthenticated
+    /// requests are buffered to check called methods; requests with bodies larger than `max_request_body_size` bytes
+    /// are rejected.
+    pub(crate) fn new(
+        config: RpcAuthConfig,
+        reject_unauthenticated_connections: bool,
+        max_request_body_size: usize,
+    ) -> Self {
+        let authenticator = Authenticator {
+            bearer_tokens: config.bearer_tokens.iter().cloned().collect(),
+            jwt_key: config.jwt_secret.as_deref().map(DecodingKey::from_secret),
+            config,
+            reject_unauthenticated_connections,
+            max_request_body_size,
+        };
+        Self {
+            authenticator: Arc::new(authenticator),
+        }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            inner,
+            authenticator: self.authenticator.clone(),
+        }
+    }
+}
+
+/// Service produced by [`AuthLayer`].
+#[derive(Clone)]
+pub(crate) struct AuthService<S> {
+    inner: S,
+    authenticator: Arc<Authenticator>,
+}
+
+impl<S: fmt::Debug> fmt::Debug for AuthService<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AuthService")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S> Service<Request<Body>> for AuthService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Send,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Body>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        if self.authenticator.is_authenticated(&request) {
+            return self.inner.call(request).boxed();
+        }
+
+        // JSON-RPC calls are only sent in HTTP `POST` requests; other requests are WebSocket handshakes.
+        if request.method() != Method::POST {
+            if self.authenticator.reject_unauthenticated_connections {
+                METRICS.rejected_connections.inc();
+                let response = error_response(StatusCode::UNAUTHORIZED, None);
+                return futures::future::ready(Ok(response)).boxed();
+            }
+            return self.inner.call(request).boxed();
+        }
+        if self.authenticator.config.protected_namespaces.is_none() {
+            METRICS.rejected_requests.inc();
+            let response = error_response(StatusCode::UNAUTHORIZED, None);
+            return futures::future::ready(Ok(response)).boxed();
+        }
+
+        // The inner service was polled to readiness, so we need to use it rather than its clone.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let authenticator = self.authenticator.clone();
+        async move {
+            let (parts, body) = request.into_parts();
+            let max_size = authenticator.max_request_body_size;
+            let body = match read_request_body(&parts, body, max_size).await {
+                Ok(body) => body,
+                Err(err) => return Ok(error_response(err.status(), None)),
+            };
+            // Malformed requests are passed through; they will be rejected by the server anyway.
+            let methods = request_methods(&body).unwrap_or_default();
+            if methods
+                .iter()
+                .any(|method| authenticator.config.is_protected(method))
+            {
+                METRICS.rejected_requests.inc();
+                return Ok(error_response(StatusCode::UNAUTHORIZED, None));
+            }
+            inner
+                .call(Request::from_parts(parts, Body::from(body)))
+                .await
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use jsonwebtoken::{EncodingKey, Header};
+
+    use super::*;
+
+    const MAX_REQUEST_BODY_SIZE: usize = 1_024;
+    const SECRET: &[u8] = &[1; 32];
+    const CALL: &str = r#"{"jsonrpc":"2.0","id":1,"method":"eth_chainId","params":[]}"#;
+    const DEBUG_CALL: &str =
+        r#"{"jsonrpc":"2.0","id":1,"method":"debug_traceTransaction","params":[]}"#;
+
+    fn echo_service(
+        protected_namespaces: Option<Vec<String>>,
+        reject_unauthenticated_connections: bool,
+    ) -> impl Service<Request<Body>, Response = Response<Body>, Error = Infallible> {
+        let inner = tower::service_fn(|request: Request<Body>| async move {
+            let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+            Ok::<_, Infallible>(Response::new(Body::from(body)))
+        });
+        let config = RpcAuthConfig {
+            bearer_tokens: vec!["token".to_owned()],
+            jwt_secret: Some(SECRET.to_vec()),
+            protected_namespaces,
+        };
+        AuthLayer::new(
+            config,
+            reject_unauthenticated_connections,
+            MAX_REQUEST_BODY_SIZE,
+        )
+        .layer(inner)
+    }
+
+    fn jwt(secret: &[u8], issued_at: SystemTime) -> String {
+        let iat = issued_at.duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let claims = serde_json::json!({ "iat": iat });
+        jsonwebtoken::encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(secret),
+        )
+        .unwrap()
+    }
+
+    fn post(token: Option<&str>, body: &str) -> Request<Body> {
+        let mut builder = Request::post("/");
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        builder.body(Body::from(body.to_owned())).unwrap()
+    }
+
+    async fn call(
+        service: &mut impl Service<Request<Body>, Response = Response<Body>, Error = Infallible>,
+        request: Request<Body>,
+    ) -> Response<Body> {
+        futures::future::poll_fn(|cx| service.poll_ready(cx))
+            .await
+            .unwrap();
+        service.call(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn authenticating_requests() {
+        let mut service = echo_service(None, true);
+
+        let response = call(&mut service, post(None, CALL)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = call(&mut service, post(Some("wrong"), CALL)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = call(&mut service, post(Some("token"), CALL)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, CALL.as_bytes());
+
+        let token = jwt(SECRET, SystemTime::now());
+        let response = call(&mut service, post(Some(&token), CALL)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let stale_token = jwt(SECRET, SystemTime::now() - JWT_IAT_TOLERANCE * 2);
+        let response = call(&mut service, post(Some(&stale_token), CALL)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let wrong_token = jwt(&[2; 32], SystemTime::now());
+        let response = call(&mut service, post(Some(&wrong_token), CALL)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let handshake = Request::get("/").body(Body::empty()).unwrap();
+        let response = call(&mut service, handshake).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn protecting_namespaces() {
+        let mut service = echo_service(Some(vec!["debug".to_owned()]), false);
+
+        let response = call(&mut service, post(None, CALL)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, CALL.as_bytes());
+
+        let response = call(&mut service, post(None, DEBUG_CALL)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let batch = format!("[{CALL},{DEBUG_CALL}]");
+        let response = call(&mut service, post(None, &batch)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = call(&mut service, post(Some("token"), &batch)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let handshake = Request::get("/").body(Body::empty()).unwrap();
+        let response = call(&mut service, handshake).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn rejecting_oversized_unauthenticated_requests() {
+        let mut service = echo_service(Some(vec!["debug".to_owned()]), false);
+        let large_batch = format!("[{}]", vec![CALL; 50].join(","));
+        assert!(large_batch.len() > MAX_REQUEST_BODY_SIZE);
+
+        let response = call(&mut service, post(None, &large_batch)).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let request = Request::post("/")
+            .header(header::CONTENT_LENGTH, "1000000000")
+            .body(Body::empty())
+            .unwrap();
+        let response = call(&mut service, request).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Authenticated requests are not buffered by the middleware.
+        let response = call(&mut service, post(Some("token"), &large_batch)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn reading_jwt_secret() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("jwt.hex");
+        std::fs::write(&path, format!("0x{}\n", hex::encode(SECRET))).unwrap();
+        let secret = RpcAuthConfig::read_jwt_secret(&path).unwrap();
+        assert_eq!(secret, SECRET);
+
+        std::fs::write(&path, "not hex").unwrap();
+        RpcAuthConfig::read_jwt_secret(&path).unwrap_err();
+    }
+}
@@ -0,0 +1,502 @@
+//! HTTP-level middleware enforcing limits on JSON-RPC batch requests.
+//!
+//! The middleware splits each batch into individual calls and dispatches them to the server separately,
+//! so that limits can be enforced on a per-call basis. Rather than rejecting the entire batch, calls violating
+//! a limit are replaced with error responses in the returned batch:
+//!
+//! - If the batch contains more calls than allowed, all calls are rejected with an error.
+//! - Once the aggregate size of call responses exceeds the limit, the remaining responses are replaced with errors.
+//! - Calls not completed within the time budget of the batch are replaced with errors.
+//!
+//! Calls in a batch are processed concurrently, with at most [`MAX_CONCURRENT_CALLS`] calls in flight at a time.
+
+use std::{
+    fmt,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, FutureExt, StreamExt};
+use hyper::{
+    header::{self, HeaderValue},
+    Body, Method, Request, Response, StatusCode,
+};
+use tokio::time::Instant;
+use tower::{Layer, Service, ServiceExt};
+use zksync_web3_decl::jsonrpsee::types::error::{
+    ErrorCode, TOO_BIG_BATCH_REQUEST_CODE, TOO_BIG_BATCH_RESPONSE_CODE,
+};
+
+use super::{
+    api_keys::error_response,
+    middleware::{Transport, METRICS},
+    request_body::read_request_body,
+};
+
+/// Maximum number of calls from a single batch processed concurrently.
+const MAX_CONCURRENT_CALLS: usize = 16;
+
+/// Limits applied to HTTP batch requests.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct BatchLimits {
+    /// Maximum number of calls in a batch.
+    pub max_size: Option<usize>,
+    /// Maximum aggregate size of call responses in a batch, in bytes.
+    pub max_response_size: Option<usize>,
+    /// Maximum time to process a batch.
+    pub time_budget: Option<Duration>,
+}
+
+impl BatchLimits {
+    fn is_unlimited(&self) -> bool {
+        self.max_size.is_none() && self.max_response_size.is_none() && self.time_budget.is_none()
+    }
+}
+
+fn error_item(id: &serde_json::Value, code: i32, message: &str) -> Vec<u8> {
+    let error = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": code,
+            "message": message,
+        },
+        "id": id,
+    });
+    serde_json::to_vec(&error).expect("failed serializing error")
+}
+
+fn batch_response(items: Vec<Vec<u8>>) -> Response<Body> {
+    let mut body = Vec::with_capacity(items.iter().map(|item| item.len() + 1).sum::<usize>() + 1);
+    body.push(b'[');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            body.push(b',');
+        }
+        body.extend_from_slice(item);
+    }
+    body.push(b']');
+
+    let mut response = Response::new(Body::from(body));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+/// Layer enforcing [`BatchLimits`]. See the [module docs](self) for details.
+#[derive(Debug, Clone)]
+pub(crate) struct BatchLimitsLayer {
+    limits: BatchLimits,
+    max_request_body_size: usize,
+}
+
+impl BatchLimitsLayer {
+    /// Creates a layer. Returns `None` if `limits` don't limit anything. Requests with bodies larger than
+    /// `max_request_body_size` bytes are rejected.
+    pub(crate) fn new(limits: BatchLimits, max_request_body_size: usize) -> Option<Self> {
+        (!limits.is_unlimited()).then_some(Self {
+            limits,
+            max_request_body_size,
+        })
+    }
+}
+
+impl<S> Layer<S> for BatchLimitsLayer {
+    type Service = BatchLimitsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BatchLimitsService {
+            inner,
+            limits: self.limits,
+            max_request_body_size: self.max_request_body_size,
+        }
+    }
+}
+
+/// Service produced by [`BatchLimitsLayer`].
+#[derive(Clone)]
+pub(crate) struct BatchLimitsService<S> {
+    inner: S,
+    limits: BatchLimits,
+    max_request_body_size: usize,
+}
+
+impl<S: fmt::Debug> fmt::Debug for BatchLimitsService<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("BatchLimitsService")
+            .field("inner", &self.inner)
+            .field("limits", &self.limits)
+            .field("max_request_body_size", &self.max_request_body_size)
+            .finish()
+    }
+}
+
+impl<S> Service<Request<Body>> for BatchLimitsService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Send,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Body>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        if request.method() != Method::POST {
+            return self.inner.call(request).boxed();
+        }
+
+        // The inner service was polled to readiness, so we need to use it rather than its clone.
+        let clone = self.inner.clone();
+        let inner = std::mem::replace(&mut self.inner, clone);
+        let limits = self.limits;
+        let max_request_body_size = self.max_request_body_size;
+        async move {
+            let (parts, body) = request.into_parts();
+            let body = match read_request_body(&parts, body, max_request_body_size).await {
+                Ok(body) => body,
+                Err(err) => return Ok(error_response(err.status(), None)),
+            };
+            let calls = match serde_json::from_slice::<Vec<serde_json::Value>>(&body) {
+                Ok(calls) if !calls.is_empty() => calls,
+                _ => {
+                    // Not a batch (or an empty batch, which is handled by the server).
+                    let mut inner = inner;
+                    return inner
+                        .call(Request::from_parts(parts, Body::from(body)))
+                        .await;
+                }
+            };
+
+            METRICS.size[&Transport::Http].observe(calls.len());
+            if let Some(max_size) = limits.max_size {
+                if calls.len() > max_size {
+                    METRICS.rejected[&Transport::Http].inc();
+                    let message = format!("Batch is too large; max allowed size is {max_size}");
+                    let items = calls.iter().filter_map(|call| {
+                        let id = call.get("id")?; // Notifications don't get a response
+                        Some(error_item(id, TOO_BIG_BATCH_REQUEST_CODE, &message))
+                    });
+                    return Ok(batch_response(items.collect()));
+                }
+            }
+
+            let deadline = limits.time_budget.map(|budget| Instant::now() + budget);
+            let responses = calls.iter().map(|call| {
+                let mut request = Request::new(Body::from(call.to_string()));
+                *request.method_mut() = parts.method.clone();
+                *request.uri_mut() = parts.uri.clone();
+                *request.headers_mut() = parts.headers.clone();
+                request.headers_mut().remove(header::CONTENT_LENGTH);
+
+                let response = inner.clone().oneshot(request);
+                async move {
+                    if let Some(deadline) = deadline {
+                        tokio::time::timeout_at(deadline, response).await.ok()
+                    } else {
+                        Some(response.await)
+                    }
+                }
+            });
+            let responses: Vec<_> = futures::stream::iter(responses)
+                .buffered(MAX_CONCURRENT_CALLS)
+                .collect()
+                .await;
+
+            let mut items = Vec::with_capacity(calls.len());
+            let mut response_size = 0_usize;
+            for (call, response) in calls.iter().zip(responses) {
+                let id = call.get("id");
+                let Some(response) = response.transpose()? else {
+                    METRICS.timed_out_calls[&Transport::Http].inc();
+                    if let Some(id) = id {
+                        let code = i32::from(StatusCode::REQUEST_TIMEOUT.as_u16());
+                        items.push(error_item(id, code, "Batch time budget exceeded"));
+                    }
+                    continue;
+                };
+                let status = response.status();
+                // The error can only occur on I/O errors, which are impossible for a body generated by the server.
+                let Ok(response) = hyper::body::to_bytes(response.into_body()).await else {
+                    continue;
+                };
+                if response.is_empty() {
+                    continue; // Notifications don't have a response
+                }
+                // The server can respond with a non-JSON body (e.g., a plain-text HTTP error), which cannot be
+                // included into the batch response as-is.
+                if serde_json::from_slice::<serde::de::IgnoredAny>(&response).is_err() {
+                    let message = format!("Call failed with HTTP status {status}");
+                    let id = id.unwrap_or(&serde_json::Value::Null);
+                    items.push(error_item(id, ErrorCode::InternalError.code(), &message));
+                    continue;
+                }
+
+                response_size += response.len();
+                if limits
+                    .max_response_size
+                    .map_or(false, |limit| response_size > limit)
+                {
+                    METRICS.oversized_responses[&Transport::Http].inc();
+                    let message = "Batch response exceeds the size limit";
+                    let id = id.unwrap_or(&serde_json::Value::Null);
+                    items.push(error_item(id, TOO_BIG_BATCH_RESPONSE_CODE, message));
+                } else {
+                    items.push(response.to_vec());
+                }
+            }
+            Ok(batch_response(items))
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use super::*;
+
+    const MAX_REQUEST_BODY_SIZE: usize = 1_024;
+
+    /// Creates a service responding with the method name. The `sleep` method sleeps for 1 minute,
+    /// and the `plain` method responds with a non-JSON body.
+    fn echo_service(
+        limits: BatchLimits,
+    ) -> impl Service<Request<Body>, Response = Response<Body>, Error = Infallible> {
+        let inner = tower::service_fn(|request: Request<Body>| async move {
+            let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+            let call: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            if call["method"] == "sleep" {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+            if call["method"] == "plain" {
+                let mut response = Response::new(Body::from("Service unavailable"));
+                *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+                return Ok(response);
+            }
+            let response = match call.get("id") {
+                Some(id) => serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "result": call["method"],
+                    "id": id,
+                })
+                .to_string(),
+                None => String::new(),
+            };
+            Ok::<_, Infallible>(Response::new(Body::from(response)))
+        });
+        BatchLimitsLayer::new(limits, MAX_REQUEST_BODY_SIZE)
+            .unwrap()
+            .layer(inner)
+    }
+
+    async fn send(
+        service: &mut impl Service<Request<Body>, Response = Response<Body>, Error = Infallible>,
+        body: String,
+    ) -> Response<Body> {
+        let request = Request::post("/").body(Body::from(body)).unwrap();
+        futures::future::poll_fn(|cx| service.poll_ready(cx))
+            .await
+            .unwrap();
+        service.call(request).await.unwrap()
+    }
+
+    async fn call(
+        service: &mut impl Service<Request<Body>, Response = Response<Body>, Error = Infallible>,
+        body: serde_json::Value,
+    ) -> serde_json::Value {
+        let response = send(service, body.to_string()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn batch(methods: &[&str]) -> serde_json::Value {
+        let calls = methods.iter().enumerate().map(|(i, method)| {
+            serde_json::json!({ "jsonrpc": "2.0", "id": i, "method": method, "params": [] })
+        });
+        serde_json::Value::Array(calls.collect())
+    }
+
+    #[tokio::test]
+    async fn single_calls_are_not_affected() {
+        let mut service = echo_service(BatchLimits {
+            max_size: Some(1),
+            ..BatchLimits::default()
+        });
+        let single_call = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "test" });
+        let response = call(&mut service, single_call).await;
+        assert_eq!(response["result"], "test");
+    }
+
+    #[tokio::test]
+    async fn limiting_batch_size() {
+        let mut service = echo_service(BatchLimits {
+            max_size: Some(2),
+            ..BatchLimits::default()
+        });
+        let response = call(&mut service, batch(&["first", "second"])).await;
+        let results: Vec<_> = response
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["result"].as_str().unwrap())
+            .collect();
+        assert_eq!(results, ["first", "second"]);
+
+        let response = call(&mut service, batch(&["first", "second", "third"])).await;
+        let response = response.as_array().unwrap();
+        assert_eq!(response.len(), 3);
+        for (i, item) in response.iter().enumerate() {
+            assert_eq!(item["id"], i);
+            assert_eq!(item["error"]["code"], TOO_BIG_BATCH_REQUEST_CODE);
+        }
+    }
+
+    #[tokio::test]
+    async fn notifications_in_batch() {
+        let mut service = echo_service(BatchLimits {
+            max_size: Some(5),
+            ..BatchLimits::default()
+        });
+        let body = serde_json::json!([
+            { "jsonrpc": "2.0", "method": "notification" },
+            { "jsonrpc": "2.0", "id": 1, "method": "call" },
+        ]);
+        let response = call(&mut service, body).await;
+        let response = response.as_array().unwrap();
+        assert_eq!(response.len(), 1);
+        assert_eq!(response[0]["result"], "call");
+    }
+
+    #[tokio::test]
+    async fn limiting_response_size() {
+        let single_response_len =
+            serde_json::json!({ "jsonrpc": "2.0", "result": "call", "id": 0 })
+                .to_string()
+                .len();
+        let mut service = echo_service(BatchLimits {
+            max_response_size: Some(single_response_len * 2),
+            ..BatchLimits::default()
+        });
+
+        let response = call(&mut service, batch(&["call", "call", "call", "call"])).await;
+        let response = response.as_array().unwrap();
+        assert_eq!(response.len(), 4);
+        for (i, item) in response.iter().enumerate() {
+            assert_eq!(item["id"], i);
+            if i < 2 {
+                assert_eq!(item["result"], "call");
+            } else {
+                assert_eq!(item["error"]["code"], TOO_BIG_BATCH_RESPONSE_CODE);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn limiting_batch_time() {
+        let mut service = echo_service(BatchLimits {
+            time_budget: Some(Duration::from_millis(100)),
+            ..BatchLimits::default()
+        });
+
+        let response = call(&mut service, batch(&["first", "sleep", "third"])).await;
+        let response = response.as_array().unwrap();
+        assert_eq!(response.len(), 3);
+        assert_eq!(response[0]["result"], "first");
+        assert_eq!(response[1]["id"], 1);
+        assert_eq!(response[1]["error"]["code"], 408);
+        assert_eq!(response[2]["result"], "third");
+    }
+
+    #[tokio::test]
+    async fn rejecting_oversized_request_body() {
+        let mut service = echo_service(BatchLimits {
+            max_size: Some(100),
+            ..BatchLimits::default()
+        });
+        let methods = vec!["call"; 50];
+        let body = batch(&methods).to_string();
+        assert!(body.len() > MAX_REQUEST_BODY_SIZE);
+
+        let response = send(&mut service, body).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn non_json_responses_in_batch() {
+        let mut service = echo_service(BatchLimits {
+            max_size: Some(5),
+            ..BatchLimits::default()
+        });
+        let response = call(&mut service, batch(&["first", "plain", "third"])).await;
+        let response = response.as_array().unwrap();
+        assert_eq!(response.len(), 3);
+        assert_eq!(response[0]["result"], "first");
+        assert_eq!(response[1]["id"], 1);
+        assert_eq!(
+            response[1]["error"]["code"],
+            ErrorCode::InternalError.code()
+        );
+        assert_eq!(response[2]["result"], "third");
+    }
+
+    #[tokio::test]
+    async fn bounding_call_concurrency() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let inner = tower::service_fn({
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            move |request: Request<Body>| {
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                async move {
+                    let count = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(count, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                    let call: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                    let response = serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "result": call["method"],
+                        "id": call["id"],
+                    });
+                    Ok::<_, Infallible>(Response::new(Body::from(response.to_string())))
+                }
+            }
+        });
+        let limits = BatchLimits {
+            max_size: Some(100),
+            ..BatchLimits::default()
+        };
+        let mut service = BatchLimitsLayer::new(limits, usize::MAX)
+            .unwrap()
+            .layer(inner);
+
+        let methods = vec!["call"; MAX_CONCURRENT_CALLS * 3];
+        let response = call(&mut service, batch(&methods)).await;
+        let response = response.as_array().unwrap();
+        assert_eq!(response.len(), methods.len());
+        for (i, item) in response.iter().enumerate() {
+            assert_eq!(item["id"], i);
+        }
+        let max_in_flight = max_in_flight.load(Ordering::SeqCst);
+        assert!(max_in_flight <= MAX_CONCURRENT_CALLS, "{max_in_flight}");
+    }
+}
@@ -0,0 +1,375 @@
+//! HTTP-level middleware compressing JSON-RPC responses.
+//!
+//! The encoding is negotiated using the `Accept-Encoding` request header; Brotli and gzip are supported, with Brotli
+//! preferred if the client accepts both with the same quality. Only responses larger than the configured threshold
+//! are compressed, since compressing small responses wastes CPU and can even increase their size. Responses produced
+//! by `jsonrpsee` are fully buffered, so buffering them here doesn't increase memory usage. To keep buffering bounded
+//! regardless, only responses with a known size not exceeding the response size limit are compressed; other responses
+//! are passed through as-is. Request bodies are never decompressed by the server.
+
+use std::{
+    fmt,
+    io::Write as _,
+    task::{Context, Poll},
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use hyper::{
+    body::{Bytes, HttpBody as _},
+    header::{self, HeaderValue},
+    Body, Method, Request, Response,
+};
+use tower::{Layer, Service};
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Metrics};
+
+/// Brotli quality used for compression. The maximum quality (11) is too slow for multi-MB responses.
+const BROTLI_QUALITY: u32 = 5;
+/// Base-2 logarithm of the Brotli window size.
+const BROTLI_WINDOW_LOG: u32 = 22;
+const BROTLI_BUFFER_SIZE: usize = 4_096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "encoding", rename_all = "snake_case")]
+enum ContentEncoding {
+    Brotli,
+    Gzip,
+}
+
+impl ContentEncoding {
+    fn header_value(self) -> HeaderValue {
+        HeaderValue::from_static(match self {
+            Self::Brotli => "br",
+            Self::Gzip => "gzip",
+        })
+    }
+
+    /// Selects the encoding based on the `Accept-Encoding` header value.
+    fn negotiate(accept_encoding: &str) -> Option<Self> {
+        let mut selected = None::<(Self, f32)>;
+        for item in accept_encoding.split(',') {
+            let mut parts = item.split(';').map(str::trim);
+            let encoding = match parts.next() {
+                Some("br") => Self::Brotli,
+                Some("gzip" | "x-gzip") => Self::Gzip,
+                _ => continue,
+            };
+            let quality = parts
+                .find_map(|param| param.strip_prefix("q="))
+                .map_or(Some(1.0), |quality| quality.parse::<f32>().ok());
+            let Some(quality) = quality else {
+                continue; // Malformed quality value
+            };
+            if quality <= 0.0 {
+                continue;
+            }
+
+            let is_better = selected.map_or(true, |(selected_encoding, selected_quality)| {
+                if quality == selected_quality {
+                    encoding == Self::Brotli && selected_encoding != Self::Brotli
+                } else {
+                    quality > selected_quality
+                }
+            });
+            if is_better {
+                selected = Some((encoding, quality));
+            }
+        }
+        selected.map(|(encoding, _)| encoding)
+    }
+
+    fn compress(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Brotli => {
+                let mut encoder = brotli::CompressorWriter::new(
+                    Vec::new(),
+                    BROTLI_BUFFER_SIZE,
+                    BROTLI_QUALITY,
+                    BROTLI_WINDOW_LOG,
+                );
+                encoder.write_all(data)?;
+                encoder.flush()?;
+                Ok(encoder.into_inner())
+            }
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_compression")]
+struct CompressionMetrics {
+    /// Number of compressed responses.
+    responses: Family<ContentEncoding, Counter>,
+    /// Total size of compressed responses before compression.
+    original_bytes: Family<ContentEncoding, Counter>,
+    /// Number of bytes saved by compression.
+    saved_bytes: Family<ContentEncoding, Counter>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<CompressionMetrics> = vise::Global::new();
+
+/// Layer compressing HTTP responses. See the [module docs](self) for details.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CompressionLayer {
+    min_response_size: usize,
+    max_response_size: usize,
+}
+
+impl CompressionLayer {
+    /// Creates a layer compressing responses with size greater than or equal to `min_response_size` bytes
+    /// and not exceeding `max_response_size` bytes.
+    pub(crate) fn new(min_response_size: usize, max_response_size: usize) -> Self {
+        Self {
+            min_response_size,
+            max_response_size,
+        }
+    }
+}
+
+impl<S> Layer<S> for CompressionLayer {
+    type Service = CompressionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CompressionService {
+            inner,
+            min_response_size: self.min_response_size,
+            max_response_size: self.max_response_size,
+        }
+    }
+}
+
+/// Service produced by [`CompressionLayer`].
+#[derive(Clone)]
+pub(crate) struct CompressionService<S> {
+    inner: S,
+    min_response_size: usize,
+    max_response_size: usize,
+}
+
+impl<S: fmt::Debug> fmt::Debug for CompressionService<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("CompressionService")
+            .field("inner", &self.inner)
+            .field("min_response_size", &self.min_response_size)
+            .field("max_response_size", &self.max_response_size)
+            .finish()
+    }
+}
+
+impl<S> Service<Request<Body>> for CompressionService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Send,
+    S::Future: Send + 'static,
+    S::Error: Send,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Body>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // JSON-RPC calls are only sent in HTTP `POST` requests; other requests are WebSocket handshakes.
+        let encoding = if request.method() == Method::POST {
+            request
+                .headers()
+                .get(header::ACCEPT_ENCODING)
+                .and_then(|value| value.to_str().ok())
+                .and_then(ContentEncoding::negotiate)
+        } else {
+            None
+        };
+        let response = self.inner.call(request);
+        let Some(encoding) = encoding else {
+            return response.boxed();
+        };
+
+        let min_response_size = self.min_response_size as u64;
+        let max_response_size = self.max_response_size as u64;
+        async move {
+            let response = response.await?;
+            if response.headers().contains_key(header::CONTENT_ENCODING) {
+                return Ok(response);
+            }
+            // `jsonrpsee` responses are never streamed, so their size is known in advance. Responses of unknown
+            // or excessive size are passed through without buffering.
+            let body_size = response.body().size_hint().exact();
+            let is_compressible = body_size.map_or(false, |size| {
+                (min_response_size..=max_response_size).contains(&size)
+            });
+            if !is_compressible {
+                return Ok(response);
+            }
+            let (mut parts, body) = response.into_parts();
+            let Ok(body) = hyper::body::to_bytes(body).await else {
+                // The body has a known size, so it's not streamed and this shouldn't happen.
+                return Ok(Response::from_parts(parts, Body::empty()));
+            };
+
+            let compressed = match compress_in_background(encoding, body.clone()).await {
+                Ok(compressed) if compressed.len() < body.len() => compressed,
+                Ok(_) => return Ok(Response::from_parts(parts, Body::from(body))),
+                Err(err) => {
+                    tracing::warn!("Failed compressing response with {encoding:?}: {err}");
+                    return Ok(Response::from_parts(parts, Body::from(body)));
+                }
+            };
+            METRICS.responses[&encoding].inc();
+            METRICS.original_bytes[&encoding].inc_by(body.len() as u64);
+            METRICS.saved_bytes[&encoding].inc_by((body.len() - compressed.len()) as u64);
+
+            parts
+                .headers
+                .insert(header::CONTENT_ENCODING, encoding.header_value());
+            parts
+                .headers
+                .insert(header::CONTENT_LENGTH, compressed.len().into());
+            parts
+                .headers
+                .append(header::VARY, HeaderValue::from_static("accept-encoding"));
+            Ok(Response::from_parts(parts, Body::from(compressed)))
+        }
+        .boxed()
+    }
+}
+
+/// Compresses the data on a blocking thread, since compressing large responses can take a while.
+async fn compress_in_background(
+    encoding: ContentEncoding,
+    data: Bytes,
+) -> std::io::Result<Vec<u8>> {
+    tokio::task::spawn_blocking(move || encoding.compress(&data))
+        .await
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, io::Read as _};
+
+    use super::*;
+
+    const MIN_RESPONSE_SIZE: usize = 1_024;
+    const MAX_RESPONSE_SIZE: usize = 1_024 * 1_024;
+
+    fn response_service(
+        response_size: usize,
+    ) -> impl Service<Request<Body>, Response = Response<Body>, Error = Infallible> {
+        let inner = tower::service_fn(move |_: Request<Body>| async move {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"result":"{}"}}"#,
+                "0".repeat(response_size)
+            );
+            Ok::<_, Infallible>(Response::new(Body::from(body)))
+        });
+        CompressionLayer::new(MIN_RESPONSE_SIZE, MAX_RESPONSE_SIZE).layer(inner)
+    }
+
+    fn post(accept_encoding: Option<&str>) -> Request<Body> {
+        let mut builder = Request::post("/");
+        if let Some(accept_encoding) = accept_encoding {
+            builder = builder.header(header::ACCEPT_ENCODING, accept_encoding);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    async fn call(
+        service: &mut impl Service<Request<Body>, Response = Response<Body>, Error = Infallible>,
+        request: Request<Body>,
+    ) -> (Option<HeaderValue>, Bytes) {
+        futures::future::poll_fn(|cx| service.poll_ready(cx))
+            .await
+            .unwrap();
+        let response = service.call(request).await.unwrap();
+        let encoding = response.headers().get(header::CONTENT_ENCODING).cloned();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (encoding, body)
+    }
+
+    #[test]
+    fn negotiating_encoding() {
+        let negotiate = ContentEncoding::negotiate;
+        assert_eq!(negotiate("gzip"), Some(ContentEncoding::Gzip));
+        assert_eq!(
+            negotiate("gzip, deflate, br"),
+            Some(ContentEncoding::Brotli)
+        );
+        assert_eq!(negotiate("br;q=0.5, gzip"), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate("br;q=0, gzip;q=0.1"), Some(ContentEncoding::Gzip));
+        assert_eq!(negotiate("deflate, identity"), None);
+        assert_eq!(negotiate("br;q=0"), None);
+        assert_eq!(negotiate(""), None);
+    }
+
+    #[tokio::test]
+    async fn compressing_responses() {
+        let mut service = response_service(MIN_RESPONSE_SIZE);
+        let (encoding, uncompressed_body) = call(&mut service, post(None)).await;
+        assert_eq!(encoding, None);
+
+        let (encoding, body) = call(&mut service, post(Some("gzip"))).await;
+        assert_eq!(encoding.unwrap(), "gzip");
+        assert!(body.len() < uncompressed_body.len());
+        let mut decompressed = vec![];
+        flate2::read::GzDecoder::new(&*body)
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, uncompressed_body);
+
+        let (encoding, body) = call(&mut service, post(Some("gzip, br"))).await;
+        assert_eq!(encoding.unwrap(), "br");
+        let mut decompressed = vec![];
+        brotli::Decompressor::new(&*body, BROTLI_BUFFER_SIZE)
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, uncompressed_body);
+
+        let handshake = Request::get("/")
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .body(Body::empty())
+            .unwrap();
+        let (encoding, _) = call(&mut service, handshake).await;
+        assert_eq!(encoding, None);
+    }
+
+    #[tokio::test]
+    async fn small_responses_are_not_compressed() {
+        let mut service = response_service(10);
+        let (encoding, body) = call(&mut service, post(Some("gzip, br"))).await;
+        assert_eq!(encoding, None);
+        assert!(body.starts_with(br#"{"jsonrpc":"2.0""#));
+    }
+
+    #[tokio::test]
+    async fn large_responses_are_not_compressed() {
+        let mut service = response_service(MAX_RESPONSE_SIZE);
+        let (encoding, body) = call(&mut service, post(Some("gzip, br"))).await;
+        assert_eq!(encoding, None);
+        assert!(body.len() > MAX_RESPONSE_SIZE);
+    }
+
+    #[tokio::test]
+    async fn streamed_responses_are_not_buffered() {
+        let inner = tower::service_fn(|_: Request<Body>| async {
+            let (mut sender, body) = Body::channel();
+            tokio::spawn(async move {
+                let chunk = Bytes::from("0".repeat(MIN_RESPONSE_SIZE));
+                sender.send_data(chunk).await.ok();
+            });
+            Ok::<_, Infallible>(Response::new(body))
+        });
+        let mut service = CompressionLayer::new(MIN_RESPONSE_SIZE, MAX_RESPONSE_SIZE).layer(inner);
+        let (encoding, body) = call(&mut service, post(Some("gzip"))).await;
+        assert_eq!(encoding, None);
+        assert_eq!(body.len(), MIN_RESPONSE_SIZE);
+    }
+}
@@ -0,0 +1,133 @@
+//! Compute unit costs of JSON-RPC methods.
+//!
+//! Compute units estimate the server resources spent on a method call, so that rate limits can account
+//! for heavy methods (e.g., `eth_call` executes a transaction in the VM, while `eth_chainId` returns a constant).
+
+use std::borrow::Cow;
+
+use serde::Deserialize;
+
+/// Cost of methods not listed in [`method_compute_units()`], which are mostly simple lookups by a Postgres index.
+pub(crate) const DEFAULT_COMPUTE_UNITS: u32 = 10;
+
+/// Returns the compute unit cost of a call to the specified method.
+pub(crate) fn method_compute_units(method: &str) -> u32 {
+    match method {
+        // Constants or values cached in memory.
+        "eth_chainId"
+        | "eth_accounts"
+        | "eth_coinbase"
+        | "eth_hashrate"
+        | "eth_mining"
+        | "eth_protocolVersion"
+        | "eth_blockNumber"
+        | "net_version"
+        | "net_listening"
+        | "net_peerCount"
+        | "web3_clientVersion"
+        | "zks_L1ChainId"
+        | "zks_getMainContract"
+        | "zks_getTestnetPaymaster"
+        | "zks_getBridgeContracts"
+        | "zks_getBridgehubContract"
+        | "zks_L1BatchNumber"
+        | "zks_getL1GasPrice"
+        | "zks_getFeeParams"
+        | "eth_unsubscribe"
+        | "eth_uninstallFilter"
+        | "zks_unsubscribe"
+        | "zks_unsubscribeStreamLogs" => 1,
+
+        // Filters and subscriptions, which are polled or pushed later.
+        "eth_newFilter"
+        | "eth_newBlockFilter"
+        | "eth_newPendingTransactionFilter"
+        | "eth_subscribe"
+        | "zks_subscribe"
+        | "eth_getFilterChanges" => 20,
+        "eth_feeHistory" => 20,
+
+        // Methods loading many entities from Postgres.
+        "eth_getLogs" | "eth_getFilterLogs" | "zks_getLogsPaginated" | "zks_streamLogs" => 75,
+        "eth_getBlockReceipts" | "zks_getRawBlockTransactions" | "zks_getAllAccountBalances" => 50,
+        "zks_getL2ToL1LogProof" | "zks_getL2ToL1MsgProof" => 50,
+        "txpool_content" | "txpool_contentFrom" | "txpool_inspect" | "txpool_status" => 50,
+        "eth_getProof" | "zks_getProof" => 100,
+
+        // Methods executing transactions in the VM.
+        "eth_call" => 50,
+        "eth_sendRawTransaction" | "eth_sendRawTransactionConditional" => 50,
+        "eth_estimateGas"
+        | "zks_estimateFee"
+        | "zks_estimateGasL1ToL2"
+        | "eth_createAccessList" => 100,
+        "debug_traceCall" | "debug_traceTransaction" => 200,
+        "debug_traceBlockByNumber" | "debug_traceBlockByHash" => 500,
+
+        _ => DEFAULT_COMPUTE_UNITS,
+    }
+}
+
+/// Returns names of methods called in an HTTP request body, which can contain a single call or a batch of calls.
+/// Returns `None` if the body is malformed.
+pub(crate) fn request_methods(body: &[u8]) -> Option<Vec<Cow<'_, str>>> {
+    #[derive(Debug, Deserialize)]
+    struct Call<'a> {
+        #[serde(borrow, default)]
+        method: Cow<'a, str>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum Request<'a> {
+        #[serde(borrow)]
+        Single(Call<'a>),
+        #[serde(borrow)]
+        Batch(Vec<Call<'a>>),
+    }
+
+    match serde_json::from_slice::<Request<'_>>(body).ok()? {
+        Request::Single(call) => Some(vec![call.method]),
+        Request::Batch(calls) => Some(calls.into_iter().map(|call| call.method).collect()),
+    }
+}
+
+/// Returns the compute unit cost of an HTTP request body, which can contain a single call or a batch of calls.
+/// Malformed bodies cost a single compute unit; they will be rejected by the server anyway.
+pub(crate) fn request_compute_units(body: &[u8]) -> u32 {
+    let Some(methods) = request_methods(body) else {
+        return 1;
+    };
+    methods
+        .iter()
+        .map(|method| method_compute_units(method))
+        .fold(0_u32, u32::saturating_add)
+        .max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computing_request_costs() {
+        let call = r#"{"jsonrpc":"2.0","id":1,"method":"eth_chainId","params":[]}"#;
+        assert_eq!(request_compute_units(call.as_bytes()), 1);
+        let call = r#"{"jsonrpc":"2.0","id":1,"method":"eth_call","params":[]}"#;
+        assert_eq!(request_compute_units(call.as_bytes()), 50);
+        let call = r#"{"jsonrpc":"2.0","id":1,"method":"eth_unknown","params":[]}"#;
+        assert_eq!(
+            request_compute_units(call.as_bytes()),
+            DEFAULT_COMPUTE_UNITS
+        );
+
+        let batch = r#"[
+            {"jsonrpc":"2.0","id":1,"method":"eth_chainId"},
+            {"jsonrpc":"2.0","id":2,"method":"eth_getLogs","params":[{}]}
+        ]"#;
+        assert_eq!(request_compute_units(batch.as_bytes()), 76);
+
+        assert_eq!(request_compute_units(b"[]"), 1);
+        assert_eq!(request_compute_units(b"garbage"), 1);
+    }
+}
@@ -0,0 +1,167 @@
+//! CORS policy for the HTTP JSON-RPC server.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use hyper::{
+    header::{self, HeaderName, HeaderValue},
+    Method,
+};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+use super::REQUEST_ID_HEADER;
+
+/// CORS policy for the HTTP JSON-RPC server. The default policy allows requests from any origin
+/// and lets browsers cache preflight responses for [`Self::DEFAULT_MAX_AGE`].
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// `None` means that any origin is allowed.
+    allowed_origins: Option<Vec<HeaderValue>>,
+    allowed_headers: Vec<HeaderName>,
+    max_age: Duration,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: None,
+            allowed_headers: vec![],
+            max_age: Self::DEFAULT_MAX_AGE,
+        }
+    }
+}
+
+impl CorsConfig {
+    pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(3_600);
+
+    /// Restricts cross-origin requests to the specified origins (e.g., `https://app.example.com`).
+    /// The `*` origin allows requests from any origin.
+    pub fn with_allowed_origins(mut self, origins: &[String]) -> anyhow::Result<Self> {
+        if origins.iter().any(|origin| origin == "*") {
+            self.allowed_origins = None;
+            return Ok(self);
+        }
+        let origins = origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin).with_context(|| format!("invalid origin `{origin}`"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        self.allowed_origins = Some(origins);
+        Ok(self)
+    }
+
+    /// Allows the specified request headers in addition to `Content-Type` and headers used by the server itself.
+    pub fn with_allowed_headers(mut self, headers: &[String]) -> anyhow::Result<Self> {
+        self.allowed_headers = headers
+            .iter()
+            .map(|name| {
+                HeaderName::try_from(name.as_str())
+                    .with_context(|| format!("invalid header name `{name}`"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(self)
+    }
+
+    /// Sets the time for which browsers may cache responses to preflight requests.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Creates a layer enforcing this policy. `server_headers` are request headers used by the server
+    /// (e.g., for authentication), which are always allowed.
+    pub(crate) fn layer(&self, server_headers: Vec<HeaderName>) -> CorsLayer {
+        let allowed_origins = match &self.allowed_origins {
+            Some(origins) => AllowOrigin::list(origins.iter().cloned()),
+            None => Any.into(),
+        };
+        let mut allowed_headers = vec![header::CONTENT_TYPE];
+        allowed_headers.extend(server_headers);
+        allowed_headers.extend(self.allowed_headers.iter().cloned());
+
+        CorsLayer::new()
+            .allow_methods([Method::POST])
+            .allow_origin(allowed_origins)
+            .allow_headers(allowed_headers)
+            .expose_headers([REQUEST_ID_HEADER])
+            .max_age(self.max_age)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use hyper::{Body, Request, Response, StatusCode};
+    use tower::{Layer, Service};
+
+    use super::*;
+
+    async fn preflight(config: &CorsConfig, origin: &str, headers: &str) -> Response<Body> {
+        let mut service = config
+            .layer(vec![])
+            .layer(tower::service_fn(|_: Request<Body>| async {
+                Ok::<_, Infallible>(Response::new(Body::empty()))
+            }));
+        let request = Request::options("/")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, headers)
+            .body(Body::empty())
+            .unwrap();
+        service.call(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn default_cors_policy() {
+        let config = CorsConfig::default();
+        let response = preflight(&config, "https://app.example.com", "content-type").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "3600");
+    }
+
+    #[tokio::test]
+    async fn restricted_cors_policy() {
+        let config = CorsConfig::default()
+            .with_allowed_origins(&["https://app.example.com".to_owned()])
+            .unwrap()
+            .with_allowed_headers(&["x-client-version".to_owned()])
+            .unwrap()
+            .with_max_age(Duration::from_secs(600));
+
+        let response = preflight(&config, "https://app.example.com", "x-client-version").await;
+        let headers = response.headers();
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        let allowed_headers = headers[header::ACCESS_CONTROL_ALLOW_HEADERS]
+            .to_str()
+            .unwrap();
+        assert!(
+            allowed_headers.contains("x-client-version"),
+            "{allowed_headers}"
+        );
+        assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+
+        let response = preflight(&config, "https://evil.example.com", "content-type").await;
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[test]
+    fn invalid_cors_config() {
+        let err = CorsConfig::default()
+            .with_allowed_origins(&["https://app.example.com\n".to_owned()])
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid origin"), "{err}");
+        let err = CorsConfig::default()
+            .with_allowed_headers(&["x client".to_owned()])
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid header name"), "{err}");
+    }
+}
@@ -0,0 +1,289 @@
+//! Draining of JSON-RPC servers on shutdown.
+//!
+//! Once a server receives a stop signal, it is reported as shutting down (i.e., not ready) by its health check
+//! and enters the drain phase:
+//!
+//! - New HTTP requests and WebSocket handshakes are rejected with 503 Service Unavailable, with the `Retry-After`
+//!   and `Connection: close` headers set, so that clients or load balancers retry them on another server instance.
+//! - New calls over established WebSocket connections are rejected with an error asking the client to reconnect.
+//! - Requests and calls that were already being processed are allowed to complete within a deadline.
+//!
+//! After the drain, the server is stopped; `jsonrpsee` closes the remaining WebSocket connections with close frames.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use hyper::{
+    header::{self, HeaderValue},
+    Body, Request, Response, StatusCode,
+};
+use pin_project_lite::pin_project;
+use tokio::sync::watch;
+use tower::{Layer, Service};
+use vise::{Counter, Metrics};
+use zksync_web3_decl::jsonrpsee::{
+    server::middleware::rpc::{layer::ResponseFuture, RpcServiceT},
+    types::{error::ErrorCode, ErrorObject, Request as RpcRequest},
+    MethodResponse,
+};
+
+use super::api_keys::error_response;
+
+/// Suggested delay before retrying a request rejected during the drain.
+const RETRY_AFTER: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_drain")]
+struct DrainMetrics {
+    /// Number of HTTP requests and WebSocket handshakes rejected because the server is draining.
+    rejected_requests: Counter,
+    /// Number of calls over WebSocket connections rejected because the server is draining.
+    rejected_calls: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<DrainMetrics> = vise::Global::new();
+
+/// Drain state shared by the HTTP- and RPC-level middleware of a server. See the [module docs](self) for details.
+#[derive(Debug)]
+pub(crate) struct ServerDrain {
+    is_draining: AtomicBool,
+    in_flight_requests: watch::Sender<usize>,
+}
+
+impl Default for ServerDrain {
+    fn default() -> Self {
+        Self {
+            is_draining: AtomicBool::new(false),
+            in_flight_requests: watch::channel(0).0,
+        }
+    }
+}
+
+impl ServerDrain {
+    fn is_draining(&self) -> bool {
+        self.is_draining.load(Ordering::SeqCst)
+    }
+
+    /// Registers a new request. Returns `None` if the server is draining.
+    fn enter(self: &Arc<Self>) -> Option<InFlightGuard> {
+        // Increment the counter before checking the flag, so that `drain()` cannot miss the request.
+        let guard = InFlightGuard::new(self.clone());
+        (!self.is_draining()).then_some(guard)
+    }
+
+    /// Starts the drain and waits until all in-flight requests are completed. Returns `false` if requests
+    /// haven't completed within `timeout`.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.is_draining.store(true, Ordering::SeqCst);
+        let mut in_flight_requests = self.in_flight_requests.subscribe();
+        let all_completed = in_flight_requests.wait_for(|&count| count == 0);
+        tokio::time::timeout(timeout, all_completed).await.is_ok()
+    }
+}
+
+#[derive(Debug)]
+struct InFlightGuard(Arc<ServerDrain>);
+
+impl InFlightGuard {
+    fn new(drain: Arc<ServerDrain>) -> Self {
+        drain.in_flight_requests.send_modify(|count| *count += 1);
+        Self(drain)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.in_flight_requests.send_modify(|count| *count -= 1);
+    }
+}
+
+/// HTTP-level layer rejecting requests while the server is draining.
+#[derive(Debug, Clone)]
+pub(crate) struct DrainLayer {
+    drain: Arc<ServerDrain>,
+}
+
+impl DrainLayer {
+    pub(crate) fn new(drain: Arc<ServerDrain>) -> Self {
+        Self { drain }
+    }
+}
+
+impl<S> Layer<S> for DrainLayer {
+    type Service = DrainService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DrainService {
+            inner,
+            drain: self.drain.clone(),
+        }
+    }
+}
+
+/// Service produced by [`DrainLayer`].
+#[derive(Debug, Clone)]
+pub(crate) struct DrainService<S> {
+    inner: S,
+    drain: Arc<ServerDrain>,
+}
+
+impl<S> Service<Request<Body>> for DrainService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Future: Send + 'static,
+    S::Error: Send,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Body>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let Some(guard) = self.drain.enter() else {
+            METRICS.rejected_requests.inc();
+            let mut response = error_response(StatusCode::SERVICE_UNAVAILABLE, Some(RETRY_AFTER));
+            response
+                .headers_mut()
+                .insert(header::CONNECTION, HeaderValue::from_static("close"));
+            return futures::future::ready(Ok(response)).boxed();
+        };
+
+        let response = self.inner.call(request);
+        async move {
+            let response = response.await;
+            drop(guard);
+            response
+        }
+        .boxed()
+    }
+}
+
+/// RPC-level middleware rejecting calls over WebSocket connections while the server is draining.
+#[derive(Debug)]
+pub(crate) struct DrainMiddleware<S> {
+    inner: S,
+    drain: Arc<ServerDrain>,
+}
+
+impl<S> DrainMiddleware<S> {
+    pub fn new(inner: S, drain: Arc<ServerDrain>) -> Self {
+        Self { inner, drain }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for DrainMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = ResponseFuture<WithInFlightGuard<S::Future>>;
+
+    fn call(&self, request: RpcRequest<'a>) -> Self::Future {
+        let Some(guard) = self.drain.enter() else {
+            METRICS.rejected_calls.inc();
+            let code = ErrorCode::ServerError(StatusCode::SERVICE_UNAVAILABLE.as_u16().into());
+            let rp = MethodResponse::error(
+                request.id,
+                ErrorObject::borrowed(
+                    code.code(),
+                    "Server is shutting down; reconnect to retry the call",
+                    None,
+                ),
+            );
+            return ResponseFuture::ready(rp);
+        };
+
+        ResponseFuture::future(WithInFlightGuard {
+            inner: self.inner.call(request),
+            _guard: guard,
+        })
+    }
+}
+
+pin_project! {
+    #[derive(Debug)]
+    pub(crate) struct WithInFlightGuard<F> {
+        #[pin]
+        inner: F,
+        _guard: InFlightGuard,
+    }
+}
+
+impl<F: Future> Future for WithInFlightGuard<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn draining_http_requests() {
+        let drain = Arc::<ServerDrain>::default();
+        let (response_sender, response_receiver) = oneshot::channel::<()>();
+        let mut response_receiver = Some(response_receiver);
+        let mut service =
+            DrainLayer::new(drain.clone()).layer(tower::service_fn(move |_: Request<Body>| {
+                let response_receiver = response_receiver.take();
+                async move {
+                    if let Some(receiver) = response_receiver {
+                        receiver.await.ok();
+                    }
+                    Ok::<_, Infallible>(Response::new(Body::empty()))
+                }
+            }));
+
+        let in_flight_response = service.call(Request::post("/").body(Body::empty()).unwrap());
+        let in_flight_response = tokio::spawn(in_flight_response);
+        assert_eq!(*drain.in_flight_requests.borrow(), 1);
+
+        let drain_task = tokio::spawn({
+            let drain = drain.clone();
+            async move { drain.drain(Duration::from_secs(10)).await }
+        });
+        while !drain.is_draining() {
+            tokio::task::yield_now().await;
+        }
+        let rejected_response = service
+            .call(Request::post("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(rejected_response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(rejected_response.headers()[header::RETRY_AFTER], "1");
+        assert_eq!(rejected_response.headers()[header::CONNECTION], "close");
+        assert!(!drain_task.is_finished());
+
+        response_sender.send(()).unwrap();
+        let response = in_flight_response.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(drain_task.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn drain_timeout() {
+        let drain = Arc::<ServerDrain>::default();
+        let _guard = drain.enter().unwrap();
+        assert!(!drain.drain(Duration::from_millis(10)).await);
+        assert!(drain.enter().is_none());
+    }
+}
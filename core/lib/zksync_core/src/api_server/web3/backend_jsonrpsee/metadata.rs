@@ -1,6 +1,12 @@
 //! Method metadata.
 
-use std::{cell::RefCell, mem, sync::Arc, time::Instant};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    mem,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use thread_local::ThreadLocal;
 use zksync_types::api;
@@ -11,29 +17,35 @@ use zksync_web3_decl::{
 
 #[cfg(test)]
 use super::testonly::RecordedMethodCalls;
-use crate::api_server::web3::metrics::API_METRICS;
+use super::{compute_units::method_compute_units, request_id::RequestId};
+use crate::api_server::web3::metrics::{
+    ApiTransportLabel, ErrorClass, RollingLatencies, API_METRICS,
+};
 
 /// Metadata assigned to a JSON-RPC method call.
 #[derive(Debug, Clone)]
 pub(crate) struct MethodMetadata {
     pub name: &'static str,
+    /// ID of the request the call belongs to. Calls in an HTTP batch share the same ID.
+    pub request_id: RequestId,
     pub started_at: Instant,
     /// Block ID requested by the call.
     pub block_id: Option<api::BlockId>,
     /// Difference between the latest block number and the requested block ID.
     pub block_diff: Option<u32>,
-    /// Did this call return an app-level error?
-    pub has_app_error: bool,
+    /// Class of the app-level error returned by this call, if any.
+    pub app_error: Option<ErrorClass>,
 }
 
 impl MethodMetadata {
-    fn new(name: &'static str) -> Self {
+    fn new(name: &'static str, request_id: RequestId) -> Self {
         Self {
             name,
+            request_id,
             started_at: Instant::now(),
             block_id: None,
             block_diff: None,
-            has_app_error: false,
+            app_error: None,
         }
     }
 }
@@ -62,6 +74,8 @@ impl Drop for CurrentMethodGuard<'_> {
 #[derive(Debug, Default)]
 pub(crate) struct MethodTracer {
     inner: ThreadLocal<CurrentMethodInner>,
+    /// Recent call latencies used to report rolling percentiles.
+    rolling_latencies: Mutex<HashMap<(&'static str, ApiTransportLabel), RollingLatencies>>,
     #[cfg(test)]
     recorder: RecordedMethodCalls,
 }
@@ -88,10 +102,16 @@ impl MethodTracer {
         }
     }
 
-    pub(super) fn new_call(self: &Arc<Self>, name: &'static str) -> MethodCall {
+    pub(super) fn new_call(
+        self: &Arc<Self>,
+        name: &'static str,
+        request_id: RequestId,
+        transport: ApiTransportLabel,
+    ) -> MethodCall {
         MethodCall {
             tracer: self.clone(),
-            meta: MethodMetadata::new(name),
+            meta: MethodMetadata::new(name, request_id),
+            transport,
             is_completed: false,
         }
     }
@@ -100,7 +120,25 @@ impl MethodTracer {
         let cell = self.inner.get_or_default();
         if let Some(metadata) = &mut *cell.borrow_mut() {
             API_METRICS.observe_web3_error(metadata.name, err);
-            metadata.has_app_error = true;
+            metadata.app_error = Some(ErrorClass::new(err));
+        }
+    }
+
+    fn observe_rolling_latency(&self, meta: &MethodMetadata, transport: ApiTransportLabel) {
+        let now = Instant::now();
+        let latency = now.duration_since(meta.started_at);
+        let mut rolling_latencies = self
+            .rolling_latencies
+            .lock()
+            .expect("rolling latencies are poisoned");
+        let p99 = rolling_latencies
+            .entry((meta.name, transport))
+            .or_default()
+            .push(now, latency);
+        drop(rolling_latencies);
+
+        if let Some(p99) = p99 {
+            API_METRICS.observe_latency_p99(meta.name, transport, p99);
         }
     }
 }
@@ -121,6 +159,7 @@ impl MethodTracer {
 pub(super) struct MethodCall {
     tracer: Arc<MethodTracer>,
     meta: MethodMetadata,
+    transport: ApiTransportLabel,
     is_completed: bool,
 }
 
@@ -147,15 +186,21 @@ impl MethodCall {
     pub(super) fn observe_response(&mut self, response: &MethodResponse) {
         self.is_completed = true;
         let meta = &self.meta;
-        match response.success_or_error {
+        let error_code = match response.success_or_error {
             MethodResponseResult::Success => {
                 API_METRICS.observe_response_size(meta.name, response.result.len());
+                None
             }
             MethodResponseResult::Failed(error_code) => {
-                API_METRICS.observe_protocol_error(meta.name, error_code, meta.has_app_error);
+                let has_app_error = meta.app_error.is_some();
+                API_METRICS.observe_protocol_error(meta.name, error_code, has_app_error);
+                Some(error_code)
             }
-        }
+        };
         API_METRICS.observe_latency(meta);
+        API_METRICS.observe_call_result(meta, self.transport, error_code);
+        self.tracer.observe_rolling_latency(meta, self.transport);
+        API_METRICS.observe_compute_units(meta.name, method_compute_units(meta.name));
         #[cfg(test)]
         self.tracer.recorder.observe_response(meta, response);
     }
@@ -14,6 +14,7 @@ use governor::{
     Quota, RateLimiter,
 };
 use pin_project_lite::pin_project;
+use tracing::{instrument::Instrumented, Instrument};
 use vise::{
     Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, GaugeGuard, Histogram, Metrics,
 };
@@ -23,50 +24,93 @@ use zksync_web3_decl::jsonrpsee::{
     MethodResponse,
 };
 
-use super::metadata::{MethodCall, MethodTracer};
-use crate::api_server::web3::metrics::API_METRICS;
+use super::{
+    compute_units::method_compute_units,
+    metadata::{MethodCall, MethodTracer},
+    request_id::RequestId,
+};
+use crate::api_server::web3::metrics::{ApiTransportLabel, API_METRICS};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "transport", rename_all = "snake_case")]
 pub(crate) enum Transport {
+    Http,
     Ws,
 }
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "api_jsonrpc_backend_batch")]
-struct LimitMiddlewareMetrics {
+pub(super) struct LimitMiddlewareMetrics {
     /// Number of rate-limited requests.
     rate_limited: Family<Transport, Counter>,
+    /// Number of requests rate-limited because of the compute units limit. Included into `rate_limited`.
+    compute_units_limited: Family<Transport, Counter>,
     /// Size of batch requests.
     #[metrics(buckets = Buckets::exponential(1.0..=512.0, 2.0))]
-    size: Family<Transport, Histogram<usize>>,
-    /// Number of requests rejected by the limiter.
-    rejected: Family<Transport, Counter>,
+    pub size: Family<Transport, Histogram<usize>>,
+    /// Number of batch requests rejected because they are too large.
+    pub rejected: Family<Transport, Counter>,
+    /// Number of calls in batch requests not completed within the batch time budget.
+    pub timed_out_calls: Family<Transport, Counter>,
+    /// Number of call responses in batch requests replaced with errors because of the aggregate response size limit.
+    pub oversized_responses: Family<Transport, Counter>,
 }
 
 #[vise::register]
-static METRICS: vise::Global<LimitMiddlewareMetrics> = vise::Global::new();
+pub(super) static METRICS: vise::Global<LimitMiddlewareMetrics> = vise::Global::new();
+
+type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
 
 /// A rate-limiting middleware.
 ///
 /// `jsonrpsee` will allocate the instance of this struct once per session.
+///
+/// Besides limiting the number of requests, the middleware can limit the number of compute units spent per second,
+/// with each method having its own cost (see [`method_compute_units()`]).
 pub(crate) struct LimitMiddleware<S> {
     inner: S,
-    rate_limiter: Option<RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>>,
+    rate_limiter: Option<DirectRateLimiter>,
+    compute_units_limiter: Option<DirectRateLimiter>,
     transport: Transport,
     _guard: GaugeGuard,
 }
 
 impl<S> LimitMiddleware<S> {
-    pub(crate) fn new(inner: S, requests_per_minute_limit: Option<NonZeroU32>) -> Self {
+    pub(crate) fn new(
+        inner: S,
+        requests_per_minute_limit: Option<NonZeroU32>,
+        compute_units_per_second_limit: Option<NonZeroU32>,
+    ) -> Self {
         Self {
             inner,
             rate_limiter: requests_per_minute_limit
                 .map(|limit| RateLimiter::direct(Quota::per_minute(limit))),
+            compute_units_limiter: compute_units_per_second_limit
+                .map(|limit| RateLimiter::direct(Quota::per_second(limit))),
             transport: Transport::Ws,
             _guard: API_METRICS.ws_open_sessions.inc_guard(1),
         }
     }
+
+    fn is_rate_limited(&self, method_name: &str) -> bool {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            let num_requests = NonZeroU32::MIN; // 1 request, no batches possible
+
+            // Note: if required, we can extract data on rate limiting from the error.
+            if rate_limiter.check_n(num_requests).is_err() {
+                return true;
+            }
+        }
+        if let Some(limiter) = &self.compute_units_limiter {
+            let compute_units = NonZeroU32::new(method_compute_units(method_name));
+            let compute_units = compute_units.unwrap_or(NonZeroU32::MIN);
+            if !matches!(limiter.check_n(compute_units), Ok(Ok(()))) {
+                METRICS.compute_units_limited[&self.transport].inc();
+                return true;
+            }
+        }
+        false
+    }
 }
 
 impl<'a, S> RpcServiceT<'a> for LimitMiddleware<S>
@@ -76,26 +120,19 @@ where
     type Future = ResponseFuture<S::Future>;
 
     fn call(&self, request: Request<'a>) -> Self::Future {
-        if let Some(rate_limiter) = &self.rate_limiter {
-            let num_requests = NonZeroU32::MIN; // 1 request, no batches possible
+        if self.is_rate_limited(request.method_name()) {
+            METRICS.rate_limited[&self.transport].inc();
 
-            // Note: if required, we can extract data on rate limiting from the error.
-            if rate_limiter.check_n(num_requests).is_err() {
-                METRICS.rate_limited[&self.transport].inc();
-
-                let rp = MethodResponse::error(
-                    request.id,
-                    ErrorObject::borrowed(
-                        ErrorCode::ServerError(
-                            reqwest::StatusCode::TOO_MANY_REQUESTS.as_u16().into(),
-                        )
+            let rp = MethodResponse::error(
+                request.id,
+                ErrorObject::borrowed(
+                    ErrorCode::ServerError(reqwest::StatusCode::TOO_MANY_REQUESTS.as_u16().into())
                         .code(),
-                        "Too many requests",
-                        None,
-                    ),
-                );
-                return ResponseFuture::ready(rp);
-            }
+                    "Too many requests",
+                    None,
+                ),
+            );
+            return ResponseFuture::ready(rp);
         }
         ResponseFuture::future(self.inner.call(request))
     }
@@ -111,6 +148,7 @@ pub(crate) struct MetadataMiddleware<S> {
     inner: S,
     registered_method_names: Arc<HashSet<&'static str>>,
     method_tracer: Arc<MethodTracer>,
+    transport: ApiTransportLabel,
 }
 
 impl<S> MetadataMiddleware<S> {
@@ -118,11 +156,13 @@ impl<S> MetadataMiddleware<S> {
         inner: S,
         registered_method_names: Arc<HashSet<&'static str>>,
         method_tracer: Arc<MethodTracer>,
+        transport: ApiTransportLabel,
     ) -> Self {
         Self {
             inner,
             registered_method_names,
             method_tracer,
+            transport,
         }
     }
 }
@@ -131,7 +171,7 @@ impl<'a, S> RpcServiceT<'a> for MetadataMiddleware<S>
 where
     S: Send + Sync + RpcServiceT<'a>,
 {
-    type Future = WithMethodCall<S::Future>;
+    type Future = WithMethodCall<Instrumented<S::Future>>;
 
     fn call(&self, request: Request<'a>) -> Self::Future {
         // "Normalize" the method name by searching it in the set of all registered methods. This extends the lifetime
@@ -142,9 +182,16 @@ where
             .copied()
             .unwrap_or("");
 
+        // All logs produced while processing the call (incl. ones from the VM sandbox and DAL) will contain the request ID.
+        let request_id = RequestId::current_or_generate();
+        let span = tracing::info_span!("rpc_call", method = method_name, request_id = %request_id);
+        let inner = span.in_scope(|| self.inner.call(request));
+
         WithMethodCall {
-            call: self.method_tracer.new_call(method_name),
-            inner: self.inner.call(request),
+            call: self
+                .method_tracer
+                .new_call(method_name, request_id, self.transport),
+            inner: inner.instrument(span),
         }
     }
 }
@@ -219,7 +266,11 @@ mod tests {
             };
 
             WithMethodCall {
-                call: method_tracer.new_call("test"),
+                call: method_tracer.new_call(
+                    "test",
+                    RequestId::current_or_generate(),
+                    ApiTransportLabel::Http,
+                ),
                 inner,
             }
         });
@@ -7,15 +7,32 @@ use zksync_web3_decl::{
     jsonrpsee::types::{error::ErrorCode, ErrorObjectOwned},
 };
 
+pub use self::{api_keys::ApiKeyLimits, auth::RpcAuthConfig, cors::CorsConfig};
 pub(crate) use self::{
+    api_keys::{ApiKeyLayer, API_KEY_HEADER},
+    auth::AuthLayer,
+    batch_limits::{BatchLimits, BatchLimitsLayer},
+    compression::CompressionLayer,
+    drain::{DrainLayer, DrainMiddleware, ServerDrain},
     metadata::{MethodMetadata, MethodTracer},
     middleware::{LimitMiddleware, MetadataMiddleware},
+    request_body::DEFAULT_MAX_REQUEST_BODY_SIZE,
+    request_id::{RequestIdLayer, REQUEST_ID_HEADER},
 };
 use crate::api_server::tx_sender::SubmitTxError;
 
+mod api_keys;
+mod auth;
+mod batch_limits;
+mod compression;
+mod compute_units;
+mod cors;
+mod drain;
 mod metadata;
 mod middleware;
 pub mod namespaces;
+mod request_body;
+mod request_id;
 #[cfg(test)]
 pub(crate) mod testonly;
 
@@ -24,8 +41,13 @@ impl MethodTracer {
         self.observe_error(&err);
 
         let data = match &err {
-            Web3Error::SubmitTransactionError(_, data) => Some(format!("0x{}", hex::encode(data))),
-            Web3Error::ProxyError(_) => Some("0x".to_owned()),
+            // Like Geth, we only include revert data if it's non-empty. Clients decode `Error(string)`, `Panic(uint256)`
+            // or custom errors from it; decoded reason (if any) is included into the error message.
+            Web3Error::SubmitTransactionError(_, data) if !data.is_empty() => {
+                Some(format!("0x{}", hex::encode(data)).into())
+            }
+            Web3Error::TxLimitExceeded(_, details) => Some(details.clone()),
+            Web3Error::ProxyError(_) => Some(serde_json::Value::from("0x")),
             _ => None,
         };
         let code = match err {
@@ -38,17 +60,26 @@ impl MethodTracer {
             | Web3Error::TooManyTopics
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFilterBlockHash
-            | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
+            | Web3Error::InvalidRewardPercentiles
+            | Web3Error::TraceTooLarge(_)
+            | Web3Error::TooManyCalls(_)
+            | Web3Error::InvalidStateOverride(_)
+            | Web3Error::LogsLimitExceeded(_, _, _)
+            | Web3Error::TooManyLogs(_)
+            | Web3Error::LogsRangeUnavailable(_, _) => ErrorCode::InvalidParams.code(),
             Web3Error::SubmitTransactionError(_, _)
+            | Web3Error::TxLimitExceeded(_, _)
             | Web3Error::SerializationError(_)
-            | Web3Error::ProxyError(_) => 3,
+            | Web3Error::ProxyError(_)
+            | Web3Error::TraceTimeout(_) => 3,
             Web3Error::TreeApiUnavailable => 6,
         };
         let message = match err {
             // Do not expose internal error details to the client.
             Web3Error::InternalError(_) => "Internal error".to_owned(),
             Web3Error::ProxyError(err) => err.as_ref().to_string(),
-            Web3Error::SubmitTransactionError(message, _) => message,
+            Web3Error::SubmitTransactionError(message, _)
+            | Web3Error::TxLimitExceeded(message, _) => message,
             _ => err.to_string(),
         };
 
@@ -61,6 +92,10 @@ impl From<SubmitTxError> for Web3Error {
         match err {
             SubmitTxError::Internal(err) => Self::InternalError(err),
             SubmitTxError::ProxyError(err) => Self::ProxyError(err),
+            SubmitTxError::TxLimitExceeded(err) => {
+                let details = serde_json::to_value(&err).unwrap_or_default();
+                Self::TxLimitExceeded(err.to_string(), details)
+            }
             _ => Self::SubmitTransactionError(err.to_string(), err.data()),
         }
     }
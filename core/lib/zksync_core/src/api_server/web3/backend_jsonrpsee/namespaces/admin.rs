@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+use zksync_types::Address;
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::AdminNamespaceServer};
+
+use crate::api_server::web3::namespaces::AdminNamespace;
+
+#[async_trait]
+impl AdminNamespaceServer for AdminNamespace {
+    async fn set_fee_account(&self, address: Address) -> RpcResult<()> {
+        self.set_fee_account_impl(address)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+}
@@ -1,5 +1,7 @@
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, DebugCall, ResultTransactionTrace, TracerConfig, TransactionTrace,
+    },
     transaction_request::CallRequest,
     H256,
 };
@@ -16,7 +18,7 @@ impl DebugNamespaceServer for DebugNamespace {
         &self,
         block: BlockNumber,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Vec<ResultDebugCall>> {
+    ) -> RpcResult<Vec<ResultTransactionTrace>> {
         self.debug_trace_block_impl(BlockId::Number(block), options)
             .await
             .map_err(|err| self.current_method().map_err(err))
@@ -26,7 +28,7 @@ impl DebugNamespaceServer for DebugNamespace {
         &self,
         hash: H256,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Vec<ResultDebugCall>> {
+    ) -> RpcResult<Vec<ResultTransactionTrace>> {
         self.debug_trace_block_impl(BlockId::Hash(hash), options)
             .await
             .map_err(|err| self.current_method().map_err(err))
@@ -47,7 +49,7 @@ impl DebugNamespaceServer for DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Option<DebugCall>> {
+    ) -> RpcResult<Option<TransactionTrace>> {
         self.debug_trace_transaction_impl(tx_hash, options)
             .await
             .map_err(|err| self.current_method().map_err(err))
@@ -1,11 +1,12 @@
 use zksync_types::{
     api::{
-        Block, BlockId, BlockIdVariant, BlockNumber, Log, Transaction, TransactionId,
-        TransactionReceipt, TransactionVariant,
+        proof::AccountProof, AccessListWithGasUsed, Block, BlockId, BlockIdVariant, BlockNumber,
+        Log, SyncInfo, Transaction, TransactionConditions, TransactionId, TransactionReceipt,
+        TransactionVariant,
     },
     transaction_request::CallRequest,
-    web3::types::{FeeHistory, Index, SyncState},
-    Address, Bytes, H256, U256, U64,
+    web3::types::{FeeHistory, Index},
+    Address, Bytes, L1BatchNumber, H256, U256, U64,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
@@ -33,6 +34,16 @@ impl EthNamespaceServer for EthNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn create_access_list(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<AccessListWithGasUsed> {
+        self.create_access_list_impl(req, block.map(Into::into))
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn estimate_gas(&self, req: CallRequest, block: Option<BlockNumber>) -> RpcResult<U256> {
         self.estimate_gas_impl(req, block)
             .await
@@ -45,6 +56,12 @@ impl EthNamespaceServer for EthNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn max_priority_fee_per_gas(&self) -> RpcResult<U256> {
+        self.max_priority_fee_per_gas_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn new_filter(&self, filter: Filter) -> RpcResult<U256> {
         self.new_filter_impl(filter)
             .await
@@ -161,6 +178,17 @@ impl EthNamespaceServer for EthNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<AccountProof>> {
+        self.get_proof_impl(address, keys, l1_batch_number)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_transaction_count(
         &self,
         address: Address,
@@ -197,6 +225,22 @@ impl EthNamespaceServer for EthNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_raw_transaction_by_hash(&self, hash: H256) -> RpcResult<Option<Bytes>> {
+        self.get_raw_transaction_impl(TransactionId::Hash(hash))
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_raw_transaction_by_block_number_and_index(
+        &self,
+        block_number: BlockNumber,
+        index: Index,
+    ) -> RpcResult<Option<Bytes>> {
+        self.get_raw_transaction_impl(TransactionId::Block(BlockId::Number(block_number), index))
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_transaction_receipt(&self, hash: H256) -> RpcResult<Option<TransactionReceipt>> {
         self.get_transaction_receipt_impl(hash)
             .await
@@ -213,8 +257,20 @@ impl EthNamespaceServer for EthNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
-    async fn syncing(&self) -> RpcResult<SyncState> {
-        Ok(self.syncing_impl())
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx_bytes: Bytes,
+        conditions: TransactionConditions,
+    ) -> RpcResult<H256> {
+        self.send_raw_transaction_conditional_impl(tx_bytes, conditions)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn syncing(&self) -> RpcResult<SyncInfo> {
+        self.syncing_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
     }
 
     async fn accounts(&self) -> RpcResult<Vec<Address>> {
@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+use zksync_types::{
+    api::{Transaction, TxpoolAccountContent, TxpoolContent, TxpoolStatus},
+    Address,
+};
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::TxpoolNamespaceServer};
+
+use crate::api_server::web3::namespaces::TxpoolNamespace;
+
+#[async_trait]
+impl TxpoolNamespaceServer for TxpoolNamespace {
+    async fn content(&self) -> RpcResult<TxpoolContent<Transaction>> {
+        self.content_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn content_from(&self, address: Address) -> RpcResult<TxpoolAccountContent<Transaction>> {
+        self.content_from_impl(address)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn status(&self) -> RpcResult<TxpoolStatus> {
+        self.status_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn inspect(&self) -> RpcResult<TxpoolContent<String>> {
+        self.inspect_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+}
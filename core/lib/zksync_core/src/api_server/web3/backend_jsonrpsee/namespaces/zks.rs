@@ -2,7 +2,9 @@ use std::collections::HashMap;
 
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
+        BatchCallResult, BlockDetails, BlockFinality, BlockIdVariant, BridgeAddresses,
+        BundleTxResult, L1BatchDetails, L1BatchFinality, L2ToL1LogProof, LogCursor, Page, Proof,
+        ProtocolVersion, ProtocolVersionDetails, StateOverride, Transaction, TransactionCursor,
         TransactionDetails,
     },
     fee::Fee,
@@ -13,7 +15,7 @@ use zksync_types::{
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
     namespaces::zks::ZksNamespaceServer,
-    types::Token,
+    types::{Filter, Log, Token},
 };
 
 use crate::api_server::web3::ZksNamespace;
@@ -110,6 +112,25 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_block_details_page(
+        &self,
+        before: Option<MiniblockNumber>,
+        limit: Option<u32>,
+    ) -> RpcResult<Page<BlockDetails, MiniblockNumber>> {
+        self.get_block_details_page_impl(before, limit)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_block_finality(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<BlockFinality>> {
+        self.get_block_finality_impl(block_number)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>> {
         self.get_transaction_details_impl(hash)
             .await
@@ -125,6 +146,27 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_transactions_by_initiator(
+        &self,
+        initiator: Address,
+        cursor: Option<TransactionCursor>,
+        limit: Option<u32>,
+    ) -> RpcResult<Page<Transaction, TransactionCursor>> {
+        self.get_transactions_by_initiator_impl(initiator, cursor, limit)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_logs_paginated(
+        &self,
+        filter: Filter,
+        cursor: Option<LogCursor>,
+    ) -> RpcResult<Page<Log, LogCursor>> {
+        self.get_logs_paginated_impl(filter, cursor)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_l1_batch_details(
         &self,
         batch_number: L1BatchNumber,
@@ -134,6 +176,25 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_l1_batch_details_page(
+        &self,
+        before: Option<L1BatchNumber>,
+        limit: Option<u32>,
+    ) -> RpcResult<Page<L1BatchDetails, L1BatchNumber>> {
+        self.get_l1_batch_details_page_impl(before, limit)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_l1_batch_finality(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchFinality>> {
+        self.get_l1_batch_finality_impl(batch_number)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>> {
         self.get_bytecode_by_hash_impl(hash)
             .await
@@ -157,6 +218,12 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_protocol_versions(&self) -> RpcResult<Vec<ProtocolVersionDetails>> {
+        self.get_protocol_versions_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_proof(
         &self,
         address: Address,
@@ -167,4 +234,25 @@ impl ZksNamespaceServer for ZksNamespace {
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
+
+    async fn batch_call(
+        &self,
+        requests: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<Vec<BatchCallResult>> {
+        self.batch_call_impl(requests, block.map(Into::into))
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn simulate_bundle(
+        &self,
+        requests: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Vec<BundleTxResult>> {
+        self.simulate_bundle_impl(requests, block.map(Into::into), state_override)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
 }
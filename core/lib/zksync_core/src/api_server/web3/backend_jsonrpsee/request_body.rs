@@ -0,0 +1,106 @@
+//! Size-limited buffering of HTTP request bodies for HTTP-level middleware.
+//!
+//! Middleware inspecting JSON-RPC calls needs to buffer the request body before it reaches `jsonrpsee`,
+//! so the body size limit enforced by the server doesn't apply yet. Hence, the limit is enforced here as well.
+
+use hyper::{
+    body::{Bytes, HttpBody as _},
+    header, http, Body, StatusCode,
+};
+
+/// Default maximum request body size. Matches the default limit used by `jsonrpsee`.
+pub(crate) const DEFAULT_MAX_REQUEST_BODY_SIZE: usize = 10 * 1_024 * 1_024;
+
+/// Errors that can occur when buffering a request body.
+#[derive(Debug)]
+pub(super) enum ReadBodyError {
+    /// Body exceeds the size limit.
+    TooLarge,
+    /// I/O error reading the body.
+    Io(hyper::Error),
+}
+
+impl ReadBodyError {
+    /// Returns the HTTP status of the response to a request with a body that couldn't be read.
+    pub(super) fn status(&self) -> StatusCode {
+        match self {
+            Self::TooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Io(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// Buffers the request body, failing if it is larger than `max_size` bytes. If the request specifies
+/// the `Content-Length` header, it is checked before reading the body, so that oversized requests are rejected
+/// without reading them.
+pub(super) async fn read_request_body(
+    parts: &http::request::Parts,
+    mut body: Body,
+    max_size: usize,
+) -> Result<Bytes, ReadBodyError> {
+    let content_length = parts
+        .headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if content_length.map_or(false, |len| len > max_size as u64) {
+        return Err(ReadBodyError::TooLarge);
+    }
+
+    let mut buffer = Vec::with_capacity(content_length.map_or(0, |len| len as usize));
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(ReadBodyError::Io)?;
+        if buffer.len() + chunk.len() > max_size {
+            return Err(ReadBodyError::TooLarge);
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+    Ok(buffer.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::Request;
+
+    use super::*;
+
+    async fn read(request: Request<Body>, max_size: usize) -> Result<Bytes, ReadBodyError> {
+        let (parts, body) = request.into_parts();
+        read_request_body(&parts, body, max_size).await
+    }
+
+    #[tokio::test]
+    async fn reading_body_with_limit() {
+        let request = Request::post("/").body(Body::from("0123456789")).unwrap();
+        let body = read(request, 10).await.unwrap();
+        assert_eq!(body, "0123456789");
+
+        let request = Request::post("/").body(Body::from("0123456789")).unwrap();
+        let err = read(request, 9).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn rejecting_body_by_content_length() {
+        // The body is never read, so it doesn't matter that it's shorter than declared.
+        let request = Request::post("/")
+            .header(header::CONTENT_LENGTH, "1000000")
+            .body(Body::empty())
+            .unwrap();
+        let err = read(request, 1_000).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn reading_streamed_body_with_limit() {
+        let (mut sender, body) = Body::channel();
+        tokio::spawn(async move {
+            for chunk in ["0123", "4567", "89"] {
+                sender.send_data(Bytes::from(chunk)).await.ok();
+            }
+        });
+        let request = Request::post("/").body(body).unwrap();
+        let err = read(request, 8).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
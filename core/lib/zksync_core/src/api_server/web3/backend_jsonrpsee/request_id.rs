@@ -0,0 +1,170 @@
+//! Request IDs allowing to correlate JSON-RPC calls with logs produced while processing them.
+//!
+//! Each call is processed in an `rpc_call` tracing span with the `request_id` field, so that logs produced
+//! by the VM sandbox and DAL (e.g., slow query warnings) during the call can be attributed to it.
+//! For HTTP requests, the ID is shared by all calls in the request and is returned in the [`REQUEST_ID_HEADER`]
+//! response header. A client may supply its own ID in the same request header; otherwise, a random ID is generated.
+//! For calls over WebSocket connections, a random ID is generated for each call.
+
+use std::{
+    fmt,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use hyper::{
+    header::{HeaderName, HeaderValue},
+    Body, Request, Response,
+};
+use tower::{Layer, Service};
+
+/// Name of the HTTP header containing the request ID.
+pub(crate) const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+/// Maximum length of a request ID supplied by the client.
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+tokio::task_local! {
+    static HTTP_REQUEST_ID: RequestId;
+}
+
+/// ID of a JSON-RPC request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequestId(Arc<str>);
+
+impl RequestId {
+    fn generate() -> Self {
+        let bytes: [u8; 16] = rand::random();
+        Self(hex::encode(bytes).into())
+    }
+
+    /// Parses an ID supplied by the client. To prevent log injection, only short IDs consisting of
+    /// alphanumeric chars, `-` and `_` are accepted.
+    fn parse(value: &HeaderValue) -> Option<Self> {
+        let value = value.to_str().ok()?;
+        let is_valid = !value.is_empty()
+            && value.len() <= MAX_REQUEST_ID_LEN
+            && value
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_');
+        is_valid.then(|| Self(value.into()))
+    }
+
+    /// Returns the ID of the HTTP request currently being processed, or generates a new ID if there is no such request
+    /// (e.g., for calls over WebSocket connections).
+    pub(super) fn current_or_generate() -> Self {
+        HTTP_REQUEST_ID
+            .try_with(Self::clone)
+            .unwrap_or_else(|_| Self::generate())
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+/// Layer assigning IDs to HTTP requests. See the [module docs](self) for details.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestIdLayer;
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestIdService { inner }
+    }
+}
+
+/// Service produced by [`RequestIdLayer`].
+#[derive(Debug, Clone)]
+pub(crate) struct RequestIdService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for RequestIdService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Future: Send + 'static,
+    S::Error: Send,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response<Body>, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let request_id = request
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(RequestId::parse)
+            .unwrap_or_else(RequestId::generate);
+        let header_value =
+            HeaderValue::from_str(&request_id.0).expect("request ID is not a valid header value");
+
+        // `jsonrpsee` processes all calls in an HTTP request within the future returned by the service,
+        // so the task-local ID is accessible to RPC-level middleware.
+        let response = HTTP_REQUEST_ID.scope(request_id, self.inner.call(request));
+        async move {
+            let mut response = response.await?;
+            response
+                .headers_mut()
+                .insert(REQUEST_ID_HEADER, header_value);
+            Ok(response)
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    async fn call(request: Request<Body>) -> (String, String) {
+        let mut service = RequestIdLayer.layer(tower::service_fn(|_: Request<Body>| async {
+            let request_id = RequestId::current_or_generate();
+            Ok::<_, Infallible>(Response::new(Body::from(request_id.to_string())))
+        }));
+        let response = service.call(request).await.unwrap();
+        let header = response.headers()[REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (header, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn post(request_id: Option<&str>) -> Request<Body> {
+        let mut builder = Request::post("/");
+        if let Some(request_id) = request_id {
+            builder = builder.header(REQUEST_ID_HEADER, request_id);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn assigning_request_ids() {
+        let (header, body) = call(post(None)).await;
+        assert_eq!(header.len(), 32);
+        assert_eq!(header, body);
+        let (other_header, _) = call(post(None)).await;
+        assert_ne!(other_header, header);
+
+        let (header, body) = call(post(Some("test-request_1"))).await;
+        assert_eq!(header, "test-request_1");
+        assert_eq!(body, "test-request_1");
+
+        for invalid_id in ["", "test request", "test\"", "1".repeat(65).as_str()] {
+            let (header, body) = call(post(Some(invalid_id))).await;
+            assert_ne!(header, invalid_id);
+            assert_eq!(header.len(), 32);
+            assert_eq!(header, body);
+        }
+    }
+}
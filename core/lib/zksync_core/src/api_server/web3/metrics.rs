@@ -1,19 +1,23 @@
 //! Metrics for the JSON-RPC server.
 
-use std::{fmt, time::Duration};
+use std::{
+    collections::VecDeque,
+    fmt,
+    time::{Duration, Instant},
+};
 
 use vise::{
     Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     Metrics, Unit,
 };
 use zksync_types::api;
-use zksync_web3_decl::error::Web3Error;
+use zksync_web3_decl::{error::Web3Error, jsonrpsee::types::error::ErrorCode};
 
 use super::{backend_jsonrpsee::MethodMetadata, ApiTransport, TypedFilter};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "scheme", rename_all = "UPPERCASE")]
-pub(super) enum ApiTransportLabel {
+pub(crate) enum ApiTransportLabel {
     Http,
     Ws,
 }
@@ -100,6 +104,11 @@ enum Web3ErrorKind {
     FilterNotFound,
     LogsLimitExceeded,
     InvalidFilterBlockHash,
+    InvalidRewardPercentiles,
+    TraceTimeout,
+    TraceTooLarge,
+    TooManyCalls,
+    InvalidStateOverride,
     TreeApiUnavailable,
     Internal,
 }
@@ -108,20 +117,127 @@ impl Web3ErrorKind {
     fn new(err: &Web3Error) -> Self {
         match err {
             Web3Error::NoBlock => Self::NoBlock,
-            Web3Error::PrunedBlock(_) | Web3Error::PrunedL1Batch(_) => Self::Pruned,
-            Web3Error::SubmitTransactionError(..) => Self::SubmitTransaction,
+            Web3Error::PrunedBlock(_)
+            | Web3Error::PrunedL1Batch(_)
+            | Web3Error::LogsRangeUnavailable(_, _) => Self::Pruned,
+            Web3Error::SubmitTransactionError(..) | Web3Error::TxLimitExceeded(..) => {
+                Self::SubmitTransaction
+            }
             Web3Error::ProxyError(_) => Self::Proxy,
             Web3Error::SerializationError(_) => Self::TransactionSerialization,
             Web3Error::TooManyTopics => Self::TooManyTopics,
             Web3Error::FilterNotFound => Self::FilterNotFound,
-            Web3Error::LogsLimitExceeded(..) => Self::LogsLimitExceeded,
+            Web3Error::LogsLimitExceeded(..) | Web3Error::TooManyLogs(_) => Self::LogsLimitExceeded,
             Web3Error::InvalidFilterBlockHash => Self::InvalidFilterBlockHash,
+            Web3Error::InvalidRewardPercentiles => Self::InvalidRewardPercentiles,
+            Web3Error::TraceTimeout(_) => Self::TraceTimeout,
+            Web3Error::TraceTooLarge(_) => Self::TraceTooLarge,
+            Web3Error::TooManyCalls(_) => Self::TooManyCalls,
+            Web3Error::InvalidStateOverride(_) => Self::InvalidStateOverride,
             Web3Error::TreeApiUnavailable => Self::TreeApiUnavailable,
             Web3Error::InternalError(_) | Web3Error::NotImplemented => Self::Internal,
         }
     }
 }
 
+/// Class of an error returned by a Web3 call. Allows distinguishing errors caused by clients from server-side issues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(crate) enum ErrorClass {
+    /// Error caused by the request, e.g., invalid params or a reverted transaction.
+    User,
+    /// Server-side error.
+    Internal,
+    /// Call processing has timed out.
+    Timeout,
+    /// Call was rejected by rate limiting.
+    RateLimited,
+}
+
+impl ErrorClass {
+    pub fn new(err: &Web3Error) -> Self {
+        match Web3ErrorKind::new(err) {
+            Web3ErrorKind::Internal | Web3ErrorKind::Proxy | Web3ErrorKind::TreeApiUnavailable => {
+                Self::Internal
+            }
+            Web3ErrorKind::TraceTimeout => Self::Timeout,
+            _ => Self::User,
+        }
+    }
+
+    /// Classifies a protocol-level error (i.e., an error not produced by the method logic) by its code.
+    fn from_error_code(error_code: i32) -> Self {
+        let rate_limited_code = i32::from(reqwest::StatusCode::TOO_MANY_REQUESTS.as_u16());
+        if error_code == rate_limited_code {
+            Self::RateLimited
+        } else if error_code == ErrorCode::InternalError.code() {
+            Self::Internal
+        } else {
+            // Parse errors, unknown methods, invalid params etc.
+            Self::User
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct CallResultLabels {
+    method: &'static str,
+    transport: ApiTransportLabel,
+    error_class: Option<ErrorClass>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct MethodTransportLabels {
+    method: &'static str,
+    transport: ApiTransportLabel,
+}
+
+/// Latencies of recent calls to a method, used to compute the rolling 99th percentile of latency.
+#[derive(Debug, Default)]
+pub(super) struct RollingLatencies {
+    samples: VecDeque<(Instant, Duration)>,
+    last_reported_at: Option<Instant>,
+}
+
+impl RollingLatencies {
+    const WINDOW: Duration = Duration::from_secs(60);
+    const MAX_SAMPLES: usize = 1_000;
+    const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Records a latency sample. Returns the 99th percentile of latencies within the window if it should be reported;
+    /// reports are throttled to not sort samples on each call.
+    pub fn push(&mut self, now: Instant, latency: Duration) -> Option<Duration> {
+        while let Some(&(timestamp, _)) = self.samples.front() {
+            let is_expired = now.duration_since(timestamp) > Self::WINDOW;
+            if !is_expired && self.samples.len() < Self::MAX_SAMPLES {
+                break;
+            }
+            self.samples.pop_front();
+        }
+        self.samples.push_back((now, latency));
+
+        let should_report = self.last_reported_at.map_or(true, |reported_at| {
+            now.duration_since(reported_at) >= Self::REPORT_INTERVAL
+        });
+        if !should_report {
+            return None;
+        }
+        self.last_reported_at = Some(now);
+        Some(self.percentile(0.99))
+    }
+
+    /// Computes a percentile of latencies using the nearest-rank method.
+    fn percentile(&self, quantile: f64) -> Duration {
+        let mut latencies: Vec<_> = self.samples.iter().map(|&(_, latency)| latency).collect();
+        if latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        let rank = (latencies.len() as f64 * quantile).ceil() as usize;
+        let (_, &mut latency, _) = latencies.select_nth_unstable(rank.max(1) - 1);
+        latency
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
 #[metrics(rename_all = "snake_case")]
 enum ProtocolErrorOrigin {
@@ -159,12 +275,22 @@ pub(super) struct ApiMetrics {
     web3_call: Family<MethodLabels, Histogram<Duration>>,
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     web3_dropped_call_latency: Family<MethodLabels, Histogram<Duration>>,
+    /// Latency of a finished Web3 call split by the method, transport and error class (not set for successful calls).
+    /// The number of observations can be used to alert on error rates for specific methods.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    web3_call_results: Family<CallResultLabels, Histogram<Duration>>,
+    /// 99th percentile of Web3 call latency during the last minute. Updated when the method is called.
+    #[metrics(unit = Unit::Seconds)]
+    web3_call_latency_p99: Family<MethodTransportLabels, Gauge<Duration>>,
     /// Difference between the latest sealed miniblock and the resolved miniblock for a web3 call.
     #[metrics(buckets = BLOCK_DIFF_BUCKETS, labels = ["method"])]
     web3_call_block_diff: LabeledFamily<&'static str, Histogram<u64>>,
     /// Serialized response size in bytes. Only recorded for successful responses.
     #[metrics(buckets = RESPONSE_SIZE_BUCKETS, labels = ["method"], unit = Unit::Bytes)]
     web3_call_response_size: LabeledFamily<&'static str, Histogram<usize>>,
+    /// Compute units consumed by finished RPC calls. See `backend_jsonrpsee::method_compute_units()` for method costs.
+    #[metrics(labels = ["method"])]
+    web3_compute_units: LabeledFamily<&'static str, Counter>,
 
     /// Number of application errors grouped by error kind and method name. Only collected for errors that were successfully routed
     /// to a method (i.e., this method is defined).
@@ -191,6 +317,41 @@ impl ApiMetrics {
         }
     }
 
+    /// Observes the result of a finished RPC call.
+    pub fn observe_call_result(
+        &self,
+        meta: &MethodMetadata,
+        transport: ApiTransportLabel,
+        error_code: Option<i32>,
+    ) {
+        let error_class = error_code.map(|code| {
+            meta.app_error
+                .unwrap_or_else(|| ErrorClass::from_error_code(code))
+        });
+        let labels = CallResultLabels {
+            method: meta.name,
+            transport,
+            error_class,
+        };
+        self.web3_call_results[&labels].observe(meta.started_at.elapsed());
+    }
+
+    /// Reports the rolling 99th percentile of latency for a method.
+    pub fn observe_latency_p99(
+        &self,
+        method: &'static str,
+        transport: ApiTransportLabel,
+        latency: Duration,
+    ) {
+        let labels = MethodTransportLabels { method, transport };
+        self.web3_call_latency_p99[&labels].set(latency);
+    }
+
+    /// Observes compute units consumed by an RPC call.
+    pub fn observe_compute_units(&self, method: &'static str, compute_units: u32) {
+        self.web3_compute_units[&method].inc_by(compute_units.into());
+    }
+
     /// Observes latency of a dropped RPC call.
     pub fn observe_dropped_call(&self, meta: &MethodMetadata) {
         let latency = meta.started_at.elapsed();
@@ -260,6 +421,9 @@ pub(super) enum SubscriptionType {
     Blocks,
     Txs,
     Logs,
+    L1BatchSealed,
+    L1BatchVerified,
+    L1BatchExecuted,
 }
 
 #[derive(Debug, Metrics)]
@@ -327,3 +491,52 @@ pub(super) struct FilterMetrics {
 
 #[vise::register]
 pub(super) static FILTER_METRICS: vise::Global<FilterMetrics> = vise::Global::new();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifying_protocol_errors() {
+        assert_eq!(ErrorClass::from_error_code(429), ErrorClass::RateLimited);
+        assert_eq!(
+            ErrorClass::from_error_code(ErrorCode::InternalError.code()),
+            ErrorClass::Internal
+        );
+        assert_eq!(
+            ErrorClass::from_error_code(ErrorCode::MethodNotFound.code()),
+            ErrorClass::User
+        );
+        assert_eq!(
+            ErrorClass::new(&Web3Error::TraceTimeout(Duration::from_secs(1))),
+            ErrorClass::Timeout
+        );
+        assert_eq!(ErrorClass::new(&Web3Error::NoBlock), ErrorClass::User);
+    }
+
+    #[test]
+    fn rolling_latency_percentile() {
+        let start = Instant::now();
+        let mut latencies = RollingLatencies::default();
+        let p99 = latencies.push(start, Duration::from_millis(5));
+        assert_eq!(p99, Some(Duration::from_millis(5)));
+
+        for i in 1..=99 {
+            let p99 = latencies.push(start, Duration::from_millis(i));
+            assert_eq!(p99, None); // reports are throttled
+        }
+        // 100 samples: 1..=99 ms and 5 ms.
+        assert_eq!(latencies.percentile(0.99), Duration::from_millis(98));
+        assert_eq!(latencies.percentile(0.5), Duration::from_millis(49));
+
+        let now = start + RollingLatencies::REPORT_INTERVAL;
+        let p99 = latencies.push(now, Duration::from_secs(1));
+        assert_eq!(p99, Some(Duration::from_millis(99)));
+
+        // All samples except for the latest one expire.
+        let now = now + RollingLatencies::WINDOW;
+        let p99 = latencies.push(now, Duration::from_millis(3));
+        assert_eq!(p99, Some(Duration::from_secs(1)));
+        assert_eq!(latencies.samples.len(), 2);
+    }
+}
@@ -1,14 +1,16 @@
-use std::{collections::HashSet, net::SocketAddr, num::NonZeroU32, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet, net::SocketAddr, num::NonZeroU32, str::FromStr, sync::Arc, time::Duration,
+};
 
 use anyhow::Context as _;
 use chrono::NaiveDateTime;
 use futures::future;
-use serde::Deserialize;
+use serde::{de::IntoDeserializer, Deserialize, Serialize};
 use tokio::{
     sync::{mpsc, oneshot, watch, Mutex},
     task::JoinHandle,
 };
-use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
+use tower_http::metrics::InFlightRequestsLayer;
 use zksync_dal::ConnectionPool;
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::MiniblockNumber;
@@ -18,21 +20,30 @@ use zksync_web3_decl::{
         RpcModule,
     },
     namespaces::{
-        DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, EthPubSubServer,
-        NetNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
+        AdminNamespaceServer, DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer,
+        EthPubSubServer, NetNamespaceServer, SnapshotsNamespaceServer, TxpoolNamespaceServer,
+        Web3NamespaceServer, ZksNamespaceServer, ZksPubSubServer,
     },
     types::Filter,
 };
 
 use self::{
-    backend_jsonrpsee::{LimitMiddleware, MetadataMiddleware, MethodTracer},
+    backend_jsonrpsee::{
+        ApiKeyLayer, ApiKeyLimits, AuthLayer, BatchLimits, BatchLimitsLayer, CompressionLayer,
+        CorsConfig, DrainLayer, DrainMiddleware, LimitMiddleware, MetadataMiddleware, MethodTracer,
+        RequestIdLayer, RpcAuthConfig, ServerDrain, API_KEY_HEADER, DEFAULT_MAX_REQUEST_BODY_SIZE,
+        REQUEST_ID_HEADER,
+    },
     metrics::API_METRICS,
     namespaces::{
-        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace, Web3Namespace,
-        ZksNamespace,
+        AdminNamespace, DebugNamespace, EnNamespace, EthNamespace, NetNamespace,
+        SnapshotsNamespace, TxpoolNamespace, Web3Namespace, ZksNamespace,
     },
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
-    state::{Filters, InternalApiConfig, RpcState, SealedMiniblockNumber},
+    state::{
+        Filters, InstalledFilters, InternalApiConfig, PersistentFilters, PriorityFeeSuggester,
+        RpcState, SealedMiniblockNumber,
+    },
 };
 use crate::{
     api_server::{
@@ -40,6 +51,7 @@ use crate::{
         tree::TreeApiClient,
         tx_sender::TxSender,
     },
+    house_keeper::db_partition_manager::DetachedEventsRanges,
     sync_layer::SyncState,
     utils::wait_for_l1_batch,
 };
@@ -54,9 +66,12 @@ pub(crate) mod tests;
 
 /// Timeout for graceful shutdown logic within API servers.
 const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+/// Default time given to in-flight requests to complete once an API server receives a stop signal.
+const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
 
-/// Represents all kinds of `Filter`.
-#[derive(Debug, Clone)]
+/// Represents all kinds of `Filter`. Filters are serialized when persisted in Postgres,
+/// so changes to this type must be backward-compatible.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum TypedFilter {
     // Events from some block with additional filters
     Events(Filter, MiniblockNumber),
@@ -83,6 +98,8 @@ pub enum Namespace {
     En,
     Pubsub,
     Snapshots,
+    Txpool,
+    Admin,
 }
 
 impl Namespace {
@@ -96,6 +113,14 @@ impl Namespace {
     ];
 }
 
+impl FromStr for Namespace {
+    type Err = serde::de::value::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(s.into_deserializer())
+    }
+}
+
 /// Handles to the initialized API server.
 #[derive(Debug)]
 pub struct ApiServerHandles {
@@ -112,10 +137,24 @@ struct OptionalApiParams {
     sync_state: Option<SyncState>,
     filters_limit: Option<usize>,
     subscriptions_limit: Option<usize>,
+    subscriptions_per_connection_limit: Option<usize>,
+    websocket_message_buffer_capacity: Option<usize>,
     batch_request_size_limit: Option<usize>,
+    request_body_size_limit: Option<usize>,
     response_body_size_limit: Option<usize>,
+    batch_response_size_limit: Option<usize>,
+    batch_time_budget: Option<Duration>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    websocket_compute_units_per_second_limit: Option<NonZeroU32>,
+    api_keys: Option<(Vec<String>, ApiKeyLimits)>,
+    auth: Option<RpcAuthConfig>,
+    cors: Option<CorsConfig>,
+    response_compression_threshold: Option<usize>,
+    drain_timeout: Option<Duration>,
     tree_api: Option<Arc<dyn TreeApiClient>>,
+    sealed_miniblocks_pool: Option<ConnectionPool>,
+    persistent_filters: Option<(ConnectionPool, Duration)>,
+    admin_pool: Option<ConnectionPool>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -207,16 +246,53 @@ impl ApiBuilder {
         self
     }
 
+    pub fn with_subscriptions_per_connection_limit(
+        mut self,
+        subscriptions_per_connection_limit: usize,
+    ) -> Self {
+        self.optional.subscriptions_per_connection_limit = Some(subscriptions_per_connection_limit);
+        self
+    }
+
+    pub fn with_websocket_message_buffer_capacity(
+        mut self,
+        websocket_message_buffer_capacity: usize,
+    ) -> Self {
+        self.optional.websocket_message_buffer_capacity = Some(websocket_message_buffer_capacity);
+        self
+    }
+
     pub fn with_batch_request_size_limit(mut self, batch_request_size_limit: usize) -> Self {
         self.optional.batch_request_size_limit = Some(batch_request_size_limit);
         self
     }
 
+    /// Limits the size of HTTP request bodies, including ones buffered by the server middleware before they reach
+    /// the JSON-RPC server. If not set, the default `jsonrpsee` limit (10 MiB) is used.
+    pub fn with_request_body_size_limit(mut self, request_body_size_limit: usize) -> Self {
+        self.optional.request_body_size_limit = Some(request_body_size_limit);
+        self
+    }
+
     pub fn with_response_body_size_limit(mut self, response_body_size_limit: usize) -> Self {
         self.optional.response_body_size_limit = Some(response_body_size_limit);
         self
     }
 
+    /// Limits the aggregate size of responses to an HTTP batch request. Responses exceeding the limit are replaced
+    /// with errors. If not set, the response body size limit is used.
+    pub fn with_batch_response_size_limit(mut self, batch_response_size_limit: usize) -> Self {
+        self.optional.batch_response_size_limit = Some(batch_response_size_limit);
+        self
+    }
+
+    /// Sets the time budget for processing an HTTP batch request. Calls not completed within the budget
+    /// are replaced with errors.
+    pub fn with_batch_time_budget(mut self, batch_time_budget: Duration) -> Self {
+        self.optional.batch_time_budget = Some(batch_time_budget);
+        self
+    }
+
     pub fn with_websocket_requests_per_minute_limit(
         mut self,
         websocket_requests_per_minute_limit: NonZeroU32,
@@ -226,6 +302,52 @@ impl ApiBuilder {
         self
     }
 
+    /// Limits the number of compute units spent per second by each WebSocket connection. Each method has its own cost;
+    /// e.g., `eth_call` costs 50 compute units, while `eth_chainId` costs 1 unit.
+    pub fn with_websocket_compute_units_per_second_limit(
+        mut self,
+        websocket_compute_units_per_second_limit: NonZeroU32,
+    ) -> Self {
+        self.optional.websocket_compute_units_per_second_limit =
+            Some(websocket_compute_units_per_second_limit);
+        self
+    }
+
+    /// Requires requests to the server to be authenticated with one of the specified API keys,
+    /// and applies `limits` to each key.
+    pub fn with_api_keys(mut self, api_keys: Vec<String>, limits: ApiKeyLimits) -> Self {
+        self.optional.api_keys = Some((api_keys, limits));
+        self
+    }
+
+    /// Requires requests to the server to be authenticated with a bearer token, either for all methods
+    /// or for methods in the protected namespaces.
+    pub fn with_auth(mut self, auth: RpcAuthConfig) -> Self {
+        self.optional.auth = Some(auth);
+        self
+    }
+
+    /// Sets the CORS policy for the HTTP server. If not set, requests from any origin are allowed.
+    /// Has no effect on WebSocket servers.
+    pub fn with_cors(mut self, cors: CorsConfig) -> Self {
+        self.optional.cors = Some(cors);
+        self
+    }
+
+    /// Enables gzip / Brotli compression for HTTP responses with size greater than or equal to `min_size` bytes.
+    /// The encoding is negotiated using the `Accept-Encoding` request header. Has no effect on WebSocket servers.
+    pub fn with_response_compression(mut self, min_size: usize) -> Self {
+        self.optional.response_compression_threshold = Some(min_size);
+        self
+    }
+
+    /// Sets the time given to in-flight requests to complete once the server receives a stop signal. During this time,
+    /// new requests are rejected. If not set, a default timeout of 5 seconds is used.
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.optional.drain_timeout = Some(timeout);
+        self
+    }
+
     pub fn with_sync_state(mut self, sync_state: SyncState) -> Self {
         self.optional.sync_state = Some(sync_state);
         self
@@ -236,6 +358,29 @@ impl ApiBuilder {
         self
     }
 
+    /// Makes pubsub notifiers listen to DB notifications about sealed miniblocks instead of relying
+    /// on polling only. The `pool` must be connected to the master DB since notifications are not replicated;
+    /// it must have a connection for each of the block and log notifiers.
+    pub fn with_sealed_miniblock_notifications(mut self, pool: ConnectionPool) -> Self {
+        self.optional.sealed_miniblocks_pool = Some(pool);
+        self
+    }
+
+    /// Persists filters installed via `eth_newFilter` and similar methods in Postgres, so that they survive
+    /// server restarts and are shared among all API servers connected to the same DB. The `pool` must be connected
+    /// to the master DB. Filters not polled for `ttl` are removed.
+    pub fn with_persistent_filters(mut self, pool: ConnectionPool, ttl: Duration) -> Self {
+        self.optional.persistent_filters = Some((pool, ttl));
+        self
+    }
+
+    /// Sets the pool used by the `admin` namespace to persist changes. The `pool` must be connected
+    /// to the master DB. Required if the `admin` namespace is enabled.
+    pub fn with_admin_pool(mut self, pool: ConnectionPool) -> Self {
+        self.optional.admin_pool = Some(pool);
+        self
+    }
+
     pub fn enable_api_namespaces(mut self, namespaces: Vec<Namespace>) -> Self {
         self.namespaces = Some(namespaces);
         self
@@ -294,9 +439,19 @@ impl ApiServer {
         self.health_updater.subscribe()
     }
 
+    fn persistent_filters(&self) -> Option<PersistentFilters> {
+        let (pool, ttl) = self.optional.persistent_filters.clone()?;
+        Some(PersistentFilters::new(
+            pool,
+            ttl,
+            self.optional.filters_limit,
+        ))
+    }
+
     async fn build_rpc_state(
         self,
         last_sealed_miniblock: SealedMiniblockNumber,
+        detached_events_ranges: DetachedEventsRanges,
     ) -> anyhow::Result<RpcState> {
         let mut storage = self.updaters_pool.access_storage_tagged("api").await?;
         let start_info = BlockStartInfo::new(&mut storage).await?;
@@ -304,11 +459,14 @@ impl ApiServer {
 
         let installed_filters = if self.config.filters_disabled {
             None
+        } else if let Some(filters) = self.persistent_filters() {
+            Some(Arc::new(InstalledFilters::Persistent(filters)))
         } else {
-            Some(Arc::new(Mutex::new(Filters::new(
-                self.optional.filters_limit,
+            Some(Arc::new(InstalledFilters::InMemory(Mutex::new(
+                Filters::new(self.optional.filters_limit),
             ))))
         };
+        let priority_fee_suggester = Arc::new(PriorityFeeSuggester::new(&self.config));
 
         Ok(RpcState {
             current_method: self.method_tracer,
@@ -319,7 +477,9 @@ impl ApiServer {
             api_config: self.config,
             start_info,
             last_sealed_miniblock,
+            detached_events_ranges,
             tree_api: self.optional.tree_api,
+            priority_fee_suggester,
         })
     }
 
@@ -327,14 +487,22 @@ impl ApiServer {
         self,
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
+        detached_events_ranges: DetachedEventsRanges,
     ) -> anyhow::Result<RpcModule<()>> {
         let namespaces = self.namespaces.clone();
         let zksync_network_id = self.config.l2_chain_id;
-        let rpc_state = self.build_rpc_state(last_sealed_miniblock).await?;
+        let admin_pool = self.optional.admin_pool.clone();
+        let rpc_state = self
+            .build_rpc_state(last_sealed_miniblock, detached_events_ranges)
+            .await?;
 
         // Collect all the methods into a single RPC module.
         let mut rpc = RpcModule::new(());
         if let Some(pub_sub) = pub_sub {
+            if namespaces.contains(&Namespace::Zks) {
+                rpc.merge(pub_sub.zks_subscribe(rpc_state.clone()).into_rpc())
+                    .expect("Can't merge zks pubsub namespace");
+            }
             rpc.merge(pub_sub.into_rpc())
                 .expect("Can't merge eth pubsub namespace");
         }
@@ -364,9 +532,20 @@ impl ApiServer {
                 .expect("Can't merge debug namespace");
         }
         if namespaces.contains(&Namespace::Snapshots) {
-            rpc.merge(SnapshotsNamespace::new(rpc_state).into_rpc())
+            rpc.merge(SnapshotsNamespace::new(rpc_state.clone()).into_rpc())
                 .expect("Can't merge snapshots namespace");
         }
+        if namespaces.contains(&Namespace::Txpool) {
+            rpc.merge(TxpoolNamespace::new(rpc_state.clone()).into_rpc())
+                .expect("Can't merge txpool namespace");
+        }
+        if namespaces.contains(&Namespace::Admin) {
+            let admin_pool = admin_pool.context(
+                "`admin` namespace requires a master DB pool; use `ApiBuilder::with_admin_pool()`",
+            )?;
+            rpc.merge(AdminNamespace::new(rpc_state, admin_pool).into_rpc())
+                .expect("Can't merge admin namespace");
+        }
         Ok(rpc)
     }
 
@@ -429,6 +608,9 @@ impl ApiServer {
         // processes enough requests, information about the latest sealed miniblock will be updated
         // by reporting block difference metrics, so the actual update lag would be much smaller than this value.
         const SEALED_MINIBLOCK_UPDATE_INTERVAL: Duration = Duration::from_millis(25);
+        // Events partitions are detached at most once per partition manager run, which is expected to be
+        // much less frequent than this interval.
+        const DETACHED_EVENTS_RANGES_UPDATE_INTERVAL: Duration = Duration::from_secs(5);
 
         let transport = self.transport;
 
@@ -438,7 +620,18 @@ impl ApiServer {
             stop_receiver.clone(),
         );
 
-        let mut tasks = vec![tokio::spawn(update_task)];
+        let (detached_events_ranges, ranges_update_task) = DetachedEventsRanges::new(
+            self.updaters_pool.clone(),
+            DETACHED_EVENTS_RANGES_UPDATE_INTERVAL,
+            stop_receiver.clone(),
+        );
+
+        let mut tasks = vec![tokio::spawn(update_task), tokio::spawn(ranges_update_task)];
+        if !self.config.filters_disabled {
+            if let Some(filters) = self.persistent_filters() {
+                tasks.push(tokio::spawn(filters.run_cleanup(stop_receiver.clone())));
+            }
+        }
         let pub_sub = if matches!(transport, ApiTransport::WebSocket(_))
             && self.namespaces.contains(&Namespace::Pubsub)
         {
@@ -449,7 +642,9 @@ impl ApiServer {
 
             tasks.extend(pub_sub.spawn_notifiers(
                 self.pool.clone(),
+                self.config.l2_chain_id,
                 self.polling_interval,
+                self.optional.sealed_miniblocks_pool.clone(),
                 stop_receiver.clone(),
             ));
             Some(pub_sub)
@@ -465,6 +660,7 @@ impl ApiServer {
             stop_receiver,
             pub_sub,
             last_sealed_miniblock,
+            detached_events_ranges,
             local_addr_sender,
         ));
 
@@ -481,6 +677,7 @@ impl ApiServer {
         mut stop_receiver: watch::Receiver<bool>,
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
+        detached_events_ranges: DetachedEventsRanges,
         local_addr_sender: oneshot::Sender<SocketAddr>,
     ) -> anyhow::Result<()> {
         let transport = self.transport;
@@ -513,18 +710,39 @@ impl ApiServer {
             .map_or(BatchRequestConfig::Unlimited, |limit| {
                 BatchRequestConfig::Limit(limit as u32)
             });
+        let request_body_size_limit = self
+            .optional
+            .request_body_size_limit
+            .unwrap_or(DEFAULT_MAX_REQUEST_BODY_SIZE);
         let response_body_size_limit = self
             .optional
             .response_body_size_limit
             .map_or(u32::MAX, |limit| limit as u32);
+        let batch_limits = BatchLimits {
+            max_size: self.optional.batch_request_size_limit,
+            max_response_size: self
+                .optional
+                .batch_response_size_limit
+                .or(self.optional.response_body_size_limit),
+            time_budget: self.optional.batch_time_budget,
+        };
         let websocket_requests_per_minute_limit = self.optional.websocket_requests_per_minute_limit;
+        let websocket_compute_units_per_second_limit =
+            self.optional.websocket_compute_units_per_second_limit;
+        let api_keys = self.optional.api_keys.clone();
+        let auth = self.optional.auth.clone();
+        let cors = self.optional.cors.clone().unwrap_or_default();
+        let response_compression_threshold = self.optional.response_compression_threshold;
+        let drain_timeout = self.optional.drain_timeout.unwrap_or(DEFAULT_DRAIN_TIMEOUT);
         let subscriptions_limit = self.optional.subscriptions_limit;
+        let subscriptions_per_connection_limit = self.optional.subscriptions_per_connection_limit;
+        let websocket_message_buffer_capacity = self.optional.websocket_message_buffer_capacity;
         let vm_barrier = self.optional.vm_barrier.clone();
         let health_updater = self.health_updater.clone();
         let method_tracer = self.method_tracer.clone();
 
         let rpc = self
-            .build_rpc_module(pub_sub, last_sealed_miniblock)
+            .build_rpc_module(pub_sub, last_sealed_miniblock, detached_events_ranges)
             .await?;
         let registered_method_names = Arc::new(rpc.method_names().collect::<HashSet<_>>());
         tracing::debug!(
@@ -534,13 +752,46 @@ impl ApiServer {
 
         // Setup CORS.
         let cors = is_http.then(|| {
-            CorsLayer::new()
-                // Allow `POST` when accessing the resource
-                .allow_methods([reqwest::Method::POST])
-                // Allow requests from any origin
-                .allow_origin(tower_http::cors::Any)
-                .allow_headers([reqwest::header::CONTENT_TYPE])
+            let mut server_headers = vec![REQUEST_ID_HEADER];
+            if api_keys.is_some() {
+                server_headers.push(API_KEY_HEADER);
+            }
+            if auth.is_some() {
+                server_headers.push(reqwest::header::AUTHORIZATION);
+            }
+            cors.layer(server_headers)
         });
+        // Setup draining on shutdown.
+        let drain = Arc::new(ServerDrain::default());
+        // Setup request IDs. Calls over WS connections are assigned IDs in RPC-level middleware.
+        let request_ids = is_http.then_some(RequestIdLayer);
+        // Setup response compression. WS messages are not compressed.
+        let compression = is_http
+            .then(|| {
+                response_compression_threshold.map(|threshold| {
+                    CompressionLayer::new(threshold, response_body_size_limit as usize)
+                })
+            })
+            .flatten();
+        // Setup API key authentication.
+        let api_keys =
+            api_keys.map(|(keys, limits)| ApiKeyLayer::new(keys, limits, request_body_size_limit));
+        // Setup bearer token authentication. Since calls over WebSocket connections are not inspected,
+        // unauthenticated connections are rejected if any of the registered methods is protected.
+        let auth = auth.map(|auth| {
+            let has_protected_methods = registered_method_names
+                .iter()
+                .any(|method| auth.is_protected(method));
+            AuthLayer::new(
+                auth,
+                !is_http && has_protected_methods,
+                request_body_size_limit,
+            )
+        });
+        // Setup limits for batch requests. WS batches are limited by `jsonrpsee` itself.
+        let batch_limits = is_http
+            .then(|| BatchLimitsLayer::new(batch_limits, request_body_size_limit))
+            .flatten();
         // Setup metrics for the number of in-flight requests.
         let (in_flight_requests, counter) = InFlightRequestsLayer::pair();
         tokio::spawn(
@@ -552,7 +803,13 @@ impl ApiServer {
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .layer(DrainLayer::new(drain.clone()))
+            .option_layer(cors)
+            .option_layer(request_ids)
+            .option_layer(compression)
+            .option_layer(api_keys)
+            .option_layer(auth)
+            .option_layer(batch_limits);
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -560,20 +817,35 @@ impl ApiServer {
             .flatten()
             .unwrap_or(5_000);
 
+        let rpc_drain = drain.clone();
         #[allow(clippy::let_and_return)] // simplifies conditional compilation
         let rpc_middleware = RpcServiceBuilder::new()
             .layer_fn(move |svc| {
-                MetadataMiddleware::new(svc, registered_method_names.clone(), method_tracer.clone())
+                MetadataMiddleware::new(
+                    svc,
+                    registered_method_names.clone(),
+                    method_tracer.clone(),
+                    transport_label,
+                )
             })
+            // HTTP requests are drained by the HTTP-level middleware.
+            .option_layer((!is_http).then(|| {
+                tower::layer::layer_fn(move |svc| DrainMiddleware::new(svc, rpc_drain.clone()))
+            }))
             .option_layer((!is_http).then(|| {
                 tower::layer::layer_fn(move |svc| {
-                    LimitMiddleware::new(svc, websocket_requests_per_minute_limit)
+                    LimitMiddleware::new(
+                        svc,
+                        websocket_requests_per_minute_limit,
+                        websocket_compute_units_per_second_limit,
+                    )
                 })
             }));
 
         let server_builder = ServerBuilder::default()
             .max_connections(max_connections as u32)
             .set_http_middleware(middleware)
+            .max_request_body_size(request_body_size_limit as u32)
             .max_response_body_size(response_body_size_limit)
             .set_batch_request_config(batch_request_config)
             .set_rpc_middleware(rpc_middleware);
@@ -588,8 +860,14 @@ impl ApiServer {
             (server.local_addr(), server.start(rpc))
         } else {
             // WS-specific settings
+            let mut server_builder = server_builder.set_id_provider(EthSubscriptionIdProvider);
+            if let Some(limit) = subscriptions_per_connection_limit {
+                server_builder = server_builder.max_subscriptions_per_connection(limit as u32);
+            }
+            if let Some(capacity) = websocket_message_buffer_capacity {
+                server_builder = server_builder.set_message_buffer_capacity(capacity as u32);
+            }
             let server = server_builder
-                .set_id_provider(EthSubscriptionIdProvider)
                 .build(addr)
                 .await
                 .context("Failed building WS JSON-RPC server")?;
@@ -621,8 +899,18 @@ impl ApiServer {
                 health_updater.update(HealthStatus::ShuttingDown.into());
             }
             tracing::info!(
-                "Stop signal received, {transport_str} JSON-RPC server is shutting down"
+                "Stop signal received, draining {transport_str} JSON-RPC server for up to {drain_timeout:?}"
             );
+            if drain.drain(drain_timeout).await {
+                tracing::info!(
+                    "All in-flight requests to {transport_str} JSON-RPC server completed"
+                );
+            } else {
+                tracing::warn!(
+                    "In-flight requests to {transport_str} JSON-RPC server didn't complete after {drain_timeout:?}; \
+                     shutting down anyway"
+                );
+            }
             if let Some(closing_vm_barrier) = closing_vm_barrier {
                 closing_vm_barrier.close();
             }
@@ -0,0 +1,37 @@
+use anyhow::Context as _;
+use zksync_dal::ConnectionPool;
+use zksync_types::Address;
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::web3::{backend_jsonrpsee::MethodTracer, state::RpcState};
+
+/// Implementation of the `admin` namespace. Changes are persisted to the master DB and are picked up
+/// by the state keeper, so this namespace works on API servers running separately from the state keeper.
+#[derive(Debug, Clone)]
+pub(crate) struct AdminNamespace {
+    state: RpcState,
+    master_pool: ConnectionPool,
+}
+
+impl AdminNamespace {
+    pub fn new(state: RpcState, master_pool: ConnectionPool) -> Self {
+        Self { state, master_pool }
+    }
+
+    pub(crate) fn current_method(&self) -> &MethodTracer {
+        &self.state.current_method
+    }
+
+    pub async fn set_fee_account_impl(&self, address: Address) -> Result<(), Web3Error> {
+        let mut storage = self.master_pool.access_storage_tagged("api").await?;
+        storage
+            .fee_account_dal()
+            .schedule_fee_account(address)
+            .await
+            .context("schedule_fee_account")?;
+        tracing::info!(
+            "Scheduled fee account change to {address:?} starting from the next L1 batch"
+        );
+        Ok(())
+    }
+}
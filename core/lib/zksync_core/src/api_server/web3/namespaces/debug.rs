@@ -1,21 +1,35 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
 
 use anyhow::Context as _;
-use multivm::{interface::ExecutionResult, vm_latest::constants::BLOCK_GAS_LIMIT};
+use multivm::{
+    interface::{ExecutionResult, VmExecutionResultAndLogs},
+    vm_latest::constants::BLOCK_GAS_LIMIT,
+};
 use once_cell::sync::OnceCell;
+use tokio::sync::Semaphore;
 use zksync_system_constants::MAX_ENCODED_TX_SIZE;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, DebugCall, PrestateAccount, ResultTransactionTrace, SupportedTracers,
+        TracerConfig, TransactionTrace,
+    },
     fee_model::BatchFeeInput,
+    get_nonce_key,
     l2::L2Tx,
     transaction_request::CallRequest,
+    utils::{decompose_full_nonce, storage_key_for_eth_balance},
     vm_trace::Call,
-    AccountTreeId, H256,
+    AccountTreeId, Address, StorageKey, StorageLogQuery, Transaction, H256,
 };
+use zksync_utils::{h256_to_u256, u256_to_h256};
 use zksync_web3_decl::error::Web3Error;
 
 use crate::api_server::{
-    execution_sandbox::{ApiTracer, TxSharedArgs},
+    execution_sandbox::{ApiTracer, BlockArgs, TxExecutionArgs, TxSharedArgs},
     tx_sender::{ApiContracts, TxSenderConfig},
     web3::{backend_jsonrpsee::MethodTracer, state::RpcState},
 };
@@ -25,11 +39,14 @@ pub(crate) struct DebugNamespace {
     batch_fee_input: BatchFeeInput,
     state: RpcState,
     api_contracts: ApiContracts,
+    /// Limits the number of concurrent miniblock replays in `debug_traceBlockBy*` methods.
+    block_replay_limiter: Arc<Semaphore>,
 }
 
 impl DebugNamespace {
     pub async fn new(state: RpcState) -> Self {
         let api_contracts = ApiContracts::load_from_disk();
+        let block_replay_limiter = Semaphore::new(state.api_config.trace_block_concurrency);
         Self {
             // For now, the same scaling is used for both the L1 gas price and the pubdata price
             batch_fee_input: state
@@ -43,9 +60,17 @@ impl DebugNamespace {
                 .await,
             state,
             api_contracts,
+            block_replay_limiter: Arc::new(block_replay_limiter),
         }
     }
 
+    /// Returns the requested tracer and whether only the top-level call should be traced.
+    fn parse_options(options: Option<TracerConfig>) -> (SupportedTracers, bool) {
+        options.map_or((SupportedTracers::CallTracer, false), |options| {
+            (options.tracer, options.tracer_config.only_top_call)
+        })
+    }
+
     fn sender_config(&self) -> &TxSenderConfig {
         &self.state.tx_sender.0.sender_config
     }
@@ -54,70 +79,240 @@ impl DebugNamespace {
         &self.state.current_method
     }
 
+    /// Traces all transactions in a miniblock. Similar to [`Self::debug_trace_transaction_impl()`], saved call traces
+    /// are returned if available; otherwise, the entire miniblock is replayed in the sandbox. The number of concurrent
+    /// replays is limited separately from other VM invocations since replaying a miniblock may take a long time.
     #[tracing::instrument(skip(self))]
     pub async fn debug_trace_block_impl(
         &self,
         block_id: BlockId,
         options: Option<TracerConfig>,
-    ) -> Result<Vec<ResultDebugCall>, Web3Error> {
+    ) -> Result<Vec<ResultTransactionTrace>, Web3Error> {
         self.current_method().set_block_id(block_id);
 
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+        let (tracer, only_top_call) = Self::parse_options(options);
         let mut connection = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await?;
-        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        let block_number = self
+            .state
+            .resolve_block_with_state(&mut connection, block_id)
+            .await?;
         self.current_method()
             .set_block_diff(self.state.last_sealed_miniblock.diff(block_number));
 
-        let call_traces = connection
-            .blocks_web3_dal()
-            .get_traces_for_miniblock(block_number)
+        if matches!(tracer, SupportedTracers::CallTracer) {
+            let call_traces = connection
+                .blocks_web3_dal()
+                .get_traces_for_miniblock(block_number)
+                .await
+                .context("get_traces_for_miniblock")?;
+            // Call traces are either saved for all transactions in the miniblock, or for none of them.
+            if !call_traces.is_empty() {
+                let traces = call_traces
+                    .into_iter()
+                    .map(|call| TransactionTrace::Call(self.map_call(call, only_top_call)));
+                return self.check_block_traces(traces.collect());
+            }
+        }
+
+        let block_txs = connection
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(block_number)
             .await
-            .context("get_traces_for_miniblock")?;
-        let call_trace = call_traces
+            .context("get_raw_miniblock_transactions")?;
+        if block_txs.is_empty() {
+            return Ok(vec![]);
+        }
+        let block_id = BlockId::Number(BlockNumber::Number(block_number.0.into()));
+        let block_args = self
+            .state
+            .resolve_block_args(&mut connection, block_id)
+            .await?;
+        drop(connection);
+
+        let _permit = self
+            .block_replay_limiter
+            .acquire()
+            .await
+            .context("block replay limiter is closed")?;
+        let traces = self
+            .replay_and_trace(block_args, block_txs, 0, &tracer, only_top_call)
+            .await?;
+        self.check_block_traces(traces)
+    }
+
+    fn check_block_traces(
+        &self,
+        traces: Vec<TransactionTrace>,
+    ) -> Result<Vec<ResultTransactionTrace>, Web3Error> {
+        let max_result_size = self.state.api_config.trace_block_max_result_size;
+        let result_size: usize = traces.iter().map(trace_size).sum();
+        if result_size > max_result_size {
+            return Err(Web3Error::TraceTooLarge(max_result_size));
+        }
+        Ok(traces
             .into_iter()
-            .map(|call_trace| {
-                let mut result: DebugCall = call_trace.into();
-                if only_top_call {
-                    result.calls = vec![];
-                }
-                ResultDebugCall { result }
-            })
-            .collect();
-        Ok(call_trace)
+            .map(|result| ResultTransactionTrace { result })
+            .collect())
     }
 
+    /// Traces a transaction included into a miniblock. Call traces are returned from the storage if they were saved
+    /// by the state keeper; otherwise, the transaction is re-executed in the sandbox together with the transactions
+    /// preceding it in the miniblock. Prestate traces always require re-execution.
     #[tracing::instrument(skip(self))]
     pub async fn debug_trace_transaction_impl(
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> Result<Option<DebugCall>, Web3Error> {
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+    ) -> Result<Option<TransactionTrace>, Web3Error> {
+        let (tracer, only_top_call) = Self::parse_options(options);
         let mut connection = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await?;
-        let call_trace = connection
-            .transactions_dal()
-            .get_call_trace(tx_hash)
-            .await
-            .context("get_call_trace")?;
-        Ok(call_trace.map(|call_trace| {
-            let mut result: DebugCall = call_trace.into();
-            if only_top_call {
-                result.calls = vec![];
+        if matches!(tracer, SupportedTracers::CallTracer) {
+            let call_trace = connection
+                .transactions_dal()
+                .get_call_trace(tx_hash)
+                .await
+                .context("get_call_trace")?;
+            if let Some(call_trace) = call_trace {
+                let call = self.map_call(call_trace, only_top_call);
+                return Ok(Some(TransactionTrace::Call(call)));
             }
-            result
-        }))
+        }
+
+        let api_tx = connection
+            .transactions_web3_dal()
+            .get_transaction_by_hash(tx_hash, self.sender_config().chain_id)
+            .await
+            .context("get_transaction_by_hash")?;
+        let Some(block_number) = api_tx.and_then(|tx| tx.block_number) else {
+            return Ok(None); // The transaction is unknown or is not included into a miniblock yet
+        };
+        let block_id = BlockId::Number(BlockNumber::Number(block_number));
+        self.current_method().set_block_id(block_id);
+        let block_args = self
+            .state
+            .resolve_block_args(&mut connection, block_id)
+            .await?;
+        self.current_method().set_block_diff(
+            self.state
+                .last_sealed_miniblock
+                .diff_with_block_args(&block_args),
+        );
+
+        let mut block_txs = connection
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(block_args.resolved_block_number())
+            .await
+            .context("get_raw_miniblock_transactions")?;
+        drop(connection);
+        let tx_index = block_txs
+            .iter()
+            .position(|tx| tx.hash() == tx_hash)
+            .context("transaction is missing from its miniblock")?;
+        block_txs.truncate(tx_index + 1);
+
+        let mut traces = self
+            .replay_and_trace(block_args, block_txs, tx_index, &tracer, only_top_call)
+            .await?;
+        Ok(traces.pop())
+    }
+
+    /// Replays `txs` (a prefix of transactions in the miniblock specified by `block_args`) in the sandbox
+    /// and returns traces for transactions starting from `first_traced_tx`.
+    async fn replay_and_trace(
+        &self,
+        block_args: BlockArgs,
+        txs: Vec<Transaction>,
+        first_traced_tx: usize,
+        tracer: &SupportedTracers,
+        only_top_call: bool,
+    ) -> Result<Vec<TransactionTrace>, Web3Error> {
+        let vm_permit = self
+            .state
+            .tx_sender
+            .vm_concurrency_limiter()
+            .acquire()
+            .await;
+        let vm_permit = vm_permit.context("cannot acquire VM permit")?;
+        let trace_timeout = self.state.api_config.trace_timeout;
+        let deadline = Instant::now() + trace_timeout;
+
+        let trace_calls = matches!(tracer, SupportedTracers::CallTracer) && !only_top_call;
+        let call_tracer_results: Vec<_> = (0..txs.len())
+            .map(|i| (trace_calls && i >= first_traced_tx).then(|| Arc::new(OnceCell::default())))
+            .collect();
+        let txs_with_tracers = txs.iter().zip(&call_tracer_results).map(|(tx, result)| {
+            let tracers = result
+                .iter()
+                .map(|result| ApiTracer::CallTracer(result.clone()))
+                .collect();
+            (tx.clone(), tracers)
+        });
+
+        let executor = &self.state.tx_sender.0.executor;
+        let results = executor
+            .replay_txs_in_sandbox(
+                vm_permit,
+                self.shared_args(),
+                TxExecutionArgs::for_replay(deadline),
+                self.state.connection_pool.clone(),
+                block_args,
+                txs_with_tracers.collect(),
+            )
+            .await?;
+        let is_halted = results
+            .iter()
+            .any(|result| matches!(result.result, ExecutionResult::Halt { .. }));
+        if results.len() < txs.len() || (is_halted && Instant::now() >= deadline) {
+            return Err(Web3Error::TraceTimeout(trace_timeout));
+        }
+
+        let it = txs.into_iter().zip(results).zip(call_tracer_results);
+        let mut traces = Vec::with_capacity(it.len().saturating_sub(first_traced_tx));
+        for ((tx, result), call_tracer_result) in it.skip(first_traced_tx) {
+            let trace = match tracer {
+                SupportedTracers::CallTracer => {
+                    let calls = call_tracer_result
+                        .and_then(|result| result.get().cloned())
+                        .unwrap_or_default();
+                    let call = Self::replayed_call(tx, result, calls);
+                    TransactionTrace::Call(self.map_call(call, only_top_call))
+                }
+                SupportedTracers::PrestateTracer => {
+                    let accounts = [tx.initiator_account(), tx.recipient_account()];
+                    let prestate = build_prestate(accounts, &result.logs.storage_logs);
+                    TransactionTrace::Prestate(prestate)
+                }
+            };
+            traces.push(trace);
+        }
+        Ok(traces)
+    }
+
+    /// Creates a top-level call for a replayed transaction, similar to call traces saved by the state keeper.
+    fn replayed_call(tx: Transaction, result: VmExecutionResultAndLogs, calls: Vec<Call>) -> Call {
+        let revert_reason = match result.result {
+            ExecutionResult::Success { .. } => None,
+            ExecutionResult::Revert { output } => Some(output.to_string()),
+            ExecutionResult::Halt { reason } => Some(reason.to_string()),
+        };
+        let gas_limit = tx.gas_limit().as_u32();
+        Call::new_high_level(
+            gas_limit,
+            gas_limit.saturating_sub(result.refunds.gas_refunded),
+            tx.execute.value,
+            tx.execute.calldata,
+            vec![],
+            revert_reason,
+            calls,
+        )
     }
 
     #[tracing::instrument(skip(self, request, block_id))]
@@ -207,7 +402,22 @@ impl DebugNamespace {
             revert_reason,
             trace,
         );
-        Ok(call.into())
+        Ok(self.map_call(call, false))
+    }
+
+    /// Converts a call trace to the API format, truncating nested calls according to the tracer options
+    /// and the server-side depth limit.
+    fn map_call(&self, call: Call, only_top_call: bool) -> DebugCall {
+        let mut call = DebugCall::from(call);
+        let max_depth = if only_top_call {
+            Some(0)
+        } else {
+            self.state.api_config.trace_max_call_depth
+        };
+        if let Some(max_depth) = max_depth {
+            truncate_calls(&mut call, max_depth);
+        }
+        call
     }
 
     fn shared_args(&self) -> TxSharedArgs {
@@ -217,8 +427,178 @@ impl DebugNamespace {
             fee_input: self.batch_fee_input,
             base_system_contracts: self.api_contracts.eth_call.clone(),
             caches: self.state.tx_sender.storage_caches().clone(),
+            archive_state: self.state.tx_sender.archive_state().cloned(),
             validation_computational_gas_limit: BLOCK_GAS_LIMIT,
             chain_id: sender_config.chain_id,
         }
     }
 }
+
+/// Returns the number of entries in a trace: call frames for call traces, and accounts and storage slots for prestate traces.
+fn trace_size(trace: &TransactionTrace) -> usize {
+    fn call_size(call: &DebugCall) -> usize {
+        1 + call.calls.iter().map(call_size).sum::<usize>()
+    }
+
+    match trace {
+        TransactionTrace::Call(call) => call_size(call),
+        TransactionTrace::Prestate(prestate) => prestate
+            .values()
+            .map(|account| 1 + account.storage.len())
+            .sum(),
+    }
+}
+
+/// Removes calls nested deeper than `max_depth`; the top-level call has zero depth.
+pub(super) fn truncate_calls(call: &mut DebugCall, max_depth: usize) {
+    if max_depth == 0 {
+        call.calls = vec![];
+    } else {
+        for nested_call in &mut call.calls {
+            truncate_calls(nested_call, max_depth - 1);
+        }
+    }
+}
+
+/// Builds a prestate trace from storage logs produced by a transaction. For each accessed slot, the value read
+/// by the first access is the value before the transaction. Slots encoding balances and nonces of `accounts`
+/// and of the contracts accessed by the transaction are reported as the corresponding account fields rather than raw storage.
+fn build_prestate(
+    accounts: impl IntoIterator<Item = Address>,
+    storage_logs: &[StorageLogQuery],
+) -> BTreeMap<Address, PrestateAccount> {
+    let mut initial_values = HashMap::new();
+    for log in storage_logs {
+        let log = &log.log_query;
+        if log.rollback {
+            continue;
+        }
+        let key = StorageKey::new(AccountTreeId::new(log.address), u256_to_h256(log.key));
+        initial_values
+            .entry(key)
+            .or_insert_with(|| u256_to_h256(log.read_value));
+    }
+
+    let accounts: HashSet<_> = initial_values
+        .keys()
+        .map(|key| *key.address())
+        .chain(accounts)
+        .collect();
+    let mut prestate = BTreeMap::<_, PrestateAccount>::new();
+    for address in accounts {
+        if let Some(balance) = initial_values.remove(&storage_key_for_eth_balance(&address)) {
+            prestate.entry(address).or_default().balance = Some(h256_to_u256(balance));
+        }
+        if let Some(full_nonce) = initial_values.remove(&get_nonce_key(&address)) {
+            let (nonce, _) = decompose_full_nonce(h256_to_u256(full_nonce));
+            prestate.entry(address).or_default().nonce = Some(nonce.as_u64());
+        }
+    }
+    for (key, value) in initial_values {
+        prestate
+            .entry(*key.address())
+            .or_default()
+            .storage
+            .insert(*key.key(), value);
+    }
+    prestate
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        zk_evm_types::{LogQuery, Timestamp},
+        StorageLogQueryType, L2_ETH_TOKEN_ADDRESS, NONCE_HOLDER_ADDRESS, U256,
+    };
+
+    use super::*;
+
+    fn read_query(key: &StorageKey, value: U256) -> StorageLogQuery {
+        StorageLogQuery {
+            log_query: LogQuery {
+                timestamp: Timestamp(0),
+                tx_number_in_block: 0,
+                aux_byte: 0,
+                shard_id: 0,
+                address: *key.address(),
+                key: h256_to_u256(*key.key()),
+                read_value: value,
+                written_value: value,
+                rw_flag: false,
+                rollback: false,
+                is_service: false,
+            },
+            log_type: StorageLogQueryType::Read,
+        }
+    }
+
+    #[test]
+    fn building_prestate() {
+        let sender = Address::repeat_byte(0x23);
+        let contract_slot =
+            StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        let mut overwritten_slot = read_query(&contract_slot, 1.into());
+        overwritten_slot.log_query.written_value = 2.into();
+        let storage_logs = [
+            read_query(&storage_key_for_eth_balance(&sender), 1_000.into()),
+            read_query(&get_nonce_key(&sender), 3.into()),
+            overwritten_slot,
+            read_query(&contract_slot, 2.into()),
+        ];
+
+        let prestate = build_prestate([sender], &storage_logs);
+        assert_eq!(prestate.len(), 2, "{prestate:?}");
+        assert_eq!(
+            prestate[&sender],
+            PrestateAccount {
+                balance: Some(1_000.into()),
+                nonce: Some(3),
+                storage: BTreeMap::new(),
+            }
+        );
+        assert_eq!(
+            prestate[&Address::repeat_byte(1)].storage,
+            BTreeMap::from([(H256::zero(), u256_to_h256(1.into()))])
+        );
+        assert!(!prestate.contains_key(&L2_ETH_TOKEN_ADDRESS));
+        assert!(!prestate.contains_key(&NONCE_HOLDER_ADDRESS));
+    }
+
+    #[test]
+    fn truncating_calls() {
+        let leaf = DebugCall::from(Call::default());
+        let mut call = DebugCall {
+            calls: vec![DebugCall {
+                calls: vec![leaf.clone()],
+                ..leaf.clone()
+            }],
+            ..leaf.clone()
+        };
+        truncate_calls(&mut call, 1);
+        assert_eq!(call.calls.len(), 1);
+        assert!(call.calls[0].calls.is_empty());
+        truncate_calls(&mut call, 0);
+        assert!(call.calls.is_empty());
+    }
+
+    #[test]
+    fn computing_trace_size() {
+        let leaf = DebugCall::from(Call::default());
+        let call = DebugCall {
+            calls: vec![leaf.clone(), leaf.clone()],
+            ..leaf
+        };
+        assert_eq!(trace_size(&TransactionTrace::Call(call)), 3);
+
+        let account = PrestateAccount {
+            balance: Some(1.into()),
+            nonce: None,
+            storage: BTreeMap::from([(H256::zero(), H256::repeat_byte(1))]),
+        };
+        let prestate = BTreeMap::from([
+            (Address::repeat_byte(1), account),
+            (Address::repeat_byte(2), PrestateAccount::default()),
+        ]);
+        assert_eq!(trace_size(&TransactionTrace::Prestate(prestate)), 3);
+    }
+}
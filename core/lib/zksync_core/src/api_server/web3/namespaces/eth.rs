@@ -1,27 +1,27 @@
 use anyhow::Context as _;
+use zksync_state::ReadStorage;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        proof::AccountProof, AccessListWithGasUsed, BlockId, BlockNumber, StorageProof, SyncInfo,
+        Transaction, TransactionConditions, TransactionId, TransactionReceipt, TransactionVariant,
     },
     l2::{L2Tx, TransactionType},
     transaction_request::CallRequest,
-    utils::decompose_full_nonce,
-    web3::{
-        self,
-        types::{FeeHistory, SyncInfo, SyncState},
-    },
-    AccountTreeId, Bytes, MiniblockNumber, StorageKey, H256, L2_ETH_TOKEN_ADDRESS, U256,
+    utils::{decompose_full_nonce, storage_key_for_eth_balance},
+    web3::{self, types::FeeHistory},
+    AccountTreeId, Bytes, L1BatchNumber, MiniblockNumber, StorageKey, H256, L2_ETH_TOKEN_ADDRESS,
+    U256,
 };
-use zksync_utils::u256_to_h256;
+use zksync_utils::{h256_to_u256, u256_to_h256};
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Address, Block, Filter, FilterChanges, Log, U64},
 };
 
-use crate::api_server::web3::{
-    backend_jsonrpsee::MethodTracer, metrics::API_METRICS, state::RpcState, TypedFilter,
+use crate::api_server::{
+    tree::TreeEntryWithProof,
+    web3::{backend_jsonrpsee::MethodTracer, metrics::API_METRICS, state::RpcState, TypedFilter},
 };
 
 pub const EVENT_TOPIC_NUMBER_LIMIT: usize = 4;
@@ -87,6 +87,46 @@ impl EthNamespace {
         Ok(call_result.into())
     }
 
+    /// Executes the call and returns contracts and storage slots accessed by it, together with a gas estimate.
+    /// Unlike the access list, the gas estimate is always computed for the pending block, same as in `eth_estimateGas`.
+    #[tracing::instrument(skip(self, request))]
+    pub async fn create_access_list_impl(
+        &self,
+        request: CallRequest,
+        block_id: Option<BlockId>,
+    ) -> Result<AccessListWithGasUsed, Web3Error> {
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        self.current_method().set_block_id(block_id);
+
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await?;
+        let block_args = self
+            .state
+            .resolve_block_args(&mut connection, block_id)
+            .await?;
+        self.current_method().set_block_diff(
+            self.state
+                .last_sealed_miniblock
+                .diff_with_block_args(&block_args),
+        );
+        drop(connection);
+
+        let tx = L2Tx::from_request(request.clone().into(), self.state.api_config.max_tx_size)?;
+        let access_list = self
+            .state
+            .tx_sender
+            .create_access_list(block_args, tx)
+            .await?;
+        let gas_used = self.estimate_gas_impl(request, None).await?;
+        Ok(AccessListWithGasUsed {
+            access_list,
+            gas_used,
+        })
+    }
+
     #[tracing::instrument(skip(self, request, _block))]
     pub async fn estimate_gas_impl(
         &self,
@@ -140,8 +180,28 @@ impl EthNamespace {
 
     #[tracing::instrument(skip(self))]
     pub async fn gas_price_impl(&self) -> Result<U256, Web3Error> {
-        let gas_price = self.state.tx_sender.gas_price().await?;
-        Ok(gas_price.into())
+        let base_fee = self.state.tx_sender.gas_price().await?;
+        let priority_fee = self.suggest_priority_fee().await?;
+        Ok(U256::from(base_fee).saturating_add(priority_fee))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn max_priority_fee_per_gas_impl(&self) -> Result<U256, Web3Error> {
+        self.suggest_priority_fee().await
+    }
+
+    async fn suggest_priority_fee(&self) -> Result<U256, Web3Error> {
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await?;
+        let fee = self
+            .state
+            .priority_fee_suggester
+            .suggest(&mut connection)
+            .await?;
+        Ok(fee)
     }
 
     #[tracing::instrument(skip(self))]
@@ -158,7 +218,16 @@ impl EthNamespace {
             .connection_pool
             .access_storage_tagged("api")
             .await?;
-        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        let block_number = self
+            .state
+            .resolve_block_with_state(&mut connection, block_id)
+            .await?;
+        self.set_block_diff(block_number);
+
+        let balance_key = storage_key_for_eth_balance(&address);
+        if let Some(balance) = self.read_archived_value(balance_key, block_number).await? {
+            return Ok(h256_to_u256(balance));
+        }
 
         let balance = connection
             .storage_web3_dal()
@@ -169,11 +238,27 @@ impl EthNamespace {
             )
             .await
             .context("standard_token_historical_balance")?;
-        self.set_block_diff(block_number);
-
         Ok(balance)
     }
 
+    /// Reads a storage value after the specified miniblock from the archived state. Returns `None` if the state
+    /// archive is disabled or doesn't contain the miniblock.
+    async fn read_archived_value(
+        &self,
+        key: StorageKey,
+        block_number: MiniblockNumber,
+    ) -> anyhow::Result<Option<H256>> {
+        let Some(archive) = self.state.tx_sender.archive_state().cloned() else {
+            return Ok(None);
+        };
+        tokio::task::spawn_blocking(move || {
+            let mut storage = archive.storage_at(block_number)?;
+            Some(storage.read_value(&key))
+        })
+        .await
+        .context("panicked reading archived state")
+    }
+
     fn set_block_diff(&self, block_number: MiniblockNumber) {
         let diff = self.state.last_sealed_miniblock.diff(block_number);
         self.current_method().set_block_diff(diff);
@@ -201,7 +286,7 @@ impl EthNamespace {
             .as_ref()
             .ok_or(Web3Error::NotImplemented)?;
         // We clone the filter to not hold the filter lock for an extended period of time.
-        let maybe_filter = installed_filters.lock().await.get_and_update_stats(idx);
+        let maybe_filter = installed_filters.get_and_update_stats(idx).await?;
 
         let Some(TypedFilter::Events(filter, _)) = maybe_filter else {
             return Err(Web3Error::FilterNotFound);
@@ -339,22 +424,23 @@ impl EthNamespace {
         else {
             return Ok(None);
         };
-        let Some(block) = storage
-            .blocks_web3_dal()
-            .get_api_block(block_number)
-            .await
-            .with_context(|| format!("get_api_block({block_number})"))?
-        else {
-            return Ok(None);
-        };
-        self.set_block_diff(block_number); // only report block diff for existing miniblocks
-
-        let mut receipts = storage
+        let receipts = storage
             .transactions_web3_dal()
-            .get_transaction_receipts(&block.transactions)
+            .get_block_receipts(block_number)
             .await
-            .with_context(|| format!("get_transaction_receipts({block_number})"))?;
-        receipts.sort_unstable_by_key(|receipt| receipt.transaction_index);
+            .with_context(|| format!("get_block_receipts({block_number})"))?;
+        if receipts.is_empty() {
+            // Distinguish between an empty and a missing miniblock.
+            let header = storage
+                .blocks_dal()
+                .get_miniblock_header(block_number)
+                .await
+                .with_context(|| format!("get_miniblock_header({block_number})"))?;
+            if header.is_none() {
+                return Ok(None);
+            }
+        }
+        self.set_block_diff(block_number); // only report block diff for existing miniblocks
         Ok(Some(receipts))
     }
 
@@ -372,7 +458,10 @@ impl EthNamespace {
             .connection_pool
             .access_storage_tagged("api")
             .await?;
-        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        let block_number = self
+            .state
+            .resolve_block_with_state(&mut connection, block_id)
+            .await?;
         self.set_block_diff(block_number);
 
         let contract_code = connection
@@ -404,8 +493,15 @@ impl EthNamespace {
             .connection_pool
             .access_storage_tagged("api")
             .await?;
-        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        let block_number = self
+            .state
+            .resolve_block_with_state(&mut connection, block_id)
+            .await?;
         self.set_block_diff(block_number);
+        if let Some(value) = self.read_archived_value(storage_key, block_number).await? {
+            return Ok(value);
+        }
+
         let value = connection
             .storage_web3_dal()
             .get_historical_value_unchecked(&storage_key, block_number)
@@ -414,6 +510,76 @@ impl EthNamespace {
         Ok(value)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_proof_impl(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<AccountProof>, Web3Error> {
+        let account_keys = AccountProof::account_keys(&address);
+        let hashed_keys =
+            account_keys
+                .iter()
+                .map(StorageKey::hashed_key_u256)
+                .chain(keys.iter().map(|key| {
+                    StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256()
+                }))
+                .collect();
+        let Some(mut proofs) = self
+            .state
+            .get_tree_proofs(l1_batch_number, hashed_keys)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await?;
+        let root_hash = connection
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await
+            .context("get_l1_batch_state_root")?;
+        // The root hash may be not persisted in Postgres yet even if the tree has processed the batch.
+        let Some(root_hash) = root_hash else {
+            return Ok(None);
+        };
+
+        let storage_proofs = proofs.split_off(account_keys.len());
+        let to_api_proof = |key: H256, proof: TreeEntryWithProof| StorageProof {
+            key,
+            proof: proof.merkle_path,
+            value: proof.value,
+            index: proof.index,
+        };
+        let account_proof: Vec<_> = proofs
+            .into_iter()
+            .zip(&account_keys)
+            .map(|(proof, key)| to_api_proof(*key.key(), proof))
+            .collect();
+        let storage_proof = storage_proofs
+            .into_iter()
+            .zip(keys)
+            .map(|(proof, key)| to_api_proof(key, proof))
+            .collect();
+
+        let (nonce, _) = decompose_full_nonce(h256_to_u256(account_proof[1].value));
+        Ok(Some(AccountProof {
+            address,
+            l1_batch_number,
+            root_hash,
+            balance: h256_to_u256(account_proof[0].value),
+            nonce,
+            code_hash: account_proof[2].value,
+            account_proof,
+            storage_proof,
+        }))
+    }
+
     /// Account nonce.
     #[tracing::instrument(skip(self))]
     pub async fn get_transaction_count_impl(
@@ -430,7 +596,10 @@ impl EthNamespace {
             .access_storage_tagged("api")
             .await?;
 
-        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        let block_number = self
+            .state
+            .resolve_block_with_state(&mut connection, block_id)
+            .await?;
         self.set_block_diff(block_number);
         let full_nonce = connection
             .storage_web3_dal()
@@ -511,6 +680,47 @@ impl EthNamespace {
         Ok(transaction)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_raw_transaction_impl(
+        &self,
+        id: TransactionId,
+    ) -> Result<Option<Bytes>, Web3Error> {
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await?;
+        let raw_transaction = match id {
+            TransactionId::Hash(hash) => storage
+                .transactions_web3_dal()
+                .get_raw_transaction_by_hash(hash)
+                .await
+                .with_context(|| format!("get_raw_transaction_by_hash({hash:?})"))?,
+
+            TransactionId::Block(block_id, idx) => {
+                let Ok(idx) = u32::try_from(idx) else {
+                    return Ok(None); // index overflow means no transaction
+                };
+                let Some(block_number) = self
+                    .state
+                    .resolve_block_unchecked(&mut storage, block_id)
+                    .await?
+                else {
+                    return Ok(None);
+                };
+
+                storage
+                    .transactions_web3_dal()
+                    .get_raw_transaction_by_position(block_number, idx)
+                    .await
+                    .with_context(|| {
+                        format!("get_raw_transaction_by_position({block_number}, {idx})")
+                    })?
+            }
+        };
+        Ok(raw_transaction.map(Bytes))
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_transaction_receipt_impl(
         &self,
@@ -550,9 +760,8 @@ impl EthNamespace {
         drop(storage);
 
         Ok(installed_filters
-            .lock()
-            .await
-            .add(TypedFilter::Blocks(next_block_number)))
+            .add(TypedFilter::Blocks(next_block_number))
+            .await?)
     }
 
     #[tracing::instrument(skip(self, filter))]
@@ -571,9 +780,8 @@ impl EthNamespace {
         self.state.resolve_filter_block_hash(&mut filter).await?;
         let from_block = self.state.get_filter_from_block(&filter).await?;
         Ok(installed_filters
-            .lock()
-            .await
-            .add(TypedFilter::Events(filter, from_block)))
+            .add(TypedFilter::Events(filter, from_block))
+            .await?)
     }
 
     #[tracing::instrument(skip(self))]
@@ -584,11 +792,10 @@ impl EthNamespace {
             .as_ref()
             .ok_or(Web3Error::NotImplemented)?;
         Ok(installed_filters
-            .lock()
-            .await
             .add(TypedFilter::PendingTransactions(
                 chrono::Utc::now().naive_utc(),
-            )))
+            ))
+            .await?)
     }
 
     #[tracing::instrument(skip(self))]
@@ -599,19 +806,18 @@ impl EthNamespace {
             .as_ref()
             .ok_or(Web3Error::NotImplemented)?;
         let mut filter = installed_filters
-            .lock()
-            .await
             .get_and_update_stats(idx)
+            .await?
             .ok_or(Web3Error::FilterNotFound)?;
 
         match self.filter_changes(&mut filter).await {
             Ok(changes) => {
-                installed_filters.lock().await.update(idx, filter);
+                installed_filters.update(idx, filter).await?;
                 Ok(changes)
             }
             Err(Web3Error::LogsLimitExceeded(..)) => {
                 // The filter was not being polled for a long time, so we remove it.
-                installed_filters.lock().await.remove(idx);
+                installed_filters.remove(idx).await?;
                 Err(Web3Error::FilterNotFound)
             }
             Err(err) => Err(err),
@@ -625,7 +831,7 @@ impl EthNamespace {
             .installed_filters
             .as_ref()
             .ok_or(Web3Error::NotImplemented)?;
-        Ok(installed_filters.lock().await.remove(idx))
+        Ok(installed_filters.remove(idx).await?)
     }
 
     #[tracing::instrument(skip(self))]
@@ -647,28 +853,87 @@ impl EthNamespace {
         })
     }
 
+    #[tracing::instrument(skip(self, tx_bytes, conditions))]
+    pub async fn send_raw_transaction_conditional_impl(
+        &self,
+        tx_bytes: Bytes,
+        conditions: TransactionConditions,
+    ) -> Result<H256, Web3Error> {
+        let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+        tx.set_input(tx_bytes.0, hash);
+
+        let submit_result = self
+            .state
+            .tx_sender
+            .submit_conditional_tx(tx, conditions)
+            .await;
+        submit_result.map(|_| hash).map_err(|err| {
+            tracing::debug!("Send conditional raw transaction error: {err}");
+            API_METRICS.submit_tx_error[&err.prom_error_code()].inc();
+            err.into()
+        })
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn accounts_impl(&self) -> Vec<Address> {
         Vec::new()
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn syncing_impl(&self) -> SyncState {
-        if let Some(state) = &self.state.sync_state {
+    pub async fn syncing_impl(&self) -> Result<SyncInfo, Web3Error> {
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await?;
+        let first_miniblock = self.state.start_info.first_miniblock;
+        let current_block = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("get_sealed_miniblock_number")?
+            // Can happen after snapshot recovery before the first miniblock is synced
+            .unwrap_or(MiniblockNumber(first_miniblock.0.saturating_sub(1)));
+        let current_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("get_sealed_l1_batch_number")?;
+        let committed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_committed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_committed_on_eth")?;
+        let proven_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_proven_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_proven_on_eth")?;
+        let executed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_executed_on_eth")?;
+        drop(storage);
+
+        let (highest_block, is_synced) = if let Some(state) = &self.state.sync_state {
             // Node supports syncing process (i.e. not the main node).
-            if state.is_synced() {
-                SyncState::NotSyncing
-            } else {
-                SyncState::Syncing(SyncInfo {
-                    starting_block: 0u64.into(), // We always start syncing from genesis right now.
-                    current_block: state.get_local_block().0.into(),
-                    highest_block: state.get_main_node_block().0.into(),
-                })
-            }
+            let main_node_block = state.get_main_node_block();
+            (main_node_block.max(current_block), state.is_synced())
         } else {
             // If there is no sync state, then the node is the main node and it's always synced.
-            SyncState::NotSyncing
-        }
+            (current_block, true)
+        };
+        Ok(SyncInfo {
+            starting_block: first_miniblock.0.into(),
+            current_block: current_block.0.into(),
+            highest_block: highest_block.0.into(),
+            is_synced,
+            current_l1_batch,
+            committed_l1_batch,
+            proven_l1_batch,
+            executed_l1_batch,
+        })
     }
 
     #[tracing::instrument(skip(self))]
@@ -681,6 +946,13 @@ impl EthNamespace {
         self.current_method()
             .set_block_id(BlockId::Number(newest_block));
 
+        let is_valid_percentile = |&percentile: &f32| (0.0..=100.0).contains(&percentile);
+        let are_percentiles_valid = reward_percentiles.iter().all(is_valid_percentile)
+            && reward_percentiles.windows(2).all(|pair| pair[0] <= pair[1]);
+        if !are_percentiles_valid {
+            return Err(Web3Error::InvalidRewardPercentiles);
+        }
+
         // Limit `block_count`.
         let block_count = block_count
             .as_u64()
@@ -698,22 +970,31 @@ impl EthNamespace {
             .await?;
         self.set_block_diff(newest_miniblock);
 
-        let mut base_fee_per_gas = connection
+        let oldest_miniblock =
+            MiniblockNumber(newest_miniblock.0.saturating_sub(block_count as u32 - 1));
+        let fee_series = connection
             .blocks_web3_dal()
-            .get_fee_history(newest_miniblock, block_count)
+            .get_fee_series(oldest_miniblock..=newest_miniblock)
             .await
-            .context("get_fee_history")?;
-        // DAL method returns fees in DESC order while we need ASC.
-        base_fee_per_gas.reverse();
+            .context("get_fee_series")?;
+        let mut base_fee_per_gas: Vec<_> = fee_series
+            .iter()
+            .map(|fees| fees.base_fee_per_gas)
+            .collect();
 
         let oldest_block = newest_miniblock.0 + 1 - base_fee_per_gas.len() as u32;
         // We do not store gas used ratio for blocks, returns array of zeroes as a placeholder.
         let gas_used_ratio = vec![0.0; base_fee_per_gas.len()];
-        // Effective priority gas price is currently 0.
-        let reward = Some(vec![
-            vec![U256::zero(); reward_percentiles.len()];
-            base_fee_per_gas.len()
-        ]);
+        let reward = if reward_percentiles.is_empty() {
+            vec![vec![]; base_fee_per_gas.len()]
+        } else {
+            let percentiles: Vec<_> = reward_percentiles.iter().map(|&p| f64::from(p)).collect();
+            connection
+                .blocks_web3_dal()
+                .get_fee_rewards(oldest_miniblock..=newest_miniblock, &percentiles)
+                .await
+                .context("get_fee_rewards")?
+        };
 
         // `base_fee_per_gas` for next miniblock cannot be calculated, appending last fee as a placeholder.
         base_fee_per_gas.push(*base_fee_per_gas.last().unwrap());
@@ -721,7 +1002,7 @@ impl EthNamespace {
             oldest_block: web3::types::BlockNumber::Number(oldest_block.into()),
             base_fee_per_gas,
             gas_used_ratio,
-            reward,
+            reward: Some(reward),
         })
     }
 
@@ -772,42 +1053,11 @@ impl EthNamespace {
             }
 
             TypedFilter::Events(filter, from_block) => {
-                let addresses = if let Some(addresses) = &filter.address {
-                    addresses.0.clone()
-                } else {
-                    vec![]
-                };
-                let topics = if let Some(topics) = &filter.topics {
-                    if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
-                        return Err(Web3Error::TooManyTopics);
-                    }
-                    let topics_by_idx = topics.iter().enumerate().filter_map(|(idx, topics)| {
-                        Some((idx as u32 + 1, topics.as_ref()?.0.clone()))
-                    });
-                    topics_by_idx.collect::<Vec<_>>()
-                } else {
-                    vec![]
-                };
-
-                let mut to_block = self
+                let mut get_logs_filter = self
                     .state
-                    .resolve_filter_block_number(filter.to_block)
+                    .resolve_get_logs_filter(filter, *from_block)
                     .await?;
-
-                if matches!(filter.to_block, Some(BlockNumber::Number(_))) {
-                    to_block = to_block.min(
-                        self.state
-                            .resolve_filter_block_number(Some(BlockNumber::Latest))
-                            .await?,
-                    );
-                }
-
-                let get_logs_filter = GetLogsFilter {
-                    from_block: *from_block,
-                    to_block,
-                    addresses,
-                    topics,
-                };
+                let to_block = get_logs_filter.to_block;
 
                 let mut storage = self
                     .state
@@ -815,9 +1065,25 @@ impl EthNamespace {
                     .access_storage_tagged("api")
                     .await?;
 
+                // Narrow down the block range using logs blooms to avoid scanning events in blocks
+                // that definitely don't contain matching logs.
+                if !get_logs_filter.addresses.is_empty() || !get_logs_filter.topics.is_empty() {
+                    let matching_range = storage
+                        .events_web3_dal()
+                        .get_bloom_matching_range(&get_logs_filter)
+                        .await
+                        .context("get_bloom_matching_range")?;
+                    let Some((matching_from_block, matching_to_block)) = matching_range else {
+                        *from_block = to_block + 1;
+                        return Ok(FilterChanges::Logs(vec![]));
+                    };
+                    get_logs_filter.from_block = matching_from_block;
+                    get_logs_filter.to_block = matching_to_block;
+                }
+
                 // Check if there is more than one block in range and there are more than `req_entities_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
-                if *from_block != to_block {
+                if get_logs_filter.from_block != get_logs_filter.to_block {
                     if let Some(miniblock_number) = storage
                         .events_web3_dal()
                         .get_log_block_number(
@@ -835,11 +1101,19 @@ impl EthNamespace {
                     }
                 }
 
+                let max_logs_result_size = self.state.api_config.max_logs_result_size;
+                // Request an extra log to check whether the result size is exceeded.
+                let limit = max_logs_result_size.map_or(i32::MAX as usize, |size| size + 1);
                 let logs = storage
                     .events_web3_dal()
-                    .get_logs(get_logs_filter, i32::MAX as usize)
+                    .get_logs(get_logs_filter, limit)
                     .await
                     .context("get_logs")?;
+                if let Some(max_size) = max_logs_result_size {
+                    if logs.len() > max_size {
+                        return Err(Web3Error::TooManyLogs(max_size));
+                    }
+                }
                 *from_block = to_block + 1;
                 FilterChanges::Logs(logs)
             }
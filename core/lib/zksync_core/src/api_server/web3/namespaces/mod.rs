@@ -1,15 +1,18 @@
 //! Actual implementation of Web3 API namespaces logic, not tied to the backend
 //! used to create a JSON RPC server.
 
+mod admin;
 mod debug;
 mod en;
 pub(crate) mod eth;
 mod net;
 mod snapshots;
+mod txpool;
 mod web3;
 mod zks;
 
 pub(super) use self::{
-    debug::DebugNamespace, en::EnNamespace, eth::EthNamespace, net::NetNamespace,
-    snapshots::SnapshotsNamespace, web3::Web3Namespace, zks::ZksNamespace,
+    admin::AdminNamespace, debug::DebugNamespace, en::EnNamespace, eth::EthNamespace,
+    net::NetNamespace, snapshots::SnapshotsNamespace, txpool::TxpoolNamespace, web3::Web3Namespace,
+    zks::ZksNamespace,
 };
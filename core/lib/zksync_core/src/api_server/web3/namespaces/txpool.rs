@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+use zksync_types::{
+    api::{Transaction, TxpoolAccountContent, TxpoolContent, TxpoolStatus},
+    Address, Bytes,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::web3::{backend_jsonrpsee::MethodTracer, state::RpcState};
+
+/// Implementation of the `txpool` namespace. Pending transactions are read from Postgres, which is the source
+/// of the state keeper mempool, so this namespace works on API servers running separately from the state keeper.
+#[derive(Debug, Clone)]
+pub(crate) struct TxpoolNamespace {
+    state: RpcState,
+}
+
+impl TxpoolNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    pub(crate) fn current_method(&self) -> &MethodTracer {
+        &self.state.current_method
+    }
+
+    /// Loads pending transactions and splits them into executable and nonce-gapped ones.
+    /// At most `req_entities_limit` transactions are loaded.
+    async fn load_content(&self) -> Result<TxpoolContent<Transaction>, Web3Error> {
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await?;
+        let limit = u32::try_from(self.state.api_config.req_entities_limit).unwrap_or(u32::MAX);
+        let transactions = storage
+            .transactions_web3_dal()
+            .get_pending_transactions(limit, self.state.api_config.l2_chain_id)
+            .await
+            .context("get_pending_transactions")?;
+
+        let mut txs_by_account = BTreeMap::<Address, Vec<_>>::new();
+        for tx in transactions {
+            // `from` is always set for transactions loaded from Postgres.
+            let initiator = tx.from.unwrap_or_default();
+            txs_by_account.entry(initiator).or_default().push(tx);
+        }
+        let accounts: Vec<_> = txs_by_account.keys().copied().collect();
+        let stored_nonces = storage
+            .storage_web3_dal()
+            .get_nonces_for_addresses(&accounts)
+            .await
+            .context("get_nonces_for_addresses")?;
+        drop(storage);
+
+        let mut content = TxpoolContent::default();
+        for (account, txs) in txs_by_account {
+            let stored_nonce = stored_nonces.get(&account).map_or(0, |nonce| nonce.0);
+            let account_content = Self::split_by_nonce(txs, stored_nonce);
+            if !account_content.pending.is_empty() {
+                content.pending.insert(account, account_content.pending);
+            }
+            if !account_content.queued.is_empty() {
+                content.queued.insert(account, account_content.queued);
+            }
+        }
+        Ok(content)
+    }
+
+    /// Splits pending transactions of a single account (ordered by nonce) into executable and nonce-gapped ones.
+    /// Transactions with nonces lower than `stored_nonce` can never be executed, so they are skipped.
+    fn split_by_nonce(
+        txs: Vec<Transaction>,
+        stored_nonce: u32,
+    ) -> TxpoolAccountContent<Transaction> {
+        let mut content = TxpoolAccountContent::default();
+        let mut next_nonce = u64::from(stored_nonce);
+        for tx in txs {
+            let nonce = tx.nonce.as_u64();
+            let group = if nonce < next_nonce {
+                continue;
+            } else if nonce == next_nonce {
+                next_nonce += 1;
+                &mut content.pending
+            } else {
+                &mut content.queued
+            };
+            group.insert(nonce, tx);
+        }
+        content
+    }
+
+    fn redact_transaction(tx: &mut Transaction) {
+        tx.input = Bytes::default();
+        tx.v = None;
+        tx.r = None;
+        tx.s = None;
+        tx.raw = None;
+    }
+
+    pub async fn content_impl(&self) -> Result<TxpoolContent<Transaction>, Web3Error> {
+        let mut content = self.load_content().await?;
+        if self.state.api_config.txpool_redact_transactions {
+            let all_txs = content
+                .pending
+                .values_mut()
+                .chain(content.queued.values_mut())
+                .flat_map(BTreeMap::values_mut);
+            all_txs.for_each(Self::redact_transaction);
+        }
+        Ok(content)
+    }
+
+    pub async fn content_from_impl(
+        &self,
+        address: Address,
+    ) -> Result<TxpoolAccountContent<Transaction>, Web3Error> {
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await?;
+        let limit = u32::try_from(self.state.api_config.req_entities_limit).unwrap_or(u32::MAX);
+        let transactions = storage
+            .transactions_web3_dal()
+            .get_pending_transactions_by_initiator(
+                address,
+                limit,
+                self.state.api_config.l2_chain_id,
+            )
+            .await
+            .context("get_pending_transactions_by_initiator")?;
+        let stored_nonce = storage
+            .storage_web3_dal()
+            .get_nonces_for_addresses(&[address])
+            .await
+            .context("get_nonces_for_addresses")?
+            .get(&address)
+            .map_or(0, |nonce| nonce.0);
+        drop(storage);
+
+        let mut content = Self::split_by_nonce(transactions, stored_nonce);
+        if self.state.api_config.txpool_redact_transactions {
+            let all_txs = content
+                .pending
+                .values_mut()
+                .chain(content.queued.values_mut());
+            all_txs.for_each(Self::redact_transaction);
+        }
+        Ok(content)
+    }
+
+    pub async fn status_impl(&self) -> Result<TxpoolStatus, Web3Error> {
+        let content = self.load_content().await?;
+        let count =
+            |txs: &BTreeMap<_, BTreeMap<_, _>>| txs.values().map(BTreeMap::len).sum::<usize>();
+        Ok(TxpoolStatus {
+            pending: count(&content.pending).into(),
+            queued: count(&content.queued).into(),
+        })
+    }
+
+    pub async fn inspect_impl(&self) -> Result<TxpoolContent<String>, Web3Error> {
+        let content = self.load_content().await?;
+        let summarize = |txs: BTreeMap<Address, BTreeMap<u64, Transaction>>| {
+            txs.into_iter()
+                .map(|(account, txs)| {
+                    let summaries = txs
+                        .into_iter()
+                        .map(|(nonce, tx)| (nonce, Self::summarize_transaction(&tx)))
+                        .collect();
+                    (account, summaries)
+                })
+                .collect()
+        };
+        Ok(TxpoolContent {
+            pending: summarize(content.pending),
+            queued: summarize(content.queued),
+        })
+    }
+
+    /// Summarizes a transaction in the same format as Geth.
+    fn summarize_transaction(tx: &Transaction) -> String {
+        let recipient = tx
+            .to
+            .map_or_else(|| "contract creation".to_owned(), |to| format!("{to:?}"));
+        let gas_price = tx.gas_price.or(tx.max_fee_per_gas).unwrap_or_default();
+        format!(
+            "{recipient}: {} wei + {} gas × {gas_price} wei",
+            tx.value, tx.gas
+        )
+    }
+}
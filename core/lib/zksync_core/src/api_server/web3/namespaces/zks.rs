@@ -1,4 +1,4 @@
-use std::{collections::HashMap, convert::TryInto};
+use std::{collections::HashMap, convert::TryInto, mem};
 
 use anyhow::Context as _;
 use zksync_dal::StorageProcessor;
@@ -6,8 +6,10 @@ use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        self, BatchCallResult, BlockDetails, BlockFinality, BlockId, BlockNumber, BridgeAddresses,
+        BundleTxResult, DebugCall, GetLogsFilter, L1BatchDetails, L1BatchFinality, L2ToL1LogProof,
+        LogCursor, Page, Proof, ProtocolVersion, ProtocolVersionDetails, StateOverride,
+        StorageProof, TransactionCursor, TransactionDetails,
     },
     fee::Fee,
     fee_model::FeeParams,
@@ -17,17 +19,20 @@ use zksync_types::{
     tokens::ETHEREUM_ADDRESS,
     transaction_request::CallRequest,
     utils::storage_key_for_standard_token_balance,
+    vm_trace::Call,
     AccountTreeId, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey, Transaction,
     L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
 };
 use zksync_utils::{address_to_h256, h256_to_u256};
 use zksync_web3_decl::{
     error::Web3Error,
-    types::{Address, Token, H256},
+    types::{Address, Filter, Token, H256},
 };
 
+use super::debug::truncate_calls;
 use crate::api_server::{
-    tree::TreeApiError,
+    execution_sandbox::StorageOverrides,
+    tx_sender::ApiCallResult,
     web3::{backend_jsonrpsee::MethodTracer, RpcState},
 };
 
@@ -295,6 +300,17 @@ impl ZksNamespace {
             return Ok(None);
         };
 
+        let precomputed_proof = storage
+            .blocks_web3_dal()
+            .get_l2_to_l1_log_proof(l1_batch_number, l1_log_index as u32)
+            .await
+            .context("get_l2_to_l1_log_proof")?;
+        if let Some(proof) = precomputed_proof {
+            return Ok(Some(proof));
+        }
+        // Proofs are computed by the commitment generator; fall back to computing the proof on the fly
+        // for L1 batches not processed by it yet.
+
         let Some(batch) = storage
             .blocks_dal()
             .get_l1_batch_header(l1_batch_number)
@@ -388,6 +404,51 @@ impl ZksNamespace {
             .context("get_block_details")?)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_block_details_page_impl(
+        &self,
+        before: Option<MiniblockNumber>,
+        limit: Option<u32>,
+    ) -> Result<Page<BlockDetails, MiniblockNumber>, Web3Error> {
+        let limit = self.page_limit(limit);
+        if limit == 0 {
+            return Ok(Page {
+                items: vec![],
+                next_cursor: before,
+            });
+        }
+        if let Some(before) = before {
+            self.state.start_info.ensure_not_pruned(before)?;
+        }
+
+        let mut storage = self.access_storage().await?;
+        Ok(storage
+            .blocks_web3_dal()
+            .get_block_details_page(before, limit)
+            .await
+            .context("get_block_details_page")?)
+    }
+
+    /// Returns the page size for paginated methods, capped by `req_entities_limit`.
+    fn page_limit(&self, limit: Option<u32>) -> usize {
+        let max_limit = self.state.api_config.req_entities_limit;
+        limit.map_or(max_limit, |limit| (limit as usize).min(max_limit))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_block_finality_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<BlockFinality>, Web3Error> {
+        self.state.start_info.ensure_not_pruned(block_number)?;
+        let mut storage = self.access_storage().await?;
+        Ok(storage
+            .blocks_web3_dal()
+            .get_miniblock_finality(block_number)
+            .await
+            .context("get_miniblock_finality")?)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_raw_block_transactions_impl(
         &self,
@@ -402,6 +463,131 @@ impl ZksNamespace {
             .context("get_raw_miniblock_transactions")?)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transactions_by_initiator_impl(
+        &self,
+        initiator: Address,
+        cursor: Option<TransactionCursor>,
+        limit: Option<u32>,
+    ) -> Result<Page<api::Transaction, TransactionCursor>, Web3Error> {
+        let max_limit = u32::try_from(self.state.api_config.req_entities_limit).unwrap_or(u32::MAX);
+        let limit = limit.map_or(max_limit, |limit| limit.min(max_limit));
+        if limit == 0 {
+            return Ok(Page {
+                items: vec![],
+                next_cursor: cursor,
+            });
+        }
+        if let Some(cursor) = &cursor {
+            self.state
+                .start_info
+                .ensure_not_pruned(cursor.block_number)?;
+        }
+
+        let mut storage = self.access_storage().await?;
+        let transactions = storage
+            .transactions_web3_dal()
+            .get_transactions_by_initiator(
+                initiator,
+                cursor,
+                limit,
+                self.state.api_config.l2_chain_id,
+            )
+            .await
+            .context("get_transactions_by_initiator")?;
+
+        let next_cursor = if transactions.len() == limit as usize {
+            let last_tx = transactions.last().unwrap();
+            // Both fields are always set for transactions included into miniblocks.
+            Some(TransactionCursor {
+                block_number: MiniblockNumber(last_tx.block_number.unwrap().as_u32()),
+                index_in_block: last_tx.transaction_index.unwrap().as_u32(),
+            })
+        } else {
+            None
+        };
+        Ok(Page {
+            items: transactions,
+            next_cursor,
+        })
+    }
+
+    /// Unlike `eth_getLogs`, doesn't reject filters matching too many logs. Instead, returns the first page of logs
+    /// together with a cursor that can be passed to get the following page.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_logs_paginated_impl(
+        &self,
+        mut filter: Filter,
+        cursor: Option<LogCursor>,
+    ) -> Result<Page<api::Log, LogCursor>, Web3Error> {
+        self.state.resolve_filter_block_hash(&mut filter).await?;
+        let from_block = self
+            .state
+            .resolve_filter_block_number(filter.from_block)
+            .await?;
+        let mut get_logs_filter = self
+            .state
+            .resolve_get_logs_filter(&filter, from_block)
+            .await?;
+        if let Some(cursor) = &cursor {
+            self.state
+                .start_info
+                .ensure_not_pruned(cursor.block_number)?;
+            // Logs preceding the cursor are skipped anyway, so we can narrow the block range.
+            get_logs_filter.from_block = get_logs_filter.from_block.max(cursor.block_number);
+        }
+
+        let empty_page = Page {
+            items: vec![],
+            next_cursor: None,
+        };
+        if get_logs_filter.from_block > get_logs_filter.to_block {
+            return Ok(empty_page);
+        }
+
+        let mut storage = self.access_storage().await?;
+        if !get_logs_filter.addresses.is_empty() || !get_logs_filter.topics.is_empty() {
+            let matching_range = storage
+                .events_web3_dal()
+                .get_bloom_matching_range(&get_logs_filter)
+                .await
+                .context("get_bloom_matching_range")?;
+            let Some((matching_from_block, matching_to_block)) = matching_range else {
+                return Ok(empty_page);
+            };
+            get_logs_filter.from_block = matching_from_block;
+            get_logs_filter.to_block = matching_to_block;
+        }
+
+        let api_config = &self.state.api_config;
+        let page_size = api_config
+            .req_entities_limit
+            .min(api_config.max_logs_result_size.unwrap_or(usize::MAX))
+            .max(1);
+        // Request an extra log to check whether there are more logs after the page.
+        let mut logs = storage
+            .events_web3_dal()
+            .get_logs_page(&get_logs_filter, cursor, page_size + 1)
+            .await
+            .context("get_logs_page")?;
+
+        let next_cursor = if logs.len() > page_size {
+            logs.truncate(page_size);
+            let last_log = logs.last().unwrap();
+            // Both fields are always set for logs included into miniblocks.
+            Some(LogCursor {
+                block_number: MiniblockNumber(last_log.block_number.unwrap().as_u32()),
+                log_index: last_log.log_index.unwrap().as_u32(),
+            })
+        } else {
+            None
+        };
+        Ok(Page {
+            items: logs,
+            next_cursor,
+        })
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_transaction_details_impl(
         &self,
@@ -435,6 +621,45 @@ impl ZksNamespace {
             .context("get_l1_batch_details")?)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_l1_batch_details_page_impl(
+        &self,
+        before: Option<L1BatchNumber>,
+        limit: Option<u32>,
+    ) -> Result<Page<L1BatchDetails, L1BatchNumber>, Web3Error> {
+        let limit = self.page_limit(limit);
+        if limit == 0 {
+            return Ok(Page {
+                items: vec![],
+                next_cursor: before,
+            });
+        }
+        if let Some(before) = before {
+            self.state.start_info.ensure_not_pruned(before)?;
+        }
+
+        let mut storage = self.access_storage().await?;
+        Ok(storage
+            .blocks_web3_dal()
+            .get_l1_batch_details_page(before, limit)
+            .await
+            .context("get_l1_batch_details_page")?)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_l1_batch_finality_impl(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchFinality>, Web3Error> {
+        self.state.start_info.ensure_not_pruned(batch_number)?;
+        let mut storage = self.access_storage().await?;
+        Ok(storage
+            .blocks_web3_dal()
+            .get_l1_batch_finality(batch_number)
+            .await
+            .context("get_l1_batch_finality")?)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_bytecode_by_hash_impl(
         &self,
@@ -493,6 +718,18 @@ impl ZksNamespace {
         Ok(protocol_version)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_protocol_versions_impl(
+        &self,
+    ) -> Result<Vec<ProtocolVersionDetails>, Web3Error> {
+        let mut storage = self.access_storage().await?;
+        Ok(storage
+            .protocol_versions_web3_dal()
+            .get_protocol_versions()
+            .await
+            .context("get_protocol_versions")?)
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn get_proofs_impl(
         &self,
@@ -500,30 +737,16 @@ impl ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> Result<Option<Proof>, Web3Error> {
-        self.state.start_info.ensure_not_pruned(l1_batch_number)?;
         let hashed_keys = keys
             .iter()
             .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
             .collect();
-        let tree_api = self
+        let Some(proofs) = self
             .state
-            .tree_api
-            .as_deref()
-            .ok_or(Web3Error::TreeApiUnavailable)?;
-        let proofs_result = tree_api.get_proofs(l1_batch_number, hashed_keys).await;
-        let proofs = match proofs_result {
-            Ok(proofs) => proofs,
-            Err(TreeApiError::NotReady) => return Err(Web3Error::TreeApiUnavailable),
-            Err(TreeApiError::NoVersion(err)) => {
-                return if err.missing_version > err.version_count {
-                    Ok(None)
-                } else {
-                    Err(Web3Error::InternalError(anyhow::anyhow!(
-                        "L1 batch #{l1_batch_number} is pruned in Merkle tree, but not in Postgres"
-                    )))
-                };
-            }
-            Err(TreeApiError::Internal(err)) => return Err(Web3Error::InternalError(err)),
+            .get_tree_proofs(l1_batch_number, hashed_keys)
+            .await?
+        else {
+            return Ok(None);
         };
 
         let storage_proof = proofs
@@ -542,4 +765,172 @@ impl ZksNamespace {
             storage_proof,
         }))
     }
+
+    /// Executes independent calls on the same block. Unlike with a batch of `eth_call` requests, calls are executed
+    /// in a single sandbox session sharing storage reads. The number of calls is limited by `req_entities_limit`.
+    #[tracing::instrument(skip(self, requests))]
+    pub async fn batch_call_impl(
+        &self,
+        requests: Vec<CallRequest>,
+        block_id: Option<BlockId>,
+    ) -> Result<Vec<BatchCallResult>, Web3Error> {
+        let max_calls = self.state.api_config.req_entities_limit;
+        if requests.len() > max_calls {
+            return Err(Web3Error::TooManyCalls(max_calls));
+        }
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        self.current_method().set_block_id(block_id);
+
+        let mut storage = self.access_storage().await?;
+        let block_args = self
+            .state
+            .resolve_block_args(&mut storage, block_id)
+            .await?;
+        self.current_method().set_block_diff(
+            self.state
+                .last_sealed_miniblock
+                .diff_with_block_args(&block_args),
+        );
+        drop(storage);
+
+        let txs = requests
+            .into_iter()
+            .map(|request| L2Tx::from_request(request.into(), self.state.api_config.max_tx_size))
+            .collect::<Result<Vec<_>, _>>()?;
+        let results = self.state.tx_sender.eth_call_batch(block_args, txs).await?;
+        Ok(results
+            .into_iter()
+            .map(|result| match result {
+                Ok(output) => BatchCallResult {
+                    success: true,
+                    output: output.into(),
+                    error: None,
+                },
+                Err(err) => {
+                    let (message, data) = match Web3Error::from(err) {
+                        Web3Error::SubmitTransactionError(message, data) => (message, data),
+                        err => (err.to_string(), vec![]),
+                    };
+                    BatchCallResult {
+                        success: false,
+                        output: data.into(),
+                        error: Some(message),
+                    }
+                }
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self, requests, state_override))]
+    pub async fn simulate_bundle_impl(
+        &self,
+        requests: Vec<CallRequest>,
+        block_id: Option<BlockId>,
+        state_override: Option<StateOverride>,
+    ) -> Result<Vec<BundleTxResult>, Web3Error> {
+        let max_txs = self.state.api_config.req_entities_limit;
+        if requests.len() > max_txs {
+            return Err(Web3Error::TooManyCalls(max_txs));
+        }
+        let storage_overrides = state_override
+            .map(|state_override| StorageOverrides::new(&state_override))
+            .transpose()
+            .map_err(|err| Web3Error::InvalidStateOverride(err.to_string()))?
+            .unwrap_or_default();
+
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        self.current_method().set_block_id(block_id);
+        let mut storage = self.access_storage().await?;
+        let block_args = self
+            .state
+            .resolve_block_args(&mut storage, block_id)
+            .await?;
+        self.current_method().set_block_diff(
+            self.state
+                .last_sealed_miniblock
+                .diff_with_block_args(&block_args),
+        );
+        drop(storage);
+
+        let txs = requests
+            .into_iter()
+            .map(|request| L2Tx::from_request(request.into(), self.state.api_config.max_tx_size))
+            .collect::<Result<Vec<_>, _>>()?;
+        let call_inputs: Vec<_> = txs
+            .iter()
+            .map(|tx| {
+                let gas_limit = tx.common_data.fee.gas_limit.as_u32();
+                (gas_limit, tx.execute.value, tx.execute.calldata.clone())
+            })
+            .collect();
+        let results = self
+            .state
+            .tx_sender
+            .simulate_bundle(block_args, txs, storage_overrides)
+            .await?;
+
+        let max_call_depth = self.state.api_config.trace_max_call_depth;
+        Ok(results
+            .into_iter()
+            .zip(call_inputs)
+            .enumerate()
+            .map(
+                |(tx_index, ((mut result, calls), (gas_limit, value, calldata)))| {
+                    let logs = mem::take(&mut result.logs.events)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(log_index, event)| api::Log {
+                            address: event.address,
+                            topics: event.indexed_topics,
+                            data: event.value.into(),
+                            block_hash: None,
+                            block_number: None,
+                            l1_batch_number: None,
+                            transaction_hash: None,
+                            transaction_index: Some((tx_index as u32).into()),
+                            log_index: None,
+                            transaction_log_index: Some((log_index as u32).into()),
+                            log_type: None,
+                            removed: None,
+                        })
+                        .collect();
+                    let gas_used = result.statistics.gas_used;
+                    let (success, output, error) = match result.into_api_call_result() {
+                        Ok(output) => (true, output, None),
+                        Err(err) => match Web3Error::from(err) {
+                            Web3Error::SubmitTransactionError(message, data) => {
+                                (false, data, Some(message))
+                            }
+                            err => (false, vec![], Some(err.to_string())),
+                        },
+                    };
+
+                    let revert_reason = if success { None } else { error.clone() };
+                    let trace_output = if success { output.clone() } else { vec![] };
+                    let call = Call::new_high_level(
+                        gas_limit,
+                        gas_used,
+                        value,
+                        calldata,
+                        trace_output,
+                        revert_reason,
+                        calls,
+                    );
+                    let mut trace = DebugCall::from(call);
+                    if let Some(max_depth) = max_call_depth {
+                        truncate_calls(&mut trace, max_depth);
+                    }
+
+                    BundleTxResult {
+                        success,
+                        output: output.into(),
+                        error,
+                        gas_used: gas_used.into(),
+                        logs,
+                        trace,
+                    }
+                },
+            )
+            .collect())
+    }
 }
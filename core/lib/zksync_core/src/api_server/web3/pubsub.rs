@@ -1,33 +1,50 @@
 //! (Largely) backend-agnostic logic for dealing with Web3 subscriptions.
 
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
 use anyhow::Context as _;
 use futures::FutureExt;
 use tokio::{
     sync::{broadcast, mpsc, watch},
     task::JoinHandle,
-    time::{interval, Duration},
+    time::{interval, Duration, Interval},
+};
+use zksync_dal::{
+    blocks_dal::{BlocksDal, SealedMiniblockListener},
+    ConnectionPool, StorageProcessor,
+};
+use zksync_system_constants::EMPTY_UNCLES_HASH;
+use zksync_types::{
+    api, block::MiniblockHeader, Address, Bytes, L1BatchNumber, L2ChainId, MiniblockNumber, H128,
+    H2048, H256, U256,
 };
-use zksync_dal::ConnectionPool;
-use zksync_types::{MiniblockNumber, H128, H256};
 use zksync_web3_decl::{
+    error::Web3Error,
     jsonrpsee::{
         core::{server::SubscriptionMessage, SubscriptionResult},
         server::IdProvider,
         types::{error::ErrorCode, ErrorObject, SubscriptionId},
         PendingSubscriptionSink, SendTimeoutError, SubscriptionSink,
     },
-    namespaces::EthPubSubServer,
-    types::{BlockHeader, Log, PubSubFilter, PubSubResult},
+    namespaces::{EthPubSubServer, ZksPubSubServer},
+    types::{BlockHeader, Filter, Log, PubSubFilter, PubSubResult},
 };
 
 use super::{
     metrics::{SubscriptionType, PUB_SUB_METRICS},
     namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT,
+    state::RpcState,
 };
 use crate::api_server::execution_sandbox::BlockStartInfo;
 
 const BROADCAST_CHANNEL_CAPACITY: usize = 1024;
 const SUBSCRIPTION_SINK_SEND_TIMEOUT: Duration = Duration::from_secs(1);
+/// Factor by which the polling interval is increased if notifiers are woken up by DB notifications.
+/// Polling is still necessary since DB notifications may be lost.
+const DB_NOTIFICATIONS_POLLING_FACTOR: u32 = 10;
 
 #[derive(Debug, Clone, Copy)]
 pub struct EthSubscriptionIdProvider;
@@ -47,12 +64,246 @@ pub(super) enum PubSubEvent {
     MiniblockAdvanced(SubscriptionType, MiniblockNumber),
 }
 
+/// Waits until new miniblocks may be available in the DB, either by polling or by listening
+/// to DB notifications about sealed miniblocks.
+#[derive(Debug)]
+struct MiniblockWaiter {
+    timer: Interval,
+    polling_interval: Duration,
+    listener: Option<SealedMiniblockListener>,
+}
+
+impl MiniblockWaiter {
+    async fn new(polling_interval: Duration, notifications_pool: Option<&ConnectionPool>) -> Self {
+        let listener = if let Some(pool) = notifications_pool {
+            match SealedMiniblockListener::new(pool).await {
+                Ok(listener) => Some(listener),
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed creating sealed miniblock listener, falling back to polling: {err}"
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let timer_interval = if listener.is_some() {
+            polling_interval * DB_NOTIFICATIONS_POLLING_FACTOR
+        } else {
+            polling_interval
+        };
+        Self {
+            timer: interval(timer_interval),
+            polling_interval,
+            listener,
+        }
+    }
+
+    /// Waits until new miniblocks may be available in `pool`.
+    ///
+    /// Notifications are sent by the master DB, while `pool` may be connected to a replica lagging behind it.
+    /// Thus, after receiving a notification, the waiter polls `pool` until the notified miniblock is visible
+    /// (or until the next fallback polling tick), so that notifiers don't miss the miniblock until the next tick.
+    async fn wait(&mut self, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let Some(listener) = &mut self.listener else {
+            self.timer.tick().await;
+            return Ok(());
+        };
+
+        let sealed_miniblock = tokio::select! {
+            _ = self.timer.tick() => return Ok(()), // Poll the DB as a fallback
+            res = listener.recv() => match res {
+                Ok(number) => number,
+                Err(err) => {
+                    tracing::warn!(
+                        "Sealed miniblock listener failed, falling back to polling: {err}"
+                    );
+                    self.listener = None;
+                    self.timer = interval(self.polling_interval);
+                    return Ok(());
+                }
+            }
+        };
+        self.wait_for_miniblock(pool, sealed_miniblock).await
+    }
+
+    async fn wait_for_miniblock(
+        &mut self,
+        pool: &ConnectionPool,
+        number: MiniblockNumber,
+    ) -> anyhow::Result<()> {
+        loop {
+            let mut storage = pool
+                .access_storage_tagged("api")
+                .await
+                .context("access_storage_tagged")?;
+            let sealed_miniblock_number = storage
+                .blocks_dal()
+                .get_sealed_miniblock_number()
+                .await
+                .context("get_sealed_miniblock_number()")?;
+            drop(storage);
+            if sealed_miniblock_number >= Some(number) {
+                return Ok(());
+            }
+
+            tracing::debug!(
+                "Notified about sealed miniblock #{number}, but the last visible miniblock is \
+                 {sealed_miniblock_number:?}; re-polling in {:?}",
+                self.polling_interval
+            );
+            tokio::select! {
+                _ = self.timer.tick() => return Ok(()),
+                () = tokio::time::sleep(self.polling_interval) => { /* Re-poll the DB */ }
+            }
+        }
+    }
+}
+
+/// Log subscription filter with addresses and topics collected into hash sets, so that matching
+/// is efficient even for filters with many alternatives.
+#[derive(Debug, Default)]
+struct LogsFilter {
+    addresses: Option<HashSet<Address>>,
+    /// Allowed topics for each topic position; `None` allows any topic at the position.
+    topics: Vec<Option<HashSet<H256>>>,
+    /// Logs from earlier miniblocks are skipped. Used by log streams to skip logs already sent as historical ones.
+    min_block_number: MiniblockNumber,
+}
+
+impl LogsFilter {
+    /// Returns `None` if the filter specifies more topic positions than a log may have.
+    fn new(filter: PubSubFilter) -> Option<Self> {
+        let topics = filter.topics.unwrap_or_default();
+        if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
+            return None;
+        }
+        Some(Self {
+            addresses: filter
+                .address
+                .map(|addresses| addresses.0.into_iter().collect()),
+            topics: topics
+                .into_iter()
+                .map(|topics| topics.map(|topics| topics.0.into_iter().collect()))
+                .collect(),
+            min_block_number: MiniblockNumber(0),
+        })
+    }
+
+    fn matches(&self, log: &Log) -> bool {
+        if let Some(block_number) = log.block_number {
+            if block_number.as_u32() < self.min_block_number.0 {
+                return false;
+            }
+        }
+        if let Some(addresses) = &self.addresses {
+            if !addresses.contains(&log.address) {
+                return false;
+            }
+        }
+        self.topics
+            .iter()
+            .enumerate()
+            .all(|(idx, expected_topics)| match expected_topics {
+                Some(expected_topics) => log
+                    .topics
+                    .get(idx)
+                    .map_or(false, |topic| expected_topics.contains(topic)),
+                None => true,
+            })
+    }
+}
+
+/// Stage of the L1 batch lifecycle notified by the `zks_subscribe` subscription.
+#[derive(Debug, Clone, Copy)]
+enum L1BatchStage {
+    Sealed,
+    Verified,
+    Executed,
+}
+
+impl L1BatchStage {
+    const ALL: [Self; 3] = [Self::Sealed, Self::Verified, Self::Executed];
+
+    fn from_topic(topic: &str) -> Option<Self> {
+        Some(match topic {
+            "l1BatchSealed" => Self::Sealed,
+            "l1BatchVerified" => Self::Verified,
+            "l1BatchExecuted" => Self::Executed,
+            _ => return None,
+        })
+    }
+
+    fn subscription_type(self) -> SubscriptionType {
+        match self {
+            Self::Sealed => SubscriptionType::L1BatchSealed,
+            Self::Verified => SubscriptionType::L1BatchVerified,
+            Self::Executed => SubscriptionType::L1BatchExecuted,
+        }
+    }
+
+    /// Returns the number of the first L1 batch that can reach this stage. The genesis L1 batch is never
+    /// proven or executed on L1.
+    fn first_l1_batch_number(self) -> L1BatchNumber {
+        match self {
+            Self::Sealed => L1BatchNumber(0),
+            Self::Verified | Self::Executed => L1BatchNumber(1),
+        }
+    }
+
+    /// Returns the number of the last L1 batch that has reached this stage. Verification and execution
+    /// are tracked using L1 transactions confirmed by `eth_sender`.
+    async fn last_l1_batch_number(
+        self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let mut blocks_dal = storage.blocks_dal();
+        match self {
+            Self::Sealed => blocks_dal.get_sealed_l1_batch_number().await,
+            Self::Verified => blocks_dal.get_number_of_last_l1_batch_proven_on_eth().await,
+            Self::Executed => {
+                blocks_dal
+                    .get_number_of_last_l1_batch_executed_on_eth()
+                    .await
+            }
+        }
+    }
+}
+
+/// Broadcast channels for L1 batch notifications, one per [`L1BatchStage`].
+#[derive(Debug, Clone)]
+struct L1BatchSenders {
+    sealed: broadcast::Sender<Arc<[PubSubResult]>>,
+    verified: broadcast::Sender<Arc<[PubSubResult]>>,
+    executed: broadcast::Sender<Arc<[PubSubResult]>>,
+}
+
+impl L1BatchSenders {
+    fn new() -> Self {
+        Self {
+            sealed: broadcast::channel(BROADCAST_CHANNEL_CAPACITY).0,
+            verified: broadcast::channel(BROADCAST_CHANNEL_CAPACITY).0,
+            executed: broadcast::channel(BROADCAST_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    fn get(&self, stage: L1BatchStage) -> &broadcast::Sender<Arc<[PubSubResult]>> {
+        match stage {
+            L1BatchStage::Sealed => &self.sealed,
+            L1BatchStage::Verified => &self.verified,
+            L1BatchStage::Executed => &self.executed,
+        }
+    }
+}
+
 /// Manager of notifications for a certain type of subscriptions.
 #[derive(Debug)]
 struct PubSubNotifier {
-    sender: broadcast::Sender<Vec<PubSubResult>>,
+    sender: broadcast::Sender<Arc<[PubSubResult]>>,
     connection_pool: ConnectionPool,
     polling_interval: Duration,
+    notifications_pool: Option<ConnectionPool>,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -88,17 +339,24 @@ impl PubSubNotifier {
 impl PubSubNotifier {
     async fn notify_blocks(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let mut last_block_number = self.get_starting_miniblock_number().await?;
-        let mut timer = interval(self.polling_interval);
+        let mut waiter =
+            MiniblockWaiter::new(self.polling_interval, self.notifications_pool.as_ref()).await;
+        let mut has_more_blocks = false;
         loop {
             if *stop_receiver.borrow() {
                 tracing::info!("Stop signal received, pubsub_block_notifier is shutting down");
                 break;
             }
-            timer.tick().await;
+            if !has_more_blocks {
+                waiter.wait(&self.connection_pool).await?;
+            }
 
             let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::Blocks].start();
             let new_blocks = self.new_blocks(last_block_number).await?;
             db_latency.observe();
+            // New blocks are loaded in bounded chunks; if the chunk is full, load the next one right away.
+            has_more_blocks =
+                new_blocks.len() >= BlocksDal::MAX_MINIBLOCK_HEADERS_IN_RANGE as usize;
 
             if let Some(last_block) = new_blocks.last() {
                 last_block_number = MiniblockNumber(last_block.number.unwrap().as_u32());
@@ -118,7 +376,7 @@ impl PubSubNotifier {
 
     fn send_pub_sub_results(&self, results: Vec<PubSubResult>, sub_type: SubscriptionType) {
         // Errors only on 0 receivers, but we want to go on if we have 0 subscribers so ignore the error.
-        self.sender.send(results).ok();
+        self.sender.send(results.into()).ok();
         PUB_SUB_METRICS.broadcast_channel_len[&sub_type].set(self.sender.len());
     }
 
@@ -126,17 +384,67 @@ impl PubSubNotifier {
         &self,
         last_block_number: MiniblockNumber,
     ) -> anyhow::Result<Vec<BlockHeader>> {
-        self.connection_pool
+        let numbers = (last_block_number + 1)
+            ..=(last_block_number + BlocksDal::MAX_MINIBLOCK_HEADERS_IN_RANGE);
+        let mut storage = self
+            .connection_pool
             .access_storage_tagged("api")
             .await
-            .context("access_storage_tagged")?
+            .context("access_storage_tagged")?;
+        let headers = storage
+            .blocks_dal()
+            .get_miniblock_headers_range(numbers.clone())
+            .await
+            .with_context(|| format!("get_miniblock_headers_range({numbers:?})"))?;
+        let Some(last_header) = headers.last() else {
+            return Ok(vec![]);
+        };
+        let bloom_numbers = *numbers.start()..=last_header.number;
+        let logs_blooms = storage
             .blocks_web3_dal()
-            .get_block_headers_after(last_block_number)
+            .get_logs_blooms(bloom_numbers.clone())
             .await
-            .with_context(|| format!("get_block_headers_after({last_block_number})"))
+            .with_context(|| format!("get_logs_blooms({bloom_numbers:?})"))?;
+
+        Ok(headers
+            .iter()
+            .map(|header| {
+                let logs_bloom = logs_blooms.get(&header.number).copied();
+                Self::block_header(header, logs_bloom.unwrap_or_default())
+            })
+            .collect())
     }
 
-    async fn notify_txs(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+    fn block_header(header: &MiniblockHeader, logs_bloom: H2048) -> BlockHeader {
+        BlockHeader {
+            hash: Some(header.hash),
+            parent_hash: H256::zero(),
+            uncles_hash: EMPTY_UNCLES_HASH,
+            author: Address::zero(),
+            state_root: H256::zero(),
+            transactions_root: H256::zero(),
+            receipts_root: H256::zero(),
+            number: Some(header.number.0.into()),
+            gas_used: U256::zero(),
+            gas_limit: U256::zero(),
+            base_fee_per_gas: None,
+            extra_data: Bytes::default(),
+            logs_bloom,
+            timestamp: header.timestamp.into(),
+            difficulty: U256::zero(),
+            mix_hash: None,
+            nonce: None,
+        }
+    }
+
+    /// Besides broadcasting hashes of new transactions, sends full transactions to `full_txs_sender`.
+    /// Full transactions are only loaded if there are subscribers for them.
+    async fn notify_txs(
+        self,
+        full_txs_sender: broadcast::Sender<Arc<[PubSubResult]>>,
+        l2_chain_id: L2ChainId,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
         let mut last_time = chrono::Utc::now().naive_utc();
         let mut timer = interval(self.polling_interval);
         loop {
@@ -147,12 +455,29 @@ impl PubSubNotifier {
             timer.tick().await;
 
             let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::Txs].start();
-            let (new_txs, new_last_time) = self.new_txs(last_time).await?;
+            let (new_tx_hashes, new_last_time) = self.new_txs(last_time).await?;
+            let has_full_txs_subscribers = full_txs_sender.receiver_count() > 0;
+            let new_full_txs = if has_full_txs_subscribers && !new_tx_hashes.is_empty() {
+                self.new_full_txs(&new_tx_hashes, l2_chain_id).await?
+            } else {
+                vec![]
+            };
             db_latency.observe();
 
             if let Some(new_last_time) = new_last_time {
                 last_time = new_last_time;
-                let new_txs = new_txs.into_iter().map(PubSubResult::TxHash).collect();
+                if !new_full_txs.is_empty() {
+                    let new_full_txs: Vec<_> = new_full_txs
+                        .into_iter()
+                        .map(PubSubResult::Transaction)
+                        .collect();
+                    // Errors only on 0 receivers, which is fine.
+                    full_txs_sender.send(new_full_txs.into()).ok();
+                }
+                let new_txs = new_tx_hashes
+                    .into_iter()
+                    .map(PubSubResult::TxHash)
+                    .collect();
                 self.send_pub_sub_results(new_txs, SubscriptionType::Txs);
             }
             self.emit_event(PubSubEvent::NotifyIterationFinished(SubscriptionType::Txs));
@@ -174,16 +499,41 @@ impl PubSubNotifier {
             .context("get_pending_txs_hashes_after()")
     }
 
+    async fn new_full_txs(
+        &self,
+        hashes: &[H256],
+        l2_chain_id: L2ChainId,
+    ) -> anyhow::Result<Vec<api::Transaction>> {
+        let mut txs = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?
+            .transactions_web3_dal()
+            .get_transactions(hashes, l2_chain_id)
+            .await
+            .context("get_transactions()")?;
+        // `get_transactions()` doesn't preserve the order of hashes; restore it so that transactions
+        // are notified in the order they were received.
+        let positions: HashMap<_, _> = hashes
+            .iter()
+            .enumerate()
+            .map(|(i, hash)| (*hash, i))
+            .collect();
+        txs.sort_unstable_by_key(|tx| positions.get(&tx.hash).copied());
+        Ok(txs)
+    }
+
     async fn notify_logs(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let mut last_block_number = self.get_starting_miniblock_number().await?;
-
-        let mut timer = interval(self.polling_interval);
+        let mut waiter =
+            MiniblockWaiter::new(self.polling_interval, self.notifications_pool.as_ref()).await;
         loop {
             if *stop_receiver.borrow() {
                 tracing::info!("Stop signal received, pubsub_logs_notifier is shutting down");
                 break;
             }
-            timer.tick().await;
+            waiter.wait(&self.connection_pool).await?;
 
             let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::Logs].start();
             let new_logs = self.new_logs(last_block_number).await?;
@@ -213,13 +563,90 @@ impl PubSubNotifier {
             .await
             .context("events_web3_dal().get_all_logs()")
     }
+
+    /// Notifies about L1 batches reaching `stage`. Only L1 batches reaching the stage after the notifier
+    /// has started are notified.
+    async fn notify_l1_batches(
+        self,
+        stage: L1BatchStage,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let sub_type = stage.subscription_type();
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let mut last_l1_batch_number = stage
+            .last_l1_batch_number(&mut storage)
+            .await
+            .with_context(|| format!("failed getting last L1 batch number for {stage:?}"))?;
+        drop(storage);
+
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!(
+                    "Stop signal received, pubsub_l1_batch_notifier for {stage:?} is shutting down"
+                );
+                break;
+            }
+            timer.tick().await;
+
+            let db_latency = PUB_SUB_METRICS.db_poll_latency[&sub_type].start();
+            let new_l1_batches = self.new_l1_batches(stage, last_l1_batch_number).await?;
+            db_latency.observe();
+
+            if let Some(last_l1_batch) = new_l1_batches.last() {
+                last_l1_batch_number = Some(last_l1_batch.number);
+                let new_l1_batches = new_l1_batches
+                    .into_iter()
+                    .map(PubSubResult::L1Batch)
+                    .collect();
+                self.send_pub_sub_results(new_l1_batches, sub_type);
+            }
+            self.emit_event(PubSubEvent::NotifyIterationFinished(sub_type));
+        }
+        Ok(())
+    }
+
+    async fn new_l1_batches(
+        &self,
+        stage: L1BatchStage,
+        last_l1_batch_number: Option<L1BatchNumber>,
+    ) -> anyhow::Result<Vec<api::L1BatchDetails>> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let current_l1_batch_number = stage
+            .last_l1_batch_number(&mut storage)
+            .await
+            .with_context(|| format!("failed getting last L1 batch number for {stage:?}"))?;
+        let Some(current_l1_batch_number) = current_l1_batch_number else {
+            return Ok(vec![]);
+        };
+        let next_l1_batch_number =
+            last_l1_batch_number.map_or(stage.first_l1_batch_number(), |number| number + 1);
+        if next_l1_batch_number > current_l1_batch_number {
+            return Ok(vec![]);
+        }
+        storage
+            .blocks_web3_dal()
+            .get_l1_batch_details_range(next_l1_batch_number..=current_l1_batch_number)
+            .await
+            .context("get_l1_batch_details_range()")
+    }
 }
 
 /// Subscription support for Web3 APIs.
 pub(super) struct EthSubscribe {
-    blocks: broadcast::Sender<Vec<PubSubResult>>,
-    transactions: broadcast::Sender<Vec<PubSubResult>>,
-    logs: broadcast::Sender<Vec<PubSubResult>>,
+    blocks: broadcast::Sender<Arc<[PubSubResult]>>,
+    transactions: broadcast::Sender<Arc<[PubSubResult]>>,
+    full_transactions: broadcast::Sender<Arc<[PubSubResult]>>,
+    logs: broadcast::Sender<Arc<[PubSubResult]>>,
+    l1_batches: L1BatchSenders,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -227,12 +654,15 @@ impl EthSubscribe {
     pub fn new() -> Self {
         let (blocks, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (transactions, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (full_transactions, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (logs, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
 
         Self {
             blocks,
             transactions,
+            full_transactions,
             logs,
+            l1_batches: L1BatchSenders::new(),
             events_sender: None,
         }
     }
@@ -241,6 +671,16 @@ impl EthSubscribe {
         self.events_sender = Some(sender);
     }
 
+    /// Creates a server for `zks` subscriptions sharing notifications with this instance.
+    pub fn zks_subscribe(&self, state: RpcState) -> ZksSubscribe {
+        ZksSubscribe {
+            logs: self.logs.clone(),
+            l1_batches: self.l1_batches.clone(),
+            state,
+            events_sender: self.events_sender.clone(),
+        }
+    }
+
     async fn reject(sink: PendingSubscriptionSink) {
         sink.reject(ErrorObject::borrowed(
             ErrorCode::InvalidParams.code(),
@@ -253,8 +693,8 @@ impl EthSubscribe {
     async fn run_subscriber(
         sink: SubscriptionSink,
         subscription_type: SubscriptionType,
-        mut receiver: broadcast::Receiver<Vec<PubSubResult>>,
-        filter: Option<PubSubFilter>,
+        mut receiver: broadcast::Receiver<Arc<[PubSubResult]>>,
+        filter: Option<LogsFilter>,
     ) {
         let _guard = PUB_SUB_METRICS.active_subscribers[&subscription_type].inc_guard(1);
         let lifetime_latency = PUB_SUB_METRICS.subscriber_lifetime[&subscription_type].start();
@@ -272,6 +712,8 @@ impl EthSubscribe {
                             break;
                         }
                         Err(broadcast::error::RecvError::Lagged(message_count)) => {
+                            // The subscriber cannot keep up with notifications; drop it rather than
+                            // buffering notifications indefinitely.
                             PUB_SUB_METRICS
                                 .skipped_broadcast_messages[&subscription_type]
                                 .observe(message_count);
@@ -282,7 +724,7 @@ impl EthSubscribe {
                     let handle_result = Self::handle_new_items(
                         &sink,
                         subscription_type,
-                        new_items,
+                        &new_items,
                         filter.as_ref()
                     )
                     .await;
@@ -302,12 +744,12 @@ impl EthSubscribe {
     async fn handle_new_items(
         sink: &SubscriptionSink,
         subscription_type: SubscriptionType,
-        new_items: Vec<PubSubResult>,
-        filter: Option<&PubSubFilter>,
+        new_items: &[PubSubResult],
+        filter: Option<&LogsFilter>,
     ) -> Result<(), SendTimeoutError> {
         let notify_latency = PUB_SUB_METRICS.notify_subscribers_latency[&subscription_type].start();
         for item in new_items {
-            if let PubSubResult::Log(log) = &item {
+            if let PubSubResult::Log(log) = item {
                 if let Some(filter) = &filter {
                     if !filter.matches(log) {
                         continue;
@@ -315,8 +757,10 @@ impl EthSubscribe {
                 }
             }
 
+            // Sending blocks if the connection buffer is full, which provides backpressure for slow clients.
+            // If the buffer doesn't free up in time, the subscriber is dropped.
             sink.send_timeout(
-                SubscriptionMessage::from_json(&item)
+                SubscriptionMessage::from_json(item)
                     .expect("PubSubResult always serializable to json;qed"),
                 SUBSCRIPTION_SINK_SEND_TIMEOUT,
             )
@@ -355,7 +799,14 @@ impl EthSubscribe {
                 let Ok(sink) = pending_sink.accept().await else {
                     return;
                 };
-                let transactions_rx = self.transactions.subscribe();
+                let include_transactions = params
+                    .and_then(|params| params.include_transactions)
+                    .unwrap_or(false);
+                let transactions_rx = if include_transactions {
+                    self.full_transactions.subscribe()
+                } else {
+                    self.transactions.subscribe()
+                };
                 tokio::spawn(Self::run_subscriber(
                     sink,
                     SubscriptionType::Txs,
@@ -365,25 +816,21 @@ impl EthSubscribe {
                 Some(SubscriptionType::Txs)
             }
             "logs" => {
-                let filter = params.unwrap_or_default();
-                let topic_count = filter.topics.as_ref().map_or(0, Vec::len);
-
-                if topic_count > EVENT_TOPIC_NUMBER_LIMIT {
+                let Some(filter) = LogsFilter::new(params.unwrap_or_default()) else {
                     Self::reject(pending_sink).await;
-                    None
-                } else {
-                    let Ok(sink) = pending_sink.accept().await else {
-                        return;
-                    };
-                    let logs_rx = self.logs.subscribe();
-                    tokio::spawn(Self::run_subscriber(
-                        sink,
-                        SubscriptionType::Logs,
-                        logs_rx,
-                        Some(filter),
-                    ));
-                    Some(SubscriptionType::Logs)
-                }
+                    return;
+                };
+                let Ok(sink) = pending_sink.accept().await else {
+                    return;
+                };
+                let logs_rx = self.logs.subscribe();
+                tokio::spawn(Self::run_subscriber(
+                    sink,
+                    SubscriptionType::Logs,
+                    logs_rx,
+                    Some(filter),
+                ));
+                Some(SubscriptionType::Logs)
             }
             "syncing" => {
                 let Ok(sink) = pending_sink.accept().await else {
@@ -413,18 +860,24 @@ impl EthSubscribe {
     }
 
     /// Spawns notifier tasks. This should be called once per instance.
+    ///
+    /// If `notifications_pool` is provided, block and log notifiers are additionally woken up
+    /// by DB notifications about sealed miniblocks; see [`SealedMiniblockListener`].
     pub fn spawn_notifiers(
         &self,
         connection_pool: ConnectionPool,
+        l2_chain_id: L2ChainId,
         polling_interval: Duration,
+        notifications_pool: Option<ConnectionPool>,
         stop_receiver: watch::Receiver<bool>,
     ) -> Vec<JoinHandle<anyhow::Result<()>>> {
-        let mut notifier_tasks = Vec::with_capacity(3);
+        let mut notifier_tasks = Vec::with_capacity(3 + L1BatchStage::ALL.len());
 
         let notifier = PubSubNotifier {
             sender: self.blocks.clone(),
             connection_pool: connection_pool.clone(),
             polling_interval,
+            notifications_pool: notifications_pool.clone(),
             events_sender: self.events_sender.clone(),
         };
         let notifier_task = tokio::spawn(notifier.notify_blocks(stop_receiver.clone()));
@@ -434,24 +887,272 @@ impl EthSubscribe {
             sender: self.transactions.clone(),
             connection_pool: connection_pool.clone(),
             polling_interval,
+            notifications_pool: None,
             events_sender: self.events_sender.clone(),
         };
-        let notifier_task = tokio::spawn(notifier.notify_txs(stop_receiver.clone()));
+        let notifier_task = tokio::spawn(notifier.notify_txs(
+            self.full_transactions.clone(),
+            l2_chain_id,
+            stop_receiver.clone(),
+        ));
         notifier_tasks.push(notifier_task);
 
         let notifier = PubSubNotifier {
             sender: self.logs.clone(),
-            connection_pool,
+            connection_pool: connection_pool.clone(),
             polling_interval,
+            notifications_pool,
             events_sender: self.events_sender.clone(),
         };
-        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver));
-
+        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver.clone()));
         notifier_tasks.push(notifier_task);
+
+        for stage in L1BatchStage::ALL {
+            let notifier = PubSubNotifier {
+                sender: self.l1_batches.get(stage).clone(),
+                connection_pool: connection_pool.clone(),
+                polling_interval,
+                notifications_pool: None,
+                events_sender: self.events_sender.clone(),
+            };
+            let notifier_task =
+                tokio::spawn(notifier.notify_l1_batches(stage, stop_receiver.clone()));
+            notifier_tasks.push(notifier_task);
+        }
         notifier_tasks
     }
 }
 
+/// Subscriptions in the `zks` namespace: L1 batch notifications for `zks_subscribe`, and log streams
+/// for `zks_streamLogs`. A log stream sends historical logs loaded from Postgres in chunks, and then switches
+/// to logs broadcast by the logs notifier.
+///
+/// To not miss any logs when switching, the stream subscribes to broadcast logs *before* determining
+/// the last historical miniblock; broadcast logs from this miniblock and earlier ones are skipped. If the client
+/// cannot keep up while historical logs are sent, broadcast logs overflow and the stream is dropped, same as
+/// for slow `eth_subscribe` subscribers.
+#[derive(Debug, Clone)]
+pub(super) struct ZksSubscribe {
+    logs: broadcast::Sender<Arc<[PubSubResult]>>,
+    l1_batches: L1BatchSenders,
+    state: RpcState,
+    events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+}
+
+impl ZksSubscribe {
+    async fn reject(sink: PendingSubscriptionSink, err: Web3Error) {
+        let err = match err {
+            Web3Error::InternalError(err) => {
+                tracing::warn!("Internal error starting logs stream: {err:#}");
+                ErrorObject::owned(
+                    ErrorCode::InternalError.code(),
+                    "Internal error",
+                    None::<()>,
+                )
+            }
+            err => ErrorObject::owned(ErrorCode::InvalidParams.code(), err.to_string(), None::<()>),
+        };
+        sink.reject(err).await;
+    }
+
+    /// Resolves the range of historical logs. The range ends with the latest sealed miniblock.
+    async fn historical_logs_filter(
+        &self,
+        filter: &Filter,
+    ) -> Result<api::GetLogsFilter, Web3Error> {
+        let from_block = self
+            .state
+            .resolve_filter_block_number(filter.from_block)
+            .await?;
+        self.state.start_info.ensure_not_pruned(from_block)?;
+        // `to_block` isn't specified in the filter, so it's resolved to the latest sealed miniblock.
+        self.state.resolve_get_logs_filter(filter, from_block).await
+    }
+
+    async fn load_historical_logs(
+        &self,
+        filter: &api::GetLogsFilter,
+        after: Option<api::LogCursor>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Log>> {
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        storage
+            .events_web3_dal()
+            .get_logs_page(filter, after, limit)
+            .await
+            .context("get_logs_page")
+    }
+
+    /// Narrows down the historical range using miniblock blooms. Returns `None` if no miniblocks in the range
+    /// can contain matching logs.
+    async fn narrow_historical_range(
+        &self,
+        mut filter: api::GetLogsFilter,
+    ) -> anyhow::Result<Option<api::GetLogsFilter>> {
+        if filter.from_block > filter.to_block {
+            return Ok(None);
+        }
+        if filter.addresses.is_empty() && filter.topics.is_empty() {
+            return Ok(Some(filter));
+        }
+
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let matching_range = storage
+            .events_web3_dal()
+            .get_bloom_matching_range(&filter)
+            .await
+            .context("get_bloom_matching_range")?;
+        Ok(matching_range.map(|(from_block, to_block)| {
+            filter.from_block = from_block;
+            filter.to_block = to_block;
+            filter
+        }))
+    }
+
+    /// Sends historical logs in chunks. Returns `false` if the stream should be terminated.
+    async fn send_historical_logs(
+        &self,
+        sink: &SubscriptionSink,
+        filter: api::GetLogsFilter,
+    ) -> bool {
+        let filter = match self.narrow_historical_range(filter).await {
+            Ok(Some(filter)) => filter,
+            Ok(None) => return true,
+            Err(err) => {
+                tracing::warn!("Failed narrowing historical logs range: {err:#}");
+                return false;
+            }
+        };
+
+        let api_config = &self.state.api_config;
+        let chunk_size = api_config
+            .req_entities_limit
+            .min(api_config.max_logs_result_size.unwrap_or(usize::MAX))
+            .max(1);
+        let mut cursor = None;
+        loop {
+            let logs = match self.load_historical_logs(&filter, cursor, chunk_size).await {
+                Ok(logs) => logs,
+                Err(err) => {
+                    tracing::warn!("Failed loading historical logs: {err:#}");
+                    return false;
+                }
+            };
+            let is_last_chunk = logs.len() < chunk_size;
+            cursor = logs.last().map(|log| api::LogCursor {
+                // Both fields are always set for logs included into miniblocks.
+                block_number: MiniblockNumber(log.block_number.unwrap().as_u32()),
+                log_index: log.log_index.unwrap().as_u32(),
+            });
+
+            let logs: Vec<_> = logs.into_iter().map(PubSubResult::Log).collect();
+            let send_result =
+                EthSubscribe::handle_new_items(sink, SubscriptionType::Logs, &logs, None).await;
+            if send_result.is_err() {
+                PUB_SUB_METRICS.subscriber_send_timeouts[&SubscriptionType::Logs].inc();
+                return false;
+            }
+            if is_last_chunk {
+                return true;
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self, pending_sink))]
+    pub async fn stream(&self, pending_sink: PendingSubscriptionSink, filter: Filter) {
+        if filter.to_block.is_some() || filter.block_hash.is_some() {
+            pending_sink
+                .reject(ErrorObject::borrowed(
+                    ErrorCode::InvalidParams.code(),
+                    "`toBlock` and `blockHash` are not supported for log streams",
+                    None,
+                ))
+                .await;
+            return;
+        }
+        let pub_sub_filter = PubSubFilter {
+            address: filter.address.clone(),
+            topics: filter.topics.clone(),
+            include_transactions: None,
+        };
+        let Some(live_filter) = LogsFilter::new(pub_sub_filter) else {
+            Self::reject(pending_sink, Web3Error::TooManyTopics).await;
+            return;
+        };
+
+        // Subscribe to broadcast logs before resolving the historical range, so that no logs are missed in between.
+        let live_logs = self.logs.subscribe();
+        let historical_filter = match self.historical_logs_filter(&filter).await {
+            Ok(filter) => filter,
+            Err(err) => {
+                Self::reject(pending_sink, err).await;
+                return;
+            }
+        };
+        let Ok(sink) = pending_sink.accept().await else {
+            return;
+        };
+
+        let this = self.clone();
+        tokio::spawn(async move {
+            let last_historical_block = historical_filter.to_block;
+            if !this.send_historical_logs(&sink, historical_filter).await {
+                return;
+            }
+            let live_filter = LogsFilter {
+                min_block_number: last_historical_block + 1,
+                ..live_filter
+            };
+            EthSubscribe::run_subscriber(
+                sink,
+                SubscriptionType::Logs,
+                live_logs,
+                Some(live_filter),
+            )
+            .await;
+        });
+
+        if let Some(sender) = &self.events_sender {
+            sender
+                .send(PubSubEvent::Subscribed(SubscriptionType::Logs))
+                .ok();
+        }
+    }
+
+    #[tracing::instrument(skip(self, pending_sink))]
+    pub async fn sub(&self, pending_sink: PendingSubscriptionSink, topic: String) {
+        let Some(stage) = L1BatchStage::from_topic(&topic) else {
+            EthSubscribe::reject(pending_sink).await;
+            return;
+        };
+        let Ok(sink) = pending_sink.accept().await else {
+            return;
+        };
+        let sub_type = stage.subscription_type();
+        let l1_batches_rx = self.l1_batches.get(stage).subscribe();
+        tokio::spawn(EthSubscribe::run_subscriber(
+            sink,
+            sub_type,
+            l1_batches_rx,
+            None,
+        ));
+
+        if let Some(sender) = &self.events_sender {
+            sender.send(PubSubEvent::Subscribed(sub_type)).ok();
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl EthPubSubServer for EthSubscribe {
     async fn subscribe(
@@ -464,3 +1165,24 @@ impl EthPubSubServer for EthSubscribe {
         Ok(())
     }
 }
+
+#[async_trait::async_trait]
+impl ZksPubSubServer for ZksSubscribe {
+    async fn stream_logs(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: Filter,
+    ) -> SubscriptionResult {
+        self.stream(pending, filter).await;
+        Ok(())
+    }
+
+    async fn subscribe_l1_batches(
+        &self,
+        pending: PendingSubscriptionSink,
+        topic: String,
+    ) -> SubscriptionResult {
+        self.sub(pending, topic).await;
+        Ok(())
+    }
+}
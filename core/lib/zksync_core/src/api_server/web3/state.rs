@@ -17,19 +17,22 @@ use zksync_types::{
     api, l2::L2Tx, transaction_request::CallRequest, Address, L1BatchNumber, L1ChainId, L2ChainId,
     MiniblockNumber, H256, U256, U64,
 };
+use zksync_utils::{h256_to_u256, u256_to_h256};
 use zksync_web3_decl::{error::Web3Error, types::Filter};
 
 use super::{
     backend_jsonrpsee::MethodTracer,
     metrics::{FilterType, FILTER_METRICS},
+    namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT,
     TypedFilter,
 };
 use crate::{
     api_server::{
         execution_sandbox::{BlockArgs, BlockArgsError, BlockStartInfo},
-        tree::TreeApiClient,
+        tree::{TreeApiClient, TreeApiError, TreeEntryWithProof},
         tx_sender::{tx_sink::TxSink, TxSender},
     },
+    house_keeper::db_partition_manager::DetachedEventsRanges,
     sync_layer::SyncState,
 };
 
@@ -73,6 +76,16 @@ impl BlockStartInfo {
     }
 }
 
+impl From<BlockArgsError> for Web3Error {
+    fn from(err: BlockArgsError) -> Self {
+        match err {
+            BlockArgsError::Pruned(number) => Self::PrunedBlock(number),
+            BlockArgsError::Missing => Self::NoBlock,
+            BlockArgsError::Database(err) => Self::InternalError(err),
+        }
+    }
+}
+
 /// Configuration values for the API.
 /// This structure is detached from `ZkSyncConfig`, since different node types (main, external, etc)
 /// may require different configuration layouts.
@@ -89,8 +102,17 @@ pub struct InternalApiConfig {
     pub diamond_proxy_addr: Address,
     pub l2_testnet_paymaster_addr: Option<Address>,
     pub req_entities_limit: usize,
+    pub max_logs_result_size: Option<usize>,
     pub fee_history_limit: u64,
+    pub gas_price_suggestion_blocks: u32,
+    pub gas_price_suggestion_percentile: f64,
+    pub gas_price_suggestion_smoothing: f64,
     pub filters_disabled: bool,
+    pub trace_timeout: Duration,
+    pub trace_max_call_depth: Option<usize>,
+    pub trace_block_concurrency: usize,
+    pub trace_block_max_result_size: usize,
+    pub txpool_redact_transactions: bool,
 }
 
 impl InternalApiConfig {
@@ -116,8 +138,17 @@ impl InternalApiConfig {
             diamond_proxy_addr: contracts_config.diamond_proxy_addr,
             l2_testnet_paymaster_addr: contracts_config.l2_testnet_paymaster_addr,
             req_entities_limit: web3_config.req_entities_limit(),
+            max_logs_result_size: web3_config.max_logs_result_size,
             fee_history_limit: web3_config.fee_history_limit(),
+            gas_price_suggestion_blocks: web3_config.gas_price_suggestion_blocks(),
+            gas_price_suggestion_percentile: web3_config.gas_price_suggestion_percentile(),
+            gas_price_suggestion_smoothing: web3_config.gas_price_suggestion_smoothing(),
             filters_disabled: web3_config.filters_disabled,
+            trace_timeout: web3_config.trace_timeout(),
+            trace_max_call_depth: web3_config.trace_max_call_depth,
+            trace_block_concurrency: web3_config.trace_block_concurrency(),
+            trace_block_max_result_size: web3_config.trace_block_max_result_size(),
+            txpool_redact_transactions: web3_config.txpool_redact_transactions,
         }
     }
 }
@@ -197,11 +228,81 @@ impl SealedMiniblockNumber {
     }
 }
 
+/// Suggests priority fees for `eth_maxPriorityFeePerGas` and `eth_gasPrice` based on effective priority fees
+/// of transactions included into recent miniblocks.
+///
+/// For each of the recent miniblocks, a gas-weighted percentile of priority fees is computed (empty miniblocks
+/// have zero fees). Percentiles are then smoothed with an exponential moving average from the oldest miniblock
+/// to the newest one. The suggestion is cached until a new miniblock is sealed.
+#[derive(Debug)]
+pub(crate) struct PriorityFeeSuggester {
+    block_count: u32,
+    percentile: f64,
+    smoothing: f64,
+    cached: Mutex<Option<(MiniblockNumber, U256)>>,
+}
+
+impl PriorityFeeSuggester {
+    pub fn new(config: &InternalApiConfig) -> Self {
+        Self {
+            block_count: config.gas_price_suggestion_blocks.max(1),
+            percentile: config.gas_price_suggestion_percentile.clamp(0.0, 100.0),
+            smoothing: config.gas_price_suggestion_smoothing.clamp(0.0, 1.0),
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub async fn suggest(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<U256> {
+        // Holding the lock while querying Postgres ensures that the suggestion is computed once per miniblock.
+        let mut cached = self.cached.lock().await;
+        let latest_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("get_sealed_miniblock_number")?;
+        let Some(latest_miniblock) = latest_miniblock else {
+            return Ok(U256::zero()); // No miniblocks yet
+        };
+        if let Some((cached_miniblock, fee)) = *cached {
+            if cached_miniblock == latest_miniblock {
+                return Ok(fee);
+            }
+        }
+
+        let oldest_miniblock =
+            MiniblockNumber(latest_miniblock.0.saturating_sub(self.block_count - 1));
+        let rewards = storage
+            .blocks_web3_dal()
+            .get_fee_rewards(oldest_miniblock..=latest_miniblock, &[self.percentile])
+            .await
+            .context("get_fee_rewards")?;
+        let rewards = rewards
+            .into_iter()
+            .map(|rewards| rewards.first().copied().unwrap_or_default());
+        let fee = Self::smooth(rewards, self.smoothing);
+        *cached = Some((latest_miniblock, fee));
+        Ok(fee)
+    }
+
+    /// Computes an exponential moving average of `rewards` ordered from the oldest to the newest miniblock.
+    fn smooth(rewards: impl Iterator<Item = U256>, smoothing: f64) -> U256 {
+        let mut average = None::<f64>;
+        for reward in rewards {
+            let reward = reward.min(U256::from(u128::MAX)).as_u128() as f64;
+            average = Some(match average {
+                None => reward,
+                Some(average) => smoothing * average + (1.0 - smoothing) * reward,
+            });
+        }
+        U256::from(average.unwrap_or(0.0).round() as u128)
+    }
+}
+
 /// Holder for the data required for the API to be functional.
 #[derive(Debug, Clone)]
 pub(crate) struct RpcState {
     pub(super) current_method: Arc<MethodTracer>,
-    pub(super) installed_filters: Option<Arc<Mutex<Filters>>>,
+    pub(super) installed_filters: Option<Arc<InstalledFilters>>,
     pub(super) connection_pool: ConnectionPool,
     pub(super) tree_api: Option<Arc<dyn TreeApiClient>>,
     pub(super) tx_sender: TxSender,
@@ -211,6 +312,8 @@ pub(crate) struct RpcState {
     /// from a snapshot.
     pub(super) start_info: BlockStartInfo,
     pub(super) last_sealed_miniblock: SealedMiniblockNumber,
+    pub(super) detached_events_ranges: DetachedEventsRanges,
+    pub(super) priority_fee_suggester: Arc<PriorityFeeSuggester>,
 }
 
 impl RpcState {
@@ -251,6 +354,20 @@ impl RpcState {
             .ok_or(Web3Error::NoBlock)
     }
 
+    /// Resolves the specified block ID to a block number, additionally checking that storage logs for the block
+    /// are not pruned. Should be used by methods reading historical state (balances, storage slots, etc.).
+    pub(crate) async fn resolve_block_with_state(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block: api::BlockId,
+    ) -> Result<MiniblockNumber, Web3Error> {
+        let block_number = self.resolve_block(connection, block).await?;
+        self.start_info
+            .ensure_state_not_pruned(connection, block_number)
+            .await?;
+        Ok(block_number)
+    }
+
     /// Resolves the specified block ID to a block number, which is **not** guaranteed to be present in the node storage.
     /// Returns `None` if the block is known to not be present in the storage (e.g., it's a "finalized" block ID and no blocks
     /// were finalized yet).
@@ -277,18 +394,40 @@ impl RpcState {
         }
     }
 
+    /// Loads Merkle proofs for the specified tree keys from the tree API. Returns `None` if the L1 batch
+    /// is not processed by the tree yet.
+    pub(crate) async fn get_tree_proofs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<Option<Vec<TreeEntryWithProof>>, Web3Error> {
+        self.start_info.ensure_not_pruned(l1_batch_number)?;
+        let tree_api = self
+            .tree_api
+            .as_deref()
+            .ok_or(Web3Error::TreeApiUnavailable)?;
+        match tree_api.get_proofs(l1_batch_number, hashed_keys).await {
+            Ok(proofs) => Ok(Some(proofs)),
+            Err(TreeApiError::NotReady) => Err(Web3Error::TreeApiUnavailable),
+            Err(TreeApiError::NoVersion(err)) => {
+                if err.missing_version > err.version_count {
+                    Ok(None)
+                } else {
+                    Err(Web3Error::InternalError(anyhow::anyhow!(
+                        "L1 batch #{l1_batch_number} is pruned in Merkle tree, but not in Postgres"
+                    )))
+                }
+            }
+            Err(TreeApiError::Internal(err)) => Err(Web3Error::InternalError(err)),
+        }
+    }
+
     pub(crate) async fn resolve_block_args(
         &self,
         connection: &mut StorageProcessor<'_>,
         block: api::BlockId,
     ) -> Result<BlockArgs, Web3Error> {
-        BlockArgs::new(connection, block, self.start_info)
-            .await
-            .map_err(|err| match err {
-                BlockArgsError::Pruned(number) => Web3Error::PrunedBlock(number),
-                BlockArgsError::Missing => Web3Error::NoBlock,
-                BlockArgsError::Database(err) => Web3Error::InternalError(err),
-            })
+        Ok(BlockArgs::new(connection, block, self.start_info).await?)
     }
 
     pub async fn resolve_filter_block_number(
@@ -316,6 +455,60 @@ impl RpcState {
         Ok((from_block, to_block))
     }
 
+    /// Converts a logs `filter` to the form used by the DAL. `to_block` is resolved from the filter and capped
+    /// by the latest sealed miniblock. Returns an error if events for `from_block` are pruned, or if events in the resolved
+    /// block range are archived by the partition manager.
+    pub async fn resolve_get_logs_filter(
+        &self,
+        filter: &Filter,
+        from_block: MiniblockNumber,
+    ) -> Result<api::GetLogsFilter, Web3Error> {
+        let addresses = if let Some(addresses) = &filter.address {
+            addresses.0.clone()
+        } else {
+            vec![]
+        };
+        let topics = if let Some(topics) = &filter.topics {
+            if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
+                return Err(Web3Error::TooManyTopics);
+            }
+            let topics_by_idx = topics
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, topics)| Some((idx as u32 + 1, topics.as_ref()?.0.clone())));
+            topics_by_idx.collect::<Vec<_>>()
+        } else {
+            vec![]
+        };
+
+        let mut to_block = self.resolve_filter_block_number(filter.to_block).await?;
+        if matches!(filter.to_block, Some(api::BlockNumber::Number(_))) {
+            to_block = to_block.min(
+                self.resolve_filter_block_number(Some(api::BlockNumber::Latest))
+                    .await?,
+            );
+        }
+
+        let mut storage = self.connection_pool.access_storage_tagged("api").await?;
+        self.start_info
+            .ensure_state_not_pruned(&mut storage, from_block)
+            .await?;
+        drop(storage);
+        let archived_range = self
+            .detached_events_ranges
+            .find_overlapping(from_block..=to_block);
+        if let Some(range) = archived_range {
+            return Err(Web3Error::LogsRangeUnavailable(range.start, range.end));
+        }
+
+        Ok(api::GetLogsFilter {
+            from_block,
+            to_block,
+            addresses,
+            topics,
+        })
+    }
+
     /// If filter has `block_hash` then it resolves block number by hash and sets it to `from_block` and `to_block`.
     pub async fn resolve_filter_block_hash(&self, filter: &mut Filter) -> Result<(), Web3Error> {
         match (filter.block_hash, filter.from_block, filter.to_block) {
@@ -481,6 +674,154 @@ impl Filters {
     }
 }
 
+/// Filters persisted in Postgres. Unlike [`Filters`], persisted filters survive API server restarts
+/// and are shared among all API servers connected to the same database.
+#[derive(Debug, Clone)]
+pub(crate) struct PersistentFilters {
+    pool: ConnectionPool,
+    ttl: Duration,
+    max_count: Option<usize>,
+}
+
+impl PersistentFilters {
+    const CLEANUP_INTERVAL: Duration = Duration::from_secs(10);
+
+    /// Creates persistent filters. Filters not polled for `ttl` are removed, as well as the least recently polled
+    /// filters exceeding `max_count`. The `pool` must be connected to the master DB.
+    pub fn new(pool: ConnectionPool, ttl: Duration, max_count: Option<usize>) -> Self {
+        Self {
+            pool,
+            ttl,
+            max_count,
+        }
+    }
+
+    /// Adds filter to the state and returns its key.
+    async fn add(&self, filter: TypedFilter) -> anyhow::Result<U256> {
+        let serialized_filter =
+            serde_json::to_value(&filter).context("failed serializing filter")?;
+        let mut storage = self.pool.access_storage_tagged("api").await?;
+        loop {
+            let filter_id = H256::random();
+            let inserted = storage
+                .api_filters_dal()
+                .insert_filter(filter_id, &serialized_filter)
+                .await
+                .context("insert_filter")?;
+            if inserted {
+                return Ok(h256_to_u256(filter_id));
+            }
+        }
+    }
+
+    /// Retrieves filter from the state and marks it as polled.
+    async fn poll(&self, index: U256) -> anyhow::Result<Option<TypedFilter>> {
+        let mut storage = self.pool.access_storage_tagged("api").await?;
+        let serialized_filter = storage
+            .api_filters_dal()
+            .poll_filter(u256_to_h256(index), self.ttl)
+            .await
+            .context("poll_filter")?;
+        let Some(serialized_filter) = serialized_filter else {
+            return Ok(None);
+        };
+        let filter = serde_json::from_value(serialized_filter)
+            .with_context(|| format!("failed deserializing filter {index:#x}"))?;
+        Ok(Some(filter))
+    }
+
+    async fn update(&self, index: U256, new_filter: &TypedFilter) -> anyhow::Result<()> {
+        let serialized_filter =
+            serde_json::to_value(new_filter).context("failed serializing filter")?;
+        let mut storage = self.pool.access_storage_tagged("api").await?;
+        storage
+            .api_filters_dal()
+            .update_filter(u256_to_h256(index), &serialized_filter)
+            .await
+            .context("update_filter")?;
+        Ok(())
+    }
+
+    async fn remove(&self, index: U256) -> anyhow::Result<bool> {
+        let mut storage = self.pool.access_storage_tagged("api").await?;
+        let removed = storage
+            .api_filters_dal()
+            .remove_filter(u256_to_h256(index))
+            .await
+            .context("remove_filter")?;
+        Ok(removed)
+    }
+
+    /// Periodically removes expired filters and filters exceeding the limit.
+    pub async fn run_cleanup(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            let mut storage = self.pool.access_storage_tagged("api").await?;
+            let removed_count = storage
+                .api_filters_dal()
+                .remove_stale_filters(self.ttl, self.max_count)
+                .await
+                .context("remove_stale_filters")?;
+            drop(storage);
+            if removed_count > 0 {
+                tracing::debug!("Removed {removed_count} stale persistent filters");
+            }
+
+            if tokio::time::timeout(Self::CLEANUP_INTERVAL, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::debug!("Stopping persistent filters cleanup");
+        Ok(())
+    }
+}
+
+/// Filters installed via the Web3 API, either stored in memory or persisted in Postgres.
+#[derive(Debug)]
+pub(crate) enum InstalledFilters {
+    InMemory(Mutex<Filters>),
+    Persistent(PersistentFilters),
+}
+
+impl InstalledFilters {
+    /// Adds filter to the state and returns its key.
+    pub async fn add(&self, filter: TypedFilter) -> anyhow::Result<U256> {
+        match self {
+            Self::InMemory(filters) => Ok(filters.lock().await.add(filter)),
+            Self::Persistent(filters) => filters.add(filter).await,
+        }
+    }
+
+    /// Retrieves filter from the state.
+    pub async fn get_and_update_stats(&self, index: U256) -> anyhow::Result<Option<TypedFilter>> {
+        match self {
+            Self::InMemory(filters) => Ok(filters.lock().await.get_and_update_stats(index)),
+            Self::Persistent(filters) => filters.poll(index).await,
+        }
+    }
+
+    /// Updates filter in the state.
+    pub async fn update(&self, index: U256, new_filter: TypedFilter) -> anyhow::Result<()> {
+        match self {
+            Self::InMemory(filters) => {
+                filters.lock().await.update(index, new_filter);
+                Ok(())
+            }
+            Self::Persistent(filters) => filters.update(index, &new_filter).await,
+        }
+    }
+
+    /// Removes filter from the state.
+    pub async fn remove(&self, index: U256) -> anyhow::Result<bool> {
+        match self {
+            Self::InMemory(filters) => Ok(filters.lock().await.remove(index)),
+            Self::Persistent(filters) => filters.remove(index).await,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::NaiveDateTime;
@@ -519,4 +860,20 @@ mod tests {
         assert!(filters.0.contains(&idx2));
         assert!(!filters.0.contains(&idx3));
     }
+
+    #[test]
+    fn smoothing_priority_fees() {
+        use super::*;
+
+        let rewards = [100_u64, 0, 200].map(U256::from);
+        let smooth = |smoothing| PriorityFeeSuggester::smooth(rewards.into_iter(), smoothing);
+        assert_eq!(smooth(0.0), U256::from(200));
+        assert_eq!(smooth(1.0), U256::from(100));
+        // (0.5 * 100 + 0.5 * 0) * 0.5 + 0.5 * 200 = 125
+        assert_eq!(smooth(0.5), U256::from(125));
+        assert_eq!(
+            PriorityFeeSuggester::smooth(std::iter::empty(), 0.5),
+            U256::zero()
+        );
+    }
 }
@@ -0,0 +1,29 @@
+//! Tests for the `admin` Web3 namespace.
+
+use zksync_web3_decl::namespaces::AdminNamespaceClient;
+
+use super::*;
+
+#[derive(Debug)]
+struct SetFeeAccountTest;
+
+#[async_trait]
+impl HttpTest for SetFeeAccountTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let fee_account = Address::repeat_byte(0xfe);
+        client.set_fee_account(fee_account).await?;
+
+        let mut storage = pool.access_storage().await?;
+        let scheduled_fee_account = storage
+            .fee_account_dal()
+            .get_scheduled_fee_account()
+            .await?;
+        assert_eq!(scheduled_fee_account, Some(fee_account));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn setting_fee_account() {
+    test_http_server(SetFeeAccountTest).await;
+}
@@ -1,5 +1,6 @@
 //! Tests for the `debug` Web3 namespace.
 
+use multivm::interface::ExecutionResult;
 use zksync_types::{tx::TransactionExecutionResult, vm_trace::Call, BOOTLOADER_ADDRESS};
 use zksync_web3_decl::namespaces::DebugNamespaceClient;
 
@@ -54,7 +55,12 @@ impl HttpTest for TraceBlockTest {
 
             assert_eq!(block_traces.len(), tx_results.len()); // equals to the number of transactions in the block
             for (trace, tx_result) in block_traces.iter().zip(&tx_results) {
-                let api::ResultDebugCall { result } = trace;
+                let api::ResultTransactionTrace {
+                    result: api::TransactionTrace::Call(result),
+                } = trace
+                else {
+                    panic!("Unexpected trace: {trace:?}");
+                };
                 assert_eq!(result.from, Address::zero());
                 assert_eq!(result.to, BOOTLOADER_ADDRESS);
                 assert_eq!(result.gas, tx_result.transaction.gas_limit());
@@ -113,6 +119,9 @@ impl HttpTest for TraceTransactionTest {
             .trace_transaction(tx_results[0].hash, None)
             .await?
             .context("no transaction traces")?;
+        let api::TransactionTrace::Call(result) = result else {
+            panic!("Unexpected trace: {result:?}");
+        };
         assert_eq!(result.from, Address::zero());
         assert_eq!(result.to, BOOTLOADER_ADDRESS);
         assert_eq!(result.gas, tx_results[0].transaction.gas_limit());
@@ -127,6 +136,78 @@ async fn tracing_transaction() {
     test_http_server(TraceTransactionTest).await;
 }
 
+/// Tests tracing transactions and blocks that requires re-executing them in the sandbox.
+#[derive(Debug)]
+struct TraceWithReplayTest;
+
+#[async_trait]
+impl HttpTest for TraceWithReplayTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_tx_responses(|_, block_args| {
+            assert_eq!(block_args.resolved_block_number(), MiniblockNumber(1));
+            ExecutionResult::Success { output: vec![] }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        // Call traces are not saved for this transaction, so it will be re-executed.
+        let tx_results = [execute_l2_transaction(create_l2_transaction(1, 2))];
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let result = client
+            .trace_transaction(tx_results[0].hash, None)
+            .await?
+            .context("no transaction traces")?;
+        let api::TransactionTrace::Call(result) = result else {
+            panic!("Unexpected trace: {result:?}");
+        };
+        assert_eq!(result.to, BOOTLOADER_ADDRESS);
+        assert_eq!(result.gas, tx_results[0].transaction.gas_limit());
+        assert!(result.calls.is_empty());
+
+        let prestate_config = api::TracerConfig {
+            tracer: api::SupportedTracers::PrestateTracer,
+            tracer_config: api::CallTracerConfig::default(),
+        };
+        let result = client
+            .trace_transaction(tx_results[0].hash, Some(prestate_config.clone()))
+            .await?
+            .context("no transaction traces")?;
+        // The mock executor doesn't produce storage logs.
+        assert_eq!(result, api::TransactionTrace::Prestate(Default::default()));
+
+        let missing_trace = client
+            .trace_transaction(H256::repeat_byte(0xff), Some(prestate_config.clone()))
+            .await?;
+        assert!(missing_trace.is_none());
+
+        let block_traces = client.trace_block_by_number(1_u32.into(), None).await?;
+        assert_eq!(block_traces.len(), 1);
+        assert_matches!(
+            &block_traces[0].result,
+            api::TransactionTrace::Call(call) if call.to == BOOTLOADER_ADDRESS && call.calls.is_empty()
+        );
+        let block_traces = client
+            .trace_block_by_number(1_u32.into(), Some(prestate_config))
+            .await?;
+        assert_eq!(block_traces.len(), 1);
+        assert_eq!(
+            block_traces[0].result,
+            api::TransactionTrace::Prestate(Default::default())
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn tracing_with_replay() {
+    test_http_server(TraceWithReplayTest).await;
+}
+
 #[derive(Debug)]
 struct TraceBlockTestWithSnapshotRecovery;
 
@@ -2,7 +2,9 @@
 
 use std::fmt::Debug;
 
+use anyhow::Context as _;
 use jsonrpsee::{core::client::Error, types::error::ErrorCode};
+use zksync_dal::partitions_dal::PartitionedTable;
 use zksync_web3_decl::{jsonrpsee::core::ClientError as RpcError, types::FilterChanges};
 
 use super::*;
@@ -267,6 +269,155 @@ async fn log_filter_changes_with_block_boundaries() {
     test_http_server(LogFilterChangesWithBlockBoundariesTest).await;
 }
 
+#[derive(Debug)]
+struct PaginatedLogsTest;
+
+#[async_trait]
+impl HttpTest for PaginatedLogsTest {
+    fn web3_config(&self) -> Web3JsonRpcConfig {
+        Web3JsonRpcConfig {
+            req_entities_limit: Some(3),
+            ..Web3JsonRpcConfig::for_tests()
+        }
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let (_, mut events) = store_events(&mut storage, 1, 0).await?;
+        let (_, new_events) = store_events(&mut storage, 2, 4).await?;
+        drop(storage);
+        events.extend(new_events);
+        let events: Vec<_> = events.iter().collect();
+
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            ..Filter::default()
+        };
+        // `eth_getLogs` rejects the filter since it spans multiple blocks and matches too many logs.
+        let err = client.get_logs(filter.clone()).await.unwrap_err();
+        assert_matches!(err, RpcError::Call(_));
+
+        let first_page = client.get_logs_paginated(filter.clone(), None).await?;
+        assert_logs_match(&first_page.items, &events[..3]);
+        let cursor = first_page.next_cursor.unwrap();
+        assert_eq!(cursor.block_number, MiniblockNumber(1));
+
+        let second_page = client
+            .get_logs_paginated(filter.clone(), Some(cursor))
+            .await?;
+        assert_logs_match(&second_page.items, &events[3..6]);
+        let cursor = second_page.next_cursor.unwrap();
+        assert_eq!(cursor.block_number, MiniblockNumber(2));
+
+        let last_page = client
+            .get_logs_paginated(filter.clone(), Some(cursor))
+            .await?;
+        assert_logs_match(&last_page.items, &events[6..]);
+        assert_eq!(last_page.next_cursor, None);
+
+        let address_filter = Filter {
+            address: Some(Address::repeat_byte(23).into()),
+            ..filter
+        };
+        let first_page = client
+            .get_logs_paginated(address_filter.clone(), None)
+            .await?;
+        assert_logs_match(&first_page.items, &[events[0], events[3], events[4]]);
+        let cursor = first_page.next_cursor.unwrap();
+        let last_page = client
+            .get_logs_paginated(address_filter, Some(cursor))
+            .await?;
+        assert_logs_match(&last_page.items, &[events[7]]);
+        assert_eq!(last_page.next_cursor, None);
+
+        let bounded_filter = Filter {
+            from_block: Some(api::BlockNumber::Number(2.into())),
+            to_block: Some(api::BlockNumber::Number(2.into())),
+            ..Filter::default()
+        };
+        let page = client.get_logs_paginated(bounded_filter, None).await?;
+        assert_logs_match(&page.items, &events[4..7]);
+        assert!(page.next_cursor.is_some());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn paginated_logs() {
+    test_http_server(PaginatedLogsTest).await;
+}
+
+#[derive(Debug)]
+struct ArchivedLogsTest;
+
+#[async_trait]
+impl HttpTest for ArchivedLogsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        for number in 1..=3 {
+            store_events(&mut storage, number, 0).await?;
+        }
+        let table = PartitionedTable::Events;
+        let archived_range = MiniblockNumber(0)..MiniblockNumber(2);
+        for range in [
+            archived_range.clone(),
+            MiniblockNumber(2)..MiniblockNumber(4),
+        ] {
+            storage
+                .partitions_dal()
+                .create_partition(table, range)
+                .await?;
+        }
+        storage
+            .partitions_dal()
+            .detach_partition(table, &table.partition_name(&archived_range))
+            .await?;
+        drop(storage);
+
+        // Detached ranges are cached by the server and refreshed in the background.
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(0.into())),
+            ..Filter::default()
+        };
+        tokio::time::timeout(Duration::from_secs(30), async {
+            while client.get_logs(filter.clone()).await.is_ok() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .context("detached events ranges were not refreshed")?;
+
+        for from_block in [0, 1] {
+            let filter = Filter {
+                from_block: Some(api::BlockNumber::Number(from_block.into())),
+                ..Filter::default()
+            };
+            let err = client.get_logs(filter.clone()).await.unwrap_err();
+            assert_matches!(err, RpcError::Call(err) => {
+                assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+                assert!(err.message().contains("archived"), "{err:?}");
+            });
+            let err = client.get_logs_paginated(filter, None).await.unwrap_err();
+            assert_matches!(err, RpcError::Call(err) => {
+                assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+            });
+        }
+
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(2.into())),
+            ..Filter::default()
+        };
+        let logs = client.get_logs(filter).await?;
+        assert_eq!(logs.len(), 8);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn archived_logs() {
+    test_http_server(ArchivedLogsTest).await;
+}
+
 fn assert_not_implemented<T: Debug>(result: Result<T, Error>) {
     assert_matches!(result, Err(Error::Call(e)) => {
         assert_eq!(e.code(), ErrorCode::InternalError.code());
@@ -303,3 +454,94 @@ impl HttpTest for DisableFiltersTest {
 async fn disable_filters() {
     test_http_server(DisableFiltersTest).await;
 }
+
+async fn spawn_server_with_persistent_filters(
+    api_config: InternalApiConfig,
+    pool: ConnectionPool,
+    stop_receiver: watch::Receiver<bool>,
+) -> ApiServerHandles {
+    let (tx_sender, vm_barrier) = create_test_tx_sender(
+        pool.clone(),
+        api_config.l2_chain_id,
+        MockTransactionExecutor::default().into(),
+    )
+    .await;
+    ApiBuilder::jsonrpsee_backend(api_config, pool.clone())
+        .http(0)
+        .with_polling_interval(POLL_INTERVAL)
+        .with_tx_sender(tx_sender)
+        .with_vm_barrier(vm_barrier)
+        .with_persistent_filters(pool, Duration::from_secs(60))
+        .build()
+        .expect("Unable to build API server")
+        .run(stop_receiver)
+        .await
+        .expect("Failed spawning JSON-RPC server")
+}
+
+#[tokio::test]
+async fn persistent_filters_survive_server_restart() {
+    let pool = ConnectionPool::test_pool().await;
+    let network_config = NetworkConfig::for_tests();
+    let mut storage = pool.access_storage().await.unwrap();
+    StorageInitialization::Genesis
+        .prepare_storage(&network_config, &mut storage)
+        .await
+        .unwrap();
+    drop(storage);
+    let api_config = InternalApiConfig::new(
+        &network_config,
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+    );
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let mut server_handles =
+        spawn_server_with_persistent_filters(api_config.clone(), pool.clone(), stop_receiver).await;
+    let local_addr = server_handles.wait_until_ready().await;
+    let client = <HttpClient>::builder()
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    let block_filter_id = client.new_block_filter().await.unwrap();
+    let logs_filter_id = client.new_filter(Filter::default()).await.unwrap();
+    let removed_filter_id = client.new_block_filter().await.unwrap();
+    assert!(client.uninstall_filter(removed_filter_id).await.unwrap());
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let (_, events) = store_events(&mut storage, 1, 0).await.unwrap();
+    drop(storage);
+    let events: Vec<_> = events.iter().collect();
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let mut server_handles =
+        spawn_server_with_persistent_filters(api_config, pool.clone(), stop_receiver).await;
+    let local_addr = server_handles.wait_until_ready().await;
+    let client = <HttpClient>::builder()
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+
+    let block_filter_changes = client.get_filter_changes(block_filter_id).await.unwrap();
+    assert_matches!(
+        block_filter_changes,
+        FilterChanges::Hashes(hashes) if hashes == [create_miniblock(1).hash]
+    );
+    let block_filter_changes = client.get_filter_changes(block_filter_id).await.unwrap();
+    assert_matches!(block_filter_changes, FilterChanges::Hashes(hashes) if hashes.is_empty());
+
+    let all_logs = client.get_filter_changes(logs_filter_id).await.unwrap();
+    let FilterChanges::Logs(all_logs) = all_logs else {
+        panic!("Unexpected getFilterChanges output: {:?}", all_logs);
+    };
+    assert_logs_match(&all_logs, &events);
+
+    let err = client
+        .get_filter_changes(removed_filter_id)
+        .await
+        .unwrap_err();
+    assert_matches!(err, RpcError::Call(err) if err.code() == ErrorCode::InvalidParams.code());
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
@@ -18,6 +18,7 @@ use zksync_config::configs::{
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, StorageProcessor};
 use zksync_health_check::CheckHealth;
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     api,
     block::MiniblockHeader,
     fee::TransactionExecutionMetrics,
@@ -25,6 +26,7 @@ use zksync_types::{
     l2::L2Tx,
     storage::get_code_key,
     tokens::{TokenInfo, TokenMetadata},
+    transaction_request::CallRequest,
     tx::{
         tx_execution_info::TxExecutionStatus, ExecutionMetrics, IncludedTxLocation,
         TransactionExecutionResult,
@@ -32,32 +34,39 @@ use zksync_types::{
     utils::{storage_key_for_eth_balance, storage_key_for_standard_token_balance},
     AccountTreeId, Address, L1BatchNumber, Nonce, StorageKey, StorageLog, VmEvent, H256, U64,
 };
-use zksync_utils::u256_to_h256;
+use zksync_utils::{bytecode::hash_bytecode, u256_to_h256};
 use zksync_web3_decl::{
     jsonrpsee::{http_client::HttpClient, types::error::ErrorCode},
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
-use super::{metrics::ApiTransportLabel, *};
+use super::{
+    metrics::{ApiTransportLabel, ErrorClass},
+    *,
+};
 use crate::{
     api_server::{
         execution_sandbox::testonly::MockTransactionExecutor,
         tx_sender::tests::create_test_tx_sender,
     },
     genesis::{ensure_genesis_state, GenesisParams},
+    house_keeper::{db_pruner::DbPruner, periodic_job::PeriodicJob},
     utils::testonly::{
         create_l1_batch, create_l1_batch_metadata, create_l2_transaction, create_miniblock,
         l1_batch_metadata_to_commitment_artifacts, prepare_recovery_snapshot,
     },
 };
 
+mod admin;
 mod debug;
 mod filters;
 mod snapshots;
+mod txpool;
 mod vm;
 mod ws;
 
 const TEST_TIMEOUT: Duration = Duration::from_secs(20);
+const SUBSCRIPTIONS_PER_CONNECTION_LIMIT: usize = 10;
 const POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 impl ApiServerHandles {
@@ -156,14 +165,20 @@ async fn spawn_server(
     let (pub_sub_events_sender, pub_sub_events_receiver) = mpsc::unbounded_channel();
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
-    namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
+    namespaces.extend([
+        Namespace::Debug,
+        Namespace::Snapshots,
+        Namespace::Txpool,
+        Namespace::Admin,
+    ]);
 
     let server_builder = match transport {
-        ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
+        ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool.clone()).http(0),
         ApiTransportLabel::Ws => {
-            let mut builder = ApiBuilder::jsonrpsee_backend(api_config, pool)
+            let mut builder = ApiBuilder::jsonrpsee_backend(api_config, pool.clone())
                 .ws(0)
-                .with_subscriptions_limit(100);
+                .with_subscriptions_limit(100)
+                .with_subscriptions_per_connection_limit(SUBSCRIPTIONS_PER_CONNECTION_LIMIT);
             if let Some(websocket_requests_per_minute_limit) = websocket_requests_per_minute_limit {
                 builder = builder
                     .with_websocket_requests_per_minute_limit(websocket_requests_per_minute_limit);
@@ -177,6 +192,7 @@ async fn spawn_server(
         .with_vm_barrier(vm_barrier)
         .with_pub_sub_events(pub_sub_events_sender)
         .with_method_tracer(method_tracer)
+        .with_admin_pool(pool)
         .enable_api_namespaces(namespaces)
         .build()
         .expect("Unable to build API server")
@@ -203,6 +219,10 @@ trait HttpTest: Send + Sync {
 
     async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()>;
 
+    fn web3_config(&self) -> Web3JsonRpcConfig {
+        Web3JsonRpcConfig::for_tests()
+    }
+
     /// Overrides the `filters_disabled` configuration parameter for HTTP server startup
     fn filters_disabled(&self) -> bool {
         false
@@ -280,7 +300,7 @@ async fn test_http_server(test: impl HttpTest) {
 
     let (stop_sender, stop_receiver) = watch::channel(false);
     let contracts_config = ContractsConfig::for_tests();
-    let web3_config = Web3JsonRpcConfig::for_tests();
+    let web3_config = test.web3_config();
     let mut api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
     api_config.filters_disabled = test.filters_disabled();
     let mut server_handles = spawn_http_server(
@@ -349,7 +369,8 @@ async fn store_miniblock(
         let tx_submission_result = storage
             .transactions_dal()
             .insert_transaction_l2(l2_tx, TransactionExecutionMetrics::default())
-            .await;
+            .await
+            .unwrap();
         assert_matches!(tx_submission_result, L2TxSubmissionResult::Added);
     }
 
@@ -361,7 +382,8 @@ async fn store_miniblock(
     storage
         .transactions_dal()
         .mark_txs_as_executed_in_miniblock(new_miniblock.number, transaction_results, 1.into())
-        .await;
+        .await
+        .unwrap();
     Ok(new_miniblock)
 }
 
@@ -442,10 +464,35 @@ async fn store_events(
             MiniblockNumber(miniblock_number),
             &[(tx_location, events.iter().collect())],
         )
-        .await;
+        .await
+        .unwrap();
     Ok((tx_location, events))
 }
 
+async fn confirm_l1_batch_action(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_number: L1BatchNumber,
+    action_type: AggregatedActionType,
+    tx_hash: H256,
+) -> anyhow::Result<()> {
+    let eth_tx = storage
+        .eth_sender_dal()
+        .save_eth_tx(0, vec![], action_type, Address::zero(), 0, None, None)
+        .await?;
+    storage
+        .eth_sender_dal()
+        .insert_tx_history(eth_tx.id, 0, 0, None, tx_hash, &[])
+        .await?;
+    storage
+        .blocks_dal()
+        .set_eth_tx_id(l1_batch_number..=l1_batch_number, eth_tx.id, action_type)
+        .await?;
+    storage
+        .eth_sender_dal()
+        .confirm_tx(tx_hash, U256::zero())
+        .await
+}
+
 #[derive(Debug)]
 struct HttpServerBasicsTest;
 
@@ -472,6 +519,15 @@ async fn http_server_basics() {
     test_http_server(HttpServerBasicsTest).await;
 }
 
+#[test]
+fn parsing_namespaces() {
+    assert_eq!("eth".parse::<Namespace>().unwrap(), Namespace::Eth);
+    assert_eq!("txpool".parse::<Namespace>().unwrap(), Namespace::Txpool);
+    assert_eq!("admin".parse::<Namespace>().unwrap(), Namespace::Admin);
+    assert!("Eth".parse::<Namespace>().is_err());
+    assert!("explorer".parse::<Namespace>().is_err());
+}
+
 #[derive(Debug)]
 struct BlockMethodsWithSnapshotRecovery;
 
@@ -681,6 +737,130 @@ async fn storage_access_with_snapshot_recovery() {
     test_http_server(StorageAccessWithSnapshotRecovery).await;
 }
 
+#[derive(Debug)]
+struct StorageAccessAfterPruning;
+
+impl StorageAccessAfterPruning {
+    const ADDRESS: Address = Address::repeat_byte(1);
+
+    fn call_request() -> CallRequest {
+        CallRequest {
+            from: Some(Address::repeat_byte(2)),
+            to: Some(Self::ADDRESS),
+            data: Some(b"call".to_vec().into()),
+            ..CallRequest::default()
+        }
+    }
+
+    async fn prepare_storage(storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+        let balance_key = storage_key_for_eth_balance(&Self::ADDRESS);
+        for number in 1..=3 {
+            store_events(storage, number, 0).await?;
+            let balance_log =
+                StorageLog::new_write_log(balance_key, H256::from_low_u64_be(number.into()));
+            storage
+                .storage_logs_dal()
+                .insert_storage_logs(
+                    MiniblockNumber(number),
+                    &[(H256::zero(), vec![balance_log])],
+                )
+                .await?;
+            seal_l1_batch(storage, L1BatchNumber(number)).await?;
+        }
+        // Only executed L1 batches are pruned.
+        for number in 1..=2 {
+            confirm_l1_batch_action(
+                storage,
+                L1BatchNumber(number),
+                AggregatedActionType::Execute,
+                H256::from_low_u64_be(number.into()),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HttpTest for StorageAccessAfterPruning {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        Self::prepare_storage(&mut storage).await?;
+        drop(storage);
+
+        let pruned_block = api::BlockIdVariant::BlockNumber(1.into());
+        let balance = client
+            .get_balance(Self::ADDRESS, Some(pruned_block))
+            .await?;
+        assert_eq!(balance, 1.into());
+
+        // The API server is already running, so pruning must be picked up dynamically.
+        let mut pruner = DbPruner::new(Duration::ZERO, 0, pool.clone());
+        pruner.run_routine_task().await?;
+        let pruning_info = pool
+            .access_storage()
+            .await?
+            .pruning_dal()
+            .get_pruning_info()
+            .await?
+            .context("no pruning info")?;
+        assert_eq!(pruning_info.last_pruned_l1_batch, L1BatchNumber(2));
+        let first_retained_block = MiniblockNumber(3);
+
+        let error = client
+            .get_balance(Self::ADDRESS, Some(pruned_block))
+            .await
+            .unwrap_err();
+        assert_pruned_block_error(&error, first_retained_block);
+        let error = client
+            .get_storage_at(Self::ADDRESS, 0.into(), Some(pruned_block))
+            .await
+            .unwrap_err();
+        assert_pruned_block_error(&error, first_retained_block);
+        let error = client
+            .get_transaction_count(Self::ADDRESS, Some(pruned_block))
+            .await
+            .unwrap_err();
+        assert_pruned_block_error(&error, first_retained_block);
+        let error = client
+            .call(Self::call_request(), Some(pruned_block))
+            .await
+            .unwrap_err();
+        assert_pruned_block_error(&error, first_retained_block);
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            ..Filter::default()
+        };
+        let error = client.get_logs(filter).await.unwrap_err();
+        assert_pruned_block_error(&error, first_retained_block);
+
+        // Block headers are not pruned.
+        let block = client
+            .get_block_by_number(api::BlockNumber::Number(1.into()), false)
+            .await?;
+        assert!(block.is_some());
+
+        // State and events for non-pruned blocks are still available.
+        let retained_block = api::BlockIdVariant::BlockNumber(3.into());
+        let balance = client
+            .get_balance(Self::ADDRESS, Some(retained_block))
+            .await?;
+        assert_eq!(balance, 3.into());
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(3.into())),
+            ..Filter::default()
+        };
+        let logs = client.get_logs(filter).await?;
+        assert_eq!(logs.len(), 4);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn storage_access_after_pruning() {
+    test_http_server(StorageAccessAfterPruning).await;
+}
+
 #[derive(Debug)]
 struct TransactionCountTest;
 
@@ -720,7 +900,8 @@ impl HttpTest for TransactionCountTest {
         storage
             .transactions_dal()
             .insert_transaction_l2(pending_tx, TransactionExecutionMetrics::default())
-            .await;
+            .await
+            .unwrap();
 
         let pending_count = client.get_transaction_count(test_address, None).await?;
         assert_eq!(pending_count, 3.into());
@@ -795,7 +976,8 @@ impl HttpTest for TransactionCountAfterSnapshotRecoveryTest {
         storage
             .transactions_dal()
             .insert_transaction_l2(pending_tx, TransactionExecutionMetrics::default())
-            .await;
+            .await
+            .unwrap();
 
         let pending_count = client.get_transaction_count(test_address, None).await?;
         assert_eq!(pending_count, 4.into());
@@ -977,6 +1159,7 @@ impl HttpTest for RpcCallsTracingTest {
         assert_eq!(calls[0].metadata.name, "eth_blockNumber");
         assert_eq!(calls[0].metadata.block_id, None);
         assert_eq!(calls[0].metadata.block_diff, None);
+        let first_request_id = calls[0].metadata.request_id.clone();
 
         client
             .get_block_by_number(api::BlockNumber::Latest, false)
@@ -984,6 +1167,7 @@ impl HttpTest for RpcCallsTracingTest {
 
         let calls = self.tracer.recorded_calls().take();
         assert_eq!(calls.len(), 1);
+        assert_ne!(calls[0].metadata.request_id, first_request_id);
         assert!(calls[0].response.is_success());
         assert_eq!(calls[0].metadata.name, "eth_getBlockByNumber");
         assert_eq!(
@@ -1017,7 +1201,7 @@ impl HttpTest for RpcCallsTracingTest {
             calls[0].response.as_error_code(),
             Some(ErrorCode::MethodNotFound.code())
         );
-        assert!(!calls[0].metadata.has_app_error);
+        assert_eq!(calls[0].metadata.app_error, None);
 
         client
             .request::<serde_json::Value, _>("eth_getBlockByNumber", jsonrpsee::rpc_params![0])
@@ -1030,7 +1214,7 @@ impl HttpTest for RpcCallsTracingTest {
             calls[0].response.as_error_code(),
             Some(ErrorCode::InvalidParams.code())
         );
-        assert!(!calls[0].metadata.has_app_error);
+        assert_eq!(calls[0].metadata.app_error, None);
 
         // Check app-level error.
         client
@@ -1047,7 +1231,7 @@ impl HttpTest for RpcCallsTracingTest {
             calls[0].response.as_error_code(),
             Some(ErrorCode::InvalidParams.code())
         );
-        assert!(calls[0].metadata.has_app_error);
+        assert_eq!(calls[0].metadata.app_error, Some(ErrorClass::User));
 
         // Check batch RPC request.
         let mut batch = BatchRequestBuilder::new();
@@ -1065,6 +1249,8 @@ impl HttpTest for RpcCallsTracingTest {
             call_names,
             HashSet::from(["eth_blockNumber", "zks_L1BatchNumber"])
         );
+        // Calls in a batch share the request ID.
+        assert_eq!(calls[0].metadata.request_id, calls[1].metadata.request_id);
 
         Ok(())
     }
@@ -1074,3 +1260,133 @@ impl HttpTest for RpcCallsTracingTest {
 async fn tracing_rpc_calls() {
     test_http_server(RpcCallsTracingTest::default()).await;
 }
+
+#[derive(Debug)]
+struct BytecodeByHashTest;
+
+impl BytecodeByHashTest {
+    fn bytecode() -> Vec<u8> {
+        vec![42; 32 * 3]
+    }
+}
+
+#[async_trait]
+impl HttpTest for BytecodeByHashTest {
+    fn storage_initialization(&self) -> StorageInitialization {
+        let bytecode = Self::bytecode();
+        StorageInitialization::Recovery {
+            logs: vec![],
+            factory_deps: HashMap::from([(hash_bytecode(&bytecode), bytecode)]),
+        }
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let bytecode = Self::bytecode();
+        let returned_bytecode = client
+            .get_bytecode_by_hash(hash_bytecode(&bytecode))
+            .await?;
+        assert_eq!(returned_bytecode, Some(bytecode));
+
+        let missing_bytecode = client.get_bytecode_by_hash(H256::repeat_byte(1)).await?;
+        assert_eq!(missing_bytecode, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_bytecode_by_hash() {
+    test_http_server(BytecodeByHashTest).await;
+}
+
+#[derive(Debug)]
+struct SyncingTest;
+
+#[async_trait]
+impl HttpTest for SyncingTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let sync_info = client.syncing().await?;
+        assert_eq!(
+            sync_info,
+            api::SyncInfo {
+                starting_block: 0.into(),
+                current_block: 0.into(),
+                highest_block: 0.into(),
+                is_synced: true,
+                current_l1_batch: Some(L1BatchNumber(0)),
+                committed_l1_batch: None,
+                proven_l1_batch: None,
+                executed_l1_batch: None,
+            }
+        );
+
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        drop(storage);
+
+        let sync_info = client.syncing().await?;
+        assert_eq!(sync_info.current_block, 1.into());
+        assert_eq!(sync_info.highest_block, 1.into());
+        assert!(sync_info.is_synced);
+        assert_eq!(sync_info.current_l1_batch, Some(L1BatchNumber(0)));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn syncing() {
+    test_http_server(SyncingTest).await;
+}
+
+#[derive(Debug)]
+struct PriorityFeeSuggestionTest;
+
+#[async_trait]
+impl HttpTest for PriorityFeeSuggestionTest {
+    fn web3_config(&self) -> Web3JsonRpcConfig {
+        Web3JsonRpcConfig {
+            gas_price_suggestion_smoothing: Some(0.5),
+            ..Web3JsonRpcConfig::for_tests()
+        }
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        // The genesis miniblock has no transactions.
+        let priority_fee = client.max_priority_fee_per_gas().await?;
+        assert_eq!(priority_fee, 0.into());
+        let base_fee = client.gas_price().await?;
+
+        // Miniblocks created in tests have 100 wei base fee, so the transactions below pay 50 wei priority fee.
+        let tx_results = [
+            execute_l2_transaction(create_l2_transaction(200, 200)),
+            execute_l2_transaction(create_l2_transaction(200, 200)),
+        ];
+        let mut storage = pool.access_storage().await?;
+        for result in &tx_results {
+            let l2_tx = result.transaction.clone().try_into().unwrap();
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(l2_tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+        }
+        let miniblock = create_miniblock(1);
+        storage.blocks_dal().insert_miniblock(&miniblock).await?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_miniblock(miniblock.number, &tx_results, 150.into())
+            .await
+            .unwrap();
+
+        // The suggestion is smoothed over the genesis miniblock (0 wei) and the new miniblock (50 wei).
+        let priority_fee = client.max_priority_fee_per_gas().await?;
+        assert_eq!(priority_fee, 25.into());
+        let gas_price = client.gas_price().await?;
+        assert_eq!(gas_price, base_fee + priority_fee);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn priority_fee_suggestion() {
+    test_http_server(PriorityFeeSuggestionTest).await;
+}
@@ -0,0 +1,156 @@
+//! Tests for the `txpool` Web3 namespace.
+
+use zksync_web3_decl::namespaces::TxpoolNamespaceClient;
+
+use super::*;
+
+#[derive(Debug)]
+struct TxpoolTest {
+    redact_transactions: bool,
+}
+
+impl TxpoolTest {
+    const FIRST_ACCOUNT: Address = Address::repeat_byte(1);
+    const SECOND_ACCOUNT: Address = Address::repeat_byte(2);
+
+    async fn insert_pending_transaction(
+        storage: &mut StorageProcessor<'_>,
+        initiator: Address,
+        nonce: u32,
+    ) -> H256 {
+        let mut tx = create_l2_transaction(10, 200);
+        tx.common_data.initiator_address = initiator;
+        tx.common_data.nonce = Nonce(nonce);
+        let tx_hash = tx.hash();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        tx_hash
+    }
+}
+
+#[async_trait]
+impl HttpTest for TxpoolTest {
+    fn web3_config(&self) -> Web3JsonRpcConfig {
+        Web3JsonRpcConfig {
+            txpool_redact_transactions: self.redact_transactions,
+            ..Web3JsonRpcConfig::for_tests()
+        }
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        let nonce_log = StorageLog::new_write_log(
+            get_nonce_key(&Self::FIRST_ACCOUNT),
+            H256::from_low_u64_be(1),
+        );
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), vec![nonce_log])])
+            .await?;
+
+        // The transaction with nonce 0 for the first account is stale and must not be returned.
+        Self::insert_pending_transaction(&mut storage, Self::FIRST_ACCOUNT, 0).await;
+        let mut pending_hashes = vec![];
+        let mut queued_hashes = vec![];
+        for nonce in [1, 2] {
+            pending_hashes.push(
+                Self::insert_pending_transaction(&mut storage, Self::FIRST_ACCOUNT, nonce).await,
+            );
+        }
+        queued_hashes
+            .push(Self::insert_pending_transaction(&mut storage, Self::FIRST_ACCOUNT, 4).await);
+        pending_hashes
+            .push(Self::insert_pending_transaction(&mut storage, Self::SECOND_ACCOUNT, 0).await);
+        queued_hashes
+            .push(Self::insert_pending_transaction(&mut storage, Self::SECOND_ACCOUNT, 2).await);
+        drop(storage);
+
+        let status = client.status().await?;
+        assert_eq!(status.pending, 3.into());
+        assert_eq!(status.queued, 2.into());
+
+        let content = client.content().await?;
+        assert_eq!(
+            content.pending[&Self::FIRST_ACCOUNT]
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(
+            content.pending[&Self::SECOND_ACCOUNT]
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            [0]
+        );
+        assert_eq!(
+            content.queued[&Self::FIRST_ACCOUNT]
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            [4]
+        );
+        assert_eq!(
+            content.queued[&Self::SECOND_ACCOUNT]
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            [2]
+        );
+
+        let returned_hashes = |txs: &api::TxpoolTransactions<api::Transaction>| {
+            let mut hashes: Vec<_> = txs
+                .values()
+                .flat_map(|txs| txs.values())
+                .map(|tx| tx.hash)
+                .collect();
+            hashes.sort_unstable();
+            hashes
+        };
+        pending_hashes.sort_unstable();
+        queued_hashes.sort_unstable();
+        assert_eq!(returned_hashes(&content.pending), pending_hashes);
+        assert_eq!(returned_hashes(&content.queued), queued_hashes);
+
+        for tx in content.pending.values().flat_map(|txs| txs.values()) {
+            assert_eq!(tx.r.is_none(), self.redact_transactions);
+            assert_eq!(tx.s.is_none(), self.redact_transactions);
+        }
+
+        let inspect = client.inspect().await?;
+        let summary = &inspect.pending[&Self::SECOND_ACCOUNT][&0];
+        assert!(summary.ends_with("wei + 1000 gas × 10 wei"), "{summary}");
+        assert_eq!(inspect.queued[&Self::FIRST_ACCOUNT].len(), 1);
+
+        let account_content = client.content_from(Self::FIRST_ACCOUNT).await?;
+        assert_eq!(
+            account_content.pending,
+            content.pending[&Self::FIRST_ACCOUNT]
+        );
+        assert_eq!(account_content.queued, content.queued[&Self::FIRST_ACCOUNT]);
+        let account_content = client.content_from(Address::repeat_byte(0xff)).await?;
+        assert_eq!(account_content, api::TxpoolAccountContent::default());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_txpool_content() {
+    test_http_server(TxpoolTest {
+        redact_transactions: false,
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn getting_redacted_txpool_content() {
+    test_http_server(TxpoolTest {
+        redact_transactions: true,
+    })
+    .await;
+}
@@ -4,7 +4,8 @@ use std::sync::atomic::{AtomicU32, Ordering};
 
 use multivm::interface::{ExecutionResult, VmRevertReason};
 use zksync_types::{
-    get_intrinsic_constants, transaction_request::CallRequest, L2ChainId, PackedEthSignature, U256,
+    ethabi, get_intrinsic_constants, transaction_request::CallRequest, L2ChainId,
+    PackedEthSignature, U256,
 };
 use zksync_utils::u256_to_h256;
 use zksync_web3_decl::namespaces::DebugNamespaceClient;
@@ -144,6 +145,300 @@ async fn call_method_after_snapshot_recovery() {
     test_http_server(CallTestAfterSnapshotRecovery).await;
 }
 
+#[derive(Debug)]
+struct CallRevertTest;
+
+impl CallRevertTest {
+    const PANIC_DATA: &'static [u8] = &[
+        0x4e, 0x48, 0x7b, 0x71, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0x11,
+    ];
+    const CUSTOM_ERROR_DATA: &'static [u8] = &[0xde, 0xad, 0xbe, 0xef, 1, 2, 3];
+
+    fn error_data() -> Vec<u8> {
+        let mut data = vec![0x08, 0xc3, 0x79, 0xa0];
+        data.extend(ethabi::encode(&[ethabi::Token::String("oops".to_owned())]));
+        data
+    }
+
+    async fn assert_revert(
+        client: &HttpClient,
+        calldata: &[u8],
+        expected_message: &str,
+        expected_data: Option<&[u8]>,
+    ) {
+        let error = client
+            .call(CallTest::call_request(calldata), None)
+            .await
+            .unwrap_err();
+        let ClientError::Call(error) = error else {
+            panic!("Unexpected error: {error:?}");
+        };
+        assert_eq!(error.code(), 3);
+        assert_eq!(error.message(), expected_message);
+        let expected_data = expected_data
+            .map(|data| serde_json::to_string(&format!("0x{}", hex::encode(data))).unwrap());
+        assert_eq!(
+            error.data().map(|data| data.get().to_owned()),
+            expected_data
+        );
+    }
+}
+
+#[async_trait]
+impl HttpTest for CallRevertTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_call_responses(|tx, _| {
+            let revert_data = match tx.execute.calldata() {
+                b"error" => Self::error_data(),
+                b"panic" => Self::PANIC_DATA.to_vec(),
+                b"custom" => Self::CUSTOM_ERROR_DATA.to_vec(),
+                b"empty" => vec![],
+                data => panic!("Unexpected calldata: {data:?}"),
+            };
+            ExecutionResult::Revert {
+                output: VmRevertReason::from(revert_data.as_slice()),
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let error_data = Self::error_data();
+        Self::assert_revert(
+            client,
+            b"error",
+            "execution reverted: oops",
+            Some(&error_data),
+        )
+        .await;
+        Self::assert_revert(
+            client,
+            b"panic",
+            "execution reverted: arithmetic underflow or overflow",
+            Some(Self::PANIC_DATA),
+        )
+        .await;
+        Self::assert_revert(
+            client,
+            b"custom",
+            "execution reverted",
+            Some(Self::CUSTOM_ERROR_DATA),
+        )
+        .await;
+        Self::assert_revert(client, b"empty", "execution reverted", None).await;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn call_method_reverts() {
+    test_http_server(CallRevertTest).await;
+}
+
+#[derive(Debug, Default)]
+struct CallResultCacheTest {
+    call_count: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl HttpTest for CallResultCacheTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let call_count = self.call_count.clone();
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_call_responses(move |tx, _| {
+            call_count.fetch_add(1, Ordering::Relaxed);
+            ExecutionResult::Success {
+                output: tx.execute.calldata().to_vec(),
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let sealed_block = Some(api::BlockIdVariant::BlockNumber(0.into()));
+        for _ in 0..3 {
+            let call_result = client
+                .call(CallTest::call_request(b"first"), sealed_block)
+                .await?;
+            assert_eq!(call_result.0, b"first");
+        }
+        // Results for the same sealed miniblock must be cached.
+        assert_eq!(self.call_count.load(Ordering::Relaxed), 1);
+
+        let call_result = client
+            .call(CallTest::call_request(b"second"), sealed_block)
+            .await?;
+        assert_eq!(call_result.0, b"second");
+        assert_eq!(self.call_count.load(Ordering::Relaxed), 2);
+
+        // Calls on top of the pending or latest block must not be cached.
+        let latest_block = Some(api::BlockIdVariant::BlockNumber(api::BlockNumber::Latest));
+        for block in [None, latest_block] {
+            for _ in 0..2 {
+                client.call(CallTest::call_request(b"first"), block).await?;
+            }
+        }
+        assert_eq!(self.call_count.load(Ordering::Relaxed), 6);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn call_result_cache() {
+    test_http_server(CallResultCacheTest::default()).await;
+}
+
+#[derive(Debug)]
+struct BatchCallTest;
+
+#[async_trait]
+impl HttpTest for BatchCallTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_call_responses(|tx, block_args| {
+            assert_eq!(block_args.resolved_block_number(), MiniblockNumber(0));
+            match tx.execute.calldata() {
+                b"error" => ExecutionResult::Revert {
+                    output: VmRevertReason::from(CallRevertTest::error_data().as_slice()),
+                },
+                data => ExecutionResult::Success {
+                    output: data.to_vec(),
+                },
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let sealed_block = Some(api::BlockIdVariant::BlockNumber(0.into()));
+        let requests = vec![
+            CallTest::call_request(b"first"),
+            CallTest::call_request(b"error"),
+            CallTest::call_request(b"second"),
+        ];
+        let results = client.batch_call(requests, sealed_block).await?;
+        assert_eq!(
+            results,
+            [
+                api::BatchCallResult {
+                    success: true,
+                    output: b"first".to_vec().into(),
+                    error: None,
+                },
+                api::BatchCallResult {
+                    success: false,
+                    output: CallRevertTest::error_data().into(),
+                    error: Some("execution reverted: oops".to_owned()),
+                },
+                api::BatchCallResult {
+                    success: true,
+                    output: b"second".to_vec().into(),
+                    error: None,
+                },
+            ]
+        );
+
+        let results = client.batch_call(vec![], None).await?;
+        assert!(results.is_empty());
+
+        let requests_limit = self.web3_config().req_entities_limit();
+        let requests = vec![CallTest::call_request(b"first"); requests_limit + 1];
+        let error = client.batch_call(requests, None).await.unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn batch_call_method() {
+    test_http_server(BatchCallTest).await;
+}
+
+#[derive(Debug)]
+struct SimulateBundleTest;
+
+#[async_trait]
+impl HttpTest for SimulateBundleTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        BatchCallTest.transaction_executor()
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let sealed_block = Some(api::BlockIdVariant::BlockNumber(0.into()));
+        let requests = vec![
+            CallTest::call_request(b"first"),
+            CallTest::call_request(b"error"),
+        ];
+        let state_override = api::StateOverride::from([(
+            Address::repeat_byte(1),
+            api::AccountOverride {
+                balance: Some(U256::one() << 64),
+                ..api::AccountOverride::default()
+            },
+        )]);
+        let results = client
+            .simulate_bundle(requests, sealed_block, Some(state_override))
+            .await?;
+        assert_eq!(results.len(), 2);
+
+        assert!(results[0].success);
+        assert_eq!(results[0].output.0, b"first");
+        assert_eq!(results[0].error, None);
+        assert!(results[0].logs.is_empty());
+        let trace = &results[0].trace;
+        assert_eq!(trace.input.0, b"first");
+        assert_eq!(trace.output.0, b"first");
+        assert_eq!(trace.value, 4321.into());
+        assert_eq!(trace.revert_reason, None);
+
+        assert!(!results[1].success);
+        assert_eq!(results[1].output.0, CallRevertTest::error_data());
+        let expected_error = "execution reverted: oops";
+        assert_eq!(results[1].error.as_deref(), Some(expected_error));
+        assert_eq!(
+            results[1].trace.revert_reason.as_deref(),
+            Some(expected_error)
+        );
+
+        let results = client.simulate_bundle(vec![], None, None).await?;
+        assert!(results.is_empty());
+
+        let invalid_override = api::StateOverride::from([(
+            Address::repeat_byte(1),
+            api::AccountOverride {
+                code: Some(vec![0_u8; 64].into()),
+                ..api::AccountOverride::default()
+            },
+        )]);
+        let requests = vec![CallTest::call_request(b"first")];
+        let error = client
+            .simulate_bundle(requests, None, Some(invalid_override))
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+            assert!(
+                error.message().contains("Invalid state override"),
+                "{error:?}"
+            );
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn simulate_bundle_method() {
+    test_http_server(SimulateBundleTest).await;
+}
+
 #[derive(Debug)]
 struct SendRawTransactionTest {
     snapshot_recovery: bool,
@@ -487,3 +782,46 @@ async fn estimate_gas_basics() {
 async fn estimate_gas_after_snapshot_recovery() {
     test_http_server(EstimateGasTest::new(true)).await;
 }
+
+#[derive(Debug)]
+struct CreateAccessListTest;
+
+#[async_trait]
+impl HttpTest for CreateAccessListTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_call_responses(|tx, _| {
+            if tx.execute.calldata() == b"revert" {
+                ExecutionResult::Revert {
+                    output: VmRevertReason::VmError,
+                }
+            } else {
+                ExecutionResult::Success { output: vec![] }
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut call_request = CallRequest::from(create_l2_transaction(10, 100));
+        let response = client
+            .create_access_list(call_request.clone(), None)
+            .await?;
+        // The mock executor doesn't produce storage logs or calls.
+        assert!(response.access_list.is_empty());
+        assert!(response.gas_used > U256::zero());
+
+        call_request.data = Some(b"revert".to_vec().into());
+        let error = client
+            .create_access_list(call_request, None)
+            .await
+            .unwrap_err();
+        assert_matches!(error, ClientError::Call(_));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn creating_access_list() {
+    test_http_server(CreateAccessListTest).await;
+}
@@ -8,15 +8,17 @@ use reqwest::StatusCode;
 use tokio::sync::watch;
 use zksync_config::configs::chain::NetworkConfig;
 use zksync_dal::ConnectionPool;
-use zksync_types::{api, Address, L1BatchNumber, H256, U64};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, api, Address, L1BatchNumber, L2ChainId, H256, U64,
+};
 use zksync_web3_decl::{
     jsonrpsee::{
         core::client::{Subscription, SubscriptionClientT},
         rpc_params,
         ws_client::{WsClient, WsClientBuilder},
     },
-    namespaces::{EthNamespaceClient, ZksNamespaceClient},
-    types::{BlockHeader, PubSubFilter},
+    namespaces::{EthNamespaceClient, ZksNamespaceClient, ZksPubSubClient},
+    types::{BlockHeader, Filter, PubSubFilter, ValueOrArray},
 };
 
 use super::*;
@@ -110,8 +112,13 @@ async fn notifiers_start_after_snapshot_recovery() {
     let (events_sender, mut events_receiver) = mpsc::unbounded_channel();
     let mut subscribe_logic = EthSubscribe::new();
     subscribe_logic.set_events_sender(events_sender);
-    let notifier_handles =
-        subscribe_logic.spawn_notifiers(pool.clone(), POLL_INTERVAL, stop_receiver);
+    let notifier_handles = subscribe_logic.spawn_notifiers(
+        pool.clone(),
+        L2ChainId::default(),
+        POLL_INTERVAL,
+        None,
+        stop_receiver,
+    );
     assert!(!notifier_handles.is_empty());
 
     // Wait a little doing nothing and check that notifier tasks are still active (i.e., have not panicked).
@@ -159,13 +166,17 @@ trait WsTest: Send + Sync {
     fn websocket_requests_per_minute_limit(&self) -> Option<NonZeroU32> {
         None
     }
+
+    fn web3_config(&self) -> Web3JsonRpcConfig {
+        Web3JsonRpcConfig::for_tests()
+    }
 }
 
 async fn test_ws_server(test: impl WsTest) {
     let pool = ConnectionPool::test_pool().await;
     let network_config = NetworkConfig::for_tests();
     let contracts_config = ContractsConfig::for_tests();
-    let web3_config = Web3JsonRpcConfig::for_tests();
+    let web3_config = test.web3_config();
     let api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
     let mut storage = pool.access_storage().await.unwrap();
     test.storage_initialization()
@@ -344,6 +355,7 @@ impl LogSubscriptions {
         let address_filter = PubSubFilter {
             address: Some(Address::repeat_byte(23).into()),
             topics: None,
+            include_transactions: None,
         };
         let params = rpc_params!["logs", address_filter];
         let address_subscription = client
@@ -352,6 +364,7 @@ impl LogSubscriptions {
         let topic_filter = PubSubFilter {
             address: None,
             topics: Some(vec![Some(H256::repeat_byte(42).into())]),
+            include_transactions: None,
         };
         let params = rpc_params!["logs", topic_filter];
         let topic_subscription = client
@@ -511,6 +524,91 @@ async fn log_subscriptions_with_new_block() {
     test_ws_server(LogSubscriptionsWithNewBlockTest).await;
 }
 
+#[derive(Debug)]
+struct LogStreamsTest;
+
+#[async_trait]
+impl WsTest for LogStreamsTest {
+    fn web3_config(&self) -> Web3JsonRpcConfig {
+        // Use a small limit so that historical logs are loaded in several chunks.
+        Web3JsonRpcConfig {
+            req_entities_limit: Some(3),
+            ..Web3JsonRpcConfig::for_tests()
+        }
+    }
+
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Logs]).await;
+
+        let mut storage = pool.access_storage().await?;
+        let (_, events) = store_events(&mut storage, 1, 0).await?;
+        let (_, more_events) = store_events(&mut storage, 2, 4).await?;
+        drop(storage);
+        let events: Vec<_> = events.iter().chain(&more_events).collect();
+
+        let all_logs_filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            ..Filter::default()
+        };
+        let mut all_logs_stream = client.stream_logs(all_logs_filter).await?;
+        let address_filter = Filter {
+            from_block: Some(api::BlockNumber::Number(2.into())),
+            address: Some(Address::repeat_byte(23).into()),
+            ..Filter::default()
+        };
+        let mut address_stream = client.stream_logs(address_filter).await?;
+        for _ in 0..2 {
+            wait_for_subscription(&mut pub_sub_events, SubscriptionType::Logs).await;
+        }
+
+        let historical_logs = collect_logs(&mut all_logs_stream, 8).await?;
+        assert_logs_match(&historical_logs, &events);
+        let historical_address_logs = collect_logs(&mut address_stream, 2).await?;
+        assert_logs_match(&historical_address_logs, &[events[4], events[7]]);
+
+        let mut storage = pool.access_storage().await?;
+        let (_, new_events) = store_events(&mut storage, 3, 8).await?;
+        drop(storage);
+        let new_events: Vec<_> = new_events.iter().collect();
+
+        let new_logs = collect_logs(&mut all_logs_stream, 4).await?;
+        assert_logs_match(&new_logs, &new_events);
+        let new_address_logs = collect_logs(&mut address_stream, 2).await?;
+        assert_logs_match(&new_address_logs, &[new_events[0], new_events[3]]);
+
+        // Check that historical logs were not duplicated by the notifier.
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Logs]).await;
+        tokio::time::timeout(POLL_INTERVAL, all_logs_stream.next())
+            .await
+            .unwrap_err();
+        tokio::time::timeout(POLL_INTERVAL, address_stream.next())
+            .await
+            .unwrap_err();
+
+        // Streams with unsupported params are rejected.
+        let invalid_filter = Filter {
+            to_block: Some(api::BlockNumber::Latest),
+            ..Filter::default()
+        };
+        let err = client.stream_logs(invalid_filter).await.unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(error) if error.code() == ErrorCode::InvalidParams.code()
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn log_streams() {
+    test_ws_server(LogStreamsTest).await;
+}
+
 #[derive(Debug)]
 struct LogSubscriptionsWithManyBlocksTest;
 
@@ -591,6 +689,7 @@ impl WsTest for LogSubscriptionsWithDelayTest {
         let address_and_topic_filter = PubSubFilter {
             address: Some(Address::repeat_byte(23).into()),
             topics: Some(vec![Some(H256::repeat_byte(42).into())]),
+            include_transactions: None,
         };
         let params = rpc_params!["logs", address_and_topic_filter];
         let mut address_and_topic_subscription = client
@@ -707,3 +806,283 @@ impl WsTest for BatchGetsRateLimitedTest {
 async fn batch_rate_limiting() {
     test_ws_server(BatchGetsRateLimitedTest).await;
 }
+
+#[derive(Debug)]
+struct SubscriptionsPerConnectionLimitTest;
+
+#[async_trait]
+impl WsTest for SubscriptionsPerConnectionLimitTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        _pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Logs]).await;
+
+        let mut subscriptions = Vec::with_capacity(SUBSCRIPTIONS_PER_CONNECTION_LIMIT);
+        for _ in 0..SUBSCRIPTIONS_PER_CONNECTION_LIMIT {
+            let params = rpc_params!["logs"];
+            let subscription = client
+                .subscribe::<api::Log, _>("eth_subscribe", params, "eth_unsubscribe")
+                .await?;
+            subscriptions.push(subscription);
+        }
+        let params = rpc_params!["logs"];
+        client
+            .subscribe::<api::Log, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await
+            .unwrap_err();
+
+        // Closing a subscription should free up a slot.
+        subscriptions.pop().unwrap().unsubscribe().await?;
+        let params = rpc_params!["logs"];
+        client
+            .subscribe::<api::Log, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn subscriptions_per_connection_limit() {
+    test_ws_server(SubscriptionsPerConnectionLimitTest).await;
+}
+
+#[derive(Debug)]
+struct LogSubscriptionsWithTopicAlternativesTest;
+
+#[async_trait]
+impl WsTest for LogSubscriptionsWithTopicAlternativesTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Logs]).await;
+
+        let filter = PubSubFilter {
+            address: Some(ValueOrArray(vec![
+                Address::repeat_byte(23),
+                Address::repeat_byte(24),
+            ])),
+            topics: Some(vec![
+                None,
+                Some(ValueOrArray(vec![
+                    H256::repeat_byte(42),
+                    H256::repeat_byte(111),
+                ])),
+            ]),
+            include_transactions: None,
+        };
+        let params = rpc_params!["logs", filter];
+        let mut subscription = client
+            .subscribe::<api::Log, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::Logs).await;
+
+        // Filters with more topics than a log can have are rejected.
+        let filter = PubSubFilter {
+            address: None,
+            topics: Some((0..5).map(|_| None).collect()),
+            include_transactions: None,
+        };
+        let params = rpc_params!["logs", filter];
+        client
+            .subscribe::<api::Log, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await
+            .unwrap_err();
+
+        let mut storage = pool.access_storage().await?;
+        let (_, events) = store_events(&mut storage, 1, 0).await?;
+        drop(storage);
+
+        // Only the last event matches both the address and the second topic.
+        let logs = collect_logs(&mut subscription, 1).await?;
+        assert_logs_match(&logs, &[&events[3]]);
+
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Logs]).await;
+        tokio::time::timeout(POLL_INTERVAL, subscription.next())
+            .await
+            .unwrap_err();
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn log_subscriptions_with_topic_alternatives() {
+    test_ws_server(LogSubscriptionsWithTopicAlternativesTest).await;
+}
+
+#[derive(Debug)]
+struct PendingTransactionsWithBodiesTest;
+
+#[async_trait]
+impl WsTest for PendingTransactionsWithBodiesTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Txs]).await;
+
+        let params = rpc_params!["newPendingTransactions"];
+        let mut hashes_subscription = client
+            .subscribe::<H256, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::Txs).await;
+        let filter = PubSubFilter {
+            include_transactions: Some(true),
+            ..PubSubFilter::default()
+        };
+        let params = rpc_params!["newPendingTransactions", filter];
+        let mut txs_subscription = client
+            .subscribe::<api::Transaction, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::Txs).await;
+
+        let mut storage = pool.access_storage().await?;
+        let tx_results = [
+            execute_l2_transaction(create_l2_transaction(1, 2)),
+            execute_l2_transaction(create_l2_transaction(2, 3)),
+        ];
+        let tx_hashes: Vec<_> = tx_results.iter().map(|tx| tx.hash).collect();
+        store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let mut received_hashes = vec![];
+        let mut received_txs = vec![];
+        while received_txs.len() < tx_hashes.len() {
+            let tx = tokio::time::timeout(TEST_TIMEOUT, txs_subscription.next())
+                .await
+                .context("Timed out waiting for new tx")?
+                .context("Pending txs subscription terminated")??;
+            received_txs.push(tx);
+        }
+        while received_hashes.len() < tx_hashes.len() {
+            let hash = tokio::time::timeout(TEST_TIMEOUT, hashes_subscription.next())
+                .await
+                .context("Timed out waiting for new tx hash")?
+                .context("Pending tx hashes subscription terminated")??;
+            received_hashes.push(hash);
+        }
+
+        let received_tx_hashes: HashSet<_> = received_txs.iter().map(|tx| tx.hash).collect();
+        assert_eq!(received_tx_hashes, tx_hashes.iter().copied().collect());
+        assert_eq!(
+            received_hashes.into_iter().collect::<HashSet<_>>(),
+            received_tx_hashes
+        );
+        for tx in &received_txs {
+            assert_eq!(tx.block_number, Some(1.into()));
+            assert!(tx.transaction_index.is_some());
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn pending_transactions_with_bodies() {
+    test_ws_server(PendingTransactionsWithBodiesTest).await;
+}
+
+#[derive(Debug)]
+struct L1BatchSubscriptionsTest;
+
+#[async_trait]
+impl WsTest for L1BatchSubscriptionsTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        let sub_types = [
+            SubscriptionType::L1BatchSealed,
+            SubscriptionType::L1BatchVerified,
+            SubscriptionType::L1BatchExecuted,
+        ];
+        wait_for_notifiers(&mut pub_sub_events, &sub_types).await;
+
+        let mut sealed_subscription = client
+            .subscribe_l1_batches("l1BatchSealed".to_owned())
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::L1BatchSealed).await;
+        let mut verified_subscription = client
+            .subscribe_l1_batches("l1BatchVerified".to_owned())
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::L1BatchVerified).await;
+        let mut executed_subscription = client
+            .subscribe_l1_batches("l1BatchExecuted".to_owned())
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::L1BatchExecuted).await;
+
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        let sealed_l1_batch = tokio::time::timeout(TEST_TIMEOUT, sealed_subscription.next())
+            .await
+            .context("Timed out waiting for sealed L1 batch")?
+            .context("Sealed L1 batches subscription terminated")??;
+        assert_eq!(sealed_l1_batch.number, L1BatchNumber(1));
+        assert_eq!(sealed_l1_batch.base.prove_tx_hash, None);
+
+        let prove_tx_hash = H256::repeat_byte(1);
+        confirm_l1_batch_action(
+            &mut storage,
+            L1BatchNumber(1),
+            AggregatedActionType::PublishProofOnchain,
+            prove_tx_hash,
+        )
+        .await?;
+        let verified_l1_batch = tokio::time::timeout(TEST_TIMEOUT, verified_subscription.next())
+            .await
+            .context("Timed out waiting for verified L1 batch")?
+            .context("Verified L1 batches subscription terminated")??;
+        assert_eq!(verified_l1_batch.number, L1BatchNumber(1));
+        assert_eq!(verified_l1_batch.base.prove_tx_hash, Some(prove_tx_hash));
+        assert!(verified_l1_batch.base.proven_at.is_some());
+
+        let execute_tx_hash = H256::repeat_byte(2);
+        confirm_l1_batch_action(
+            &mut storage,
+            L1BatchNumber(1),
+            AggregatedActionType::Execute,
+            execute_tx_hash,
+        )
+        .await?;
+        drop(storage);
+        let executed_l1_batch = tokio::time::timeout(TEST_TIMEOUT, executed_subscription.next())
+            .await
+            .context("Timed out waiting for executed L1 batch")?
+            .context("Executed L1 batches subscription terminated")??;
+        assert_eq!(executed_l1_batch.number, L1BatchNumber(1));
+        assert_eq!(
+            executed_l1_batch.base.execute_tx_hash,
+            Some(execute_tx_hash)
+        );
+
+        // Check that L1 batches are not notified repeatedly.
+        wait_for_notifiers(&mut pub_sub_events, &sub_types).await;
+        tokio::time::timeout(POLL_INTERVAL, sealed_subscription.next())
+            .await
+            .unwrap_err();
+
+        let err = client
+            .subscribe_l1_batches("l1BatchCommitted".to_owned())
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(error) if error.code() == ErrorCode::InvalidParams.code()
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn l1_batch_subscriptions() {
+    test_ws_server(L1BatchSubscriptionsTest).await;
+}
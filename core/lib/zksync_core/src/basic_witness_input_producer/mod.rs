@@ -195,7 +195,7 @@ impl JobProcessor for BasicWitnessInputProducer {
     }
 
     fn max_attempts(&self) -> u32 {
-        JOB_MAX_ATTEMPT as u32
+        JOB_MAX_ATTEMPT
     }
 
     async fn get_job_attempts(&self, job_id: &L1BatchNumber) -> anyhow::Result<u32> {
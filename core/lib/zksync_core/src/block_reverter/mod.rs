@@ -219,7 +219,7 @@ impl BlockReverter {
     /// Reverts blocks in the state keeper cache.
     async fn rollback_state_keeper_cache(&self, last_l1_batch_to_keep: L1BatchNumber) {
         tracing::info!("opening DB with state keeper cache...");
-        let sk_cache = RocksdbStorage::builder(self.state_keeper_cache_path.as_ref())
+        let mut sk_cache = RocksdbStorage::builder(self.state_keeper_cache_path.as_ref())
             .await
             .expect("Failed initializing state keeper cache");
 
@@ -241,77 +241,11 @@ impl BlockReverter {
         tracing::info!("rolling back postgres data...");
         let mut storage = self.connection_pool.access_storage().await.unwrap();
         let mut transaction = storage.start_transaction().await.unwrap();
-
-        let (_, last_miniblock_to_keep) = transaction
-            .blocks_dal()
-            .get_miniblock_range_of_l1_batch(last_l1_batch_to_keep)
-            .await
-            .unwrap()
-            .expect("L1 batch should contain at least one miniblock");
-
-        tracing::info!("rolling back transactions state...");
-        transaction
-            .transactions_dal()
-            .reset_transactions_state(last_miniblock_to_keep)
-            .await;
-        tracing::info!("rolling back events...");
-        transaction
-            .events_dal()
-            .rollback_events(last_miniblock_to_keep)
-            .await;
-        tracing::info!("rolling back l2 to l1 logs...");
-        transaction
-            .events_dal()
-            .rollback_l2_to_l1_logs(last_miniblock_to_keep)
-            .await;
-        tracing::info!("rolling back created tokens...");
-        transaction
-            .tokens_dal()
-            .rollback_tokens(last_miniblock_to_keep)
-            .await
-            .expect("failed rolling back created tokens");
-        tracing::info!("rolling back factory deps....");
-        transaction
-            .factory_deps_dal()
-            .rollback_factory_deps(last_miniblock_to_keep)
-            .await
-            .expect("Failed rolling back factory dependencies");
-        tracing::info!("rolling back storage...");
-        #[allow(deprecated)]
-        transaction
-            .storage_logs_dal()
-            .rollback_storage(last_miniblock_to_keep)
-            .await
-            .expect("failed rolling back storage");
-        tracing::info!("rolling back storage logs...");
-        transaction
-            .storage_logs_dal()
-            .rollback_storage_logs(last_miniblock_to_keep)
-            .await
-            .unwrap();
-        tracing::info!("rolling back eth_txs...");
-        transaction
-            .eth_sender_dal()
-            .delete_eth_txs(last_l1_batch_to_keep)
-            .await
-            .unwrap();
-        tracing::info!("rolling back l1 batches...");
-        transaction
-            .blocks_dal()
-            .delete_l1_batches(last_l1_batch_to_keep)
-            .await
-            .unwrap();
-        transaction
-            .blocks_dal()
-            .delete_initial_writes(last_l1_batch_to_keep)
-            .await
-            .unwrap();
-        tracing::info!("rolling back miniblocks...");
         transaction
             .blocks_dal()
-            .delete_miniblocks(last_miniblock_to_keep)
+            .rollback_to_l1_batch(last_l1_batch_to_keep)
             .await
-            .unwrap();
+            .expect("failed rolling back postgres data");
         if self.node_role == NodeRole::Main {
             tracing::info!("performing consensus hard fork");
             transaction.consensus_dal().fork().await.unwrap();
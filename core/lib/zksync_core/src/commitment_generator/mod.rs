@@ -9,8 +9,10 @@ use zksync_commitment_utils::{bootloader_initial_content_commitment, events_queu
 use zksync_dal::ConnectionPool;
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::i_executor::commit::kzg::pubdata_to_blob_commitments;
+use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_types::{
     commitment::{AuxCommitments, CommitmentCommonInput, CommitmentInput, L1BatchCommitment},
+    l2_to_l1_log::l2_to_l1_logs_tree_size,
     writes::{InitialStorageWrite, RepeatedStorageWrite, StateDiffRecord},
     L1BatchNumber, ProtocolVersionId, StorageKey, H256,
 };
@@ -247,8 +249,17 @@ impl CommitmentGenerator {
 
         let latency =
             METRICS.generate_commitment_latency_stage[&CommitmentStage::Calculate].start();
+        let l2_to_l1_logs_tree = MiniMerkleTree::new(
+            input
+                .common()
+                .l2_to_l1_logs
+                .iter()
+                .map(|log| log.0.to_bytes()),
+            Some(l2_to_l1_logs_tree_size(input.common().protocol_version)),
+        );
         let commitment = L1BatchCommitment::new(input);
         let artifacts = commitment.artifacts();
+        let (l2_to_l1_logs_root, l2_to_l1_log_paths) = l2_to_l1_logs_tree.merkle_root_and_paths();
         let latency = latency.observe();
         tracing::debug!(
             "Generated commitment artifacts for L1 batch #{l1_batch_number} in {latency:?}"
@@ -256,12 +267,21 @@ impl CommitmentGenerator {
 
         let latency =
             METRICS.generate_commitment_latency_stage[&CommitmentStage::SaveResults].start();
-        self.connection_pool
+        let mut connection = self
+            .connection_pool
             .access_storage_tagged("commitment_generator")
-            .await?
+            .await?;
+        // Proofs are saved before the commitment, so that they aren't lost if the generator is restarted
+        // in between (the batch won't be processed again once it has a commitment).
+        connection
+            .blocks_dal()
+            .save_l2_to_l1_log_proofs(l1_batch_number, l2_to_l1_logs_root, &l2_to_l1_log_paths)
+            .await?;
+        connection
             .blocks_dal()
             .save_l1_batch_commitment_artifacts(l1_batch_number, &artifacts)
             .await?;
+        drop(connection);
         let latency = latency.observe();
         tracing::debug!(
             "Stored commitment artifacts for L1 batch #{l1_batch_number} in {latency:?}"
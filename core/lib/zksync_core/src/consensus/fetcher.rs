@@ -3,6 +3,7 @@ use zksync_concurrency::{ctx, error::Wrap as _, limiter, scope, time};
 use zksync_consensus_executor as executor;
 use zksync_consensus_roles::validator;
 use zksync_consensus_storage::BlockStore;
+use zksync_dal::blocks_dal::SealedMiniblockListener;
 use zksync_types::MiniblockNumber;
 
 use crate::{
@@ -74,6 +75,18 @@ impl Fetcher {
             block_store
                 .set_cursor(cursor)
                 .context("block_store.set_cursor()")?;
+            // The state keeper persists fetched blocks to the local DB, so we can listen to its notifications.
+            match ctx
+                .wait(SealedMiniblockListener::new(&self.store.0))
+                .await?
+            {
+                Ok(listener) => block_store
+                    .set_sealed_miniblock_listener(listener)
+                    .context("block_store.set_sealed_miniblock_listener()")?,
+                Err(err) => tracing::warn!(
+                    "Failed creating sealed miniblock listener, falling back to polling: {err}"
+                ),
+            }
             let (block_store, runner) = BlockStore::new(ctx, Box::new(block_store))
                 .await
                 .wrap("BlockStore::new()")?;
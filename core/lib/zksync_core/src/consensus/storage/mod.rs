@@ -5,7 +5,7 @@ use zksync_concurrency::{ctx, error::Wrap as _, sync, time};
 use zksync_consensus_bft::PayloadManager;
 use zksync_consensus_roles::validator;
 use zksync_consensus_storage::{PersistentBlockStore, ReplicaState, ReplicaStore};
-use zksync_dal::{consensus_dal::Payload, ConnectionPool};
+use zksync_dal::{blocks_dal::SealedMiniblockListener, consensus_dal::Payload, ConnectionPool};
 use zksync_types::MiniblockNumber;
 
 #[cfg(test)]
@@ -179,6 +179,8 @@ pub(super) struct BlockStore {
     inner: Store,
     /// Mutex preventing concurrent execution of `store_next_block` calls.
     store_next_block_mutex: sync::Mutex<Option<Cursor>>,
+    /// Listener for sealed miniblocks used by `store_next_block` to avoid busy polling.
+    sealed_miniblock_listener: sync::Mutex<Option<SealedMiniblockListener>>,
 }
 
 impl Store {
@@ -187,6 +189,7 @@ impl Store {
         BlockStore {
             inner: self,
             store_next_block_mutex: sync::Mutex::new(None),
+            sealed_miniblock_listener: sync::Mutex::new(None),
         }
     }
 
@@ -243,6 +246,36 @@ impl BlockStore {
         *self.store_next_block_mutex.try_lock()? = Some(cursor);
         Ok(())
     }
+
+    /// Makes `store_next_block()` wait for DB notifications about sealed miniblocks instead of busy polling.
+    /// The listener must be connected to the DB the state keeper writes to.
+    pub fn set_sealed_miniblock_listener(
+        &mut self,
+        listener: SealedMiniblockListener,
+    ) -> anyhow::Result<()> {
+        *self.sealed_miniblock_listener.try_lock()? = Some(listener);
+        Ok(())
+    }
+
+    /// Waits until a new miniblock may be persisted, either by polling or by listening to DB notifications.
+    async fn wait_for_new_miniblock(
+        ctx: &ctx::Ctx,
+        listener: &mut Option<SealedMiniblockListener>,
+    ) -> ctx::Result<()> {
+        const POLL_INTERVAL: time::Duration = time::Duration::milliseconds(50);
+        // Polling is still necessary with DB notifications since they may be lost.
+        const NOTIFIED_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+        let Some(inner) = listener else {
+            return Ok(ctx.sleep(POLL_INTERVAL).await?);
+        };
+        let notification = tokio::time::timeout(NOTIFIED_POLL_INTERVAL, inner.recv());
+        if let Ok(Err(err)) = ctx.wait(notification).await? {
+            tracing::warn!("Sealed miniblock listener failed, falling back to polling: {err}");
+            *listener = None;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -335,7 +368,7 @@ impl PersistentBlockStore for BlockStore {
             };
             cursor.advance(block).await.context("cursor.advance()")?;
         }
-        const POLL_INTERVAL: time::Duration = time::Duration::milliseconds(50);
+        let mut listener = ctx.wait(self.sealed_miniblock_listener.lock()).await?;
         loop {
             let mut conn = self.inner.access(ctx).await.wrap("access()")?;
             let last = conn
@@ -351,7 +384,7 @@ impl PersistentBlockStore for BlockStore {
                 }
             }
             drop(conn);
-            ctx.sleep(POLL_INTERVAL).await?;
+            BlockStore::wait_for_new_miniblock(ctx, &mut listener).await?;
         }
     }
 }
@@ -3,7 +3,7 @@ use std::{convert::TryInto, sync::Arc};
 use tokio::sync::watch;
 use zksync_config::configs::eth_sender::SenderConfig;
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_dal::{ConnectionPool, StorageProcessor, TransactionRetryPolicy};
 use zksync_eth_client::{BoundEthInterface, CallFunctionArgs};
 use zksync_l1_contract_interface::{
     i_executor::commit::kzg::{KzgInfo, ZK_SYNC_BYTES_PER_BLOB},
@@ -119,14 +119,12 @@ impl EthTxAggregator {
         stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
         loop {
-            let mut storage = pool.access_storage_tagged("eth_sender").await.unwrap();
-
             if *stop_receiver.borrow() {
                 tracing::info!("Stop signal received, eth_tx_aggregator is shutting down");
                 break;
             }
 
-            if let Err(err) = self.loop_iteration(&mut storage).await {
+            if let Err(err) = self.loop_iteration(&pool).await {
                 // Web3 API request failures can cause this,
                 // and anything more important is already properly reported.
                 tracing::warn!("eth_sender error {err:?}");
@@ -339,11 +337,8 @@ impl EthTxAggregator {
         Ok(H256::from_tokens(vk_hash)?)
     }
 
-    #[tracing::instrument(skip(self, storage))]
-    async fn loop_iteration(
-        &mut self,
-        storage: &mut StorageProcessor<'_>,
-    ) -> Result<(), ETHSenderError> {
+    #[tracing::instrument(skip(self, pool))]
+    async fn loop_iteration(&mut self, pool: &ConnectionPool) -> Result<(), ETHSenderError> {
         let MulticallData {
             base_system_contracts_hashes,
             verifier_params,
@@ -366,20 +361,25 @@ impl EthTxAggregator {
             params: verifier_params,
             recursion_scheduler_level_vk_hash,
         };
-        if let Some(agg_op) = self
+        let mut storage = pool.access_storage_tagged("eth_sender").await.unwrap();
+        let agg_op = self
             .aggregator
             .get_next_ready_operation(
-                storage,
+                &mut storage,
                 base_system_contracts_hashes,
                 protocol_version_id,
                 l1_verifier_config,
             )
-            .await
-        {
+            .await;
+        // The pool may be a singleton one, so the connection must be released before saving the operation.
+        drop(storage);
+
+        if let Some(agg_op) = agg_op {
             let tx = self
-                .save_eth_tx(storage, &agg_op, contracts_are_pre_shared_bridge)
+                .save_eth_tx(pool, &agg_op, contracts_are_pre_shared_bridge)
                 .await?;
-            Self::report_eth_tx_saving(storage, agg_op, &tx).await;
+            let mut storage = pool.access_storage_tagged("eth_sender").await.unwrap();
+            Self::report_eth_tx_saving(&mut storage, agg_op, &tx).await;
         }
         Ok(())
     }
@@ -517,13 +517,14 @@ impl EthTxAggregator {
         TxData { calldata, sidecar }
     }
 
+    /// Saves an `eth_tx` for the aggregated operation and links the operation's L1 batches to it.
+    /// The DB transaction is retried on transient errors.
     pub(super) async fn save_eth_tx(
         &self,
-        storage: &mut StorageProcessor<'_>,
+        pool: &ConnectionPool,
         aggregated_op: &AggregatedOperation,
         contracts_are_pre_shared_bridge: bool,
     ) -> Result<EthTx, ETHSenderError> {
-        let mut transaction = storage.start_transaction().await.unwrap();
         let op_type = aggregated_op.get_action_type();
         // We may be using a custom sender for commit transactions, so use this
         // var whatever it actually is: a `None` for single-addr operator or `Some`
@@ -532,61 +533,63 @@ impl EthTxAggregator {
             AggregatedActionType::Commit => self.custom_commit_sender_addr,
             _ => None,
         };
-        let nonce = self.get_next_nonce(&mut transaction, sender_addr).await?;
+        let base_nonce = self.base_nonce(sender_addr);
         let encoded_aggregated_op =
             self.encode_aggregated_op(aggregated_op, contracts_are_pre_shared_bridge);
         let l1_batch_number_range = aggregated_op.l1_batch_range();
-
-        let predicted_gas_for_batches = transaction
-            .blocks_dal()
-            .get_l1_batches_predicted_gas(l1_batch_number_range.clone(), op_type)
-            .await
-            .unwrap();
-        let eth_tx_predicted_gas = agg_l1_batch_base_cost(op_type) + predicted_gas_for_batches;
-
-        let eth_tx = transaction
-            .eth_sender_dal()
-            .save_eth_tx(
-                nonce,
-                encoded_aggregated_op.calldata,
-                op_type,
-                self.timelock_contract_address,
-                eth_tx_predicted_gas,
-                sender_addr,
-                encoded_aggregated_op.sidecar,
-            )
-            .await
-            .unwrap();
-
-        transaction
-            .blocks_dal()
-            .set_eth_tx_id(l1_batch_number_range, eth_tx.id, op_type)
-            .await
-            .unwrap();
-        transaction.commit().await.unwrap();
-        Ok(eth_tx)
+        let timelock_contract_address = self.timelock_contract_address;
+
+        let retry_policy = TransactionRetryPolicy::default();
+        pool.transaction_with_retries("save_eth_tx", &retry_policy, |transaction| {
+            let calldata = encoded_aggregated_op.calldata.clone();
+            let sidecar = encoded_aggregated_op.sidecar.clone();
+            let l1_batch_number_range = l1_batch_number_range.clone();
+            Box::pin(async move {
+                // Between server starts we can execute some txs using operator account or remove some txs from the database.
+                // At the start we have to consider this fact and get the max nonce.
+                let db_nonce = transaction
+                    .eth_sender_dal()
+                    .get_next_nonce(sender_addr)
+                    .await?
+                    .unwrap_or(0);
+                let nonce = db_nonce.max(base_nonce);
+
+                let predicted_gas_for_batches = transaction
+                    .blocks_dal()
+                    .get_l1_batches_predicted_gas(l1_batch_number_range.clone(), op_type)
+                    .await?;
+                let eth_tx_predicted_gas =
+                    agg_l1_batch_base_cost(op_type) + predicted_gas_for_batches;
+
+                let eth_tx = transaction
+                    .eth_sender_dal()
+                    .save_eth_tx(
+                        nonce,
+                        calldata,
+                        op_type,
+                        timelock_contract_address,
+                        eth_tx_predicted_gas,
+                        sender_addr,
+                        sidecar,
+                    )
+                    .await?;
+                transaction
+                    .blocks_dal()
+                    .set_eth_tx_id(l1_batch_number_range, eth_tx.id, op_type)
+                    .await?;
+                Ok(eth_tx)
+            })
+        })
+        .await
+        .map_err(ETHSenderError::Dal)
     }
 
-    async fn get_next_nonce(
-        &self,
-        storage: &mut StorageProcessor<'_>,
-        from_addr: Option<Address>,
-    ) -> Result<u64, ETHSenderError> {
-        let db_nonce = storage
-            .eth_sender_dal()
-            .get_next_nonce(from_addr)
-            .await
-            .unwrap()
-            .unwrap_or(0);
-        // Between server starts we can execute some txs using operator account or remove some txs from the database
-        // At the start we have to consider this fact and get the max nonce.
-        Ok(if from_addr.is_none() {
-            db_nonce.max(self.base_nonce)
+    fn base_nonce(&self, from_addr: Option<Address>) -> u64 {
+        if from_addr.is_none() {
+            self.base_nonce
         } else {
-            db_nonce.max(
-                self.base_nonce_custom_commit_sender
-                    .expect("custom base nonce is expected to be initialized; qed"),
-            )
-        })
+            self.base_nonce_custom_commit_sender
+                .expect("custom base nonce is expected to be initialized; qed")
+        }
     }
 }
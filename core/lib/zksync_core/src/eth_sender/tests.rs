@@ -170,11 +170,7 @@ async fn confirm_many(aggregator_operate_4844_mode: bool) -> anyhow::Result<()>
     for _ in 0..5 {
         let tx = tester
             .aggregator
-            .save_eth_tx(
-                &mut tester.conn.access_storage().await.unwrap(),
-                &DUMMY_OPERATION,
-                true,
-            )
+            .save_eth_tx(&tester.conn, &DUMMY_OPERATION, true)
             .await?;
         let hash = tester
             .manager
@@ -249,11 +245,7 @@ async fn resend_each_block() -> anyhow::Result<()> {
     let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
     let tx = tester
         .aggregator
-        .save_eth_tx(
-            &mut tester.conn.access_storage().await.unwrap(),
-            &DUMMY_OPERATION,
-            true,
-        )
+        .save_eth_tx(&tester.conn, &DUMMY_OPERATION, true)
         .await?;
 
     let hash = tester
@@ -354,11 +346,7 @@ async fn dont_resend_already_mined() -> anyhow::Result<()> {
     let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false, false).await;
     let tx = tester
         .aggregator
-        .save_eth_tx(
-            &mut tester.conn.access_storage().await.unwrap(),
-            &DUMMY_OPERATION,
-            true,
-        )
+        .save_eth_tx(&tester.conn, &DUMMY_OPERATION, true)
         .await
         .unwrap();
 
@@ -429,11 +417,7 @@ async fn three_scenarios() -> anyhow::Result<()> {
     for _ in 0..3 {
         let tx = tester
             .aggregator
-            .save_eth_tx(
-                &mut tester.conn.access_storage().await.unwrap(),
-                &DUMMY_OPERATION,
-                true,
-            )
+            .save_eth_tx(&tester.conn, &DUMMY_OPERATION, true)
             .await
             .unwrap();
 
@@ -500,11 +484,7 @@ async fn failed_eth_tx() {
 
     let tx = tester
         .aggregator
-        .save_eth_tx(
-            &mut tester.conn.access_storage().await.unwrap(),
-            &DUMMY_OPERATION,
-            true,
-        )
+        .save_eth_tx(&tester.conn, &DUMMY_OPERATION, true)
         .await
         .unwrap();
 
@@ -981,11 +961,7 @@ async fn send_operation(
 ) -> H256 {
     let tx = tester
         .aggregator
-        .save_eth_tx(
-            &mut tester.conn.access_storage().await.unwrap(),
-            &aggregated_operation,
-            true,
-        )
+        .save_eth_tx(&tester.conn, &aggregated_operation, true)
         .await
         .unwrap();
 
@@ -96,6 +96,20 @@ impl L1BatchMetricsReporter {
             APP_METRICS.blocks_state_block_eth_stage_latency[&L1StageLatencyLabel::UnexecutedBlock]
                 .set(now.saturating_sub(timestamp));
         }
+
+        let pending_batch_info = conn.blocks_dal().pending_batch_info().await.unwrap();
+        let miniblock_count = pending_batch_info
+            .miniblocks
+            .map_or(0, |range| range.end().0 - range.start().0 + 1);
+        APP_METRICS
+            .pending_l1_batch_miniblocks
+            .set(miniblock_count.into());
+        APP_METRICS
+            .pending_l1_batch_txs
+            .set(pending_batch_info.tx_count().into());
+        APP_METRICS
+            .pending_l1_batch_estimated_pubdata
+            .set(pending_batch_info.estimated_pubdata);
     }
 }
 
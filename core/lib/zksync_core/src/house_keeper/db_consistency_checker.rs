@@ -0,0 +1,115 @@
+use anyhow::Context as _;
+use async_trait::async_trait;
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+use zksync_dal::{consistency_dal::Inconsistency, ConnectionPool};
+use zksync_types::L1BatchNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+enum InconsistencyKind {
+    TxCounts,
+    LogsBloom,
+    L2ToL1LogsRoot,
+}
+
+impl From<&Inconsistency> for InconsistencyKind {
+    fn from(inconsistency: &Inconsistency) -> Self {
+        match inconsistency {
+            Inconsistency::TxCounts { .. } => Self::TxCounts,
+            Inconsistency::LogsBloom { .. } => Self::LogsBloom,
+            Inconsistency::L2ToL1LogsRoot { .. } => Self::L2ToL1LogsRoot,
+        }
+    }
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_db_consistency_checker")]
+struct DbConsistencyCheckerMetrics {
+    /// Number of inconsistencies found in derived L1 batch data.
+    inconsistencies: Family<InconsistencyKind, Counter>,
+    /// Number of the last checked L1 batch.
+    last_checked_l1_batch: Gauge<u64>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<DbConsistencyCheckerMetrics> = vise::Global::new();
+
+/// Checks derived data of new L1 batches by recomputing it from raw rows, so that silent Postgres corruption
+/// is detected early. See [`ConsistencyDal::check_l1_batch()`](zksync_dal::consistency_dal::ConsistencyDal::check_l1_batch())
+/// for the list of checks.
+///
+/// The checker starts from the latest L1 batch with a generated commitment and follows new batches.
+/// Found inconsistencies are logged and reported as metrics; they don't stop the checker.
+#[derive(Debug)]
+pub struct DbConsistencyChecker {
+    check_interval_ms: u64,
+    pool: ConnectionPool,
+    next_l1_batch: Option<L1BatchNumber>,
+}
+
+impl DbConsistencyChecker {
+    /// Maximum number of L1 batches checked in a single iteration.
+    const MAX_L1_BATCHES_PER_ITERATION: u32 = 10;
+
+    pub fn new(check_interval_ms: u64, pool: ConnectionPool) -> Self {
+        Self {
+            check_interval_ms,
+            pool,
+            next_l1_batch: None,
+        }
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for DbConsistencyChecker {
+    const SERVICE_NAME: &'static str = "DbConsistencyChecker";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("db_consistency_checker")
+            .await?;
+        let last_l1_batch = storage
+            .consistency_dal()
+            .get_last_checkable_l1_batch()
+            .await
+            .context("get_last_checkable_l1_batch()")?;
+        let Some(last_l1_batch) = last_l1_batch else {
+            return Ok(());
+        };
+        let next_l1_batch = *self.next_l1_batch.get_or_insert(last_l1_batch);
+        if next_l1_batch > last_l1_batch {
+            return Ok(());
+        }
+        let last_l1_batch =
+            last_l1_batch.min(next_l1_batch + (Self::MAX_L1_BATCHES_PER_ITERATION - 1));
+
+        for number in next_l1_batch.0..=last_l1_batch.0 {
+            let l1_batch_number = L1BatchNumber(number);
+            let inconsistencies = storage
+                .consistency_dal()
+                .check_l1_batch(l1_batch_number)
+                .await
+                .with_context(|| format!("failed checking L1 batch #{l1_batch_number}"))?
+                .with_context(|| {
+                    format!("L1 batch #{l1_batch_number} disappeared from Postgres")
+                })?;
+            for inconsistency in &inconsistencies {
+                tracing::error!(
+                    "Found inconsistency in L1 batch #{l1_batch_number}: {inconsistency:?}"
+                );
+                METRICS.inconsistencies[&inconsistency.into()].inc();
+            }
+            METRICS.last_checked_l1_batch.set(number.into());
+        }
+        tracing::debug!("Checked consistency of L1 batches #{next_l1_batch}..={last_l1_batch}");
+        self.next_l1_batch = Some(last_l1_batch + 1);
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.check_interval_ms
+    }
+}
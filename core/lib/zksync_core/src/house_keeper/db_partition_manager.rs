@@ -0,0 +1,430 @@
+use std::{
+    future::Future,
+    ops,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use tokio::sync::watch;
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Metrics};
+use zksync_dal::{
+    partitions_dal::{PartitionBounds, PartitionedTable},
+    ConnectionPool, StorageProcessor,
+};
+use zksync_types::MiniblockNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "table", rename_all = "snake_case")]
+enum TableLabel {
+    Events,
+}
+
+impl From<PartitionedTable> for TableLabel {
+    fn from(table: PartitionedTable) -> Self {
+        match table {
+            PartitionedTable::Events => Self::Events,
+        }
+    }
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_db_partition_manager")]
+struct DbPartitionManagerMetrics {
+    /// Number of created table partitions.
+    created_partitions: Family<TableLabel, Counter>,
+    /// Number of detached table partitions.
+    detached_partitions: Family<TableLabel, Counter>,
+    /// Number of re-attached table partitions.
+    attached_partitions: Family<TableLabel, Counter>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<DbPartitionManagerMetrics> = vise::Global::new();
+
+/// Cached miniblock ranges of detached `events` partitions.
+///
+/// Used by the API server to report archived events without querying Postgres catalogs on each `eth_getLogs` call.
+/// Partitions are only detached or re-attached by [`DbPartitionManager`] runs, so it's sufficient to refresh
+/// the cache on a schedule. Until a refresh, events in a just detached range may be silently missing from responses.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DetachedEventsRanges(Arc<RwLock<Vec<ops::Range<MiniblockNumber>>>>);
+
+impl DetachedEventsRanges {
+    /// Creates a handle to the cached ranges together with a task that will refresh them on a schedule.
+    pub fn new(
+        pool: ConnectionPool,
+        update_interval: Duration,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> (Self, impl Future<Output = anyhow::Result<()>>) {
+        let this = Self::default();
+        let ranges_updater = this.clone();
+
+        let update_task = async move {
+            loop {
+                if *stop_receiver.borrow() {
+                    tracing::debug!("Stopping detached events ranges updates");
+                    return Ok(());
+                }
+
+                let mut storage = pool.access_storage_tagged("api").await?;
+                let ranges = storage
+                    .partitions_dal()
+                    .get_detached_ranges(PartitionedTable::Events)
+                    .await
+                    .context("get_detached_ranges()")?;
+                drop(storage);
+
+                *ranges_updater.0.write().unwrap() = ranges;
+                tokio::time::sleep(update_interval).await;
+            }
+        };
+        (this, update_task)
+    }
+
+    /// Returns the first detached range overlapping the specified miniblocks.
+    pub fn find_overlapping(
+        &self,
+        miniblocks: ops::RangeInclusive<MiniblockNumber>,
+    ) -> Option<ops::Range<MiniblockNumber>> {
+        let ranges = self.0.read().unwrap();
+        ranges
+            .iter()
+            .find(|range| range.start <= *miniblocks.end() && *miniblocks.start() < range.end)
+            .cloned()
+    }
+}
+
+/// Maintains range partitions of the `events` table.
+///
+/// Partitions are created ahead of the sealed miniblock, so that new data doesn't end up in the default partition.
+/// If the archival retention is configured, partitions for L1 batches executed on L1 more than
+/// the retention period ago are detached; the detached partitions can then be dumped and dropped by the operator.
+/// While detached, events in the corresponding miniblock range are reported as unavailable by the API server
+/// (see [`DetachedEventsRanges`]). Conversely, detached partitions still present in the database (e.g., restored
+/// from a dump) are re-attached if they are no longer covered by the retention period, e.g., because it was increased
+/// or archival was disabled.
+#[derive(Debug)]
+pub struct DbPartitionManager {
+    partition_size: u32,
+    events_archival_retention: Option<Duration>,
+    polling_interval_ms: u64,
+    pool: ConnectionPool,
+}
+
+impl DbPartitionManager {
+    /// Number of partitions created ahead of the sealed miniblock.
+    const PARTITIONS_AHEAD: u32 = 2;
+
+    pub fn new(
+        partition_size: u32,
+        events_archival_retention: Option<Duration>,
+        polling_interval_ms: u64,
+        pool: ConnectionPool,
+    ) -> Self {
+        assert!(partition_size > 0, "Partition size must be positive");
+        Self {
+            partition_size,
+            events_archival_retention,
+            polling_interval_ms,
+            pool,
+        }
+    }
+
+    async fn create_partitions(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        table: PartitionedTable,
+        sealed_miniblock: MiniblockNumber,
+    ) -> anyhow::Result<()> {
+        let partitions = storage
+            .partitions_dal()
+            .get_partitions(table)
+            .await
+            .context("get_partitions()")?;
+        let mut covered_until = partitions
+            .iter()
+            .filter_map(|partition| match partition.bounds {
+                PartitionBounds::Range { end, .. } => Some(end),
+                PartitionBounds::Default => None,
+            })
+            .max()
+            .unwrap_or(MiniblockNumber(0));
+
+        let target = sealed_miniblock
+            .0
+            .saturating_add(self.partition_size.saturating_mul(Self::PARTITIONS_AHEAD));
+        while covered_until.0 <= target {
+            // Align partition ends to the partition size for more readable partition names.
+            let end = (covered_until.0 / self.partition_size + 1) * self.partition_size;
+            let miniblocks = covered_until..MiniblockNumber(end);
+            storage
+                .partitions_dal()
+                .create_partition(table, miniblocks.clone())
+                .await
+                .with_context(|| {
+                    format!("failed creating {table:?} partition for {miniblocks:?}")
+                })?;
+            tracing::info!("Created {table:?} partition for miniblocks {miniblocks:?}");
+            METRICS.created_partitions[&table.into()].inc();
+            covered_until = miniblocks.end;
+        }
+        Ok(())
+    }
+
+    /// Returns the last miniblock for which events should be archived, or `None` if no events should be archived.
+    async fn last_archived_miniblock(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<MiniblockNumber>> {
+        let Some(retention) = self.events_archival_retention else {
+            return Ok(None);
+        };
+        let last_archived_l1_batch = storage
+            .pruning_dal()
+            .get_last_l1_batch_executed_before(retention)
+            .await
+            .context("get_last_l1_batch_executed_before()")?;
+        let Some(last_archived_l1_batch) = last_archived_l1_batch else {
+            return Ok(None);
+        };
+        let (_, last_archived_miniblock) = storage
+            .blocks_web3_dal()
+            .get_miniblock_range_of_l1_batch(last_archived_l1_batch)
+            .await
+            .context("get_miniblock_range_of_l1_batch()")?
+            .with_context(|| format!("L1 batch #{last_archived_l1_batch} has no miniblocks"))?;
+        Ok(Some(last_archived_miniblock))
+    }
+
+    async fn attach_events_partitions(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        last_archived_miniblock: Option<MiniblockNumber>,
+    ) -> anyhow::Result<()> {
+        let table = PartitionedTable::Events;
+        let detached_partitions = storage
+            .partitions_dal()
+            .get_detached_partitions(table)
+            .await
+            .context("get_detached_partitions()")?;
+        for miniblocks in detached_partitions {
+            if last_archived_miniblock.map_or(false, |last| miniblocks.end <= last + 1) {
+                continue;
+            }
+            storage
+                .partitions_dal()
+                .attach_partition(table, miniblocks.clone())
+                .await
+                .with_context(|| {
+                    format!("failed attaching {table:?} partition for {miniblocks:?}")
+                })?;
+            tracing::info!("Re-attached {table:?} partition for miniblocks {miniblocks:?}");
+            METRICS.attached_partitions[&table.into()].inc();
+        }
+        Ok(())
+    }
+
+    async fn detach_events_partitions(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        last_archived_miniblock: MiniblockNumber,
+    ) -> anyhow::Result<()> {
+        let table = PartitionedTable::Events;
+        let partitions = storage
+            .partitions_dal()
+            .get_partitions(table)
+            .await
+            .context("get_partitions()")?;
+        for partition in partitions {
+            let PartitionBounds::Range { end, .. } = partition.bounds else {
+                continue;
+            };
+            if end > last_archived_miniblock + 1 {
+                continue;
+            }
+            storage
+                .partitions_dal()
+                .detach_partition(table, &partition.name)
+                .await
+                .with_context(|| format!("failed detaching partition {}", partition.name))?;
+            tracing::info!(
+                "Detached {table:?} partition {} for miniblocks before #{end}",
+                partition.name
+            );
+            METRICS.detached_partitions[&table.into()].inc();
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for DbPartitionManager {
+    const SERVICE_NAME: &'static str = "DbPartitionManager";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("db_partition_manager")
+            .await?;
+        let sealed_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("get_sealed_miniblock_number()")?
+            .unwrap_or(MiniblockNumber(0));
+        for table in PartitionedTable::ALL {
+            self.create_partitions(&mut storage, table, sealed_miniblock)
+                .await?;
+        }
+        let last_archived_miniblock = self.last_archived_miniblock(&mut storage).await?;
+        self.attach_events_partitions(&mut storage, last_archived_miniblock)
+            .await?;
+        if let Some(last_archived_miniblock) = last_archived_miniblock {
+            self.detach_events_partitions(&mut storage, last_archived_miniblock)
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.polling_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops;
+
+    use zksync_types::{
+        aggregated_operations::AggregatedActionType, Address, L1BatchNumber, ProtocolVersion, H256,
+        U256,
+    };
+
+    use super::*;
+    use crate::utils::testonly::{create_l1_batch, create_miniblock};
+
+    async fn execute_l1_batches(
+        storage: &mut StorageProcessor<'_>,
+        l1_batches: ops::RangeInclusive<L1BatchNumber>,
+    ) {
+        let action_type = AggregatedActionType::Execute;
+        let eth_tx = storage
+            .eth_sender_dal()
+            .save_eth_tx(0, vec![], action_type, Address::zero(), 0, None, None)
+            .await
+            .unwrap();
+        let tx_hash = H256::repeat_byte(1);
+        storage
+            .eth_sender_dal()
+            .insert_tx_history(eth_tx.id, 0, 0, None, tx_hash, &[])
+            .await
+            .unwrap();
+        storage
+            .blocks_dal()
+            .set_eth_tx_id(l1_batches, eth_tx.id, action_type)
+            .await
+            .unwrap();
+        storage
+            .eth_sender_dal()
+            .confirm_tx(tx_hash, U256::zero())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn detaching_and_reattaching_events_partitions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 0..6 {
+            storage
+                .blocks_dal()
+                .insert_miniblock(&create_miniblock(number))
+                .await
+                .unwrap();
+            storage
+                .blocks_dal()
+                .insert_mock_l1_batch(&create_l1_batch(number))
+                .await
+                .unwrap();
+            storage
+                .blocks_dal()
+                .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+                .await
+                .unwrap();
+        }
+
+        let table = PartitionedTable::Events;
+        let retention = Some(Duration::ZERO);
+        let mut manager = DbPartitionManager::new(2, retention, 0, pool.clone());
+        manager.run_routine_task().await.unwrap();
+        let partitions = storage
+            .partitions_dal()
+            .get_partitions(table)
+            .await
+            .unwrap();
+        let partition_names: Vec<_> = partitions.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            partition_names,
+            [
+                "events_legacy",
+                "events_0_2",
+                "events_2_4",
+                "events_4_6",
+                "events_6_8",
+                "events_8_10",
+                "events_default"
+            ]
+        );
+
+        // Miniblocks #1 and #2 are executed; only the partition fully covered by them (and the legacy partition
+        // preceding it) should be detached.
+        execute_l1_batches(&mut storage, L1BatchNumber(1)..=L1BatchNumber(2)).await;
+        manager.run_routine_task().await.unwrap();
+        let expected_range = MiniblockNumber(0)..MiniblockNumber(2);
+        let detached_ranges = storage
+            .partitions_dal()
+            .get_detached_ranges(table)
+            .await
+            .unwrap();
+        assert_eq!(detached_ranges, [expected_range.clone()]);
+        let detached_partitions = storage
+            .partitions_dal()
+            .get_detached_partitions(table)
+            .await
+            .unwrap();
+        assert_eq!(detached_partitions, [expected_range]);
+
+        // Running the manager again with the same config should be a no-op.
+        manager.run_routine_task().await.unwrap();
+        let detached_partitions = storage
+            .partitions_dal()
+            .get_detached_partitions(table)
+            .await
+            .unwrap();
+        assert_eq!(detached_partitions.len(), 1);
+
+        // Disabling archival should re-attach the detached partition.
+        let mut manager = DbPartitionManager::new(2, None, 0, pool.clone());
+        manager.run_routine_task().await.unwrap();
+        let detached_ranges = storage
+            .partitions_dal()
+            .get_detached_ranges(table)
+            .await
+            .unwrap();
+        assert_eq!(detached_ranges, []);
+        let detached_partitions = storage
+            .partitions_dal()
+            .get_detached_partitions(table)
+            .await
+            .unwrap();
+        assert_eq!(detached_partitions, []);
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use zksync_dal::{pruning_dal::PruningStats, ConnectionPool};
+use zksync_types::L1BatchNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+enum PrunedRowsKind {
+    Events,
+    CallTraces,
+    StorageLogs,
+    Transactions,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_db_pruner")]
+struct DbPrunerMetrics {
+    /// Number of rows deleted or updated by DB pruning.
+    pruned_rows: Family<PrunedRowsKind, Counter>,
+    /// Latency of pruning a chunk of L1 batches.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    chunk_latency: Histogram<Duration>,
+    /// Number of the last pruned L1 batch.
+    last_pruned_l1_batch: Gauge<u64>,
+}
+
+impl DbPrunerMetrics {
+    fn observe_stats(&self, stats: &PruningStats) {
+        self.pruned_rows[&PrunedRowsKind::Events].inc_by(stats.deleted_events);
+        self.pruned_rows[&PrunedRowsKind::CallTraces].inc_by(stats.deleted_call_traces);
+        self.pruned_rows[&PrunedRowsKind::StorageLogs].inc_by(stats.deleted_storage_logs);
+        self.pruned_rows[&PrunedRowsKind::Transactions].inc_by(stats.pruned_transactions);
+    }
+}
+
+#[vise::register]
+static METRICS: vise::Global<DbPrunerMetrics> = vise::Global::new();
+
+/// Prunes Postgres data for L1 batches executed on L1 more than the configured retention period ago.
+/// See [`PruningDal::prune_l1_batches()`](zksync_dal::pruning_dal::PruningDal::prune_l1_batches())
+/// for the list of pruned data.
+#[derive(Debug)]
+pub struct DbPruner {
+    retention: Duration,
+    pruning_interval_ms: u64,
+    pool: ConnectionPool,
+}
+
+impl DbPruner {
+    /// Maximum number of L1 batches pruned in a single DB transaction.
+    const MAX_L1_BATCHES_PER_CHUNK: u32 = 10;
+
+    pub fn new(retention: Duration, pruning_interval_ms: u64, pool: ConnectionPool) -> Self {
+        Self {
+            retention,
+            pruning_interval_ms,
+            pool,
+        }
+    }
+
+    /// Prunes the next chunk of L1 batches. Returns the last pruned L1 batch, or `None` if there's nothing to prune.
+    async fn prune_next_chunk(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        let mut storage = self.pool.access_storage_tagged("db_pruner").await?;
+        let last_prunable_l1_batch = storage
+            .pruning_dal()
+            .get_last_l1_batch_executed_before(self.retention)
+            .await
+            .context("get_last_l1_batch_executed_before()")?;
+        let Some(last_prunable_l1_batch) = last_prunable_l1_batch else {
+            return Ok(None);
+        };
+        let next_l1_batch = storage
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .context("get_pruning_info()")?
+            .map_or(L1BatchNumber(0), |info| info.last_pruned_l1_batch + 1);
+        if next_l1_batch > last_prunable_l1_batch {
+            return Ok(None);
+        }
+
+        let last_l1_batch =
+            last_prunable_l1_batch.min(next_l1_batch + (Self::MAX_L1_BATCHES_PER_CHUNK - 1));
+        let (_, last_miniblock) = storage
+            .blocks_web3_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch)
+            .await
+            .context("get_miniblock_range_of_l1_batch()")?
+            .with_context(|| format!("L1 batch #{last_l1_batch} has no miniblocks"))?;
+
+        let latency = METRICS.chunk_latency.start();
+        let stats = storage
+            .pruning_dal()
+            .prune_l1_batches(last_l1_batch, last_miniblock)
+            .await
+            .with_context(|| {
+                format!("failed pruning L1 batches #{next_l1_batch}..={last_l1_batch}")
+            })?;
+        let latency = latency.observe();
+
+        METRICS.observe_stats(&stats);
+        METRICS.last_pruned_l1_batch.set(last_l1_batch.0.into());
+        tracing::info!(
+            "Pruned L1 batches #{next_l1_batch}..={last_l1_batch} (up to miniblock #{last_miniblock}) \
+             in {latency:?}: {stats:?}"
+        );
+        Ok(Some(last_l1_batch))
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for DbPruner {
+    const SERVICE_NAME: &'static str = "DbPruner";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        while self.prune_next_chunk().await?.is_some() {
+            // Continue pruning until we've caught up with the retention period.
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.pruning_interval_ms
+    }
+}
@@ -1,4 +1,7 @@
 pub mod blocks_state_reporter;
+pub mod db_consistency_checker;
+pub mod db_partition_manager;
+pub mod db_pruner;
 pub mod fri_proof_compressor_job_retry_manager;
 pub mod fri_proof_compressor_queue_monitor;
 pub mod fri_prover_job_retry_manager;
@@ -32,7 +32,7 @@ use zksync_config::{
     ApiConfig, ContractsConfig, DBConfig, ETHSenderConfig, PostgresConfig,
 };
 use zksync_contracts::{governance_contract, BaseSystemContracts};
-use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
+use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool, PoolComponent};
 use zksync_eth_client::{
     clients::{PKSigningClient, QueryClient},
     BoundEthInterface, CallFunctionArgs, EthInterface,
@@ -40,7 +40,7 @@ use zksync_eth_client::{
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_queued_job_processor::JobProcessor;
-use zksync_state::PostgresStorageCaches;
+use zksync_state::{PostgresStorageCaches, RocksdbArchive};
 use zksync_types::{
     fee_model::FeeModelConfig,
     protocol_version::{L1VerifierConfig, VerifierParams},
@@ -51,12 +51,18 @@ use zksync_types::{
 
 use crate::{
     api_server::{
+        archive_state::ArchiveStateUpdater,
         contract_verification,
         execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
         healthcheck::HealthCheckHandle,
         tree::TreeApiHttpClient,
         tx_sender::{ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig},
-        web3::{self, state::InternalApiConfig, Namespace},
+        web3::{
+            self,
+            backend_jsonrpsee::{ApiKeyLimits, CorsConfig, RpcAuthConfig},
+            state::InternalApiConfig,
+            Namespace,
+        },
     },
     basic_witness_input_producer::BasicWitnessInputProducer,
     commitment_generator::CommitmentGenerator,
@@ -64,6 +70,8 @@ use crate::{
     eth_watch::start_eth_watch,
     house_keeper::{
         blocks_state_reporter::L1BatchMetricsReporter,
+        db_consistency_checker::DbConsistencyChecker, db_partition_manager::DbPartitionManager,
+        db_pruner::DbPruner,
         fri_proof_compressor_job_retry_manager::FriProofCompressorJobRetryManager,
         fri_proof_compressor_queue_monitor::FriProofCompressorStatsReporter,
         fri_prover_job_retry_manager::FriProverJobRetryManager,
@@ -78,7 +86,9 @@ use crate::{
     metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
     metrics::{InitStage, APP_METRICS},
     state_keeper::{
-        create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer, SequencerSealer,
+        create_state_keeper,
+        tx_filter::{DenyListFilter, TxLimits},
+        MempoolFetcher, MempoolGuard, MiniblockSealer, SequencerSealer, ShadowMempoolMirror,
     },
 };
 
@@ -116,6 +126,7 @@ pub async fn genesis_init(
 ) -> anyhow::Result<()> {
     let db_url = postgres_config.master_url()?;
     let pool = ConnectionPool::singleton(db_url)
+        .set_schema(postgres_config.schema())
         .build()
         .await
         .context("failed to build connection_pool")?;
@@ -204,6 +215,7 @@ pub async fn genesis_init(
 pub async fn is_genesis_needed(postgres_config: &PostgresConfig) -> bool {
     let db_url = postgres_config.master_url().unwrap();
     let pool = ConnectionPool::singleton(db_url)
+        .set_schema(postgres_config.schema())
         .build()
         .await
         .expect("failed to build connection_pool");
@@ -323,18 +335,42 @@ pub async fn initialize_components(
 
     let pool_size = postgres_config.max_connections()?;
     let connection_pool = ConnectionPool::builder(postgres_config.master_url()?, pool_size)
+        .set_schema(postgres_config.schema())
         .build()
         .await
         .context("failed to build connection_pool")?;
     // We're most interested in setting acquire / statement timeouts for the API server, which puts the most load
-    // on Postgres.
+    // on Postgres. For the same reason, the API server load is balanced among all replicas, with failover to master.
     let replica_connection_pool =
         ConnectionPool::builder(postgres_config.replica_url()?, pool_size)
+            .add_replica_urls(postgres_config.additional_replica_urls())
+            .set_failover_url(postgres_config.master_url.as_deref())
             .set_acquire_timeout(postgres_config.acquire_timeout())
             .set_statement_timeout(postgres_config.statement_timeout())
+            .set_schema(postgres_config.schema())
             .build()
             .await
             .context("failed to build replica_connection_pool")?;
+    // Components with a configured connection quota get a dedicated pool with the reserved number of connections,
+    // so that e.g. a burst of API traffic doesn't starve the state keeper.
+    let component_pool = |component, max_connections: Option<u32>| {
+        max_connections.map(|max_connections| connection_pool.sub_pool(component, max_connections))
+    };
+    let api_connection_pool =
+        component_pool(PoolComponent::Api, postgres_config.api_max_connections)
+            .unwrap_or_else(|| connection_pool.clone());
+    let state_keeper_pool = component_pool(
+        PoolComponent::StateKeeper,
+        postgres_config.state_keeper_max_connections,
+    );
+    let witness_generator_pool = component_pool(
+        PoolComponent::WitnessGenerator,
+        postgres_config.witness_generator_max_connections,
+    );
+    let house_keeper_pool = component_pool(
+        PoolComponent::HouseKeeper,
+        postgres_config.house_keeper_max_connections,
+    );
 
     let health_check_config = configs
         .health_check_config
@@ -433,6 +469,19 @@ pub async fn initialize_components(
         // terminate immediately if storage caches are dropped, which will lead to the (unexpected)
         // program termination.
         let mut storage_caches = None;
+        let archive_state =
+            if components.contains(&Component::HttpApi) || components.contains(&Component::WsApi) {
+                build_archive_state(
+                    &api_config.web3_json_rpc,
+                    &replica_connection_pool,
+                    &mut task_futures,
+                    stop_receiver.clone(),
+                )
+                .await
+                .context("build_archive_state()")?
+            } else {
+                None
+            };
 
         if components.contains(&Component::HttpApi) {
             storage_caches = Some(
@@ -458,12 +507,13 @@ pub async fn initialize_components(
                 &state_keeper_config,
                 &internal_api_config,
                 &api_config,
-                connection_pool.clone(),
+                api_connection_pool.clone(),
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 batch_fee_input_provider,
                 state_keeper_config.save_call_traces,
                 storage_caches.clone().unwrap(),
+                archive_state.clone(),
             )
             .await
             .context("run_http_api")?;
@@ -502,10 +552,11 @@ pub async fn initialize_components(
                 &internal_api_config,
                 &api_config,
                 batch_fee_input_provider,
-                connection_pool.clone(),
+                api_connection_pool.clone(),
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 storage_caches,
+                archive_state,
             )
             .await
             .context("run_ws_api")?;
@@ -522,7 +573,7 @@ pub async fn initialize_components(
             let started_at = Instant::now();
             tracing::info!("initializing contract verification REST API");
             task_futures.push(contract_verification::start_server_thread_detached(
-                connection_pool.clone(),
+                api_connection_pool.clone(),
                 replica_connection_pool.clone(),
                 api_config.contract_verification.clone(),
                 stop_receiver.clone(),
@@ -550,6 +601,24 @@ pub async fn initialize_components(
             .state_keeper_config
             .clone()
             .context("state_keeper_config")?;
+        if let Some(source_schema) = &state_keeper_config.shadow_source_schema {
+            anyhow::ensure!(
+                !components
+                    .iter()
+                    .any(|c| matches!(c, Component::EthTxAggregator | Component::EthTxManager)),
+                "Ethereum sender components cannot run in the state keeper shadow mode"
+            );
+            anyhow::ensure!(
+                postgres_config
+                    .schema()
+                    .is_some_and(|schema| schema != source_schema),
+                "State keeper shadow mode requires a dedicated Postgres schema different from the mirrored one \
+                 (`{source_schema}`)"
+            );
+            tracing::warn!(
+                "State keeper runs in the shadow mode, mirroring L2 transactions from schema `{source_schema}`"
+            );
+        }
         let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
             bounded_gas_adjuster,
             FeeModelConfig::from_state_keeper_config(&state_keeper_config),
@@ -557,6 +626,7 @@ pub async fn initialize_components(
         add_state_keeper_to_task_futures(
             &mut task_futures,
             &postgres_config,
+            state_keeper_pool.as_ref(),
             &contracts_config,
             state_keeper_config,
             &configs.network_config.clone().context("network_config")?,
@@ -619,6 +689,7 @@ pub async fn initialize_components(
         let started_at = Instant::now();
         tracing::info!("initializing ETH-Watcher");
         let eth_watch_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .set_schema(postgres_config.schema())
             .build()
             .await
             .context("failed to build eth_watch_pool")?;
@@ -648,6 +719,7 @@ pub async fn initialize_components(
         let started_at = Instant::now();
         tracing::info!("initializing ETH-TxAggregator");
         let eth_sender_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .set_schema(postgres_config.schema())
             .build()
             .await
             .context("failed to build eth_sender_pool")?;
@@ -694,6 +766,7 @@ pub async fn initialize_components(
         let started_at = Instant::now();
         tracing::info!("initializing ETH-TxManager");
         let eth_manager_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .set_schema(postgres_config.schema())
             .build()
             .await
             .context("failed to build eth_manager_pool")?;
@@ -734,14 +807,14 @@ pub async fn initialize_components(
     .context("add_trees_to_task_futures()")?;
 
     if components.contains(&Component::BasicWitnessInputProducer) {
-        let singleton_connection_pool = ConnectionPool::singleton(postgres_config.master_url()?)
-            .build()
-            .await
-            .context("failed to build singleton connection_pool")?;
+        let witness_input_pool =
+            build_component_pool(witness_generator_pool.as_ref(), &postgres_config)
+                .await
+                .context("failed to build witness_input_pool")?;
         let network_config = configs.network_config.clone().context("network_config")?;
         add_basic_witness_input_producer_to_task_futures(
             &mut task_futures,
-            &singleton_connection_pool,
+            &witness_input_pool,
             &store_factory,
             network_config.zksync_network_id,
             stop_receiver.clone(),
@@ -751,7 +824,7 @@ pub async fn initialize_components(
     }
 
     if components.contains(&Component::Housekeeper) {
-        add_house_keeper_to_task_futures(configs, &mut task_futures)
+        add_house_keeper_to_task_futures(configs, house_keeper_pool.as_ref(), &mut task_futures)
             .await
             .context("add_house_keeper_to_task_futures()")?;
     }
@@ -774,6 +847,7 @@ pub async fn initialize_components(
 
     if components.contains(&Component::CommitmentGenerator) {
         let commitment_generator_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .set_schema(postgres_config.schema())
             .build()
             .await
             .context("failed to build commitment_generator_pool")?;
@@ -796,10 +870,30 @@ pub async fn initialize_components(
     Ok((task_futures, stop_sender, cb_receiver, health_check_handle))
 }
 
+/// Returns a pool for a component writing to the master DB. If the component has a connection quota configured,
+/// the returned pool is a clone of its dedicated sub-pool; otherwise, a singleton pool is created.
+async fn build_component_pool(
+    component_pool: Option<&ConnectionPool>,
+    postgres_config: &PostgresConfig,
+) -> anyhow::Result<ConnectionPool> {
+    if let Some(pool) = component_pool {
+        return Ok(pool.clone());
+    }
+    build_singleton_pool(postgres_config).await
+}
+
+async fn build_singleton_pool(postgres_config: &PostgresConfig) -> anyhow::Result<ConnectionPool> {
+    ConnectionPool::singleton(postgres_config.master_url()?)
+        .set_schema(postgres_config.schema())
+        .build()
+        .await
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn add_state_keeper_to_task_futures(
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
     postgres_config: &PostgresConfig,
+    component_pool: Option<&ConnectionPool>,
     contracts_config: &ContractsConfig,
     state_keeper_config: StateKeeperConfig,
     network_config: &NetworkConfig,
@@ -809,23 +903,23 @@ async fn add_state_keeper_to_task_futures(
     object_store: Arc<dyn ObjectStore>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
-    let pool_builder = ConnectionPool::singleton(postgres_config.master_url()?);
-    let state_keeper_pool = pool_builder
-        .build()
+    let state_keeper_pool = build_component_pool(component_pool, postgres_config)
         .await
         .context("failed to build state_keeper_pool")?;
+    let shadow_source_schema = state_keeper_config.shadow_source_schema.clone();
     let mempool = {
         let mut storage = state_keeper_pool
             .access_storage()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config).await;
         mempool.register_metrics();
         mempool
     };
 
-    let miniblock_sealer_pool = pool_builder
-        .build()
+    // The state keeper waits for the miniblock sealer while holding its connection, so the sealer
+    // (as well as other auxiliary tasks) must not share connections with it.
+    let miniblock_sealer_pool = build_singleton_pool(postgres_config)
         .await
         .context("failed to build miniblock_sealer_pool")?;
     let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
@@ -854,8 +948,7 @@ async fn add_state_keeper_to_task_futures(
     ));
     task_futures.push(tokio::spawn(state_keeper.run()));
 
-    let mempool_fetcher_pool = pool_builder
-        .build()
+    let mempool_fetcher_pool = build_singleton_pool(postgres_config)
         .await
         .context("failed to build mempool_fetcher_pool")?;
     let mempool_fetcher = MempoolFetcher::new(
@@ -864,8 +957,22 @@ async fn add_state_keeper_to_task_futures(
         mempool_config,
         mempool_fetcher_pool,
     );
-    let mempool_fetcher_handle = tokio::spawn(mempool_fetcher.run(stop_receiver));
+    let mempool_fetcher_handle = tokio::spawn(mempool_fetcher.run(stop_receiver.clone()));
     task_futures.push(mempool_fetcher_handle);
+
+    if let Some(source_schema) = shadow_source_schema {
+        // The mirror only reads from the source schema, so it's safe to use a replica.
+        let source_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .set_schema(Some(&source_schema))
+            .build()
+            .await
+            .context("failed to build shadow source pool")?;
+        let mirror_pool = build_singleton_pool(postgres_config)
+            .await
+            .context("failed to build shadow mirror pool")?;
+        let mirror = ShadowMempoolMirror::new(source_pool, mirror_pool, mempool_config);
+        task_futures.push(tokio::spawn(mirror.run(stop_receiver)));
+    }
     Ok(())
 }
 
@@ -958,6 +1065,7 @@ async fn run_tree(
     let tree_health_check = metadata_calculator.tree_health_check();
     app_health.insert_component(tree_health_check);
     let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+        .set_schema(postgres_config.schema())
         .build()
         .await
         .context("failed to build connection pool")?;
@@ -998,6 +1106,7 @@ async fn add_basic_witness_input_producer_to_task_futures(
 
 async fn add_house_keeper_to_task_futures(
     configs: &TempConfigStore,
+    component_pool: Option<&ConnectionPool>,
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
 ) -> anyhow::Result<()> {
     let house_keeper_config = configs
@@ -1009,6 +1118,7 @@ async fn add_house_keeper_to_task_futures(
         postgres_config.replica_url()?,
         postgres_config.max_connections()?,
     )
+    .set_schema(postgres_config.schema())
     .build()
     .await
     .context("failed to build a connection pool")?;
@@ -1035,6 +1145,38 @@ async fn add_house_keeper_to_task_futures(
     .context("failed to build a prover_connection_pool")?;
     task_futures.push(tokio::spawn(l1_batch_metrics_reporter.run()));
 
+    if let Some(retention) = house_keeper_config.db_pruning_retention() {
+        // Pruning modifies data, so it needs the master DB.
+        let pruning_pool = build_component_pool(component_pool, &postgres_config)
+            .await
+            .context("failed to build a pruning_pool")?;
+        let db_pruner = DbPruner::new(
+            retention,
+            house_keeper_config.db_pruning_interval_ms(),
+            pruning_pool,
+        );
+        task_futures.push(tokio::spawn(db_pruner.run()));
+    }
+
+    if let Some(partition_size) = house_keeper_config.db_partition_size_miniblocks {
+        let partitioning_pool = build_component_pool(component_pool, &postgres_config)
+            .await
+            .context("failed to build a partitioning_pool")?;
+        let db_partition_manager = DbPartitionManager::new(
+            partition_size,
+            house_keeper_config.db_events_archival_retention(),
+            house_keeper_config.db_partitioning_interval_ms(),
+            partitioning_pool,
+        );
+        task_futures.push(tokio::spawn(db_partition_manager.run()));
+    }
+
+    if let Some(check_interval_ms) = house_keeper_config.db_consistency_check_interval_ms {
+        let db_consistency_checker =
+            DbConsistencyChecker::new(check_interval_ms, connection_pool.clone());
+        task_futures.push(tokio::spawn(db_consistency_checker.run()));
+    }
+
     // All FRI Prover related components are configured below.
     let fri_prover_config = configs
         .fri_prover_config
@@ -1136,6 +1278,23 @@ fn build_storage_caches(
     Ok(storage_caches)
 }
 
+/// Opens the state archive if it is configured, and spawns a task keeping it synchronized with Postgres.
+async fn build_archive_state(
+    web3_json_config: &Web3JsonRpcConfig,
+    replica_connection_pool: &ConnectionPool,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<Option<RocksdbArchive>> {
+    let Some(path) = &web3_json_config.archive_state_path else {
+        return Ok(None);
+    };
+    let (updater, archive_state) =
+        ArchiveStateUpdater::new(path.as_ref(), replica_connection_pool.clone()).await?;
+    task_futures.push(tokio::spawn(updater.run(stop_receiver)));
+    Ok(Some(archive_state))
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn build_tx_sender(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
@@ -1144,15 +1303,26 @@ async fn build_tx_sender(
     master_pool: ConnectionPool,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     storage_caches: PostgresStorageCaches,
-) -> (TxSender, VmConcurrencyBarrier) {
+    archive_state: Option<RocksdbArchive>,
+) -> anyhow::Result<(TxSender, VmConcurrencyBarrier)> {
     let sequencer_sealer = SequencerSealer::new(state_keeper_config.clone());
-    let master_pool_sink = MasterPoolSink::new(master_pool);
-    let tx_sender_builder = TxSenderBuilder::new(
+    let tx_filter = DenyListFilter::from_config(state_keeper_config);
+    let tx_limits = TxLimits::from_config(state_keeper_config)?;
+    let master_pool_sink = MasterPoolSink::new(
+        master_pool,
+        web3_json_config.tx_replacement_fee_bump_percent(),
+    );
+    let mut tx_sender_builder = TxSenderBuilder::new(
         tx_sender_config.clone(),
         replica_pool.clone(),
         Arc::new(master_pool_sink),
     )
-    .with_sealer(Arc::new(sequencer_sealer));
+    .with_sealer(Arc::new(sequencer_sealer))
+    .with_tx_filter(Arc::new(tx_filter))
+    .with_tx_limits(tx_limits);
+    if let Some(archive_state) = archive_state {
+        tx_sender_builder = tx_sender_builder.with_archive_state(archive_state);
+    }
 
     let max_concurrency = web3_json_config.vm_concurrency_limit();
     let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
@@ -1168,7 +1338,62 @@ async fn build_tx_sender(
             storage_caches,
         )
         .await;
-    (tx_sender, vm_barrier)
+    Ok((tx_sender, vm_barrier))
+}
+
+/// Returns API keys accepted by the API servers together with per-key limits, or `None` if API keys are not required.
+fn api_keys(web3_json_config: &Web3JsonRpcConfig) -> Option<(Vec<String>, ApiKeyLimits)> {
+    let limits = ApiKeyLimits {
+        requests_per_second: web3_json_config.api_key_requests_per_second_limit,
+        compute_units_per_second: web3_json_config.api_key_compute_units_per_second_limit,
+    };
+    Some((web3_json_config.api_keys.clone()?, limits))
+}
+
+/// Returns authentication settings for the API servers, or `None` if authentication is not configured.
+fn rpc_auth(web3_json_config: &Web3JsonRpcConfig) -> anyhow::Result<Option<RpcAuthConfig>> {
+    let jwt_secret = web3_json_config
+        .auth_jwt_secret_path
+        .as_ref()
+        .map(|path| RpcAuthConfig::read_jwt_secret(path.as_ref()))
+        .transpose()?;
+    if web3_json_config.auth_bearer_tokens.is_empty() && jwt_secret.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(RpcAuthConfig {
+        bearer_tokens: web3_json_config.auth_bearer_tokens.clone(),
+        jwt_secret,
+        protected_namespaces: web3_json_config.auth_protected_namespaces.clone(),
+    }))
+}
+
+/// Returns the CORS policy for the HTTP API server.
+fn cors_config(web3_json_config: &Web3JsonRpcConfig) -> anyhow::Result<CorsConfig> {
+    let mut cors = CorsConfig::default()
+        .with_allowed_headers(&web3_json_config.cors_allowed_headers)
+        .context("invalid CORS allowed headers")?
+        .with_max_age(web3_json_config.cors_max_age());
+    if let Some(origins) = &web3_json_config.cors_allowed_origins {
+        cors = cors
+            .with_allowed_origins(origins)
+            .context("invalid CORS allowed origins")?;
+    }
+    Ok(cors)
+}
+
+/// Parses namespaces explicitly enabled in the config. Returns `None` if the default namespaces should be used.
+fn api_namespaces(web3_json_config: &Web3JsonRpcConfig) -> anyhow::Result<Option<Vec<Namespace>>> {
+    let Some(namespaces) = &web3_json_config.api_namespaces else {
+        return Ok(None);
+    };
+    let namespaces = namespaces
+        .iter()
+        .map(|name| {
+            name.parse::<Namespace>()
+                .with_context(|| format!("unknown API namespace `{name}`"))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok(Some(namespaces))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1186,25 +1411,36 @@ async fn run_http_api(
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     with_debug_namespace: bool,
     storage_caches: PostgresStorageCaches,
+    archive_state: Option<RocksdbArchive>,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
         &api_config.web3_json_rpc,
         state_keeper_config,
         replica_connection_pool.clone(),
-        master_connection_pool,
+        master_connection_pool.clone(),
         batch_fee_model_input_provider,
         storage_caches,
+        archive_state,
     )
-    .await;
+    .await?;
 
-    let mut namespaces = Namespace::DEFAULT.to_vec();
-    if with_debug_namespace {
-        namespaces.push(Namespace::Debug)
-    }
-    namespaces.push(Namespace::Snapshots);
+    let namespaces = if let Some(namespaces) = api_namespaces(&api_config.web3_json_rpc)? {
+        namespaces
+    } else {
+        let mut namespaces = Namespace::DEFAULT.to_vec();
+        if with_debug_namespace {
+            namespaces.push(Namespace::Debug)
+        }
+        namespaces.push(Namespace::Snapshots);
+        if api_config.web3_json_rpc.txpool_namespace_enabled {
+            namespaces.push(Namespace::Txpool);
+        }
+        namespaces
+    };
 
     let updaters_pool = ConnectionPool::builder(postgres_config.replica_url()?, 2)
+        .set_schema(postgres_config.schema())
         .build()
         .await
         .context("failed to build last_miniblock_pool")?;
@@ -1216,14 +1452,39 @@ async fn run_http_api(
             .with_filter_limit(api_config.web3_json_rpc.filters_limit())
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
+            .with_batch_response_size_limit(api_config.web3_json_rpc.max_batch_response_size())
             .with_tx_sender(tx_sender)
             .with_vm_barrier(vm_barrier)
+            .with_cors(cors_config(&api_config.web3_json_rpc)?)
             .enable_api_namespaces(namespaces);
+    if let Some((api_keys, limits)) = api_keys(&api_config.web3_json_rpc) {
+        api_builder = api_builder.with_api_keys(api_keys, limits);
+    }
+    if let Some(auth) = rpc_auth(&api_config.web3_json_rpc)? {
+        api_builder = api_builder.with_auth(auth);
+    }
+    if let Some(time_budget) = api_config.web3_json_rpc.batch_request_time_budget() {
+        api_builder = api_builder.with_batch_time_budget(time_budget);
+    }
+    if let Some(threshold) = api_config.web3_json_rpc.http_response_compression_threshold {
+        api_builder = api_builder.with_response_compression(threshold);
+    }
+    if let Some(timeout) = api_config.web3_json_rpc.shutdown_drain_timeout() {
+        api_builder = api_builder.with_drain_timeout(timeout);
+    }
     if let Some(tree_api_url) = api_config.web3_json_rpc.tree_api_url() {
         let tree_api = Arc::new(TreeApiHttpClient::new(tree_api_url));
         api_builder = api_builder.with_tree_api(tree_api.clone());
         app_health.insert_custom_component(tree_api);
     }
+    // The pool is only used if the `admin` namespace is enabled.
+    api_builder = api_builder.with_admin_pool(master_connection_pool.clone());
+    if api_config.web3_json_rpc.persistent_filters {
+        api_builder = api_builder.with_persistent_filters(
+            master_connection_pool,
+            api_config.web3_json_rpc.filters_ttl(),
+        );
+    }
 
     let server_handles = api_builder
         .build()
@@ -1249,24 +1510,35 @@ async fn run_ws_api(
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
+    archive_state: Option<RocksdbArchive>,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
         &api_config.web3_json_rpc,
         state_keeper_config,
         replica_connection_pool.clone(),
-        master_connection_pool,
+        master_connection_pool.clone(),
         batch_fee_model_input_provider,
         storage_caches,
+        archive_state,
     )
-    .await;
+    .await?;
     let last_miniblock_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+        .set_schema(postgres_config.schema())
         .build()
         .await
         .context("failed to build last_miniblock_pool")?;
 
-    let mut namespaces = Namespace::DEFAULT.to_vec();
-    namespaces.push(Namespace::Snapshots);
+    let namespaces = if let Some(namespaces) = api_namespaces(&api_config.web3_json_rpc)? {
+        namespaces
+    } else {
+        let mut namespaces = Namespace::DEFAULT.to_vec();
+        namespaces.push(Namespace::Snapshots);
+        if api_config.web3_json_rpc.txpool_namespace_enabled {
+            namespaces.push(Namespace::Txpool);
+        }
+        namespaces
+    };
 
     let mut api_builder =
         web3::ApiBuilder::jsonrpsee_backend(internal_api.clone(), replica_connection_pool)
@@ -1274,6 +1546,14 @@ async fn run_ws_api(
             .with_updaters_pool(last_miniblock_pool)
             .with_filter_limit(api_config.web3_json_rpc.filters_limit())
             .with_subscriptions_limit(api_config.web3_json_rpc.subscriptions_limit())
+            .with_subscriptions_per_connection_limit(
+                api_config
+                    .web3_json_rpc
+                    .subscriptions_per_connection_limit(),
+            )
+            .with_websocket_message_buffer_capacity(
+                api_config.web3_json_rpc.websocket_message_buffer_capacity(),
+            )
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
             .with_websocket_requests_per_minute_limit(
@@ -1285,11 +1565,43 @@ async fn run_ws_api(
             .with_tx_sender(tx_sender)
             .with_vm_barrier(vm_barrier)
             .enable_api_namespaces(namespaces);
+    if let Some((api_keys, limits)) = api_keys(&api_config.web3_json_rpc) {
+        api_builder = api_builder.with_api_keys(api_keys, limits);
+    }
+    if let Some(auth) = rpc_auth(&api_config.web3_json_rpc)? {
+        api_builder = api_builder.with_auth(auth);
+    }
+    if let Some(limit) = api_config
+        .web3_json_rpc
+        .websocket_compute_units_per_second_limit
+    {
+        api_builder = api_builder.with_websocket_compute_units_per_second_limit(limit);
+    }
+    if let Some(timeout) = api_config.web3_json_rpc.shutdown_drain_timeout() {
+        api_builder = api_builder.with_drain_timeout(timeout);
+    }
     if let Some(tree_api_url) = api_config.web3_json_rpc.tree_api_url() {
         let tree_api = Arc::new(TreeApiHttpClient::new(tree_api_url));
         api_builder = api_builder.with_tree_api(tree_api.clone());
         app_health.insert_custom_component(tree_api);
     }
+    if api_config.web3_json_rpc.pubsub_db_notifications {
+        // Each of the block and log notifiers holds a connection for its listener.
+        let notifications_pool = ConnectionPool::builder(postgres_config.master_url()?, 2)
+            .set_schema(postgres_config.schema())
+            .build()
+            .await
+            .context("failed to build sealed miniblock notifications pool")?;
+        api_builder = api_builder.with_sealed_miniblock_notifications(notifications_pool);
+    }
+    // The pool is only used if the `admin` namespace is enabled.
+    api_builder = api_builder.with_admin_pool(master_connection_pool.clone());
+    if api_config.web3_json_rpc.persistent_filters {
+        api_builder = api_builder.with_persistent_filters(
+            master_connection_pool,
+            api_config.web3_json_rpc.filters_ttl(),
+        );
+    }
 
     let server_handles = api_builder
         .build()
@@ -1313,6 +1625,7 @@ async fn circuit_breakers_for_components(
         .any(|c| matches!(c, Component::EthTxAggregator | Component::EthTxManager))
     {
         let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .set_schema(postgres_config.schema())
             .build()
             .await
             .context("failed to build a connection pool")?;
@@ -1326,6 +1639,7 @@ async fn circuit_breakers_for_components(
         )
     }) {
         let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .set_schema(postgres_config.schema())
             .build()
             .await?;
         circuit_breakers.push(Box::new(ReplicationLagChecker {
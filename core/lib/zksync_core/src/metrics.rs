@@ -2,7 +2,9 @@
 
 use std::{fmt, time::Duration};
 
-use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
 use zksync_dal::transactions_dal::L2TxSubmissionResult;
 use zksync_types::aggregated_operations::AggregatedActionType;
 
@@ -141,6 +143,14 @@ pub(crate) struct AppMetrics {
     pub processed_l1_txs: Family<TxStage, Counter>,
 
     pub blocks_state_block_eth_stage_latency: Family<L1StageLatencyLabel, Gauge<u64>>,
+
+    /// Number of miniblocks in the pending L1 batch.
+    pub pending_l1_batch_miniblocks: Gauge<u64>,
+    /// Number of transactions in the pending L1 batch.
+    pub pending_l1_batch_txs: Gauge<u64>,
+    /// Estimated pubdata size of the pending L1 batch.
+    #[metrics(unit = Unit::Bytes)]
+    pub pending_l1_batch_estimated_pubdata: Gauge<u64>,
 }
 
 #[vise::register]
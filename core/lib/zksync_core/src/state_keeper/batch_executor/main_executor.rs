@@ -13,7 +13,7 @@ use multivm::{
 use once_cell::sync::OnceCell;
 use tokio::sync::{mpsc, watch};
 use zksync_dal::ConnectionPool;
-use zksync_state::{RocksdbStorage, StorageView, WriteStorage};
+use zksync_state::{ReadStorage, RocksdbStorage, StorageView, WriteStorage};
 use zksync_types::{vm_trace::Call, Transaction, U256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
@@ -151,6 +151,14 @@ impl CommandReceiver {
                     self.start_next_miniblock(l2_block_env, &mut vm);
                     resp.send(()).unwrap();
                 }
+                Command::ReadStorage(keys, resp) => {
+                    let mut storage_view = storage_view.borrow_mut();
+                    let values = keys
+                        .iter()
+                        .map(|key| storage_view.read_value(key))
+                        .collect();
+                    resp.send(values).unwrap();
+                }
                 Command::FinishBatch(resp) => {
                     let vm_block_result = self.finish_batch(&mut vm);
                     let witness_block_state = if upload_witness_inputs_to_gcs {
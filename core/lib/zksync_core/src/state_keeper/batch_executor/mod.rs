@@ -8,7 +8,9 @@ use tokio::{
     sync::{mpsc, oneshot, watch},
     task::JoinHandle,
 };
-use zksync_types::{vm_trace::Call, witness_block_state::WitnessBlockState, Transaction};
+use zksync_types::{
+    vm_trace::Call, witness_block_state::WitnessBlockState, StorageKey, Transaction, H256,
+};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
 use crate::state_keeper::{
@@ -149,6 +151,22 @@ impl BatchExecutorHandle {
         latency.observe();
     }
 
+    /// Reads values of the specified storage slots from the current VM state, i.e., taking into account
+    /// all transactions executed in the batch so far.
+    pub(super) async fn read_storage(&self, keys: Vec<StorageKey>) -> Vec<H256> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.commands
+            .send(Command::ReadStorage(keys, response_sender))
+            .await
+            .unwrap();
+        let latency = EXECUTOR_METRICS.batch_executor_command_response_time
+            [&ExecutorCommand::ReadStorage]
+            .start();
+        let values = response_receiver.await.unwrap();
+        latency.observe();
+        values
+    }
+
     pub(super) async fn finish_batch(self) -> (FinishedL1Batch, Option<WitnessBlockState>) {
         let (response_sender, response_receiver) = oneshot::channel();
         self.commands
@@ -170,5 +188,6 @@ pub(super) enum Command {
     ExecuteTx(Box<Transaction>, oneshot::Sender<TxExecutionResult>),
     StartNextMiniblock(L2BlockEnv, oneshot::Sender<()>),
     RollbackLastTx(oneshot::Sender<()>),
+    ReadStorage(Vec<StorageKey>, oneshot::Sender<Vec<H256>>),
     FinishBatch(oneshot::Sender<(FinishedL1Batch, Option<WitnessBlockState>)>),
 }
@@ -13,15 +13,20 @@ use multivm::{
 };
 use vm_utils::storage::{l1_batch_params, L1BatchParamsProvider};
 use zksync_config::configs::chain::StateKeeperConfig;
-use zksync_dal::ConnectionPool;
+use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_mempool::L2TxFilter;
 use zksync_object_store::ObjectStore;
 use zksync_types::{
-    protocol_version::ProtocolUpgradeTx, witness_block_state::WitnessBlockState, Address,
-    L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
+    api::TransactionConditions,
+    protocol_version::ProtocolUpgradeTx,
+    storage::storage_diff::{L1BatchStorageDiff, StorageDiffWrite},
+    witness_block_state::WitnessBlockState,
+    zk_evm_types::LogQuery,
+    AccountTreeId, Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId,
+    StorageKey, Transaction, H256,
 };
 // TODO (SMA-1206): use seconds instead of milliseconds.
-use zksync_utils::time::millis_since_epoch;
+use zksync_utils::{time::millis_since_epoch, u256_to_h256};
 
 use crate::{
     fee_model::BatchFeeModelInputProvider,
@@ -67,6 +72,7 @@ pub struct MempoolIO {
 
     virtual_blocks_interval: u32,
     virtual_blocks_per_miniblock: u32,
+    upload_storage_diffs: bool,
 }
 
 impl IoSealCriteria for MempoolIO {
@@ -198,6 +204,9 @@ impl StateKeeperIO for MempoolIO {
                 continue;
             }
 
+            // The fee account may only be changed on the L1 batch boundary, so it's safe to update it here.
+            self.update_fee_account().await?;
+            self.update_proof_backlog().await?;
             // We only need to get the root hash when we're certain that we have a new transaction.
             let prev_l1_batch_hash = self.wait_for_previous_l1_batch_hash().await?;
             return Ok(Some(l1_batch_params(
@@ -256,6 +265,10 @@ impl StateKeeperIO for MempoolIO {
         None
     }
 
+    fn transaction_conditions(&self, tx: &Transaction) -> Option<TransactionConditions> {
+        self.mempool.transaction_conditions(tx)
+    }
+
     async fn rollback(&mut self, tx: Transaction) {
         // Reset nonces in the mempool.
         self.mempool.rollback(&tx);
@@ -271,6 +284,7 @@ impl StateKeeperIO for MempoolIO {
 
         // Reset the nonces in the mempool, but don't insert the transaction back.
         self.mempool.rollback(rejected);
+        self.mempool.remove_conditions([rejected]);
 
         // Mark tx as rejected in the storage.
         let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
@@ -294,6 +308,12 @@ impl StateKeeperIO for MempoolIO {
             false,
         );
         self.miniblock_sealer_handle.submit(command).await;
+        let executed_txs = updates_manager
+            .miniblock
+            .executed_transactions
+            .iter()
+            .map(|tx_result| &tx_result.transaction);
+        self.mempool.remove_conditions(executed_txs);
         self.update_miniblock_fields(&updates_manager.miniblock);
     }
 
@@ -334,6 +354,13 @@ impl StateKeeperIO for MempoolIO {
         let pool = self.pool.clone();
         let mut storage = pool.access_storage_tagged("state_keeper").await?;
 
+        let l1_batch_number = self.current_l1_batch_number();
+        let storage_log_queries = self.upload_storage_diffs.then(|| {
+            finished_batch
+                .final_execution_state
+                .deduplicated_storage_log_queries
+                .clone()
+        });
         let fictive_miniblock = updates_manager
             .seal_l1_batch(
                 &mut storage,
@@ -345,6 +372,22 @@ impl StateKeeperIO for MempoolIO {
             .await;
         self.update_miniblock_fields(&fictive_miniblock);
         self.current_l1_batch_number += 1;
+
+        if let Some(log_queries) = storage_log_queries {
+            match self
+                .upload_storage_diff(&mut storage, l1_batch_number, &log_queries)
+                .await
+            {
+                Ok(path) => {
+                    tracing::debug!("Successfully uploaded storage diff for L1 batch #{l1_batch_number} to Object Store to path = '{path}'");
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to upload storage diff for L1 batch #{l1_batch_number} to Object Store: {err:#}"
+                    );
+                }
+            }
+        }
         Ok(())
     }
 
@@ -372,6 +415,57 @@ impl StateKeeperIO for MempoolIO {
     }
 }
 
+/// Loads the storage diff for a sealed L1 batch based on its deduplicated storage log queries.
+pub(super) async fn load_storage_diff(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_number: L1BatchNumber,
+    log_queries: &[LogQuery],
+) -> anyhow::Result<L1BatchStorageDiff> {
+    let mut touched_accounts: Vec<_> = log_queries.iter().map(|query| query.address).collect();
+    touched_accounts.sort_unstable();
+    touched_accounts.dedup();
+
+    let written_slots: Vec<_> = log_queries
+        .iter()
+        .filter(|query| query.rw_flag)
+        .map(|query| {
+            let key = StorageKey::new(AccountTreeId::new(query.address), u256_to_h256(query.key));
+            (key, u256_to_h256(query.written_value))
+        })
+        .collect();
+    let hashed_keys: Vec<_> = written_slots
+        .iter()
+        .map(|(key, _)| key.hashed_key())
+        .collect();
+    let enumeration_indices = storage
+        .storage_logs_dedup_dal()
+        .get_enumeration_indices(&hashed_keys)
+        .await
+        .context("failed loading enumeration indices")?;
+
+    let mut writes = written_slots
+        .into_iter()
+        .zip(hashed_keys)
+        .map(|((key, value), hashed_key)| {
+            let (enumeration_index, initial_write_l1_batch) = *enumeration_indices
+                .get(&hashed_key)
+                .with_context(|| format!("written slot {key:?} has no enumeration index"))?;
+            Ok(StorageDiffWrite {
+                key,
+                value,
+                enumeration_index,
+                is_initial: initial_write_l1_batch == l1_batch_number,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    writes.sort_unstable_by_key(|write| write.key);
+
+    Ok(L1BatchStorageDiff {
+        touched_accounts,
+        writes,
+    })
+}
+
 /// Sleeps until the current timestamp is larger than the provided `timestamp`.
 ///
 /// Returns the current timestamp after the sleep. It is guaranteed to be larger than `timestamp`.
@@ -473,6 +567,7 @@ impl MempoolIO {
             chain_id,
             virtual_blocks_interval: config.virtual_blocks_interval,
             virtual_blocks_per_miniblock: config.virtual_blocks_per_miniblock,
+            upload_storage_diffs: config.upload_l1_batch_state_diffs,
         })
     }
 
@@ -486,6 +581,59 @@ impl MempoolIO {
         self.prev_miniblock_timestamp = miniblock.timestamp;
     }
 
+    /// Picks up the fee account scheduled via [`FeeAccountDal`](zksync_dal::fee_account_dal::FeeAccountDal),
+    /// if any. Must only be called when starting a new L1 batch.
+    async fn update_fee_account(&mut self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let scheduled_fee_account = storage
+            .fee_account_dal()
+            .get_scheduled_fee_account()
+            .await
+            .context("failed loading scheduled fee account")?;
+        if let Some(fee_account) = scheduled_fee_account {
+            if fee_account != self.fee_account {
+                tracing::info!(
+                    "Changing fee account from {:?} to {fee_account:?} starting from L1 batch #{}",
+                    self.fee_account,
+                    self.current_l1_batch_number
+                );
+                self.fee_account = fee_account;
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the proof backlog used to slow down L1 batch sealing if proof generation falls behind.
+    async fn update_proof_backlog(&mut self) -> anyhow::Result<()> {
+        if !self.timeout_sealer.tracks_proof_backlog() {
+            return Ok(());
+        }
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let proof_backlog = storage
+            .proof_generation_dal()
+            .get_unproven_l1_batch_count()
+            .await
+            .context("failed loading proof backlog")?;
+        KEEPER_METRICS.proof_backlog.set(proof_backlog);
+        self.timeout_sealer.set_proof_backlog(proof_backlog);
+        Ok(())
+    }
+
+    async fn upload_storage_diff(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+        log_queries: &[LogQuery],
+    ) -> anyhow::Result<String> {
+        let storage_diff = load_storage_diff(storage, l1_batch_number, log_queries).await?;
+        let path = self
+            .object_store
+            .put(l1_batch_number, &storage_diff)
+            .await
+            .context("failed uploading storage diff")?;
+        Ok(path)
+    }
+
     async fn wait_for_previous_l1_batch_hash(&self) -> anyhow::Result<H256> {
         tracing::trace!(
             "Getting previous L1 batch hash for L1 batch #{}",
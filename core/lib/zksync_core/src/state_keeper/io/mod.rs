@@ -1,14 +1,16 @@
 use std::{
     fmt,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
+use anyhow::Context as _;
 use async_trait::async_trait;
 use multivm::interface::{FinishedL1Batch, L1BatchEnv, SystemEnv};
 use tokio::sync::{mpsc, oneshot};
-use zksync_dal::ConnectionPool;
+use zksync_dal::{ConnectionPool, TransactionRetryPolicy};
 use zksync_types::{
-    block::MiniblockExecutionData, protocol_version::ProtocolUpgradeTx,
+    api::TransactionConditions, block::MiniblockExecutionData, protocol_version::ProtocolUpgradeTx,
     witness_block_state::WitnessBlockState, L1BatchNumber, MiniblockNumber, ProtocolVersionId,
     Transaction,
 };
@@ -91,6 +93,9 @@ pub trait StateKeeperIO: 'static + Send + IoSealCriteria {
     /// Blocks for up to `max_wait` until the next transaction is available for execution.
     /// Returns `None` if no transaction became available until the timeout.
     async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction>;
+    /// Returns conditions that must hold immediately before executing the transaction, or `None` if the transaction
+    /// is unconditional. Conditions are specified by submitting transactions via `eth_sendRawTransactionConditional`.
+    fn transaction_conditions(&self, tx: &Transaction) -> Option<TransactionConditions>;
     /// Marks the transaction as "not executed", so it can be retrieved from the IO again.
     async fn rollback(&mut self, tx: Transaction);
     /// Marks the transaction as "rejected", e.g. one that is not correct and can't be executed.
@@ -261,13 +266,13 @@ impl MiniblockSealer {
         let mut miniblock_seal_delta: Option<Instant> = None;
         // Commands must be processed sequentially: a later miniblock cannot be saved before
         // an earlier one.
+        let retry_policy = TransactionRetryPolicy::default();
         while let Some(completable) = self.next_command().await {
-            let mut conn = self
-                .pool
-                .access_storage_tagged("state_keeper")
+            let command = Arc::new(completable.command);
+            command
+                .seal_with_retries(&self.pool, &retry_policy)
                 .await
-                .unwrap();
-            completable.command.seal(&mut conn).await;
+                .context("failed sealing miniblock")?;
             if let Some(delta) = miniblock_seal_delta {
                 MINIBLOCK_METRICS.seal_delta.observe(delta.elapsed());
             }
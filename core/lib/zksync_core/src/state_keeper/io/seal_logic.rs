@@ -1,14 +1,18 @@
 //! This module is a source-of-truth on what is expected to be done when sealing a block.
 //! It contains the logic of the block sealing, which is used by both the mempool-based and external node IO.
 
-use std::time::{Duration, Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use anyhow::Context as _;
 use itertools::Itertools;
 use multivm::{
     interface::{FinishedL1Batch, L1BatchEnv},
     utils::get_max_gas_per_pubdata_byte,
 };
-use zksync_dal::StorageProcessor;
+use zksync_dal::{ConnectionPool, StorageProcessor, TransactionRetryPolicy};
 use zksync_types::{
     block::{unpack_block_info, L1BatchHeader, MiniblockHeader},
     event::{extract_added_tokens, extract_long_l2_to_l1_messages},
@@ -33,7 +37,7 @@ use crate::{
     metrics::{BlockStage, MiniblockStage, APP_METRICS},
     state_keeper::{
         metrics::{
-            L1BatchSealStage, MiniblockSealStage, TxExecutionType, KEEPER_METRICS,
+            L1BatchSealStage, MiniblockSealStage, SealProgress, TxExecutionType, KEEPER_METRICS,
             L1_BATCH_METRICS, MINIBLOCK_METRICS,
         },
         types::ExecutionMetricsForCriteria,
@@ -169,7 +173,8 @@ impl UpdatesManager {
                 l1_batch_env.number,
                 &self.l1_batch.executed_transactions,
             )
-            .await;
+            .await
+            .unwrap();
         progress.observe(None);
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::InsertProtectiveReads);
@@ -254,6 +259,17 @@ impl UpdatesManager {
         L1_BATCH_METRICS
             .transactions_in_l1_batch
             .observe(self.l1_batch.executed_transactions.len());
+        // Execution metrics of the batch tip are stored in the fictive miniblock.
+        let execution_metrics = self.pending_execution_metrics();
+        L1_BATCH_METRICS
+            .gas_used
+            .observe(execution_metrics.gas_used);
+        L1_BATCH_METRICS
+            .computational_gas_used
+            .observe(execution_metrics.computational_gas_used as usize);
+        L1_BATCH_METRICS
+            .pubdata_published
+            .observe(execution_metrics.pubdata_published as usize);
 
         let l1_batch_latency =
             unix_timestamp_ms().saturating_sub(batch_timestamp * 1_000) as f64 / 1_000.0;
@@ -271,7 +287,32 @@ impl MiniblockSealCommand {
         self.seal_inner(storage, false).await;
     }
 
-    async fn insert_transactions(&self, transaction: &mut StorageProcessor<'_>) {
+    /// Same as [`Self::seal()`], but runs the sealing DB transaction on a connection from `pool`, retrying it
+    /// on transient errors (e.g., deadlocks or a broken connection).
+    pub(crate) async fn seal_with_retries(
+        self: Arc<Self>,
+        pool: &ConnectionPool,
+        retry_policy: &TransactionRetryPolicy,
+    ) -> anyhow::Result<()> {
+        self.assert_valid_miniblock(false);
+
+        let started_at = Instant::now();
+        let (current_l2_virtual_block_number, progress) = pool
+            .transaction_with_retries("seal_miniblock", retry_policy, |transaction| {
+                let this = self.clone();
+                Box::pin(async move { this.insert_miniblock_data(transaction, false).await })
+            })
+            .await?;
+        progress.observe(None);
+
+        self.report_metrics(started_at, current_l2_virtual_block_number, false);
+        Ok(())
+    }
+
+    async fn insert_transactions(
+        &self,
+        transaction: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
         for tx_result in &self.miniblock.executed_transactions {
             let tx = tx_result.transaction.clone();
             match &tx.common_data {
@@ -282,7 +323,7 @@ impl MiniblockSealCommand {
                     transaction
                         .transactions_dal()
                         .insert_transaction_l1(l1_tx, l1_block_number)
-                        .await;
+                        .await?;
                 }
                 ExecuteTransactionCommon::L2(_) => {
                     // `unwrap` is safe due to the check above
@@ -291,7 +332,7 @@ impl MiniblockSealCommand {
                     transaction
                         .transactions_dal()
                         .insert_transaction_l2(l2_tx, Default::default())
-                        .await;
+                        .await?;
                 }
                 ExecuteTransactionCommon::ProtocolUpgrade(_) => {
                     // `unwrap` is safe due to the check above
@@ -299,10 +340,11 @@ impl MiniblockSealCommand {
                     transaction
                         .transactions_dal()
                         .insert_system_transaction(protocol_system_upgrade_tx)
-                        .await;
+                        .await?;
                 }
             }
         }
+        Ok(())
     }
 
     /// Seals a miniblock with the given number.
@@ -317,16 +359,46 @@ impl MiniblockSealCommand {
     async fn seal_inner(&self, storage: &mut StorageProcessor<'_>, is_fictive: bool) {
         self.assert_valid_miniblock(is_fictive);
 
+        let started_at = Instant::now();
         let mut transaction = storage.start_transaction().await.unwrap();
+        let (current_l2_virtual_block_number, progress) = self
+            .insert_miniblock_data(&mut transaction, is_fictive)
+            .await
+            .expect("failed persisting miniblock data");
+        transaction.commit().await.unwrap();
+        progress.observe(None);
+
+        self.report_metrics(started_at, current_l2_virtual_block_number, is_fictive);
+    }
+
+    fn report_metrics(
+        &self,
+        started_at: Instant,
+        current_l2_virtual_block_number: u64,
+        is_fictive: bool,
+    ) {
+        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::ReportTxMetrics, is_fictive);
+        self.report_transaction_metrics();
+        progress.observe(Some(self.miniblock.executed_transactions.len()));
+
+        self.report_miniblock_metrics(started_at, current_l2_virtual_block_number);
+    }
+
+    /// Inserts miniblock data in the provided DB transaction without committing it. Returns the current L2 virtual block
+    /// number and the progress of the commit stage, which should be observed once the transaction is committed.
+    async fn insert_miniblock_data(
+        &self,
+        transaction: &mut StorageProcessor<'_>,
+        is_fictive: bool,
+    ) -> anyhow::Result<(u64, SealProgress<'static>)> {
         if self.pre_insert_txs {
             let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::PreInsertTxs, is_fictive);
-            self.insert_transactions(&mut transaction).await;
+            self.insert_transactions(transaction).await?;
             progress.observe(Some(self.miniblock.executed_transactions.len()));
         }
 
         let l1_batch_number = self.l1_batch_number;
         let miniblock_number = self.miniblock_number;
-        let started_at = Instant::now();
         let progress =
             MINIBLOCK_METRICS.start(MiniblockSealStage::InsertMiniblockHeader, is_fictive);
 
@@ -363,8 +435,7 @@ impl MiniblockSealCommand {
         transaction
             .blocks_dal()
             .insert_miniblock(&miniblock_header)
-            .await
-            .unwrap();
+            .await?;
         progress.observe(None);
 
         let progress =
@@ -376,7 +447,7 @@ impl MiniblockSealCommand {
                 &self.miniblock.executed_transactions,
                 self.base_fee_per_gas.into(),
             )
-            .await;
+            .await?;
         progress.observe(self.miniblock.executed_transactions.len());
 
         let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::InsertStorageLogs, is_fictive);
@@ -385,8 +456,7 @@ impl MiniblockSealCommand {
         transaction
             .storage_logs_dal()
             .insert_storage_logs(miniblock_number, &write_logs)
-            .await
-            .unwrap();
+            .await?;
         progress.observe(write_log_count);
 
         #[allow(deprecated)] // Will be removed shortly
@@ -407,8 +477,7 @@ impl MiniblockSealCommand {
             transaction
                 .factory_deps_dal()
                 .insert_factory_deps(miniblock_number, new_factory_deps)
-                .await
-                .unwrap();
+                .await?;
         }
         progress.observe(new_factory_deps_count);
 
@@ -418,11 +487,7 @@ impl MiniblockSealCommand {
         let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::InsertTokens, is_fictive);
         let added_tokens_len = added_tokens.len();
         if !added_tokens.is_empty() {
-            transaction
-                .tokens_dal()
-                .add_tokens(&added_tokens)
-                .await
-                .unwrap();
+            transaction.tokens_dal().add_tokens(&added_tokens).await?;
         }
         progress.observe(added_tokens_len);
 
@@ -437,7 +502,7 @@ impl MiniblockSealCommand {
         transaction
             .events_dal()
             .save_events(miniblock_number, &miniblock_events)
-            .await;
+            .await?;
         progress.observe(miniblock_event_count);
 
         let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::ExtractL2ToL1Logs, is_fictive);
@@ -460,7 +525,7 @@ impl MiniblockSealCommand {
         transaction
             .events_dal()
             .save_user_l2_to_l1_logs(miniblock_number, &user_l2_to_l1_logs)
-            .await;
+            .await?;
         progress.observe(user_l2_to_l1_log_count);
 
         let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::CommitMiniblock, is_fictive);
@@ -471,18 +536,10 @@ impl MiniblockSealCommand {
                 CURRENT_VIRTUAL_BLOCK_INFO_POSITION,
             ))
             .await
-            .expect("failed getting virtual block info from VM state");
+            .context("failed getting virtual block info from VM state")?;
         let (current_l2_virtual_block_number, _) =
             unpack_block_info(h256_to_u256(current_l2_virtual_block_info));
-
-        transaction.commit().await.unwrap();
-        progress.observe(None);
-
-        let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::ReportTxMetrics, is_fictive);
-        self.report_transaction_metrics();
-        progress.observe(Some(self.miniblock.executed_transactions.len()));
-
-        self.report_miniblock_metrics(started_at, current_l2_virtual_block_number);
+        Ok((current_l2_virtual_block_number, progress))
     }
 
     /// Performs several sanity checks to make sure that the miniblock is valid.
@@ -18,7 +18,7 @@ use zksync_utils::time::seconds_since_epoch;
 use self::tester::Tester;
 use crate::{
     state_keeper::{
-        io::{MiniblockParams, MiniblockSealer, StateKeeperIO},
+        io::{mempool::load_storage_diff, MiniblockParams, MiniblockSealer, StateKeeperIO},
         mempool_actor::l2_tx_filter,
         tests::{
             create_execution_result, create_transaction, create_updates_manager,
@@ -133,6 +133,45 @@ async fn test_filter_with_no_pending_batch() {
     assert_eq!(mempool.filter(), &want_filter);
 }
 
+/// Ensure that the fee account scheduled in Postgres is used for the next L1 batch.
+#[tokio::test]
+async fn scheduled_fee_account_is_used_for_new_batch() {
+    let connection_pool = ConnectionPool::constrained_test_pool(1).await;
+    let tester = Tester::new();
+    tester.genesis(&connection_pool).await;
+
+    let tx_result = tester
+        .insert_miniblock(&connection_pool, 1, 5, BatchFeeInput::l1_pegged(55, 555))
+        .await;
+    tester
+        .insert_sealed_batch(&connection_pool, 1, &[tx_result])
+        .await;
+    let new_fee_account = Address::repeat_byte(0xfe);
+    connection_pool
+        .access_storage()
+        .await
+        .unwrap()
+        .fee_account_dal()
+        .schedule_fee_account(new_fee_account)
+        .await
+        .unwrap();
+
+    let (mut mempool, mut guard) = tester.create_test_mempool_io(connection_pool, 1).await;
+    let tx_filter = l2_tx_filter(
+        &tester.create_batch_fee_input_provider().await,
+        ProtocolVersionId::latest().into(),
+    )
+    .await;
+    tester.insert_tx(&mut guard, tx_filter.fee_per_gas, tx_filter.gas_per_pubdata);
+
+    let (_, l1_batch_env) = mempool
+        .wait_for_new_batch_params(Duration::from_secs(10))
+        .await
+        .unwrap()
+        .expect("No batch params in the test mempool");
+    assert_eq!(l1_batch_env.fee_account, new_fee_account);
+}
+
 async fn test_timestamps_are_distinct(
     connection_pool: ConnectionPool,
     prev_miniblock_timestamp: u64,
@@ -433,6 +472,60 @@ async fn miniblock_and_l1_batch_processing_with_sync_sealer() {
     test_miniblock_and_l1_batch_processing(pool, 0).await;
 }
 
+#[tokio::test]
+async fn loading_storage_diff_for_sealed_l1_batch() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let initial_write_key = StorageKey::new(AccountTreeId::default(), H256::from_low_u64_be(1));
+    let repeated_write_key = StorageKey::new(AccountTreeId::default(), H256::from_low_u64_be(2));
+    storage
+        .storage_logs_dedup_dal()
+        .insert_initial_writes(L1BatchNumber(0), &[repeated_write_key])
+        .await
+        .unwrap();
+    storage
+        .storage_logs_dedup_dal()
+        .insert_initial_writes(L1BatchNumber(1), &[initial_write_key])
+        .await
+        .unwrap();
+
+    let log_queries: Vec<_> = [
+        (U256::from(1), Query::InitialWrite(U256::from(10))),
+        (
+            U256::from(2),
+            Query::RepeatedWrite(U256::from(1), U256::from(20)),
+        ),
+        (U256::from(3), Query::Read(U256::from(30))),
+    ]
+    .into_iter()
+    .map(|(key, query)| query.into_log(key, 0).log_query)
+    .collect();
+    let storage_diff = load_storage_diff(&mut storage, L1BatchNumber(1), &log_queries)
+        .await
+        .unwrap();
+
+    assert_eq!(storage_diff.touched_accounts, [Address::default()]);
+    let writes: Vec<_> = storage_diff
+        .writes
+        .iter()
+        .map(|write| {
+            (
+                write.key,
+                write.value,
+                write.enumeration_index,
+                write.is_initial,
+            )
+        })
+        .collect();
+    assert_eq!(
+        writes,
+        [
+            (initial_write_key, H256::from_low_u64_be(10), 2, true),
+            (repeated_write_key, H256::from_low_u64_be(20), 1, false),
+        ]
+    );
+}
+
 #[tokio::test]
 async fn miniblock_processing_after_snapshot_recovery() {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -468,7 +561,8 @@ async fn miniblock_processing_after_snapshot_recovery() {
     storage
         .transactions_dal()
         .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
-        .await;
+        .await
+        .unwrap();
 
     let (system_env, l1_batch_env) = mempool
         .wait_for_new_batch_params(Duration::from_secs(10))
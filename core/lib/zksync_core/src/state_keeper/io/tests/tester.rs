@@ -168,7 +168,8 @@ impl Tester {
         storage
             .transactions_dal()
             .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
-            .await;
+            .await
+            .unwrap();
         storage
             .blocks_dal()
             .insert_miniblock(&MiniblockHeader {
@@ -188,7 +189,8 @@ impl Tester {
                 slice::from_ref(&tx_result),
                 1.into(),
             )
-            .await;
+            .await
+            .unwrap();
         tx_result
     }
 
@@ -213,7 +215,8 @@ impl Tester {
         storage
             .transactions_dal()
             .mark_txs_as_executed_in_l1_batch(batch_header.number, tx_results)
-            .await;
+            .await
+            .unwrap();
         storage
             .blocks_dal()
             .set_l1_batch_hash(batch_header.number, H256::default())
@@ -10,6 +10,7 @@ use multivm::interface::{Halt, L1BatchEnv, SystemEnv};
 use tokio::sync::watch;
 use zksync_dal::ConnectionPool;
 use zksync_types::{
+    api::TransactionConditionsError,
     block::MiniblockExecutionData,
     l2::TransactionType,
     protocol_version::{ProtocolUpgradeTx, ProtocolVersionId},
@@ -21,8 +22,12 @@ use super::{
     batch_executor::{BatchExecutor, BatchExecutorHandle, TxExecutionResult},
     extractors,
     io::{MiniblockParams, PendingBatchData, StateKeeperIO},
-    metrics::{AGGREGATION_METRICS, KEEPER_METRICS, L1_BATCH_METRICS},
+    metrics::{
+        L1BatchSealReason, MiniblockSealReason, AGGREGATION_METRICS, KEEPER_METRICS,
+        L1_BATCH_METRICS, MINIBLOCK_METRICS,
+    },
     seal_criteria::{ConditionalSealer, SealData, SealResolution},
+    tx_filter::{NoopTransactionFilter, TransactionFilter},
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
 };
@@ -68,6 +73,8 @@ pub struct ZkSyncStateKeeper {
     io: Box<dyn StateKeeperIO>,
     batch_executor_base: Box<dyn BatchExecutor>,
     sealer: Arc<dyn ConditionalSealer>,
+    seal_l1_batch_on_shutdown: bool,
+    tx_filter: Arc<dyn TransactionFilter>,
 }
 
 impl ZkSyncStateKeeper {
@@ -82,9 +89,28 @@ impl ZkSyncStateKeeper {
             io,
             batch_executor_base,
             sealer,
+            seal_l1_batch_on_shutdown: false,
+            tx_filter: Arc::new(NoopTransactionFilter),
         }
     }
 
+    /// Enables sealing the open L1 batch on shutdown. If enabled, once the stop signal is received, the state keeper
+    /// finishes the current miniblock and seals the L1 batch (provided it has any transactions) before exiting,
+    /// so that the batch doesn't need to be re-executed on the next start.
+    ///
+    /// Should only be enabled with I/O that doesn't depend on other components to provide new miniblock params
+    /// (e.g., [`MempoolIO`](super::MempoolIO)); otherwise, the shutdown may hang.
+    pub fn with_l1_batch_sealing_on_shutdown(mut self, enabled: bool) -> Self {
+        self.seal_l1_batch_on_shutdown = enabled;
+        self
+    }
+
+    /// Sets the filter applied to L2 transactions before their execution. Filtered transactions are rejected.
+    pub fn with_tx_filter(mut self, tx_filter: Arc<dyn TransactionFilter>) -> Self {
+        self.tx_filter = tx_filter;
+        self
+    }
+
     /// Temporary method to migrate fee addresses from L1 batches to miniblocks.
     pub fn run_fee_address_migration(
         &self,
@@ -180,12 +206,17 @@ impl ZkSyncStateKeeper {
         let mut l1_batch_seal_delta: Option<Instant> = None;
         while !self.is_canceled() {
             // This function will run until the batch can be sealed.
-            self.process_l1_batch(&batch_executor, &mut updates_manager, protocol_upgrade_tx)
+            let execution_latency = L1_BATCH_METRICS.execution_time.start();
+            let seal_reason = self
+                .process_l1_batch(&batch_executor, &mut updates_manager, protocol_upgrade_tx)
                 .await?;
+            execution_latency.observe();
+            L1_BATCH_METRICS.seal_reason[&seal_reason].inc();
 
             // Finish current batch.
             if !updates_manager.miniblock.executed_transactions.is_empty() {
                 self.io.seal_miniblock(&updates_manager).await;
+                MINIBLOCK_METRICS.seal_reason[&MiniblockSealReason::L1BatchSeal].inc();
                 // We've sealed the miniblock that we had, but we still need to setup the timestamp
                 // for the fictive miniblock.
                 let new_miniblock_params = self.wait_for_new_miniblock_params().await?;
@@ -312,7 +343,9 @@ impl ZkSyncStateKeeper {
     }
 
     async fn wait_for_new_miniblock_params(&mut self) -> Result<MiniblockParams, Error> {
-        while !self.is_canceled() {
+        // If the L1 batch is sealed on shutdown, we need miniblock params regardless of cancellation
+        // (e.g., for the fictive miniblock).
+        while !self.is_canceled() || self.seal_l1_batch_on_shutdown {
             if let Some(params) = self
                 .io
                 .wait_for_new_miniblock_params(POLL_WAIT_DURATION)
@@ -439,12 +472,13 @@ impl ZkSyncStateKeeper {
         Ok(())
     }
 
+    /// Processes transactions in the current L1 batch until it should be sealed. Returns the reason for sealing.
     async fn process_l1_batch(
         &mut self,
         batch_executor: &BatchExecutorHandle,
         updates_manager: &mut UpdatesManager,
         protocol_upgrade_tx: Option<ProtocolUpgradeTx>,
-    ) -> Result<(), Error> {
+    ) -> Result<L1BatchSealReason, Error> {
         if let Some(protocol_upgrade_tx) = protocol_upgrade_tx {
             self.process_upgrade_tx(batch_executor, updates_manager, protocol_upgrade_tx)
                 .await;
@@ -459,7 +493,7 @@ impl ZkSyncStateKeeper {
                     "L1 batch #{} should be sealed unconditionally as per sealing rules",
                     self.io.current_l1_batch_number()
                 );
-                return Ok(());
+                return Ok(L1BatchSealReason::Io);
             }
 
             if self.io.should_seal_miniblock(updates_manager) {
@@ -469,6 +503,7 @@ impl ZkSyncStateKeeper {
                     self.io.current_l1_batch_number()
                 );
                 self.io.seal_miniblock(updates_manager).await;
+                MINIBLOCK_METRICS.seal_reason[&MiniblockSealReason::Io].inc();
 
                 let new_miniblock_params = self
                     .wait_for_new_miniblock_params()
@@ -493,6 +528,25 @@ impl ZkSyncStateKeeper {
             waiting_latency.observe();
 
             let tx_hash = tx.hash();
+            if !tx.is_l1() {
+                if let Err(reason) = self.tx_filter.check(&tx) {
+                    self.io
+                        .reject(&tx, &format!("transaction is filtered: {reason}"))
+                        .await
+                        .with_context(|| format!("cannot reject transaction {tx_hash:?}"))?;
+                    continue;
+                }
+            }
+            if let Err(err) = self
+                .check_tx_conditions(batch_executor, updates_manager, &tx)
+                .await
+            {
+                self.io
+                    .reject(&tx, &format!("transaction conditions are not met: {err}"))
+                    .await
+                    .with_context(|| format!("cannot reject transaction {tx_hash:?}"))?;
+                continue;
+            }
             let (seal_resolution, exec_result) = self
                 .process_one_tx(batch_executor, updates_manager, tx.clone())
                 .await;
@@ -543,12 +597,47 @@ impl ZkSyncStateKeeper {
                      transaction {tx_hash}",
                     self.io.current_l1_batch_number()
                 );
-                return Ok(());
+                return Ok(L1BatchSealReason::Conditional);
             }
         }
+
+        if self.seal_l1_batch_on_shutdown && updates_manager.pending_executed_transactions_len() > 0
+        {
+            tracing::info!(
+                "Stop signal received, sealing L1 batch #{} before shutting down",
+                self.io.current_l1_batch_number()
+            );
+            return Ok(L1BatchSealReason::Shutdown);
+        }
         Err(Error::Canceled)
     }
 
+    /// Re-checks conditions of a transaction submitted via `eth_sendRawTransactionConditional` against the current
+    /// miniblock and the VM state. Conditions are checked on submission as well, but they may stop holding
+    /// while the transaction is in the mempool.
+    async fn check_tx_conditions(
+        &self,
+        batch_executor: &BatchExecutorHandle,
+        updates_manager: &UpdatesManager,
+        tx: &Transaction,
+    ) -> Result<(), TransactionConditionsError> {
+        let Some(conditions) = self.io.transaction_conditions(tx) else {
+            return Ok(());
+        };
+        conditions.check_block(
+            self.io.current_miniblock_number(),
+            updates_manager.miniblock.timestamp,
+        )?;
+
+        let keys: Vec<_> = conditions.storage_slots().map(|(key, _)| key).collect();
+        if !keys.is_empty() {
+            let values = batch_executor.read_storage(keys.clone()).await;
+            let values = keys.into_iter().zip(values).collect();
+            conditions.check_storage(&values)?;
+        }
+        Ok(())
+    }
+
     async fn process_upgrade_tx(
         &mut self,
         batch_executor: &BatchExecutorHandle,
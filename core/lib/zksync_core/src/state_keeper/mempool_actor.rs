@@ -88,6 +88,12 @@ impl MempoolFetcher {
             let latency = KEEPER_METRICS.mempool_sync.start();
             let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
             let mempool_info = self.mempool.get_mempool_info();
+            KEEPER_METRICS
+                .mempool_purged_accounts
+                .inc_by(mempool_info.purged_accounts.len() as u64);
+            KEEPER_METRICS
+                .mempool_evicted_transactions
+                .inc_by(mempool_info.evicted_transactions.len() as u64);
             let protocol_version = pending_protocol_version(&mut storage)
                 .await
                 .context("failed getting pending protocol version")?;
@@ -98,6 +104,13 @@ impl MempoolFetcher {
             )
             .await;
 
+            if !mempool_info.evicted_transactions.is_empty() {
+                storage
+                    .transactions_dal()
+                    .remove_evicted_txs(&mempool_info.evicted_transactions)
+                    .await
+                    .context("failed removing evicted transactions")?;
+            }
             let transactions = storage
                 .transactions_dal()
                 .sync_mempool(
@@ -110,7 +123,20 @@ impl MempoolFetcher {
                 .await
                 .context("failed syncing mempool")?;
             let nonces = get_transaction_nonces(&mut storage, &transactions).await?;
+            let l2_tx_hashes: Vec<_> = transactions
+                .iter()
+                .filter(|tx| !tx.is_l1())
+                .map(Transaction::hash)
+                .collect();
+            let conditions = storage
+                .transactions_dal()
+                .get_transaction_conditions(&l2_tx_hashes)
+                .await
+                .context("failed loading transaction conditions")?;
             drop(storage);
+            self.mempool
+                .remove_account_conditions(&mempool_info.purged_accounts);
+            self.mempool.update_conditions(&transactions, conditions);
 
             #[cfg(test)]
             {
@@ -160,9 +186,10 @@ async fn get_transaction_nonces(
 
 #[cfg(test)]
 mod tests {
+    use zksync_config::configs::chain::MempoolTxOrdering;
     use zksync_types::{
-        fee::TransactionExecutionMetrics, L2ChainId, MiniblockNumber, PriorityOpId,
-        ProtocolVersionId, StorageLog, H256,
+        api::TransactionConditions, fee::TransactionExecutionMetrics, L2ChainId, MiniblockNumber,
+        PriorityOpId, ProtocolVersionId, StorageLog, H256,
     };
     use zksync_utils::u256_to_h256;
 
@@ -179,6 +206,9 @@ mod tests {
         stuck_tx_timeout: 0,
         remove_stuck_txs: false,
         delay_interval: 10,
+        max_queued_txs_per_account: None,
+        max_size_bytes: None,
+        tx_ordering: MempoolTxOrdering::Fifo,
     };
 
     #[tokio::test]
@@ -248,7 +278,8 @@ mod tests {
         storage
             .transactions_dal()
             .insert_transaction_l2(transaction, TransactionExecutionMetrics::default())
-            .await;
+            .await
+            .unwrap();
         drop(storage);
 
         // Check that the transaction is eventually synced.
@@ -260,6 +291,77 @@ mod tests {
         fetcher_task.await.unwrap().expect("fetcher errored");
     }
 
+    #[tokio::test]
+    async fn syncing_transaction_conditions() {
+        let pool = ConnectionPool::constrained_test_pool(1).await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let mut mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let fee_params_provider = Arc::new(MockBatchFeeParamsProvider::default());
+        let fee_input = fee_params_provider.get_batch_fee_input().await;
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, ProtocolVersionId::latest().into());
+
+        let mut fetcher = MempoolFetcher::new(
+            mempool.clone(),
+            fee_params_provider,
+            &TEST_MEMPOOL_CONFIG,
+            pool.clone(),
+        );
+        let (tx_hashes_sender, mut tx_hashes_receiver) = mpsc::unbounded_channel();
+        fetcher.transaction_hashes_sender = tx_hashes_sender;
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let fetcher_task = tokio::spawn(fetcher.run(stop_receiver));
+
+        let conditional_tx = create_l2_transaction(base_fee, gas_per_pubdata);
+        let conditions = TransactionConditions {
+            block_number_max: Some(100.into()),
+            ..TransactionConditions::default()
+        };
+        let other_tx = create_l2_transaction(base_fee, gas_per_pubdata);
+        let mut storage = pool.access_storage().await.unwrap();
+        let mut transaction = storage.start_transaction().await.unwrap();
+        transaction
+            .transactions_dal()
+            .insert_transaction_l2(
+                conditional_tx.clone(),
+                TransactionExecutionMetrics::default(),
+            )
+            .await
+            .unwrap();
+        transaction
+            .transactions_dal()
+            .insert_transaction_conditions(conditional_tx.hash(), &conditions)
+            .await
+            .unwrap();
+        transaction
+            .transactions_dal()
+            .insert_transaction_l2(other_tx.clone(), TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+        drop(storage);
+
+        let tx_hashes = wait_for_new_transactions(&mut tx_hashes_receiver).await;
+        assert_eq!(tx_hashes.len(), 2);
+        let conditional_tx = Transaction::from(conditional_tx);
+        assert_eq!(
+            mempool.transaction_conditions(&conditional_tx),
+            Some(conditions)
+        );
+        assert_eq!(mempool.transaction_conditions(&other_tx.into()), None);
+
+        mempool.remove_conditions([&conditional_tx]);
+        assert_eq!(mempool.transaction_conditions(&conditional_tx), None);
+
+        stop_sender.send_replace(true);
+        fetcher_task.await.unwrap().expect("fetcher errored");
+    }
+
     async fn wait_for_new_transactions(
         tx_hashes_receiver: &mut mpsc::UnboundedReceiver<Vec<H256>>,
     ) -> Vec<H256> {
@@ -302,7 +404,8 @@ mod tests {
         storage
             .transactions_dal()
             .insert_transaction_l2(transaction, TransactionExecutionMetrics::default())
-            .await;
+            .await
+            .unwrap();
         drop(storage);
 
         tokio::time::sleep(TEST_MEMPOOL_CONFIG.sync_interval() * 5).await;
@@ -353,7 +456,8 @@ mod tests {
         storage
             .transactions_dal()
             .insert_transaction_l2(transaction, TransactionExecutionMetrics::default())
-            .await;
+            .await
+            .unwrap();
         drop(storage);
 
         // Check that the transaction is eventually synced.
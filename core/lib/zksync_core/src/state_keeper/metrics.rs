@@ -8,7 +8,7 @@ use std::{
 use multivm::interface::VmExecutionResultAndLogs;
 use vise::{
     Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
-    Metrics,
+    Metrics, Unit,
 };
 use zksync_mempool::MempoolStore;
 use zksync_types::{tx::tx_execution_info::DeduplicatedWritesMetrics, ProtocolVersionId};
@@ -61,6 +61,10 @@ pub(crate) struct StateKeeperMetrics {
     /// Latency to synchronize the mempool with Postgres.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub mempool_sync: Histogram<Duration>,
+    /// Number of accounts purged from the mempool because it was full.
+    pub mempool_purged_accounts: Counter,
+    /// Number of L2 transactions evicted from the mempool because their initiator had too many queued transactions.
+    pub mempool_evicted_transactions: Counter,
     /// Latency of the state keeper waiting for a transaction.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub waiting_for_tx: Histogram<Duration>,
@@ -69,6 +73,10 @@ pub(crate) struct StateKeeperMetrics {
     pub get_tx_from_mempool: Histogram<Duration>,
     /// Number of transactions rejected by the state keeper.
     pub rejected_transactions: Counter,
+    /// Number of L2 transactions mirrored from another chain in the shadow mode.
+    pub shadow_mirrored_transactions: Counter,
+    /// Number of L1 batches awaiting proof generation, as observed when opening a new L1 batch.
+    pub proof_backlog: Gauge<usize>,
     /// Time spent waiting for the hash of a previous L1 batch.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub wait_for_prev_hash_time: Histogram<Duration>,
@@ -98,6 +106,9 @@ pub(super) struct StateKeeperGauges {
     mempool_l1_size: Gauge<usize>,
     /// Current number of L2 transactions in the mempool.
     mempool_l2_size: Gauge<u64>,
+    /// Current total size of L2 transactions in the mempool.
+    #[metrics(unit = Unit::Bytes)]
+    mempool_l2_size_in_bytes: Gauge<usize>,
     /// Current size of the L2 priority queue.
     l2_priority_queue_size: Gauge<usize>,
 }
@@ -115,6 +126,9 @@ impl StateKeeperGauges {
                 let gauges = StateKeeperGauges::default();
                 gauges.mempool_l1_size.set(stats.l1_transaction_count);
                 gauges.mempool_l2_size.set(stats.l2_transaction_count);
+                gauges
+                    .mempool_l2_size_in_bytes
+                    .set(stats.l2_transactions_size_in_bytes);
                 gauges
                     .l2_priority_queue_size
                     .set(stats.l2_priority_queue_size);
@@ -197,6 +211,19 @@ pub(super) enum L1BatchSealStage {
     CommitL1Batch,
 }
 
+/// Reason for sealing an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(super) enum L1BatchSealReason {
+    /// Sealed as per I/O criteria (e.g., the `no_txs_timeout` rule on the main node).
+    Io,
+    /// Sealed as per conditional criteria after executing a transaction. Criteria that have fired
+    /// are reported in `server_tx_aggregation_reason`.
+    Conditional,
+    /// Sealed on the state keeper shutdown.
+    Shutdown,
+}
+
 /// Buckets for positive integer, not-so-large values (e.g., initial writes count).
 const COUNT_BUCKETS: Buckets = Buckets::values(&[
     10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1_000.0, 2_000.0, 5_000.0, 10_000.0, 20_000.0, 50_000.0,
@@ -205,6 +232,10 @@ const COUNT_BUCKETS: Buckets = Buckets::values(&[
 const L1_BATCH_SEAL_DELTA_BUCKETS: Buckets = Buckets::values(&[
     0.1, 0.5, 1.0, 5.0, 10.0, 20.0, 30.0, 40.0, 60.0, 90.0, 120.0, 180.0, 240.0, 300.0,
 ]);
+/// Buckets for gas used by an L1 batch.
+const L1_BATCH_GAS_BUCKETS: Buckets = Buckets::exponential(100_000.0..=100_000_000.0, 2.0);
+/// Buckets for pubdata published by an L1 batch (in bytes).
+const L1_BATCH_PUBDATA_BUCKETS: Buckets = Buckets::exponential(1_000.0..=1_000_000.0, 2.0);
 
 /// Metrics related to L1 batch sealing.
 #[derive(Debug, Metrics)]
@@ -222,6 +253,21 @@ pub(crate) struct L1BatchMetrics {
     /// Number of transactions in a single L1 batch.
     #[metrics(buckets = COUNT_BUCKETS)]
     pub transactions_in_l1_batch: Histogram<usize>,
+    /// Number of sealed L1 batches split by the sealing reason.
+    pub seal_reason: Family<L1BatchSealReason, Counter>,
+    /// Gas used by a single L1 batch, including the batch tip.
+    #[metrics(buckets = L1_BATCH_GAS_BUCKETS)]
+    pub gas_used: Histogram<usize>,
+    /// Computational gas used by a single L1 batch, including the batch tip.
+    #[metrics(buckets = L1_BATCH_GAS_BUCKETS)]
+    pub computational_gas_used: Histogram<usize>,
+    /// Pubdata published by a single L1 batch.
+    #[metrics(buckets = L1_BATCH_PUBDATA_BUCKETS, unit = Unit::Bytes)]
+    pub pubdata_published: Histogram<usize>,
+    /// Time spent by the state keeper executing transactions in a single L1 batch, i.e., from opening the batch
+    /// to the decision to seal it.
+    #[metrics(buckets = L1_BATCH_SEAL_DELTA_BUCKETS)]
+    pub execution_time: Histogram<Duration>,
     /// Total latency of sealing an L1 batch.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub sealed_time: Histogram<Duration>,
@@ -260,6 +306,16 @@ pub(super) enum MiniblockQueueStage {
     NextCommand,
 }
 
+/// Reason for sealing a miniblock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(super) enum MiniblockSealReason {
+    /// Sealed as per I/O criteria (e.g., the miniblock commit deadline on the main node).
+    Io,
+    /// Sealed as the last miniblock in an L1 batch being sealed.
+    L1BatchSeal,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
 #[metrics(rename_all = "snake_case")]
 pub(super) enum MiniblockSealStage {
@@ -299,6 +355,8 @@ pub(super) struct MiniblockMetrics {
     /// Number of transactions in a single miniblock.
     #[metrics(buckets = Buckets::linear(0.0..=50.0, 5.0))]
     pub transactions_in_miniblock: Histogram<usize>,
+    /// Number of sealed miniblocks (excluding fictive ones) split by the sealing reason.
+    pub seal_reason: Family<MiniblockSealReason, Counter>,
     /// Total latency of sealing a miniblock.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub sealed_time: Histogram<Duration>,
@@ -373,6 +431,7 @@ pub(super) enum ExecutorCommand {
     ExecuteTx,
     StartNextMiniblock,
     RollbackLastTx,
+    ReadStorage,
     FinishBatch,
 }
 
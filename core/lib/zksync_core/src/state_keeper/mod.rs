@@ -8,12 +8,14 @@ use zksync_config::{
 use zksync_dal::ConnectionPool;
 use zksync_object_store::ObjectStore;
 
+use self::tx_filter::{DenyListFilter, TxLimits};
 pub use self::{
     batch_executor::{main_executor::MainBatchExecutor, BatchExecutor},
     io::{mempool::MempoolIO, MiniblockSealer, MiniblockSealerHandle, StateKeeperIO},
     keeper::ZkSyncStateKeeper,
     mempool_actor::MempoolFetcher,
     seal_criteria::SequencerSealer,
+    shadow::ShadowMempoolMirror,
     types::MempoolGuard,
 };
 use crate::fee_model::BatchFeeModelInputProvider;
@@ -25,8 +27,10 @@ mod keeper;
 mod mempool_actor;
 pub(crate) mod metrics;
 pub mod seal_criteria;
+mod shadow;
 #[cfg(test)]
 pub(crate) mod tests;
+pub mod tx_filter;
 pub(crate) mod types;
 pub(crate) mod updates;
 
@@ -69,6 +73,10 @@ pub(crate) async fn create_state_keeper(
     .await
     .expect("Failed initializing main node I/O for state keeper");
 
+    let seal_l1_batch_on_shutdown = state_keeper_config.seal_l1_batch_on_shutdown;
+    let tx_limits = TxLimits::from_config(&state_keeper_config)
+        .expect("Invalid transaction limits in state keeper config");
+    let tx_filter = (DenyListFilter::from_config(&state_keeper_config), tx_limits);
     let sealer = SequencerSealer::new(state_keeper_config);
     ZkSyncStateKeeper::new(
         stop_receiver,
@@ -76,4 +84,6 @@ pub(crate) async fn create_state_keeper(
         Box::new(batch_executor_base),
         Arc::new(sealer),
     )
+    .with_l1_batch_sealing_on_shutdown(seal_l1_batch_on_shutdown)
+    .with_tx_filter(Arc::new(tx_filter))
 }
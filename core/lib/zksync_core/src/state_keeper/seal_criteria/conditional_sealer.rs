@@ -115,20 +115,21 @@ impl ConditionalSealer for SequencerSealer {
 }
 
 impl SequencerSealer {
+    /// Creates a sealer with the [default criteria](Self::default_sealers()).
     pub fn new(config: StateKeeperConfig) -> Self {
         let sealers = Self::default_sealers(&config);
         Self { config, sealers }
     }
 
-    #[cfg(test)]
-    pub(in crate::state_keeper) fn with_sealers(
-        config: StateKeeperConfig,
-        sealers: Vec<Box<dyn SealCriterion>>,
-    ) -> Self {
+    /// Creates a sealer with the specified criteria. This allows chain operators to add chain-specific
+    /// criteria (e.g., by extending [`Self::default_sealers()`]) or to disable some of the built-in ones.
+    pub fn with_sealers(config: StateKeeperConfig, sealers: Vec<Box<dyn SealCriterion>>) -> Self {
         Self { config, sealers }
     }
 
-    fn default_sealers(config: &StateKeeperConfig) -> Vec<Box<dyn SealCriterion>> {
+    /// Returns the criteria used by default: transaction slots, L1 gas, pubdata size, circuits,
+    /// transaction encoding size and gas for the batch tip.
+    pub fn default_sealers(config: &StateKeeperConfig) -> Vec<Box<dyn SealCriterion>> {
         vec![
             Box::new(criteria::SlotsCriterion),
             Box::new(criteria::GasCriterion),
@@ -169,3 +170,63 @@ impl ConditionalSealer for NoopSealer {
         SealResolution::NoSeal
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Criterion rejecting transactions that publish any bytecodes.
+    #[derive(Debug)]
+    struct NoBytecodesCriterion;
+
+    impl SealCriterion for NoBytecodesCriterion {
+        fn should_seal(
+            &self,
+            _config: &StateKeeperConfig,
+            _block_open_timestamp_ms: u128,
+            _tx_count: usize,
+            _block_data: &SealData,
+            tx_data: &SealData,
+            _protocol_version: ProtocolVersionId,
+        ) -> SealResolution {
+            if tx_data.execution_metrics().published_bytecode_bytes > 0 {
+                SealResolution::Unexecutable("publishes bytecodes".into())
+            } else {
+                SealResolution::NoSeal
+            }
+        }
+
+        fn prom_criterion_name(&self) -> &'static str {
+            "no_bytecodes"
+        }
+    }
+
+    #[test]
+    fn custom_seal_criterion() {
+        let config = StateKeeperConfig::for_tests();
+        let mut sealers = SequencerSealer::default_sealers(&config);
+        sealers.push(Box::new(NoBytecodesCriterion));
+        let sealer = SequencerSealer::with_sealers(config, sealers);
+
+        let protocol_version = ProtocolVersionId::latest();
+        let mut data = SealData {
+            gas_remaining: u32::MAX, // Enough gas for the batch tip
+            ..SealData::default()
+        };
+        assert_eq!(
+            sealer.find_unexecutable_reason(&data, protocol_version),
+            None
+        );
+
+        data.execution_metrics.published_bytecode_bytes = 32;
+        assert_eq!(
+            sealer.find_unexecutable_reason(&data, protocol_version),
+            Some("no_bytecodes")
+        );
+        let resolution = sealer.should_seal_l1_batch(1, 0, 1, &data, &data, protocol_version);
+        assert_eq!(
+            resolution,
+            SealResolution::Unexecutable("publishes bytecodes".into())
+        );
+    }
+}
@@ -104,9 +104,39 @@ impl SealData {
             gas_remaining: tx_metrics.gas_remaining,
         }
     }
+
+    /// Returns VM execution metrics (e.g., the number of emitted events or published bytecodes).
+    pub fn execution_metrics(&self) -> &ExecutionMetrics {
+        &self.execution_metrics
+    }
+
+    /// Returns L1 gas necessary to commit, prove and execute the transaction or block.
+    pub fn gas_count(&self) -> BlockGasCount {
+        self.gas_count
+    }
+
+    /// Returns the cumulative size of transactions encoded for the bootloader.
+    pub fn cumulative_size(&self) -> usize {
+        self.cumulative_size
+    }
+
+    /// Returns metrics for deduplicated storage writes.
+    pub fn writes_metrics(&self) -> &DeduplicatedWritesMetrics {
+        &self.writes_metrics
+    }
+
+    /// Returns gas remaining after transaction execution.
+    pub fn gas_remaining(&self) -> u32 {
+        self.gas_remaining
+    }
 }
 
-pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
+/// Deterministic criterion deciding whether an L1 batch should be sealed after executing a transaction.
+/// Criteria are registered in [`SequencerSealer`]; besides the built-in criteria, chain operators
+/// can provide custom ones via [`SequencerSealer::with_sealers()`].
+pub trait SealCriterion: fmt::Debug + Send + Sync + 'static {
+    /// Decides whether the batch should be sealed given the `block_data` for the entire batch
+    /// (including the transaction) and `tx_data` for the last executed transaction.
     fn should_seal(
         &self,
         config: &StateKeeperConfig,
@@ -117,6 +147,7 @@ pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
         protocol_version: ProtocolVersionId,
     ) -> SealResolution;
 
+    /// Returns the criterion name used in metrics and logs. Should be unique among registered criteria.
     // We need self here only for rust restrictions for creating an object from trait
     // https://doc.rust-lang.org/reference/items/traits.html#object-safety
     fn prom_criterion_name(&self) -> &'static str;
@@ -131,18 +162,55 @@ pub trait IoSealCriteria {
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool;
 }
 
+/// Seals miniblocks and L1 batches by timeout.
+///
+/// If the proof backlog is tracked, the L1 batch commit deadline is stretched when the backlog grows. This only
+/// has an effect when traffic is low: capacity criteria (see [`SequencerSealer`]) are hard limits on the L1 batch
+/// contents and are not affected by the backlog, so under high load L1 batches are still sealed once they are full.
 #[derive(Debug, Clone, Copy)]
 pub(super) struct TimeoutSealer {
     block_commit_deadline_ms: u64,
     miniblock_commit_deadline_ms: u64,
+    proof_backlog_threshold: Option<u32>,
+    proof_backlog: usize,
 }
 
 impl TimeoutSealer {
+    /// Maximum factor by which the L1 batch commit deadline can be increased because of the proof backlog.
+    const MAX_BACKLOG_DEADLINE_FACTOR: u64 = 4;
+
     pub fn new(config: &StateKeeperConfig) -> Self {
         Self {
             block_commit_deadline_ms: config.block_commit_deadline_ms,
             miniblock_commit_deadline_ms: config.miniblock_commit_deadline_ms,
+            proof_backlog_threshold: config.proof_backlog_threshold,
+            proof_backlog: 0,
+        }
+    }
+
+    /// Checks whether the proof backlog should be tracked (i.e., whether it can affect sealing).
+    pub fn tracks_proof_backlog(&self) -> bool {
+        self.proof_backlog_threshold.is_some()
+    }
+
+    /// Sets the number of L1 batches awaiting proof generation.
+    pub fn set_proof_backlog(&mut self, proof_backlog: usize) {
+        self.proof_backlog = proof_backlog;
+    }
+
+    /// Returns the L1 batch commit deadline adjusted for the proof backlog. If the backlog exceeds the threshold,
+    /// the deadline grows proportionally to the backlog.
+    fn block_commit_deadline_ms(&self) -> u64 {
+        let Some(threshold) = self.proof_backlog_threshold else {
+            return self.block_commit_deadline_ms;
+        };
+        let threshold = u64::from(threshold.max(1));
+        let backlog = self.proof_backlog as u64;
+        if backlog <= threshold {
+            return self.block_commit_deadline_ms;
         }
+        let backlog = backlog.min(threshold * Self::MAX_BACKLOG_DEADLINE_FACTOR);
+        self.block_commit_deadline_ms * backlog / threshold
     }
 }
 
@@ -155,7 +223,7 @@ impl IoSealCriteria for TimeoutSealer {
             return false;
         }
 
-        let block_commit_deadline_ms = self.block_commit_deadline_ms;
+        let block_commit_deadline_ms = self.block_commit_deadline_ms();
         // Verify timestamp
         let should_seal_timeout =
             millis_since(manager.batch_timestamp()) > block_commit_deadline_ms;
@@ -181,9 +249,12 @@ impl IoSealCriteria for TimeoutSealer {
 mod tests {
     use zksync_utils::time::seconds_since_epoch;
 
+    use zksync_types::Address;
+
     use super::*;
     use crate::state_keeper::tests::{
-        create_execution_result, create_transaction, create_updates_manager,
+        create_execution_result, create_transaction, create_updates_manager, default_l1_batch_env,
+        default_system_env,
     };
 
     fn apply_tx_to_manager(manager: &mut UpdatesManager) {
@@ -204,6 +275,8 @@ mod tests {
         let mut timeout_miniblock_sealer = TimeoutSealer {
             block_commit_deadline_ms: 10_000,
             miniblock_commit_deadline_ms: 10_000,
+            proof_backlog_threshold: None,
+            proof_backlog: 0,
         };
 
         let mut manager = create_updates_manager();
@@ -230,4 +303,63 @@ mod tests {
             "Non-empty miniblock with too recent timestamp shouldn't be sealed"
         );
     }
+
+    #[test]
+    fn block_commit_deadline_with_proof_backlog() {
+        let mut sealer = TimeoutSealer {
+            block_commit_deadline_ms: 10_000,
+            miniblock_commit_deadline_ms: 1_000,
+            proof_backlog_threshold: None,
+            proof_backlog: 100,
+        };
+        assert_eq!(sealer.block_commit_deadline_ms(), 10_000);
+
+        sealer.proof_backlog_threshold = Some(10);
+        for (backlog, expected_deadline_ms) in [
+            (0, 10_000),
+            (10, 10_000),
+            (15, 15_000),
+            (30, 30_000),
+            (40, 40_000),
+            (100, 40_000),
+        ] {
+            sealer.set_proof_backlog(backlog);
+            assert_eq!(
+                sealer.block_commit_deadline_ms(),
+                expected_deadline_ms,
+                "backlog: {backlog}"
+            );
+        }
+    }
+
+    #[test]
+    fn proof_backlog_does_not_affect_capacity_sealing() {
+        let config = StateKeeperConfig {
+            transaction_slots: 2,
+            block_commit_deadline_ms: 10_000,
+            proof_backlog_threshold: Some(10),
+            ..StateKeeperConfig::for_tests()
+        };
+        let mut timeout_sealer = TimeoutSealer::new(&config);
+        timeout_sealer.set_proof_backlog(100);
+
+        let l1_batch_env = default_l1_batch_env(1, seconds_since_epoch() - 20, Address::default());
+        let mut manager = UpdatesManager::new(&l1_batch_env, &default_system_env());
+        apply_tx_to_manager(&mut manager);
+        // The batch is older than the configured commit deadline, but not older than the deadline
+        // stretched because of the backlog.
+        assert!(!timeout_sealer.should_seal_l1_batch_unconditionally(&manager));
+
+        // Capacity criteria still seal a full batch regardless of the backlog.
+        let sealer = SequencerSealer::new(config);
+        let resolution = sealer.should_seal_l1_batch(
+            1,
+            0,
+            2,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::IncludeAndSeal);
+    }
 }
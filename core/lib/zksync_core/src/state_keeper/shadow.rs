@@ -0,0 +1,168 @@
+//! Shadow mode of the state keeper. In this mode, L2 transactions are mirrored from the schema of another chain
+//! (e.g., a production one), so that changes like protocol upgrades can be rehearsed against real traffic
+//! without affecting the mirrored chain.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::chain::MempoolConfig;
+use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
+use zksync_types::{fee::TransactionExecutionMetrics, helpers::unix_timestamp_ms, H256};
+
+use super::metrics::KEEPER_METRICS;
+
+/// Position of the last mirrored transaction: its receipt timestamp (in milliseconds) and hash.
+type MirrorCursor = (u64, H256);
+
+/// Copies L2 transactions from the source pool (connected to the schema of the mirrored chain) to the node pool,
+/// from which they are picked up by the mempool as usual. Never modifies data in the source schema.
+#[derive(Debug)]
+pub struct ShadowMempoolMirror {
+    source_pool: ConnectionPool,
+    pool: ConnectionPool,
+    poll_interval: Duration,
+    batch_size: usize,
+}
+
+impl ShadowMempoolMirror {
+    pub fn new(source_pool: ConnectionPool, pool: ConnectionPool, config: &MempoolConfig) -> Self {
+        Self {
+            source_pool,
+            pool,
+            poll_interval: config.sync_interval(),
+            batch_size: config.sync_batch_size,
+        }
+    }
+
+    /// Mirroring continues from the last transaction stored in the node schema. If there are no such transactions,
+    /// only transactions received from now on are mirrored.
+    async fn initial_cursor(&self) -> anyhow::Result<MirrorCursor> {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let last_tx = storage
+            .transactions_dal()
+            .get_last_received_l2_transaction()
+            .await
+            .context("failed loading last received L2 transaction")?;
+        Ok(match last_tx {
+            Some(tx) => (tx.received_timestamp_ms, tx.hash()),
+            None => (unix_timestamp_ms(), H256::zero()),
+        })
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut cursor = self.initial_cursor().await?;
+        tracing::info!("Mirroring L2 transactions received after {cursor:?}");
+
+        while !*stop_receiver.borrow_and_update() {
+            let loaded_count = self.mirror_transactions(&mut cursor).await?;
+            if loaded_count == self.batch_size {
+                continue; // There may be more transactions to mirror
+            }
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, shadow mempool mirror is shutting down");
+        Ok(())
+    }
+
+    /// Mirrors the next portion of transactions and advances the `cursor`. Returns the number of loaded transactions.
+    async fn mirror_transactions(&self, cursor: &mut MirrorCursor) -> anyhow::Result<usize> {
+        let mut source_storage = self
+            .source_pool
+            .access_storage_tagged("state_keeper")
+            .await?;
+        let transactions = source_storage
+            .transactions_dal()
+            .get_l2_transactions_received_after(cursor.0, cursor.1, self.batch_size)
+            .await
+            .context("failed loading L2 transactions to mirror")?;
+        drop(source_storage);
+
+        let Some(last_tx) = transactions.last() else {
+            return Ok(0);
+        };
+        let new_cursor = (last_tx.received_timestamp_ms, last_tx.hash());
+        let loaded_count = transactions.len();
+
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let mut added_count = 0;
+        for tx in transactions {
+            let result = storage
+                .transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await
+                .context("failed inserting mirrored L2 transaction")?;
+            if matches!(
+                result,
+                L2TxSubmissionResult::Added | L2TxSubmissionResult::Replaced
+            ) {
+                added_count += 1;
+            }
+        }
+        drop(storage);
+
+        KEEPER_METRICS
+            .shadow_mirrored_transactions
+            .inc_by(added_count);
+        tracing::debug!(
+            "Mirrored {added_count} out of {loaded_count} loaded L2 transactions; new cursor: {new_cursor:?}"
+        );
+        *cursor = new_cursor;
+        Ok(loaded_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testonly::create_l2_transaction;
+
+    #[tokio::test]
+    async fn mirroring_transactions() {
+        let source_pool = ConnectionPool::test_pool().await;
+        let pool = ConnectionPool::test_pool().await;
+        let mirror = ShadowMempoolMirror {
+            source_pool: source_pool.clone(),
+            pool: pool.clone(),
+            poll_interval: Duration::from_millis(10),
+            batch_size: 2,
+        };
+
+        let mut cursor = mirror.initial_cursor().await.unwrap();
+        let mut source_storage = source_pool.access_storage().await.unwrap();
+        let mut tx_hashes = vec![];
+        for i in 1..=3 {
+            let mut tx = create_l2_transaction(10, 100);
+            tx.received_timestamp_ms = cursor.0 + i;
+            tx_hashes.push(tx.hash());
+            source_storage
+                .transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+        }
+        drop(source_storage);
+
+        assert_eq!(mirror.mirror_transactions(&mut cursor).await.unwrap(), 2);
+        assert_eq!(cursor.1, tx_hashes[1]);
+        assert_eq!(mirror.mirror_transactions(&mut cursor).await.unwrap(), 1);
+        assert_eq!(cursor.1, tx_hashes[2]);
+        assert_eq!(mirror.mirror_transactions(&mut cursor).await.unwrap(), 0);
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let last_tx = storage
+            .transactions_dal()
+            .get_last_received_l2_transaction()
+            .await
+            .unwrap()
+            .expect("no mirrored transactions");
+        assert_eq!(last_tx.hash(), tx_hashes[2]);
+        drop(storage);
+        assert_eq!(mirror.initial_cursor().await.unwrap(), cursor);
+    }
+}
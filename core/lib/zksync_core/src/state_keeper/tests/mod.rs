@@ -20,6 +20,7 @@ use zksync_contracts::BaseSystemContracts;
 use zksync_system_constants::ZKPORTER_IS_AVAILABLE;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
+    api::{KnownAccountState, TransactionConditions},
     block::{BlockGasCount, MiniblockExecutionData, MiniblockHasher},
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
     tx::tx_execution_info::ExecutionMetrics,
@@ -165,7 +166,7 @@ pub(super) enum Query {
 }
 
 impl Query {
-    fn into_log(self, key: U256, tx_number_in_block: u16) -> StorageLogQuery {
+    pub(super) fn into_log(self, key: U256, tx_number_in_block: u16) -> StorageLogQuery {
         let log_type = match self {
             Self::Read(_) => StorageLogQueryType::Read,
             Self::InitialWrite(_) => StorageLogQueryType::InitialWrite,
@@ -351,6 +352,70 @@ async fn rejected_tx() {
         .await;
 }
 
+#[tokio::test]
+async fn rejected_conditional_txs() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    let expired_tx = random_tx(1);
+    let expired_conditions = TransactionConditions {
+        block_number_max: Some(0.into()),
+        ..TransactionConditions::default()
+    };
+    let slot_mismatch_tx = random_tx(2);
+    let slot = (Address::repeat_byte(1), H256::repeat_byte(2));
+    let slot_mismatch_conditions = TransactionConditions {
+        known_accounts: [(
+            slot.0,
+            KnownAccountState::Slots([(slot.1, H256::repeat_byte(3))].into()),
+        )]
+        .into(),
+        ..TransactionConditions::default()
+    };
+    let valid_tx = random_tx(3);
+    let valid_conditions = TransactionConditions {
+        block_number_min: Some(1.into()),
+        known_accounts: [(
+            slot.0,
+            KnownAccountState::Slots([(slot.1, H256::zero())].into()),
+        )]
+        .into(),
+        ..TransactionConditions::default()
+    };
+
+    TestScenario::new()
+        .with_tx_conditions(&expired_tx, expired_conditions)
+        .with_tx_conditions(&slot_mismatch_tx, slot_mismatch_conditions)
+        .with_tx_conditions(&valid_tx, valid_conditions)
+        .seal_miniblock_when(|updates| updates.miniblock.executed_transactions.len() == 1)
+        .next_tx("Expired tx", expired_tx.clone(), successful_exec())
+        .tx_rejected(
+            "Expired tx got rejected",
+            expired_tx,
+            Some("block number 1 is out of the allowed range".to_owned()),
+        )
+        .next_tx(
+            "Tx with slot mismatch",
+            slot_mismatch_tx.clone(),
+            successful_exec(),
+        )
+        .tx_rejected(
+            "Tx with slot mismatch got rejected",
+            slot_mismatch_tx,
+            Some("has unexpected value".to_owned()),
+        )
+        .next_tx("Tx with valid conditions", valid_tx, successful_exec())
+        .miniblock_sealed("Miniblock with conditional tx")
+        .next_tx("Second successful tx", random_tx(4), successful_exec())
+        .miniblock_sealed("Second miniblock")
+        .batch_sealed("Batch with 2 successful txs")
+        .run(sealer)
+        .await;
+}
+
 #[tokio::test]
 async fn bootloader_tip_out_of_gas_flow() {
     let config = StateKeeperConfig {
@@ -649,3 +714,26 @@ async fn protocol_upgrade() {
         .run(sealer)
         .await;
 }
+
+#[tokio::test]
+async fn sealing_l1_batch_on_shutdown() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    TestScenario::new()
+        .seal_l1_batch_on_shutdown()
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .no_txs_until_next_action("No more txs")
+        .stop("Stop signal")
+        .miniblock_sealed_with("Miniblock sealed on shutdown", |updates| {
+            assert_eq!(updates.miniblock.executed_transactions.len(), 1);
+        })
+        .batch_sealed_with("Batch sealed on shutdown", |_, updates, _| {
+            assert_eq!(updates.pending_executed_transactions_len(), 1);
+        })
+        .run(sealer)
+        .await;
+}
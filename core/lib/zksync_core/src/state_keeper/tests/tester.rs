@@ -16,9 +16,9 @@ use multivm::{
 };
 use tokio::sync::{mpsc, watch};
 use zksync_types::{
-    block::MiniblockExecutionData, fee_model::BatchFeeInput, protocol_version::ProtocolUpgradeTx,
-    witness_block_state::WitnessBlockState, Address, L1BatchNumber, L2ChainId, MiniblockNumber,
-    ProtocolVersionId, Transaction, H256,
+    api::TransactionConditions, block::MiniblockExecutionData, fee_model::BatchFeeInput,
+    protocol_version::ProtocolUpgradeTx, witness_block_state::WitnessBlockState, Address,
+    L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
 };
 
 use crate::{
@@ -53,6 +53,8 @@ pub(crate) struct TestScenario {
     pending_batch: Option<PendingBatchData>,
     l1_batch_seal_fn: Box<SealFn>,
     miniblock_seal_fn: Box<SealFn>,
+    tx_conditions: HashMap<H256, TransactionConditions>,
+    seal_l1_batch_on_shutdown: bool,
 }
 
 type SealFn = dyn FnMut(&UpdatesManager) -> bool + Send;
@@ -74,6 +76,8 @@ impl TestScenario {
             pending_batch: None,
             l1_batch_seal_fn: Box::new(|_| false),
             miniblock_seal_fn: Box::new(|_| false),
+            tx_conditions: HashMap::new(),
+            seal_l1_batch_on_shutdown: false,
         }
     }
 
@@ -92,6 +96,19 @@ impl TestScenario {
         self
     }
 
+    /// Sends a stop signal to the state keeper. Unlike the signal sent after the last action, this one can be sent
+    /// in the middle of the scenario.
+    pub(crate) fn stop(mut self, description: &'static str) -> Self {
+        self.actions.push_back(ScenarioItem::Stop(description));
+        self
+    }
+
+    /// Enables sealing the open L1 batch on shutdown in the tested state keeper.
+    pub(crate) fn seal_l1_batch_on_shutdown(mut self) -> Self {
+        self.seal_l1_batch_on_shutdown = true;
+        self
+    }
+
     /// Increments protocol version returned by IO.
     pub(crate) fn increment_protocol_version(mut self, description: &'static str) -> Self {
         self.actions
@@ -113,6 +130,16 @@ impl TestScenario {
         self
     }
 
+    /// Sets conditions for the transaction that will be returned by the IO.
+    pub(crate) fn with_tx_conditions(
+        mut self,
+        tx: &Transaction,
+        conditions: TransactionConditions,
+    ) -> Self {
+        self.tx_conditions.insert(tx.hash(), conditions);
+        self
+    }
+
     /// Expect the state keeper to rollback the transaction (i.e. return to the mempool).
     pub(crate) fn tx_rollback(mut self, description: &'static str, tx: Transaction) -> Self {
         self.actions
@@ -196,13 +223,15 @@ impl TestScenario {
 
         let batch_executor_base = TestBatchExecutorBuilder::new(&self);
         let (stop_sender, stop_receiver) = watch::channel(false);
+        let seal_l1_batch_on_shutdown = self.seal_l1_batch_on_shutdown;
         let io = TestIO::new(stop_sender, self);
         let sk = ZkSyncStateKeeper::new(
             stop_receiver,
             Box::new(io),
             Box::new(batch_executor_base),
             Arc::new(sealer),
-        );
+        )
+        .with_l1_batch_sealing_on_shutdown(seal_l1_batch_on_shutdown);
         let sk_thread = tokio::spawn(sk.run());
 
         // We must assume that *theoretically* state keeper may ignore the stop signal from IO once scenario is
@@ -341,6 +370,8 @@ enum ScenarioItem {
     NoTxsUntilNextAction(&'static str),
     /// Increments protocol version in IO state.
     IncrementProtocolVersion(&'static str),
+    /// Sends a stop signal to the state keeper.
+    Stop(&'static str),
     Tx(&'static str, Transaction, TxExecutionResult),
     Rollback(&'static str, Transaction),
     Reject(&'static str, Transaction, Option<String>),
@@ -364,6 +395,7 @@ impl std::fmt::Debug for ScenarioItem {
                 .debug_tuple("IncrementProtocolVersion")
                 .field(descr)
                 .finish(),
+            Self::Stop(descr) => f.debug_tuple("Stop").field(descr).finish(),
             Self::Tx(descr, tx, result) => f
                 .debug_tuple("Tx")
                 .field(descr)
@@ -529,6 +561,10 @@ impl TestBatchExecutor {
                 Command::StartNextMiniblock(_, resp) => {
                     resp.send(()).unwrap();
                 }
+                Command::ReadStorage(keys, resp) => {
+                    // The storage is empty in test scenarios.
+                    resp.send(vec![H256::zero(); keys.len()]).unwrap();
+                }
                 Command::RollbackLastTx(resp) => {
                     // This is an additional safety check: IO would check that every rollback is included in the
                     // test scenario, but here we want to additionally check that each such request goes to the
@@ -615,6 +651,12 @@ impl TestIO {
             return self.pop_next_item(request);
         }
 
+        if matches!(action, ScenarioItem::Stop(_)) {
+            self.stop_sender.send(true).unwrap();
+            // This is a mock item, so pop an actual one for the IO to process.
+            return self.pop_next_item(request);
+        }
+
         // If that was a last action, tell the state keeper to stop after that.
         if self.scenario.actions.is_empty() {
             self.stop_sender.send(true).unwrap();
@@ -709,6 +751,10 @@ impl StateKeeperIO for TestIO {
         Some(tx)
     }
 
+    fn transaction_conditions(&self, tx: &Transaction) -> Option<TransactionConditions> {
+        self.scenario.tx_conditions.get(&tx.hash()).cloned()
+    }
+
     async fn rollback(&mut self, tx: Transaction) {
         let action = self.pop_next_item("rollback");
         let ScenarioItem::Rollback(_, expected_tx) = action else {
@@ -811,6 +857,9 @@ impl BatchExecutor for MockBatchExecutor {
                 match cmd {
                     Command::ExecuteTx(_, resp) => resp.send(successful_exec()).unwrap(),
                     Command::StartNextMiniblock(_, resp) => resp.send(()).unwrap(),
+                    Command::ReadStorage(keys, resp) => {
+                        resp.send(vec![H256::zero(); keys.len()]).unwrap();
+                    }
                     Command::RollbackLastTx(_) => panic!("unexpected rollback"),
                     Command::FinishBatch(resp) => {
                         // Blanket result, it doesn't really matter.
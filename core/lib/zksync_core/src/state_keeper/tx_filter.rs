@@ -0,0 +1,359 @@
+//! Filtering of L2 transactions. Filters are applied both on mempool admission (in the API server)
+//! and by the state keeper before including a transaction into a miniblock, so that changes in the filter
+//! affect transactions already present in the mempool.
+
+use std::{collections::HashSet, fmt};
+
+use serde::Serialize;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_types::{
+    Address, ExecuteTransactionCommon, Transaction, H256, MAX_NEW_FACTORY_DEPS, U256,
+};
+use zksync_utils::bytecode::{hash_bytecode, validate_bytecode};
+
+/// Decides whether an L2 transaction is allowed to be accepted into the mempool and executed.
+///
+/// L1 transactions are never filtered since their execution is enforced by L1.
+pub trait TransactionFilter: 'static + fmt::Debug + Send + Sync {
+    /// Checks the transaction, returning a human-readable reason if it must be rejected.
+    fn check(&self, tx: &Transaction) -> Result<(), String>;
+}
+
+/// Filter that allows all transactions.
+#[derive(Debug)]
+pub struct NoopTransactionFilter;
+
+impl TransactionFilter for NoopTransactionFilter {
+    fn check(&self, _tx: &Transaction) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Combination of two filters; a transaction is allowed only if it's allowed by both of them.
+impl<A: TransactionFilter, B: TransactionFilter> TransactionFilter for (A, B) {
+    fn check(&self, tx: &Transaction) -> Result<(), String> {
+        self.0.check(tx)?;
+        self.1.check(tx)
+    }
+}
+
+/// Filter rejecting transactions that interact with denied addresses or deploy denied bytecodes.
+#[derive(Debug, Default)]
+pub struct DenyListFilter {
+    addresses: HashSet<Address>,
+    bytecode_hashes: HashSet<H256>,
+}
+
+impl DenyListFilter {
+    pub fn new(
+        addresses: impl IntoIterator<Item = Address>,
+        bytecode_hashes: impl IntoIterator<Item = H256>,
+    ) -> Self {
+        Self {
+            addresses: addresses.into_iter().collect(),
+            bytecode_hashes: bytecode_hashes.into_iter().collect(),
+        }
+    }
+
+    pub fn from_config(config: &StateKeeperConfig) -> Self {
+        Self::new(
+            config.denied_addresses.iter().copied(),
+            config.denied_bytecode_hashes.iter().copied(),
+        )
+    }
+
+    /// Checks whether the filter doesn't deny anything.
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty() && self.bytecode_hashes.is_empty()
+    }
+}
+
+impl TransactionFilter for DenyListFilter {
+    fn check(&self, tx: &Transaction) -> Result<(), String> {
+        let initiator = tx.initiator_account();
+        if self.addresses.contains(&initiator) {
+            return Err(format!("initiator {initiator:?} is denied"));
+        }
+        let payer = tx.payer();
+        if self.addresses.contains(&payer) {
+            return Err(format!("payer {payer:?} is denied"));
+        }
+        let contract_address = tx.execute.contract_address;
+        if self.addresses.contains(&contract_address) {
+            return Err(format!("contract {contract_address:?} is denied"));
+        }
+
+        if self.bytecode_hashes.is_empty() {
+            return Ok(());
+        }
+        for bytecode in tx.execute.factory_deps.iter().flatten() {
+            // Invalid bytecodes cannot be hashed; transactions with them will fail anyway.
+            if validate_bytecode(bytecode).is_err() {
+                continue;
+            }
+            let bytecode_hash = hash_bytecode(bytecode);
+            if self.bytecode_hashes.contains(&bytecode_hash) {
+                return Err(format!("deploying bytecode {bytecode_hash:?} is denied"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Per-transaction limit exceeded by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxLimitKind {
+    GasPerPubdata,
+    CalldataSize,
+    FactoryDeps,
+    FactoryDepsSize,
+}
+
+impl TxLimitKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::GasPerPubdata => "gas per pubdata",
+            Self::CalldataSize => "calldata size",
+            Self::FactoryDeps => "factory dependencies count",
+            Self::FactoryDepsSize => "factory dependencies size",
+        }
+    }
+}
+
+/// Error returned if a transaction exceeds one of [`TxLimits`]. The error is serializable, so that it can be returned
+/// to API clients in a machine-readable form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[error("transaction exceeds {} limit: {value} > {max}", .kind.as_str())]
+pub struct TxLimitError {
+    pub kind: TxLimitKind,
+    pub value: u64,
+    pub max: u64,
+}
+
+impl TxLimitError {
+    fn check(kind: TxLimitKind, value: u64, max: Option<u32>) -> Result<(), Self> {
+        match max {
+            Some(max) if value > u64::from(max) => Err(Self {
+                kind,
+                value,
+                max: max.into(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Configurable per-transaction caps for L2 transactions. The gas limit is capped separately
+/// (by `max_allowed_l2_tx_gas_limit` from the state keeper config).
+#[derive(Debug, Clone)]
+pub struct TxLimits {
+    max_gas_per_pubdata: Option<u32>,
+    max_calldata_size: Option<u32>,
+    max_factory_deps: u32,
+    max_factory_deps_size: Option<u32>,
+}
+
+impl Default for TxLimits {
+    /// Returns limits enforcing only protocol caps.
+    fn default() -> Self {
+        Self {
+            max_gas_per_pubdata: None,
+            max_calldata_size: None,
+            max_factory_deps: MAX_NEW_FACTORY_DEPS as u32,
+            max_factory_deps_size: None,
+        }
+    }
+}
+
+impl TxLimits {
+    pub fn from_config(config: &StateKeeperConfig) -> anyhow::Result<Self> {
+        let protocol_max_factory_deps = MAX_NEW_FACTORY_DEPS as u32;
+        let max_factory_deps = config
+            .max_tx_factory_deps
+            .unwrap_or(protocol_max_factory_deps);
+        anyhow::ensure!(
+            max_factory_deps <= protocol_max_factory_deps,
+            "`max_tx_factory_deps` ({max_factory_deps}) exceeds the protocol limit ({protocol_max_factory_deps})"
+        );
+        let caps = [
+            ("max_tx_gas_per_pubdata", config.max_tx_gas_per_pubdata),
+            ("max_tx_calldata_size", config.max_tx_calldata_size),
+            ("max_tx_factory_deps_size", config.max_tx_factory_deps_size),
+        ];
+        for (name, cap) in caps {
+            anyhow::ensure!(cap != Some(0), "`{name}` must be positive");
+        }
+
+        Ok(Self {
+            max_gas_per_pubdata: config.max_tx_gas_per_pubdata,
+            max_calldata_size: config.max_tx_calldata_size,
+            max_factory_deps,
+            max_factory_deps_size: config.max_tx_factory_deps_size,
+        })
+    }
+
+    /// Checks the transaction against the limits. L1 transactions are never checked.
+    pub fn check_limits(&self, tx: &Transaction) -> Result<(), TxLimitError> {
+        let ExecuteTransactionCommon::L2(common_data) = &tx.common_data else {
+            return Ok(());
+        };
+        let gas_per_pubdata = saturating_u64(common_data.fee.gas_per_pubdata_limit);
+        TxLimitError::check(
+            TxLimitKind::GasPerPubdata,
+            gas_per_pubdata,
+            self.max_gas_per_pubdata,
+        )?;
+        let calldata_size = tx.execute.calldata.len() as u64;
+        TxLimitError::check(
+            TxLimitKind::CalldataSize,
+            calldata_size,
+            self.max_calldata_size,
+        )?;
+
+        let factory_deps = tx.execute.factory_deps.as_deref().unwrap_or_default();
+        TxLimitError::check(
+            TxLimitKind::FactoryDeps,
+            factory_deps.len() as u64,
+            Some(self.max_factory_deps),
+        )?;
+        let factory_deps_size = factory_deps.iter().map(|dep| dep.len() as u64).sum();
+        TxLimitError::check(
+            TxLimitKind::FactoryDepsSize,
+            factory_deps_size,
+            self.max_factory_deps_size,
+        )
+    }
+}
+
+impl TransactionFilter for TxLimits {
+    fn check(&self, tx: &Transaction) -> Result<(), String> {
+        self.check_limits(tx).map_err(|err| err.to_string())
+    }
+}
+
+fn saturating_u64(value: U256) -> u64 {
+    if value > U256::from(u64::MAX) {
+        u64::MAX
+    } else {
+        value.as_u64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{l2::L2Tx, Execute};
+
+    use super::*;
+    use crate::utils::testonly::create_l2_transaction;
+
+    fn tx_with_execute(execute: Execute) -> Transaction {
+        let mut tx: L2Tx = create_l2_transaction(10, 100);
+        tx.execute = execute;
+        tx.into()
+    }
+
+    #[test]
+    fn deny_list_filter_checks_addresses() {
+        let tx: Transaction = create_l2_transaction(10, 100).into();
+        assert_eq!(DenyListFilter::default().check(&tx), Ok(()));
+
+        let filter = DenyListFilter::new([tx.initiator_account()], []);
+        let err = filter.check(&tx).unwrap_err();
+        assert!(err.contains("initiator"), "{err}");
+
+        let denied_contract = Address::repeat_byte(0xad);
+        let filter = DenyListFilter::new([denied_contract], []);
+        assert_eq!(filter.check(&tx), Ok(()));
+        let tx = tx_with_execute(Execute {
+            contract_address: denied_contract,
+            calldata: vec![],
+            value: 0.into(),
+            factory_deps: None,
+        });
+        let err = filter.check(&tx).unwrap_err();
+        assert!(err.contains("contract"), "{err}");
+    }
+
+    #[test]
+    fn deny_list_filter_checks_bytecodes() {
+        let bytecode = vec![0_u8; 32];
+        let tx = tx_with_execute(Execute {
+            contract_address: Address::repeat_byte(1),
+            calldata: vec![],
+            value: 0.into(),
+            factory_deps: Some(vec![bytecode.clone()]),
+        });
+
+        let filter = DenyListFilter::new([], [H256::repeat_byte(1)]);
+        assert_eq!(filter.check(&tx), Ok(()));
+        let filter = DenyListFilter::new([], [hash_bytecode(&bytecode)]);
+        let err = filter.check(&tx).unwrap_err();
+        assert!(err.contains("bytecode"), "{err}");
+    }
+
+    #[test]
+    fn tx_limits() {
+        let config = StateKeeperConfig {
+            max_tx_gas_per_pubdata: Some(100),
+            max_tx_calldata_size: Some(4),
+            max_tx_factory_deps_size: Some(32),
+            ..StateKeeperConfig::for_tests()
+        };
+        let limits = TxLimits::from_config(&config).unwrap();
+        let tx: Transaction = create_l2_transaction(10, 100).into();
+        limits.check_limits(&tx).unwrap();
+
+        let tx: Transaction = create_l2_transaction(10, 101).into();
+        let err = limits.check_limits(&tx).unwrap_err();
+        assert_eq!(
+            err,
+            TxLimitError {
+                kind: TxLimitKind::GasPerPubdata,
+                value: 101,
+                max: 100,
+            }
+        );
+        let err = serde_json::to_value(err).unwrap();
+        assert_eq!(
+            err,
+            serde_json::json!({ "kind": "gas_per_pubdata", "value": 101, "max": 100 })
+        );
+
+        let tx = tx_with_execute(Execute {
+            contract_address: Address::repeat_byte(1),
+            calldata: vec![0; 5],
+            value: 0.into(),
+            factory_deps: None,
+        });
+        let err = limits.check_limits(&tx).unwrap_err();
+        assert_eq!(err.kind, TxLimitKind::CalldataSize);
+
+        let tx = tx_with_execute(Execute {
+            contract_address: Address::repeat_byte(1),
+            calldata: vec![],
+            value: 0.into(),
+            factory_deps: Some(vec![vec![0; 32]; 2]),
+        });
+        let err = limits.check_limits(&tx).unwrap_err();
+        assert_eq!(err.kind, TxLimitKind::FactoryDepsSize);
+        assert_eq!((err.value, err.max), (64, 32));
+    }
+
+    #[test]
+    fn invalid_tx_limits() {
+        let config = StateKeeperConfig {
+            max_tx_factory_deps: Some(MAX_NEW_FACTORY_DEPS as u32 + 1),
+            ..StateKeeperConfig::for_tests()
+        };
+        let err = TxLimits::from_config(&config).unwrap_err().to_string();
+        assert!(err.contains("protocol limit"), "{err}");
+
+        let config = StateKeeperConfig {
+            max_tx_calldata_size: Some(0),
+            ..StateKeeperConfig::for_tests()
+        };
+        let err = TxLimits::from_config(&config).unwrap_err().to_string();
+        assert!(err.contains("max_tx_calldata_size"), "{err}");
+    }
+}
@@ -4,62 +4,162 @@ use std::{
 };
 
 use multivm::interface::VmExecutionResultAndLogs;
+use zksync_config::configs::chain::{MempoolConfig, MempoolTxOrdering};
 use zksync_dal::StorageProcessor;
-use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
+use zksync_mempool::{L2TxFilter, L2TxOrdering, MempoolInfo, MempoolStore};
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
+    api::TransactionConditions, block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce,
+    PriorityOpId, Transaction, H256,
 };
 
 use super::metrics::StateKeeperGauges;
 use crate::gas_tracker::{gas_count_from_metrics, gas_count_from_tx_and_metrics};
 
+fn l2_tx_ordering(ordering: MempoolTxOrdering) -> L2TxOrdering {
+    match ordering {
+        MempoolTxOrdering::EffectivePriorityFee => L2TxOrdering::EffectivePriorityFee,
+        MempoolTxOrdering::Fifo => L2TxOrdering::Fifo,
+    }
+}
+
 #[derive(Debug, Clone)]
-pub struct MempoolGuard(Arc<Mutex<MempoolStore>>);
+pub struct MempoolGuard {
+    store: Arc<Mutex<MempoolStore>>,
+    /// Conditions of pending conditional L2 transactions keyed by the initiator and nonce. Values contain
+    /// the transaction hash as well, since a transaction may be replaced by another one with the same nonce.
+    tx_conditions: Arc<Mutex<HashMap<(Address, Nonce), (H256, TransactionConditions)>>>,
+}
 
 impl MempoolGuard {
-    pub async fn from_storage(storage_processor: &mut StorageProcessor<'_>, capacity: u64) -> Self {
+    pub async fn from_storage(
+        storage_processor: &mut StorageProcessor<'_>,
+        config: &MempoolConfig,
+    ) -> Self {
         let next_priority_id = storage_processor
             .transactions_dal()
             .next_priority_id()
             .await;
-        Self::new(next_priority_id, capacity)
+        let mut store = MempoolStore::new(next_priority_id, config.capacity)
+            .with_tx_ordering(l2_tx_ordering(config.tx_ordering));
+        if let Some(limit) = config.max_queued_txs_per_account {
+            store = store.with_max_queued_txs_per_account(limit);
+        }
+        if let Some(limit) = config.max_size_bytes {
+            store = store.with_max_size_in_bytes(limit);
+        }
+        Self::from_store(store)
     }
 
     pub(super) fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
-        let store = MempoolStore::new(next_priority_id, capacity);
-        Self(Arc::new(Mutex::new(store)))
+        let store = MempoolStore::new(next_priority_id, capacity)
+            .with_tx_ordering(l2_tx_ordering(MempoolTxOrdering::default()));
+        Self::from_store(store)
+    }
+
+    fn from_store(store: MempoolStore) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            tx_conditions: Arc::default(),
+        }
     }
 
     pub fn insert(&mut self, transactions: Vec<Transaction>, nonces: HashMap<Address, Nonce>) {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .insert(transactions, nonces);
     }
 
     pub fn has_next(&self, filter: &L2TxFilter) -> bool {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .has_next(filter)
     }
 
     pub fn next_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .next_transaction(filter)
     }
 
     pub fn rollback(&mut self, rejected: &Transaction) {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .rollback(rejected);
     }
 
+    /// Updates conditions for the provided transactions loaded from the storage. `conditions` are keyed by
+    /// the transaction hash; transactions missing from `conditions` are unconditional.
+    pub fn update_conditions(
+        &mut self,
+        transactions: &[Transaction],
+        mut conditions: HashMap<H256, TransactionConditions>,
+    ) {
+        let mut tx_conditions = self
+            .tx_conditions
+            .lock()
+            .expect("failed to acquire conditions lock");
+        for tx in transactions {
+            let Some(nonce) = tx.nonce() else {
+                continue; // Not an L2 transaction
+            };
+            let key = (tx.initiator_account(), nonce);
+            let tx_hash = tx.hash();
+            if let Some(conditions) = conditions.remove(&tx_hash) {
+                tx_conditions.insert(key, (tx_hash, conditions));
+            } else {
+                // The transaction may have replaced a conditional one.
+                tx_conditions.remove(&key);
+            }
+        }
+    }
+
+    /// Returns conditions for the specified transaction, or `None` if the transaction is unconditional.
+    pub fn transaction_conditions(&self, tx: &Transaction) -> Option<TransactionConditions> {
+        let key = (tx.initiator_account(), tx.nonce()?);
+        let tx_conditions = self
+            .tx_conditions
+            .lock()
+            .expect("failed to acquire conditions lock");
+        let (tx_hash, conditions) = tx_conditions.get(&key)?;
+        (*tx_hash == tx.hash()).then(|| conditions.clone())
+    }
+
+    /// Removes conditions for transactions that have left the mempool, i.e., were executed or rejected.
+    pub fn remove_conditions<'a>(
+        &mut self,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+    ) {
+        let mut tx_conditions = self
+            .tx_conditions
+            .lock()
+            .expect("failed to acquire conditions lock");
+        if tx_conditions.is_empty() {
+            return;
+        }
+        for tx in transactions {
+            if let Some(nonce) = tx.nonce() {
+                tx_conditions.remove(&(tx.initiator_account(), nonce));
+            }
+        }
+    }
+
+    /// Removes conditions for all transactions of the specified accounts.
+    pub fn remove_account_conditions(&mut self, accounts: &[Address]) {
+        if accounts.is_empty() {
+            return;
+        }
+        self.tx_conditions
+            .lock()
+            .expect("failed to acquire conditions lock")
+            .retain(|(account, _), _| !accounts.contains(account));
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .get_mempool_info()
@@ -67,14 +167,14 @@ impl MempoolGuard {
 
     #[cfg(test)]
     pub fn stats(&self) -> zksync_mempool::MempoolStats {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .stats()
     }
 
     pub fn register_metrics(&self) {
-        StateKeeperGauges::register(Arc::downgrade(&self.0));
+        StateKeeperGauges::register(Arc::downgrade(&self.store));
     }
 }
 
@@ -101,3 +201,16 @@ impl ExecutionMetricsForCriteria {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_tx_ordering_is_consistent() {
+        assert_eq!(
+            l2_tx_ordering(MempoolTxOrdering::default()),
+            L2TxOrdering::default()
+        );
+    }
+}
@@ -7,9 +7,9 @@ use vm_utils::storage::{l1_batch_params, L1BatchParamsProvider};
 use zksync_contracts::{BaseSystemContracts, SystemContractCode};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
-    ethabi::Address, fee_model::BatchFeeInput, protocol_version::ProtocolUpgradeTx,
-    witness_block_state::WitnessBlockState, L1BatchNumber, L2ChainId, MiniblockNumber,
-    ProtocolVersionId, Transaction, H256,
+    api::TransactionConditions, ethabi::Address, fee_model::BatchFeeInput,
+    protocol_version::ProtocolUpgradeTx, witness_block_state::WitnessBlockState, L1BatchNumber,
+    L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
 };
 use zksync_utils::bytes_to_be_words;
 
@@ -478,6 +478,11 @@ impl StateKeeperIO for ExternalIO {
         None
     }
 
+    fn transaction_conditions(&self, _tx: &Transaction) -> Option<TransactionConditions> {
+        // Conditions were already checked when the transactions were executed on the main node.
+        None
+    }
+
     async fn rollback(&mut self, tx: Transaction) {
         // We are replaying the already sealed batches so no rollbacks are expected to occur.
         panic!("Rollback requested. Transaction hash: {:?}", tx.hash());
@@ -385,13 +385,14 @@ async fn external_io_with_multiple_miniblocks(snapshot_recovery: bool) {
         (snapshot.miniblock_number + 2, &tx_hashes[5..]),
     ];
     let mut storage = pool.access_storage().await.unwrap();
-    for (number, expected_tx_hashes) in tx_hashes_by_miniblock {
-        let miniblock = storage
-            .blocks_dal()
-            .get_miniblock_header(number)
-            .await
-            .unwrap()
-            .unwrap_or_else(|| panic!("Miniblock #{number} is not persisted"));
+    let miniblocks = storage
+        .blocks_dal()
+        .get_miniblock_headers_range(snapshot.miniblock_number + 1..=snapshot.miniblock_number + 2)
+        .await
+        .unwrap();
+    assert_eq!(miniblocks.len(), 2, "{miniblocks:?}");
+    for (miniblock, (number, expected_tx_hashes)) in miniblocks.iter().zip(tx_hashes_by_miniblock) {
+        assert_eq!(miniblock.number, number);
         assert_eq!(miniblock.l2_tx_count, expected_tx_hashes.len() as u16);
         assert_eq!(miniblock.timestamp, u64::from(number.0));
 
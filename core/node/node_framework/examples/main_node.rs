@@ -110,15 +110,17 @@ impl MainNodeBuilder {
     }
 
     fn add_state_keeper_layer(mut self) -> anyhow::Result<Self> {
+        let state_keeper_config = StateKeeperConfig::from_env()?;
+        let state_keeper_layer = StateKeeperLayer::default()
+            .with_l1_batch_sealing_on_shutdown(state_keeper_config.seal_l1_batch_on_shutdown);
         let mempool_io_layer = MempoolIOLayer::new(
             NetworkConfig::from_env()?,
             ContractsConfig::from_env()?,
-            StateKeeperConfig::from_env()?,
+            state_keeper_config,
             MempoolConfig::from_env()?,
         );
         let main_node_batch_executor_builder_layer =
             MainBatchExecutorLayer::new(DBConfig::from_env()?, StateKeeperConfig::from_env()?);
-        let state_keeper_layer = StateKeeperLayer;
         self.node
             .add_layer(mempool_io_layer)
             .add_layer(main_node_batch_executor_builder_layer)
@@ -159,7 +161,9 @@ impl MainNodeBuilder {
         };
 
         // On main node we always use master pool sink.
-        self.node.add_layer(TxSinkLayer::MasterPoolSink);
+        self.node.add_layer(TxSinkLayer::MasterPoolSink {
+            replacement_fee_bump_percent: rpc_config.tx_replacement_fee_bump_percent(),
+        });
         self.node.add_layer(TxSenderLayer::new(
             TxSenderConfig::new(
                 &state_keeper_config,
@@ -5,7 +5,8 @@ use zksync_config::configs::{
     FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig,
 };
 use zksync_core::house_keeper::{
-    blocks_state_reporter::L1BatchMetricsReporter,
+    blocks_state_reporter::L1BatchMetricsReporter, db_consistency_checker::DbConsistencyChecker,
+    db_partition_manager::DbPartitionManager, db_pruner::DbPruner,
     fri_proof_compressor_job_retry_manager::FriProofCompressorJobRetryManager,
     fri_proof_compressor_queue_monitor::FriProofCompressorStatsReporter,
     fri_prover_job_retry_manager::FriProverJobRetryManager,
@@ -19,7 +20,9 @@ use zksync_core::house_keeper::{
 use zksync_dal::ConnectionPool;
 
 use crate::{
-    implementations::resources::pools::{ProverPoolResource, ReplicaPoolResource},
+    implementations::resources::pools::{
+        MasterPoolResource, ProverPoolResource, ReplicaPoolResource,
+    },
     service::{ServiceContext, StopReceiver},
     task::Task,
     wiring_layer::{WiringError, WiringLayer},
@@ -81,6 +84,38 @@ impl WiringLayer for HouseKeeperLayer {
             l1_batch_metrics_reporter,
         }));
 
+        if let Some(retention) = self.house_keeper_config.db_pruning_retention() {
+            // Pruning modifies data, so it needs the master DB.
+            let master_pool_resource = context.get_resource::<MasterPoolResource>().await?;
+            let db_pruner = DbPruner::new(
+                retention,
+                self.house_keeper_config.db_pruning_interval_ms(),
+                master_pool_resource.get_singleton().await?,
+            );
+            context.add_task(Box::new(DbPrunerTask { db_pruner }));
+        }
+
+        if let Some(partition_size) = self.house_keeper_config.db_partition_size_miniblocks {
+            let master_pool_resource = context.get_resource::<MasterPoolResource>().await?;
+            let db_partition_manager = DbPartitionManager::new(
+                partition_size,
+                self.house_keeper_config.db_events_archival_retention(),
+                self.house_keeper_config.db_partitioning_interval_ms(),
+                master_pool_resource.get_singleton().await?,
+            );
+            context.add_task(Box::new(DbPartitionManagerTask {
+                db_partition_manager,
+            }));
+        }
+
+        if let Some(check_interval_ms) = self.house_keeper_config.db_consistency_check_interval_ms {
+            let db_consistency_checker =
+                DbConsistencyChecker::new(check_interval_ms, replica_pool.clone());
+            context.add_task(Box::new(DbConsistencyCheckerTask {
+                db_consistency_checker,
+            }));
+        }
+
         let fri_prover_job_retry_manager = FriProverJobRetryManager::new(
             self.fri_prover_config.max_attempts,
             self.fri_prover_config.proof_generation_timeout(),
@@ -328,3 +363,51 @@ impl Task for FriProofCompressorJobRetryManagerTask {
         self.fri_proof_compressor_retry_manager.run().await
     }
 }
+
+#[derive(Debug)]
+struct DbPrunerTask {
+    db_pruner: DbPruner,
+}
+
+#[async_trait::async_trait]
+impl Task for DbPrunerTask {
+    fn name(&self) -> &'static str {
+        "db_pruner"
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.db_pruner.run().await
+    }
+}
+
+#[derive(Debug)]
+struct DbPartitionManagerTask {
+    db_partition_manager: DbPartitionManager,
+}
+
+#[async_trait::async_trait]
+impl Task for DbPartitionManagerTask {
+    fn name(&self) -> &'static str {
+        "db_partition_manager"
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.db_partition_manager.run().await
+    }
+}
+
+#[derive(Debug)]
+struct DbConsistencyCheckerTask {
+    db_consistency_checker: DbConsistencyChecker,
+}
+
+#[async_trait::async_trait]
+impl Task for DbConsistencyCheckerTask {
+    fn name(&self) -> &'static str {
+        "db_consistency_checker"
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.db_consistency_checker.run().await
+    }
+}
@@ -73,17 +73,30 @@ impl WiringLayer for PoolsLayer {
             ));
         }
 
+        if let Some(threshold) = self.config.slow_query_threshold() {
+            ConnectionPool::global_config().set_slow_query_threshold(threshold)?;
+        }
+        if let Some(threshold) = self.config.long_connection_threshold() {
+            ConnectionPool::global_config().set_long_connection_threshold(threshold)?;
+        }
+
         if self.with_master {
             let mut master_pool =
                 ConnectionPool::builder(self.config.master_url()?, self.config.max_connections()?);
-            master_pool.set_statement_timeout(self.config.statement_timeout());
+            master_pool
+                .set_statement_timeout(self.config.statement_timeout())
+                .set_schema(self.config.schema());
             context.insert_resource(MasterPoolResource::new(master_pool))?;
         }
 
         if self.with_replica {
             let mut replica_pool =
                 ConnectionPool::builder(self.config.replica_url()?, self.config.max_connections()?);
-            replica_pool.set_statement_timeout(self.config.statement_timeout());
+            replica_pool
+                .add_replica_urls(self.config.additional_replica_urls())
+                .set_failover_url(self.config.master_url.as_deref())
+                .set_statement_timeout(self.config.statement_timeout())
+                .set_schema(self.config.schema());
             context.insert_resource(ReplicaPoolResource::new(replica_pool))?;
         }
 
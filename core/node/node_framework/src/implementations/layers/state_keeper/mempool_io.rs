@@ -6,7 +6,8 @@ use zksync_config::{
     ContractsConfig,
 };
 use zksync_core::state_keeper::{
-    MempoolFetcher, MempoolGuard, MempoolIO, MiniblockSealer, SequencerSealer,
+    seal_criteria::SealCriterion, MempoolFetcher, MempoolGuard, MempoolIO, MiniblockSealer,
+    SequencerSealer,
 };
 
 use crate::{
@@ -28,6 +29,7 @@ pub struct MempoolIOLayer {
     contracts_config: ContractsConfig,
     state_keeper_config: StateKeeperConfig,
     mempool_config: MempoolConfig,
+    custom_seal_criteria: Vec<Box<dyn SealCriterion>>,
 }
 
 impl MempoolIOLayer {
@@ -42,9 +44,16 @@ impl MempoolIOLayer {
             contracts_config,
             state_keeper_config,
             mempool_config,
+            custom_seal_criteria: Vec::new(),
         }
     }
 
+    /// Adds a chain-specific seal criterion, which will be checked in addition to the default ones.
+    pub fn with_seal_criterion(mut self, criterion: impl SealCriterion) -> Self {
+        self.custom_seal_criteria.push(Box::new(criterion));
+        self
+    }
+
     async fn build_mempool_guard(
         &self,
         master_pool: &MasterPoolResource,
@@ -57,7 +66,7 @@ impl MempoolIOLayer {
             .access_storage()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, self.mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(&mut storage, &self.mempool_config).await;
         mempool.register_metrics();
         Ok(mempool)
     }
@@ -120,7 +129,9 @@ impl WiringLayer for MempoolIOLayer {
         context.insert_resource(StateKeeperIOResource(Unique::new(Box::new(io))))?;
 
         // Create sealer.
-        let sealer = SequencerSealer::new(self.state_keeper_config);
+        let mut seal_criteria = SequencerSealer::default_sealers(&self.state_keeper_config);
+        seal_criteria.extend(self.custom_seal_criteria);
+        let sealer = SequencerSealer::with_sealers(self.state_keeper_config, seal_criteria);
         context.insert_resource(ConditionalSealerResource(Arc::new(sealer)))?;
 
         Ok(())
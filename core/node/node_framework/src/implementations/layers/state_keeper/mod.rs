@@ -23,8 +23,18 @@ use crate::{
 /// - `BatchExecutorResource`
 /// - `ConditionalSealerResource`
 ///
-#[derive(Debug)]
-pub struct StateKeeperLayer;
+#[derive(Debug, Default)]
+pub struct StateKeeperLayer {
+    seal_l1_batch_on_shutdown: bool,
+}
+
+impl StateKeeperLayer {
+    /// Enables sealing the open L1 batch when the state keeper is stopped.
+    pub fn with_l1_batch_sealing_on_shutdown(mut self, enabled: bool) -> Self {
+        self.seal_l1_batch_on_shutdown = enabled;
+        self
+    }
+}
 
 #[async_trait::async_trait]
 impl WiringLayer for StateKeeperLayer {
@@ -51,6 +61,7 @@ impl WiringLayer for StateKeeperLayer {
             io,
             batch_executor_base,
             sealer,
+            seal_l1_batch_on_shutdown: self.seal_l1_batch_on_shutdown,
         }));
         Ok(())
     }
@@ -61,6 +72,7 @@ struct StateKeeperTask {
     io: Box<dyn StateKeeperIO>,
     batch_executor_base: Box<dyn BatchExecutor>,
     sealer: Arc<dyn ConditionalSealer>,
+    seal_l1_batch_on_shutdown: bool,
 }
 
 #[async_trait::async_trait]
@@ -75,7 +87,8 @@ impl Task for StateKeeperTask {
             self.io,
             self.batch_executor_base,
             self.sealer,
-        );
+        )
+        .with_l1_batch_sealing_on_shutdown(self.seal_l1_batch_on_shutdown);
         let result = state_keeper.run().await;
 
         // Wait for all the instances of RocksDB to be destroyed.
@@ -12,7 +12,7 @@ use crate::{
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum TxSinkLayer {
-    MasterPoolSink,
+    MasterPoolSink { replacement_fee_bump_percent: u32 },
     ProxySink { main_node_url: String },
 }
 
@@ -24,13 +24,18 @@ impl WiringLayer for TxSinkLayer {
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
         let tx_sink = match self.as_ref() {
-            TxSinkLayer::MasterPoolSink => {
+            TxSinkLayer::MasterPoolSink {
+                replacement_fee_bump_percent,
+            } => {
                 let pool = context
                     .get_resource::<MasterPoolResource>()
                     .await?
                     .get()
                     .await?;
-                TxSinkResource(Arc::new(MasterPoolSink::new(pool)))
+                TxSinkResource(Arc::new(MasterPoolSink::new(
+                    pool,
+                    *replacement_fee_bump_percent,
+                )))
             }
             TxSinkLayer::ProxySink { main_node_url } => {
                 let client = HttpClient::<HttpBackend>::builder()